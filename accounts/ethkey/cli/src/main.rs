@@ -31,7 +31,7 @@ use std::num::ParseIntError;
 use std::{env, fmt, process, io, sync};
 
 use docopt::Docopt;
-use ethkey::{Brain, BrainPrefix, Prefix, brain_recover};
+use ethkey::{Brain, BrainPrefix, Prefix, Mnemonic, BIP44_PATH, bip44_secret, brain_recover};
 use parity_crypto::publickey::{KeyPair, Random, Error as EthkeyError, Generator, sign, verify_public, verify_address};
 use rustc_hex::{FromHex, FromHexError};
 
@@ -43,6 +43,7 @@ Usage:
     ethkey info <secret-or-phrase> [options]
     ethkey generate random [options]
     ethkey generate prefix <prefix> [options]
+    ethkey generate mnemonic [options]
     ethkey sign <secret> <message>
     ethkey verify public <public> <signature> <message>
     ethkey verify address <address> <signature> <message>
@@ -55,11 +56,16 @@ Options:
     -p, --public       Display only the public key.
     -a, --address      Display only the address.
     -b, --brain        Use parity brain wallet algorithm. Not recommended.
+    -m, --mnemonic     Use BIP-39 mnemonic and BIP-44 derivation path m/44'/60'/0'/0/INDEX.
+    --index INDEX      Index of the key derived from the mnemonic [default: 0].
+    --passphrase PASS  BIP-39 passphrase of the mnemonic.
+    --words WORDS      Number of words of the generated mnemonic [default: 12].
 
 Commands:
     info               Display public key and address of the secret.
     generate random    Generates new random Ethereum key.
     generate prefix    Random generation, but address must start with a prefix ("vanity address").
+    generate mnemonic  Generates new BIP-39 mnemonic and derives a key from it.
     sign               Sign message using a secret key.
     verify             Verify signer of the signature by public key or address.
     recover            Try to find brain phrase matching given address from partial phrase.
@@ -71,6 +77,7 @@ struct Args {
 	cmd_generate: bool,
 	cmd_random: bool,
 	cmd_prefix: bool,
+	cmd_mnemonic: bool,
 	cmd_sign: bool,
 	cmd_verify: bool,
 	cmd_public: bool,
@@ -88,6 +95,10 @@ struct Args {
 	flag_public: bool,
 	flag_address: bool,
 	flag_brain: bool,
+	flag_mnemonic: bool,
+	flag_index: u32,
+	flag_passphrase: Option<String>,
+	flag_words: usize,
 }
 
 #[derive(Debug)]
@@ -201,6 +212,10 @@ fn execute<S, I>(command: I) -> Result<String, Error> where I: IntoIterator<Item
 			let phrase_info = validate_phrase(&phrase);
 			let keypair = Brain::new(phrase).generate().expect("Brain wallet generator is infallible; qed");
 			(keypair, Some(phrase_info))
+		} else if args.flag_mnemonic {
+			let mnemonic = Mnemonic::from_phrase(&args.arg_secret_or_phrase).map_err(|e| EthkeyError::Custom(e.to_string()))?;
+			let keypair = mnemonic_keypair(&mnemonic, &args)?;
+			(keypair, Some(format!("path: {}/{}", BIP44_PATH, args.flag_index)))
 		} else {
 			let secret = args.arg_secret_or_phrase.parse().map_err(|_| EthkeyError::InvalidSecretKey)?;
 			(KeyPair::from_secret(secret)?, None)
@@ -217,6 +232,11 @@ fn execute<S, I>(command: I) -> Result<String, Error> where I: IntoIterator<Item
 			} else {
 				(Random.generate()?, None)
 			}
+		} else if args.cmd_mnemonic {
+			let mnemonic = Mnemonic::generate(args.flag_words).map_err(|e| EthkeyError::Custom(e.to_string()))?;
+			let keypair = mnemonic_keypair(&mnemonic, &args)?;
+			let phrase = format!("recovery phrase: {}\npath: {}/{}", mnemonic.phrase(), BIP44_PATH, args.flag_index);
+			(keypair, Some(phrase))
 		} else if args.cmd_prefix {
 			let prefix = args.arg_prefix.from_hex()?;
 			let brain = args.flag_brain;
@@ -299,6 +319,12 @@ fn validate_phrase(phrase: &str) -> String {
 	}
 }
 
+fn mnemonic_keypair(mnemonic: &Mnemonic, args: &Args) -> Result<KeyPair, EthkeyError> {
+	let seed = mnemonic.seed(args.flag_passphrase.as_ref().map_or("", String::as_str));
+	let secret = bip44_secret(&seed, args.flag_index).map_err(|e| EthkeyError::Custom(format!("{:?}", e)))?;
+	KeyPair::from_secret(secret)
+}
+
 fn in_threads<F, X, O>(prepare: F) -> Result<O, EthkeyError> where
 	O: Send + 'static,
 	X: Send + 'static,
@@ -407,6 +433,25 @@ address: 006e27b6a72e1f34c626762f3c4761547aff1421".to_owned();
 		assert_eq!(execute(command).unwrap(), expected);
 	}
 
+	#[test]
+	fn mnemonic() {
+		let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+		let command = vec!["ethkey", "info", "--mnemonic", phrase, "--address"]
+			.into_iter()
+			.map(Into::into)
+			.collect::<Vec<String>>();
+
+		let expected = "9858effd232b4033e47d90003d41ec34ecaeda94".to_owned();
+		assert_eq!(execute(command).unwrap(), expected);
+
+		let command = vec!["ethkey", "info", "--mnemonic", "abandon about", "--address"]
+			.into_iter()
+			.map(Into::into)
+			.collect::<Vec<String>>();
+
+		assert!(execute(command).is_err());
+	}
+
 	#[test]
 	fn sign() {
 		let command = vec!["ethkey", "sign", "17d08f5fe8c77af811caa0c9a187e668ce3b74a99acc3f6d976f075fa8e0be55", "bd50b7370c3f96733b31744c6c45079e7ae6c8d299613246d28ebcef507ec987"]
@@ -16,6 +16,7 @@
 
 // #![warn(missing_docs)]
 
+extern crate bip39;
 extern crate edit_distance;
 extern crate parity_crypto;
 extern crate parity_wordlist;
@@ -28,6 +29,7 @@ extern crate serde_derive;
 
 mod brain;
 mod brain_prefix;
+mod mnemonic;
 mod password;
 mod prefix;
 
@@ -36,5 +38,6 @@ pub mod brain_recover;
 pub use self::parity_wordlist::Error as WordlistError;
 pub use self::brain::Brain;
 pub use self::brain_prefix::BrainPrefix;
+pub use self::mnemonic::{Mnemonic, MnemonicError, bip44_secret, BIP44_PATH};
 pub use self::password::Password;
 pub use self::prefix::Prefix;
\ No newline at end of file
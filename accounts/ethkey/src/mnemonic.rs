@@ -0,0 +1,120 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::fmt;
+use bip39::{self, Language, MnemonicType, Seed};
+use parity_crypto::publickey::{Derivation, DerivationError, ExtendedKeyPair, Secret};
+
+/// Derivation path of the Ethereum accounts, the account index is appended to it.
+pub const BIP44_PATH: &str = "m/44'/60'/0'/0";
+
+const HARDENED: u32 = 0x8000_0000;
+
+/// Invalid BIP-39 mnemonic phrase or word count.
+#[derive(Debug, PartialEq)]
+pub struct MnemonicError(String);
+
+impl fmt::Display for MnemonicError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "Invalid mnemonic: {}", self.0)
+	}
+}
+
+/// BIP-39 mnemonic phrase (English wordlist).
+pub struct Mnemonic(bip39::Mnemonic);
+
+impl Mnemonic {
+	/// Generates a random mnemonic of 12, 15, 18, 21 or 24 words.
+	pub fn generate(words: usize) -> Result<Self, MnemonicError> {
+		let mnemonic_type = MnemonicType::for_word_count(words).map_err(|e| MnemonicError(e.to_string()))?;
+		Ok(Mnemonic(bip39::Mnemonic::new(mnemonic_type, Language::English)))
+	}
+
+	/// Parses the phrase, validating the words and the checksum.
+	pub fn from_phrase(phrase: &str) -> Result<Self, MnemonicError> {
+		let phrase = phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+		bip39::Mnemonic::from_phrase(&phrase, Language::English)
+			.map(Mnemonic)
+			.map_err(|e| MnemonicError(e.to_string()))
+	}
+
+	/// The phrase.
+	pub fn phrase(&self) -> &str {
+		self.0.phrase()
+	}
+
+	/// The 64-byte BIP-39 seed, salted with an optional passphrase.
+	pub fn seed(&self, passphrase: &str) -> Vec<u8> {
+		Seed::new(&self.0, passphrase).as_bytes().to_vec()
+	}
+}
+
+/// Derives the secret of the account with the given index along `m/44'/60'/0'/0/index`
+/// (BIP-32/BIP-44) from the BIP-39 seed.
+pub fn bip44_secret(seed: &[u8], index: u32) -> Result<Secret, DerivationError> {
+	let extended = ExtendedKeyPair::with_seed(seed)?
+		.derive(Derivation::Hard(HARDENED | 44))?
+		.derive(Derivation::Hard(HARDENED | 60))?
+		.derive(Derivation::Hard(HARDENED))?
+		.derive(Derivation::Soft(0))?
+		.derive(Derivation::Soft(index))?;
+	Ok(extended.secret().as_raw().clone())
+}
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+	use parity_crypto::publickey::{Address, KeyPair};
+	use super::{Mnemonic, bip44_secret};
+
+	fn bip44_address(seed: &[u8], index: u32) -> Address {
+		KeyPair::from_secret(bip44_secret(seed, index).unwrap()).unwrap().address()
+	}
+
+	const PHRASE: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+	#[test]
+	fn seed_matches_bip39_vector() {
+		let mnemonic = Mnemonic::from_phrase(PHRASE).unwrap();
+		let seed = mnemonic.seed("TREZOR");
+		assert_eq!(
+			seed.iter().map(|b| format!("{:02x}", b)).collect::<String>(),
+			"c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+		);
+	}
+
+	#[test]
+	fn derives_bip44_accounts() {
+		let seed = Mnemonic::from_phrase(PHRASE).unwrap().seed("");
+		assert_eq!(bip44_address(&seed, 0), Address::from_str("9858effd232b4033e47d90003d41ec34ecaeda94").unwrap());
+		assert!(bip44_address(&seed, 1) != bip44_address(&seed, 0));
+	}
+
+	#[test]
+	fn rejects_invalid_phrases() {
+		assert!(Mnemonic::from_phrase("abandon abandon abandon").is_err());
+		assert!(Mnemonic::from_phrase(&PHRASE.replace("about", "abandon")).is_err());
+		assert!(Mnemonic::generate(13).is_err());
+	}
+
+	#[test]
+	fn generated_mnemonic_roundtrips() {
+		let mnemonic = Mnemonic::generate(24).unwrap();
+		assert_eq!(mnemonic.phrase().split(' ').count(), 24);
+		let parsed = Mnemonic::from_phrase(mnemonic.phrase()).unwrap();
+		assert_eq!(parsed.seed("x"), mnemonic.seed("x"));
+	}
+}
@@ -36,7 +36,7 @@ use std::{env, process, fs, fmt};
 
 use docopt::Docopt;
 use ethstore::accounts_dir::{KeyDirectory, RootDiskDirectory};
-use ethkey::Password;
+use ethkey::{Mnemonic, Password};
 use parity_crypto::publickey::Address;
 use ethstore::{EthStore, SimpleSecretStore, SecretStore, import_accounts, PresaleWallet, SecretVaultRef, StoreAccountRef};
 
@@ -53,6 +53,9 @@ Usage:
     ethstore import [<password>] [--src DIR] [--dir DIR]
     ethstore import-wallet <path> <password> [--dir DIR] [--vault VAULT] [--vault-pwd VAULTPWD]
     ethstore find-wallet-pass <path> <password>
+    ethstore import-mnemonic <phrase> <password> [--count COUNT] [--passphrase PHRASE] [--dir DIR]
+    ethstore list-hd [--dir DIR]
+    ethstore derive-hd <password> [--count COUNT] [--dir DIR]
     ethstore remove <address> <password> [--dir DIR] [--vault VAULT] [--vault-pwd VAULTPWD]
     ethstore sign <address> <password> <message> [--dir DIR] [--vault VAULT] [--vault-pwd VAULTPWD]
     ethstore public <address> <password> [--dir DIR] [--vault VAULT] [--vault-pwd VAULTPWD]
@@ -75,6 +78,8 @@ Options:
     --src DIR                Specify import source. It may be either
                              parity, parity-(chain), geth, geth-test
                              or a path [default: geth].
+    --count COUNT            Number of HD wallet accounts to derive [default: 1].
+    --passphrase PHRASE      BIP-39 passphrase of the mnemonic.
 
 Commands:
     insert             Save account with password.
@@ -83,6 +88,10 @@ Commands:
     import             Import accounts from src.
     import-wallet      Import presale wallet.
     find-wallet-pass   Tries to open a wallet with list of passwords given.
+    import-mnemonic    Create HD wallet from BIP-39 mnemonic and derive its accounts
+                       along m/44'/60'/0'/0/i.
+    list-hd            List HD wallet accounts.
+    derive-hd          Derive more HD wallet accounts.
     remove             Remove account.
     sign               Sign message.
     public             Displays public key for an address.
@@ -101,6 +110,9 @@ struct Args {
 	cmd_import: bool,
 	cmd_import_wallet: bool,
 	cmd_find_wallet_pass: bool,
+	cmd_import_mnemonic: bool,
+	cmd_list_hd: bool,
+	cmd_derive_hd: bool,
 	cmd_remove: bool,
 	cmd_sign: bool,
 	cmd_public: bool,
@@ -117,10 +129,13 @@ struct Args {
 	arg_message: String,
 	arg_path: String,
 	arg_vault: String,
+	arg_phrase: String,
 	flag_src: String,
 	flag_dir: String,
 	flag_vault: String,
 	flag_vault_pwd: String,
+	flag_count: u32,
+	flag_passphrase: Option<String>,
 }
 
 enum Error {
@@ -269,6 +284,19 @@ fn execute<S, I>(command: I) -> Result<String, Error> where I: IntoIterator<Item
 		let passwords = passwords.as_str().lines().map(|line| str::to_owned(line).into()).collect::<VecDeque<_>>();
 		crack::run(passwords, &args.arg_path)?;
 		Ok(format!("Password not found."))
+	} else if args.cmd_import_mnemonic {
+		let mnemonic = Mnemonic::from_phrase(&args.arg_phrase).map_err(|e| ethstore::Error::Custom(e.to_string()))?;
+		let password = load_password(&args.arg_password)?;
+		let seed = mnemonic.seed(args.flag_passphrase.as_ref().map_or("", String::as_str));
+		store.create_hd_wallet(&seed, &password)?;
+		store.derive_hd_accounts(&password, args.flag_count)?;
+		Ok(format_accounts(&store.hd_accounts()?))
+	} else if args.cmd_list_hd {
+		Ok(format_accounts(&store.hd_accounts()?))
+	} else if args.cmd_derive_hd {
+		let password = load_password(&args.arg_password)?;
+		store.derive_hd_accounts(&password, args.flag_count)?;
+		Ok(format_accounts(&store.hd_accounts()?))
 	} else if args.cmd_remove {
 		let address = args.arg_address.parse().map_err(|_| ethstore::Error::InvalidAccount)?;
 		let password = load_password(&args.arg_password)?;
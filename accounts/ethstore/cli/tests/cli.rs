@@ -80,3 +80,26 @@ fn cli_cmd() {
 			   "--vault-pwd", test_password]);
 	assert_eq!(output, "0x35f222d88b80151857a2877826d940104887376a94c1cbd2c8c7c192eb701df88a18a4ecb8b05b1466c5b3706042027b5e079fe3a3683e66d822b0e047aa3418\n");
 }
+
+#[test]
+fn cli_hd_wallet() {
+	let dir = TempDir::new("test-hd-wallet").unwrap();
+
+	let mut passwd = File::create(dir.path().join("test-password")).unwrap();
+	writeln!(passwd, "password").unwrap();
+
+	let test_password_buf = dir.path().join("test-password");
+	let test_password: &str = test_password_buf.to_str().unwrap();
+	let dir_str: &str = dir.path().to_str().unwrap();
+	let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+	let output = run(&["import-mnemonic", phrase, test_password, "--dir", dir_str]);
+	assert_eq!(output, " 0: 0x9858effd232b4033e47d90003d41ec34ecaeda94\n");
+
+	let output = run(&["derive-hd", test_password, "--count", "2", "--dir", dir_str]);
+	assert_eq!(output.lines().count(), 3);
+
+	let output = run(&["list-hd", "--dir", dir_str]);
+	assert!(output.starts_with(" 0: 0x9858effd232b4033e47d90003d41ec34ecaeda94\n"));
+	assert_eq!(run(&["list", "--dir", dir_str]).lines().count(), 3);
+}
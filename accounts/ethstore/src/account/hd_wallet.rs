@@ -0,0 +1,46 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+use crypto::publickey::Address;
+use json;
+use super::crypto::Crypto;
+
+/// HD wallet: encrypted BIP-39 seed and the accounts derived from it
+#[derive(Debug, PartialEq, Clone)]
+pub struct HdWallet {
+	/// Encrypted seed
+	pub crypto: Crypto,
+	/// Addresses of the derived accounts, in the order of their BIP-44 index
+	pub accounts: Vec<Address>,
+}
+
+impl From<json::HdWalletFile> for HdWallet {
+	fn from(json: json::HdWalletFile) -> Self {
+		HdWallet {
+			crypto: json.crypto.into(),
+			accounts: json.accounts.into_iter().map(Into::into).collect(),
+		}
+	}
+}
+
+impl From<HdWallet> for json::HdWalletFile {
+	fn from(wallet: HdWallet) -> Self {
+		json::HdWalletFile {
+			crypto: wallet.crypto.into(),
+			accounts: wallet.accounts.into_iter().map(Into::into).collect(),
+		}
+	}
+}
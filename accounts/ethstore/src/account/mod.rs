@@ -16,12 +16,14 @@
 
 mod cipher;
 mod crypto;
+mod hd_wallet;
 mod kdf;
 mod safe_account;
 mod version;
 
 pub use self::cipher::{Cipher, Aes128Ctr};
 pub use self::crypto::Crypto;
+pub use self::hd_wallet::HdWallet;
 pub use self::kdf::{Kdf, Pbkdf2, Scrypt, Prf};
 pub use self::safe_account::SafeAccount;
 pub use self::version::Version;
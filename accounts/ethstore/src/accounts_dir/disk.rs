@@ -19,7 +19,7 @@ use std::io::Write;
 use std::path::{PathBuf, Path};
 use std::collections::HashMap;
 use time;
-use {json, SafeAccount, Error};
+use {json, SafeAccount, HdWallet, Error};
 use json::Uuid;
 use super::{KeyDirectory, VaultKeyDirectory, VaultKeyDirectoryProvider, VaultKey};
 use super::vault::{VAULT_FILE_NAME, VaultDiskDirectory};
@@ -32,8 +32,14 @@ const IGNORED_FILES: &'static [&'static str] = &[
 	"dapps_accounts.json",
 	"dapps_history.json",
 	"vault.json",
+	"hd_wallet.json",
 ];
 
+/// HD wallet file name.
+const HD_WALLET_FILE_NAME: &'static str = "hd_wallet.json";
+/// HD wallet temporary file name, hidden from the key files.
+const HD_WALLET_TEMP_FILE_NAME: &'static str = ".hd_wallet.json.tmp";
+
 /// Find a unique filename that does not exist using four-letter random suffix.
 pub fn find_unique_filename_using_random_suffix(parent_path: &Path, original_filename: &str) -> io::Result<String> {
 	let mut path = parent_path.join(original_filename);
@@ -284,6 +290,29 @@ impl<T> KeyDirectory for DiskDirectory<T> where T: KeyFileManager {
 
 	fn path(&self) -> Option<&PathBuf> { Some(&self.path) }
 
+	fn hd_wallet(&self) -> Result<Option<HdWallet>, Error> {
+		let file = match fs::File::open(self.path.join(HD_WALLET_FILE_NAME)) {
+			Ok(file) => file,
+			Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+			Err(e) => return Err(e.into()),
+		};
+		let wallet = json::HdWalletFile::load(file).map_err(|e| Error::Custom(format!("{:?}", e)))?;
+		Ok(Some(wallet.into()))
+	}
+
+	fn set_hd_wallet(&self, wallet: HdWallet) -> Result<(), Error> {
+		// write to temporary file first, then rename temporary file to wallet file
+		let temp_path = self.path.join(HD_WALLET_TEMP_FILE_NAME);
+		{
+			let mut file = replace_file_with_permissions_to_owner(&temp_path)?;
+			json::HdWalletFile::from(wallet).write(&mut file).map_err(|e| Error::Custom(format!("{:?}", e)))?;
+			file.flush()?;
+			file.sync_all()?;
+		}
+		fs::rename(&temp_path, self.path.join(HD_WALLET_FILE_NAME))?;
+		Ok(())
+	}
+
 	fn as_vault_provider(&self) -> Option<&dyn VaultKeyDirectoryProvider> {
 		Some(self)
 	}
@@ -19,13 +19,14 @@ use parking_lot::RwLock;
 use itertools;
 use crypto::publickey::Address;
 
-use {SafeAccount, Error};
+use {SafeAccount, HdWallet, Error};
 use super::KeyDirectory;
 
 /// Accounts in-memory storage.
 #[derive(Default)]
 pub struct MemoryDirectory {
 	accounts: RwLock<HashMap<Address, Vec<SafeAccount>>>,
+	hd_wallet: RwLock<Option<HdWallet>>,
 }
 
 impl KeyDirectory for MemoryDirectory {
@@ -65,6 +66,15 @@ impl KeyDirectory for MemoryDirectory {
 		Ok(())
 	}
 
+	fn hd_wallet(&self) -> Result<Option<HdWallet>, Error> {
+		Ok(self.hd_wallet.read().clone())
+	}
+
+	fn set_hd_wallet(&self, wallet: HdWallet) -> Result<(), Error> {
+		*self.hd_wallet.write() = Some(wallet);
+		Ok(())
+	}
+
 	fn unique_repr(&self) -> Result<u64, Error> {
 		let mut val = 0u64;
 		let accounts = self.accounts.read();
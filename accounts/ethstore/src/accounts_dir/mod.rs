@@ -18,7 +18,7 @@
 
 use ethkey::Password;
 use std::path::{PathBuf};
-use {SafeAccount, Error};
+use {SafeAccount, HdWallet, Error};
 
 mod disk;
 mod memory;
@@ -58,6 +58,10 @@ pub trait KeyDirectory: Send + Sync {
 	fn path(&self) -> Option<&PathBuf> { None }
 	/// Return vault provider, if available
 	fn as_vault_provider(&self) -> Option<&dyn VaultKeyDirectoryProvider> { None }
+	/// Read HD wallet, if stored
+	fn hd_wallet(&self) -> Result<Option<HdWallet>, Error> { Ok(None) }
+	/// Store HD wallet, replacing the existing one
+	fn set_hd_wallet(&self, _wallet: HdWallet) -> Result<(), Error> { Err(Error::HdWalletsAreNotSupported) }
 	/// Unique representation of directory account collection
 	fn unique_repr(&self) -> Result<u64, Error>;
 }
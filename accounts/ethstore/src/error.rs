@@ -46,6 +46,14 @@ pub enum Error {
 	VaultNotFound,
 	/// Account creation failed.
 	CreationFailed,
+	/// HD wallets are not supported.
+	HdWalletsAreNotSupported,
+	/// HD wallet not found
+	HdWalletNotFound,
+	/// HD wallet already exists
+	HdWalletAlreadyExists,
+	/// Too many HD wallet accounts requested at once
+	TooManyHdAccounts(u32),
 	/// `EthCrypto` error
 	EthCrypto(EthCryptoError),
 	/// `EthPublicKeyCryptoError` error
@@ -71,6 +79,10 @@ impl fmt::Display for Error {
 			Error::InvalidVaultName => "Invalid vault name".into(),
 			Error::VaultNotFound => "Vault not found".into(),
 			Error::CreationFailed => "Account creation failed".into(),
+			Error::HdWalletsAreNotSupported => "HD wallets are not supported".into(),
+			Error::HdWalletNotFound => "HD wallet not found".into(),
+			Error::HdWalletAlreadyExists => "HD wallet already exists".into(),
+			Error::TooManyHdAccounts(count) => format!("Cannot derive {} HD wallet accounts at once", count),
 			Error::EthCrypto(ref err) => err.to_string(),
 			Error::EthPublicKeyCrypto(ref err) => err.to_string(),
 			Error::Derivation(ref err) => format!("Derivation error: {:?}", err),
@@ -25,11 +25,14 @@ use random::Random;
 use crypto::publickey::{Signature, Address, Message, Secret, Public, KeyPair, ExtendedKeyPair};
 use ethkey::Password;
 use accounts_dir::{KeyDirectory, VaultKeyDirectory, VaultKey, SetKeyError};
-use account::SafeAccount;
+use account::{SafeAccount, Crypto, HdWallet};
 use presale::PresaleWallet;
 use json::{self, Uuid, OpaqueKeyFile};
 use {import, Error, SimpleSecretStore, SecretStore, SecretVaultRef, StoreAccountRef, Derivation, OpaqueSecret};
 
+/// Maximal number of HD wallet accounts derived at once.
+pub const MAX_HD_ACCOUNTS_DERIVED: u32 = 1000;
+
 /// Accounts store.
 pub struct EthStore {
 	store: EthMultiStore,
@@ -113,6 +116,18 @@ impl SimpleSecretStore for EthStore {
 		self.store.agree(account, password, other)
 	}
 
+	fn create_hd_wallet(&self, seed: &[u8], password: &Password) -> Result<(), Error> {
+		self.store.create_hd_wallet(seed, password)
+	}
+
+	fn hd_accounts(&self) -> Result<Vec<Address>, Error> {
+		self.store.hd_accounts()
+	}
+
+	fn derive_hd_accounts(&self, password: &Password, count: u32) -> Result<Vec<StoreAccountRef>, Error> {
+		self.store.derive_hd_accounts(password, count)
+	}
+
 	fn decrypt(&self, account: &StoreAccountRef, password: &Password, shared_mac: &[u8], message: &[u8]) -> Result<Vec<u8>, Error> {
 		let account = self.get(account)?;
 		account.decrypt(password, shared_mac, message)
@@ -263,6 +278,8 @@ pub struct EthMultiStore {
 	cache: RwLock<BTreeMap<StoreAccountRef, Vec<SafeAccount>>>,
 	vaults: Mutex<HashMap<String, Box<dyn VaultKeyDirectory>>>,
 	timestamp: Mutex<Timestamp>,
+	// guards updates of the HD wallet
+	hd_wallet: Mutex<()>,
 }
 
 struct Timestamp {
@@ -290,6 +307,7 @@ impl EthMultiStore {
 				// by default we never refresh accounts
 				refresh_time: Duration::from_secs(u64::max_value()),
 			}),
+			hd_wallet: Mutex::new(()),
 		};
 		store.reload_accounts()?;
 		Ok(store)
@@ -573,6 +591,46 @@ impl SimpleSecretStore for EthMultiStore {
 		}
 	}
 
+	fn create_hd_wallet(&self, seed: &[u8], password: &Password) -> Result<(), Error> {
+		let _lock = self.hd_wallet.lock();
+		if self.dir.hd_wallet()?.is_some() {
+			return Err(Error::HdWalletAlreadyExists);
+		}
+
+		let crypto = Crypto::with_plain(seed, password, self.iterations)?;
+		self.dir.set_hd_wallet(HdWallet { crypto, accounts: Vec::new() })
+	}
+
+	fn hd_accounts(&self) -> Result<Vec<Address>, Error> {
+		Ok(self.dir.hd_wallet()?.ok_or(Error::HdWalletNotFound)?.accounts)
+	}
+
+	fn derive_hd_accounts(&self, password: &Password, count: u32) -> Result<Vec<StoreAccountRef>, Error> {
+		if count > MAX_HD_ACCOUNTS_DERIVED {
+			return Err(Error::TooManyHdAccounts(count));
+		}
+		let _lock = self.hd_wallet.lock();
+		let mut wallet = self.dir.hd_wallet()?.ok_or(Error::HdWalletNotFound)?;
+		let seed = wallet.crypto.decrypt(password)?;
+
+		let start = wallet.accounts.len() as u32;
+		let mut derived = Vec::new();
+		for index in start..start.saturating_add(count) {
+			let secret = ethkey::bip44_secret(&seed, index)?;
+			let keypair = KeyPair::from_secret(secret).map_err(|_| Error::CreationFailed)?;
+			// the key file may be left from a previously removed wallet with the same seed
+			let account_ref = match self.account_ref(&keypair.address()) {
+				Ok(account_ref) => account_ref,
+				Err(_) => self.insert_account(SecretVaultRef::Root, keypair.secret().clone(), password)?,
+			};
+			wallet.accounts.push(account_ref.address);
+			derived.push(account_ref);
+		}
+
+		self.dir.set_hd_wallet(wallet)?;
+		Ok(derived)
+	}
+
 	fn create_vault(&self, name: &str, password: &Password) -> Result<(), Error> {
 		let is_vault_created = { // lock border
 			let mut vaults = self.vaults.lock();
@@ -697,6 +755,8 @@ mod tests {
 	use self::tempdir::TempDir;
 	use ethereum_types::H256;
 
+	const HD_PHRASE: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
 	fn keypair() -> KeyPair {
 		Random.generate().unwrap()
 	}
@@ -1096,6 +1156,47 @@ mod tests {
 		assert!(store.sign(&derived, &"test".into(), &Default::default()).is_ok(), "Second password should work for second store.");
 	}
 
+	#[test]
+	fn should_derive_hd_wallet_accounts() {
+		// given a store with the HD wallet
+		let store = store();
+		let seed = ::ethkey::Mnemonic::from_phrase(HD_PHRASE).unwrap().seed("");
+		store.create_hd_wallet(&seed, &"test".into()).unwrap();
+		assert!(store.create_hd_wallet(&seed, &"test".into()).is_err(), "Wallet should not be replaced.");
+
+		// when we derive the accounts in two steps
+		let first = store.derive_hd_accounts(&"test".into(), 2).unwrap();
+		let second = store.derive_hd_accounts(&"test".into(), 1).unwrap();
+
+		// then they follow the BIP-44 indices
+		let hd_accounts = store.hd_accounts().unwrap();
+		assert_eq!(hd_accounts.len(), 3);
+		assert_eq!(hd_accounts[0], "9858effd232b4033e47d90003d41ec34ecaeda94".parse().unwrap());
+		assert_eq!(first.iter().chain(second.iter()).map(|a| a.address).collect::<Vec<_>>(), hd_accounts);
+		assert_eq!(store.accounts().unwrap().len(), 3);
+		assert!(store.sign(&second[0], &"test".into(), &Default::default()).is_ok());
+		assert!(store.derive_hd_accounts(&"wrong".into(), 1).is_err());
+		assert!(store.derive_hd_accounts(&"test".into(), MAX_HD_ACCOUNTS_DERIVED + 1).is_err());
+		assert_eq!(store.hd_accounts().unwrap().len(), 3);
+	}
+
+	#[test]
+	fn should_keep_hd_wallet_on_disk() {
+		let temp_path = TempDir::new("").unwrap();
+		let seed = ::ethkey::Mnemonic::from_phrase(HD_PHRASE).unwrap().seed("");
+		{
+			let store = EthStore::open(Box::new(RootDiskDirectory::create(temp_path.path()).unwrap())).unwrap();
+			store.create_hd_wallet(&seed, &"test".into()).unwrap();
+			store.derive_hd_accounts(&"test".into(), 2).unwrap();
+		}
+
+		let store = EthStore::open(Box::new(RootDiskDirectory::at(temp_path.path()))).unwrap();
+		assert_eq!(store.accounts().unwrap().len(), 2, "Wallet file should not be loaded as a key file.");
+		assert_eq!(store.hd_accounts().unwrap().len(), 2);
+		store.derive_hd_accounts(&"test".into(), 1).unwrap();
+		assert_eq!(store.hd_accounts().unwrap().len(), 3);
+	}
+
 	#[test]
 	fn should_save_meta_when_setting_before_password() {
 		// given
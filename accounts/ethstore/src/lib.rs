@@ -57,9 +57,9 @@ mod presale;
 mod random;
 mod secret_store;
 
-pub use self::account::{SafeAccount, Crypto};
+pub use self::account::{SafeAccount, Crypto, HdWallet};
 pub use self::error::Error;
-pub use self::ethstore::{EthStore, EthMultiStore};
+pub use self::ethstore::{EthStore, EthMultiStore, MAX_HD_ACCOUNTS_DERIVED};
 pub use self::import::{import_account, import_accounts, read_geth_accounts};
 pub use self::json::OpaqueKeyFile as KeyFile;
 pub use self::presale::PresaleWallet;
@@ -77,6 +77,13 @@ pub trait SimpleSecretStore: Send + Sync {
 	/// Agree on shared key.
 	fn agree(&self, account: &StoreAccountRef, password: &Password, other: &Public) -> Result<Secret, Error>;
 
+	/// Stores the BIP-39 seed of the HD wallet, encrypted with given password.
+	fn create_hd_wallet(&self, seed: &[u8], password: &Password) -> Result<(), Error>;
+	/// Returns the HD wallet accounts, in the order of their BIP-44 index.
+	fn hd_accounts(&self) -> Result<Vec<Address>, Error>;
+	/// Derives the next `count` HD wallet accounts along `m/44'/60'/0'/0/i` and inserts them to the store.
+	fn derive_hd_accounts(&self, password: &Password, count: u32) -> Result<Vec<StoreAccountRef>, Error>;
+
 	/// Returns all accounts in this secret store.
 	fn accounts(&self) -> Result<Vec<StoreAccountRef>, Error>;
 	/// Get reference to some account with given address.
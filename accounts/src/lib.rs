@@ -28,12 +28,12 @@ use self::stores::AddressBook;
 use std::collections::HashMap;
 use std::time::{Instant, Duration};
 
-use ethkey::Password;
+use ethkey::{Mnemonic, Password};
 use parity_crypto::publickey::{Address, Message, Public, Secret, Random, Generator, Signature};
 use ethstore::accounts_dir::MemoryDirectory;
 use ethstore::{
 	SimpleSecretStore, SecretStore, EthStore, EthMultiStore,
-	random_string, SecretVaultRef, StoreAccountRef, OpaqueSecret,
+	random_string, SecretVaultRef, StoreAccountRef, OpaqueSecret, MAX_HD_ACCOUNTS_DERIVED,
 };
 use log::warn;
 use parking_lot::RwLock;
@@ -157,6 +157,38 @@ impl AccountProvider {
 		)
 	}
 
+	/// Creates the HD wallet from a new random BIP-39 mnemonic with given number of words,
+	/// derives its first account and returns the phrase.
+	pub fn new_hd_wallet(&self, words: usize, password: &Password) -> Result<String, Error> {
+		let mnemonic = Mnemonic::generate(words).map_err(|e| Error::Custom(e.to_string()))?;
+		self.sstore.create_hd_wallet(&mnemonic.seed(""), password)?;
+		self.derive_hd_accounts(password, 1)?;
+		Ok(mnemonic.phrase().to_owned())
+	}
+
+	/// Creates the HD wallet from a BIP-39 mnemonic phrase (and optional BIP-39 passphrase)
+	/// and derives its first `count` accounts.
+	pub fn import_hd_wallet(&self, phrase: &str, passphrase: &str, password: &Password, count: u32) -> Result<Vec<Address>, Error> {
+		if count > MAX_HD_ACCOUNTS_DERIVED {
+			return Err(Error::TooManyHdAccounts(count));
+		}
+		let mnemonic = Mnemonic::from_phrase(phrase).map_err(|e| Error::Custom(e.to_string()))?;
+		self.sstore.create_hd_wallet(&mnemonic.seed(passphrase), password)?;
+		self.derive_hd_accounts(password, count)
+	}
+
+	/// Returns addresses of the HD wallet accounts, in the order of their BIP-44 index.
+	pub fn hd_accounts(&self) -> Result<Vec<Address>, Error> {
+		self.sstore.hd_accounts()
+	}
+
+	/// Derives the next `count` accounts of the HD wallet. The accounts are stored with
+	/// the password of the wallet.
+	pub fn derive_hd_accounts(&self, password: &Password, count: u32) -> Result<Vec<Address>, Error> {
+		let accounts = self.sstore.derive_hd_accounts(password, count)?;
+		Ok(accounts.into_iter().map(|a| a.address).collect())
+	}
+
 	/// Import a new presale wallet.
 	pub fn import_presale(&self, presale_json: &[u8], password: &Password) -> Result<Address, Error> {
 		let account = self.sstore.import_presale(SecretVaultRef::Root, presale_json, password)?;
@@ -518,6 +550,23 @@ mod tests {
 		assert!(ap.sign(kp.address(), None, Default::default()).is_err());
 	}
 
+	#[test]
+	fn hd_wallet_accounts() {
+		let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+		let ap = AccountProvider::transient_provider();
+		assert!(ap.import_hd_wallet("abandon abandon", "", &"test".into(), 1).is_err());
+		assert!(ap.import_hd_wallet(phrase, "", &"test".into(), MAX_HD_ACCOUNTS_DERIVED + 1).is_err());
+
+		let accounts = ap.import_hd_wallet(phrase, "", &"test".into(), 2).unwrap();
+		assert_eq!(accounts[0], "9858effd232b4033e47d90003d41ec34ecaeda94".parse::<Address>().unwrap());
+		assert!(ap.new_hd_wallet(12, &"test".into()).is_err(), "There is one HD wallet per store");
+
+		let derived = ap.derive_hd_accounts(&"test".into(), 1).unwrap();
+		assert_eq!(ap.hd_accounts().unwrap(), vec![accounts[0], accounts[1], derived[0]]);
+		assert_eq!(ap.accounts().unwrap().len(), 3);
+		assert!(ap.unlock_account_temporarily(derived[0], "test".into()).is_ok());
+	}
+
 	#[test]
 	fn derived_account_nosave() {
 		let kp = Random.generate().unwrap();
@@ -521,6 +521,11 @@ impl Account {
 		Ok(())
 	}
 
+	/// Whether `commit_code` writes a new code.
+	pub fn has_new_code(&self) -> bool {
+		self.code_filth == Filth::Dirty && !self.code_cache.is_empty()
+	}
+
 	/// Commit any unsaved code. `code_hash` will always return the hash of the `code_cache` after this.
 	pub fn commit_code(&mut self, db: &mut dyn HashDB<KeccakHasher, DBValue>) {
 		trace!("Commiting code of {:?} - {:?}, {:?}", self, self.code_filth == Filth::Dirty, self.code_cache.is_empty());
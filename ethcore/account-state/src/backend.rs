@@ -28,6 +28,7 @@ use ethereum_types::{Address, H256};
 use hash_db::{AsHashDB, EMPTY_PREFIX, HashDB, Prefix};
 use kvdb::DBValue;
 use memory_db::{HashKey, MemoryDB};
+use parity_bytes::Bytes;
 use parking_lot::Mutex;
 use keccak_hasher::KeccakHasher;
 
@@ -69,6 +70,30 @@ pub trait Backend: Send {
 	/// Check whether an account is known to be empty. Returns true if known to be
 	/// empty, false otherwise.
 	fn is_known_null(&self, address: &Address) -> bool;
+
+	/// Get an account by its address hash from the flat state at the given state root.
+	/// Returns `None` if the backend has no flat state for that root.
+	fn get_flat_account(&self, _root: &H256, _address_hash: &H256) -> Option<Option<Account>> { None }
+
+	/// Get a storage value by the account address hash and the storage key hash from the
+	/// flat state at the given state root. Returns `None` if the backend has no flat state
+	/// for that root.
+	fn get_flat_storage(&self, _root: &H256, _address_hash: &H256, _key: &H256) -> Option<H256> { None }
+
+	/// Note the changes of a commit moving the state trie from `parent` to `root`.
+	fn note_flat_changes(&mut self, _parent: &H256, _root: &H256, _changes: FlatChanges) {}
+}
+
+/// Account and storage changes made by a state commit, by account address hash
+/// and storage key hash.
+#[derive(Debug, Default)]
+pub struct FlatChanges {
+	/// Updated account RLPs. `None` for removed accounts.
+	pub accounts: Vec<(H256, Option<Bytes>)>,
+	/// Accounts whose storage has been cleared before applying `storage`.
+	pub wiped: Vec<H256>,
+	/// Updated storage values by account address hash and storage key hash.
+	pub storage: Vec<(H256, H256, H256)>,
 }
 
 /// A raw backend used to check proofs of execution.
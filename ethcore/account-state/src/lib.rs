@@ -28,6 +28,6 @@ pub mod state;
 
 pub use {
 	account::Account,
-	backend::Backend,
+	backend::{Backend, FlatChanges},
 	state::{State, CleanupMode},
 };
@@ -38,7 +38,7 @@ use ethereum_types::{Address, H256, U256};
 use ethtrie::{TrieDB, Result as TrieResult};
 use trie_vm_factories::{Factories, VmFactory};
 use hash_db::HashDB;
-use keccak_hash::{KECCAK_EMPTY, KECCAK_NULL_RLP};
+use keccak_hash::{keccak, KECCAK_EMPTY, KECCAK_NULL_RLP};
 use keccak_hasher::KeccakHasher;
 use kvdb::DBValue;
 use log::{warn, trace};
@@ -48,7 +48,7 @@ use trie_db::{Trie, TrieError, Recorder};
 
 use crate::{
 	account::Account,
-	backend::Backend,
+	backend::{Backend, FlatChanges},
 };
 
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
@@ -538,6 +538,15 @@ impl<B: Backend> State<B> {
 		// 1. If there's an entry for the account in the local cache check for the key and return it if found.
 		// 2. If there's an entry for the account in the global cache check for the key or load it into that account.
 		// 3. If account is missing in the global cache load it into the local cache and cache the key there.
+		// Storage not in the caches is read from the flat state when the backend has it.
+		let flat_or_at = |account: &Account, db: &dyn HashDB<KeccakHasher, DBValue>, key: &H256| {
+			if account.is_base_storage_root_unchanged() {
+				if let Some(value) = self.db.get_flat_storage(&self.root, &account.address_hash(address), &keccak(key)) {
+					return Ok(value);
+				}
+			}
+			f_at(account, db, key)
+		};
 
 		{
 			// check local cache first without updating
@@ -560,7 +569,7 @@ impl<B: Backend> State<B> {
 				None => Ok(H256::zero()),
 				Some(a) => {
 					let account_db = self.factories.accountdb.readonly(self.db.as_hash_db(), a.address_hash(address));
-					f_at(a, account_db.as_hash_db(), key)
+					flat_or_at(a, account_db.as_hash_db(), key)
 				}
 			});
 
@@ -572,7 +581,7 @@ impl<B: Backend> State<B> {
 			if let Some(ref mut acc) = local_account {
 				if let Some(ref account) = acc.account {
 					let account_db = self.factories.accountdb.readonly(self.db.as_hash_db(), account.address_hash(address));
-					return f_at(account, account_db.as_hash_db(), key)
+					return flat_or_at(account, account_db.as_hash_db(), key)
 				} else {
 					return Ok(H256::zero())
 				}
@@ -583,13 +592,18 @@ impl<B: Backend> State<B> {
 		if self.db.is_known_null(address) { return Ok(H256::zero()) }
 
 		// account is not found in the global cache, get from the DB and insert into local
-		let db = &self.db.as_hash_db();
-		let db = self.factories.trie.readonly(db, &self.root).expect(SEC_TRIE_DB_UNWRAP_STR);
-		let from_rlp = |b: &[u8]| Account::from_rlp(b).expect("decoding db value failed");
-		let maybe_acc = db.get_with(address.as_bytes(), from_rlp)?;
+		let maybe_acc = match self.db.get_flat_account(&self.root, &keccak(address)) {
+			Some(maybe_acc) => maybe_acc,
+			None => {
+				let db = &self.db.as_hash_db();
+				let db = self.factories.trie.readonly(db, &self.root).expect(SEC_TRIE_DB_UNWRAP_STR);
+				let from_rlp = |b: &[u8]| Account::from_rlp(b).expect("decoding db value failed");
+				db.get_with(address.as_bytes(), from_rlp)?
+			},
+		};
 		let r = maybe_acc.as_ref().map_or(Ok(H256::zero()), |a| {
 			let account_db = self.factories.accountdb.readonly(self.db.as_hash_db(), a.address_hash(address));
-			f_at(a, account_db.as_hash_db(), key)
+			flat_or_at(a, account_db.as_hash_db(), key)
 		});
 		self.insert_cache(address, AccountEntry::new_clean(maybe_acc));
 		r
@@ -711,34 +725,51 @@ impl<B: Backend> State<B> {
 		assert!(self.checkpoints.borrow().is_empty());
 		// first, commit the sub trees.
 		let mut accounts = self.cache.borrow_mut();
+		let mut flat_changes = FlatChanges::default();
 		for (address, ref mut a) in accounts.iter_mut().filter(|&(_, ref a)| a.is_dirty()) {
 			if let Some(ref mut account) = a.account {
 				let addr_hash = account.address_hash(address);
+				if !account.is_base_storage_root_unchanged() {
+					flat_changes.wiped.push(addr_hash);
+				}
+				flat_changes.storage.extend(account.storage_changes().iter().map(|(k, v)| (addr_hash, keccak(k), *v)));
+				let new_code = if account.has_new_code() { account.code() } else { None };
 				{
 					let mut account_db = self.factories.accountdb.create(self.db.as_hash_db_mut(), addr_hash);
 					account.commit_storage(&self.factories.trie, account_db.as_hash_db_mut())?;
 					account.commit_code(account_db.as_hash_db_mut());
 				}
+				// codes are also stored by their hash alone, to be served without knowing an owner
+				if let (Some(code), account_db::Factory::Mangled) = (new_code, &self.factories.accountdb) {
+					self.db.as_hash_db_mut().emplace(account.code_hash(), hash_db::EMPTY_PREFIX, code.to_vec());
+				}
 				if !account.is_empty() {
 					self.db.note_non_null_account(address);
 				}
 			}
 		}
 
+		let parent = self.root;
 		{
 			let mut trie = self.factories.trie.from_existing(self.db.as_hash_db_mut(), &mut self.root)?;
 			for (address, ref mut a) in accounts.iter_mut().filter(|&(_, ref a)| a.is_dirty()) {
 				a.state = AccountState::Committed;
 				match a.account {
 					Some(ref mut account) => {
-						trie.insert(address.as_bytes(), &account.rlp())?;
+						let rlp = account.rlp();
+						trie.insert(address.as_bytes(), &rlp)?;
+						flat_changes.accounts.push((account.address_hash(address), Some(rlp)));
 					},
 					None => {
 						trie.remove(address.as_bytes())?;
+						let addr_hash = keccak(address);
+						flat_changes.wiped.push(addr_hash);
+						flat_changes.accounts.push((addr_hash, None));
 					},
 				};
 			}
 		}
+		self.db.note_flat_changes(&parent, &self.root, flat_changes);
 
 		Ok(())
 	}
@@ -996,10 +1027,7 @@ impl<B: Backend> State<B> {
 				if check_null && self.db.is_known_null(a) { return Ok(f(None)); }
 
 				// not found in the global cache, get from the DB and insert into local
-				let db = &self.db.as_hash_db();
-				let db = self.factories.trie.readonly(db, &self.root)?;
-				let from_rlp = |b: &[u8]| Account::from_rlp(b).expect("decoding db value failed");
-				let mut maybe_acc = db.get_with(a.as_bytes(), from_rlp)?;
+				let mut maybe_acc = self.load_account(a)?;
 				if let Some(ref mut account) = maybe_acc.as_mut() {
 					let accountdb = self.factories.accountdb.readonly(self.db.as_hash_db(), account.address_hash(a));
 					if !Self::update_account_cache(require, account, &self.db, accountdb.as_hash_db()) {
@@ -1013,6 +1041,18 @@ impl<B: Backend> State<B> {
 		}
 	}
 
+	/// Load account `a` at the current state root, from the flat state if the backend
+	/// has it or from the trie otherwise.
+	fn load_account(&self, a: &Address) -> TrieResult<Option<Account>> {
+		if let Some(maybe_acc) = self.db.get_flat_account(&self.root, &keccak(a)) {
+			return Ok(maybe_acc);
+		}
+		let db = &self.db.as_hash_db();
+		let db = self.factories.trie.readonly(db, &self.root)?;
+		let from_rlp = |b: &[u8]| Account::from_rlp(b).expect("decoding db value failed");
+		db.get_with(a.as_bytes(), from_rlp)
+	}
+
 	/// Pull account `a` in our cache from the trie DB. `require_code` requires that the code be cached, too.
 	pub fn require<'a>(&'a self, a: &Address, require_code: bool) -> TrieResult<RefMut<'a, Account>> {
 		self.require_or_from(a, require_code, || Account::new_basic(0u8.into(), self.account_start_nonce), |_| {})
@@ -1029,10 +1069,7 @@ impl<B: Backend> State<B> {
 				Some(acc) => self.insert_cache(a, AccountEntry::new_clean_cached(acc)),
 				None => {
 					let maybe_acc = if !self.db.is_known_null(a) {
-						let db = &self.db.as_hash_db();
-						let db = self.factories.trie.readonly(db, &self.root)?;
-						let from_rlp = |b:&[u8]| { Account::from_rlp(b).expect("decoding db value failed") };
-						AccountEntry::new_clean(db.get_with(a.as_bytes(), from_rlp)?)
+						AccountEntry::new_clean(self.load_account(a)?)
 					} else {
 						AccountEntry::new_clean(None)
 					};
@@ -43,6 +43,7 @@ use ethcore_db::cache_manager::CacheManager;
 use ethcore_db::keys::{BlockReceipts, BlockDetails, TransactionAddress, EPOCH_KEY_PREFIX, EpochTransitions};
 use ethcore_db::{self as db, Writable, Readable, CacheUpdatePolicy};
 use ethereum_types::{H256, Bloom, BloomRef, U256};
+use freezer_db::Table as FreezerTable;
 use util_mem::{MallocSizeOf, allocators::new_malloc_size_ops};
 use itertools::Itertools;
 use kvdb::{DBTransaction, KeyValueDB};
@@ -54,6 +55,7 @@ use rlp::RlpStream;
 use rlp_compress::{compress, decompress, blocks_swapper};
 
 use crate::best_block::{BestBlock, BestAncientBlock};
+use crate::freezer::freeze_blocks;
 use crate::update::{ExtrasUpdate, ExtrasInsert};
 use crate::{CacheSize, Config};
 
@@ -68,18 +70,29 @@ pub trait BlockChainDB: Send + Sync {
 	/// Trace blooms database.
 	fn trace_blooms(&self) -> &blooms_db::Database;
 
+	/// Ancient blocks database, if the blocks are not all kept in the key value store.
+	fn freezer(&self) -> Option<&freezer_db::Database> {
+		None
+	}
+
 	/// Restore the DB from the given path
 	fn restore(&self, new_db: &str) -> Result<(), io::Error> {
-		// First, close the Blooms databases
+		// First, close the Blooms and freezer databases
 		self.blooms().close()?;
 		self.trace_blooms().close()?;
+		if let Some(freezer) = self.freezer() {
+			freezer.close()?;
+		}
 
 		// Restore the key_value DB
 		self.key_value().restore(new_db)?;
 
-		// Re-open the Blooms databases
+		// Re-open the Blooms and freezer databases
 		self.blooms().reopen()?;
 		self.trace_blooms().reopen()?;
+		if let Some(freezer) = self.freezer() {
+			freezer.reopen()?;
+		}
 		Ok(())
 	}
 }
@@ -142,10 +155,15 @@ pub trait BlockProvider {
 	/// Get the block body (uncles and transactions).
 	fn block_body(&self, hash: &H256) -> Option<encoded::Body>;
 
+	/// Number of the first block with a base fee in its header (EIP-1559).
+	fn eip1559_transition(&self) -> BlockNumber {
+		BlockNumber::max_value()
+	}
+
 	/// Get a list of uncles for a given block.
 	/// Returns None if block does not exist.
 	fn uncles(&self, hash: &H256) -> Option<Vec<Header>> {
-		self.block_body(hash).map(|body| body.uncles())
+		self.block_body(hash).map(|body| body.uncles(self.eip1559_transition()))
 	}
 
 	/// Get a list of uncle hashes for a given block.
@@ -227,6 +245,10 @@ pub struct BlockChain {
 	// are no gaps in the chain; `Some(hash)` means that the database was warp-synced.
 	// This is calculated on start and is not updated.
 	first_block: Option<H256>,
+	// Number of the first block with a base fee in its header.
+	eip1559_transition: BlockNumber,
+	// Number of blocks below the best block which are kept out of the freezer.
+	freezer_depth: Option<BlockNumber>,
 
 	// block cache
 	block_headers: RwLock<HashMap<H256, encoded::Header>>,
@@ -250,6 +272,10 @@ pub struct BlockChain {
 }
 
 impl BlockProvider for BlockChain {
+	fn eip1559_transition(&self) -> BlockNumber {
+		self.eip1559_transition
+	}
+
 	/// Returns true if the given block is known
 	/// (though not necessarily a part of the canon chain).
 	fn is_known(&self, hash: &H256) -> bool {
@@ -295,9 +321,12 @@ impl BlockProvider for BlockChain {
 
 		// Read from DB and populate cache
 		let b = self.db.key_value().get(db::COL_HEADERS, hash.as_bytes())
-			.expect("Low level database error when fetching block header data. Some issue with disk?")?;
+			.expect("Low level database error when fetching block header data. Some issue with disk?");
 
-		let header = encoded::Header::new(decompress(&b, blocks_swapper()).into_vec());
+		let header = match b {
+			Some(b) => encoded::Header::new(decompress(&b, blocks_swapper()).into_vec()),
+			None => encoded::Header::new(self.frozen(FreezerTable::Headers, hash)?),
+		};
 		let mut write = self.block_headers.write();
 		write.insert(*hash, header.clone());
 
@@ -325,9 +354,12 @@ impl BlockProvider for BlockChain {
 
 		// Read from DB and populate cache
 		let b = self.db.key_value().get(db::COL_BODIES, hash.as_bytes())
-			.expect("Low level database error when fetching block body data. Some issue with disk?")?;
+			.expect("Low level database error when fetching block body data. Some issue with disk?");
 
-		let body = encoded::Body::new(decompress(&b, blocks_swapper()).into_vec());
+		let body = match b {
+			Some(b) => encoded::Body::new(decompress(&b, blocks_swapper()).into_vec()),
+			None => encoded::Body::new(self.frozen(FreezerTable::Bodies, hash)?),
+		};
 		let mut write = self.block_bodies.write();
 		write.insert(*hash, body.clone());
 
@@ -358,7 +390,16 @@ impl BlockProvider for BlockChain {
 
 	/// Get receipts of block with given hash.
 	fn block_receipts(&self, hash: &H256) -> Option<BlockReceipts> {
-		let result = self.db.key_value().read_with_cache(db::COL_EXTRA, &self.block_receipts, hash)?;
+		let result = match self.db.key_value().read_with_cache(db::COL_EXTRA, &self.block_receipts, hash) {
+			Some(receipts) => receipts,
+			None => {
+				let receipts: BlockReceipts = self.frozen(FreezerTable::Receipts, hash)
+					.filter(|receipts| !receipts.is_empty())
+					.map(|receipts| rlp::decode(&receipts).expect("decode frozen receipts failed"))?;
+				self.block_receipts.write().insert(*hash, receipts.clone());
+				receipts
+			},
+		};
 		self.cache_man.lock().note_used(CacheId::BlockReceipts(*hash));
 		Some(result)
 	}
@@ -480,7 +521,7 @@ impl<'a> Iterator for AncestryWithMetadataIter<'a> {
 		} else {
 			let details = self.chain.block_details(&self.current);
 			let header = self.chain.block_header_data(&self.current)
-				.map(|h| h.decode().expect("Stored block header data is valid RLP; qed"));
+				.map(|h| h.decode(self.chain.eip1559_transition).expect("Stored block header data is valid RLP; qed"));
 
 			match (details, header) {
 				(Some(details), Some(header)) => {
@@ -550,6 +591,8 @@ impl BlockChain {
 
 		let mut bc = BlockChain {
 			first_block: None,
+			eip1559_transition: config.eip1559_transition,
+			freezer_depth: config.freezer_depth,
 			best_block: RwLock::new(BestBlock {
 				// BestBlock will be overwritten anyway.
 				header: Default::default(),
@@ -619,7 +662,7 @@ impl BlockChain {
 			let mut best_block = bc.best_block.write();
 			*best_block = BestBlock {
 				total_difficulty: best_block_total_difficulty,
-				header: best_block_rlp.decode_header(),
+				header: best_block_rlp.decode_header(bc.eip1559_transition),
 				block: best_block_rlp,
 			};
 		}
@@ -899,7 +942,7 @@ impl BlockChain {
 		let mut best_block = self.best_block.write();
 		*best_block = BestBlock {
 			total_difficulty: best_block_total_difficulty,
-			header: best_block_rlp.decode_header(),
+			header: best_block_rlp.decode_header(self.eip1559_transition),
 			block: best_block_rlp,
 		};
 	}
@@ -1231,7 +1274,7 @@ impl BlockChain {
 				batch.put(db::COL_EXTRA, b"best", update.info.hash.as_bytes());
 				*best_block = Some(BestBlock {
 					total_difficulty: update.info.total_difficulty,
-					header: update.block.decode_header(),
+					header: update.block.decode_header(self.eip1559_transition),
 					block: update.block,
 				});
 			}
@@ -1541,6 +1584,32 @@ impl BlockChain {
 		}
 	}
 
+	/// Moves the data of the canonical blocks deeper than the configured freezer depth
+	/// from the key value store to the freezer, at most `max_blocks` of them.
+	/// Returns the number of moved blocks.
+	pub fn freeze_ancient_blocks(&self, max_blocks: u64) -> io::Result<u64> {
+		let depth = match self.freezer_depth {
+			Some(depth) => depth,
+			None => return Ok(0),
+		};
+		let limit = (self.best_block_number() + 1).saturating_sub(depth);
+		freeze_blocks(&*self.db, limit, max_blocks)
+	}
+
+	/// Reads an item of a canonical block from the freezer.
+	fn frozen(&self, table: FreezerTable, hash: &H256) -> Option<Bytes> {
+		let freezer = self.db.freezer()?;
+		if freezer.len() == 0 {
+			return None;
+		}
+		let number = self.block_details(hash)?.number;
+		if number >= freezer.len() || self.block_hash(number)? != *hash {
+			return None;
+		}
+		freezer.get(table, number)
+			.expect("Low level database error when fetching frozen block data. Some issue with disk?")
+	}
+
 	/// Ticks our cache system and throws out any old data.
 	pub fn collect_garbage(&self) {
 		let current_size = self.cache_size().total();
@@ -1635,8 +1704,10 @@ mod tests {
 	struct TestBlockChainDB {
 		_blooms_dir: TempDir,
 		_trace_blooms_dir: TempDir,
+		_freezer_dir: TempDir,
 		blooms: blooms_db::Database,
 		trace_blooms: blooms_db::Database,
+		freezer: freezer_db::Database,
 		key_value: Arc<dyn KeyValueDB>,
 	}
 
@@ -1652,18 +1723,25 @@ mod tests {
 		fn trace_blooms(&self) -> &blooms_db::Database {
 			&self.trace_blooms
 		}
+
+		fn freezer(&self) -> Option<&freezer_db::Database> {
+			Some(&self.freezer)
+		}
 	}
 
 	/// Creates new test instance of `BlockChainDB`
 	pub fn new_db() -> Arc<dyn BlockChainDB> {
 		let blooms_dir = TempDir::new("").unwrap();
 		let trace_blooms_dir = TempDir::new("").unwrap();
+		let freezer_dir = TempDir::new("").unwrap();
 
 		let db = TestBlockChainDB {
 			blooms: blooms_db::Database::open(blooms_dir.path()).unwrap(),
 			trace_blooms: blooms_db::Database::open(trace_blooms_dir.path()).unwrap(),
+			freezer: freezer_db::Database::open(freezer_dir.path()).unwrap(),
 			_blooms_dir: blooms_dir,
 			_trace_blooms_dir: trace_blooms_dir,
+			_freezer_dir: freezer_dir,
 			key_value: Arc::new(kvdb_memorydb::create(ethcore_db::NUM_COLUMNS))
 		};
 
@@ -1761,6 +1839,46 @@ mod tests {
 		assert_eq!(bc.block_hash(2), None);
 	}
 
+	#[test]
+	fn moves_ancient_blocks_to_freezer() {
+		let genesis = BlockBuilder::genesis();
+		let first_10 = genesis.add_blocks(10);
+		let generator = BlockGenerator::new(vec![first_10]);
+		let receipts = vec![Receipt {
+			outcome: TransactionOutcome::StatusCode(1),
+			gas_used: 21_000.into(),
+			log_bloom: Default::default(),
+			logs: vec![],
+			tx_type: Default::default(),
+		}];
+
+		let db = new_db();
+		let config = Config { freezer_depth: Some(3), ..Default::default() };
+		let bc = BlockChain::new(config.clone(), genesis.last().encoded().raw(), db.clone());
+		let mut blocks = vec![genesis.last().encoded()];
+		for block in generator {
+			blocks.push(block.encoded());
+			insert_block(&db, &bc, block.encoded(), receipts.clone());
+		}
+
+		// blocks #0..#7 are deeper than 3 blocks below the best block #10.
+		assert_eq!(bc.freeze_ancient_blocks(5).unwrap(), 5);
+		assert_eq!(bc.freeze_ancient_blocks(100).unwrap(), 3);
+		assert_eq!(bc.freeze_ancient_blocks(100).unwrap(), 0);
+		assert_eq!(db.freezer().unwrap().len(), 8);
+
+		// read through a new chain to bypass the caches.
+		let bc = BlockChain::new(config, genesis.last().encoded().raw(), db.clone());
+		for (number, block) in blocks.iter().enumerate() {
+			let hash = block.hash();
+			let in_key_value = db.key_value().get(ethcore_db::COL_HEADERS, hash.as_bytes()).unwrap().is_some();
+			assert_eq!(in_key_value, number >= 8);
+			assert_eq!(bc.block(&hash).unwrap().raw(), block.raw());
+			assert_eq!(bc.block_receipts(&hash).map(|r| r.receipts), if number == 0 { None } else { Some(receipts.clone()) });
+		}
+		assert_eq!(bc.block_header_data(&H256::from_low_u64_be(1)), None);
+	}
+
 	#[test]
 	fn check_ancestry_iter() {
 		let genesis = BlockBuilder::genesis();
@@ -2195,6 +2313,7 @@ mod tests {
 				LogEntry { address: Default::default(), topics: vec![], data: vec![1], },
 				LogEntry { address: Default::default(), topics: vec![], data: vec![2], },
 			],
+			tx_type: Default::default(),
 		},
 		Receipt {
 			outcome: TransactionOutcome::StateRoot(H256::zero()),
@@ -2203,6 +2322,7 @@ mod tests {
 			logs: vec![
 				LogEntry { address: Default::default(), topics: vec![], data: vec![3], },
 			],
+			tx_type: Default::default(),
 		}]);
 		insert_block(&db, &bc, b2.last().encoded(), vec![
 			Receipt {
@@ -2212,6 +2332,7 @@ mod tests {
 				logs: vec![
 					LogEntry { address: Default::default(), topics: vec![], data: vec![4], },
 				],
+				tx_type: Default::default(),
 			}
 		]);
 		insert_block(&db, &bc, b3.last().encoded(), vec![
@@ -2222,6 +2343,7 @@ mod tests {
 				logs: vec![
 					LogEntry { address: Default::default(), topics: vec![], data: vec![5], },
 				],
+				tx_type: Default::default(),
 			}
 		]);
 
@@ -16,6 +16,10 @@
 
 //! Blockchain configuration.
 
+use common_types::BlockNumber;
+
+use crate::freezer::DEFAULT_FREEZER_DEPTH;
+
 /// Blockchain configuration.
 #[derive(Debug, PartialEq, Clone)]
 pub struct Config {
@@ -23,6 +27,11 @@ pub struct Config {
 	pub pref_cache_size: usize,
 	/// Maximum cache size in bytes.
 	pub max_cache_size: usize,
+	/// Number of the first block with a base fee in its header (EIP-1559).
+	pub eip1559_transition: BlockNumber,
+	/// Number of blocks below the best block whose data is kept out of the freezer,
+	/// `None` to never move blocks to the freezer.
+	pub freezer_depth: Option<BlockNumber>,
 }
 
 impl Default for Config {
@@ -30,6 +39,8 @@ impl Default for Config {
 		Config {
 			pref_cache_size: 1 << 14,
 			max_cache_size: 1 << 20,
+			eip1559_transition: BlockNumber::max_value(),
+			freezer_depth: Some(DEFAULT_FREEZER_DEPTH),
 		}
 	}
 }
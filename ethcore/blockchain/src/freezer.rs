@@ -0,0 +1,101 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Moving the ancient blocks from the key-value database to the freezer.
+
+use std::{cmp, io};
+
+use common_types::BlockNumber;
+use ethcore_db::keys::BlockReceipts;
+use ethcore_db::{self as db, Key, Readable};
+use ethereum_types::{H256, H264};
+use kvdb::DBTransaction;
+use log::debug;
+use rlp_compress::{decompress, blocks_swapper};
+
+use crate::blockchain::BlockChainDB;
+
+/// Number of blocks below the best block which are kept in the key-value database by default.
+pub const DEFAULT_FREEZER_DEPTH: BlockNumber = 90_000;
+
+/// Number of frozen blocks which are already removed from the key-value database.
+const FROZEN_KEY: &[u8] = b"frozen";
+
+/// Moves the canonical blocks below `limit` which follow the frozen ones to the freezer,
+/// at most `max_blocks` of them. Stops at the first block which is missing (e.g. not yet
+/// downloaded after a warp sync). Returns the number of moved blocks.
+///
+/// Blocks are appended to the freezer and flushed before they are deleted from the
+/// key-value database, so an interruption at any point loses no data; the deletion
+/// of blocks which were frozen but not deleted is finished by the next call.
+pub fn freeze_blocks(db: &dyn BlockChainDB, limit: BlockNumber, max_blocks: u64) -> io::Result<u64> {
+	let freezer = match db.freezer() {
+		Some(freezer) => freezer,
+		None => return Ok(0),
+	};
+	let key_value = db.key_value();
+
+	let start = freezer.len();
+	let end = cmp::min(limit, start.saturating_add(max_blocks));
+	for number in start..end {
+		let hash: H256 = match key_value.read(db::COL_EXTRA, &number) {
+			Some(hash) => hash,
+			None => break,
+		};
+		let header = key_value.get(db::COL_HEADERS, hash.as_bytes())?;
+		let body = key_value.get(db::COL_BODIES, hash.as_bytes())?;
+		let (header, body) = match (header, body) {
+			(Some(header), Some(body)) => (header, body),
+			_ => break,
+		};
+		// the genesis block has no receipts, these are frozen as an empty item.
+		let receipts_key: H264 = Key::<BlockReceipts>::key(&hash);
+		let receipts = key_value.get(db::COL_EXTRA, receipts_key.as_bytes())?.unwrap_or_default();
+
+		freezer.append(
+			&decompress(&header, blocks_swapper()),
+			&decompress(&body, blocks_swapper()),
+			&receipts,
+		)?;
+	}
+	freezer.flush()?;
+
+	let frozen = freezer.len();
+	let deleted = match key_value.get(db::COL_EXTRA, FROZEN_KEY)? {
+		Some(deleted) => rlp::decode(&deleted).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}", e)))?,
+		None => 0,
+	};
+	if deleted == frozen {
+		return Ok(0);
+	}
+
+	let mut batch = DBTransaction::new();
+	for number in deleted..frozen {
+		let hash: H256 = match key_value.read(db::COL_EXTRA, &number) {
+			Some(hash) => hash,
+			None => continue,
+		};
+		let receipts_key: H264 = Key::<BlockReceipts>::key(&hash);
+		batch.delete(db::COL_HEADERS, hash.as_bytes());
+		batch.delete(db::COL_BODIES, hash.as_bytes());
+		batch.delete(db::COL_EXTRA, receipts_key.as_bytes());
+	}
+	batch.put(db::COL_EXTRA, FROZEN_KEY, &rlp::encode(&frozen));
+	key_value.write(batch)?;
+
+	debug!(target: "blockchain", "Moved blocks #{}..#{} to the freezer", start, frozen);
+	Ok(frozen - start)
+}
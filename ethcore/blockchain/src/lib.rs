@@ -25,6 +25,7 @@ mod best_block;
 mod blockchain;
 mod cache;
 mod config;
+mod freezer;
 mod update;
 
 pub mod generator;
@@ -33,6 +34,7 @@ pub use crate::{
 	blockchain::{BlockProvider, BlockChain, BlockChainDB, BlockChainDBHandler},
 	cache::CacheSize,
 	config::Config,
+	freezer::{freeze_blocks, DEFAULT_FREEZER_DEPTH},
 	update::ExtrasInsert,
 };
 pub use ethcore_db::keys::{BlockReceipts, BlockDetails, TransactionAddress, BlockNumberKey};
@@ -37,6 +37,9 @@ use num::{BigUint, Zero, One};
 use parity_bytes::BytesRef;
 use parity_crypto::digest;
 use eip_152::compress;
+use eth_pairings::public_interface::eip2537::{
+	EIP2537Executor, SCALAR_BYTE_LENGTH, SERIALIZED_G1_POINT_BYTE_LENGTH, SERIALIZED_G2_POINT_BYTE_LENGTH,
+};
 
 /// Native implementation of a built-in contract.
 pub trait Implementation: Send + Sync {
@@ -77,6 +80,9 @@ enum Pricing {
 	Blake2F(Blake2FPricer),
 	Linear(Linear),
 	Modexp(ModexpPricer),
+	Bls12ConstOperations(Bls12ConstOperations),
+	Bls12Multiexp(Bls12MultiexpPricer),
+	Bls12Pairing(Bls12PairingPricer),
 }
 
 impl Pricer for Pricing {
@@ -87,6 +93,9 @@ impl Pricer for Pricing {
 			Pricing::Blake2F(inner) => inner.cost(input),
 			Pricing::Linear(inner) => inner.cost(input),
 			Pricing::Modexp(inner) => inner.cost(input),
+			Pricing::Bls12ConstOperations(inner) => inner.cost(input),
+			Pricing::Bls12Multiexp(inner) => inner.cost(input),
+			Pricing::Bls12Pairing(inner) => inner.cost(input),
 		}
 	}
 }
@@ -142,6 +151,71 @@ impl Pricer for AltBn128PairingPricer {
 	}
 }
 
+/// Length of a point-scalar pair of a G1 multiexponentiation.
+const BLS12_G1_MULTIEXP_PAIR_LEN: usize = SERIALIZED_G1_POINT_BYTE_LENGTH + SCALAR_BYTE_LENGTH;
+/// Length of a point-scalar pair of a G2 multiexponentiation.
+const BLS12_G2_MULTIEXP_PAIR_LEN: usize = SERIALIZED_G2_POINT_BYTE_LENGTH + SCALAR_BYTE_LENGTH;
+/// Length of a G1-G2 point pair of a pairing.
+const BLS12_PAIRING_PAIR_LEN: usize = SERIALIZED_G1_POINT_BYTE_LENGTH + SERIALIZED_G2_POINT_BYTE_LENGTH;
+/// Divisor of the multiexponentiation discounts.
+const BLS12_MULTIEXP_DISCOUNT_DIVISOR: u64 = 1000;
+/// Discount of a multiexponentiation of `k` pairs is at index `k - 1`. Larger ones get the last discount.
+const BLS12_MULTIEXP_DISCOUNTS: [u64; 128] = [
+	1200, 888, 764, 641, 594, 547, 500, 453, 438, 423, 408, 394, 379, 364, 349, 334,
+	330, 326, 322, 318, 314, 310, 306, 302, 298, 294, 289, 285, 281, 277, 273, 269,
+	268, 266, 265, 263, 262, 260, 259, 257, 256, 254, 253, 251, 250, 248, 247, 245,
+	244, 242, 241, 239, 238, 236, 235, 233, 232, 231, 229, 228, 226, 225, 223, 222,
+	221, 220, 219, 219, 218, 217, 216, 216, 215, 214, 213, 213, 212, 211, 211, 210,
+	209, 208, 208, 207, 206, 205, 205, 204, 203, 202, 202, 201, 200, 199, 199, 198,
+	197, 196, 196, 195, 194, 193, 193, 192, 191, 191, 190, 189, 188, 188, 187, 186,
+	185, 185, 184, 183, 182, 182, 181, 180, 179, 179, 178, 177, 176, 176, 175, 174,
+];
+
+/// Pricing for constant BLS12-381 operations (additions, multiplications and mappings to the curve)
+#[derive(Debug, Copy, Clone)]
+pub struct Bls12ConstOperations {
+	/// Fixed price.
+	pub price: u64,
+}
+
+impl Pricer for Bls12ConstOperations {
+	fn cost(&self, _input: &[u8]) -> U256 {
+		self.price.into()
+	}
+}
+
+/// BLS12-381 multiexponentiation pricing model (EIP-2537). This computes a price using the cost of
+/// a single multiplication, discounted depending on the number of point-scalar pairs.
+#[derive(Debug)]
+struct Bls12MultiexpPricer {
+	base: u64,
+	pair_len: usize,
+}
+
+impl Pricer for Bls12MultiexpPricer {
+	fn cost(&self, input: &[u8]) -> U256 {
+		let pairs = input.len() / self.pair_len;
+		if pairs == 0 {
+			return U256::zero();
+		}
+		let discount = BLS12_MULTIEXP_DISCOUNTS[min(pairs, BLS12_MULTIEXP_DISCOUNTS.len()) - 1];
+		U256::from(self.base) * U256::from(pairs) * U256::from(discount) / U256::from(BLS12_MULTIEXP_DISCOUNT_DIVISOR)
+	}
+}
+
+/// BLS12-381 pairing pricing model. This computes a price using a base cost and a cost per pair.
+#[derive(Debug)]
+struct Bls12PairingPricer {
+	base: u64,
+	pair: u64,
+}
+
+impl Pricer for Bls12PairingPricer {
+	fn cost(&self, input: &[u8]) -> U256 {
+		U256::from(self.base) + U256::from(self.pair) * U256::from(input.len() / BLS12_PAIRING_PAIR_LEN)
+	}
+}
+
 impl Pricer for ModexpPricer {
 	fn cost(&self, input: &[u8]) -> U256 {
 		let mut reader = input.chain(io::repeat(0));
@@ -297,6 +371,29 @@ impl From<ethjson::spec::builtin::Pricing> for Pricing {
 					price: pricer.price
 				})
 			}
+			ethjson::spec::builtin::Pricing::Bls12ConstOperations(pricer) => {
+				Pricing::Bls12ConstOperations(Bls12ConstOperations {
+					price: pricer.price
+				})
+			}
+			ethjson::spec::builtin::Pricing::Bls12G1Multiexp(pricer) => {
+				Pricing::Bls12Multiexp(Bls12MultiexpPricer {
+					base: pricer.base,
+					pair_len: BLS12_G1_MULTIEXP_PAIR_LEN,
+				})
+			}
+			ethjson::spec::builtin::Pricing::Bls12G2Multiexp(pricer) => {
+				Pricing::Bls12Multiexp(Bls12MultiexpPricer {
+					base: pricer.base,
+					pair_len: BLS12_G2_MULTIEXP_PAIR_LEN,
+				})
+			}
+			ethjson::spec::builtin::Pricing::Bls12Pairing(pricer) => {
+				Pricing::Bls12Pairing(Bls12PairingPricer {
+					base: pricer.base,
+					pair: pricer.pair,
+				})
+			}
 		}
 	}
 }
@@ -320,7 +417,25 @@ enum EthereumBuiltin {
 	/// alt_bn128_pairing
 	Bn128Pairing(Bn128Pairing),
 	/// blake2_f (The Blake2 compression function F, EIP-152)
-	Blake2F(Blake2F)
+	Blake2F(Blake2F),
+	/// bls12_381_g1_add (EIP-2537)
+	Bls12G1Add(Bls12G1Add),
+	/// bls12_381_g1_mul (EIP-2537)
+	Bls12G1Mul(Bls12G1Mul),
+	/// bls12_381_g1_multiexp (EIP-2537)
+	Bls12G1Multiexp(Bls12G1Multiexp),
+	/// bls12_381_g2_add (EIP-2537)
+	Bls12G2Add(Bls12G2Add),
+	/// bls12_381_g2_mul (EIP-2537)
+	Bls12G2Mul(Bls12G2Mul),
+	/// bls12_381_g2_multiexp (EIP-2537)
+	Bls12G2Multiexp(Bls12G2Multiexp),
+	/// bls12_381_pairing (EIP-2537)
+	Bls12Pairing(Bls12Pairing),
+	/// bls12_381_fp_to_g1 (EIP-2537)
+	Bls12MapFpToG1(Bls12MapFpToG1),
+	/// bls12_381_fp2_to_g2 (EIP-2537)
+	Bls12MapFp2ToG2(Bls12MapFp2ToG2),
 }
 
 impl FromStr for EthereumBuiltin {
@@ -337,6 +452,15 @@ impl FromStr for EthereumBuiltin {
 			"alt_bn128_mul" => Ok(EthereumBuiltin::Bn128Mul(Bn128Mul)),
 			"alt_bn128_pairing" => Ok(EthereumBuiltin::Bn128Pairing(Bn128Pairing)),
 			"blake2_f" => Ok(EthereumBuiltin::Blake2F(Blake2F)),
+			"bls12_381_g1_add" => Ok(EthereumBuiltin::Bls12G1Add(Bls12G1Add)),
+			"bls12_381_g1_mul" => Ok(EthereumBuiltin::Bls12G1Mul(Bls12G1Mul)),
+			"bls12_381_g1_multiexp" => Ok(EthereumBuiltin::Bls12G1Multiexp(Bls12G1Multiexp)),
+			"bls12_381_g2_add" => Ok(EthereumBuiltin::Bls12G2Add(Bls12G2Add)),
+			"bls12_381_g2_mul" => Ok(EthereumBuiltin::Bls12G2Mul(Bls12G2Mul)),
+			"bls12_381_g2_multiexp" => Ok(EthereumBuiltin::Bls12G2Multiexp(Bls12G2Multiexp)),
+			"bls12_381_pairing" => Ok(EthereumBuiltin::Bls12Pairing(Bls12Pairing)),
+			"bls12_381_fp_to_g1" => Ok(EthereumBuiltin::Bls12MapFpToG1(Bls12MapFpToG1)),
+			"bls12_381_fp2_to_g2" => Ok(EthereumBuiltin::Bls12MapFp2ToG2(Bls12MapFp2ToG2)),
 			_ => return Err(EthcoreError::Msg(format!("invalid builtin name: {}", name))),
 		}
 	}
@@ -354,6 +478,15 @@ impl Implementation for EthereumBuiltin {
 			EthereumBuiltin::Bn128Mul(inner) => inner.execute(input, output),
 			EthereumBuiltin::Bn128Pairing(inner) => inner.execute(input, output),
 			EthereumBuiltin::Blake2F(inner) => inner.execute(input, output),
+			EthereumBuiltin::Bls12G1Add(inner) => inner.execute(input, output),
+			EthereumBuiltin::Bls12G1Mul(inner) => inner.execute(input, output),
+			EthereumBuiltin::Bls12G1Multiexp(inner) => inner.execute(input, output),
+			EthereumBuiltin::Bls12G2Add(inner) => inner.execute(input, output),
+			EthereumBuiltin::Bls12G2Mul(inner) => inner.execute(input, output),
+			EthereumBuiltin::Bls12G2Multiexp(inner) => inner.execute(input, output),
+			EthereumBuiltin::Bls12Pairing(inner) => inner.execute(input, output),
+			EthereumBuiltin::Bls12MapFpToG1(inner) => inner.execute(input, output),
+			EthereumBuiltin::Bls12MapFp2ToG2(inner) => inner.execute(input, output),
 		}
 	}
 }
@@ -394,6 +527,42 @@ pub struct Bn128Pairing;
 /// The Blake2F builtin
 pub struct Blake2F;
 
+#[derive(Debug)]
+/// The Bls12G1Add builtin
+pub struct Bls12G1Add;
+
+#[derive(Debug)]
+/// The Bls12G1Mul builtin
+pub struct Bls12G1Mul;
+
+#[derive(Debug)]
+/// The Bls12G1Multiexp builtin
+pub struct Bls12G1Multiexp;
+
+#[derive(Debug)]
+/// The Bls12G2Add builtin
+pub struct Bls12G2Add;
+
+#[derive(Debug)]
+/// The Bls12G2Mul builtin
+pub struct Bls12G2Mul;
+
+#[derive(Debug)]
+/// The Bls12G2Multiexp builtin
+pub struct Bls12G2Multiexp;
+
+#[derive(Debug)]
+/// The Bls12Pairing builtin
+pub struct Bls12Pairing;
+
+#[derive(Debug)]
+/// The Bls12MapFpToG1 builtin
+pub struct Bls12MapFpToG1;
+
+#[derive(Debug)]
+/// The Bls12MapFp2ToG2 builtin
+pub struct Bls12MapFp2ToG2;
+
 impl Implementation for Identity {
 	fn execute(&self, input: &[u8], output: &mut BytesRef) -> Result<(), &'static str> {
 		output.write(0, input);
@@ -750,21 +919,99 @@ impl Bn128Pairing {
 	}
 }
 
+/// Writes the result of a BLS12-381 operation to `output`. Inputs are validated by the operation
+/// itself, which fails on malformed encodings or points not on the curve (or subgroup, when required).
+fn write_bls12_result<T: AsRef<[u8]>, E: std::fmt::Debug>(
+	operation: &'static str,
+	result: Result<T, E>,
+	output: &mut BytesRef,
+) -> Result<(), &'static str> {
+	match result {
+		Ok(bytes) => {
+			output.write(0, bytes.as_ref());
+			Ok(())
+		}
+		Err(err) => {
+			trace!(target: "builtin", "{} error: {:?}", operation, err);
+			Err(operation)
+		}
+	}
+}
+
+impl Implementation for Bls12G1Add {
+	fn execute(&self, input: &[u8], output: &mut BytesRef) -> Result<(), &'static str> {
+		write_bls12_result("Bls12G1Add error", EIP2537Executor::g1_add(input), output)
+	}
+}
+
+impl Implementation for Bls12G1Mul {
+	fn execute(&self, input: &[u8], output: &mut BytesRef) -> Result<(), &'static str> {
+		write_bls12_result("Bls12G1Mul error", EIP2537Executor::g1_mul(input), output)
+	}
+}
+
+impl Implementation for Bls12G1Multiexp {
+	fn execute(&self, input: &[u8], output: &mut BytesRef) -> Result<(), &'static str> {
+		write_bls12_result("Bls12G1Multiexp error", EIP2537Executor::g1_multiexp(input), output)
+	}
+}
+
+impl Implementation for Bls12G2Add {
+	fn execute(&self, input: &[u8], output: &mut BytesRef) -> Result<(), &'static str> {
+		write_bls12_result("Bls12G2Add error", EIP2537Executor::g2_add(input), output)
+	}
+}
+
+impl Implementation for Bls12G2Mul {
+	fn execute(&self, input: &[u8], output: &mut BytesRef) -> Result<(), &'static str> {
+		write_bls12_result("Bls12G2Mul error", EIP2537Executor::g2_mul(input), output)
+	}
+}
+
+impl Implementation for Bls12G2Multiexp {
+	fn execute(&self, input: &[u8], output: &mut BytesRef) -> Result<(), &'static str> {
+		write_bls12_result("Bls12G2Multiexp error", EIP2537Executor::g2_multiexp(input), output)
+	}
+}
+
+impl Implementation for Bls12Pairing {
+	fn execute(&self, input: &[u8], output: &mut BytesRef) -> Result<(), &'static str> {
+		write_bls12_result("Bls12Pairing error", EIP2537Executor::pair(input), output)
+	}
+}
+
+impl Implementation for Bls12MapFpToG1 {
+	fn execute(&self, input: &[u8], output: &mut BytesRef) -> Result<(), &'static str> {
+		write_bls12_result("Bls12MapFpToG1 error", EIP2537Executor::map_fp_to_g1(input), output)
+	}
+}
+
+impl Implementation for Bls12MapFp2ToG2 {
+	fn execute(&self, input: &[u8], output: &mut BytesRef) -> Result<(), &'static str> {
+		write_bls12_result("Bls12MapFp2ToG2 error", EIP2537Executor::map_fp2_to_g2(input), output)
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use std::convert::TryFrom;
+	use std::{fs, path::Path};
 	use ethereum_types::U256;
 	use ethjson::spec::builtin::{
 		Builtin as JsonBuiltin, Linear as JsonLinearPricing,
 		PricingAt, AltBn128Pairing as JsonAltBn128PairingPricing, Pricing as JsonPricing,
+		Bls12ConstOperations as JsonBls12ConstOperationsPricing,
+		Bls12Multiexp as JsonBls12MultiexpPricing, Bls12Pairing as JsonBls12PairingPricing,
 	};
 	use hex_literal::hex;
 	use macros::map;
 	use num::{BigUint, Zero, One};
 	use parity_bytes::BytesRef;
+	use rustc_hex::FromHex;
+	use serde::Deserialize;
 	use super::{
 		BTreeMap, Builtin, EthereumBuiltin, FromStr, Implementation, Linear,
-		ModexpPricer, modexp as me, Pricing
+		ModexpPricer, modexp as me, Pricing, Pricer
 	};
 
 	#[test]
@@ -1307,6 +1554,251 @@ mod tests {
 		);
 	}
 
+	// generator of G1
+	fn bls12_g1_generator() -> Vec<u8> {
+		hex!("
+			0000000000000000000000000000000017f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb
+			0000000000000000000000000000000008b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1"
+		).to_vec()
+	}
+
+	// 2 * generator of G1
+	fn bls12_g1_generator_doubled() -> Vec<u8> {
+		hex!("
+			000000000000000000000000000000000572cbea904d67468808c8eb50a9450c9721db309128012543902d0ac358a62ae28f75bb8f1c7c42c39a8c5529bf0f4e
+			00000000000000000000000000000000166a9d8cabc673a322fda673779d8e3822ba3ecb8670e461f73bb9021d5fd76a4c56d9d4cd16bd1bba86881979749d28"
+		).to_vec()
+	}
+
+	// -generator of G1
+	fn bls12_g1_generator_negated() -> Vec<u8> {
+		hex!("
+			0000000000000000000000000000000017f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb
+			00000000000000000000000000000000114d1d6855d545a8aa7d76c8cf2e21f267816aef1db507c96655b9d5caac42364e6f38ba0ecb751bad54dcd6b939c2ca"
+		).to_vec()
+	}
+
+	// generator of G2, coordinates in (c0, c1) order
+	fn bls12_g2_generator() -> Vec<u8> {
+		hex!("
+			00000000000000000000000000000000024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8
+			0000000000000000000000000000000013e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e
+			000000000000000000000000000000000ce5d527727d6e118cc9cdc6da2e351aadfd9baa8cbdd3a76d429a695160d12c923ac9cc3baca289e193548608b82801
+			000000000000000000000000000000000606c4a02ea734cc32acd2b02bc28b99cb3e287e85a763af267492ab572e99ab3f370d275cec1da1aaa9075ff05f79be"
+		).to_vec()
+	}
+
+	// 2 * generator of G2
+	fn bls12_g2_generator_doubled() -> Vec<u8> {
+		hex!("
+			000000000000000000000000000000001638533957d540a9d2370f17cc7ed5863bc0b995b8825e0ee1ea1e1e4d00dbae81f14b0bf3611b78c952aacab827a053
+			000000000000000000000000000000000a4edef9c1ed7f729f520e47730a124fd70662a904ba1074728114d1031e1572c6c886f6b57ec72a6178288c47c33577
+			000000000000000000000000000000000468fb440d82b0630aeb8dca2b5256789a66da69bf91009cbfe6bd221e47aa8ae88dece9764bf3bd999d95d71e4c9899
+			000000000000000000000000000000000f6d4552fa65dd2638b361543f887136a43253d9c66c411697003f7a13c308f5422e1aa0a59c8967acdefd8b6e36ccf3"
+		).to_vec()
+	}
+
+	fn bls12_scalar(n: u64) -> Vec<u8> {
+		let mut scalar = vec![0u8; 32];
+		U256::from(n).to_big_endian(&mut scalar);
+		scalar
+	}
+
+	fn bls12_execute(name: &str, input: &[u8], output_len: usize) -> Result<Vec<u8>, &'static str> {
+		let builtin = EthereumBuiltin::from_str(name).expect("known builtin");
+		let mut output = vec![0u8; output_len];
+		builtin.execute(input, &mut BytesRef::Fixed(&mut output[..]))?;
+		Ok(output)
+	}
+
+	#[test]
+	fn bls12_g1_operations() {
+		let g = bls12_g1_generator();
+		let doubled = bls12_g1_generator_doubled();
+		let infinity = vec![0u8; 128];
+
+		assert_eq!(bls12_execute("bls12_381_g1_add", &[&g[..], &g[..]].concat(), 128), Ok(doubled.clone()));
+		assert_eq!(bls12_execute("bls12_381_g1_add", &[&g[..], &infinity[..]].concat(), 128), Ok(g.clone()));
+		assert_eq!(bls12_execute("bls12_381_g1_mul", &[&g[..], &bls12_scalar(2)[..]].concat(), 128), Ok(doubled.clone()));
+		assert_eq!(bls12_execute("bls12_381_g1_mul", &[&g[..], &bls12_scalar(0)[..]].concat(), 128), Ok(infinity.clone()));
+
+		let pairs = [&g[..], &bls12_scalar(1)[..], &g[..], &bls12_scalar(1)[..]].concat();
+		assert_eq!(bls12_execute("bls12_381_g1_multiexp", &pairs, 128), Ok(doubled.clone()));
+
+		// invalid length
+		assert!(bls12_execute("bls12_381_g1_add", &g, 128).is_err());
+		assert!(bls12_execute("bls12_381_g1_multiexp", &[], 128).is_err());
+		// not on the curve
+		let not_on_curve = [&g[..64], &doubled[64..]].concat();
+		assert!(bls12_execute("bls12_381_g1_add", &[&not_on_curve[..], &g[..]].concat(), 128).is_err());
+	}
+
+	#[test]
+	fn bls12_g2_operations() {
+		let g = bls12_g2_generator();
+		let doubled = bls12_g2_generator_doubled();
+		let infinity = vec![0u8; 256];
+
+		assert_eq!(bls12_execute("bls12_381_g2_add", &[&g[..], &g[..]].concat(), 256), Ok(doubled.clone()));
+		assert_eq!(bls12_execute("bls12_381_g2_add", &[&infinity[..], &g[..]].concat(), 256), Ok(g.clone()));
+		assert_eq!(bls12_execute("bls12_381_g2_mul", &[&g[..], &bls12_scalar(2)[..]].concat(), 256), Ok(doubled.clone()));
+
+		let pairs = [&g[..], &bls12_scalar(1)[..], &g[..], &bls12_scalar(1)[..]].concat();
+		assert_eq!(bls12_execute("bls12_381_g2_multiexp", &pairs, 256), Ok(doubled.clone()));
+
+		// invalid length
+		assert!(bls12_execute("bls12_381_g2_mul", &g, 256).is_err());
+		// not on the curve
+		let not_on_curve = [&g[..128], &doubled[128..]].concat();
+		assert!(bls12_execute("bls12_381_g2_add", &[&not_on_curve[..], &g[..]].concat(), 256).is_err());
+	}
+
+	#[test]
+	fn bls12_pairing() {
+		let g1 = bls12_g1_generator();
+		let g2 = bls12_g2_generator();
+		let mut one = vec![0u8; 32];
+		one[31] = 1;
+
+		// e(g1, g2) * e(-g1, g2) == 1
+		let input = [&g1[..], &g2[..], &bls12_g1_generator_negated()[..], &g2[..]].concat();
+		assert_eq!(bls12_execute("bls12_381_pairing", &input, 32), Ok(one.clone()));
+
+		// e(g1, g2) != 1
+		let input = [&g1[..], &g2[..]].concat();
+		assert_eq!(bls12_execute("bls12_381_pairing", &input, 32), Ok(vec![0u8; 32]));
+
+		// e(0, g2) == 1
+		let input = [&[0u8; 128][..], &g2[..]].concat();
+		assert_eq!(bls12_execute("bls12_381_pairing", &input, 32), Ok(one));
+
+		assert!(bls12_execute("bls12_381_pairing", &[], 32).is_err());
+		assert!(bls12_execute("bls12_381_pairing", &g1, 32).is_err());
+	}
+
+	#[test]
+	fn bls12_map_to_curve() {
+		let mut fp = vec![0u8; 64];
+		fp[63] = 1;
+
+		// mapped points are valid curve points
+		let g1 = bls12_execute("bls12_381_fp_to_g1", &fp, 128).expect("valid field element");
+		assert_eq!(bls12_execute("bls12_381_g1_add", &[&g1[..], &[0u8; 128][..]].concat(), 128), Ok(g1));
+		let g2 = bls12_execute("bls12_381_fp2_to_g2", &[&fp[..], &fp[..]].concat(), 256).expect("valid field element");
+		assert_eq!(bls12_execute("bls12_381_g2_add", &[&g2[..], &[0u8; 256][..]].concat(), 256), Ok(g2));
+
+		// the field modulus is not a field element
+		let modulus = hex!("000000000000000000000000000000001a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab");
+		assert!(bls12_execute("bls12_381_fp_to_g1", &modulus, 128).is_err());
+		assert!(bls12_execute("bls12_381_fp_to_g1", &fp[..32], 128).is_err());
+	}
+
+	#[test]
+	fn bls12_pricing() {
+		let g1_multiexp = Pricing::from(JsonPricing::Bls12G1Multiexp(JsonBls12MultiexpPricing { base: 12000 }));
+		assert_eq!(g1_multiexp.cost(&[]), U256::zero());
+		assert_eq!(g1_multiexp.cost(&[0u8; 160]), U256::from(14_400));
+		assert_eq!(g1_multiexp.cost(&[0u8; 2 * 160]), U256::from(21_312));
+		// maximal discount
+		assert_eq!(g1_multiexp.cost(&vec![0u8; 200 * 160]), U256::from(417_600));
+
+		let g2_multiexp = Pricing::from(JsonPricing::Bls12G2Multiexp(JsonBls12MultiexpPricing { base: 55000 }));
+		assert_eq!(g2_multiexp.cost(&[0u8; 288]), U256::from(66_000));
+
+		let pairing = Pricing::from(JsonPricing::Bls12Pairing(JsonBls12PairingPricing { base: 115000, pair: 23000 }));
+		assert_eq!(pairing.cost(&[0u8; 2 * 384]), U256::from(161_000));
+	}
+
+	/// A test vector in the format of the vectors published with EIP-2537. Failing vectors carry
+	/// `ExpectedError` instead of `Expected` and `Gas`.
+	#[derive(Deserialize)]
+	#[serde(rename_all = "PascalCase")]
+	struct Bls12Vector {
+		name: String,
+		input: String,
+		expected: Option<String>,
+		gas: Option<u64>,
+		expected_error: Option<String>,
+	}
+
+	/// Loads `file` from `res/eip2537`, see the README there for where the vectors come from.
+	fn bls12_vectors(file: &str) -> Vec<Bls12Vector> {
+		let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("res").join("eip2537").join(file);
+		let json = fs::read(&path)
+			.unwrap_or_else(|e| panic!("EIP-2537 test vectors {} can't be read: {}", path.display(), e));
+		let vectors: Vec<Bls12Vector> = serde_json::from_slice(&json).expect("EIP-2537 test vectors are valid JSON");
+		assert!(!vectors.is_empty(), "EIP-2537 test vectors {} are empty", path.display());
+		vectors
+	}
+
+	fn bls12_run_vectors(file: &str, name: &str, output_len: usize, pricing: JsonPricing) {
+		let builtin = Builtin {
+			pricer: map![0 => Pricing::from(pricing)],
+			native: EthereumBuiltin::from_str(name).expect("known builtin"),
+		};
+
+		for vector in bls12_vectors(file) {
+			let input = vector.input.from_hex::<Vec<u8>>().expect("vector input is hex");
+			let mut output = vec![0u8; output_len];
+			let result = builtin.execute(&input, &mut BytesRef::Fixed(&mut output[..]));
+
+			match (vector.expected, vector.expected_error) {
+				(Some(expected), _) => {
+					assert_eq!(result, Ok(()), "{}: {}", file, vector.name);
+					assert_eq!(output, expected.from_hex::<Vec<u8>>().expect("vector output is hex"), "{}: {}", file, vector.name);
+					if let Some(gas) = vector.gas {
+						assert_eq!(builtin.cost(&input, 0), U256::from(gas), "{}: {}", file, vector.name);
+					}
+				}
+				(None, Some(error)) => assert!(result.is_err(), "{}: {} should fail with {}", file, vector.name, error),
+				(None, None) => panic!("{}: {} has neither an expected output nor an error", file, vector.name),
+			}
+		}
+	}
+
+	fn bls12_run_all_vectors(file: &str, name: &str, output_len: usize, pricing: JsonPricing) {
+		bls12_run_vectors(file, name, output_len, pricing.clone());
+		bls12_run_vectors(&format!("fail-{}", file), name, output_len, pricing);
+	}
+
+	fn bls12_const_pricing(price: u64) -> JsonPricing {
+		JsonPricing::Bls12ConstOperations(JsonBls12ConstOperationsPricing { price })
+	}
+
+	#[test]
+	fn bls12_g1_vectors() {
+		bls12_run_all_vectors("add_G1_bls.json", "bls12_381_g1_add", 128, bls12_const_pricing(600));
+		bls12_run_all_vectors("mul_G1_bls.json", "bls12_381_g1_mul", 128, bls12_const_pricing(12000));
+		bls12_run_all_vectors(
+			"multiexp_G1_bls.json", "bls12_381_g1_multiexp", 128,
+			JsonPricing::Bls12G1Multiexp(JsonBls12MultiexpPricing { base: 12000 }),
+		);
+	}
+
+	#[test]
+	fn bls12_g2_vectors() {
+		bls12_run_all_vectors("add_G2_bls.json", "bls12_381_g2_add", 256, bls12_const_pricing(4500));
+		bls12_run_all_vectors("mul_G2_bls.json", "bls12_381_g2_mul", 256, bls12_const_pricing(55000));
+		bls12_run_all_vectors(
+			"multiexp_G2_bls.json", "bls12_381_g2_multiexp", 256,
+			JsonPricing::Bls12G2Multiexp(JsonBls12MultiexpPricing { base: 55000 }),
+		);
+	}
+
+	#[test]
+	fn bls12_pairing_vectors() {
+		bls12_run_all_vectors(
+			"pairing_check_bls.json", "bls12_381_pairing", 32,
+			JsonPricing::Bls12Pairing(JsonBls12PairingPricing { base: 115000, pair: 23000 }),
+		);
+	}
+
+	#[test]
+	fn bls12_map_to_curve_vectors() {
+		bls12_run_all_vectors("map_fp_to_G1_bls.json", "bls12_381_fp_to_g1", 128, bls12_const_pricing(5500));
+		bls12_run_all_vectors("map_fp2_to_G2_bls.json", "bls12_381_fp2_to_g2", 256, bls12_const_pricing(110000));
+	}
+
 	#[test]
 	#[should_panic]
 	fn from_unknown_linear() {
@@ -222,7 +222,7 @@ pub trait BadBlocks {
 /// Blockchain database client. Owns and manages a blockchain and a block queue.
 pub trait BlockChainClient:
 	Sync + Send + AccountData + BlockChain + CallContract + RegistrarClient
-	+ ImportBlock + IoClient + BadBlocks
+	+ ImportBlock + IoClient + BadBlocks + SnapClient
 {
 	/// Look up the block number for the given block ID.
 	fn block_number(&self, id: BlockId) -> Option<BlockNumber>;
@@ -344,6 +344,9 @@ pub trait BlockChainClient:
 	/// List all ready transactions that should be propagated to other peers.
 	fn transactions_to_propagate(&self) -> Vec<Arc<VerifiedTransaction>>;
 
+	/// Get a transaction from the pool by its hash, if it's still there.
+	fn pooled_transaction(&self, hash: &H256) -> Option<Arc<VerifiedTransaction>>;
+
 	/// Sorted list of transaction gas prices from at least last sample_size blocks.
 	fn gas_price_corpus(&self, sample_size: usize) -> stats::Corpus<U256> {
 		let mut h = self.chain_info().best_block_hash;
@@ -457,6 +460,44 @@ impl TransactionRequest {
 	}
 }
 
+/// Serves and imports flat state ranges for the `snap` sync protocol.
+///
+/// Trie keys are hashed (secure trie); an `account_hash` of `None` refers to the account trie,
+/// otherwise to the storage trie of that account.
+pub trait SnapClient {
+	/// Get the accounts of the state trie with `root` starting at `origin`, in slim encoding.
+	/// Stops after the first account at or past `limit` or once `max_bytes` are collected.
+	/// Returns the accounts together with the proof of the first and the last of them,
+	/// or `None` if the state is not available.
+	fn account_range(&self, root: &H256, origin: &H256, limit: &H256, max_bytes: usize) -> Option<(Vec<(H256, Bytes)>, Vec<Bytes>)>;
+
+	/// Get the storage slots of an account, like `account_range` does for accounts.
+	/// `root` is the state root the account is looked up in.
+	fn storage_range(&self, root: &H256, account_hash: &H256, origin: &H256, limit: &H256, max_bytes: usize) -> Option<(Vec<(H256, Bytes)>, Vec<Bytes>)>;
+
+	/// Get the code with the given hash owned by an account.
+	fn account_code(&self, account_hash: &H256, code_hash: &H256) -> Option<Bytes>;
+
+	/// Get the code with the given hash, if it's stored by its hash alone.
+	fn code_by_hash(&self, code_hash: &H256) -> Option<Bytes>;
+
+	/// Get the trie node found at the given nibble path of the state trie with `root`.
+	fn state_node_at_path(&self, root: &H256, account_hash: Option<&H256>, path: &[u8]) -> Option<Bytes>;
+
+	/// Check whether a trie node or code with the given hash is already in the database.
+	fn has_state_node(&self, account_hash: Option<&H256>, hash: &H256) -> bool;
+
+	/// Insert downloaded entries into the local trie with `root` and return the new root.
+	/// Account entries must be in full (not slim) encoding.
+	fn import_state_entries(&self, account_hash: Option<&H256>, root: &H256, entries: &[(H256, Bytes)]) -> Result<H256, EthcoreError>;
+
+	/// Write downloaded trie nodes or code to the database as they are.
+	fn import_state_nodes(&self, account_hash: Option<&H256>, nodes: &[Bytes]) -> Result<(), EthcoreError>;
+
+	/// Finish a state download: make the block with the downloaded state the new best block.
+	fn complete_state_sync(&self, block: Unverified, receipts_bytes: Bytes, total_difficulty: U256) -> EthcoreResult<()>;
+}
+
 /// resets the blockchain
 pub trait BlockChainReset {
 	/// reset to best_block - n
@@ -466,6 +507,24 @@ pub trait BlockChainReset {
 	fn pruning_history(&self) -> u64;
 }
 
+/// Removes the state which is no longer reachable from the recent blocks.
+pub trait PruneState {
+	/// Delete the state trie nodes not reachable from the states of the latest `keep` blocks,
+	/// marking the reachable ones in a bloom filter of `bloom_size` bytes.
+	/// An interrupted pruning is resumed where it stopped.
+	fn prune_state(&self, keep: u64, bloom_size: usize) -> Result<(), EthcoreError>;
+
+	/// Whether a state pruning is under way.
+	fn is_pruning_state(&self) -> bool;
+}
+
+/// Builds the flat state of databases which don't have a usable one.
+pub trait GenerateFlatState {
+	/// Generate the flat state at the best block, pausing pruning at it until done.
+	/// Does nothing if the flat state is available or being generated already.
+	fn generate_flat_state(&self) -> Result<(), EthcoreError>;
+}
+
 
 /// Provides `latest_schedule` method
 pub trait ScheduleInfo {
@@ -43,8 +43,10 @@ pub const COL_NODE_INFO: u32 = 6;
 pub const COL_LIGHT_CHAIN: u32 = 7;
 /// Column for the private transactions state.
 pub const COL_PRIVATE_TRANSACTIONS_STATE: u32 = 8;
+/// Column for the flat account and storage state.
+pub const COL_FLAT_STATE: u32 = 9;
 /// Number of columns in DB
-pub const NUM_COLUMNS: u32 = 9;
+pub const NUM_COLUMNS: u32 = 10;
 
 /// Modes for updating caches.
 #[derive(Clone, Copy)]
@@ -121,6 +121,7 @@ fn check_first_proof(machine: &Machine, contract_address: Address, old_header: H
 			Arc::new(last_hashes)
 		},
 		gas_used: 0.into(),
+		base_fee: None,
 	};
 
 	// check state proof using given machine.
@@ -553,7 +554,7 @@ mod tests {
 		let sync_client = generate_dummy_client_with_spec(spec::new_validator_safe_contract);
 		sync_client.engine().register_client(Arc::downgrade(&sync_client) as _);
 		for i in 1..4 {
-			sync_client.import_block(Unverified::from_rlp(client.block(BlockId::Number(i)).unwrap().into_inner()).unwrap()).unwrap();
+			sync_client.import_block(Unverified::from_rlp(client.block(BlockId::Number(i)).unwrap().into_inner(), client.engine().params().eip1559_transition).unwrap()).unwrap();
 		}
 		sync_client.flush_queue();
 		assert_eq!(sync_client.chain_info().best_block_number, 3);
@@ -153,6 +153,8 @@ enum_with_from_u8! {
 		CHAINID = 0x46,
 		#[doc = "get balance of own account"]
 		SELFBALANCE = 0x47,
+		#[doc = "get the block's base fee"]
+		BASEFEE = 0x48,
 
 		#[doc = "remove item from stack"]
 		POP = 0x50,
@@ -179,6 +181,8 @@ enum_with_from_u8! {
 		#[doc = "set a potential jump destination"]
 		JUMPDEST = 0x5b,
 
+		#[doc = "place the constant value 0 on stack"]
+		PUSH0 = 0x5f,
 		#[doc = "place 1 byte item on stack"]
 		PUSH1 = 0x60,
 		#[doc = "place 2 byte item on stack"]
@@ -505,6 +509,7 @@ lazy_static! {
 		arr[GASLIMIT as usize] = Some(InstructionInfo::new("GASLIMIT", 0, 1, GasPriceTier::Base));
 		arr[CHAINID as usize] = Some(InstructionInfo::new("CHAINID", 0, 1, GasPriceTier::Base));
 		arr[SELFBALANCE as usize] = Some(InstructionInfo::new("SELFBALANCE", 0, 1, GasPriceTier::Low));
+		arr[BASEFEE as usize] = Some(InstructionInfo::new("BASEFEE", 0, 1, GasPriceTier::Base));
 		arr[POP as usize] = Some(InstructionInfo::new("POP", 1, 0, GasPriceTier::Base));
 		arr[MLOAD as usize] = Some(InstructionInfo::new("MLOAD", 1, 1, GasPriceTier::VeryLow));
 		arr[MSTORE as usize] = Some(InstructionInfo::new("MSTORE", 2, 0, GasPriceTier::VeryLow));
@@ -517,6 +522,7 @@ lazy_static! {
 		arr[MSIZE as usize] = Some(InstructionInfo::new("MSIZE", 0, 1, GasPriceTier::Base));
 		arr[GAS as usize] = Some(InstructionInfo::new("GAS", 0, 1, GasPriceTier::Base));
 		arr[JUMPDEST as usize] = Some(InstructionInfo::new("JUMPDEST", 0, 0, GasPriceTier::Special));
+		arr[PUSH0 as usize] = Some(InstructionInfo::new("PUSH0", 0, 1, GasPriceTier::Base));
 		arr[PUSH1 as usize] = Some(InstructionInfo::new("PUSH1", 0, 1, GasPriceTier::VeryLow));
 		arr[PUSH2 as usize] = Some(InstructionInfo::new("PUSH2", 0, 1, GasPriceTier::VeryLow));
 		arr[PUSH3 as usize] = Some(InstructionInfo::new("PUSH3", 0, 1, GasPriceTier::VeryLow));
@@ -611,6 +617,7 @@ mod tests {
 		assert!(PUSH1.is_push());
 		assert!(PUSH32.is_push());
 		assert!(!DUP1.is_push());
+		assert!(!PUSH0.is_push());
 	}
 
 	#[test]
@@ -15,7 +15,7 @@
 // along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::cmp;
-use ethereum_types::{BigEndianHash, U256};
+use ethereum_types::{Address, BigEndianHash, U256};
 use super::u256_to_address;
 
 use {evm, vm};
@@ -129,7 +129,7 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 				let newval = stack.peek(1);
 				let val = ext.storage_at(&address)?.into_uint();
 
-				let gas = if schedule.eip1283 {
+				let mut gas = if schedule.eip1283 {
 					let orig = ext.initial_storage_at(&address)?.into_uint();
 					calculate_eip1283_sstore_gas(schedule, &orig, &val, &newval)
 				} else {
@@ -141,25 +141,40 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 						schedule.sstore_reset_gas
 					}
 				};
+				if schedule.eip2929 && !ext.is_storage_key_warm(&address) {
+					gas += schedule.cold_sload_cost;
+				}
 				Request::Gas(Gas::from(gas))
 			},
 			instructions::SLOAD => {
-				Request::Gas(Gas::from(schedule.sload_gas))
+				let key = BigEndianHash::from_uint(stack.peek(0));
+				let gas = if schedule.eip2929 && !ext.is_storage_key_warm(&key) {
+					schedule.cold_sload_cost
+				} else {
+					schedule.sload_gas
+				};
+				Request::Gas(Gas::from(gas))
 			},
 			instructions::BALANCE => {
-				Request::Gas(Gas::from(schedule.balance_gas))
+				let address = u256_to_address(stack.peek(0));
+				Request::Gas(Gas::from(account_access_gas(ext, schedule, &address, schedule.balance_gas)))
 			},
 			instructions::EXTCODESIZE => {
-				Request::Gas(Gas::from(schedule.extcodesize_gas))
+				let address = u256_to_address(stack.peek(0));
+				Request::Gas(Gas::from(account_access_gas(ext, schedule, &address, schedule.extcodesize_gas)))
 			},
 			instructions::EXTCODEHASH => {
-				Request::Gas(Gas::from(schedule.extcodehash_gas))
+				let address = u256_to_address(stack.peek(0));
+				Request::Gas(Gas::from(account_access_gas(ext, schedule, &address, schedule.extcodehash_gas)))
 			},
 			instructions::SUICIDE => {
 				let mut gas = Gas::from(schedule.suicide_gas);
 
 				let is_value_transfer = !ext.origin_balance()?.is_zero();
 				let address = u256_to_address(stack.peek(0));
+				if schedule.eip2929 && !ext.is_address_warm(&address) {
+					gas = overflowing!(gas.overflow_add(schedule.cold_account_access_cost.into()));
+				}
 				if (
 					!schedule.no_empty && !ext.exists(&address)?
 				) || (
@@ -188,7 +203,9 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 				Request::GasMemCopy(default_gas, mem_needed(stack.peek(0), stack.peek(2))?, Gas::from_u256(*stack.peek(2))?)
 			},
 			instructions::EXTCODECOPY => {
-				Request::GasMemCopy(schedule.extcodecopy_base_gas.into(), mem_needed(stack.peek(1), stack.peek(3))?, Gas::from_u256(*stack.peek(3))?)
+				let address = u256_to_address(stack.peek(0));
+				let base_gas = account_access_gas(ext, schedule, &address, schedule.extcodecopy_base_gas);
+				Request::GasMemCopy(base_gas.into(), mem_needed(stack.peek(1), stack.peek(3))?, Gas::from_u256(*stack.peek(3))?)
 			},
 			instructions::LOG0 | instructions::LOG1 | instructions::LOG2 | instructions::LOG3 | instructions::LOG4 => {
 				let no_of_topics = instruction.log_topics().expect("log_topics always return some for LOG* instructions; qed");
@@ -199,13 +216,13 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 				Request::GasMem(gas, mem_needed(stack.peek(0), stack.peek(1))?)
 			},
 			instructions::CALL | instructions::CALLCODE => {
-				let mut gas = Gas::from(schedule.call_gas);
+				let address = u256_to_address(stack.peek(1));
+				let mut gas = Gas::from(account_access_gas(ext, schedule, &address, schedule.call_gas));
 				let mem = cmp::max(
 					mem_needed(stack.peek(5), stack.peek(6))?,
 					mem_needed(stack.peek(3), stack.peek(4))?
 				);
 
-				let address = u256_to_address(stack.peek(1));
 				let is_value_transfer = !stack.peek(2).is_zero();
 
 				if instruction == instructions::CALL && (
@@ -225,7 +242,8 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 				Request::GasMemProvide(gas, mem, Some(requested))
 			},
 			instructions::DELEGATECALL | instructions::STATICCALL => {
-				let gas = Gas::from(schedule.call_gas);
+				let address = u256_to_address(stack.peek(1));
+				let gas = Gas::from(account_access_gas(ext, schedule, &address, schedule.call_gas));
 				let mem = cmp::max(
 					mem_needed(stack.peek(4), stack.peek(5))?,
 					mem_needed(stack.peek(2), stack.peek(3))?
@@ -238,7 +256,8 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 				let start = stack.peek(1);
 				let len = stack.peek(2);
 
-				let gas = Gas::from(schedule.create_gas);
+				let base = Gas::from(schedule.create_gas);
+				let gas = overflowing!(base.overflow_add(initcode_gas(schedule, len)?));
 				let mem = mem_needed(start, len)?;
 
 				Request::GasMemProvide(gas, mem, None)
@@ -251,6 +270,7 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 				let word = overflowing!(to_word_size(Gas::from_u256(*len)?));
 				let word_gas = overflowing!(Gas::from(schedule.sha3_word_gas).overflow_mul(word));
 				let gas = overflowing!(base.overflow_add(word_gas));
+				let gas = overflowing!(gas.overflow_add(initcode_gas(schedule, len)?));
 				let mem = mem_needed(start, len)?;
 
 				Request::GasMemProvide(gas, mem, None)
@@ -342,6 +362,17 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 	}
 }
 
+/// Gas for accessing an account, `warm_gas` if it has already been accessed by the transaction,
+/// the EIP-2929 cold account access cost otherwise.
+#[inline]
+fn account_access_gas(ext: &dyn vm::Ext, schedule: &Schedule, address: &Address, warm_gas: usize) -> usize {
+	if schedule.eip2929 && !ext.is_address_warm(address) {
+		schedule.cold_account_access_cost
+	} else {
+		warm_gas
+	}
+}
+
 #[inline]
 fn mem_needed_const<Gas: evm::CostType>(mem: &U256, add: usize) -> vm::Result<Gas> {
 	Gas::from_u256(overflowing!(mem.overflowing_add(U256::from(add))))
@@ -371,6 +402,19 @@ fn to_word_size<Gas: evm::CostType>(value: Gas) -> (Gas, bool) {
 	(gas >> 5, false)
 }
 
+/// Gas charged for the init code of `CREATE` and `CREATE2` (EIP-3860).
+/// Init code over the size limit fails like running out of gas.
+#[inline]
+fn initcode_gas<Gas: evm::CostType>(schedule: &Schedule, len: &U256) -> vm::Result<Gas> {
+	if let Some(limit) = schedule.max_initcode_size {
+		if *len > U256::from(limit) {
+			return Err(vm::Error::OutOfGas);
+		}
+	}
+	let word = overflowing!(to_word_size(Gas::from_u256(*len)?));
+	Ok(overflowing!(Gas::from(schedule.initcode_word_gas).overflow_mul(word)))
+}
+
 #[inline]
 fn calculate_eip1283_sstore_gas<Gas: evm::CostType>(schedule: &Schedule, original: &U256, current: &U256, new: &U256) -> Gas {
 	Gas::from(
@@ -363,6 +363,9 @@ impl<Cost: CostType> Interpreter<Cost> {
 				self.mem.expand(requirements.memory_required_size);
 				self.gasometer.as_mut().expect(GASOMETER_PROOF).current_mem_gas = requirements.memory_total_gas;
 				self.gasometer.as_mut().expect(GASOMETER_PROOF).current_gas = self.gasometer.as_mut().expect(GASOMETER_PROOF).current_gas - requirements.gas_cost;
+				if ext.schedule().eip2929 {
+					Self::mark_accessed(ext, instruction, &self.stack);
+				}
 
 				evm_debug!({ self.informant.before_instruction(self.reader.position, instruction, info, &self.gasometer.as_mut().expect(GASOMETER_PROOF).current_gas, &self.stack) });
 
@@ -445,7 +448,9 @@ impl<Cost: CostType> Interpreter<Cost> {
 			((instruction == instructions::SHL || instruction == instructions::SHR || instruction == instructions::SAR) && !schedule.have_bitwise_shifting) ||
 			(instruction == instructions::EXTCODEHASH && !schedule.have_extcodehash) ||
 			(instruction == instructions::CHAINID && !schedule.have_chain_id) ||
-			(instruction == instructions::SELFBALANCE && !schedule.have_selfbalance)
+			(instruction == instructions::SELFBALANCE && !schedule.have_selfbalance) ||
+			(instruction == instructions::BASEFEE && !schedule.have_basefee) ||
+			(instruction == instructions::PUSH0 && !schedule.have_push0)
 		{
 			return Err(vm::Error::BadInstruction {
 				instruction: instruction as u8
@@ -500,6 +505,27 @@ impl<Cost: CostType> Interpreter<Cost> {
 		}
 	}
 
+	/// Record the account or storage key touched by `instruction`, once its (cold) access has been paid for.
+	fn mark_accessed(
+		ext: &mut dyn vm::Ext,
+		instruction: Instruction,
+		stack: &dyn Stack<U256>
+	) {
+		match instruction {
+			instructions::SLOAD | instructions::SSTORE => {
+				ext.warm_storage_key(BigEndianHash::from_uint(stack.peek(0)));
+			},
+			instructions::BALANCE | instructions::EXTCODESIZE | instructions::EXTCODECOPY |
+			instructions::EXTCODEHASH | instructions::SUICIDE => {
+				ext.warm_address(u256_to_address(stack.peek(0)));
+			},
+			instructions::CALL | instructions::CALLCODE | instructions::DELEGATECALL | instructions::STATICCALL => {
+				ext.warm_address(u256_to_address(stack.peek(1)));
+			},
+			_ => {},
+		}
+	}
+
 	fn exec_instruction(
 		&mut self,
 		gas: Cost,
@@ -702,6 +728,9 @@ impl<Cost: CostType> Interpreter<Cost> {
 					.collect();
 				ext.log(topics, self.mem.read_slice(offset, size))?;
 			},
+			instructions::PUSH0 => {
+				self.stack.push(U256::zero());
+			},
 			instructions::PUSH1 | instructions::PUSH2 | instructions::PUSH3 | instructions::PUSH4 |
 			instructions::PUSH5 | instructions::PUSH6 | instructions::PUSH7 | instructions::PUSH8 |
 			instructions::PUSH9 | instructions::PUSH10 | instructions::PUSH11 | instructions::PUSH12 |
@@ -874,7 +903,10 @@ impl<Cost: CostType> Interpreter<Cost> {
 			},
 			instructions::SELFBALANCE => {
 				self.stack.push(ext.balance(&self.params.address)?);
-			}
+			},
+			instructions::BASEFEE => {
+				self.stack.push(ext.env_info().base_fee.unwrap_or_default());
+			},
 
 			// Stack instructions
 
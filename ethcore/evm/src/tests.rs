@@ -195,6 +195,96 @@ fn test_chain_id(factory: super::Factory) {
 	assert_store(&ext, 0, "0000000000000000000000000000000000000000000000000000000000000009");
 }
 
+evm_test!{test_base_fee: test_base_fee_int}
+fn test_base_fee(factory: super::Factory) {
+	// 48       BASEFEE
+	// 60 00    PUSH 0
+	// 55       SSTORE
+	let code = hex!("48 60 00 55").to_vec();
+
+	let mut params = ActionParams::default();
+	params.gas = U256::from(100_000);
+	params.code = Some(Arc::new(code));
+	let mut ext = FakeExt::new_london();
+	ext.info.base_fee = Some(U256::from(7));
+
+	let gas_left = {
+		let vm = factory.create(params, ext.schedule(), ext.depth());
+		test_finalize(vm.exec(&mut ext).ok().unwrap()).unwrap()
+	};
+
+	assert_eq!(gas_left, U256::from(100_000 - 2 - 3 - 2_100 - 20_000));
+	assert_store(&ext, 0, "0000000000000000000000000000000000000000000000000000000000000007");
+}
+
+evm_test!{test_push0: test_push0_int}
+fn test_push0(factory: super::Factory) {
+	// 60 2a    PUSH 42
+	// 5f       PUSH0
+	// 55       SSTORE
+	let code = hex!("60 2a 5f 55").to_vec();
+
+	let mut params = ActionParams::default();
+	params.gas = U256::from(100_000);
+	params.code = Some(Arc::new(code));
+	let mut ext = FakeExt::new_shanghai();
+
+	let gas_left = {
+		let vm = factory.create(params, ext.schedule(), ext.depth());
+		test_finalize(vm.exec(&mut ext).ok().unwrap()).unwrap()
+	};
+
+	assert_eq!(gas_left, U256::from(100_000 - 3 - 2 - 2_100 - 20_000));
+	assert_store(&ext, 0, "000000000000000000000000000000000000000000000000000000000000002a");
+}
+
+#[test]
+fn test_push0_before_shanghai_int() {
+	let factory = super::Factory::new(1024 * 32);
+	let code = hex!("5f").to_vec();
+
+	let mut params = ActionParams::default();
+	params.gas = U256::from(100_000);
+	params.code = Some(Arc::new(code));
+	let mut ext = FakeExt::new_london();
+
+	let err = {
+		let vm = factory.create(params, ext.schedule(), ext.depth());
+		test_finalize(vm.exec(&mut ext).ok().unwrap()).unwrap_err()
+	};
+
+	match err {
+		vm::Error::BadInstruction { instruction: 0x5f } => (),
+		_ => assert!(false, "Expected bad instruction")
+	}
+}
+
+#[test]
+fn test_create_initcode_size_exceeded_int() {
+	let factory = super::Factory::new(1024 * 32);
+	// 62 00c001    PUSH3 0xc001 (one byte over the limit)
+	// 60 00        PUSH 0
+	// 60 00        PUSH 0
+	// f0           CREATE
+	let code = hex!("62 00c001 60 00 60 00 f0").to_vec();
+
+	let mut params = ActionParams::default();
+	params.gas = U256::from(1_000_000);
+	params.code = Some(Arc::new(code));
+	let mut ext = FakeExt::new_shanghai();
+
+	let err = {
+		let vm = factory.create(params, ext.schedule(), ext.depth());
+		test_finalize(vm.exec(&mut ext).ok().unwrap()).unwrap_err()
+	};
+
+	match err {
+		vm::Error::OutOfGas => (),
+		_ => assert!(false, "Expected out of gas")
+	}
+	assert!(ext.calls.is_empty());
+}
+
 evm_test!{test_extcodecopy: test_extcodecopy_int}
 fn test_extcodecopy(factory: super::Factory) {
 		// 33 - sender
@@ -1093,6 +1183,56 @@ fn test_sar(factory: super::Factory) {
 		"0000000000000000000000000000000000000000000000000000000000000000");
 }
 
+evm_test!{test_sload_cold_then_warm: test_sload_cold_then_warm_int}
+fn test_sload_cold_then_warm(factory: super::Factory) {
+	// 60 00    PUSH1 0
+	// 54       SLOAD (cold)
+	// 50       POP
+	// 60 00    PUSH1 0
+	// 54       SLOAD (warm)
+	// 50       POP
+	let code = hex!("6000545060005450").to_vec();
+
+	let mut params = ActionParams::default();
+	params.gas = U256::from(100_000);
+	params.code = Some(Arc::new(code));
+	let mut ext = FakeExt::new_berlin();
+
+	let gas_left = {
+		let vm = factory.create(params, ext.schedule(), ext.depth());
+		test_finalize(vm.exec(&mut ext).ok().unwrap()).unwrap()
+	};
+
+	assert_eq!(gas_left, U256::from(100_000 - 3 - 2_100 - 2 - 3 - 100 - 2));
+	assert_set_contains(&ext.warm_storage_keys, &H256::zero());
+}
+
+evm_test!{test_balance_cold_then_warm: test_balance_cold_then_warm_int}
+fn test_balance_cold_then_warm(factory: super::Factory) {
+	let address = Address::from_str("0f572e5295c57f15886f9b263e2f6d2d6c7b5ec6").unwrap();
+	// 73 0f57..5ec6    PUSH20 0f572e5295c57f15886f9b263e2f6d2d6c7b5ec6
+	// 31               BALANCE (cold)
+	// 50               POP
+	// 73 0f57..5ec6    PUSH20 0f572e5295c57f15886f9b263e2f6d2d6c7b5ec6
+	// 31               BALANCE (warm)
+	// 50               POP
+	let code = hex!("730f572e5295c57f15886f9b263e2f6d2d6c7b5ec63150730f572e5295c57f15886f9b263e2f6d2d6c7b5ec63150").to_vec();
+
+	let mut params = ActionParams::default();
+	params.gas = U256::from(100_000);
+	params.code = Some(Arc::new(code));
+	let mut ext = FakeExt::new_berlin();
+	ext.balances.insert(address, U256::from(10));
+
+	let gas_left = {
+		let vm = factory.create(params, ext.schedule(), ext.depth());
+		test_finalize(vm.exec(&mut ext).ok().unwrap()).unwrap()
+	};
+
+	assert_eq!(gas_left, U256::from(100_000 - 3 - 2_600 - 2 - 3 - 100 - 2));
+	assert_set_contains(&ext.warm_addresses, &address);
+}
+
 fn push_two_pop_one_constantinople_test(factory: &super::Factory, opcode: u8, mut push1: Vec<u8>, mut push2:  Vec<u8>, result: &str) {
 	assert!(push1.len() <= 32 && push1.len() != 0);
 	assert!(push2.len() <= 32 && push2.len() != 0);
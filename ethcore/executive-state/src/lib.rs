@@ -221,7 +221,7 @@ impl<B: Backend> ExecutiveState for State<B> {
 		};
 
 		let output = e.output;
-		let receipt = Receipt::new(outcome, e.cumulative_gas_used, e.logs);
+		let receipt = Receipt::new(outcome, e.cumulative_gas_used, e.logs).with_tx_type(t.tx_type());
 		trace!(target: "state", "Transaction receipt: {:?}", receipt);
 
 		Ok(ApplyOutcome {
@@ -1137,6 +1137,16 @@ mod tests {
 		assert_eq!(state.code(&a).unwrap(), Some(Arc::new(vec![1u8, 2, 3])));
 	}
 
+	#[test]
+	fn code_stored_by_hash() {
+		let a = Address::zero();
+		let mut state = get_temp_state();
+		state.init_code(&a, vec![1, 2, 3]).unwrap();
+		state.commit().unwrap();
+		let db = state.drop().1;
+		assert_eq!(db.as_hash_db().get(&keccak(&[1u8, 2, 3]), hash_db::EMPTY_PREFIX).map(|code| code.to_vec()), Some(vec![1u8, 2, 3]));
+	}
+
 	#[test]
 	fn storage_at_from_database() {
 		let a = Address::zero();
@@ -23,7 +23,7 @@ use bytes::Bytes;
 use common_types::basic_account::BasicAccount;
 use common_types::encoded;
 use common_types::receipt::Receipt;
-use common_types::transaction::SignedTransaction;
+use common_types::transaction::{raw_item_bytes, SignedTransaction};
 use engine::{Engine, StateDependentProof};
 use executive_state::{ProvedExecution, self};
 use ethereum_types::{H256, U256, Address};
@@ -924,7 +924,7 @@ impl Body {
 	pub fn check_response(&self, cache: &Mutex<::cache::Cache>, body: &encoded::Body) -> Result<encoded::Block, Error> {
 		// check the integrity of the the body against the header
 		let header = self.0.as_ref()?;
-		let tx_root = ::triehash::ordered_trie_root(body.transactions_rlp().iter().map(|r| r.as_raw()));
+		let tx_root = ::triehash::ordered_trie_root(body.transactions_rlp().iter().map(|r| raw_item_bytes(&r)));
 		if tx_root != header.transactions_root() {
 			trace!(target: "on_demand", "Body Response: \"WrongTrieRoot\" tx_root: {:?} header_root: {:?}", tx_root, header.transactions_root());
 			return Err(Error::WrongTrieRoot(header.transactions_root(), tx_root));
@@ -952,7 +952,7 @@ impl BlockReceipts {
 	/// Check a response with receipts against the stored header.
 	pub fn check_response(&self, cache: &Mutex<::cache::Cache>, receipts: &[Receipt]) -> Result<Vec<Receipt>, Error> {
 		let receipts_root = self.0.as_ref()?.receipts_root();
-		let found_root = ::triehash::ordered_trie_root(receipts.iter().map(|r| ::rlp::encode(r)));
+		let found_root = ::triehash::ordered_trie_root(receipts.iter().map(|r| r.encode_raw()));
 
 		if receipts_root == found_root {
 			cache.lock().insert_block_receipts(receipts_root, receipts.to_vec());
@@ -1253,6 +1253,7 @@ mod tests {
 			gas_used: 21_000u64.into(),
 			log_bloom: Default::default(),
 			logs: Vec::new(),
+			tx_type: Default::default(),
 		}).collect::<Vec<_>>();
 
 		let mut header = Header::new();
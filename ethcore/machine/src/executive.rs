@@ -29,13 +29,13 @@ use account_state::{Backend as StateBackend, State, CleanupMode};
 use evm::{ActionType, Finalize, FinalizationResult};
 use vm::{
 	self, EnvInfo, CreateContractAddress, ReturnData, CleanDustMode, ActionParams,
-	ActionValue, Schedule, TrapError, ResumeCall, ResumeCreate
+	ActionValue, Schedule, TrapError, ResumeCall, ResumeCreate, AccessList, RecordedAccesses
 };
 use trie_vm_factories::VmFactory;
 use trace::{self, Tracer, VMTracer};
 use common_types::{
 	errors::ExecutionError,
-	transaction::{Action, SignedTransaction},
+	transaction::{self, Action, SignedTransaction},
 	engines::machine::Executed,
 };
 
@@ -133,6 +133,8 @@ pub struct TransactOptions<T, V> {
 	pub check_nonce: bool,
 	/// Records the output from init contract calls.
 	pub output_from_init_contract: bool,
+	/// Records the addresses and storage keys accessed by the transaction.
+	pub record_access_list: bool,
 }
 
 impl<T, V> TransactOptions<T, V> {
@@ -143,6 +145,7 @@ impl<T, V> TransactOptions<T, V> {
 			vm_tracer,
 			check_nonce: true,
 			output_from_init_contract: false,
+			record_access_list: false,
 		}
 	}
 
@@ -157,6 +160,12 @@ impl<T, V> TransactOptions<T, V> {
 		self.output_from_init_contract = true;
 		self
 	}
+
+	/// Records the accessed addresses and storage keys.
+	pub fn record_access_list(mut self) -> Self {
+		self.record_access_list = true;
+		self
+	}
 }
 
 impl TransactOptions<trace::ExecutiveTracer, trace::ExecutiveVMTracer> {
@@ -167,6 +176,7 @@ impl TransactOptions<trace::ExecutiveTracer, trace::ExecutiveVMTracer> {
 			vm_tracer: trace::ExecutiveVMTracer::toplevel(),
 			check_nonce: true,
 			output_from_init_contract: false,
+			record_access_list: false,
 		}
 	}
 }
@@ -179,6 +189,7 @@ impl TransactOptions<trace::ExecutiveTracer, trace::NoopVMTracer> {
 			vm_tracer: trace::NoopVMTracer,
 			check_nonce: true,
 			output_from_init_contract: false,
+			record_access_list: false,
 		}
 	}
 }
@@ -191,6 +202,7 @@ impl TransactOptions<trace::NoopTracer, trace::ExecutiveVMTracer> {
 			vm_tracer: trace::ExecutiveVMTracer::toplevel(),
 			check_nonce: true,
 			output_from_init_contract: false,
+			record_access_list: false,
 		}
 	}
 }
@@ -203,6 +215,7 @@ impl TransactOptions<trace::NoopTracer, trace::NoopVMTracer> {
 			vm_tracer: trace::NoopVMTracer,
 			check_nonce: true,
 			output_from_init_contract: false,
+			record_access_list: false,
 		}
 	}
 }
@@ -351,6 +364,7 @@ impl<'a> CallCreateExecutive<'a> {
 				| Err(vm::Error::Reverted)
 				| Ok(FinalizationResult { apply_state: false, .. }) => {
 					state.revert_to_checkpoint();
+					un_substate.access_list.rollback();
 			},
 			Ok(_) | Err(vm::Error::Internal(_)) => {
 				state.discard_checkpoint();
@@ -465,6 +479,8 @@ impl<'a> CallCreateExecutive<'a> {
 					}
 				}
 
+				unconfirmed_substate.access_list = substate.access_list.fork();
+
 				let origin_info = OriginInfo::from(&params);
 				let exec = self.factory.create(params, self.schedule, self.depth);
 
@@ -497,6 +513,9 @@ impl<'a> CallCreateExecutive<'a> {
 			CallCreateExecutiveKind::ExecCreate(params, mut unconfirmed_substate) => {
 				assert!(self.is_create);
 
+				// The created address stays warm even if the creation fails (EIP-2929).
+				substate.access_list.insert_address(params.address);
+
 				{
 					let static_flag = self.static_flag;
 					let is_create = self.is_create;
@@ -516,6 +535,8 @@ impl<'a> CallCreateExecutive<'a> {
 					}
 				}
 
+				unconfirmed_substate.access_list = substate.access_list.fork();
+
 				let origin_info = OriginInfo::from(&params);
 				let exec = self.factory.create(params, self.schedule, self.depth);
 
@@ -810,6 +831,7 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 			t,
 			options.check_nonce,
 			options.output_from_init_contract,
+			options.record_access_list,
 			options.tracer,
 			options.vm_tracer
 		)
@@ -838,6 +860,7 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 		t: &SignedTransaction,
 		check_nonce: bool,
 		output_from_create: bool,
+		record_access_list: bool,
 		mut tracer: T,
 		mut vm_tracer: V
 	) -> Result<Executed<T::Output, V::Output>, ExecutionError> where T: Tracer, V: VMTracer {
@@ -851,6 +874,13 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 			return Err(ExecutionError::NotEnoughBaseGas { required: base_gas_required, got: t.gas });
 		}
 
+		// validate the size of the init code of contract creations (EIP-3860)
+		if let (Action::Create, Some(limit)) = (&t.action, schedule.max_initcode_size) {
+			if t.data.len() > limit {
+				return Err(ExecutionError::InitcodeSizeExceeded { limit, got: t.data.len() });
+			}
+		}
+
 		if !t.is_unsigned() && check_nonce && schedule.kill_dust != CleanDustMode::Off && !self.state.exists(&sender)? {
 			return Err(ExecutionError::SenderMustExist);
 		}
@@ -871,10 +901,20 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 			});
 		}
 
+		// validate the transaction pays at least the block base fee (EIP-1559);
+		// virtual calls without a gas price are exempt, like `eth_call` in other clients
+		if let Some(base_fee) = self.info.base_fee {
+			if t.gas_price < base_fee && (check_nonce || !t.gas_price.is_zero()) {
+				return Err(ExecutionError::GasPriceLowerThanBaseFee { gas_price: t.gas_price, base_fee });
+			}
+		}
+		let gas_price = t.effective_gas_price(self.info.base_fee);
+
 		// TODO: we might need bigints here, or at least check overflows.
+		// The sender must be able to afford the maximum fee, but is only charged the effective price.
 		let balance = self.state.balance(&sender)?;
-		let gas_cost = t.gas.full_mul(t.gas_price);
-		let total_cost = U512::from(t.value) + gas_cost;
+		let gas_cost = t.gas.full_mul(gas_price);
+		let total_cost = U512::from(t.value) + t.gas.full_mul(t.gas_price);
 
 		// avoid unaffordable transactions
 		let balance512 = U512::from(balance);
@@ -882,7 +922,30 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 			return Err(ExecutionError::NotEnoughCash { required: total_cost, got: balance512 });
 		}
 
-		let mut substate = Substate::new();
+		let mut access_list = AccessList::new(schedule.eip2929);
+		if schedule.eip2929 {
+			// Pre-warm the sender, the recipient, the precompiles and the transaction's access list.
+			// The address of a created contract is warmed when the creation is executed.
+			access_list.insert_address(sender);
+			if let Action::Call(ref address) = t.action {
+				access_list.insert_address(*address);
+			}
+			for (address, builtin) in self.machine.builtins() {
+				if builtin.is_active(self.info.number) {
+					access_list.insert_address(*address);
+				}
+			}
+			for item in t.access_list().into_iter().flatten() {
+				access_list.insert_address(item.address);
+				for key in &item.storage_keys {
+					access_list.insert_storage_key(item.address, *key);
+				}
+			}
+		}
+		if record_access_list {
+			access_list.start_recording();
+		}
+		let mut substate = Substate::from_access_list(access_list);
 
 		// NOTE: there can be no invalid transactions from this point.
 		if !schedule.keep_unsigned_nonce || !t.is_unsigned() {
@@ -904,7 +967,7 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 					sender: sender.clone(),
 					origin: sender.clone(),
 					gas: init_gas,
-					gas_price: t.gas_price,
+					gas_price,
 					value: ActionValue::Transfer(t.value),
 					code: Some(Arc::new(t.data.clone())),
 					code_version: schedule.latest_version,
@@ -926,7 +989,7 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 					sender: sender.clone(),
 					origin: sender.clone(),
 					gas: init_gas,
-					gas_price: t.gas_price,
+					gas_price,
 					value: ActionValue::Transfer(t.value),
 					code: self.state.code(address)?,
 					code_hash: self.state.code_hash(address)?,
@@ -1150,12 +1213,13 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 
 		// real amount to refund
 		let gas_left_prerefund = match result { Ok(FinalizationResult{ gas_left, .. }) => gas_left, _ => 0.into() };
-		let refunded = cmp::min(refunds_bound, (t.gas - gas_left_prerefund) >> 1);
+		let refunded = cmp::min(refunds_bound, (t.gas - gas_left_prerefund) / schedule.max_refund_quotient);
 		let gas_left = gas_left_prerefund + refunded;
 
 		let gas_used = t.gas.saturating_sub(gas_left);
-		let (refund_value, overflow_1) = gas_left.overflowing_mul(t.gas_price);
-		let (fees_value, overflow_2) = gas_used.overflowing_mul(t.gas_price);
+		// since EIP-1559 the base fee part of the gas price is burnt and only the priority fee goes to the author
+		let (refund_value, overflow_1) = gas_left.overflowing_mul(t.effective_gas_price(self.info.base_fee));
+		let (fees_value, overflow_2) = gas_used.overflowing_mul(t.effective_priority_fee(self.info.base_fee));
 		if overflow_1 || overflow_2 {
 			return Err(ExecutionError::TransactionMalformed("U256 Overflow".to_string()));
 		}
@@ -1171,6 +1235,8 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 		trace!(target: "executive", "exec::finalize: Compensating author: fees_value={}, author={}\n", fees_value, &self.info.author);
 		self.state.add_balance(&self.info.author, &fees_value, cleanup_mode(&mut substate, &schedule))?;
 
+		let access_list = substate.access_list.recorded().map(|recorded| self.recorded_access_list(t, &substate, recorded));
+
 		// perform suicides
 		for address in &substate.suicides {
 			self.state.kill_account(address);
@@ -1195,6 +1261,7 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 					trace: trace,
 					vm_trace: vm_trace,
 					state_diff: None,
+					access_list,
 				})
 			},
 			Ok(r) => {
@@ -1210,10 +1277,31 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 					trace: trace,
 					vm_trace: vm_trace,
 					state_diff: None,
+					access_list,
 				})
 			},
 		}
 	}
+
+	/// Turns the accesses recorded during a transaction into an access list. Accounts that are
+	/// warm anyway are left out, unless some of their storage has been accessed.
+	fn recorded_access_list(&self, t: &SignedTransaction, substate: &Substate, recorded: RecordedAccesses) -> transaction::AccessList {
+		let sender = t.sender();
+		let is_warm_anyway = |address: &Address| {
+			*address == sender
+				|| t.action == Action::Call(*address)
+				|| substate.contracts_created.contains(address)
+				|| self.machine.builtin(address, self.info.number).is_some()
+		};
+
+		recorded.into_iter()
+			.filter(|(address, storage_keys)| !storage_keys.is_empty() || !is_warm_anyway(address))
+			.map(|(address, storage_keys)| transaction::AccessListItem {
+				address,
+				storage_keys: storage_keys.into_iter().collect(),
+			})
+			.collect()
+	}
 }
 
 #[cfg(test)]
@@ -2101,6 +2189,107 @@ mod tests {
 		}
 	}
 
+	evm_test!{test_transact_records_access_list: test_transact_records_access_list_int}
+	fn test_transact_records_access_list(factory: Factory) {
+		let contract = Address::from_low_u64_be(0x1000);
+		let other = Address::from_low_u64_be(0x2000);
+		// SLOAD(0x2a) BALANCE(other) BALANCE(0x01, a precompile)
+		let code = format!("602a545073{:x}31506001315000", other).from_hex().unwrap();
+		let keypair = Random.generate().unwrap();
+		let t = Transaction {
+			action: Action::Call(contract),
+			value: U256::zero(),
+			data: vec![],
+			gas: U256::from(100_000),
+			gas_price: U256::zero(),
+			nonce: U256::zero()
+		}.sign(keypair.secret(), None);
+
+		let mut state = get_temp_state_with_factory(factory);
+		state.init_code(&contract, code).unwrap();
+		let mut info = EnvInfo::default();
+		info.gas_limit = U256::from(100_000);
+		let mut machine = new_byzantium_test_machine();
+		machine.set_schedule_creation_rules(Box::new(|s, _| s.enable_eip2929()));
+		let schedule = machine.schedule(info.number);
+
+		let executed = {
+			let mut ex = Executive::new(&mut state, &info, &machine, &schedule);
+			let opts = TransactOptions::with_no_tracing().record_access_list();
+			ex.transact(&t, opts).unwrap()
+		};
+
+		assert_eq!(executed.exception, None);
+		assert_eq!(executed.access_list, Some(vec![
+			transaction::AccessListItem { address: contract, storage_keys: vec![H256::from_low_u64_be(0x2a)] },
+			transaction::AccessListItem { address: other, storage_keys: vec![] },
+		]));
+	}
+
+	evm_test!{test_transact_rejects_code_starting_with_ef: test_transact_rejects_code_starting_with_ef_int}
+	fn test_transact_rejects_code_starting_with_ef(factory: Factory) {
+		// MSTORE8(0, 0xef) RETURN(0, 1)
+		let code = "60ef60005360016000f3".from_hex().unwrap();
+		let keypair = Random.generate().unwrap();
+		let t = Transaction {
+			action: Action::Create,
+			value: U256::zero(),
+			data: code,
+			gas: U256::from(100_000),
+			gas_price: U256::zero(),
+			nonce: U256::zero()
+		}.sign(keypair.secret(), None);
+		let contract = contract_address(CreateContractAddress::FromSenderAndNonce, &t.sender(), &U256::zero(), &[]).0;
+
+		let mut state = get_temp_state_with_factory(factory);
+		let mut info = EnvInfo::default();
+		info.gas_limit = U256::from(100_000);
+		let mut machine = new_byzantium_test_machine();
+		machine.set_schedule_creation_rules(Box::new(|s, _| s.eip3541 = true));
+		let schedule = machine.schedule(info.number);
+
+		let executed = {
+			let mut ex = Executive::new(&mut state, &info, &machine, &schedule);
+			let opts = TransactOptions::with_no_tracing();
+			ex.transact(&t, opts).unwrap()
+		};
+
+		assert_eq!(executed.exception, Some(vm::Error::OutOfGas));
+		assert_eq!(executed.gas_used, U256::from(100_000));
+		assert_eq!(state.code(&contract).unwrap(), None);
+	}
+
+	evm_test!{test_transact_initcode_size_exceeded: test_transact_initcode_size_exceeded_int}
+	fn test_transact_initcode_size_exceeded(factory: Factory) {
+		let keypair = Random.generate().unwrap();
+		let t = Transaction {
+			action: Action::Create,
+			value: U256::zero(),
+			data: vec![0; 2 * 24576 + 1],
+			gas: U256::from(300_000),
+			gas_price: U256::zero(),
+			nonce: U256::zero()
+		}.sign(keypair.secret(), None);
+
+		let mut state = get_temp_state_with_factory(factory);
+		let mut info = EnvInfo::default();
+		info.gas_limit = U256::from(300_000);
+		let mut machine = new_byzantium_test_machine();
+		machine.set_schedule_creation_rules(Box::new(|s, _| s.enable_eip3860()));
+		let schedule = machine.schedule(info.number);
+
+		let res = {
+			let mut ex = Executive::new(&mut state, &info, &machine, &schedule);
+			let opts = TransactOptions::with_no_tracing();
+			ex.transact(&t, opts)
+		};
+
+		match res {
+			Err(ExecutionError::InitcodeSizeExceeded { limit, got }) if limit == 49152 && got == 49153 => (),
+			_ => assert!(false, "Expected init code size error. {:?}", res)
+		}
+	}
+
 	evm_test!{test_keccak: test_keccak_int}
 	fn test_keccak(factory: Factory) {
 		let code = "6064640fffffffff20600055".from_hex().unwrap();
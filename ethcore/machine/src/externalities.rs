@@ -372,6 +372,10 @@ impl<'a, T: 'a, V: 'a, B: 'a> Ext for Externalities<'a, T, V, B>
 				Ok(*gas)
 			},
 			OutputPolicy::InitContract if apply_state => {
+				// new code starting with the 0xEF byte is rejected, consuming all gas (EIP-3541)
+				if self.schedule.eip3541 && data.first() == Some(&0xef) {
+					return Err(vm::Error::OutOfGas);
+				}
 				let return_cost = U256::from(data.len()) * U256::from(self.schedule.create_data_gas);
 				if return_cost > *gas || data.len() > self.schedule.create_data_limit {
 					return match self.schedule.exceptional_failed_code_deposit {
@@ -432,10 +436,28 @@ impl<'a, T: 'a, V: 'a, B: 'a> Ext for Externalities<'a, T, V, B>
 
 	fn add_sstore_refund(&mut self, value: usize) {
 		self.substate.sstore_clears_refund += value as i128;
+		self.vm_tracer.trace_refund(value as i128);
 	}
 
 	fn sub_sstore_refund(&mut self, value: usize) {
 		self.substate.sstore_clears_refund -= value as i128;
+		self.vm_tracer.trace_refund(-(value as i128));
+	}
+
+	fn is_address_warm(&self, address: &Address) -> bool {
+		self.substate.access_list.contains_address(address)
+	}
+
+	fn warm_address(&mut self, address: Address) {
+		self.substate.access_list.insert_address(address)
+	}
+
+	fn is_storage_key_warm(&self, key: &H256) -> bool {
+		self.substate.access_list.contains_storage_key(&self.origin_info.address, key)
+	}
+
+	fn warm_storage_key(&mut self, key: H256) {
+		self.substate.access_list.insert_storage_key(self.origin_info.address, key)
 	}
 
 	fn trace_next_instruction(&mut self, pc: usize, instruction: u8, current_gas: U256) -> bool {
@@ -493,6 +515,7 @@ mod tests {
 			last_hashes: Arc::new(vec![]),
 			gas_used: 0.into(),
 			gas_limit: 0.into(),
+			base_fee: None,
 		}
 	}
 
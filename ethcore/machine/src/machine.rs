@@ -18,9 +18,10 @@
 
 use std::collections::BTreeMap;
 use std::cmp;
+use std::convert::TryFrom;
 use std::sync::Arc;
 
-use ethereum_types::{U256, H256, Address};
+use ethereum_types::{U256, U512, H256, Address};
 use rlp::Rlp;
 use log::debug;
 
@@ -32,7 +33,7 @@ use common_types::{
 		params::CommonParams,
 	},
 	errors::{EngineError, EthcoreError as Error},
-	transaction::{self, SYSTEM_ADDRESS, UNSIGNED_SENDER, UnverifiedTransaction, SignedTransaction},
+	transaction::{self, SYSTEM_ADDRESS, UNSIGNED_SENDER, TypedTxId, UnverifiedTransaction, SignedTransaction},
 };
 use vm::{ActionType, ActionParams, ActionValue, ParamsType};
 use vm::{EnvInfo, Schedule};
@@ -219,8 +220,14 @@ impl Machine {
 	/// The gas floor target must not be lower than the engine's minimum gas limit.
 	pub fn populate_from_parent(&self, header: &mut Header, parent: &Header, gas_floor_target: U256, gas_ceil_target: U256) {
 		header.set_difficulty(parent.difficulty().clone());
-		let gas_limit = parent.gas_limit().clone();
+		let gas_limit = if parent.number() + 1 == self.params().eip1559_transition {
+			// the gas limit of the first EIP-1559 block is derived from the scaled up target
+			*parent.gas_limit() * self.params().eip1559_elasticity_multiplier
+		} else {
+			*parent.gas_limit()
+		};
 		assert!(!gas_limit.is_zero(), "Gas limit should be > 0");
+		header.set_base_fee(self.calc_base_fee(parent));
 
 		if let Some(ref ethash_params) = self.ethash_extensions {
 			let gas_limit = {
@@ -264,6 +271,41 @@ impl Machine {
 		});
 	}
 
+	/// Computes the base fee of the block following `parent`, as defined by EIP-1559.
+	/// Returns `None` when that block is before the EIP-1559 transition.
+	pub fn calc_base_fee(&self, parent: &Header) -> Option<U256> {
+		let params = self.params();
+		let number = parent.number() + 1;
+		if number < params.eip1559_transition {
+			return None;
+		}
+		if number == params.eip1559_transition {
+			return Some(params.eip1559_base_fee_initial_value);
+		}
+
+		let parent_base_fee = parent.base_fee().unwrap_or(params.eip1559_base_fee_initial_value);
+		let parent_gas_target = *parent.gas_limit() / params.eip1559_elasticity_multiplier;
+		if parent_gas_target.is_zero() {
+			return Some(parent_base_fee);
+		}
+		let denominator = params.eip1559_base_fee_max_change_denominator;
+		let parent_gas_used = *parent.gas_used();
+
+		let base_fee = if parent_gas_used > parent_gas_target {
+			let gas_used_delta = parent_gas_used - parent_gas_target;
+			let delta = parent_base_fee.full_mul(gas_used_delta) / U512::from(parent_gas_target) / U512::from(denominator);
+			let delta = cmp::max(U256::try_from(delta).unwrap_or_else(|_| U256::max_value()), U256::one());
+			parent_base_fee.saturating_add(delta)
+		} else if parent_gas_used < parent_gas_target {
+			let gas_used_delta = parent_gas_target - parent_gas_used;
+			let delta = parent_base_fee.full_mul(gas_used_delta) / U512::from(parent_gas_target) / U512::from(denominator);
+			parent_base_fee.saturating_sub(U256::try_from(delta).unwrap_or_else(|_| U256::max_value()))
+		} else {
+			parent_base_fee
+		};
+		Some(base_fee)
+	}
+
 	/// Get the general parameters of the chain.
 	pub fn params(&self) -> &CommonParams {
 		&self.params
@@ -345,6 +387,22 @@ impl Machine {
 		};
 		t.verify_basic(check_low_s, chain_id)?;
 
+		if t.tx_type() != TypedTxId::Legacy && header.number() < self.params().eip2930_transition {
+			return Err(transaction::Error::TransactionTypeNotEnabled);
+		}
+
+		if t.tx_type() == TypedTxId::EIP1559 {
+			if header.number() < self.params().eip1559_transition {
+				return Err(transaction::Error::TransactionTypeNotEnabled);
+			}
+			if t.max_priority_fee_per_gas() > t.gas_price {
+				return Err(transaction::Error::MaxPriorityFeeTooHigh {
+					max_fee: t.gas_price,
+					max_priority_fee: t.max_priority_fee_per_gas(),
+				});
+			}
+		}
+
 		Ok(())
 	}
 
@@ -489,4 +547,35 @@ mod tests {
 		machine.populate_from_parent(&mut header, &parent, U256::from(150_000), U256::from(150_002));
 		assert_eq!(*header.gas_limit(), U256::from(150_002));
 	}
+
+	#[test]
+	fn should_calculate_base_fee() {
+		let spec = spec::new_test();
+		let mut params = spec.params().clone();
+		params.eip1559_transition = 10;
+		let machine = Machine::regular(params, Default::default());
+
+		let mut parent = Header::new();
+		parent.set_number(8);
+		assert_eq!(machine.calc_base_fee(&parent), None);
+
+		parent.set_number(9);
+		assert_eq!(machine.calc_base_fee(&parent), Some(U256::from(1_000_000_000)));
+
+		parent.set_number(10);
+		parent.set_base_fee(Some(U256::from(1_000_000_000)));
+		parent.set_gas_limit(U256::from(20_000_000));
+
+		// gas used at the target keeps the base fee
+		parent.set_gas_used(U256::from(10_000_000));
+		assert_eq!(machine.calc_base_fee(&parent), Some(U256::from(1_000_000_000)));
+
+		// full blocks raise the base fee by 12.5%
+		parent.set_gas_used(U256::from(20_000_000));
+		assert_eq!(machine.calc_base_fee(&parent), Some(U256::from(1_125_000_000)));
+
+		// empty blocks lower the base fee by 12.5%
+		parent.set_gas_used(U256::zero());
+		assert_eq!(machine.calc_base_fee(&parent), Some(U256::from(875_000_000)));
+	}
 }
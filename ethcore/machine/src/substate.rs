@@ -19,6 +19,7 @@
 use std::collections::HashSet;
 use ethereum_types::Address;
 use common_types::log_entry::LogEntry;
+use vm::AccessList;
 
 /// State changes which should be applied in finalize,
 /// after transaction is fully executed.
@@ -38,6 +39,9 @@ pub struct Substate {
 
 	/// Created contracts.
 	pub contracts_created: Vec<Address>,
+
+	/// Addresses and storage keys accessed so far (EIP-2929).
+	pub access_list: AccessList,
 }
 
 impl Substate {
@@ -46,6 +50,14 @@ impl Substate {
 		Substate::default()
 	}
 
+	/// Creates new substate for a call frame, recording accesses in `access_list`.
+	pub fn from_access_list(access_list: AccessList) -> Self {
+		Substate {
+			access_list,
+			..Substate::default()
+		}
+	}
+
 	/// Merge secondary substate `s` into self, accruing each element correspondingly.
 	pub fn accrue(&mut self, s: Substate) {
 		self.suicides.extend(s.suicides);
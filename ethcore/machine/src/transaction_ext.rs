@@ -34,10 +34,27 @@ impl Transaction for transaction::Transaction {
 	}
 }
 
+impl Transaction for transaction::TypedTransaction {
+	fn gas_required(&self, schedule: &Schedule) -> u64 {
+		let access_list_gas = self.access_list().map_or(0, |list| list.iter().fold(0, |g, item| {
+			g + schedule.tx_access_list_address_gas as u64 +
+				item.storage_keys.len() as u64 * schedule.tx_access_list_storage_key_gas as u64
+		}));
+		(**self).gas_required(schedule) + access_list_gas
+	}
+}
+
 /// Get the transaction cost in gas for the given params.
 fn gas_required_for(is_create: bool, data: &[u8], schedule: &Schedule) -> u64 {
+	let base = if is_create {
+		// init code is charged per word since EIP-3860
+		let initcode_words = (data.len() as u64 + 31) / 32;
+		schedule.tx_create_gas as u64 + initcode_words * schedule.initcode_word_gas as u64
+	} else {
+		schedule.tx_gas as u64
+	};
 	data.iter().fold(
-		(if is_create {schedule.tx_create_gas} else {schedule.tx_gas}) as u64,
+		base,
 		|g, b| g + (match *b { 0 => schedule.tx_data_zero_gas, _ => schedule.tx_data_non_zero_gas }) as u64
 	)
 }
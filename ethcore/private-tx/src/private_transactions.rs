@@ -81,6 +81,18 @@ impl pool::ScoredTransaction for VerifiedPrivateTransaction {
 	fn nonce(&self) -> U256 {
 		self.transaction.nonce
 	}
+
+	/// Gets the fee per gas paid to the block author given the block base fee.
+	fn effective_priority_fee(&self, block_base_fee: Option<U256>) -> U256 {
+		self.transaction.effective_priority_fee(block_base_fee)
+	}
+
+	/// Private transactions are never penalized.
+	fn penalties(&self) -> usize {
+		0
+	}
+
+	fn penalize(&self) {}
 }
 
 /// Checks readiness of transactions by looking if the transaction from sender already exists.
@@ -133,7 +145,7 @@ impl Default for VerificationStore {
 			verification_pool: RwLock::new(
 				txpool::Pool::new(
 					txpool::NoopListener,
-					pool::scoring::NonceAndGasPrice(pool::PrioritizationStrategy::GasPriceOnly),
+					pool::scoring::NonceAndGasPrice::new(pool::PrioritizationStrategy::GasPriceOnly),
 					pool::Options {
 						max_count: MAX_QUEUE_LEN,
 						max_per_sender: MAX_QUEUE_LEN / 10,
@@ -164,7 +176,7 @@ impl VerificationStore {
 
 		let options = self.verification_options.clone();
 		// Use pool's verifying pipeline for original transaction's verification
-		let verifier = pool::verifier::Verifier::new(client.clone(), options, Default::default(), None);
+		let verifier = pool::verifier::Verifier::new(client.clone(), options, Default::default(), None, None);
 		let unverified = pool::verifier::Transaction::Unverified(transaction);
 		let verified_tx = verifier.verify_transaction(unverified)?;
 		let signed_tx: SignedTransaction = verified_tx.signed().clone();
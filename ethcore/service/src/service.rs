@@ -36,7 +36,7 @@ use common_types::{
 	errors::{EthcoreError, SnapshotError},
 	snapshot::RestorationStatus,
 };
-use client_traits::{ImportBlock, Tick};
+use client_traits::{GenerateFlatState, ImportBlock, PruneState, Tick};
 
 
 use ethcore_private_tx::{self, Importer, Signer};
@@ -283,6 +283,30 @@ where
 					debug!(target: "snapshot", "Failed to initialize periodic snapshot thread: {:?}", e);
 				}
 			},
+			ClientIoMessage::PruneState(keep, bloom_size) => {
+				let client = self.client.clone();
+				let res = thread::Builder::new().name("State Pruner".into()).spawn(move || {
+					if let Err(e) = client.prune_state(keep, bloom_size) {
+						warn!(target: "pruning", "Failed to prune state: {}", e);
+					}
+				});
+
+				if let Err(e) = res {
+					debug!(target: "pruning", "Failed to initialize state pruning thread: {:?}", e);
+				}
+			},
+			ClientIoMessage::GenerateFlatState => {
+				let client = self.client.clone();
+				let res = thread::Builder::new().name("Flat State Generator".into()).spawn(move || {
+					if let Err(e) = client.generate_flat_state() {
+						warn!(target: "flat_state", "Failed to generate the flat state: {}", e);
+					}
+				});
+
+				if let Err(e) = res {
+					debug!(target: "flat_state", "Failed to initialize flat state generation thread: {:?}", e);
+				}
+			},
 			ClientIoMessage::Execute(ref exec) => {
 				(*exec.0)(&self.client);
 			}
@@ -83,8 +83,26 @@ pub fn to_fat_rlps(
 ) -> Result<Vec<Bytes>, Error> {
 	let db = &(acct_db as &dyn HashDB<_,_>);
 	let db = TrieDB::new(db, &acc.storage_root)?;
+	let storage = db.iter()?.map(|item| -> Result<(Bytes, Bytes), Error> {
+		let (key, value) = item?;
+		Ok((key, value.to_vec()))
+	});
+	to_fat_rlps_with_storage(account_hash, acc, acct_db, storage, used_code, first_chunk_size, max_chunk_size, p)
+}
+
+// like `to_fat_rlps`, with the storage given as pairs of key hashes and RLP encoded values
+// ordered by key hash, e.g. read from the flat state.
+pub fn to_fat_rlps_with_storage<I>(
+	account_hash: &H256,
+	acc: &BasicAccount,
+	acct_db: &AccountDB,
+	mut storage: I,
+	used_code: &mut HashSet<H256>,
+	first_chunk_size: usize,
+	max_chunk_size: usize,
+	p: &RwLock<Progress>,
+) -> Result<Vec<Bytes>, Error> where I: Iterator<Item = Result<(Bytes, Bytes), Error>> {
 	let mut chunks = Vec::new();
-	let mut db_iter = db.iter()?;
 	let mut target_chunk_size = first_chunk_size;
 	let mut account_stream = RlpStream::new_list(2);
 	let mut leftover: Option<Vec<u8>> = None;
@@ -139,11 +157,11 @@ pub fn to_fat_rlps(
 				trace!(target: "snapshot", "to_fat_rlps: aborting snapshot");
 				return Err(Error::SnapshotAborted);
 			}
-			match db_iter.next() {
+			match storage.next() {
 				Some(Ok((k, v))) => {
 					let pair = {
 						let mut stream = RlpStream::new_list(2);
-						stream.append(&k).append(&&*v);
+						stream.append(&k).append(&v);
 						stream.drain()
 					};
 					if !account_stream.append_raw_checked(&pair, 1, target_chunk_size) {
@@ -156,7 +174,7 @@ pub fn to_fat_rlps(
 					}
 				},
 				Some(Err(e)) => {
-					return Err(e.into());
+					return Err(e);
 				},
 				None => {
 					account_stream.finalize_unbounded_list();
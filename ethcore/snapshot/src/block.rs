@@ -18,8 +18,10 @@
 
 use bytes::Bytes;
 use common_types::{
-	block::Block,
+	BlockNumber,
+	block::{Block, decode_uncles},
 	header::Header,
+	transaction::raw_item_bytes,
 	views::BlockView,
 };
 use ethereum_types::H256;
@@ -50,15 +52,17 @@ impl AbridgedBlock {
 
 	/// Given a full block view, trim out the parent hash and block number,
 	/// producing new rlp.
-	pub fn from_block_view(block_view: &BlockView) -> Self {
+	pub fn from_block_view(block_view: &BlockView, eip1559_transition: BlockNumber) -> Self {
 		let header = block_view.header_view();
-		let seal_fields = header.seal();
+		let seal_fields = header.seal(eip1559_transition);
+		let base_fee = header.base_fee(eip1559_transition);
 
-		// 10 header fields, unknown number of seal fields, and 2 block fields.
+		// 10 header fields, unknown number of seal fields, 2 block fields and the base fee since EIP-1559.
 		let mut stream = RlpStream::new_list(
 			HEADER_FIELDS +
 			seal_fields.len() +
-			BLOCK_FIELDS
+			BLOCK_FIELDS +
+			base_fee.map_or(0, |_| 1)
 		);
 
 		// write header values.
@@ -75,13 +79,17 @@ impl AbridgedBlock {
 		// write block values.
 		stream
 			.append_list(&block_view.transactions())
-			.append_list(&block_view.uncles());
+			.append_list(&block_view.uncles(eip1559_transition));
 
 		// write seal fields.
 		for field in seal_fields {
 			stream.append_raw(&field, 1);
 		}
 
+		if let Some(base_fee) = base_fee {
+			stream.append(&base_fee);
+		}
+
 		AbridgedBlock {
 			rlp: stream.out(),
 		}
@@ -90,7 +98,7 @@ impl AbridgedBlock {
 	/// Flesh out an abridged block view with the provided parent hash and block number.
 	///
 	/// Will fail if contains invalid rlp.
-	pub fn to_block(&self, parent_hash: H256, number: u64, receipts_root: H256) -> Result<Block, DecoderError> {
+	pub fn to_block(&self, parent_hash: H256, number: u64, receipts_root: H256, eip1559_transition: BlockNumber) -> Result<Block, DecoderError> {
 		let rlp = Rlp::new(&self.rlp);
 
 		let mut header: Header = Default::default();
@@ -106,10 +114,10 @@ impl AbridgedBlock {
 		header.set_extra_data(rlp.val_at(7)?);
 
 		let transactions = rlp.list_at(8)?;
-		let uncles: Vec<Header> = rlp.list_at(9)?;
+		let uncles = decode_uncles(&rlp.at(9)?, eip1559_transition)?;
 
 		header.set_transactions_root(ordered_trie_root(
-			rlp.at(8)?.iter().map(|r| r.as_raw())
+			rlp.at(8)?.iter().map(|r| raw_item_bytes(&r))
 		));
 		header.set_receipts_root(receipts_root);
 
@@ -117,8 +125,14 @@ impl AbridgedBlock {
 		uncles_rlp.append_list(&uncles);
 		header.set_uncles_hash(keccak(uncles_rlp.as_raw()));
 
+		let mut seal_end = rlp.item_count()?;
+		if number >= eip1559_transition {
+			seal_end -= 1;
+			header.set_base_fee(Some(rlp.val_at(seal_end)?));
+		}
+
 		let mut seal_fields = Vec::new();
-		for i in (HEADER_FIELDS + BLOCK_FIELDS)..rlp.item_count()? {
+		for i in (HEADER_FIELDS + BLOCK_FIELDS)..seal_end {
 			let seal_rlp = rlp.at(i)?;
 			seal_fields.push(seal_rlp.as_raw().to_owned());
 		}
@@ -32,6 +32,7 @@ use common_types::{
 	errors::{SnapshotError, EthcoreError},
 	snapshot::{ChunkSink, ManifestData, Progress},
 	receipt::Receipt,
+	transaction::raw_item_bytes,
 };
 use engine::Engine;
 use ethereum_types::{H256, U256};
@@ -131,7 +132,7 @@ impl<'a> PowWorker<'a> {
 				.and_then(|b| self.chain.block_receipts(&self.current_hash).map(|r| (b, r)))
 				.ok_or_else(||SnapshotError::BlockNotFound(self.current_hash))?;
 
-			let abridged_rlp = AbridgedBlock::from_block_view(&block.view()).into_inner();
+			let abridged_rlp = AbridgedBlock::from_block_view(&block.view(), self.chain.eip1559_transition()).into_inner();
 
 			let pair = {
 				let mut pair_stream = RlpStream::new_list(2);
@@ -260,9 +261,9 @@ impl Rebuilder for PowRebuilder {
 			let abridged_rlp = pair.at(0)?.as_raw().to_owned();
 			let abridged_block = AbridgedBlock::from_raw(abridged_rlp);
 			let receipts: Vec<Receipt> = pair.list_at(1)?;
-			let receipts_root = ordered_trie_root(pair.at(1)?.iter().map(|r| r.as_raw()));
+			let receipts_root = ordered_trie_root(pair.at(1)?.iter().map(|r| raw_item_bytes(&r)));
 
-			let block = abridged_block.to_block(parent_hash, cur_number, receipts_root)?;
+			let block = abridged_block.to_block(parent_hash, cur_number, receipts_root, engine.params().eip1559_transition)?;
 			let block_bytes = encoded::Block::new(block.rlp_bytes());
 			let is_best = cur_number == self.best_number;
 
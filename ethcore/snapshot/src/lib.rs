@@ -39,7 +39,7 @@ use common_types::{
 };
 use crossbeam_utils::thread;
 use engine::Engine;
-use ethereum_types::{H256, U256};
+use ethereum_types::{BigEndianHash, H256, U256};
 use ethtrie::{TrieDB, TrieDBMut};
 use hash_db::HashDB;
 use journaldb::{self, Algorithm, JournalDB};
@@ -51,7 +51,7 @@ use num_cpus;
 use rand::{Rng, rngs::OsRng};
 use rlp::{RlpStream, Rlp};
 use snappy;
-use state_db::StateDB;
+use state_db::{FlatStateItem, StateDB};
 use trie_db::{Trie, TrieMut};
 
 pub use self::consensus::*;
@@ -115,11 +115,14 @@ impl Default for SnapshotConfiguration {
 }
 
 /// Take a snapshot using the given blockchain, starting block hash, and database, writing into the given writer.
-pub fn take_snapshot<W: SnapshotWriter + Send>(
+/// The accounts and their storage are read from `flat_state` if given, which must be the flat
+/// state at the starting block.
+pub fn take_snapshot<'a, W: SnapshotWriter + Send>(
 	chunker: Box<dyn SnapshotComponents>,
 	chain: &BlockChain,
 	block_hash: H256,
 	state_db: &dyn HashDB<KeccakHasher, DBValue>,
+	flat_state: Option<Box<dyn Iterator<Item = FlatStateItem> + 'a>>,
 	writer: W,
 	p: &RwLock<Progress>,
 	processing_threads: usize,
@@ -139,34 +142,44 @@ pub fn take_snapshot<W: SnapshotWriter + Send>(
 			chunk_secondary(chunker, chain, block_hash, writer, p)
 		})?;
 
-		// The number of threads must be between 1 and SNAPSHOT_SUBPARTS
-		assert!(processing_threads >= 1, "Cannot use less than 1 threads for creating snapshots");
-		let num_threads = cmp::min(processing_threads, SNAPSHOT_SUBPARTS);
-		info!(target: "snapshot", "Using {} threads for Snapshot creation.", num_threads);
-
-		let mut state_guards = Vec::with_capacity(num_threads);
-
-		for thread_idx in 0..num_threads {
-			let tb = scope.builder().name(format!("Snapshot Worker #{} - State", thread_idx).to_string());
-			let state_guard = tb.spawn(move |_| -> Result<Vec<H256>, Error> {
-				let mut chunk_hashes = Vec::new();
-				for part in (thread_idx..SNAPSHOT_SUBPARTS).step_by(num_threads) {
-					debug!(target: "snapshot", "Chunking part {} of the state at {} in thread {}", part, block_number, thread_idx);
-					let mut hashes = chunk_state(state_db, &state_root, writer, p, Some(part), thread_idx)?;
-					chunk_hashes.append(&mut hashes);
+		let state_hashes = match flat_state {
+			// the flat state is read sequentially, on this thread.
+			Some(items) => {
+				info!(target: "snapshot", "Chunking the state at {} from the flat state.", block_number);
+				chunk_flat_state(state_db, items, writer, p)?
+			},
+			None => {
+				// The number of threads must be between 1 and SNAPSHOT_SUBPARTS
+				assert!(processing_threads >= 1, "Cannot use less than 1 threads for creating snapshots");
+				let num_threads = cmp::min(processing_threads, SNAPSHOT_SUBPARTS);
+				info!(target: "snapshot", "Using {} threads for Snapshot creation.", num_threads);
+
+				let mut state_guards = Vec::with_capacity(num_threads);
+
+				for thread_idx in 0..num_threads {
+					let tb = scope.builder().name(format!("Snapshot Worker #{} - State", thread_idx).to_string());
+					let state_guard = tb.spawn(move |_| -> Result<Vec<H256>, Error> {
+						let mut chunk_hashes = Vec::new();
+						for part in (thread_idx..SNAPSHOT_SUBPARTS).step_by(num_threads) {
+							debug!(target: "snapshot", "Chunking part {} of the state at {} in thread {}", part, block_number, thread_idx);
+							let mut hashes = chunk_state(state_db, &state_root, writer, p, Some(part), thread_idx)?;
+							chunk_hashes.append(&mut hashes);
+						}
+						Ok(chunk_hashes)
+					})?;
+					state_guards.push(state_guard);
 				}
-				Ok(chunk_hashes)
-			})?;
-			state_guards.push(state_guard);
-		}
 
-		let block_hashes = block_guard.join().expect("Sub-thread never panics; qed")?;
-		let mut state_hashes = Vec::new();
+				let mut state_hashes = Vec::new();
+				for guard in state_guards {
+					let part_state_hashes = guard.join().expect("Sub-thread never panics; qed")?;
+					state_hashes.extend(part_state_hashes);
+				}
+				state_hashes
+			},
+		};
 
-		for guard in state_guards {
-			let part_state_hashes = guard.join().expect("Sub-thread never panics; qed")?;
-			state_hashes.extend(part_state_hashes);
-		}
+		let block_hashes = block_guard.join().expect("Sub-thread never panics; qed")?;
 
 		info!("Took a snapshot at #{} of {} accounts", block_number, p.read().accounts());
 
@@ -372,6 +385,68 @@ pub fn chunk_state<'a>(
 	Ok(chunker.hashes)
 }
 
+/// Like `chunk_state`, but reads the accounts and their storage from the flat state instead
+/// of walking the state trie. Only the codes are looked up in the state database.
+pub fn chunk_flat_state<'a, I>(
+	db: &dyn HashDB<KeccakHasher, DBValue>,
+	items: I,
+	writer: &Mutex<dyn SnapshotWriter + 'a>,
+	progress: &'a RwLock<Progress>,
+) -> Result<Vec<H256>, Error> where I: Iterator<Item = FlatStateItem> {
+	let mut chunker = StateChunker {
+		hashes: Vec::new(),
+		rlps: Vec::new(),
+		cur_size: 0,
+		snappy_buffer: vec![0; snappy::max_compressed_len(PREFERRED_CHUNK_SIZE)],
+		writer,
+		progress,
+		thread_idx: 0,
+	};
+
+	let mut used_code = HashSet::new();
+	let mut items = items.peekable();
+	while let Some(item) = items.next() {
+		// the storage of an account follows it and is consumed along with it.
+		let (account_key_hash, account_data) = match item {
+			FlatStateItem::Account(hash, rlp) => (hash, rlp),
+			FlatStateItem::Storage(..) => continue,
+		};
+
+		let account = ::rlp::decode(&account_data)?;
+		let account_db = AccountDB::from_hash(db, account_key_hash);
+		let storage = ::std::iter::from_fn(|| match items.peek() {
+			Some(&FlatStateItem::Storage(ref owner, _, _)) if *owner == account_key_hash => match items.next() {
+				Some(FlatStateItem::Storage(_, key, value)) => Some(Ok((key.as_bytes().to_vec(), ::rlp::encode(&value.into_uint())))),
+				_ => None,
+			},
+			_ => None,
+		});
+
+		let fat_rlps = account::to_fat_rlps_with_storage(
+			&account_key_hash,
+			&account,
+			&account_db,
+			storage,
+			&mut used_code,
+			PREFERRED_CHUNK_SIZE - chunker.chunk_size(),
+			PREFERRED_CHUNK_SIZE,
+			progress
+		)?;
+		for (i, fat_rlp) in fat_rlps.into_iter().enumerate() {
+			if i > 0 {
+				chunker.write_chunk()?;
+			}
+			chunker.push(fat_rlp)?;
+		}
+	}
+
+	if chunker.cur_size != 0 {
+		chunker.write_chunk()?;
+	}
+
+	Ok(chunker.hashes)
+}
+
 /// Used to rebuild the state trie piece by piece.
 pub struct StateRebuilder {
 	db: Box<dyn JournalDB>,
@@ -556,7 +631,7 @@ pub fn verify_old_block(rng: &mut OsRng, header: &Header, engine: &dyn Engine, c
 	if always || rng.gen::<f32>() <= POW_VERIFY_RATE {
 		engine.verify_block_unordered(header)?;
 		match chain.block_header_data(header.parent_hash()) {
-			Some(parent) => engine.verify_block_family(header, &parent.decode()?).map_err(Into::into),
+			Some(parent) => engine.verify_block_family(header, &parent.decode(engine.params().eip1559_transition)?).map_err(Into::into),
 			None => Ok(()),
 		}
 	} else {
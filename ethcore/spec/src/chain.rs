@@ -170,5 +170,9 @@ mod tests {
 		assert_eq!(frontier.state_root, "d7f8974fb5ac78d9ac099b9ad5018bedc2ce0a72dad1827a1709da30580f0544".parse().unwrap());
 		let genesis = frontier.genesis_block();
 		assert_eq!(view!(BlockView, &genesis).header_view().hash(), "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3".parse().unwrap());
+		assert_eq!(
+			frontier.hard_forks.into_iter().collect::<Vec<_>>(),
+			vec![1_150_000, 1_920_000, 2_463_000, 2_675_000, 4_370_000, 7_280_000, 9_069_000, 9_200_000]
+		);
 	}
 }
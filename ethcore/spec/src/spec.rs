@@ -17,7 +17,7 @@
 //! Parameters for a block chain.
 
 use std::{
-	collections::BTreeMap,
+	collections::{BTreeMap, BTreeSet},
 	convert::TryFrom,
 	fmt,
 	io::Read,
@@ -145,6 +145,7 @@ fn run_constructors<T: Backend>(
 			last_hashes: Default::default(),
 			gas_used: U256::zero(),
 			gas_limit: U256::max_value(),
+			base_fee: None,
 		};
 
 		let from = Address::zero();
@@ -224,6 +225,8 @@ pub struct Spec {
 	pub state_root: H256,
 	/// Genesis state as plain old data.
 	pub genesis_state: PodState,
+	/// Block numbers of the hard forks scheduled after genesis, used to compute the EIP-2124 fork id.
+	pub hard_forks: BTreeSet<BlockNumber>,
 }
 
 /// Part of `Spec`. Describes the hardcoded synchronization parameters.
@@ -265,6 +268,77 @@ fn convert_json_to_spec(
 	Ok((address.into(), builtin))
 }
 
+/// Collects the block numbers of all the transitions scheduled by the chain specification.
+/// Transitions active from genesis and disabled ones are skipped.
+fn hard_forks(params: &CommonParams, engine_spec: &ethjson::spec::Engine) -> BTreeSet<BlockNumber> {
+	let mut forks = vec![
+		params.eip150_transition,
+		params.eip160_transition,
+		params.eip161abc_transition,
+		params.eip161d_transition,
+		params.eip98_transition,
+		params.eip658_transition,
+		params.eip155_transition,
+		params.validate_receipts_transition,
+		params.validate_chain_id_transition,
+		params.eip140_transition,
+		params.eip210_transition,
+		params.eip211_transition,
+		params.eip214_transition,
+		params.eip145_transition,
+		params.eip1052_transition,
+		params.eip1283_transition,
+		params.eip1283_disable_transition,
+		params.eip1283_reenable_transition,
+		params.eip1014_transition,
+		params.eip1706_transition,
+		params.eip1344_transition,
+		params.eip1884_transition,
+		params.eip2028_transition,
+		params.eip2200_advance_transition,
+		params.eip2929_transition,
+		params.eip2930_transition,
+		params.eip1559_transition,
+		params.eip3198_transition,
+		params.eip3529_transition,
+		params.eip3541_transition,
+		params.eip3855_transition,
+		params.eip3860_transition,
+		params.dust_protection_transition,
+		params.wasm_activation_transition,
+		params.kip4_transition,
+		params.kip6_transition,
+		params.max_code_size_transition,
+		params.transaction_permission_contract_transition,
+	];
+
+	if let ethjson::spec::Engine::Ethash(ref ethash) = *engine_spec {
+		let p = &ethash.params;
+		forks.extend(vec![
+			p.homestead_transition,
+			p.block_reward_contract_transition,
+			p.dao_hardfork_transition,
+			p.difficulty_hardfork_transition,
+			p.bomb_defuse_transition,
+			p.eip100b_transition,
+			p.ecip1010_pause_transition,
+			p.ecip1010_continue_transition,
+			p.expip2_transition,
+			p.progpow_transition,
+		].into_iter().filter_map(|t| t.map(Into::into)));
+		if let Some(ethjson::spec::BlockReward::Multi(ref rewards)) = p.block_reward {
+			forks.extend(rewards.keys().map(|&n| n.into()));
+		}
+		if let Some(ref delays) = p.difficulty_bomb_delays {
+			forks.extend(delays.keys().map(|&n| n.into()));
+		}
+	}
+
+	forks.into_iter()
+		.filter(|&n| n != 0 && n != BlockNumber::max_value())
+		.collect()
+}
+
 /// Load from JSON object.
 fn load_from(spec_params: SpecParams, s: ethjson::spec::Spec) -> Result<Spec, Error> {
 	let builtins: Result<BTreeMap<Address, Builtin>, _> = s
@@ -279,6 +353,7 @@ fn load_from(spec_params: SpecParams, s: ethjson::spec::Spec) -> Result<Spec, Er
 	let params = CommonParams::from(s.params);
 
 	let hardcoded_sync = s.hardcoded_sync.map(Into::into);
+	let hard_forks = hard_forks(&params, &s.engine);
 
 	let engine = Spec::engine(spec_params, s.engine, params, builtins);
 	let author = g.author;
@@ -321,6 +396,7 @@ fn load_from(spec_params: SpecParams, s: ethjson::spec::Spec) -> Result<Spec, Er
 		constructors,
 		genesis_state,
 		state_root,
+		hard_forks,
 	};
 
 	Ok(s)
@@ -529,6 +605,7 @@ impl Spec {
 				gas_limit: U256::max_value(),
 				last_hashes: Arc::new(Vec::new()),
 				gas_used: 0.into(),
+				base_fee: genesis.base_fee(),
 			};
 
 			let from = Address::zero();
@@ -47,7 +47,7 @@ use unexpected::{Mismatch, OutOfBounds};
 use vm::LastHashes;
 
 use hash::keccak;
-use rlp::{RlpStream, Encodable, encode_list};
+use rlp::{RlpStream, encode_list};
 use types::{
 	block::PreverifiedBlock,
 	errors::{EthcoreError as Error, BlockError},
@@ -68,6 +68,12 @@ pub struct OpenBlock<'x> {
 	parent: Header,
 }
 
+/// Point an `OpenBlock` can be reverted to, see `OpenBlock::checkpoint`.
+#[must_use]
+pub struct Checkpoint {
+	transactions: usize,
+}
+
 /// Just like `OpenBlock`, except that we've applied `Engine::on_close_block`, finished up the non-seal header fields,
 /// and collected the uncles.
 ///
@@ -185,6 +191,40 @@ impl<'x> OpenBlock<'x> {
 		Ok(self.block.receipts.last().expect("receipt just pushed; qed"))
 	}
 
+	/// Create a checkpoint the block can be reverted to, dropping the transactions pushed after it.
+	///
+	/// Returns `None` if the state is committed after every transaction of this block (before EIP-658),
+	/// since the committed changes can't be reverted.
+	pub fn checkpoint(&mut self) -> Option<Checkpoint> {
+		let params = self.engine.params();
+		let number = self.block.header.number();
+		let intermediate_commits = number < params.eip658_transition
+			&& (number < params.eip98_transition || number < params.validate_receipts_transition);
+		if intermediate_commits {
+			return None;
+		}
+
+		self.block.state.checkpoint();
+		Some(Checkpoint { transactions: self.block.transactions.len() })
+	}
+
+	/// Keep the transactions pushed after the checkpoint.
+	pub fn discard_checkpoint(&mut self, _checkpoint: Checkpoint) {
+		self.block.state.discard_checkpoint();
+	}
+
+	/// Revert the block to the checkpoint, dropping the transactions pushed after it.
+	pub fn revert_to_checkpoint(&mut self, checkpoint: Checkpoint) {
+		self.block.state.revert_to_checkpoint();
+		for t in self.block.transactions.drain(checkpoint.transactions..) {
+			self.block.transactions_set.remove(&t.hash());
+		}
+		self.block.receipts.truncate(checkpoint.transactions);
+		if let Tracing::Enabled(ref mut traces) = self.block.traces {
+			traces.truncate(checkpoint.transactions);
+		}
+	}
+
 	/// Push transactions onto the block.
 	#[cfg(not(feature = "slow-blocks"))]
 	fn push_transactions(&mut self, transactions: Vec<SignedTransaction>) -> Result<(), Error> {
@@ -249,11 +289,11 @@ impl<'x> OpenBlock<'x> {
 		s.engine.on_close_block(&mut s.block, &s.parent)?;
 		s.block.state.commit()?;
 
-		s.block.header.set_transactions_root(ordered_trie_root(s.block.transactions.iter().map(|e| e.rlp_bytes())));
+		s.block.header.set_transactions_root(ordered_trie_root(s.block.transactions.iter().map(|e| e.encode_raw())));
 		let uncle_bytes = encode_list(&s.block.uncles);
 		s.block.header.set_uncles_hash(keccak(&uncle_bytes));
 		s.block.header.set_state_root(s.block.state.root().clone());
-		s.block.header.set_receipts_root(ordered_trie_root(s.block.receipts.iter().map(|r| r.rlp_bytes())));
+		s.block.header.set_receipts_root(ordered_trie_root(s.block.receipts.iter().map(|r| r.encode_raw())));
 		s.block.header.set_log_bloom(s.block.receipts.iter().fold(Bloom::zero(), |mut b, r| {
 			b.accrue_bloom(&r.log_bloom);
 			b
@@ -332,7 +372,7 @@ impl LockedBlock {
 			receipt.outcome = TransactionOutcome::Unknown;
 		}
 		self.block.header.set_receipts_root(
-			ordered_trie_root(self.block.receipts.iter().map(|r| r.rlp_bytes()))
+			ordered_trie_root(self.block.receipts.iter().map(|r| r.encode_raw()))
 		);
 	}
 
@@ -494,7 +534,7 @@ mod tests {
 	use types::{
 		errors::EthcoreError as Error,
 		header::Header,
-		transaction::SignedTransaction,
+		transaction::{SignedTransaction, Transaction},
 		view,
 		views::BlockView,
 		verification::Unverified,
@@ -513,7 +553,7 @@ mod tests {
 		factories: Factories,
 	) -> Result<LockedBlock, Error> {
 
-		let block = Unverified::from_rlp(block_bytes)?;
+		let block = Unverified::from_rlp(block_bytes, engine.params().eip1559_transition)?;
 		let header = block.header;
 		let transactions: Result<Vec<_>, Error> = block
 			.transactions
@@ -564,7 +604,7 @@ mod tests {
 		last_hashes: Arc<LastHashes>,
 		factories: Factories,
 	) -> Result<SealedBlock, Error> {
-		let header = Unverified::from_rlp(block_bytes.clone())?.header;
+		let header = Unverified::from_rlp(block_bytes.clone(), engine.params().eip1559_transition)?.header;
 		Ok(enact_bytes(block_bytes, engine, tracing, db, parent, last_hashes, factories)?
 			.seal(engine, header.seal().to_vec())?)
 	}
@@ -629,7 +669,7 @@ mod tests {
 
 		let bytes = e.rlp_bytes();
 		assert_eq!(bytes, orig_bytes);
-		let uncles = view!(BlockView, &bytes).uncles();
+		let uncles = view!(BlockView, &bytes).uncles(engine.params().eip1559_transition);
 		assert_eq!(uncles[1].extra_data(), b"uncle2");
 
 		let db = e.drain().state.drop().1;
@@ -637,4 +677,44 @@ mod tests {
 		assert!(orig_db.journal_db().keys().iter().filter(|k| orig_db.journal_db().get(k.0, EMPTY_PREFIX)
 			!= db.journal_db().get(k.0, EMPTY_PREFIX)).next() == None);
 	}
+
+	#[test]
+	fn revert_to_checkpoint() {
+		let spec = spec::new_null();
+		let engine = &*spec.engine;
+		let genesis_header = spec.genesis_header();
+		let db = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let last_hashes = Arc::new(vec![genesis_header.hash()]);
+		let mut b = OpenBlock::new(engine, Default::default(), false, db, &genesis_header, last_hashes, Address::zero(), (3141562.into(), 31415620.into()), vec![], false).unwrap();
+		let transaction = |sender| Transaction { gas: 100_000.into(), ..Default::default() }.fake_sign(Address::from_low_u64_be(sender));
+		let (first, second) = (transaction(1), transaction(2));
+
+		b.push_transaction(first.clone(), None).unwrap();
+		let checkpoint = b.checkpoint().unwrap();
+		b.push_transaction(second.clone(), None).unwrap();
+		b.revert_to_checkpoint(checkpoint);
+
+		assert_eq!(b.transactions, vec![first.clone()]);
+		assert_eq!(b.receipts.len(), 1);
+		assert_eq!(b.state.nonce(&first.sender()).unwrap(), 1.into());
+		assert_eq!(b.state.nonce(&second.sender()).unwrap(), 0.into());
+
+		let checkpoint = b.checkpoint().unwrap();
+		b.push_transaction(second.clone(), None).unwrap();
+		b.discard_checkpoint(checkpoint);
+
+		assert_eq!(b.transactions, vec![first, second.clone()]);
+		assert_eq!(b.state.nonce(&second.sender()).unwrap(), 1.into());
+	}
+
+	#[test]
+	fn no_checkpoints_with_intermediate_commits() {
+		let spec = spec::new_test();
+		let genesis_header = spec.genesis_header();
+		let db = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let last_hashes = Arc::new(vec![genesis_header.hash()]);
+		let mut b = OpenBlock::new(&*spec.engine, Default::default(), false, db, &genesis_header, last_hashes, Address::zero(), (3141562.into(), 31415620.into()), vec![], false).unwrap();
+
+		assert!(b.checkpoint().is_none());
+	}
 }
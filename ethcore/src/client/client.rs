@@ -27,15 +27,16 @@ use ansi_term::Colour;
 use bytes::Bytes;
 use bytes::ToPretty;
 use ethereum_types::{Address, H256, H264, U256};
-use hash::keccak;
-use hash_db::EMPTY_PREFIX;
+use hash::{keccak, KECCAK_NULL_RLP};
+use hash_db::{HashDB, EMPTY_PREFIX};
 use itertools::Itertools;
+use keccak_hasher::KeccakHasher;
 use kvdb::{DBTransaction, DBValue, KeyValueDB};
 use parking_lot::{Mutex, RwLock};
 use rand::rngs::OsRng;
 use rlp::PayloadInfo;
 use rustc_hex::FromHex;
-use trie::{Trie, TrieFactory, TrieSpec};
+use trie::{Recorder, Trie, TrieFactory, TrieSpec};
 
 use account_state::State;
 use account_state::state::StateInfo;
@@ -47,6 +48,7 @@ use blockchain::{
 	BlockProvider,
 	BlockReceipts,
 	CacheSize as BlockChainCacheSize,
+	Config as BlockChainConfig,
 	ExtrasInsert,
 	TransactionAddress,
 	TreeRoute
@@ -69,12 +71,15 @@ use client_traits::{
 	ChainInfo,
 	ChainNotify,
 	DatabaseRestore,
+	GenerateFlatState,
 	ImportBlock,
 	ImportExportBlocks,
 	IoClient,
 	Nonce,
 	ProvingBlockChainClient,
+	PruneState,
 	ScheduleInfo,
+	SnapClient,
 	StateClient,
 	StateOrBlock,
 	Tick,
@@ -85,7 +90,7 @@ use client_traits::{
 use db::{keys::BlockDetails, Readable, Writable};
 use engine::Engine;
 use ethcore_miner::pool::VerifiedTransaction;
-use ethtrie::Layout;
+use ethtrie::{range_proof, Layout, TrieDB, TrieDBMut};
 use evm::Schedule;
 use executive_state;
 use io::IoChannel;
@@ -99,11 +104,12 @@ use miner::{Miner, MinerService, PendingOrdering};
 use registrar::RegistrarClient;
 use snapshot::{self, SnapshotClient, SnapshotWriter};
 use spec::Spec;
-use state_db::StateDB;
+use state_db::{FlatStateItem, PruningProgress, StateDB, StatePruner};
 use trace::{self, Database as TraceDatabase, ImportRequest as TraceImportRequest, LocalizedTrace, TraceDB};
 use trie_vm_factories::{Factories, VmFactory};
 use types::{
 	ancestry_action::AncestryAction,
+	basic_account::BasicAccount,
 	block::PreverifiedBlock,
 	block_status::BlockStatus,
 	blockchain_info::BlockChainInfo,
@@ -143,6 +149,8 @@ const MAX_ANCIENT_BLOCKS_QUEUE_SIZE: usize = 4096;
 const MAX_ANCIENT_BLOCKS_TO_IMPORT: usize = 4;
 const MAX_QUEUE_SIZE_TO_SLEEP_ON: usize = 2;
 const MIN_HISTORY_SIZE: u64 = 8;
+// Max number of blocks moved to the freezer at each tick.
+const MAX_FROZEN_BLOCKS_PER_TICK: u64 = 1024;
 
 struct SleepState {
 	last_activity: Option<Instant>,
@@ -205,6 +213,15 @@ pub struct Client {
 	/// Don't prune the state we're currently snapshotting
 	snapshotting_at: AtomicU64,
 
+	/// Don't canonicalize the journal while the unreachable state is being pruned
+	state_pruning: AtomicBool,
+
+	/// Don't prune the state the flat state is being generated at
+	flat_state_generating_at: AtomicU64,
+
+	/// Best block number when the unreachable state was last pruned in the background
+	state_pruned_at: AtomicU64,
+
 	/// Client uses this to store blocks, traces, etc.
 	db: RwLock<Arc<dyn BlockChainDB>>,
 
@@ -271,8 +288,8 @@ impl Importer {
 			block_queue,
 			miner,
 			ancient_verifier: AncientVerifier::new(engine.clone()),
+			bad_blocks: bad_blocks::BadBlocks::new(engine.params().eip1559_transition),
 			engine,
-			bad_blocks: Default::default(),
 		})
 	}
 
@@ -625,6 +642,7 @@ impl Importer {
 							last_hashes: client.build_last_hashes(*header.parent_hash()),
 							gas_used: U256::default(),
 							gas_limit: u64::max_value().into(),
+							base_fee: header.base_fee(),
 						};
 
 						let call = move |addr, data| {
@@ -737,10 +755,17 @@ impl Client {
 			let mut batch = DBTransaction::new();
 			state_db.journal_under(&mut batch, 0, &spec.genesis_header().hash())?;
 			db.key_value().write(batch)?;
+
+			// Start the flat state from the genesis one so that it's kept up to date from now on.
+			state_db.generate_flat_state(&spec.state_root)?;
 		}
 
 		let gb = spec.genesis_block();
-		let chain = Arc::new(BlockChain::new(config.blockchain.clone(), &gb, db.clone()));
+		let blockchain_config = BlockChainConfig {
+			eip1559_transition: spec.params().eip1559_transition,
+			..config.blockchain.clone()
+		};
+		let chain = Arc::new(BlockChain::new(blockchain_config, &gb, db.clone()));
 		let tracedb = RwLock::new(TraceDB::new(config.tracing.clone(), db.clone(), chain.clone()));
 
 		trace!("Cleanup journal: DB Earliest = {:?}, Latest = {:?}", state_db.journal_db().earliest_era(), state_db.journal_db().latest_era());
@@ -758,6 +783,12 @@ impl Client {
 			warn!("State root not found for block #{} ({:x})", chain.best_block_number(), chain.best_block_hash());
 		}
 
+		// resume an interrupted state pruning at the first tick.
+		let state_pruned_at = match PruningProgress::load(&**db.key_value())? {
+			Some(_) => chain.best_block_number().saturating_sub(config.state_pruning_interval),
+			None => chain.best_block_number(),
+		};
+
 		let engine = spec.engine.clone();
 
 		let awake = match config.mode { Mode::Dark(..) | Mode::Off => false, _ => true };
@@ -779,6 +810,9 @@ impl Client {
 			engine,
 			pruning: config.pruning,
 			snapshotting_at: AtomicU64::new(0),
+			state_pruning: AtomicBool::new(false),
+			flat_state_generating_at: AtomicU64::new(0),
+			state_pruned_at: AtomicU64::new(state_pruned_at),
 			db: RwLock::new(db.clone()),
 			state_db: RwLock::new(state_db),
 			report: RwLock::new(Default::default()),
@@ -904,6 +938,7 @@ impl Client {
 				last_hashes: self.build_last_hashes(header.parent_hash()),
 				gas_used: U256::default(),
 				gas_limit: header.gas_limit(),
+				base_fee: header.base_fee(self.engine.params().eip1559_transition),
 			}
 		})
 	}
@@ -974,6 +1009,17 @@ impl Client {
 
 			match state_db.journal_db().earliest_era() {
 				Some(earliest_era) if earliest_era + self.history <= latest_era => {
+					if self.state_pruning.load(Ordering::SeqCst) {
+						trace!(target: "pruning", "Pruning is paused (state pruning under way); earliest era={}, latest era={}, journal_size={} – Not pruning.",
+						       earliest_era, latest_era, state_db.journal_db().journal_size());
+						break;
+					}
+					let generating_at = self.flat_state_generating_at.load(Ordering::SeqCst);
+					if generating_at > 0 && generating_at == earliest_era {
+						trace!(target: "pruning", "Pruning is paused at era {} (flat state generation under way); earliest era={}, latest era={}, journal_size={} – Not pruning.",
+						       generating_at, earliest_era, latest_era, state_db.journal_db().journal_size());
+						break;
+					}
 					let freeze_at = self.snapshotting_at.load(Ordering::SeqCst);
 					if freeze_at > 0 && freeze_at == earliest_era {
 						// Note: journal_db().mem_used() can be used for a more accurate memory
@@ -1115,6 +1161,51 @@ impl Client {
 		self.tracedb.read().collect_garbage();
 	}
 
+	fn check_freezer(&self) {
+		if let Err(e) = self.chain.read().freeze_ancient_blocks(MAX_FROZEN_BLOCKS_PER_TICK) {
+			warn!(target: "client", "Failed to move ancient blocks to the freezer: {}", e);
+		}
+	}
+
+	fn check_flat_state(&self) {
+		if self.flat_state_generating_at.load(Ordering::SeqCst) > 0 || self.chain.read().best_block_number() == 0 {
+			return;
+		}
+		{
+			let state_db = self.state_db.read();
+			if !state_db.is_prunable() || state_db.flat_state_root().is_some() {
+				return;
+			}
+		}
+		if let Err(e) = self.io_channel.read().send(ClientIoMessage::GenerateFlatState) {
+			debug!(target: "flat_state", "Failed to request the flat state generation: {}", e);
+		}
+	}
+
+	fn check_state_pruning(&self) {
+		// pruning an archive database has to hold up block import till it's done.
+		let interval = self.config.state_pruning_interval;
+		if interval == 0
+			|| self.pruning != journaldb::Algorithm::OverlayRecent
+			|| self.state_pruning.load(Ordering::SeqCst)
+			|| self.snapshotting_at.load(Ordering::SeqCst) > 0
+			|| self.flat_state_generating_at.load(Ordering::SeqCst) > 0
+		{
+			return;
+		}
+
+		let best_block_number = self.chain.read().best_block_number();
+		if best_block_number < self.state_pruned_at.load(Ordering::SeqCst) + interval {
+			return;
+		}
+
+		self.state_pruned_at.store(best_block_number, Ordering::SeqCst);
+		let message = ClientIoMessage::PruneState(self.history, self.config.state_pruning_bloom_size);
+		if let Err(e) = self.io_channel.read().send(message) {
+			debug!(target: "pruning", "Failed to request state pruning: {}", e);
+		}
+	}
+
 	fn check_snooze(&self) {
 		let mode = self.mode.lock().clone();
 		match mode {
@@ -1215,7 +1306,7 @@ impl Client {
 			state: &mut State<StateDB>,
 			env_info: &EnvInfo,
 			machine: &::machine::Machine,
-			state_diff: bool,
+			analytics: CallAnalytics,
 			transaction: &SignedTransaction,
 			options: TransactOptions<T, V>,
 		) -> Result<RawExecuted<T::Output, V::Output>, CallError> where
@@ -1225,7 +1316,8 @@ impl Client {
 			let options = options
 				.dont_check_nonce()
 				.save_output_from_contract();
-			let original_state = if state_diff { Some(state.clone()) } else { None };
+			let options = if analytics.access_list_recording { options.record_access_list() } else { options };
+			let original_state = if analytics.state_diffing { Some(state.clone()) } else { None };
 			let schedule = machine.schedule(env_info.number);
 
 			let mut ret = Executive::new(state, env_info, &machine, &schedule).transact_virtual(transaction, options)?;
@@ -1236,13 +1328,11 @@ impl Client {
 			Ok(ret)
 		}
 
-		let state_diff = analytics.state_diffing;
-
 		match (analytics.transaction_tracing, analytics.vm_tracing) {
-			(true, true) => call(state, env_info, machine, state_diff, t, TransactOptions::with_tracing_and_vm_tracing()),
-			(true, false) => call(state, env_info, machine, state_diff, t, TransactOptions::with_tracing()),
-			(false, true) => call(state, env_info, machine, state_diff, t, TransactOptions::with_vm_tracing()),
-			(false, false) => call(state, env_info, machine, state_diff, t, TransactOptions::with_no_tracing()),
+			(true, true) => call(state, env_info, machine, analytics, t, TransactOptions::with_tracing_and_vm_tracing()),
+			(true, false) => call(state, env_info, machine, analytics, t, TransactOptions::with_tracing()),
+			(false, true) => call(state, env_info, machine, analytics, t, TransactOptions::with_vm_tracing()),
+			(false, false) => call(state, env_info, machine, analytics, t, TransactOptions::with_no_tracing()),
 		}
 	}
 
@@ -1267,7 +1357,7 @@ impl Client {
 				=> Some(self.chain.read().best_block_header()),
 			BlockId::Number(number) if number == self.chain.read().best_block_number()
 				=> Some(self.chain.read().best_block_header()),
-			_   => self.block_header(id).and_then(|h| h.decode().ok())
+			_   => self.block_header(id).and_then(|h| h.decode(self.engine.params().eip1559_transition).ok())
 		}
 	}
 }
@@ -1287,12 +1377,129 @@ impl DatabaseRestore for Client {
 
 		let cache_size = state_db.cache_size();
 		*state_db = StateDB::new(journaldb::new(db.key_value().clone(), self.pruning, ::db::COL_STATE), cache_size);
-		*chain = Arc::new(BlockChain::new(self.config.blockchain.clone(), &[], db.clone()));
+		let blockchain_config = BlockChainConfig {
+			eip1559_transition: self.engine.params().eip1559_transition,
+			..self.config.blockchain.clone()
+		};
+		*chain = Arc::new(BlockChain::new(blockchain_config, &[], db.clone()));
 		*tracedb = TraceDB::new(self.config.tracing.clone(), db.clone(), chain.clone());
 		Ok(())
 	}
 }
 
+impl PruneState for Client {
+	fn prune_state(&self, keep: u64, bloom_size: usize) -> Result<(), EthcoreError> {
+		// states of the refcounting algorithms can't be changed behind their back.
+		match self.pruning {
+			journaldb::Algorithm::OverlayRecent | journaldb::Algorithm::Archive => {},
+			algorithm => return Err(EthcoreError::Msg(format!("State pruning is not supported with the {} pruning method", algorithm))),
+		}
+		if keep == 0 {
+			return Err(EthcoreError::Msg("At least one state must be kept".into()));
+		}
+		// the states in the pruning history may still be reverted to.
+		let keep = match self.pruning {
+			journaldb::Algorithm::Archive => keep,
+			_ if keep < self.history => {
+				warn!(target: "pruning", "Keeping the state of the latest {} blocks of the pruning history instead of {}", self.history, keep);
+				self.history
+			},
+			_ => keep,
+		};
+		if self.snapshotting_at.load(Ordering::SeqCst) > 0 {
+			return Err(EthcoreError::Msg("State pruning is not possible while a snapshot is under way".into()));
+		}
+		if self.flat_state_generating_at.load(Ordering::SeqCst) > 0 {
+			return Err(EthcoreError::Msg("State pruning is not possible while the flat state is being generated".into()));
+		}
+		if self.state_pruning.swap(true, Ordering::SeqCst) {
+			return Err(EthcoreError::Msg("State pruning is already under way".into()));
+		}
+		scopeguard::defer! {{
+			trace!(target: "pruning", "Re-enabling journal pruning.");
+			self.state_pruning.store(false, Ordering::SeqCst)
+		}};
+
+		// the archive database writes the new states directly, so block import has to wait.
+		// Otherwise waiting for the import under way is enough for the journal to stay as it is.
+		let import_lock = self.importer.import_lock.lock();
+		let _import_lock = match self.pruning {
+			journaldb::Algorithm::Archive => Some(import_lock),
+			_ => {
+				drop(import_lock);
+				None
+			},
+		};
+
+		let db = self.db.read().key_value().clone();
+		db.flush()?;
+		let state_db = self.state_db.read().boxed_clone();
+		let roots = {
+			let chain = self.chain.read();
+			let best_block_number = chain.best_block_number();
+			let mut first = (best_block_number + 1).saturating_sub(keep);
+			// the oldest journalled state is based on the last canonical one.
+			if let Some(earliest_era) = state_db.journal_db().earliest_era() {
+				first = cmp::min(first, earliest_era.saturating_sub(1));
+			}
+			let mut roots = Vec::with_capacity((best_block_number + 1 - first) as usize);
+			for number in first..=best_block_number {
+				let header = chain.block_hash(number).and_then(|hash| chain.block_header_data(&hash))
+					.ok_or_else(|| EthcoreError::Msg(format!("Missing header of block #{}", number)))?;
+				roots.push(header.state_root());
+			}
+			roots
+		};
+
+		let start_part = match PruningProgress::load(&*db)? {
+			Some(progress) => {
+				info!(target: "pruning", "Resuming state pruning of {} states", progress.keep);
+				progress.parts_done
+			},
+			None => 0,
+		};
+		info!(target: "pruning", "Pruning state unreachable from {} blocks", roots.len());
+		let mut pruner = StatePruner::new(&*db, state_db.as_hash_db(), keep, bloom_size);
+		pruner.mark(&roots)?;
+		let deleted = pruner.sweep(start_part)?;
+		info!(target: "pruning", "State pruning done, {} nodes deleted", deleted);
+		Ok(())
+	}
+
+	fn is_pruning_state(&self) -> bool {
+		self.state_pruning.load(Ordering::SeqCst)
+	}
+}
+
+impl GenerateFlatState for Client {
+	fn generate_flat_state(&self) -> Result<(), EthcoreError> {
+		let state_db = self.state_db.read().boxed_clone();
+		if !state_db.is_prunable() || state_db.flat_state_root().is_some() {
+			return Ok(());
+		}
+		let (number, root) = {
+			let chain = self.chain.read();
+			let number = chain.best_block_number();
+			let header = chain.block_header_data(&chain.best_block_hash())
+				.ok_or_else(|| EthcoreError::Msg(format!("Missing header of block #{}", number)))?;
+			(number, header.state_root())
+		};
+		// the genesis flat state is generated along with the database.
+		if number == 0 || self.flat_state_generating_at.compare_exchange(0, number, Ordering::SeqCst, Ordering::SeqCst).is_err() {
+			return Ok(());
+		}
+		scopeguard::defer! {{
+			trace!(target: "pruning", "Re-enabling pruning.");
+			self.flat_state_generating_at.store(0, Ordering::SeqCst)
+		}};
+
+		info!(target: "flat_state", "Generating the flat state at block #{}", number);
+		state_db.generate_flat_state(&root)?;
+		info!(target: "flat_state", "Flat state generated at block #{}", number);
+		Ok(())
+	}
+}
+
 impl BlockChainReset for Client {
 	fn reset(&self, num: u32) -> Result<(), String> {
 		if num as u64 > self.pruning_history() {
@@ -1509,6 +1716,7 @@ impl Call for Client {
 			last_hashes: self.build_last_hashes(*header.parent_hash()),
 			gas_used: U256::default(),
 			gas_limit: U256::max_value(),
+			base_fee: header.base_fee(),
 		};
 		let machine = self.engine.machine();
 
@@ -1524,6 +1732,7 @@ impl Call for Client {
 			last_hashes: self.build_last_hashes(*header.parent_hash()),
 			gas_used: U256::default(),
 			gas_limit: U256::max_value(),
+			base_fee: header.base_fee(),
 		};
 
 		let mut results = Vec::with_capacity(transactions.len());
@@ -1551,6 +1760,7 @@ impl Call for Client {
 				last_hashes: self.build_last_hashes(*header.parent_hash()),
 				gas_used: U256::default(),
 				gas_limit: max,
+				base_fee: header.base_fee(),
 			};
 
 			(init, max, env_info)
@@ -2132,6 +2342,10 @@ impl BlockChainClient for Client {
 		self.importer.miner.ready_transactions(self, max_len, PendingOrdering::Priority)
 	}
 
+	fn pooled_transaction(&self, hash: &H256) -> Option<Arc<VerifiedTransaction>> {
+		self.importer.miner.transaction(hash)
+	}
+
 	fn signing_chain_id(&self) -> Option<u64> {
 		self.engine.signing_chain_id(&self.latest_env_info())
 	}
@@ -2144,7 +2358,7 @@ impl BlockChainClient for Client {
 	fn uncle_extra_info(&self, id: UncleId) -> Option<BTreeMap<String, String>> {
 		self.uncle(id)
 			.and_then(|h| {
-				h.decode().map(|dh| {
+				h.decode(self.engine.params().eip1559_transition).map(|dh| {
 					self.engine.extra_info(&dh)
 				}).ok()
 			})
@@ -2292,6 +2506,9 @@ impl Tick for Client {
 	// TODO: manage by real events.
 	fn tick(&self, prevent_sleep: bool) {
 		self.check_garbage();
+		self.check_freezer();
+		self.check_state_pruning();
+		self.check_flat_state();
 		if !prevent_sleep {
 			self.check_snooze();
 		}
@@ -2314,7 +2531,7 @@ impl ReopenBlock for Client {
 			for h in uncles {
 				if !block.uncles.iter().any(|header| header.hash() == h) {
 					let uncle = chain.block_header_data(&h).expect("find_uncle_hashes only returns hashes for existing headers; qed");
-					let uncle = uncle.decode().expect("decoding failure");
+					let uncle = uncle.decode(engine.params().eip1559_transition).expect("decoding failure");
 					block.push_uncle(uncle).expect("pushing up to maximum_uncle_count;
 												push_uncle is not ok only if more than maximum_uncle_count is pushed;
 												so all push_uncle are Ok;
@@ -2356,7 +2573,7 @@ impl PrepareOpenBlock for Client {
 			.into_iter()
 			.take(engine.maximum_uncle_count(open_block.header.number()))
 			.foreach(|h| {
-				open_block.push_uncle(h.decode().expect("decoding failure")).expect("pushing maximum_uncle_count;
+				open_block.push_uncle(h.decode(engine.params().eip1559_transition).expect("decoding failure")).expect("pushing maximum_uncle_count;
 												open_block was just created;
 												push_uncle is not ok only if more than maximum_uncle_count is pushed;
 												so all push_uncle are Ok;
@@ -2541,6 +2758,147 @@ impl ProvingBlockChainClient for Client {
 	}
 }
 
+impl SnapClient for Client {
+	fn account_range(&self, root: &H256, origin: &H256, limit: &H256, max_bytes: usize) -> Option<(Vec<(H256, Bytes)>, Vec<Bytes>)> {
+		let state_db = self.state_db.read();
+		let (accounts, proof) = proven_range(state_db.as_hash_db(), root, origin, limit, max_bytes).ok()?;
+		let accounts = accounts.into_iter()
+			.map(|(hash, account)| Ok((hash, ::rlp::decode::<BasicAccount>(&account)?.rlp_slim())))
+			.collect::<Result<_, ::rlp::DecoderError>>()
+			.ok()?;
+		Some((accounts, proof))
+	}
+
+	fn storage_range(&self, root: &H256, account_hash: &H256, origin: &H256, limit: &H256, max_bytes: usize) -> Option<(Vec<(H256, Bytes)>, Vec<Bytes>)> {
+		let state_db = self.state_db.read();
+		let storage_root = account_at(state_db.as_hash_db(), root, account_hash)?.storage_root;
+		let account_db = self.factories.accountdb.readonly(state_db.as_hash_db(), *account_hash);
+		proven_range(&*account_db, &storage_root, origin, limit, max_bytes).ok()
+	}
+
+	fn account_code(&self, account_hash: &H256, code_hash: &H256) -> Option<Bytes> {
+		let state_db = self.state_db.read();
+		let account_db = self.factories.accountdb.readonly(state_db.as_hash_db(), *account_hash);
+		account_db.get(code_hash, EMPTY_PREFIX).map(|code| code.to_vec())
+	}
+
+	fn code_by_hash(&self, code_hash: &H256) -> Option<Bytes> {
+		let state_db = self.state_db.read();
+		state_db.as_hash_db().get(code_hash, EMPTY_PREFIX).map(|code| code.to_vec())
+	}
+
+	fn state_node_at_path(&self, root: &H256, account_hash: Option<&H256>, path: &[u8]) -> Option<Bytes> {
+		let state_db = self.state_db.read();
+		let db = state_db.as_hash_db();
+		let node = match account_hash {
+			None => range_proof::node_at_path(root, path, |hash| db.get(hash, EMPTY_PREFIX).map(|node| node.to_vec())),
+			Some(account_hash) => {
+				let storage_root = account_at(db, root, account_hash)?.storage_root;
+				let account_db = self.factories.accountdb.readonly(db, *account_hash);
+				range_proof::node_at_path(&storage_root, path, |hash| account_db.get(hash, EMPTY_PREFIX).map(|node| node.to_vec()))
+			},
+		};
+		node.ok()?
+	}
+
+	fn has_state_node(&self, account_hash: Option<&H256>, hash: &H256) -> bool {
+		let state_db = self.state_db.read();
+		match account_hash {
+			None => state_db.as_hash_db().contains(hash, EMPTY_PREFIX),
+			Some(account_hash) => self.factories.accountdb.readonly(state_db.as_hash_db(), *account_hash).contains(hash, EMPTY_PREFIX),
+		}
+	}
+
+	fn import_state_entries(&self, account_hash: Option<&H256>, root: &H256, entries: &[(H256, Bytes)]) -> Result<H256, EthcoreError> {
+		let state_db = self.state_db.read();
+		let mut journal_db = state_db.journal_db().boxed_clone();
+		let mut root = *root;
+		{
+			let mut account_db;
+			let db: &mut dyn HashDB<KeccakHasher, DBValue> = match account_hash {
+				Some(account_hash) => {
+					account_db = self.factories.accountdb.create(journal_db.as_hash_db_mut(), *account_hash);
+					&mut *account_db
+				},
+				None => journal_db.as_hash_db_mut(),
+			};
+			let mut trie = if root == KECCAK_NULL_RLP {
+				TrieDBMut::new(db, &mut root)
+			} else {
+				TrieDBMut::from_existing(db, &mut root)?
+			};
+			for (key, value) in entries {
+				trie.insert(key.as_bytes(), value)?;
+			}
+		}
+
+		let mut batch = DBTransaction::new();
+		if account_hash.is_none() {
+			let hashes: Vec<_> = entries.iter().map(|(hash, _)| *hash).collect();
+			state_db.note_account_hashes(&mut batch, &hashes)?;
+		}
+		journal_db.inject(&mut batch)?;
+		self.db.read().key_value().write_buffered(batch);
+		Ok(root)
+	}
+
+	fn import_state_nodes(&self, account_hash: Option<&H256>, nodes: &[Bytes]) -> Result<(), EthcoreError> {
+		let mut journal_db = self.state_db.read().journal_db().boxed_clone();
+		{
+			let mut account_db;
+			let db: &mut dyn HashDB<KeccakHasher, DBValue> = match account_hash {
+				Some(account_hash) => {
+					account_db = self.factories.accountdb.create(journal_db.as_hash_db_mut(), *account_hash);
+					&mut *account_db
+				},
+				None => journal_db.as_hash_db_mut(),
+			};
+			for node in nodes {
+				db.insert(EMPTY_PREFIX, node);
+			}
+		}
+
+		let mut batch = DBTransaction::new();
+		journal_db.inject(&mut batch)?;
+		self.db.read().key_value().write_buffered(batch);
+		Ok(())
+	}
+
+	fn complete_state_sync(&self, unverified: Unverified, receipts_bytes: Bytes, total_difficulty: U256) -> EthcoreResult<()> {
+		let header = unverified.header.clone();
+		if !self.has_state_node(None, header.state_root()) {
+			return Err(EthcoreError::Msg(format!("State of block #{} ({}) is incomplete", header.number(), header.hash())));
+		}
+		let receipts = ::rlp::decode_list(&receipts_bytes);
+		trace!(target: "snap", "Completing state sync at block #{} ({})", header.number(), header.hash());
+
+		let _import_lock = self.importer.import_lock.lock();
+		let mut state_db = self.state_db.write();
+		let mut chain = self.chain.write();
+		let mut tracedb = self.tracedb.write();
+		self.importer.miner.clear();
+		let db = self.db.read();
+
+		let mut batch = DBTransaction::new();
+		state_db.journal_under(&mut batch, header.number(), &header.hash())?;
+		let parent_total_difficulty = total_difficulty.saturating_sub(*header.difficulty());
+		chain.insert_unordered_block(&mut batch, encoded::Block::new(unverified.bytes), receipts, Some(parent_total_difficulty), true, false);
+		db.key_value().write(batch)?;
+		chain.commit();
+
+		// reopen the chain so that the blocks before the synced one are downloaded as ancient blocks
+		let cache_size = state_db.cache_size();
+		*state_db = StateDB::new(journaldb::new(db.key_value().clone(), self.pruning, ::db::COL_STATE), cache_size);
+		let blockchain_config = BlockChainConfig {
+			eip1559_transition: self.engine.params().eip1559_transition,
+			..self.config.blockchain.clone()
+		};
+		*chain = Arc::new(BlockChain::new(blockchain_config, &[], db.clone()));
+		*tracedb = TraceDB::new(self.config.tracing.clone(), db.clone(), chain.clone());
+		Ok(())
+	}
+}
+
 impl SnapshotClient for Client {
 	fn take_snapshot<W: SnapshotWriter + Send>(
 		&self,
@@ -2551,7 +2909,8 @@ impl SnapshotClient for Client {
 		if let Snapshotting::Unsupported = self.engine.snapshot_mode() {
 			return Err(EthcoreError::Snapshot(SnapshotError::SnapshotsUnsupported));
 		}
-		let db = self.state_db.read().journal_db().boxed_clone();
+		let state_db = self.state_db.read().boxed_clone();
+		let db = state_db.journal_db().boxed_clone();
 
 		let block_number = self.block_number(at).ok_or_else(|| SnapshotError::InvalidStartingBlock(at))?;
 		let earliest_era = db.earliest_era().unwrap_or(0);
@@ -2560,13 +2919,23 @@ impl SnapshotClient for Client {
 		}
 
 
+		// The flat state on disk is at the last canonicalized era, right before the earliest one.
+		let flat_block_nr = match state_db.flat_state_root() {
+			Some(flat_root) if earliest_era > 0 => self.block_header(BlockId::Number(earliest_era - 1))
+				.filter(|header| header.state_root() == flat_root)
+				.map(|_| earliest_era - 1),
+			_ => None,
+		};
+
 		let (actual_block_nr, block_hash) = match at {
 			BlockId::Latest => {
 				// Start `self.history` blocks from the best block, but no further back than 1000
-				// blocks (or earliest era, whichever is greatest).
+				// blocks (or earliest era, whichever is greatest). Prefer the block the flat
+				// state is at, which is about as far back and much faster to read.
 				let history = cmp::min(self.history, 1000);
 				let best_block_number = self.chain_info().best_block_number;
-				let start_num = cmp::max(earliest_era, best_block_number.saturating_sub(history));
+				let start_num = flat_block_nr
+					.unwrap_or_else(|| cmp::max(earliest_era, best_block_number.saturating_sub(history)));
 
 				match self.block_hash(BlockId::Number(start_num)) {
 					Some(hash) => (start_num, hash),
@@ -2586,13 +2955,26 @@ impl SnapshotClient for Client {
 		trace!(target: "snapshot", "Snapshot requested at block {:?}. Using block #{}/{:?}. Earliest block: #{}, earliest state era #{}. Using {} threads.",
 			at, actual_block_nr, block_hash, self.pruning_info().earliest_chain, earliest_era, processing_threads,
 		);
-		// Stop pruning from happening while the snapshot is under way.
-		self.snapshotting_at.store(actual_block_nr, Ordering::SeqCst);
+		// Stop pruning from happening while the snapshot is under way. When snapshotting from
+		// the flat state, the era after it must not be canonicalized either.
+		let use_flat_state = flat_block_nr == Some(actual_block_nr);
+		let freeze_at = if use_flat_state { actual_block_nr + 1 } else { actual_block_nr };
+		self.snapshotting_at.store(freeze_at, Ordering::SeqCst);
 		{
 			scopeguard::defer! {{
 				trace!(target: "snapshot", "Re-enabling pruning.");
 				self.snapshotting_at.store(0, Ordering::SeqCst)
 			}};
+			let flat_state = match self.block_header(BlockId::Hash(block_hash)) {
+				Some(ref header) if use_flat_state => {
+					// The flat state, and with it the trie of that block, may have been pruned
+					// before pruning was paused.
+					let items = state_db.export_flat_state(&header.state_root())
+						.ok_or(SnapshotError::OldBlockPrunedDB)?;
+					Some(Box::new(items) as Box<dyn Iterator<Item = FlatStateItem>>)
+				},
+				_ => None,
+			};
 			let chunker = snapshot::chunker(self.engine.snapshot_mode()).ok_or_else(|| SnapshotError::SnapshotsUnsupported)?;
 			// Spawn threads and take snapshot
 			snapshot::take_snapshot(
@@ -2600,6 +2982,7 @@ impl SnapshotClient for Client {
 				&self.chain.read(),
 				block_hash,
 				db.as_hash_db(),
+				flat_state,
 				writer,
 				p,
 				processing_threads,
@@ -2671,7 +3054,7 @@ impl ImportExportBlocks for Client {
 		};
 
 		let do_import = |bytes: Vec<u8>| {
-			let block = Unverified::from_rlp(bytes).map_err(|_| "Invalid block rlp")?;
+			let block = Unverified::from_rlp(bytes, self.engine.params().eip1559_transition).map_err(|_| "Invalid block rlp")?;
 			let number = block.header.number();
 			while self.queue_info().is_full() {
 				std::thread::sleep(Duration::from_secs(1));
@@ -2785,9 +3168,62 @@ fn transaction_receipt(
 		}).collect(),
 		log_bloom: receipt.log_bloom,
 		outcome: receipt.outcome,
+		tx_type: receipt.tx_type,
 	}
 }
 
+/// Looks up an account by its address hash in the state trie with the given root.
+fn account_at(db: &dyn HashDB<KeccakHasher, DBValue>, root: &H256, account_hash: &H256) -> Option<BasicAccount> {
+	let trie = TrieDB::new(&db, root).ok()?;
+	trie.get_with(account_hash.as_bytes(), |account: &[u8]| ::rlp::decode::<BasicAccount>(account))
+		.ok()??
+		.ok()
+}
+
+/// Collects the trie entries from `origin` up to and including the first one at or past `limit`,
+/// or until `max_bytes` are collected, together with the proof of the range.
+/// A range starting at the beginning of the trie which holds all of its entries needs no proof.
+fn proven_range(
+	db: &dyn HashDB<KeccakHasher, DBValue>,
+	root: &H256,
+	origin: &H256,
+	limit: &H256,
+	max_bytes: usize,
+) -> ethtrie::Result<(Vec<(H256, Bytes)>, Vec<Bytes>)> {
+	let trie = TrieDB::new(&db, root)?;
+	let mut iter = trie.iter()?;
+	iter.seek(origin.as_bytes())?;
+
+	let mut entries = Vec::new();
+	let mut size = 0;
+	let mut exhausted = true;
+	for item in iter {
+		let (key, value) = item?;
+		let key = H256::from_slice(&key);
+		size += key.as_bytes().len() + value.len();
+		entries.push((key, value.to_vec()));
+		if key >= *limit || size >= max_bytes {
+			exhausted = false;
+			break;
+		}
+	}
+	if origin.is_zero() && exhausted {
+		return Ok((entries, Vec::new()));
+	}
+
+	let mut recorder = Recorder::new();
+	trie.get_with(origin.as_bytes(), (&mut recorder, |_: &[u8]| ()))?;
+	if let Some((last, _)) = entries.last() {
+		trie.get_with(last.as_bytes(), (&mut recorder, |_: &[u8]| ()))?;
+	}
+	let mut proven = HashSet::new();
+	let proof = recorder.drain().into_iter()
+		.filter(|record| proven.insert(record.hash))
+		.map(|record| record.data)
+		.collect();
+	Ok((entries, proof))
+}
+
 /// Queue some items to be processed by IO client.
 struct IoChannelQueue {
 	/// Using a *signed* integer for counting currently queued messages since the
@@ -2944,6 +3380,7 @@ mod tests {
 			gas_used,
 			log_bloom: Default::default(),
 			logs: logs.clone(),
+			tx_type: Default::default(),
 		};
 
 		// when
@@ -2982,6 +3419,7 @@ mod tests {
 			}],
 			log_bloom: Default::default(),
 			outcome: TransactionOutcome::StateRoot(state_root),
+			tx_type: Default::default(),
 		});
 	}
 
@@ -86,6 +86,10 @@ pub struct ClientConfig {
 	pub history: u64,
 	/// Ideal memory usage for state pruning history.
 	pub history_mem: usize,
+	/// Number of blocks between background prunings of the unreachable state, 0 to disable.
+	pub state_pruning_interval: u64,
+	/// Size in bytes of the bloom filter used to prune the unreachable state.
+	pub state_pruning_bloom_size: usize,
 	/// Check seal validity on block import
 	pub check_seal: bool,
 	/// Maximal number of transactions queued for verification in a separate thread.
@@ -115,6 +119,8 @@ impl Default for ClientConfig {
 			jump_table_size: 1 * mb,
 			history: 64,
 			history_mem: 32 * mb,
+			state_pruning_interval: 0,
+			state_pruning_bloom_size: 256 * mb,
 			check_seal: true,
 			transaction_verification_queue_size: 8192,
 			max_round_blocks_to_import: 12,
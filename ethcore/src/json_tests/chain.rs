@@ -101,7 +101,7 @@ pub fn json_chain_test<H: FnMut(&str, HookType)>(path: &Path, json_data: &[u8],
 
 				for b in blockchain.blocks_rlp() {
 					let bytes_len = b.len();
-					let block = Unverified::from_rlp(b);
+					let block = Unverified::from_rlp(b, spec.params().eip1559_transition);
 					match block {
 						Ok(block) => {
 							let num = block.header.number();
@@ -35,6 +35,7 @@ extern crate hash_db;
 extern crate itertools;
 extern crate journaldb;
 extern crate keccak_hash as hash;
+extern crate keccak_hasher;
 extern crate kvdb;
 extern crate machine;
 extern crate memory_cache;
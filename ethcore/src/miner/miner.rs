@@ -22,6 +22,7 @@ use std::sync::Arc;
 use ansi_term::Colour;
 use bytes::Bytes;
 use call_contract::CallContract;
+use ethcore_miner::bundle::{self, Bundle, BundlePool};
 use ethcore_miner::gas_pricer::GasPricer;
 use ethcore_miner::local_accounts::LocalAccounts;
 use ethcore_miner::pool::{self, TransactionQueue, VerifiedTransaction, QueueStatus, PrioritizationStrategy, TxStatus};
@@ -45,7 +46,7 @@ use types::{
 	io_message::ClientIoMessage,
 	engines::{Seal, SealingState},
 	errors::{EthcoreError as Error, ExecutionError},
-	receipt::RichReceipt,
+	receipt::{RichReceipt, TransactionOutcome},
 	transaction::{
 		self,
 		Action,
@@ -56,7 +57,7 @@ use types::{
 };
 use using_queue::{UsingQueue, GetAction};
 
-use block::{ClosedBlock, SealedBlock};
+use block::{ClosedBlock, OpenBlock, SealedBlock};
 use client::{BlockProducer, SealedBlockImporter, Client};
 use client_traits::{BlockChain, ChainInfo, Nonce, TransactionInfo, EngineClient, ForceUpdateSealing};
 use engine::{Engine, signer::EngineSigner};
@@ -120,6 +121,12 @@ const DEFAULT_MINIMAL_GAS_PRICE: u64 = 20_000_000_000;
 /// in case we have only a fraction of available block gas limit left.
 const MAX_SKIPPED_TRANSACTIONS: usize = 128;
 
+/// Maximal number of bundles waiting for the blocks they target.
+const MAX_BUNDLES: usize = 1024;
+/// Maximal number of bundles targeting a single block.
+/// Each of them is executed twice when the block is prepared.
+const MAX_BUNDLES_PER_BLOCK: usize = 64;
+
 /// Configures the behaviour of the miner.
 #[derive(Debug, PartialEq)]
 pub struct MinerOptions {
@@ -254,6 +261,7 @@ pub struct Miner {
 	options: MinerOptions,
 	// TODO [ToDr] Arc is only required because of price updater
 	transaction_queue: Arc<TransactionQueue>,
+	bundles: BundlePool,
 	engine: Arc<dyn Engine>,
 	accounts: Arc<dyn LocalAccounts>,
 	io_channel: RwLock<Option<IoChannel<ClientIoMessage<Client>>>>,
@@ -312,6 +320,7 @@ impl Miner {
 			nonce_cache: NonceCache::new(nonce_cache_size),
 			options,
 			transaction_queue: Arc::new(TransactionQueue::new(limits, verifier_options, tx_queue_strategy)),
+			bundles: BundlePool::new(MAX_BUNDLES, MAX_BUNDLES_PER_BLOCK),
 			accounts: Arc::new(accounts),
 			engine,
 			io_channel: RwLock::new(None),
@@ -432,6 +441,8 @@ impl Miner {
 		// Open block
 		// Some engines add transactions to the block for their own purposes, e.g. AuthorityRound RANDAO.
 		let (mut open_block, original_work_hash, engine_txs) = {
+			// Bundles have to be at the top of the block, so previous work can't be extended with them.
+			let has_bundles = self.bundles.has_pending(chain_info.best_block_number + 1);
 			let mut sealing = self.sealing.lock();
 			let last_work_hash = sealing.queue.peek_last_ref().map(|pb| pb.header.hash());
 			let best_hash = chain_info.best_block_hash;
@@ -442,7 +453,7 @@ impl Miner {
 			//   if at least one was pushed successfully, close and enqueue new ClosedBlock;
 			//   otherwise, leave everything alone.
 			// otherwise, author a fresh block.
-			match sealing.queue.get_pending_if(|b| b.header.parent_hash() == &best_hash) {
+			match sealing.queue.get_pending_if(|b| b.header.parent_hash() == &best_hash && !has_bundles) {
 				Some(old_block) => {
 					trace!(target: "miner", "prepare_block: Already have previous work; updating and returning");
 					// add transactions to old_block
@@ -523,6 +534,10 @@ impl Miner {
 		};
 
 		let block_start = Instant::now();
+
+		// Bundles go to the very top of the block, before engine and queue transactions.
+		tx_count += self.push_bundles(&mut open_block);
+
 		debug!(target: "miner", "Attempting to push {} transactions.", engine_txs.len() + queue_txs.len());
 
 		for transaction in engine_txs.into_iter().chain(queue_txs.into_iter().map(|tx| tx.signed().clone())) {
@@ -579,6 +594,10 @@ impl Miner {
 				Err(Error::Execution(ExecutionError::InvalidNonce { expected, got })) => {
 					debug!(target: "miner", "Skipping adding transaction to block because of invalid nonce: {:?} (expected: {:?}, got: {:?})", hash, expected, got);
 				},
+				// The base fee may drop in later blocks, so keep the transaction in the queue.
+				Err(Error::Execution(ExecutionError::GasPriceLowerThanBaseFee { gas_price, base_fee })) => {
+					debug!(target: "miner", "Skipping adding transaction to block because of base fee: {:?} (max fee: {:?}, base fee: {:?})", hash, gas_price, base_fee);
+				},
 				// already have transaction - ignore
 				Err(Error::Transaction(transaction::Error::AlreadyImported)) => {},
 				Err(Error::Transaction(transaction::Error::NotAllowed)) => {
@@ -616,6 +635,130 @@ impl Miner {
 		Some((block, original_work_hash))
 	}
 
+	/// Pushes bundles targeting the block, the best paying ones first.
+	///
+	/// Every bundle is simulated on its own against the parent state first and reverted, and the successful ones
+	/// are ordered by their payment to the block author per unit of gas. They are then executed again
+	/// in that order, since bundles included before may invalidate the later ones, and kept only if all
+	/// their transactions can be included. Returns the number of pushed transactions.
+	fn push_bundles(&self, open_block: &mut OpenBlock) -> usize {
+		let bundles = self.bundles.pending(open_block.header.number(), open_block.header.timestamp());
+		if bundles.is_empty() {
+			return 0;
+		}
+
+		let mut scored = Vec::with_capacity(bundles.len());
+		for bundle in bundles {
+			let checkpoint = match open_block.checkpoint() {
+				Some(checkpoint) => checkpoint,
+				None => {
+					debug!(target: "miner", "Skipping bundles: block #{} state can't be reverted before EIP-658.", open_block.header.number());
+					return 0;
+				},
+			};
+			let simulation = Self::try_bundle(open_block, &bundle);
+			open_block.revert_to_checkpoint(checkpoint);
+			if let Some(simulation) = simulation {
+				scored.push((simulation.score(), bundle));
+			}
+		}
+		// stable sort, so bundles paying the same are included in the order they were submitted in.
+		scored.sort_by(|a, b| b.0.cmp(&a.0));
+
+		let mut tx_count = 0;
+		for (_, bundle) in scored {
+			let checkpoint = open_block.checkpoint().expect("Checkpoints were created for this block above; qed");
+			match Self::try_bundle(open_block, &bundle) {
+				Some(simulation) => {
+					trace!(target: "miner", "Pushed bundle {:?} paying {} to the author.", simulation.bundle_hash, simulation.coinbase_diff());
+					tx_count += simulation.transactions.len();
+					open_block.discard_checkpoint(checkpoint);
+				},
+				None => open_block.revert_to_checkpoint(checkpoint),
+			}
+		}
+
+		tx_count
+	}
+
+	/// Executes a bundle on top of given block, returning the outcome if the bundle can be included.
+	///
+	/// The block is left with the transactions executed so far, so it should be reverted to a checkpoint
+	/// unless the bundle is included.
+	fn try_bundle(block: &mut OpenBlock, bundle: &Bundle) -> Option<bundle::Simulation> {
+		match Self::simulate_bundle(block, &bundle.transactions) {
+			Ok(ref simulation) if simulation.has_forbidden_reverts(&bundle.reverting_hashes) => {
+				debug!(target: "miner", "Skipping bundle {:?}: a transaction has reverted.", simulation.bundle_hash);
+				None
+			},
+			Ok(simulation) => Some(simulation),
+			Err(e) => {
+				debug!(target: "miner", "Skipping bundle {:?}: {}", bundle.hash(), e);
+				None
+			},
+		}
+	}
+
+	/// Verifies transactions of a bundle, the same way the transaction queue verifies them.
+	fn verify_bundle_transactions<C: miner::BlockChainClient>(
+		&self,
+		chain: &C,
+		transactions: Vec<UnverifiedTransaction>,
+	) -> Result<Vec<SignedTransaction>, bundle::Error> {
+		let best_block_header = chain.best_block_header();
+		transactions.into_iter()
+			.map(|tx| {
+				let hash = tx.hash();
+				self.engine.verify_transaction_basic(&tx, &best_block_header)
+					.and_then(|_| tx.verify_unordered().map_err(Into::into))
+					.map_err(|e| bundle::Error::Transaction(hash, e.to_string()))
+			})
+			.collect()
+	}
+
+	/// Executes transactions of a bundle on top of given block.
+	///
+	/// Fails on the first transaction which can't be included in the block,
+	/// leaving the block with the transactions executed so far.
+	fn simulate_bundle(block: &mut OpenBlock, transactions: &[SignedTransaction]) -> Result<bundle::Simulation, bundle::Error> {
+		let author = *block.header.author();
+		let base_fee = block.header.base_fee();
+		let author_balance = |block: &OpenBlock, hash: H256| block.state.balance(&author)
+			.map_err(|e| bundle::Error::Transaction(hash, e.to_string()));
+		let gas_used = |block: &OpenBlock| block.receipts.last().map_or_else(U256::zero, |r| r.gas_used);
+
+		let mut simulated = Vec::with_capacity(transactions.len());
+		for transaction in transactions {
+			let hash = transaction.hash();
+			let balance_before = author_balance(&*block, hash)?;
+			let gas_before = gas_used(&*block);
+
+			let outcome = block.push_transaction(transaction.clone(), None)
+				.map(|receipt| receipt.outcome.clone())
+				.map_err(|e| bundle::Error::Transaction(hash, e.to_string()))?;
+
+			let tx_gas_used = gas_used(&*block) - gas_before;
+			simulated.push(bundle::SimulatedTransaction {
+				hash,
+				sender: transaction.sender(),
+				to: match transaction.action {
+					Action::Create => None,
+					Action::Call(ref address) => Some(*address),
+				},
+				gas_used: tx_gas_used,
+				gas_price: transaction.effective_gas_price(base_fee),
+				gas_fees: tx_gas_used * transaction.effective_priority_fee(base_fee),
+				coinbase_diff: author_balance(&*block, hash)?.saturating_sub(balance_before),
+				reverted: outcome == TransactionOutcome::StatusCode(0),
+			});
+		}
+
+		Ok(bundle::Simulation {
+			bundle_hash: bundle::hash(transactions),
+			transactions: simulated,
+		})
+	}
+
 	/// Returns `true` if we should create pending block even if some other conditions are not met.
 	///
 	/// In general we always seal iff:
@@ -702,7 +845,7 @@ impl Miner {
 
 		let parent_header = match chain.block_header(BlockId::Hash(*block.header.parent_hash())) {
 			Some(h) => {
-				match h.decode() {
+				match h.decode(self.engine.params().eip1559_transition) {
 					Ok(decoded_hdr) => decoded_hdr,
 					Err(e) => {
 						error!(target: "miner", "seal_block_internally: Block #{}, Could not decode header from parent block (hash={}): {:?}", block_number, block.header.parent_hash(), e);
@@ -1055,6 +1198,53 @@ impl miner::MinerService for Miner {
 		}
 	}
 
+	fn import_bundle<C: miner::BlockChainClient>(&self, chain: &C, bundle: Bundle<UnverifiedTransaction>) -> Result<H256, bundle::Error> {
+		let best_block = chain.chain_info().best_block_number;
+		// reject bundles which can't be imported before recovering the senders.
+		self.bundles.check(&bundle, best_block)?;
+
+		let for_next_block = bundle.block_number == best_block + 1;
+		let bundle = Bundle {
+			transactions: self.verify_bundle_transactions(chain, bundle.transactions)?,
+			block_number: bundle.block_number,
+			min_timestamp: bundle.min_timestamp,
+			max_timestamp: bundle.max_timestamp,
+			reverting_hashes: bundle.reverting_hashes,
+		};
+		let hash = self.bundles.import(bundle, best_block)?;
+
+		// --------------------------------------------------------------------------
+		// | NOTE Code below requires sealing locks.                                |
+		// | Make sure to release the locks before calling that method.             |
+		// --------------------------------------------------------------------------
+		if for_next_block && self.options.reseal_on_own_tx && self.sealing.lock().reseal_allowed() {
+			self.prepare_and_update_sealing(chain);
+		}
+
+		Ok(hash)
+	}
+
+	fn call_bundle<C: miner::BlockChainClient>(
+		&self,
+		chain: &C,
+		transactions: Vec<UnverifiedTransaction>,
+		timestamp: Option<u64>,
+	) -> Result<bundle::Simulation, bundle::Error> {
+		if transactions.is_empty() {
+			return Err(bundle::Error::Empty);
+		}
+		let transactions = self.verify_bundle_transactions(chain, transactions)?;
+
+		let params = self.params.read().clone();
+		let mut block = chain.prepare_open_block(params.author, params.gas_range_target, params.extra_data)
+			.map_err(|e| bundle::Error::Block(e.to_string()))?;
+		if let Some(timestamp) = timestamp {
+			block.set_timestamp(timestamp);
+		}
+
+		Self::simulate_bundle(&mut block, &transactions)
+	}
+
 	fn local_transactions(&self) -> BTreeMap<H256, pool::local_transactions::Status> {
 		self.transaction_queue.local_transactions()
 	}
@@ -1224,6 +1414,7 @@ impl miner::MinerService for Miner {
 						logs: receipt.logs.clone(),
 						log_bloom: receipt.log_bloom,
 						outcome: receipt.outcome.clone(),
+						tx_type: receipt.tx_type,
 					}
 				})
 				.collect()
@@ -1363,9 +1554,18 @@ impl miner::MinerService for Miner {
 		}
 
 		// First update gas limit in transaction queue and minimal gas price.
-		let gas_limit = *chain.best_block_header().gas_limit();
+		let best_block_header = chain.best_block_header();
+		let gas_limit = *best_block_header.gas_limit();
 		self.update_transaction_queue_limits(gas_limit);
 
+		// Order transactions by the priority fee they would pay in the next block.
+		self.transaction_queue.set_block_base_fee(self.engine.machine().calc_base_fee(&best_block_header));
+
+		// Drop bundles which can't be included anymore.
+		if has_new_best_block {
+			self.bundles.cull(best_block_header.number());
+		}
+
 		// Then import all transactions from retracted blocks.
 		let client = self.pool_client(chain);
 		{
@@ -1486,7 +1686,7 @@ mod tests {
 	use hash::keccak;
 	use rustc_hex::FromHex;
 
-	use client_traits::ChainInfo;
+	use client_traits::{BlockInfo, ChainInfo, ImportBlock};
 	use client::ImportSealedBlock;
 	use miner::{MinerService, PendingOrdering, filter_options::FilterOperator};
 	use test_helpers::{
@@ -1494,7 +1694,8 @@ mod tests {
 	};
 	use types::{
 		BlockNumber,
-		transaction::Transaction
+		transaction::Transaction,
+		verification::Unverified,
 	};
 	use spec;
 
@@ -1599,6 +1800,103 @@ mod tests {
 		assert_eq!(miner.prepare_pending_block(&client), BlockPreparationStatus::NotPrepared);
 	}
 
+	#[test]
+	fn should_include_bundle_at_the_top_of_the_block() {
+		// given
+		// bundles are only included once the block state can be reverted, after EIP-658.
+		let client = TestBlockChainClient::new_with_spec(spec::new_null());
+		let miner = miner();
+		let regular = transaction();
+		let bundled = vec![transaction(), transaction()];
+		miner.import_external_transactions(&client, vec![regular.clone().into()]).pop().unwrap().unwrap();
+		let bundle = |transactions: &[SignedTransaction], block_number| Bundle {
+			transactions: transactions.iter().cloned().map(UnverifiedTransaction::from).collect(),
+			block_number,
+			min_timestamp: None,
+			max_timestamp: None,
+			reverting_hashes: Default::default(),
+		};
+		let invalid = transaction_with_chain_id(TEST_CHAIN_ID + 1);
+
+		// when
+		let outdated = miner.import_bundle(&client, bundle(&bundled, 0));
+		let too_far_ahead = miner.import_bundle(&client, bundle(&bundled, 26));
+		let with_invalid = miner.import_bundle(&client, bundle(&[invalid.clone()], 1));
+		let res = miner.import_bundle(&client, bundle(&bundled, 1));
+
+		// then
+		assert_eq!(outdated, Err(bundle::Error::Outdated { block_number: 0, best_block: 0 }));
+		assert_eq!(too_far_ahead, Err(bundle::Error::TooFarAhead { block_number: 26, best_block: 0 }));
+		assert_eq!(with_invalid, Err(bundle::Error::Transaction(invalid.hash(), transaction::Error::InvalidChainId.to_string())));
+		assert_eq!(res, Ok(bundle::hash(&bundled)));
+		assert_eq!(miner.pending_transactions(0).unwrap(), vec![bundled[0].clone(), bundled[1].clone(), regular]);
+	}
+
+	#[test]
+	fn should_import_child_of_block_with_bundles() {
+		// given
+		let spec = spec::new_instant();
+		let miner = Miner::new_for_tests(&spec, None);
+		let client = generate_dummy_client_with_spec(spec::new_instant);
+		let (first, second, third) = (
+			transaction_with_chain_id(spec.chain_id()),
+			transaction_with_chain_id(spec.chain_id()),
+			transaction_with_chain_id(spec.chain_id()),
+		);
+		let bundle = |transactions: Vec<SignedTransaction>| Bundle {
+			transactions,
+			block_number: 1,
+			min_timestamp: None,
+			max_timestamp: None,
+			reverting_hashes: Default::default(),
+		};
+		miner.bundles.import(bundle(vec![first.clone(), second.clone()]), 0).unwrap();
+		// can't be included after the first bundle, so it has to be reverted.
+		miner.bundles.import(bundle(vec![third, first.clone()]), 0).unwrap();
+
+		// when
+		miner.update_sealing(&*client, ForceUpdateSealing::No);
+		client.flush_queue();
+		assert!(miner.import_own_transaction(
+			&*client,
+			PendingTransaction::new(transaction_with_chain_id(spec.chain_id()).into(), None)
+		).is_ok());
+		miner.update_sealing(&*client, ForceUpdateSealing::No);
+		client.flush_queue();
+
+		// then
+		assert_eq!(client.chain_info().best_block_number, 2);
+		let block = client.block(BlockId::Number(1)).unwrap();
+		assert_eq!(block.transaction_hashes(), vec![first.hash(), second.hash()]);
+
+		// blocks are re-executed and verified on import.
+		let other = generate_dummy_client_with_spec(spec::new_instant);
+		for number in 1..3 {
+			let block = client.block(BlockId::Number(number)).unwrap().into_inner();
+			other.import_block(Unverified::from_rlp(block, spec.params().eip1559_transition).unwrap()).unwrap();
+		}
+		other.flush_queue();
+		assert_eq!(other.chain_info().best_block_hash, client.chain_info().best_block_hash);
+	}
+
+	#[test]
+	fn should_simulate_bundle_without_importing_it() {
+		// given
+		let client = TestBlockChainClient::default();
+		let miner = miner();
+		let transactions = vec![transaction(), transaction()];
+
+		// when
+		let simulation = miner.call_bundle(&client, transactions.iter().cloned().map(UnverifiedTransaction::from).collect(), None).unwrap();
+
+		// then
+		assert_eq!(simulation.bundle_hash, bundle::hash(&transactions));
+		assert_eq!(simulation.transactions.len(), 2);
+		assert!(simulation.transactions.iter().all(|tx| !tx.reverted && !tx.gas_used.is_zero()));
+		assert_eq!(miner.call_bundle(&client, vec![], None), Err(bundle::Error::Empty));
+		assert!(miner.pending_transactions(0).is_none());
+	}
+
 	#[test]
 	fn should_not_return_stale_work_packages() {
 		// given
@@ -29,11 +29,16 @@ pub use self::miner::{Miner, MinerOptions, Penalization, PendingSet, AuthoringPa
 pub use self::filter_options::FilterOptions;
 pub use ethcore_miner::local_accounts::LocalAccounts;
 pub use ethcore_miner::pool::PendingOrdering;
+pub use ethcore_miner::bundle::{
+	Bundle, Simulation as BundleSimulation, SimulatedTransaction as SimulatedBundleTransaction, Error as BundleError,
+	hash as bundle_hash,
+};
 
 use std::sync::Arc;
 use std::collections::{BTreeSet, BTreeMap};
 
 use bytes::Bytes;
+use ethcore_miner::bundle::{self, Bundle};
 use ethcore_miner::pool::{VerifiedTransaction, QueueStatus, local_transactions};
 use ethereum_types::{H256, U256, Address};
 use types::transaction::{self, UnverifiedTransaction, SignedTransaction, PendingTransaction};
@@ -155,6 +160,19 @@ pub trait MinerService : Send + Sync {
 		-> Result<(), transaction::Error>
 		where C: BlockChainClient;
 
+	/// Imports a bundle of transactions to be included atomically at the top of the block it targets.
+	///
+	/// The transactions are verified before the bundle is imported.
+	fn import_bundle<C>(&self, chain: &C, bundle: Bundle<UnverifiedTransaction>) -> Result<H256, bundle::Error>
+		where C: BlockChainClient;
+
+	/// Executes transactions as a bundle at the top of the next block, without submitting them.
+	///
+	/// `timestamp` overrides the timestamp of the block if given.
+	fn call_bundle<C>(&self, chain: &C, transactions: Vec<UnverifiedTransaction>, timestamp: Option<u64>)
+		-> Result<bundle::Simulation, bundle::Error>
+		where C: BlockChainClient;
+
 	/// Removes transaction from the pool.
 	///
 	/// Attempts to "cancel" a transaction. If it was not propagated yet (or not accepted by other peers)
@@ -160,7 +160,7 @@ impl<'a, C: 'a> pool::client::Client for PoolClient<'a, C> where
 		}
 	}
 
-	fn required_gas(&self, tx: &transaction::Transaction) -> U256 {
+	fn required_gas(&self, tx: &transaction::TypedTransaction) -> U256 {
 		tx.gas_required(&self.chain.latest_schedule()).into()
 	}
 
@@ -223,6 +223,7 @@ impl<'a> EvmTestClient<'a> {
 			last_hashes: Arc::new([H256::zero(); 256].to_vec()),
 			gas_used: 0.into(),
 			gas_limit: *genesis.gas_limit(),
+			base_fee: genesis.base_fee(),
 		};
 		self.call_envinfo(params, tracer, vm_tracer, info)
 	}
@@ -302,7 +303,7 @@ impl<'a> EvmTestClient<'a> {
 			Ok(result) => {
 				Ok(TransactSuccess {
 					state_root,
-					gas_left: initial_gas - result.receipt.gas_used,
+					gas_left: initial_gas - (result.receipt.gas_used - env_info.gas_used),
 					outcome: result.receipt.outcome,
 					output: result.output,
 					trace: result.trace,
@@ -19,7 +19,7 @@
 use std::str::FromStr;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering as AtomicOrder};
 use std::sync::Arc;
-use std::collections::{HashMap, BTreeMap};
+use std::collections::{HashMap, BTreeMap, VecDeque};
 use blockchain::BlockProvider;
 use std::mem;
 
@@ -72,7 +72,7 @@ use client::{
 use client_traits::{
 	BlockInfo, Nonce, Balance, ChainInfo, TransactionInfo, BlockChainClient, ImportBlock,
 	AccountData, BlockChain, IoClient, BadBlocks, ScheduleInfo, StateClient, ProvingBlockChainClient,
-	StateOrBlock, ForceUpdateSealing, TransactionRequest
+	SnapClient, StateOrBlock, ForceUpdateSealing, TransactionRequest
 };
 use engine::Engine;
 use machine::executed::Executed;
@@ -107,6 +107,10 @@ pub struct TestBlockChainClient {
 	pub code: RwLock<HashMap<Address, Bytes>>,
 	/// Execution result.
 	pub execution_result: RwLock<Option<Result<Executed, CallError>>>,
+	/// Results of the next calls, returned before the execution result.
+	pub next_execution_results: RwLock<VecDeque<Result<Executed, CallError>>>,
+	/// Transaction of the last call or gas estimation.
+	pub last_call: RwLock<Option<SignedTransaction>>,
 	/// Transaction receipts.
 	pub receipts: RwLock<HashMap<TransactionId, LocalizedReceipt>>,
 	/// Logs
@@ -188,6 +192,8 @@ impl TestBlockChainClient {
 			storage: RwLock::new(HashMap::new()),
 			code: RwLock::new(HashMap::new()),
 			execution_result: RwLock::new(None),
+			next_execution_results: RwLock::new(VecDeque::new()),
+			last_call: RwLock::new(None),
 			receipts: RwLock::new(HashMap::new()),
 			logs: RwLock::new(Vec::new()),
 			queue_size: AtomicUsize::new(0),
@@ -220,6 +226,11 @@ impl TestBlockChainClient {
 		*self.execution_result.write() = Some(result);
 	}
 
+	/// Queue the result of the next call, returned instead of the execution result once.
+	pub fn queue_execution_result(&self, result: Result<Executed, CallError>) {
+		self.next_execution_results.write().push_back(result);
+	}
+
 	/// Set the balance of account `address` to `balance`.
 	pub fn set_balance(&self, address: Address, balance: U256) {
 		self.balances.write().insert(address, balance);
@@ -323,7 +334,7 @@ impl TestBlockChainClient {
 		rlp.append(&header);
 		rlp.append_raw(&txs, 1);
 		rlp.append_raw(uncles.as_raw(), 1);
-		let unverified = Unverified::from_rlp(rlp.out()).unwrap();
+		let unverified = Unverified::from_rlp(rlp.out(), self.spec.params().eip1559_transition).unwrap();
 		self.import_block(unverified).unwrap();
 	}
 
@@ -337,7 +348,7 @@ impl TestBlockChainClient {
 	/// Make a bad block by setting invalid parent hash.
 	pub fn corrupt_block_parent(&self, n: BlockNumber) {
 		let hash = self.block_hash(BlockId::Number(n)).unwrap();
-		let mut header: Header = self.block_header(BlockId::Number(n)).unwrap().decode().expect("decoding failed");
+		let mut header: Header = self.block_header(BlockId::Number(n)).unwrap().decode(self.spec.params().eip1559_transition).expect("decoding failed");
 		header.set_parent_hash(H256::from_low_u64_be(42));
 		let mut rlp = RlpStream::new_list(3);
 		rlp.append(&header);
@@ -514,7 +525,7 @@ impl BlockInfo for TestBlockChainClient {
 	fn best_block_header(&self) -> Header {
 		self.block_header(BlockId::Hash(self.chain_info().best_block_hash))
 			.expect("Best block always has header.")
-			.decode()
+			.decode(self.spec.params().eip1559_transition)
 			.expect("decoding failed")
 	}
 
@@ -568,7 +579,7 @@ impl ImportBlock for TestBlockChainClient {
 		if number > 0 {
 			match self.blocks.read().get(header.parent_hash()) {
 				Some(parent) => {
-					let parent = view!(BlockView, parent).header();
+					let parent = view!(BlockView, parent).header_view();
 					if parent.number() != (header.number() - 1) {
 						panic!("Unexpected block parent");
 					}
@@ -593,7 +604,7 @@ impl ImportBlock for TestBlockChainClient {
 				while n > 0 && self.numbers.read()[&n] != parent_hash {
 					*self.numbers.write().get_mut(&n).unwrap() = parent_hash.clone();
 					n -= 1;
-					parent_hash = view!(BlockView, &self.blocks.read()[&parent_hash]).header().parent_hash().clone();
+					parent_hash = view!(BlockView, &self.blocks.read()[&parent_hash]).header_view().parent_hash();
 				}
 			}
 		}
@@ -612,8 +623,12 @@ impl Call for TestBlockChainClient {
 	// State will not be used by test client anyway, since all methods that accept state are mocked
 	type State = TestState;
 
-	fn call(&self, _t: &SignedTransaction, _analytics: CallAnalytics, _state: &mut Self::State, _header: &Header) -> Result<Executed, CallError> {
-		self.execution_result.read().clone().unwrap()
+	fn call(&self, t: &SignedTransaction, _analytics: CallAnalytics, _state: &mut Self::State, _header: &Header) -> Result<Executed, CallError> {
+		*self.last_call.write() = Some(t.clone());
+		match self.next_execution_results.write().pop_front() {
+			Some(result) => result,
+			None => self.execution_result.read().clone().unwrap(),
+		}
 	}
 
 	fn call_many(&self, txs: &[(SignedTransaction, CallAnalytics)], state: &mut Self::State, header: &Header) -> Result<Vec<Executed>, CallError> {
@@ -624,7 +639,8 @@ impl Call for TestBlockChainClient {
 		Ok(res)
 	}
 
-	fn estimate_gas(&self, _t: &SignedTransaction, _state: &Self::State, _header: &Header) -> Result<U256, CallError> {
+	fn estimate_gas(&self, t: &SignedTransaction, _state: &Self::State, _header: &Header) -> Result<U256, CallError> {
+		*self.last_call.write() = Some(t.clone());
 		Ok(21000.into())
 	}
 }
@@ -655,7 +671,7 @@ impl StateClient for TestBlockChainClient {
 
 impl EngineInfo for TestBlockChainClient {
 	fn engine(&self) -> &dyn Engine {
-		unimplemented!()
+		&*self.spec.engine
 	}
 }
 
@@ -798,7 +814,7 @@ impl BlockChainClient for TestBlockChainClient {
 
 	fn block_extra_info(&self, id: BlockId) -> Option<BTreeMap<String, String>> {
 		self.block(id)
-			.map(|block| block.view().header())
+			.map(|block| block.view().header(self.spec.params().eip1559_transition))
 			.map(|header| self.spec.engine.extra_info(&header))
 	}
 
@@ -892,6 +908,10 @@ impl BlockChainClient for TestBlockChainClient {
 		self.miner.ready_transactions(self, 4096, miner::PendingOrdering::Priority)
 	}
 
+	fn pooled_transaction(&self, hash: &H256) -> Option<Arc<VerifiedTransaction>> {
+		self.miner.transaction(hash)
+	}
+
 	fn signing_chain_id(&self) -> Option<u64> { None }
 
 	fn mode(&self) -> Mode { Mode::Active }
@@ -950,6 +970,44 @@ impl IoClient for TestBlockChainClient {
 	}
 }
 
+impl SnapClient for TestBlockChainClient {
+	fn account_range(&self, _: &H256, _: &H256, _: &H256, _: usize) -> Option<(Vec<(H256, Bytes)>, Vec<Bytes>)> {
+		None
+	}
+
+	fn storage_range(&self, _: &H256, _: &H256, _: &H256, _: &H256, _: usize) -> Option<(Vec<(H256, Bytes)>, Vec<Bytes>)> {
+		None
+	}
+
+	fn account_code(&self, _: &H256, _: &H256) -> Option<Bytes> {
+		None
+	}
+
+	fn code_by_hash(&self, hash: &H256) -> Option<Bytes> {
+		self.code.read().values().find(|code| keccak(code) == *hash).cloned()
+	}
+
+	fn state_node_at_path(&self, _: &H256, _: Option<&H256>, _: &[u8]) -> Option<Bytes> {
+		None
+	}
+
+	fn has_state_node(&self, _: Option<&H256>, _: &H256) -> bool {
+		false
+	}
+
+	fn import_state_entries(&self, _: Option<&H256>, root: &H256, _: &[(H256, Bytes)]) -> Result<H256, Error> {
+		Ok(*root)
+	}
+
+	fn import_state_nodes(&self, _: Option<&H256>, _: &[Bytes]) -> Result<(), Error> {
+		Ok(())
+	}
+
+	fn complete_state_sync(&self, _: Unverified, _: Bytes, _: U256) -> EthcoreResult<()> {
+		Ok(())
+	}
+}
+
 impl ProvingBlockChainClient for TestBlockChainClient {
 	fn prove_storage(&self, _: H256, _: H256, _: BlockId) -> Option<(Vec<Bytes>, H256)> {
 		None
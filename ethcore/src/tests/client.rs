@@ -105,7 +105,7 @@ fn imports_good_block() {
 		IoChannel::disconnected(),
 	).unwrap();
 	let good_block = get_good_dummy_block();
-	if client.import_block(Unverified::from_rlp(good_block).unwrap()).is_err() {
+	if client.import_block(Unverified::from_rlp(good_block, spec.params().eip1559_transition).unwrap()).is_err() {
 		panic!("error importing block being good by definition");
 	}
 	client.flush_queue();
@@ -144,7 +144,7 @@ fn returns_chain_info() {
 	let client = get_test_client_with_blocks(vec![dummy_block.clone()]);
 	let block = view!(BlockView, &dummy_block);
 	let info = client.chain_info();
-	assert_eq!(info.best_block_hash, block.header().hash());
+	assert_eq!(info.best_block_hash, block.header_view().hash());
 }
 
 #[test]
@@ -180,7 +180,7 @@ fn returns_block_body() {
 	let dummy_block = get_good_dummy_block();
 	let client = get_test_client_with_blocks(vec![dummy_block.clone()]);
 	let block = view!(BlockView, &dummy_block);
-	let body = client.block_body(BlockId::Hash(block.header().hash())).unwrap();
+	let body = client.block_body(BlockId::Hash(block.header_view().hash())).unwrap();
 	let body = body.rlp();
 	assert_eq!(body.item_count().unwrap(), 2);
 	assert_eq!(body.at(0).unwrap().as_raw()[..], block.rlp().at(1).as_raw()[..]);
@@ -0,0 +1,462 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Flat account and storage state.
+//!
+//! Keeps every account under its address hash and every storage value under the
+//! concatenation of the address hash and the storage key hash, so that state reads
+//! need a single database lookup instead of a trie walk.
+//!
+//! The data on disk corresponds to a single state root. Blocks journalled on top of it
+//! are kept in memory as diff layers keyed by the state root they produce, which makes
+//! lookups at any recent state (including non-canonical ones) possible. The canonical
+//! layer is written to disk when its era is canonicalized in the journal, and the
+//! layers of retracted blocks are dropped at the same point. Layers are journalled to the
+//! database along with their blocks so that they survive a restart.
+//!
+//! Databases without usable flat data get it generated from the state trie of a recent
+//! block, written in chunks while blocks keep being imported on top of it.
+
+use std::collections::{HashMap, HashSet};
+
+use ethereum_types::{BigEndianHash, H256, U256};
+use hash_db::HashDB;
+use keccak_hash::KECCAK_NULL_RLP;
+use kvdb::{DBTransaction, DBValue, KeyValueDB};
+use log::{debug, trace};
+use parity_bytes::Bytes;
+use rlp::{DecoderError, Rlp, RlpStream};
+use trie_db::Trie;
+
+use account_db::AccountDB;
+use account_state::FlatChanges;
+use common_types::{basic_account::BasicAccount, BlockNumber};
+use ethcore_db::COL_FLAT_STATE;
+use ethtrie::{TrieDB, Result as TrieResult};
+use keccak_hasher::KeccakHasher;
+
+/// Key of the state root the flat data on disk corresponds to.
+const FLAT_STATE_ROOT_KEY: &'static [u8] = b"flat_state_root";
+/// Prefix of the keys of journalled diff layers, followed by the state root of the layer.
+const FLAT_LAYER_PREFIX: &'static [u8] = b"flat_layer";
+/// Number of entries generated before the database transaction is written.
+const GENERATION_BATCH_SIZE: usize = 10_000;
+
+/// An item of the flat state on disk, see `StateDB::export_flat_state`.
+#[derive(Debug, PartialEq)]
+pub enum FlatStateItem {
+	/// An account RLP, by address hash.
+	Account(H256, Bytes),
+	/// A non-zero storage value, by address hash and storage key hash.
+	Storage(H256, H256, H256),
+}
+
+/// Changes made to the flat state on top of some parent state.
+#[derive(Default)]
+pub(crate) struct FlatDiff {
+	/// Changed accounts. `None` indicates the account has been removed.
+	accounts: HashMap<H256, Option<Bytes>>,
+	/// Changed storage values by address hash and storage key hash.
+	storage: HashMap<(H256, H256), H256>,
+	/// Accounts whose storage has been cleared before the changes in `storage`.
+	wiped: HashSet<H256>,
+}
+
+impl FlatDiff {
+	/// Merge the changes of a later commit into this diff.
+	pub fn apply(&mut self, changes: FlatChanges) {
+		for address_hash in changes.wiped {
+			self.storage.retain(|&(ref a, _), _| *a != address_hash);
+			self.wiped.insert(address_hash);
+		}
+		for (address_hash, key, value) in changes.storage {
+			self.storage.insert((address_hash, key), value);
+		}
+		self.accounts.extend(changes.accounts);
+	}
+}
+
+impl rlp::Encodable for FlatDiff {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(3);
+		s.begin_list(self.accounts.len());
+		for (address_hash, account) in &self.accounts {
+			match *account {
+				Some(ref rlp) => s.begin_list(2).append(address_hash).append(rlp),
+				None => s.begin_list(1).append(address_hash),
+			};
+		}
+		s.begin_list(self.storage.len());
+		for (&(ref address_hash, ref key), value) in &self.storage {
+			s.begin_list(3).append(address_hash).append(key).append(value);
+		}
+		s.append_list(&self.wiped.iter().cloned().collect::<Vec<_>>());
+	}
+}
+
+impl rlp::Decodable for FlatDiff {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		let mut accounts = HashMap::new();
+		for account in rlp.at(0)?.iter() {
+			let rlp = match account.item_count()? {
+				1 => None,
+				2 => Some(account.val_at(1)?),
+				_ => return Err(DecoderError::RlpIncorrectListLen),
+			};
+			accounts.insert(account.val_at(0)?, rlp);
+		}
+		let mut storage = HashMap::new();
+		for value in rlp.at(1)?.iter() {
+			storage.insert((value.val_at(0)?, value.val_at(1)?), value.val_at(2)?);
+		}
+		Ok(FlatDiff {
+			accounts,
+			storage,
+			wiped: rlp.list_at::<H256>(2)?.into_iter().collect(),
+		})
+	}
+}
+
+/// Flat changes committed by a `StateDB` since the last time it was journalled.
+pub(crate) struct PendingChanges {
+	/// State root the changes were committed on top of.
+	pub parent: H256,
+	/// State root after the changes.
+	pub root: H256,
+	/// The changes.
+	pub diff: FlatDiff,
+}
+
+/// Changes made by a journalled block.
+struct DiffLayer {
+	/// State root of the parent block.
+	parent: H256,
+	/// Hash of the block.
+	hash: H256,
+	/// Number of the block.
+	number: BlockNumber,
+	/// Changes on top of the parent state.
+	diff: FlatDiff,
+}
+
+impl rlp::Encodable for DiffLayer {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(4).append(&self.parent).append(&self.hash).append(&self.number).append(&self.diff);
+	}
+}
+
+impl rlp::Decodable for DiffLayer {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		Ok(DiffLayer {
+			parent: rlp.val_at(0)?,
+			hash: rlp.val_at(1)?,
+			number: rlp.val_at(2)?,
+			diff: rlp.val_at(3)?,
+		})
+	}
+}
+
+/// Shared flat state.
+pub(crate) struct FlatState {
+	/// State root the data on disk corresponds to. `None` if there is no usable data on disk,
+	/// in which case no diff layers are kept either unless it's being generated.
+	disk_root: Option<H256>,
+	/// State root the data on disk is being generated at. Layers on top of it are kept to
+	/// be written once the generation is done.
+	generating: Option<H256>,
+	/// Diff layers by the state root they produce. The layer producing `disk_root` has already
+	/// been added to the database transaction and is kept until the next canonicalization
+	/// to cover the time before that transaction is written.
+	layers: HashMap<H256, DiffLayer>,
+}
+
+impl FlatState {
+	/// Load the flat state from the database.
+	pub fn load(db: &dyn KeyValueDB) -> Self {
+		let disk_root = db.get(COL_FLAT_STATE, FLAT_STATE_ROOT_KEY)
+			.expect("Low-level database error")
+			.map(|root| H256::from_slice(&root));
+		trace!(target: "flat_state", "Flat state root on disk: {:?}", disk_root);
+
+		let mut layers = HashMap::new();
+		if disk_root.is_some() {
+			for (key, value) in db.iter_from_prefix(COL_FLAT_STATE, FLAT_LAYER_PREFIX) {
+				if !is_layer_key(&key) {
+					continue;
+				}
+				let layer = rlp::decode::<DiffLayer>(&value).expect("diff layers are journalled as valid RLP; qed");
+				layers.insert(H256::from_slice(&key[FLAT_LAYER_PREFIX.len()..]), layer);
+			}
+			debug!(target: "flat_state", "Loaded {} diff layers on top of {:?}", layers.len(), disk_root);
+		}
+
+		FlatState {
+			disk_root,
+			generating: None,
+			layers,
+		}
+	}
+
+	/// State root the data on disk corresponds to.
+	pub fn disk_root(&self) -> Option<H256> {
+		self.disk_root
+	}
+
+	/// Add the changes of a journalled block as a new diff layer, journalling it in `batch`.
+	pub fn add_layer(&mut self, batch: &mut DBTransaction, hash: H256, number: BlockNumber, changes: PendingChanges) {
+		if self.disk_root.or(self.generating).is_none() || changes.parent == changes.root || self.layers.contains_key(&changes.root) {
+			return;
+		}
+		trace!(target: "flat_state", "Adding diff layer {:?} on top of {:?} for block #{} ({:?})", changes.root, changes.parent, number, hash);
+		let layer = DiffLayer {
+			parent: changes.parent,
+			hash,
+			number,
+			diff: changes.diff,
+		};
+		batch.put(COL_FLAT_STATE, &layer_key(&changes.root), &rlp::encode(&layer));
+		self.layers.insert(changes.root, layer);
+	}
+
+	/// Get an account RLP at the given state root. `None` if the root is not covered
+	/// by the flat state.
+	pub fn account(&self, db: &dyn KeyValueDB, root: &H256, address_hash: &H256) -> Option<Option<Bytes>> {
+		self.lookup(root, |diff| diff.accounts.get(address_hash).cloned())
+			.map(|found| found.unwrap_or_else(|| {
+				db.get(COL_FLAT_STATE, address_hash.as_bytes())
+					.expect("Low-level database error")
+					.map(|rlp| rlp.to_vec())
+			}))
+	}
+
+	/// Get a storage value at the given state root. `None` if the root is not covered
+	/// by the flat state.
+	pub fn storage(&self, db: &dyn KeyValueDB, root: &H256, address_hash: &H256, key: &H256) -> Option<H256> {
+		self.lookup(root, |diff| match diff.storage.get(&(*address_hash, *key)) {
+			Some(value) => Some(*value),
+			None if diff.wiped.contains(address_hash) => Some(H256::zero()),
+			None => None,
+		}).map(|found| found.unwrap_or_else(|| {
+			db.get(COL_FLAT_STATE, &storage_key(address_hash, key))
+				.expect("Low-level database error")
+				.map_or_else(H256::zero, |value| H256::from_slice(&value))
+		}))
+	}
+
+	/// Walk the layers from `root` down to the disk. Returns `Some(Some(_))` if found in a
+	/// layer, `Some(None)` if the disk needs to be checked and `None` if `root` is not covered.
+	fn lookup<T, F>(&self, root: &H256, f: F) -> Option<Option<T>>
+		where F: Fn(&FlatDiff) -> Option<T>
+	{
+		let disk_root = self.disk_root?;
+		let mut current = *root;
+		loop {
+			match self.layers.get(&current) {
+				Some(layer) => {
+					if let Some(value) = f(&layer.diff) {
+						return Some(Some(value));
+					}
+					if current == disk_root {
+						return Some(None);
+					}
+					current = layer.parent;
+				},
+				None if current == disk_root => return Some(None),
+				None => return None,
+			}
+		}
+	}
+
+	/// Write the layer of the canonical block `canon_id` to disk and drop all layers
+	/// of blocks up to `end_era`.
+	pub fn mark_canonical(&mut self, db: &dyn KeyValueDB, batch: &mut DBTransaction, end_era: BlockNumber, canon_id: &H256) {
+		let disk_root = match self.disk_root {
+			Some(root) => root,
+			None => return,
+		};
+
+		// the transaction holding the previously flushed layer has been written by now.
+		if self.layers.remove(&disk_root).is_some() {
+			batch.delete(COL_FLAT_STATE, &layer_key(&disk_root));
+		}
+
+		let canon = self.layers.iter()
+			.find(|&(_, layer)| layer.hash == *canon_id)
+			.map(|(root, layer)| (*root, layer.parent));
+
+		match canon {
+			Some((root, parent)) if parent == disk_root => {
+				trace!(target: "flat_state", "Writing diff layer {:?} of block #{} to disk", root, end_era);
+				let layer = &self.layers[&root];
+				write_diff(db, batch, &layer.diff);
+				batch.put(COL_FLAT_STATE, FLAT_STATE_ROOT_KEY, root.as_bytes());
+				self.disk_root = Some(root);
+			},
+			Some((root, parent)) => {
+				// the canonical state was not built on top of the data on disk, e.g. it has
+				// been restored without going through the state. The data on disk can never
+				// catch up with the chain again.
+				debug!(target: "flat_state", "Disabling flat state: canonical state {:?} built on {:?}, flat state at {:?}", root, parent, disk_root);
+				batch.delete(COL_FLAT_STATE, FLAT_STATE_ROOT_KEY);
+				for root in self.layers.keys() {
+					batch.delete(COL_FLAT_STATE, &layer_key(root));
+				}
+				self.disk_root = None;
+				self.layers.clear();
+				return;
+			},
+			// the block didn't change the state.
+			None => {},
+		}
+
+		let disk_root = self.disk_root;
+		self.layers.retain(|root, layer| {
+			let keep = layer.number > end_era || Some(*root) == disk_root;
+			if !keep {
+				batch.delete(COL_FLAT_STATE, &layer_key(root));
+			}
+			keep
+		});
+	}
+
+	/// Start generating the data on disk at `root`. Returns `false` if there is usable data on
+	/// disk already or a generation is under way.
+	pub fn start_generation(&mut self, root: &H256) -> bool {
+		if self.disk_root.is_some() || self.generating.is_some() {
+			return false;
+		}
+		self.generating = Some(*root);
+		self.layers.clear();
+		true
+	}
+
+	/// Replace the data on disk with the state at `root`. The data is written in chunks and
+	/// used once `finish_generation` is called.
+	pub fn generate(db: &dyn KeyValueDB, state: &dyn HashDB<KeccakHasher, DBValue>, root: &H256) -> TrieResult<()> {
+		let mut batch = DBTransaction::new();
+		let mut pending = 0usize;
+		let mut flush = |batch: &mut DBTransaction, force: bool| {
+			pending += 1;
+			if force || pending >= GENERATION_BATCH_SIZE {
+				db.write(::std::mem::replace(batch, DBTransaction::new())).expect("Low-level database error");
+				pending = 0;
+			}
+		};
+
+		// stale data of a previous flat state, layers journalled meanwhile are kept.
+		for (key, _) in db.iter(COL_FLAT_STATE) {
+			if !is_layer_key(&key) {
+				batch.delete(COL_FLAT_STATE, &key);
+				flush(&mut batch, false);
+			}
+		}
+
+		let mut accounts = 0usize;
+		let account_trie = TrieDB::new(&state, root)?;
+		for item in account_trie.iter()? {
+			let (address_hash, account_rlp) = item?;
+			let address_hash = H256::from_slice(&address_hash);
+			let account: BasicAccount = rlp::decode(&account_rlp).expect("state trie holds valid accounts; qed");
+
+			if account.storage_root != KECCAK_NULL_RLP {
+				let account_db = AccountDB::from_hash(state, address_hash);
+				let account_db = &(&account_db as &dyn HashDB<_, _>);
+				let storage_trie = TrieDB::new(account_db, &account.storage_root)?;
+				for item in storage_trie.iter()? {
+					let (key, value) = item?;
+					let value: U256 = rlp::decode(&value).expect("storage trie holds valid values; qed");
+					batch.put(COL_FLAT_STATE, &storage_key(&address_hash, &H256::from_slice(&key)), H256::from_uint(&value).as_bytes());
+					flush(&mut batch, false);
+				}
+			}
+
+			batch.put(COL_FLAT_STATE, address_hash.as_bytes(), &account_rlp);
+			flush(&mut batch, false);
+			accounts += 1;
+		}
+		flush(&mut batch, true);
+
+		debug!(target: "flat_state", "Generated flat state of {} accounts at {:?}", accounts, root);
+		Ok(())
+	}
+
+	/// Use the generated data on disk, or drop the layers kept for it if the generation failed.
+	pub fn finish_generation(&mut self, db: &dyn KeyValueDB, root: &H256, generated: bool) {
+		if self.generating != Some(*root) {
+			return;
+		}
+		self.generating = None;
+		if generated {
+			let mut batch = DBTransaction::new();
+			batch.put(COL_FLAT_STATE, FLAT_STATE_ROOT_KEY, root.as_bytes());
+			db.write(batch).expect("Low-level database error");
+			self.disk_root = Some(*root);
+		} else {
+			self.layers.clear();
+		}
+	}
+
+	/// Iterate over the data on disk.
+	pub fn iter<'a>(db: &'a dyn KeyValueDB) -> impl Iterator<Item = FlatStateItem> + 'a {
+		db.iter(COL_FLAT_STATE).filter_map(|(key, value)| match key.len() {
+			32 => Some(FlatStateItem::Account(H256::from_slice(&key), value.to_vec())),
+			64 => Some(FlatStateItem::Storage(H256::from_slice(&key[..32]), H256::from_slice(&key[32..]), H256::from_slice(&value))),
+			_ => None,
+		})
+	}
+}
+
+/// Add the changes of a diff to the database transaction.
+fn write_diff(db: &dyn KeyValueDB, batch: &mut DBTransaction, diff: &FlatDiff) {
+	for address_hash in &diff.wiped {
+		for (key, _) in db.iter_from_prefix(COL_FLAT_STATE, address_hash.as_bytes()) {
+			if key.len() == 64 {
+				batch.delete(COL_FLAT_STATE, &key);
+			}
+		}
+	}
+	for (&(ref address_hash, ref key), value) in &diff.storage {
+		match value.is_zero() {
+			true => batch.delete(COL_FLAT_STATE, &storage_key(address_hash, key)),
+			false => batch.put(COL_FLAT_STATE, &storage_key(address_hash, key), value.as_bytes()),
+		}
+	}
+	for (address_hash, account) in &diff.accounts {
+		match *account {
+			Some(ref rlp) => batch.put(COL_FLAT_STATE, address_hash.as_bytes(), rlp),
+			None => batch.delete(COL_FLAT_STATE, address_hash.as_bytes()),
+		}
+	}
+}
+
+/// Database key of a journalled diff layer.
+fn layer_key(root: &H256) -> Vec<u8> {
+	let mut key = FLAT_LAYER_PREFIX.to_vec();
+	key.extend_from_slice(root.as_bytes());
+	key
+}
+
+fn is_layer_key(key: &[u8]) -> bool {
+	key.len() == FLAT_LAYER_PREFIX.len() + 32 && key.starts_with(FLAT_LAYER_PREFIX)
+}
+
+/// Database key of a storage value.
+fn storage_key(address_hash: &H256, key: &H256) -> [u8; 64] {
+	let mut storage_key = [0u8; 64];
+	storage_key[..32].copy_from_slice(address_hash.as_bytes());
+	storage_key[32..].copy_from_slice(key.as_bytes());
+	storage_key
+}
@@ -26,16 +26,25 @@ use keccak_hash::keccak;
 use kvdb::{DBTransaction, DBValue, KeyValueDB};
 use log::trace;
 use lru_cache::LruCache;
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
 
-use account_state::{self, Account};
+use account_state::{self, Account, FlatChanges};
 use bloom_journal::{Bloom, BloomJournal};
 use common_types::BlockNumber;
 use ethcore_db::COL_ACCOUNT_BLOOM;
+use ethtrie::Result as TrieResult;
 use journaldb::JournalDB;
 use keccak_hasher::KeccakHasher;
 use memory_cache::MemoryLruCache;
 
+mod flat;
+mod pruner;
+
+use self::flat::{FlatDiff, FlatState, PendingChanges};
+
+pub use self::flat::FlatStateItem;
+pub use self::pruner::{PruningProgress, StatePruner};
+
 /// Value used to initialize bloom bitmap size.
 ///
 /// Bitmap size is the size in bytes (not bits) that will be allocated in memory.
@@ -105,6 +114,11 @@ struct BlockChanges {
 /// using `add_to_account_cache` function.
 /// Then, after the block has been added to the chain the local cache in the
 /// `StateDB` is propagated into the global cache.
+///
+/// Flat state.
+/// Committed account and storage changes are reported through
+/// `note_flat_changes` and become a diff layer of the flat state
+/// when the block is journalled. See the `flat` module for details.
 pub struct StateDB {
 	/// Backing database.
 	db: Box<dyn JournalDB>,
@@ -124,6 +138,10 @@ pub struct StateDB {
 	commit_hash: Option<H256>,
 	/// Number of the committing block or `None` if not committed yet.
 	commit_number: Option<BlockNumber>,
+	/// Shared flat state.
+	flat_state: Arc<RwLock<FlatState>>,
+	/// Flat state changes committed since the last `journal_under`.
+	flat_changes: Option<PendingChanges>,
 }
 
 impl Clone for StateDB {
@@ -139,6 +157,7 @@ impl StateDB {
 	// into the `AccountCache` structure as its own `LruCache<(Address, H256), H256>`.
 	pub fn new(db: Box<dyn JournalDB>, cache_size: usize) -> StateDB {
 		let bloom = Self::load_bloom(&**db.backing());
+		let flat_state = FlatState::load(&**db.backing());
 		let acc_cache_size = cache_size * ACCOUNT_CACHE_RATIO / 100;
 		let code_cache_size = cache_size - acc_cache_size;
 		let cache_items = acc_cache_size / ::std::mem::size_of::<Option<Account>>();
@@ -156,6 +175,8 @@ impl StateDB {
 			parent_hash: None,
 			commit_hash: None,
 			commit_number: None,
+			flat_state: Arc::new(RwLock::new(flat_state)),
+			flat_changes: None,
 		}
 	}
 
@@ -204,6 +225,16 @@ impl StateDB {
 		Ok(())
 	}
 
+	/// Note accounts written to the database without going through the state, by their
+	/// address hashes, and add the updated parts of the account bloom to the batch.
+	pub fn note_account_hashes(&self, batch: &mut DBTransaction, hashes: &[H256]) -> io::Result<()> {
+		let mut bloom_lock = self.account_bloom.lock();
+		for hash in hashes {
+			bloom_lock.set(hash.as_bytes());
+		}
+		Self::commit_bloom(batch, bloom_lock.drain_journal())
+	}
+
 	/// Journal all recent operations under the given era and ID.
 	pub fn journal_under(&mut self, batch: &mut DBTransaction, now: u64, id: &H256) -> io::Result<u32> {
 		{
@@ -211,6 +242,9 @@ impl StateDB {
 			Self::commit_bloom(batch, bloom_lock.drain_journal())?;
 		}
 		let records = self.db.journal_under(batch, now, id)?;
+		if let Some(changes) = self.flat_changes.take() {
+			self.flat_state.write().add_layer(batch, *id, now, changes);
+		}
 		self.commit_hash = Some(id.clone());
 		self.commit_number = Some(now);
 		Ok(records)
@@ -219,7 +253,40 @@ impl StateDB {
 	/// Mark a given candidate from an ancient era as canonical, enacting its removals from the
 	/// backing database and reverting any non-canonical historical commit's insertions.
 	pub fn mark_canonical(&mut self, batch: &mut DBTransaction, end_era: u64, canon_id: &H256) -> io::Result<u32> {
-		self.db.mark_canonical(batch, end_era, canon_id)
+		let records = self.db.mark_canonical(batch, end_era, canon_id)?;
+		self.flat_state.write().mark_canonical(&**self.db.backing(), batch, end_era, canon_id);
+		Ok(records)
+	}
+
+	/// Build the flat state from the state trie at `root`, replacing any flat data on disk.
+	/// The state is written to the database directly and blocks journalled on top of `root`
+	/// in the meantime are kept, so the state at `root` must not be pruned until this returns.
+	/// Does nothing if there is a flat state already or a generation under way. The flat
+	/// state is only maintained on databases which are pruned, so this does nothing on
+	/// archive databases either.
+	pub fn generate_flat_state(&self, root: &H256) -> TrieResult<()> {
+		if !self.db.is_prunable() || !self.flat_state.write().start_generation(root) {
+			return Ok(());
+		}
+		let backing = &**self.db.backing();
+		let result = FlatState::generate(backing, self.db.as_hash_db(), root);
+		self.flat_state.write().finish_generation(backing, root, result.is_ok());
+		result
+	}
+
+	/// State root the flat state on disk corresponds to or `None` if there is no flat state.
+	pub fn flat_state_root(&self) -> Option<H256> {
+		self.flat_state.read().disk_root()
+	}
+
+	/// Iterate over the flat state at `root`, ordered by address hash, with every account
+	/// followed by its non-zero storage values. Returns `None` unless `root` is the state root
+	/// of the flat data on disk, which can be kept there by pausing pruning.
+	pub fn export_flat_state<'a>(&'a self, root: &H256) -> Option<impl Iterator<Item = FlatStateItem> + 'a> {
+		match self.flat_state_root() {
+			Some(ref disk_root) if disk_root == root => Some(FlatState::iter(&**self.db.backing())),
+			_ => None,
+		}
 	}
 
 	/// Propagate local cache into the global cache and synchonize
@@ -341,6 +408,8 @@ impl StateDB {
 			parent_hash: None,
 			commit_hash: None,
 			commit_number: None,
+			flat_state: self.flat_state.clone(),
+			flat_changes: None,
 		}
 	}
 
@@ -356,6 +425,8 @@ impl StateDB {
 			parent_hash: Some(parent.clone()),
 			commit_hash: None,
 			commit_number: None,
+			flat_state: self.flat_state.clone(),
+			flat_changes: None,
 		}
 	}
 
@@ -474,6 +545,32 @@ impl account_state::Backend for StateDB {
 		let is_null = !bloom.check(keccak(address).as_bytes());
 		is_null
 	}
+
+	fn get_flat_account(&self, root: &H256, address_hash: &H256) -> Option<Option<Account>> {
+		self.flat_state.read().account(&**self.db.backing(), root, address_hash)
+			.map(|account| account.map(|rlp| Account::from_rlp(&rlp).expect("flat state holds valid accounts; qed")))
+	}
+
+	fn get_flat_storage(&self, root: &H256, address_hash: &H256, key: &H256) -> Option<H256> {
+		self.flat_state.read().storage(&**self.db.backing(), root, address_hash, key)
+	}
+
+	fn note_flat_changes(&mut self, parent: &H256, root: &H256, changes: FlatChanges) {
+		if self.flat_state.read().disk_root().is_none() {
+			return;
+		}
+		match self.flat_changes {
+			Some(ref mut pending) if pending.root == *parent => {
+				pending.diff.apply(changes);
+				pending.root = *root;
+			},
+			_ => {
+				let mut diff = FlatDiff::default();
+				diff.apply(changes);
+				self.flat_changes = Some(PendingChanges { parent: *parent, root: *root, diff });
+			},
+		}
+	}
 }
 
 /// Sync wrapper for the account.
@@ -486,10 +583,26 @@ unsafe impl Sync for SyncAccount {}
 #[cfg(test)]
 mod tests {
 	use ethereum_types::{Address, H256, U256};
+	use keccak_hash::keccak;
 	use kvdb::DBTransaction;
 
-	use account_state::{Account, Backend};
-	use ethcore::test_helpers::get_temp_state_db;
+	use account_state::{Account, Backend, CleanupMode, State};
+	use ethcore::test_helpers::{get_temp_state, get_temp_state_db};
+
+	use super::{FlatStateItem, StateDB};
+
+	fn import_block(db: &StateDB, parent: (&H256, H256), number: u64, hash: &H256, address: &Address, balance: u64, value: u64) -> H256 {
+		let (parent_hash, parent_root) = parent;
+		let mut state = State::from_existing(db.boxed_clone_canon(parent_hash), parent_root, U256::zero(), Default::default()).unwrap();
+		state.add_balance(address, &U256::from(balance), CleanupMode::NoEmpty).unwrap();
+		state.set_storage(address, H256::from_low_u64_be(1), H256::from_low_u64_be(value)).unwrap();
+		state.commit().unwrap();
+		let (root, mut db) = state.drop();
+		let mut batch = DBTransaction::new();
+		db.journal_under(&mut batch, number, hash).unwrap();
+		db.journal_db().backing().write(batch).unwrap();
+		root
+	}
 
 	#[test]
 	fn state_db_smoke() {
@@ -557,4 +670,75 @@ mod tests {
 		let s = state_db.boxed_clone_canon(&h3a);
 		assert!(s.get_cached_account(&address).is_none());
 	}
+
+	#[test]
+	fn flat_state_follows_diff_layers() {
+		let address = Address::from_low_u64_be(10);
+		let address_hash = keccak(&address);
+		let key_hash = keccak(H256::from_low_u64_be(1));
+		let h0 = H256::random();
+		let h1a = H256::random();
+		let h1b = H256::random();
+
+		let mut state = get_temp_state();
+		state.add_balance(&address, &U256::from(100), CleanupMode::NoEmpty).unwrap();
+		state.set_storage(&address, H256::from_low_u64_be(1), H256::from_low_u64_be(1)).unwrap();
+		state.commit().unwrap();
+		let (root0, mut state_db) = state.drop();
+		let mut batch = DBTransaction::new();
+		state_db.journal_under(&mut batch, 0, &h0).unwrap();
+		state_db.journal_db().backing().write(batch).unwrap();
+
+		assert!(state_db.get_flat_account(&root0, &address_hash).is_none());
+		state_db.generate_flat_state(&root0).unwrap();
+		assert_eq!(state_db.flat_state_root(), Some(root0));
+
+		// two competing blocks on top of the generated state
+		let root1a = import_block(&state_db, (&h0, root0), 1, &h1a, &address, 10, 2);
+		let root1b = import_block(&state_db, (&h0, root0), 1, &h1b, &address, 20, 0);
+
+		let balance = |root: &H256| state_db.get_flat_account(root, &address_hash).map(|a| *a.unwrap().balance());
+		assert_eq!(balance(&root0), Some(100.into()));
+		assert_eq!(balance(&root1a), Some(110.into()));
+		assert_eq!(balance(&root1b), Some(120.into()));
+		assert_eq!(balance(&H256::random()), None);
+		assert_eq!(state_db.get_flat_storage(&root0, &address_hash, &key_hash), Some(H256::from_low_u64_be(1)));
+		assert_eq!(state_db.get_flat_storage(&root1a, &address_hash, &key_hash), Some(H256::from_low_u64_be(2)));
+		assert_eq!(state_db.get_flat_storage(&root1b, &address_hash, &key_hash), Some(H256::zero()));
+
+		// the layers are journalled and survive a restart
+		let backing = state_db.journal_db().backing().clone();
+		let reopened = StateDB::new(journaldb::new(backing, journaldb::Algorithm::EarlyMerge, ethcore_db::COL_STATE), 1024 * 1024);
+		assert_eq!(reopened.flat_state_root(), Some(root0));
+		assert_eq!(reopened.get_flat_account(&root1a, &address_hash).map(|a| *a.unwrap().balance()), Some(110.into()));
+		assert_eq!(reopened.get_flat_storage(&root1b, &address_hash, &key_hash), Some(H256::zero()));
+
+		// the state reads through the flat layers
+		let state = State::from_existing(state_db.boxed_clone(), root1a, U256::zero(), Default::default()).unwrap();
+		assert_eq!(state.balance(&address).unwrap(), 110.into());
+		assert_eq!(state.storage_at(&address, &H256::from_low_u64_be(1)).unwrap(), H256::from_low_u64_be(2));
+
+		// 1b becomes canonical and is written to disk
+		let mut state_db = state_db.boxed_clone();
+		for &(era, ref hash) in &[(0, h0), (1, h1b)] {
+			let mut batch = DBTransaction::new();
+			state_db.mark_canonical(&mut batch, era, hash).unwrap();
+			state_db.journal_db().backing().write(batch).unwrap();
+		}
+		assert_eq!(state_db.flat_state_root(), Some(root1b));
+		assert_eq!(balance(&root1b), Some(120.into()));
+		assert_eq!(balance(&root1a), None);
+		assert_eq!(balance(&root0), None);
+
+		let exported: Vec<_> = state_db.export_flat_state(&root1b).unwrap().collect();
+		assert_eq!(exported.len(), 1);
+		match exported[0] {
+			FlatStateItem::Account(ref hash, ref rlp) => {
+				assert_eq!(hash, &address_hash);
+				assert_eq!(Account::from_rlp(rlp).unwrap().balance(), &U256::from(120));
+			},
+			ref item => panic!("unexpected item {:?}", item),
+		}
+		assert!(state_db.export_flat_state(&root0).is_none());
+	}
 }
@@ -0,0 +1,338 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Removal of the state trie nodes which are not reachable from a set of kept states.
+//!
+//! Reachable nodes are marked in a bloom filter by walking the tries of the kept states,
+//! the oldest one in full and every later one only where it differs from the one before.
+//! Every node of the state column which is not in the filter is then deleted. False
+//! positives of the filter only leave some garbage behind.
+//!
+//! The state column is swept in 256 parts, by the first byte of the keys, and the number
+//! of parts done is stored in the database. An interrupted pruning resumes with the next
+//! part; the marking is always redone since the kept states may have changed meanwhile.
+//!
+//! Nodes written to the state column while pruning are not marked, so the caller must
+//! make sure nothing is written there (e.g. by pausing the journal canonicalization)
+//! from the time the kept states are chosen until the sweep is done.
+
+use std::collections::HashMap;
+use std::io;
+
+use ethereum_types::H256;
+use hash_db::{HashDB, EMPTY_PREFIX};
+use keccak_hash::{KECCAK_EMPTY, KECCAK_NULL_RLP};
+use kvdb::{DBTransaction, DBValue, KeyValueDB};
+use log::{debug, info};
+use rlp::{Rlp, RlpStream};
+
+use account_db::combine_key;
+use bloom_journal::Bloom;
+use common_types::basic_account::BasicAccount;
+use ethcore_db::{COL_NODE_INFO, COL_STATE};
+use ethtrie::{Result as TrieResult, TrieError};
+use ethtrie::range_proof::{from_nibbles, node_references, NodeReference};
+use keccak_hasher::KeccakHasher;
+
+/// Key of the pruning progress.
+const PRUNING_PROGRESS_KEY: &'static [u8] = b"state_pruning";
+/// Number of parts the state column is swept in.
+const SWEEP_PARTS: usize = 256;
+/// Number of deletions written to the database at once.
+const SWEEP_BATCH_SIZE: usize = 16 * 1024;
+/// Number of marked nodes between progress reports.
+const MARK_REPORT_INTERVAL: u64 = 1_000_000;
+
+/// Progress of an interrupted pruning, stored in the database.
+#[derive(Debug, Default, PartialEq)]
+pub struct PruningProgress {
+	/// Number of states kept.
+	pub keep: u64,
+	/// Number of parts of the state column already swept.
+	pub parts_done: usize,
+}
+
+impl PruningProgress {
+	/// Load the progress of an interrupted pruning, if any.
+	pub fn load(db: &dyn KeyValueDB) -> io::Result<Option<PruningProgress>> {
+		let progress = match db.get(COL_NODE_INFO, PRUNING_PROGRESS_KEY)? {
+			Some(progress) => progress,
+			None => return Ok(None),
+		};
+		let rlp = Rlp::new(&progress);
+		let decode = || -> Result<PruningProgress, rlp::DecoderError> {
+			Ok(PruningProgress { keep: rlp.val_at(0)?, parts_done: rlp.val_at(1)? })
+		};
+		decode().map(Some).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}", e)))
+	}
+
+	fn save(&self, batch: &mut DBTransaction) {
+		let mut stream = RlpStream::new_list(2);
+		stream.append(&self.keep).append(&self.parts_done);
+		batch.put(COL_NODE_INFO, PRUNING_PROGRESS_KEY, &stream.out());
+	}
+}
+
+/// Removes the state trie nodes not reachable from the kept states.
+pub struct StatePruner<'a> {
+	/// The raw database.
+	db: &'a dyn KeyValueDB,
+	/// The state, read through the journal.
+	state: &'a dyn HashDB<KeccakHasher, DBValue>,
+	/// Keys of the reachable nodes.
+	bloom: Bloom,
+	/// Number of nodes marked.
+	marked: u64,
+	/// Number of states kept, stored with the progress.
+	keep: u64,
+}
+
+impl<'a> StatePruner<'a> {
+	/// Create a new pruner keeping `keep` states, with a bloom filter of `bloom_size` bytes.
+	pub fn new(db: &'a dyn KeyValueDB, state: &'a dyn HashDB<KeccakHasher, DBValue>, keep: u64, bloom_size: usize) -> Self {
+		StatePruner {
+			db,
+			state,
+			// roughly 8 bits per node, i.e. a few percent of false positives when full.
+			bloom: Bloom::new(bloom_size, bloom_size),
+			marked: 0,
+			keep,
+		}
+	}
+
+	/// Mark the nodes reachable from the given state roots, ordered from the oldest state.
+	pub fn mark(&mut self, roots: &[H256]) -> TrieResult<()> {
+		let mut previous = None;
+		for (i, root) in roots.iter().enumerate() {
+			self.mark_trie(None, &mut Vec::new(), *root, previous)?;
+			previous = Some(*root);
+			debug!(target: "pruning", "Marked state {} of {} ({:?}), {} nodes", i + 1, roots.len(), root, self.marked);
+		}
+		info!(target: "pruning", "Marked {} reachable state nodes of {} states, bloom saturation {:.2}%",
+			self.marked, roots.len(), self.bloom.saturation() * 100.0);
+		Ok(())
+	}
+
+	/// Delete the unmarked nodes from the state column, starting at the given part.
+	/// Returns the number of deleted nodes.
+	pub fn sweep(&mut self, start_part: usize) -> io::Result<u64> {
+		let mut deleted = 0u64;
+		for part in start_part..SWEEP_PARTS {
+			let prefix = [part as u8];
+			let mut batch = DBTransaction::with_capacity(SWEEP_BATCH_SIZE);
+			for (key, _) in self.db.iter_from_prefix(COL_STATE, &prefix) {
+				// journal records and other metadata are not keyed by hash.
+				if key.len() != 32 || self.bloom.check(&*key) {
+					continue;
+				}
+				batch.delete(COL_STATE, &key);
+				deleted += 1;
+				if batch.ops.len() == SWEEP_BATCH_SIZE {
+					self.db.write(batch)?;
+					batch = DBTransaction::with_capacity(SWEEP_BATCH_SIZE);
+				}
+			}
+
+			PruningProgress { keep: self.keep, parts_done: part + 1 }.save(&mut batch);
+			self.db.write(batch)?;
+			if (part + 1) % 16 == 0 {
+				info!(target: "pruning", "Pruning state: {}% swept, {} nodes deleted", (part + 1) * 100 / SWEEP_PARTS, deleted);
+			}
+		}
+
+		let mut batch = DBTransaction::new();
+		batch.delete(COL_NODE_INFO, PRUNING_PROGRESS_KEY);
+		self.db.write(batch)?;
+		Ok(deleted)
+	}
+
+	/// Mark the trie at `root`, descending only into the nodes which differ from the trie at
+	/// `previous`, all of whose nodes are marked already. `path` is the nibble path of `root`.
+	fn mark_trie(&mut self, address_hash: Option<H256>, path: &mut Vec<u8>, root: H256, previous: Option<H256>) -> TrieResult<()> {
+		if previous == Some(root) || root == KECCAK_NULL_RLP {
+			return Ok(());
+		}
+
+		let node = self.node(address_hash, &root)?;
+		let mut previous_references = HashMap::new();
+		if let Some(previous) = previous.filter(|previous| *previous != KECCAK_NULL_RLP) {
+			let previous_node = self.node(address_hash, &previous)?;
+			for reference in node_references(&previous_node).map_err(|e| Box::new(TrieError::DecoderError(previous, e)))? {
+				match reference {
+					NodeReference::Child(path, hash) => previous_references.insert(path, Err(hash)),
+					NodeReference::Leaf(path, value) => previous_references.insert(path, Ok(value)),
+				};
+			}
+		}
+
+		let start = path.len();
+		for reference in node_references(&node).map_err(|e| Box::new(TrieError::DecoderError(root, e)))? {
+			match reference {
+				NodeReference::Child(child_path, hash) => {
+					let previous = match previous_references.get(&child_path) {
+						Some(&Err(ref previous)) => Some(*previous),
+						_ => None,
+					};
+					path.extend_from_slice(&child_path);
+					self.mark_trie(address_hash, path, hash, previous)?;
+				},
+				NodeReference::Leaf(leaf_path, value) => {
+					let previous = match previous_references.get(&leaf_path) {
+						Some(&Ok(ref previous)) if *previous == value => continue,
+						Some(&Ok(ref previous)) => Some(previous),
+						_ => None,
+					};
+					path.extend_from_slice(&leaf_path);
+					let key_hash = H256::from_slice(&from_nibbles(path));
+					match address_hash {
+						Some(ref address_hash) => self.mark_key(combine_key(address_hash, &key_hash)),
+						None => self.mark_account(key_hash, &value, previous)?,
+					}
+				},
+			}
+			path.truncate(start);
+		}
+		Ok(())
+	}
+
+	/// Mark the storage and the code of an account, given the account in the previous state.
+	fn mark_account(&mut self, address_hash: H256, account: &[u8], previous: Option<&Vec<u8>>) -> TrieResult<()> {
+		let decode = |rlp: &[u8]| rlp::decode::<BasicAccount>(rlp)
+			.map_err(|e| Box::new(TrieError::DecoderError(address_hash, e)));
+		let account = decode(account)?;
+		let previous = match previous {
+			Some(previous) => Some(decode(previous)?),
+			None => None,
+		};
+
+		// pre-image of the address hash, kept by fat databases.
+		self.mark_key(address_hash);
+		if account.code_hash != KECCAK_EMPTY {
+			self.mark_key(combine_key(&address_hash, &account.code_hash));
+			// codes are also stored by their plain hash, to be served by hash alone.
+			self.mark_key(account.code_hash);
+		}
+		self.mark_trie(Some(address_hash), &mut Vec::new(), account.storage_root, previous.map(|p| p.storage_root))
+	}
+
+	/// Read a trie node and mark its key.
+	fn node(&mut self, address_hash: Option<H256>, hash: &H256) -> TrieResult<DBValue> {
+		let key = match address_hash {
+			Some(ref address_hash) => combine_key(address_hash, hash),
+			None => *hash,
+		};
+		let node = self.state.get(&key, EMPTY_PREFIX).ok_or_else(|| Box::new(TrieError::IncompleteDatabase(*hash)))?;
+		self.mark_key(key);
+		Ok(node)
+	}
+
+	fn mark_key(&mut self, key: H256) {
+		self.bloom.set(key.as_bytes());
+		self.marked += 1;
+		if self.marked % MARK_REPORT_INTERVAL == 0 {
+			// the bloom journal is not needed, don't let it grow.
+			self.bloom.drain_journal();
+			info!(target: "pruning", "Marking state: {} nodes", self.marked);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use ethereum_types::{Address, H256, U256};
+	use hash_db::EMPTY_PREFIX;
+	use keccak_hash::keccak;
+	use kvdb::DBTransaction;
+
+	use account_state::{CleanupMode, State};
+	use ethcore::test_helpers::new_db;
+	use ethcore_db::COL_STATE;
+	use journaldb::Algorithm;
+
+	use super::{PruningProgress, StatePruner};
+	use crate::StateDB;
+
+	#[test]
+	fn removes_unreachable_nodes_only() {
+		let db = new_db();
+		let journal_db = journaldb::new(db.key_value().clone(), Algorithm::Archive, COL_STATE);
+		let state_db = StateDB::new(journal_db, 1024 * 1024);
+
+		let mut roots = Vec::new();
+		let mut state = State::new(state_db, U256::zero(), Default::default());
+		for i in 0..4u64 {
+			let address = Address::from_low_u64_be(i % 2);
+			state.add_balance(&address, &U256::from(i + 1), CleanupMode::NoEmpty).unwrap();
+			state.set_storage(&address, H256::from_low_u64_be(i), H256::from_low_u64_be(i + 1)).unwrap();
+			state.init_code(&address, vec![i as u8; 40]).unwrap();
+			state.commit().unwrap();
+			let (root, mut state_db) = state.drop();
+			let mut batch = DBTransaction::new();
+			state_db.journal_under(&mut batch, i, &H256::from_low_u64_be(i + 1)).unwrap();
+			db.key_value().write(batch).unwrap();
+			roots.push(root);
+			state = State::from_existing(state_db, root, U256::zero(), Default::default()).unwrap();
+		}
+		let (_, state_db) = state.drop();
+		let count = || db.key_value().iter(COL_STATE).filter(|&(ref key, _)| key.len() == 32).count();
+		let before = count();
+
+		let mut pruner = StatePruner::new(&**db.key_value(), state_db.as_hash_db(), 2, 64 * 1024);
+		pruner.mark(&roots[2..]).unwrap();
+		let deleted = pruner.sweep(0).unwrap() as usize;
+		assert!(deleted > 0);
+		assert_eq!(count(), before - deleted);
+		assert_eq!(PruningProgress::load(&**db.key_value()).unwrap(), None);
+
+		let state_db = StateDB::new(journaldb::new(db.key_value().clone(), Algorithm::Archive, COL_STATE), 1024 * 1024);
+		assert!(!state_db.as_hash_db().contains(&roots[0], EMPTY_PREFIX));
+		for i in 2..4u8 {
+			assert_eq!(state_db.as_hash_db().get(&keccak(vec![i; 40]), EMPTY_PREFIX), Some(vec![i; 40]));
+		}
+		for root in &roots[2..] {
+			let state = State::from_existing(state_db.boxed_clone(), *root, U256::zero(), Default::default()).unwrap();
+			for i in 0..2u64 {
+				let address = Address::from_low_u64_be(i);
+				assert!(!state.balance(&address).unwrap().is_zero());
+				assert!(state.code(&address).unwrap().is_some());
+			}
+		}
+		let state = State::from_existing(state_db, roots[3], U256::zero(), Default::default()).unwrap();
+		assert_eq!(state.storage_at(&Address::from_low_u64_be(1), &H256::from_low_u64_be(1)).unwrap(), H256::from_low_u64_be(2));
+		assert_eq!(state.storage_at(&Address::from_low_u64_be(1), &H256::from_low_u64_be(3)).unwrap(), H256::from_low_u64_be(4));
+	}
+
+	#[test]
+	fn resumes_sweep_from_progress() {
+		let db = new_db().key_value().clone();
+		let mut batch = DBTransaction::new();
+		for first in &[0x00u8, 0x80, 0xff] {
+			let mut key = [0u8; 32];
+			key[0] = *first;
+			batch.put(COL_STATE, &key, b"node");
+		}
+		PruningProgress { keep: 1, parts_done: 0x80 }.save(&mut batch);
+		db.write(batch).unwrap();
+
+		let progress = PruningProgress::load(&*db).unwrap().unwrap();
+		assert_eq!(progress, PruningProgress { keep: 1, parts_done: 0x80 });
+		let state = journaldb::new_memory_db();
+		let mut pruner = StatePruner::new(&*db, &state, progress.keep, 1024);
+		assert_eq!(pruner.sweep(progress.parts_done).unwrap(), 2);
+		assert_eq!(db.iter(COL_STATE).count(), 1);
+		assert!(PruningProgress::load(&*db).unwrap().is_none());
+	}
+}
@@ -15,7 +15,7 @@
 // along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::sync::{Arc, mpsc, atomic};
-use std::collections::{HashMap, BTreeMap};
+use std::collections::{HashMap, BTreeMap, BTreeSet};
 use std::io;
 use std::ops::RangeInclusive;
 use std::time::Duration;
@@ -29,8 +29,9 @@ use crate::private_tx::PrivateTxHandler;
 use crate::chain::{
 	sync_packet::SyncPacket::{PrivateTransactionPacket, SignedPrivateTransactionPacket},
 	ChainSyncApi, SyncState, SyncStatus as EthSyncStatus, ETH_PROTOCOL_VERSION_62,
-	ETH_PROTOCOL_VERSION_63, PAR_PROTOCOL_VERSION_1, PAR_PROTOCOL_VERSION_2,
-	PAR_PROTOCOL_VERSION_3, PAR_PROTOCOL_VERSION_4,
+	ETH_PROTOCOL_VERSION_63, ETH_PROTOCOL_VERSION_64, ETH_PROTOCOL_VERSION_65, ETH_PROTOCOL_VERSION_66,
+	PAR_PROTOCOL_VERSION_1, PAR_PROTOCOL_VERSION_2, PAR_PROTOCOL_VERSION_3, PAR_PROTOCOL_VERSION_4,
+	SNAP_PROTOCOL_VERSION_1,
 };
 
 use bytes::Bytes;
@@ -38,7 +39,7 @@ use client_traits::{BlockChainClient, ChainNotify};
 use devp2p::NetworkService;
 use ethcore_io::TimerToken;
 use ethcore_private_tx::PrivateStateDB;
-use ethereum_types::{H256, H512, U256};
+use ethereum_types::{H256, H512, U64, U256};
 use parity_crypto::publickey::Secret;
 use futures::sync::mpsc as futures_mpsc;
 use futures::Stream;
@@ -68,12 +69,14 @@ use common_types::{
 };
 
 
-/// Parity sync protocol
-pub const WARP_SYNC_PROTOCOL_ID: ProtocolId = *b"par";
-/// Ethereum sync protocol
-pub const ETH_PROTOCOL: ProtocolId = *b"eth";
-/// Ethereum light protocol
-pub const LIGHT_PROTOCOL: ProtocolId = *b"pip";
+/// Parity sync protocol, "par"
+pub const WARP_SYNC_PROTOCOL_ID: ProtocolId = U64([0x7061_7200_0000_0000]);
+/// Ethereum sync protocol, "eth"
+pub const ETH_PROTOCOL: ProtocolId = U64([0x6574_6800_0000_0000]);
+/// Ethereum light protocol, "pip"
+pub const LIGHT_PROTOCOL: ProtocolId = U64([0x7069_7000_0000_0000]);
+/// Ethereum state snapshot protocol, "snap"
+pub const SNAP_PROTOCOL: ProtocolId = U64([0x736e_6170_0000_0000]);
 
 /// Determine warp sync status.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, MallocSizeOf)]
@@ -111,7 +114,7 @@ impl WarpSync {
 }
 
 /// Sync configuration
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct SyncConfig {
 	/// Max blocks to download ahead
 	pub max_download_ahead_blocks: usize,
@@ -120,13 +123,19 @@ pub struct SyncConfig {
 	/// Network ID
 	pub network_id: u64,
 	/// Main "eth" subprotocol name.
-	pub subprotocol_name: [u8; 3],
+	pub subprotocol_name: ProtocolId,
 	/// Light subprotocol name.
-	pub light_subprotocol_name: [u8; 3],
+	pub light_subprotocol_name: ProtocolId,
 	/// Fork block to check
 	pub fork_block: Option<(BlockNumber, H256)>,
+	/// Hard fork blocks of the chain, used to compute and validate the eth/64 fork id.
+	pub hard_forks: BTreeSet<BlockNumber>,
+	/// Block number from which headers carry the EIP-1559 base fee.
+	pub eip1559_transition: BlockNumber,
 	/// Enable snapshot sync
 	pub warp_sync: WarpSync,
+	/// Enable state download with the `snap` protocol
+	pub snap_sync: bool,
 	/// Enable light client server.
 	pub serve_light: bool,
 }
@@ -140,7 +149,10 @@ impl Default for SyncConfig {
 			subprotocol_name: ETH_PROTOCOL,
 			light_subprotocol_name: LIGHT_PROTOCOL,
 			fork_block: None,
+			hard_forks: BTreeSet::new(),
+			eip1559_transition: BlockNumber::max_value(),
 			warp_sync: WarpSync::Disabled,
+			snap_sync: false,
 			serve_light: false,
 		}
 	}
@@ -160,6 +172,9 @@ pub trait SyncProvider: Send + Sync {
 	/// Get the enode if available.
 	fn enode(&self) -> Option<String>;
 
+	/// Get the node record if available.
+	fn enr(&self) -> Option<String>;
+
 	/// gets sync status notifications
 	fn sync_notification(&self) -> Notification<SyncState>;
 
@@ -290,13 +305,15 @@ pub struct EthSync {
 	/// Light (pip) protocol handler
 	light_proto: Option<Arc<LightProtocol>>,
 	/// The main subprotocol name
-	subprotocol_name: [u8; 3],
+	subprotocol_name: ProtocolId,
 	/// Light subprotocol name.
-	light_subprotocol_name: [u8; 3],
+	light_subprotocol_name: ProtocolId,
 	/// Priority tasks notification channel
 	priority_tasks: Mutex<mpsc::Sender<PriorityTask>>,
 	/// Track the sync state: are we importing or verifying blocks?
-	is_major_syncing: Arc<AtomicBool>
+	is_major_syncing: Arc<AtomicBool>,
+	/// The `eth` entry last announced in our node record
+	enr_entry: Mutex<Option<Bytes>>,
 }
 
 fn light_params(
@@ -351,7 +368,7 @@ impl EthSync {
 
 		let (priority_tasks_tx, priority_tasks_rx) = mpsc::channel();
 		let sync = ChainSyncApi::new(
-			params.config,
+			params.config.clone(),
 			&*params.chain,
 			params.private_tx_handler.as_ref().cloned(),
 			priority_tasks_rx,
@@ -395,7 +412,8 @@ impl EthSync {
 			subprotocol_name: params.config.subprotocol_name,
 			light_subprotocol_name: params.config.light_subprotocol_name,
 			priority_tasks: Mutex::new(priority_tasks_tx),
-			is_major_syncing
+			is_major_syncing,
+			enr_entry: Mutex::new(None),
 		});
 
 		Ok(sync)
@@ -405,6 +423,17 @@ impl EthSync {
 	pub fn priority_tasks(&self) -> mpsc::Sender<PriorityTask> {
 		self.priority_tasks.lock().clone()
 	}
+
+	/// Announces our current fork id in the `eth` entry of the node record if it changed.
+	fn update_enr(&self) {
+		let entry = self.eth_handler.sync.enr_entry(&*self.eth_handler.chain);
+		let mut last_entry = self.enr_entry.lock();
+		if last_entry.as_ref() == Some(&entry) {
+			return;
+		}
+		*last_entry = Some(entry.clone());
+		self.network.set_enr_entry("eth", entry);
+	}
 }
 
 impl SyncProvider for EthSync {
@@ -443,6 +472,10 @@ impl SyncProvider for EthSync {
 		self.network.external_url()
 	}
 
+	fn enr(&self) -> Option<String> {
+		self.network.external_enr()
+	}
+
 	fn transactions_stats(&self) -> BTreeMap<H256, TransactionStats> {
 		self.eth_handler.sync.transactions_stats()
 	}
@@ -479,7 +512,7 @@ struct SyncProtocolHandler {
 
 impl NetworkProtocolHandler for SyncProtocolHandler {
 	fn initialize(&self, io: &dyn NetworkContext) {
-		if io.subprotocol_name() != WARP_SYNC_PROTOCOL_ID {
+		if io.subprotocol_name() != WARP_SYNC_PROTOCOL_ID && io.subprotocol_name() != SNAP_PROTOCOL {
 			io.register_timer(PEERS_TIMER, Duration::from_millis(700)).expect("Error registering peers timer");
 			io.register_timer(MAINTAIN_SYNC_TIMER, Duration::from_millis(1100)).expect("Error registering sync timer");
 			io.register_timer(CONTINUE_SYNC_TIMER, Duration::from_millis(2500)).expect("Error registering sync timer");
@@ -490,16 +523,24 @@ impl NetworkProtocolHandler for SyncProtocolHandler {
 	}
 
 	fn read(&self, io: &dyn NetworkContext, peer: &PeerId, packet_id: u8, data: &[u8]) {
-		self.sync.dispatch_packet(&mut NetSyncIo::new(io,
+		let mut sync_io = NetSyncIo::new(io,
 			&*self.chain,
 			&*self.snapshot_service,
 			&self.overlay,
-			self.private_state.clone()),
-			*peer, packet_id, data);
+			self.private_state.clone());
+		if io.subprotocol_name() == SNAP_PROTOCOL {
+			self.sync.dispatch_snap_packet(&mut sync_io, *peer, packet_id, data);
+		} else {
+			self.sync.dispatch_packet(&mut sync_io, *peer, packet_id, data);
+		}
 	}
 
 	fn connected(&self, io: &dyn NetworkContext, peer: &PeerId) {
 		trace_time!("sync::connected");
+		// the snap protocol runs alongside eth, which handles the peer
+		if io.subprotocol_name() == SNAP_PROTOCOL {
+			return;
+		}
 		// If warp protocol is supported only allow warp handshake
 		let warp_protocol = io.protocol_version(WARP_SYNC_PROTOCOL_ID, *peer).unwrap_or(0) != 0;
 		let warp_context = io.subprotocol_name() == WARP_SYNC_PROTOCOL_ID;
@@ -515,7 +556,7 @@ impl NetworkProtocolHandler for SyncProtocolHandler {
 
 	fn disconnected(&self, io: &dyn NetworkContext, peer: &PeerId) {
 		trace_time!("sync::disconnected");
-		if io.subprotocol_name() != WARP_SYNC_PROTOCOL_ID {
+		if io.subprotocol_name() != WARP_SYNC_PROTOCOL_ID && io.subprotocol_name() != SNAP_PROTOCOL {
 			self.sync.write().on_peer_aborting(&mut NetSyncIo::new(io,
 				&*self.chain,
 				&*self.snapshot_service,
@@ -590,7 +631,10 @@ impl ChainNotify for EthSync {
 				serve_chain_since: None,
 				tx_relay: false,
 			})
-		})
+		});
+
+		// the fork id changes once a fork block is imported
+		self.update_enr();
 	}
 
 	fn start(&self) {
@@ -606,17 +650,24 @@ impl ChainNotify for EthSync {
 			_ => {},
 		}
 
-		self.network.register_protocol(self.eth_handler.clone(), self.subprotocol_name, &[ETH_PROTOCOL_VERSION_62, ETH_PROTOCOL_VERSION_63])
+		self.network.register_protocol(self.eth_handler.clone(), self.subprotocol_name, &[ETH_PROTOCOL_VERSION_62, ETH_PROTOCOL_VERSION_63, ETH_PROTOCOL_VERSION_64, ETH_PROTOCOL_VERSION_65, ETH_PROTOCOL_VERSION_66])
 			.unwrap_or_else(|e| warn!("Error registering ethereum protocol: {:?}", e));
 		// register the warp sync subprotocol
 		self.network.register_protocol(self.eth_handler.clone(), WARP_SYNC_PROTOCOL_ID, &[PAR_PROTOCOL_VERSION_1, PAR_PROTOCOL_VERSION_2, PAR_PROTOCOL_VERSION_3, PAR_PROTOCOL_VERSION_4])
 			.unwrap_or_else(|e| warn!("Error registering snapshot sync protocol: {:?}", e));
+		// register the snap subprotocol, used to serve the state and to download it
+		self.network.register_protocol(self.eth_handler.clone(), SNAP_PROTOCOL, &[SNAP_PROTOCOL_VERSION_1])
+			.unwrap_or_else(|e| warn!("Error registering snap protocol: {:?}", e));
 
 		// register the light protocol.
 		if let Some(light_proto) = self.light_proto.as_ref().map(|x| x.clone()) {
 			self.network.register_protocol(light_proto, self.light_subprotocol_name, ::light::net::PROTOCOL_VERSIONS)
 				.unwrap_or_else(|e| warn!("Error registering light client protocol: {:?}", e));
 		}
+
+		self.update_enr();
+		let handler = self.eth_handler.clone();
+		self.network.set_enr_filter("eth", Box::new(move |entry: &rlp::Rlp| handler.sync.is_compatible_enr(&*handler.chain, entry)));
 	}
 
 	fn stop(&self) {
@@ -748,6 +799,8 @@ pub struct NetworkConfiguration {
 	pub discovery_enabled: bool,
 	/// List of initial node addresses
 	pub boot_nodes: Vec<String>,
+	/// Locations (`enrtree://`) of node lists published in DNS
+	pub dns_discovery: Vec<String>,
 	/// Use provided node key instead of default
 	pub use_secret: Option<Secret>,
 	/// Max number of connected peers to maintain
@@ -791,6 +844,7 @@ impl NetworkConfiguration {
 			nat_type: self.nat_type,
 			discovery_enabled: self.discovery_enabled,
 			boot_nodes: self.boot_nodes,
+			dns_discovery: self.dns_discovery,
 			use_secret: self.use_secret,
 			max_peers: self.max_peers,
 			min_peers: self.min_peers,
@@ -816,6 +870,7 @@ impl From<BasicNetworkConfiguration> for NetworkConfiguration {
 			nat_type: other.nat_type,
 			discovery_enabled: other.discovery_enabled,
 			boot_nodes: other.boot_nodes,
+			dns_discovery: other.dns_discovery,
 			use_secret: other.use_secret,
 			max_peers: other.max_peers,
 			min_peers: other.min_peers,
@@ -867,6 +922,9 @@ pub trait LightSyncProvider {
 	/// Get the enode if available.
 	fn enode(&self) -> Option<String>;
 
+	/// Get the node record if available.
+	fn enr(&self) -> Option<String>;
+
 	/// Returns propagation count for pending transactions.
 	fn transactions_stats(&self) -> BTreeMap<H256, TransactionStats>;
 }
@@ -898,7 +956,7 @@ pub struct LightSyncParams<L> {
 	/// Network ID.
 	pub network_id: u64,
 	/// Subprotocol name.
-	pub subprotocol_name: [u8; 3],
+	pub subprotocol_name: ProtocolId,
 	/// Other handlers to attach.
 	pub handlers: Vec<Arc<dyn LightHandler>>,
 }
@@ -908,7 +966,7 @@ pub struct LightSync {
 	proto: Arc<LightProtocol>,
 	sync: Arc<dyn SyncInfo + Sync + Send>,
 	network: NetworkService,
-	subprotocol_name: [u8; 3],
+	subprotocol_name: ProtocolId,
 	network_id: u64,
 }
 
@@ -1063,6 +1121,10 @@ impl LightSyncProvider for LightSync {
 		self.network.external_url()
 	}
 
+	fn enr(&self) -> Option<String> {
+		self.network.external_enr()
+	}
+
 	fn network_id(&self) -> u64 {
 		self.network_id
 	}
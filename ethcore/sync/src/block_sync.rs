@@ -149,12 +149,19 @@ pub struct BlockDownloader {
 	retract_step: u64,
 	/// consecutive useless headers this round
 	useless_headers_count: usize,
+	/// Block number from which headers carry the EIP-1559 base fee.
+	eip1559_transition: BlockNumber,
 }
 
 impl BlockDownloader {
 	/// Create a new instance of syncing strategy.
 	/// For BlockSet::NewBlocks this won't reorganize to before the last kept state.
-	pub fn new(block_set: BlockSet, start_hash: &H256, start_number: BlockNumber) -> Self {
+	pub fn new(
+		block_set: BlockSet,
+		start_hash: &H256,
+		start_number: BlockNumber,
+		eip1559_transition: BlockNumber,
+	) -> Self {
 		let sync_receipts = match block_set {
 			BlockSet::NewBlocks => false,
 			BlockSet::OldBlocks => true
@@ -174,6 +181,7 @@ impl BlockDownloader {
 			target_hash: None,
 			retract_step: 1,
 			useless_headers_count: 0,
+			eip1559_transition,
 		}
 	}
 
@@ -263,7 +271,7 @@ impl BlockDownloader {
 		let mut hashes = Vec::new();
 		let mut last_header = None;
 		for i in 0..item_count {
-			let info = SyncHeader::from_rlp(r.at(i)?.as_raw().to_vec())?;
+			let info = SyncHeader::from_rlp(r.at(i)?.as_raw().to_vec(), self.eip1559_transition)?;
 			let number = BlockNumber::from(info.header.number());
 			let hash = info.header.hash();
 
@@ -374,7 +382,7 @@ impl BlockDownloader {
 		} else {
 			let mut bodies = Vec::with_capacity(item_count);
 			for i in 0..item_count {
-				let body = SyncBody::from_rlp(r.at(i)?.as_raw())?;
+				let body = SyncBody::from_rlp(r.at(i)?.as_raw(), self.eip1559_transition)?;
 				bodies.push(body);
 			}
 
@@ -469,13 +477,17 @@ impl BlockDownloader {
 		self.imported_this_round = None;
 	}
 
-	/// Find some headers or blocks to download for a peer.
-	pub fn request_blocks(&mut self, peer_id: PeerId, io: &mut dyn SyncIo, num_active_peers: usize) -> Option<BlockRequest> {
+	/// Find some headers or blocks to download for a peer. `pipelined` is set if the peer has other requests
+	/// in flight, only headers, bodies and receipts of the current round not being downloaded are requested then.
+	pub fn request_blocks(&mut self, peer_id: PeerId, io: &mut dyn SyncIo, num_active_peers: usize, pipelined: bool) -> Option<BlockRequest> {
+		if pipelined && self.state != State::Blocks {
+			return None;
+		}
 		match self.state {
 			State::Idle => {
 				self.start_sync_round(io);
 				if self.state == State::ChainHead {
-					return self.request_blocks(peer_id, io, num_active_peers);
+					return self.request_blocks(peer_id, io, num_active_peers, pipelined);
 				}
 			},
 			State::ChainHead => {
@@ -655,6 +667,7 @@ mod tests {
 	use rlp::{encode_list, RlpStream};
 	use triehash_ethereum::ordered_trie_root;
 	use common_types::{
+		BlockNumber,
 		transaction::{Transaction, SignedTransaction},
 		header::Header as BlockHeader,
 	};
@@ -696,7 +709,7 @@ mod tests {
 		let spec = spec::new_test();
 		let genesis_hash = spec.genesis_header().hash();
 
-		let mut downloader = BlockDownloader::new(BlockSet::NewBlocks, &genesis_hash, 0);
+		let mut downloader = BlockDownloader::new(BlockSet::NewBlocks, &genesis_hash, 0, BlockNumber::max_value());
 		downloader.state = State::ChainHead;
 
 		let mut chain = TestBlockChainClient::new();
@@ -778,7 +791,7 @@ mod tests {
 		let parent_hash = headers[1].hash();
 		headers.push(dummy_header(129, parent_hash));
 
-		let mut downloader = BlockDownloader::new(BlockSet::NewBlocks, &H256::random(), 0);
+		let mut downloader = BlockDownloader::new(BlockSet::NewBlocks, &H256::random(), 0, BlockNumber::max_value());
 		downloader.state = State::Blocks;
 		downloader.blocks.reset_to(vec![headers[0].hash()]);
 
@@ -848,7 +861,7 @@ mod tests {
 			headers.push(header);
 		}
 
-		let mut downloader = BlockDownloader::new(BlockSet::NewBlocks, &headers[0].hash(), 0);
+		let mut downloader = BlockDownloader::new(BlockSet::NewBlocks, &headers[0].hash(), 0, BlockNumber::max_value());
 		downloader.state = State::Blocks;
 		downloader.blocks.reset_to(vec![headers[0].hash()]);
 
@@ -912,7 +925,7 @@ mod tests {
 			headers.push(header);
 		}
 
-		let mut downloader = BlockDownloader::new(BlockSet::OldBlocks, &headers[0].hash(), 0);
+		let mut downloader = BlockDownloader::new(BlockSet::OldBlocks, &headers[0].hash(), 0, BlockNumber::max_value());
 		downloader.state = State::Blocks;
 		downloader.blocks.reset_to(vec![headers[0].hash()]);
 
@@ -945,7 +958,7 @@ mod tests {
 		let spec = spec::new_test();
 		let genesis_hash = spec.genesis_header().hash();
 
-		let mut downloader = BlockDownloader::new(BlockSet::NewBlocks, &genesis_hash, 0);
+		let mut downloader = BlockDownloader::new(BlockSet::NewBlocks, &genesis_hash, 0, BlockNumber::max_value());
 		downloader.state = State::ChainHead;
 
 		let mut chain = TestBlockChainClient::new();
@@ -985,7 +998,7 @@ mod tests {
 		let spec = spec::new_test();
 		let genesis_hash = spec.genesis_header().hash();
 
-		let mut downloader = BlockDownloader::new(BlockSet::NewBlocks, &genesis_hash, 0);
+		let mut downloader = BlockDownloader::new(BlockSet::NewBlocks, &genesis_hash, 0, BlockNumber::max_value());
 		downloader.state = State::ChainHead;
 
 		let mut chain = TestBlockChainClient::new();
@@ -0,0 +1,219 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Fork identifier as defined by EIP-2124, exchanged in the `Status` packet since eth/64.
+//!
+//! The identifier summarizes the genesis hash and the forks a node has already passed into a
+//! CRC32 checksum, together with the block number of the next fork it knows about. This lets
+//! nodes reject peers of incompatible chains right after the handshake.
+
+use std::collections::BTreeSet;
+
+use common_types::BlockNumber;
+use crc32fast::Hasher;
+use ethereum_types::{H32, H256};
+use rlp::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
+
+/// EIP-2124 fork identifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ForkId {
+	/// CRC32 checksum of the genesis hash and of the fork blocks already passed.
+	pub hash: H32,
+	/// Block number of the next known fork, zero if there is none.
+	pub next: BlockNumber,
+}
+
+impl Encodable for ForkId {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(2);
+		s.append(&self.hash);
+		s.append(&self.next);
+	}
+}
+
+impl Decodable for ForkId {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		Ok(ForkId {
+			hash: rlp.val_at(0)?,
+			next: rlp.val_at(1)?,
+		})
+	}
+}
+
+/// Reason why a remote fork identifier is rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForkIdError {
+	/// The remote node is on our chain but has not been upgraded for a fork we already passed.
+	RemoteStale,
+	/// The remote node is on an incompatible chain, or we are missing a fork it already passed.
+	LocalIncompatibleOrStale,
+}
+
+/// Computes our fork identifier and validates the ones announced by peers.
+pub struct ForkFilter {
+	/// Fork block numbers in ascending order, terminated by `BlockNumber::max_value()`.
+	forks: Vec<BlockNumber>,
+	/// Checksums after passing the first `i` forks, `sums[0]` covers the genesis hash only.
+	sums: Vec<u32>,
+}
+
+impl ForkFilter {
+	/// Creates a new filter for the chain of the given genesis and fork blocks.
+	/// Forks scheduled at genesis must not be included.
+	pub fn new(genesis: H256, forks: &BTreeSet<BlockNumber>) -> Self {
+		let mut hasher = Hasher::new();
+		hasher.update(genesis.as_bytes());
+
+		let mut sums = vec![hasher.clone().finalize()];
+		for fork in forks {
+			hasher.update(&fork.to_be_bytes());
+			sums.push(hasher.clone().finalize());
+		}
+
+		let mut forks: Vec<_> = forks.iter().cloned().collect();
+		forks.push(BlockNumber::max_value());
+
+		ForkFilter { forks, sums }
+	}
+
+	/// Index of the first fork not passed yet at `head`.
+	fn next_fork_index(&self, head: BlockNumber) -> usize {
+		self.forks.iter().position(|&fork| head < fork).unwrap_or(self.forks.len() - 1)
+	}
+
+	/// Returns our fork identifier at the given head.
+	pub fn current(&self, head: BlockNumber) -> ForkId {
+		let index = self.next_fork_index(head);
+		let next = match self.forks[index] {
+			n if n == BlockNumber::max_value() => 0,
+			n => n,
+		};
+
+		ForkId {
+			hash: H32::from(self.sums[index].to_be_bytes()),
+			next,
+		}
+	}
+
+	/// Validates a fork identifier announced by a peer, following the rules of EIP-2124.
+	pub fn validate(&self, head: BlockNumber, remote: &ForkId) -> Result<(), ForkIdError> {
+		let index = self.next_fork_index(head);
+		let remote_sum = u32::from_be_bytes(remote.hash.to_fixed_bytes());
+
+		// Both nodes passed the same forks; reject the peer only if it announces a fork we already
+		// passed without knowing about it.
+		if self.sums[index] == remote_sum {
+			return match remote.next > 0 && head >= remote.next {
+				true => Err(ForkIdError::LocalIncompatibleOrStale),
+				false => Ok(()),
+			};
+		}
+
+		// The peer is behind us, it's compatible only if it knows about the fork it has to pass next.
+		if let Some(passed) = self.sums[..index].iter().position(|&sum| sum == remote_sum) {
+			return match self.forks[passed] == remote.next {
+				true => Ok(()),
+				false => Err(ForkIdError::RemoteStale),
+			};
+		}
+
+		// The peer is ahead of us, but only passed forks we know about.
+		if self.sums[index + 1..].contains(&remote_sum) {
+			return Ok(());
+		}
+
+		Err(ForkIdError::LocalIncompatibleOrStale)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+
+	use super::*;
+
+	const BYZANTIUM: BlockNumber = 4_370_000;
+	const PETERSBURG: BlockNumber = 7_280_000;
+	const ISTANBUL: BlockNumber = 9_069_000;
+
+	fn mainnet() -> ForkFilter {
+		let genesis = H256::from_str("d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3").unwrap();
+		let forks = vec![1_150_000, 1_920_000, 2_463_000, 2_675_000, BYZANTIUM, PETERSBURG, ISTANBUL, 9_200_000];
+		ForkFilter::new(genesis, &forks.into_iter().collect())
+	}
+
+	fn fork_id(hash: u32, next: BlockNumber) -> ForkId {
+		ForkId { hash: H32::from(hash.to_be_bytes()), next }
+	}
+
+	#[test]
+	fn should_compute_mainnet_fork_ids() {
+		let filter = mainnet();
+		let cases = vec![
+			(0, fork_id(0xfc64ec04, 1_150_000)),
+			(1_149_999, fork_id(0xfc64ec04, 1_150_000)),
+			(1_150_000, fork_id(0x97c2c34c, 1_920_000)),
+			(1_920_000, fork_id(0x91d1f948, 2_463_000)),
+			(2_463_000, fork_id(0x7a64da13, 2_675_000)),
+			(2_675_000, fork_id(0x3edd5b10, BYZANTIUM)),
+			(BYZANTIUM, fork_id(0xa00bc324, PETERSBURG)),
+			(PETERSBURG, fork_id(0x668db0af, ISTANBUL)),
+			(ISTANBUL, fork_id(0x879d6e30, 9_200_000)),
+			(9_200_000, fork_id(0xe029e991, 0)),
+			(10_000_000, fork_id(0xe029e991, 0)),
+		];
+
+		for (head, expected) in cases {
+			assert_eq!(filter.current(head), expected, "head {}", head);
+		}
+	}
+
+	#[test]
+	fn should_validate_remote_fork_ids() {
+		let filter = mainnet();
+		let cases = vec![
+			// Same forks passed, no fork announced.
+			(7_987_396, fork_id(0x668db0af, 0), Ok(())),
+			// Same forks passed, remote announces a fork we don't know about yet.
+			(7_987_396, fork_id(0x668db0af, BlockNumber::max_value()), Ok(())),
+			// Remote is syncing and knows about the next fork.
+			(7_987_396, fork_id(0xa00bc324, PETERSBURG), Ok(())),
+			(7_987_396, fork_id(0x3edd5b10, BYZANTIUM), Ok(())),
+			// We are syncing, remote passed forks we know about.
+			(PETERSBURG - 1, fork_id(0x668db0af, 0), Ok(())),
+			(BYZANTIUM - 1, fork_id(0x668db0af, ISTANBUL), Ok(())),
+			// Remote is behind and didn't upgrade for Petersburg.
+			(7_987_396, fork_id(0xa00bc324, 0), Err(ForkIdError::RemoteStale)),
+			// Unknown chain.
+			(7_987_396, fork_id(0x5cddc0e1, 0), Err(ForkIdError::LocalIncompatibleOrStale)),
+			(PETERSBURG - 1, fork_id(0x5cddc0e1, 0), Err(ForkIdError::LocalIncompatibleOrStale)),
+			// Remote announces a fork we already passed without knowing about it.
+			(88_888_888, fork_id(0xe029e991, 88_888_888), Err(ForkIdError::LocalIncompatibleOrStale)),
+		];
+
+		for (head, remote, expected) in cases {
+			assert_eq!(filter.validate(head, &remote), expected, "head {}, remote {:?}", head, remote);
+		}
+	}
+
+	#[test]
+	fn should_encode_fork_id() {
+		let id = fork_id(0xe029e991, 9_200_000);
+		let encoded = rlp::encode(&id);
+		assert_eq!(encoded, vec![0xc9, 0x84, 0xe0, 0x29, 0xe9, 0x91, 0x83, 0x8c, 0x61, 0x80]);
+		assert_eq!(rlp::decode::<ForkId>(&encoded).unwrap(), id);
+	}
+}
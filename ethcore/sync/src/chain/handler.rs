@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::collections::BTreeMap;
 use std::time::Instant;
 use std::{mem, cmp};
 
@@ -27,18 +28,22 @@ use crate::{
 			PacketInfo,
 			SyncPacket::{
 				self, BlockBodiesPacket, BlockHeadersPacket, NewBlockHashesPacket, NewBlockPacket,
-				PrivateStatePacket, PrivateTransactionPacket, ReceiptsPacket, SignedPrivateTransactionPacket,
+				NewPooledTransactionHashesPacket, PooledTransactionsPacket, PrivateStatePacket,
+				PrivateTransactionPacket, ReceiptsPacket, SignedPrivateTransactionPacket,
 				SnapshotDataPacket, SnapshotManifestPacket, StatusPacket,
 			}
 		},
+		fork_filter::{ForkId, ForkIdError},
 		BlockSet, ChainSync, ForkConfirmation, PacketDecodeError, PeerAsking, PeerInfo, SyncRequester,
-		SyncState, ETH_PROTOCOL_VERSION_62, ETH_PROTOCOL_VERSION_63, MAX_NEW_BLOCK_AGE, MAX_NEW_HASHES,
-		PAR_PROTOCOL_VERSION_1, PAR_PROTOCOL_VERSION_3, PAR_PROTOCOL_VERSION_4,
+		SyncState, ETH_PROTOCOL_VERSION_62, ETH_PROTOCOL_VERSION_64, ETH_PROTOCOL_VERSION_65, ETH_PROTOCOL_VERSION_66,
+		MAX_NEW_BLOCK_AGE, MAX_NEW_HASHES, MAX_UNFETCHED_POOLED_TRANSACTIONS, PAR_PROTOCOL_VERSION_1,
+		PAR_PROTOCOL_VERSION_3, PAR_PROTOCOL_VERSION_4,
 	}
 };
 
 use bytes::Bytes;
 use enum_primitive::FromPrimitive;
+use fastmap::H256FastSet;
 use ethereum_types::{H256, U256};
 use keccak_hash::keccak;
 use network::PeerId;
@@ -63,6 +68,16 @@ impl SyncHandler {
 	pub fn on_packet(sync: &mut ChainSync, io: &mut dyn SyncIo, peer: PeerId, packet_id: u8, data: &[u8]) {
 		let rlp = Rlp::new(data);
 		if let Some(packet_id) = SyncPacket::from_u8(packet_id) {
+			let rlp = match SyncHandler::unwrap_response(sync, peer, packet_id, &rlp) {
+				Ok(Some(rlp)) => rlp,
+				Ok(None) => return,
+				Err(e) => {
+					debug!(target: "sync", "{} -> Malformed packet {} : {}", peer, packet_id.id(), e);
+					io.disable_peer(peer);
+					sync.deactivate_peer(io, peer);
+					return;
+				}
+			};
 			let result = match packet_id {
 				StatusPacket => SyncHandler::on_peer_status(sync, io, peer, &rlp),
 				BlockHeadersPacket => SyncHandler::on_peer_block_headers(sync, io, peer, &rlp),
@@ -70,6 +85,8 @@ impl SyncHandler {
 				ReceiptsPacket => SyncHandler::on_peer_block_receipts(sync, io, peer, &rlp),
 				NewBlockPacket => SyncHandler::on_peer_new_block(sync, io, peer, &rlp),
 				NewBlockHashesPacket => SyncHandler::on_peer_new_hashes(sync, io, peer, &rlp),
+				NewPooledTransactionHashesPacket => SyncHandler::on_peer_new_pooled_transaction_hashes(sync, io, peer, &rlp),
+				PooledTransactionsPacket => SyncHandler::on_peer_pooled_transactions(sync, io, peer, &rlp),
 				SnapshotManifestPacket => SyncHandler::on_snapshot_manifest(sync, io, peer, &rlp),
 				SnapshotDataPacket => SyncHandler::on_snapshot_data(sync, io, peer, &rlp),
 				PrivateTransactionPacket => SyncHandler::on_private_transaction(sync, io, peer, &rlp),
@@ -93,6 +110,7 @@ impl SyncHandler {
 				Ok(()) => {
 					// give a task to the same peer first
 					sync.sync_peer(io, peer, false);
+					SyncRequester::request_pooled_transactions(sync, io, peer);
 				},
 			}
 		} else {
@@ -100,6 +118,26 @@ impl SyncHandler {
 		}
 	}
 
+	/// Strips the eth/66 request id from a response and makes the request it answers the current one.
+	/// Returns `None` if the response doesn't match any request in flight, it's late or unexpected.
+	fn unwrap_response<'a>(sync: &mut ChainSync, peer_id: PeerId, packet_id: SyncPacket, r: &Rlp<'a>) -> Result<Option<Rlp<'a>>, PacketDecodeError> {
+		match packet_id {
+			BlockHeadersPacket | BlockBodiesPacket | ReceiptsPacket | PooledTransactionsPacket => (),
+			_ => return Ok(Some(r.clone())),
+		}
+		let peer = match sync.peers.get_mut(&peer_id) {
+			Some(peer) if peer.request_ids => peer,
+			_ => return Ok(Some(r.clone())),
+		};
+
+		let request_id: u64 = r.val_at(0)?;
+		if !peer.activate_request(request_id) {
+			trace!(target: "sync", "{}: Ignored response {} to unknown request {}", peer_id, packet_id.id(), request_id);
+			return Ok(None);
+		}
+		Ok(Some(r.at(1)?))
+	}
+
 	/// Called when peer sends us new consensus packet
 	pub fn on_consensus_packet(io: &mut dyn SyncIo, peer_id: PeerId, r: &Rlp) {
 		trace!(target: "sync", "Received consensus packet from {:?}", peer_id);
@@ -113,8 +151,10 @@ impl SyncHandler {
 		if sync.peers.contains_key(&peer_id) {
 			debug!(target: "sync", "Disconnected {}", peer_id);
 			sync.clear_peer_download(peer_id);
+			sync.clear_pipelined_downloads(peer_id);
 			sync.peers.remove(&peer_id);
 			sync.active_peers.remove(&peer_id);
+			sync.snap.on_peer_aborting(peer_id);
 
 			if sync.state == SyncState::SnapshotManifest {
 				// Check if we are asking other peers for a snapshot manifest as well. If not,
@@ -125,7 +165,7 @@ impl SyncHandler {
 
 				if !still_seeking_manifest {
 					warn!(target: "snapshot_sync", "The peer we were downloading a snapshot from ({}) went away. Retrying.", peer_id);
-					sync.state = ChainSync::get_init_state(sync.warp_sync, io.chain());
+					sync.state = ChainSync::get_init_state(sync.warp_sync, sync.snap_sync, io.chain());
 				}
 			}
 			sync.continue_sync(io);
@@ -155,7 +195,7 @@ impl SyncHandler {
 				peer.difficulty = Some(difficulty);
 			}
 		}
-		let block = Unverified::from_rlp(r.at(0)?.as_raw().to_vec())?;
+		let block = Unverified::from_rlp(r.at(0)?.as_raw().to_vec(), sync.eip1559_transition)?;
 		let hash = block.header.hash();
 		let number = block.header.number();
 		trace!(target: "sync", "{} -> NewBlock ({})", peer_id, hash);
@@ -280,6 +320,10 @@ impl SyncHandler {
 
 	/// Called by peer once it has new block bodies
 	fn on_peer_block_bodies(sync: &mut ChainSync, io: &mut dyn SyncIo, peer_id: PeerId, r: &Rlp) -> Result<(), DownloaderImportError> {
+		if SyncHandler::is_snap_pivot_request(sync, peer_id) {
+			return SyncHandler::on_snap_pivot(sync, peer_id, BlockBodiesPacket, r);
+		}
+
 		sync.clear_peer_download(peer_id);
 		let block_set = sync.peers.get(&peer_id)
 			.and_then(|p| p.block_set)
@@ -364,6 +408,9 @@ impl SyncHandler {
 		if is_fork_header_request {
 			return SyncHandler::on_peer_fork_header(sync, io, peer_id, r);
 		}
+		if SyncHandler::is_snap_pivot_request(sync, peer_id) {
+			return SyncHandler::on_snap_pivot(sync, peer_id, BlockHeadersPacket, r);
+		}
 
 		sync.clear_peer_download(peer_id);
 		let expected_hash = sync.peers.get(&peer_id).and_then(|p| p.asking_hash);
@@ -421,8 +468,25 @@ impl SyncHandler {
 		Ok(())
 	}
 
+	fn is_snap_pivot_request(sync: &ChainSync, peer_id: PeerId) -> bool {
+		sync.peers.get(&peer_id).map_or(false, |peer| peer.asking == PeerAsking::SnapPivot)
+	}
+
+	/// Called by peer once it has sent a part of the block whose state is downloaded with `snap`
+	fn on_snap_pivot(sync: &mut ChainSync, peer_id: PeerId, packet_id: SyncPacket, r: &Rlp) -> Result<(), DownloaderImportError> {
+		if !sync.reset_peer_asking(peer_id, PeerAsking::SnapPivot) || sync.state != SyncState::SnapSync {
+			trace!(target: "snap_sync", "{}: Ignored unexpected pivot data", peer_id);
+			return Ok(());
+		}
+		sync.snap.on_pivot_response(peer_id, packet_id, r).map_err(|_| DownloaderImportError::Invalid)
+	}
+
 	/// Called by peer once it has new block receipts
 	fn on_peer_block_receipts(sync: &mut ChainSync, io: &mut dyn SyncIo, peer_id: PeerId, r: &Rlp) -> Result<(), DownloaderImportError> {
+		if SyncHandler::is_snap_pivot_request(sync, peer_id) {
+			return SyncHandler::on_snap_pivot(sync, peer_id, ReceiptsPacket, r);
+		}
+
 		sync.clear_peer_download(peer_id);
 		let block_set = sync.peers.get(&peer_id).and_then(|p| p.block_set).unwrap_or(BlockSet::NewBlocks);
 		let allowed = sync.peers.get(&peer_id).map(|p| p.is_allowed()).unwrap_or(false);
@@ -567,7 +631,7 @@ impl SyncHandler {
 		let warp_protocol_version = io.protocol_version(&WARP_SYNC_PROTOCOL_ID, peer_id);
 		let warp_protocol = warp_protocol_version != 0;
 		let private_tx_protocol = warp_protocol_version >= PAR_PROTOCOL_VERSION_3.0;
-		let peer = PeerInfo {
+		let mut peer = PeerInfo {
 			protocol_version,
 			network_id: r.val_at(1)?,
 			difficulty: Some(r.val_at(2)?),
@@ -583,6 +647,12 @@ impl SyncHandler {
 			expired: false,
 			confirmation: if sync.fork_block.is_none() { ForkConfirmation::Confirmed } else { ForkConfirmation::Unconfirmed },
 			asking_snapshot_data: None,
+			asking_pooled_transactions: Vec::new(),
+			unfetched_pooled_transactions: Default::default(),
+			request_ids: io.eth_protocol_version(peer_id) >= ETH_PROTOCOL_VERSION_66.0,
+			request_id: None,
+			next_request_id: 0,
+			pipelined_requests: BTreeMap::new(),
 			snapshot_hash: if warp_protocol { Some(r.val_at(5)?) } else { None },
 			snapshot_number: if warp_protocol { Some(r.val_at(6)?) } else { None },
 			block_set: None,
@@ -630,12 +700,28 @@ impl SyncHandler {
 
 		if false
 			|| (warp_protocol && (peer.protocol_version < PAR_PROTOCOL_VERSION_1.0 || peer.protocol_version > PAR_PROTOCOL_VERSION_4.0))
-			|| (!warp_protocol && (peer.protocol_version < ETH_PROTOCOL_VERSION_62.0 || peer.protocol_version > ETH_PROTOCOL_VERSION_63.0))
+			|| (!warp_protocol && (peer.protocol_version < ETH_PROTOCOL_VERSION_62.0 || peer.protocol_version > ETH_PROTOCOL_VERSION_66.0))
 		{
 			trace!(target: "sync", "Peer {} unsupported eth protocol ({})", peer_id, peer.protocol_version);
 			return Err(DownloaderImportError::Invalid);
 		}
 
+		if !warp_protocol && peer.protocol_version >= ETH_PROTOCOL_VERSION_64.0 {
+			let fork_id: ForkId = r.val_at(5)?;
+			match sync.fork_filter.validate(chain_info.best_block_number, &fork_id) {
+				Ok(()) => {},
+				Err(ForkIdError::RemoteStale) => {
+					debug!(target: "sync", "Peer {} is stale (ours: {:?}, theirs: {:?})", peer_id, sync.fork_filter.current(chain_info.best_block_number), fork_id);
+					peer.confirmation = ForkConfirmation::Stale;
+				},
+				Err(ForkIdError::LocalIncompatibleOrStale) => {
+					trace!(target: "sync", "Peer {} fork id mismatch (ours: {:?}, theirs: {:?})", peer_id, sync.fork_filter.current(chain_info.best_block_number), fork_id);
+					return Err(DownloaderImportError::Invalid);
+				},
+			}
+		}
+		let stale = peer.confirmation == ForkConfirmation::Stale;
+
 		if sync.sync_start_time.is_none() {
 			sync.sync_start_time = Some(Instant::now());
 		}
@@ -646,8 +732,9 @@ impl SyncHandler {
 		sync.active_peers.insert(peer_id.clone());
 		debug!(target: "sync", "Connected {}:{}", peer_id, io.peer_version(peer_id));
 
-		if let Some((fork_block, _)) = sync.fork_block {
-			SyncRequester::request_fork_header(sync, io, peer_id, fork_block);
+		match sync.fork_block {
+			Some((fork_block, _)) if !stale => SyncRequester::request_fork_header(sync, io, peer_id, fork_block),
+			_ => {},
 		}
 
 		Ok(())
@@ -677,6 +764,83 @@ impl SyncHandler {
 		Ok(())
 	}
 
+	/// Called when peer announces hashes of transactions in its pool
+	fn on_peer_new_pooled_transaction_hashes(sync: &mut ChainSync, io: &mut dyn SyncIo, peer_id: PeerId, r: &Rlp) -> Result<(), DownloaderImportError> {
+		// Fetch transactions only when fully synced
+		if !io.is_chain_queue_empty() || (sync.state != SyncState::Idle && sync.state != SyncState::NewBlocks) {
+			trace!(target: "sync", "{} Ignoring transaction announcements while syncing", peer_id);
+			return Ok(());
+		}
+		let peer = match sync.peers.get_mut(&peer_id) {
+			Some(peer) if peer.can_sync() => peer,
+			_ => {
+				trace!(target: "sync", "{} Ignoring transaction announcements from unconfirmed/unknown peer", peer_id);
+				return Ok(());
+			}
+		};
+
+		let item_count = r.item_count()?;
+		trace!(target: "sync", "{:02} -> NewPooledTransactionHashes ({} entries)", peer_id, item_count);
+		for i in 0..item_count {
+			let hash: H256 = r.val_at(i)?;
+			// the peer knows about this transaction, don't send it back
+			peer.last_sent_transactions.insert(hash);
+			if peer.unfetched_pooled_transactions.len() < MAX_UNFETCHED_POOLED_TRANSACTIONS
+				&& !peer.asking_pooled_transactions.contains(&hash)
+				&& io.chain().pooled_transaction(&hash).is_none()
+			{
+				peer.unfetched_pooled_transactions.insert(hash);
+			}
+		}
+		Ok(())
+	}
+
+	/// Called when peer sends us transactions we requested from its pool
+	fn on_peer_pooled_transactions(sync: &mut ChainSync, io: &mut dyn SyncIo, peer_id: PeerId, r: &Rlp) -> Result<(), DownloaderImportError> {
+		let mut requested: H256FastSet = match sync.peers.get_mut(&peer_id) {
+			Some(peer) => mem::replace(&mut peer.asking_pooled_transactions, Vec::new()).into_iter().collect(),
+			None => return Ok(()),
+		};
+		if !sync.reset_peer_asking(peer_id, PeerAsking::PooledTransactions) {
+			trace!(target: "sync", "{}: Ignored unexpected pooled transactions", peer_id);
+			return Ok(());
+		}
+
+		let item_count = r.item_count()?;
+		trace!(target: "sync", "{:02} -> PooledTransactions ({} entries)", peer_id, item_count);
+		if item_count > requested.len() {
+			debug!(target: "sync", "{}: Peer sent {} pooled transactions, {} requested", peer_id, item_count, requested.len());
+			return Err(DownloaderImportError::Invalid);
+		}
+
+		let mut transactions = Vec::with_capacity(item_count);
+		let mut unsolicited = 0;
+		for i in 0..item_count {
+			let item = r.at(i)?;
+			// typed transactions are hashed without the RLP string header
+			let hash = match item.is_list() {
+				true => keccak(item.as_raw()),
+				false => keccak(item.data()?),
+			};
+			match requested.remove(&hash) {
+				true => transactions.push(item.as_raw().to_vec()),
+				false => unsolicited += 1,
+			}
+		}
+
+		if unsolicited > 0 {
+			debug!(target: "sync", "{}: Peer sent {} pooled transactions we didn't request", peer_id, unsolicited);
+			return Err(DownloaderImportError::Invalid);
+		}
+		if !io.is_chain_queue_empty() || (sync.state != SyncState::Idle && sync.state != SyncState::NewBlocks) {
+			trace!(target: "sync", "{} Ignoring pooled transactions while syncing", peer_id);
+			return Ok(());
+		}
+
+		io.chain().queue_transactions(transactions, peer_id);
+		Ok(())
+	}
+
 	/// Called when peer sends us signed private transaction packet
 	fn on_signed_private_transaction(sync: &mut ChainSync, _io: &mut dyn SyncIo, peer_id: PeerId, r: &Rlp) -> Result<(), DownloaderImportError> {
 		if !sync.peers.get(&peer_id).map_or(false, |p| p.can_sync()) {
@@ -792,15 +956,18 @@ mod tests {
 
 	use super::{
 		super::tests::{dummy_sync_with_peer, get_dummy_block, get_dummy_blocks, get_dummy_hashes},
-		SyncHandler
+		PacketInfo, PeerAsking, SyncHandler,
+		SyncPacket::{GetPooledTransactionsPacket, NewPooledTransactionHashesPacket, PooledTransactionsPacket},
 	};
 
 	use crate::tests::{helpers::TestIo, snapshot::TestSnapshotService};
 
 	use client_traits::ChainInfo;
+	use common_types::transaction::{AccessListTx, Action, Transaction, TypedTransaction};
 	use ethcore::test_helpers::{EachBlockWith, TestBlockChainClient};
+	use ethereum_types::{Address, H256};
 	use parking_lot::RwLock;
-	use rlp::Rlp;
+	use rlp::{Rlp, RlpStream};
 
 	#[test]
 	fn handles_peer_new_hashes() {
@@ -889,4 +1056,74 @@ mod tests {
 
 		assert!(result.is_ok());
 	}
+
+	#[test]
+	fn requests_announced_pooled_transactions() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(10, EachBlockWith::Uncle);
+		let known_hash = client.insert_transaction_to_queue();
+		let unknown_hash = H256::from_low_u64_be(1);
+		let queue = RwLock::new(VecDeque::new());
+		let mut sync = dummy_sync_with_peer(client.block_hash_delta_minus(5), &client);
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &queue, None, None);
+
+		let mut announcement = RlpStream::new_list(2);
+		announcement.append(&known_hash);
+		announcement.append(&unknown_hash);
+		SyncHandler::on_packet(&mut sync, &mut io, 0, NewPooledTransactionHashesPacket.id(), &announcement.out());
+
+		// only the transaction missing from our pool is requested
+		assert_eq!(1, io.packets.len());
+		assert_eq!(GetPooledTransactionsPacket.id(), io.packets[0].packet_id);
+		assert_eq!(Rlp::new(&io.packets[0].data).as_list::<H256>().unwrap(), vec![unknown_hash]);
+		assert_eq!(sync.peers[&0].asking, PeerAsking::PooledTransactions);
+
+		// the peer may not have the transaction anymore
+		SyncHandler::on_packet(&mut sync, &mut io, 0, PooledTransactionsPacket.id(), &rlp::EMPTY_LIST_RLP);
+		assert_eq!(sync.peers[&0].asking, PeerAsking::Nothing);
+		assert!(sync.peers[&0].unfetched_pooled_transactions.is_empty());
+		assert!(io.to_disconnect.is_empty());
+	}
+
+	#[test]
+	fn disables_peer_sending_unrequested_pooled_transactions() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(10, EachBlockWith::Uncle);
+		let queue = RwLock::new(VecDeque::new());
+		let mut sync = dummy_sync_with_peer(client.block_hash_delta_minus(5), &client);
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &queue, None, None);
+
+		let transaction = TypedTransaction::AccessList(AccessListTx::new(Transaction {
+			action: Action::Create,
+			nonce: 0.into(),
+			gas_price: 0.into(),
+			gas: 21_000.into(),
+			value: 0.into(),
+			data: vec![],
+		}, vec![])).fake_sign(Address::from_low_u64_be(1));
+		let mut response = RlpStream::new_list(1);
+		response.append(&transaction);
+		let response = response.out();
+
+		let announce = |hash: H256| {
+			let mut announcement = RlpStream::new_list(1);
+			announcement.append(&hash);
+			announcement.out()
+		};
+
+		// the requested transaction is accepted
+		SyncHandler::on_packet(&mut sync, &mut io, 0, NewPooledTransactionHashesPacket.id(), &announce(transaction.hash()));
+		assert_eq!(sync.peers[&0].asking, PeerAsking::PooledTransactions);
+		SyncHandler::on_packet(&mut sync, &mut io, 0, PooledTransactionsPacket.id(), &response);
+		assert_eq!(sync.peers[&0].asking, PeerAsking::Nothing);
+		assert!(io.to_disconnect.is_empty());
+
+		// a transaction we didn't ask for is not
+		SyncHandler::on_packet(&mut sync, &mut io, 0, NewPooledTransactionHashesPacket.id(), &announce(H256::from_low_u64_be(1)));
+		assert_eq!(sync.peers[&0].asking, PeerAsking::PooledTransactions);
+		SyncHandler::on_packet(&mut sync, &mut io, 0, PooledTransactionsPacket.id(), &response);
+		assert!(io.to_disconnect.contains(&0));
+	}
 }
@@ -16,7 +16,7 @@
 
 //! `BlockChain` synchronization strategy.
 //! Syncs to peers and keeps up to date.
-//! This implementation uses ethereum protocol v63
+//! This implementation uses ethereum protocol v66
 //!
 //! Syncing strategy summary.
 //! Split the chain into ranges of N blocks each. Download ranges sequentially. Split each range into subchains of M blocks. Download subchains in parallel.
@@ -87,6 +87,7 @@
 //!
 //! All other messages are ignored.
 
+mod fork_filter;
 mod handler;
 mod propagator;
 mod requester;
@@ -96,15 +97,16 @@ pub mod sync_packet;
 
 use std::sync::{Arc, mpsc};
 use std::collections::{HashSet, HashMap, BTreeMap};
-use std::cmp;
+use std::{cmp, mem};
 use std::time::{Duration, Instant};
 
 use crate::{
 	EthProtocolInfo as PeerInfoDigest, PriorityTask, SyncConfig, WarpSync, WARP_SYNC_PROTOCOL_ID,
-	api::{Notification, PRIORITY_TIMER_INTERVAL},
+	api::{Notification, PRIORITY_TIMER_INTERVAL, SNAP_PROTOCOL},
 	block_sync::{BlockDownloader, DownloadAction},
 	sync_io::SyncIo,
 	snapshot_sync::Snapshot,
+	snap_sync::{SnapError, SnapSync},
 	transactions_stats::{TransactionsStats, Stats as TransactionStats},
 	private_tx::PrivateTxHandler,
 };
@@ -115,13 +117,13 @@ use ethereum_types::{H256, U256};
 use fastmap::{H256FastMap, H256FastSet};
 use futures::sync::mpsc as futures_mpsc;
 use keccak_hash::keccak;
-use log::{error, trace, debug, warn};
+use log::{error, trace, debug, info, warn};
 use network::client_version::ClientVersion;
 use network::{self, PeerId, PacketId};
 use parity_util_mem::{MallocSizeOfExt, malloc_size_of_is_0};
 use parking_lot::{Mutex, RwLock, RwLockWriteGuard};
 use rand::{Rng, seq::SliceRandom};
-use rlp::{RlpStream, DecoderError};
+use rlp::{Rlp, RlpStream, DecoderError};
 use common_types::{
 	BlockNumber,
 	ids::BlockId,
@@ -132,8 +134,9 @@ use common_types::{
 	snapshot::RestorationStatus,
 };
 
+use self::fork_filter::{ForkFilter, ForkId};
 use self::handler::SyncHandler;
-use self::sync_packet::{PacketInfo, SyncPacket};
+use self::sync_packet::{PacketInfo, SnapPacket, SyncPacket};
 use self::sync_packet::SyncPacket::{
 	NewBlockPacket,
 	StatusPacket,
@@ -147,6 +150,12 @@ malloc_size_of_is_0!(PeerInfo);
 
 pub type PacketDecodeError = DecoderError;
 
+/// 66 version of Ethereum protocol (request identifiers added to requests and responses).
+pub const ETH_PROTOCOL_VERSION_66: (u8, u8) = (66, 0x11);
+/// 65 version of Ethereum protocol (transaction announcements and pooled transactions retrieval).
+pub const ETH_PROTOCOL_VERSION_65: (u8, u8) = (65, 0x11);
+/// 64 version of Ethereum protocol (EIP-2124 fork id added to `Status`).
+pub const ETH_PROTOCOL_VERSION_64: (u8, u8) = (64, 0x11);
 /// 63 version of Ethereum protocol.
 pub const ETH_PROTOCOL_VERSION_63: (u8, u8) = (63, 0x11);
 /// 62 version of Ethereum protocol.
@@ -159,6 +168,8 @@ pub const PAR_PROTOCOL_VERSION_2: (u8, u8) = (2, 0x16);
 pub const PAR_PROTOCOL_VERSION_3: (u8, u8) = (3, 0x18);
 /// 4 version of Parity protocol (private state sync added).
 pub const PAR_PROTOCOL_VERSION_4: (u8, u8) = (4, 0x20);
+/// 1 version of the snap protocol and the packet count.
+pub const SNAP_PROTOCOL_VERSION_1: (u8, u8) = (1, 0x08);
 
 pub const MAX_BODIES_TO_SEND: usize = 256;
 pub const MAX_HEADERS_TO_SEND: usize = 512;
@@ -169,6 +180,18 @@ const MAX_NODE_DATA_TOTAL_DURATION: Duration = Duration::from_secs(2);
 /// Maximum allowed duration for serving a single GetNodeData request.
 const MAX_NODE_DATA_SINGLE_DURATION: Duration = Duration::from_millis(100);
 pub const MAX_RECEIPTS_HEADERS_TO_SEND: usize = 256;
+/// Maximum number of transactions returned in a single PooledTransactions packet.
+pub const MAX_POOLED_TRANSACTIONS_TO_SEND: usize = 256;
+/// Maximum number of transactions requested in a single GetPooledTransactions packet.
+const MAX_POOLED_TRANSACTIONS_TO_REQUEST: usize = 256;
+/// Maximum number of hashes announced in a single NewPooledTransactionHashes packet.
+const MAX_TRANSACTION_HASHES_TO_ANNOUNCE: usize = 4096;
+/// Maximum number of announced transactions waiting to be requested from a single peer.
+const MAX_UNFETCHED_POOLED_TRANSACTIONS: usize = 4096;
+/// Maximum number of code owners remembered to serve `GetByteCodes`.
+const MAX_SNAP_CODE_OWNERS: usize = 65536;
+/// Maximum number of requests in flight to a peer supporting request identifiers.
+const MAX_PIPELINED_REQUESTS: usize = 3;
 const MIN_PEERS_PROPAGATION: usize = 4;
 const MAX_PEERS_PROPAGATION: usize = 128;
 const MAX_PEER_LAG_PROPAGATION: BlockNumber = 20;
@@ -203,6 +226,8 @@ const FORK_HEADER_TIMEOUT: Duration = Duration::from_secs(3);
 const SNAPSHOT_MANIFEST_TIMEOUT: Duration = Duration::from_secs(5);
 const SNAPSHOT_DATA_TIMEOUT: Duration = Duration::from_secs(120);
 const PRIVATE_STATE_TIMEOUT: Duration = Duration::from_secs(120);
+const POOLED_TRANSACTIONS_TIMEOUT: Duration = Duration::from_secs(5);
+const SNAP_PIVOT_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// Defines how much time we have to complete priority transaction or block propagation.
 /// after the deadline is reached the task is considered finished
@@ -220,6 +245,8 @@ pub enum SyncState {
 	SnapshotData,
 	/// Waiting for snapshot restoration progress.
 	SnapshotWaiting,
+	/// Downloading the state of a recent block with the `snap` protocol
+	SnapSync,
 	/// Downloading new blocks
 	Blocks,
 	/// Initial chain sync complete. Waiting for new packets
@@ -302,6 +329,34 @@ pub enum PeerAsking {
 	SnapshotManifest,
 	SnapshotData,
 	PrivateState,
+	PooledTransactions,
+	SnapPivot,
+}
+
+impl PeerAsking {
+	/// Whether the request is part of the eth protocol, such requests carry an identifier since eth/66.
+	fn is_eth_request(&self) -> bool {
+		match *self {
+			PeerAsking::ForkHeader |
+			PeerAsking::BlockHeaders |
+			PeerAsking::BlockBodies |
+			PeerAsking::BlockReceipts |
+			PeerAsking::PooledTransactions |
+			PeerAsking::SnapPivot => true,
+			_ => false,
+		}
+	}
+}
+
+#[derive(Clone, Debug)]
+/// Request kept in flight while other requests are sent to the same peer.
+struct PeerRequest {
+	asking: PeerAsking,
+	asking_blocks: Vec<H256>,
+	asking_hash: Option<H256>,
+	asking_pooled_transactions: Vec<H256>,
+	block_set: Option<BlockSet>,
+	ask_time: Instant,
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy, MallocSizeOf)]
@@ -318,6 +373,8 @@ pub enum ForkConfirmation {
 	Unconfirmed,
 	/// Peer's chain is too short to confirm the fork.
 	TooShort,
+	/// Peer is on our chain but its fork id misses a fork we already passed.
+	Stale,
 	/// Fork is confirmed.
 	Confirmed,
 }
@@ -345,6 +402,18 @@ pub struct PeerInfo {
 	asking_private_state: Option<H256>,
 	/// Holds requested snapshot chunk hash if any.
 	asking_snapshot_data: Option<H256>,
+	/// Hashes of the pooled transactions being requested
+	asking_pooled_transactions: Vec<H256>,
+	/// Transactions announced by the peer which we haven't requested yet
+	unfetched_pooled_transactions: H256FastSet,
+	/// Peer supports eth/66 request identifiers
+	request_ids: bool,
+	/// Identifier of the current request, if it was sent with one
+	request_id: Option<u64>,
+	/// Identifier of the next request sent to the peer
+	next_request_id: u64,
+	/// Other requests in flight by their identifiers, while the peer is pipelining requests
+	pipelined_requests: BTreeMap<u64, PeerRequest>,
 	/// Request timestamp
 	ask_time: Instant,
 	/// Holds a set of transactions recently sent to this peer to avoid spamming.
@@ -380,12 +449,70 @@ impl PeerInfo {
 		self.asking_blocks.clear();
 		self.asking_hash = None;
 		self.asking_private_state = None;
+		self.asking_pooled_transactions.clear();
+		// a late response won't match any request id, no need to wait for it
+		if self.request_id.take().is_some() {
+			self.asking = PeerAsking::Nothing;
+		}
 		// mark any pending requests as expired
 		if self.asking != PeerAsking::Nothing && self.is_allowed() {
 			self.expired = true;
 		}
 	}
 
+	/// Whether we can send a new request to the peer, either because it's idle or because
+	/// the current request can be kept in flight.
+	fn can_request(&self) -> bool {
+		self.asking == PeerAsking::Nothing || self.can_pipeline()
+	}
+
+	/// Whether a new request can be sent while the current one is in flight.
+	fn can_pipeline(&self) -> bool {
+		self.request_id.is_some() && self.asking.is_eth_request() && self.pipelined_requests.len() + 1 < MAX_PIPELINED_REQUESTS
+	}
+
+	/// Keeps the current request in flight in `pipelined_requests` to make room for a new one.
+	fn park_request(&mut self) {
+		if let Some(request_id) = self.request_id.take() {
+			if self.asking != PeerAsking::Nothing {
+				self.pipelined_requests.insert(request_id, PeerRequest {
+					asking: mem::replace(&mut self.asking, PeerAsking::Nothing),
+					asking_blocks: mem::replace(&mut self.asking_blocks, Vec::new()),
+					asking_hash: self.asking_hash.take(),
+					asking_pooled_transactions: mem::replace(&mut self.asking_pooled_transactions, Vec::new()),
+					block_set: self.block_set.take(),
+					ask_time: self.ask_time,
+				});
+			}
+		}
+	}
+
+	/// Makes the request with the given identifier the current one, keeping the current one in flight.
+	/// Returns `false` if there is no such request, the response is then late or unexpected.
+	fn activate_request(&mut self, request_id: u64) -> bool {
+		if self.request_id == Some(request_id) && self.asking != PeerAsking::Nothing {
+			return true;
+		}
+		let request = match self.pipelined_requests.remove(&request_id) {
+			Some(request) => request,
+			None => return false,
+		};
+		self.park_request();
+		self.request_id = Some(request_id);
+		self.asking = request.asking;
+		self.asking_blocks = request.asking_blocks;
+		self.asking_hash = request.asking_hash;
+		self.asking_pooled_transactions = request.asking_pooled_transactions;
+		self.block_set = request.block_set;
+		self.ask_time = request.ask_time;
+		true
+	}
+
+	/// Drops the requests kept in flight for the given block set, their responses will be ignored.
+	fn reset_pipelined_requests(&mut self, block_set: BlockSet) {
+		self.pipelined_requests.retain(|_, request| request.block_set != Some(block_set));
+	}
+
 	fn reset_private_stats(&mut self) {
 		self.last_sent_private_transactions.clear();
 	}
@@ -460,11 +587,30 @@ impl ChainSyncApi {
 			.collect()
 	}
 
+	/// Returns the `eth` entry of our node record, announcing the fork id at the current head.
+	pub fn enr_entry(&self, chain: &dyn BlockChainClient) -> Bytes {
+		let fork_id = self.sync.read().fork_filter.current(chain.chain_info().best_block_number);
+		rlp::encode_list::<ForkId, _>(&[fork_id])
+	}
+
+	/// Checks the `eth` entry of a node record, i.e. whether the announced fork id is compatible with our chain.
+	pub fn is_compatible_enr(&self, chain: &dyn BlockChainClient, entry: &Rlp) -> bool {
+		match entry.val_at::<ForkId>(0) {
+			Ok(fork_id) => self.sync.read().fork_filter.validate(chain.chain_info().best_block_number, &fork_id).is_ok(),
+			Err(_) => false,
+		}
+	}
+
 	/// Dispatch incoming requests and responses
 	pub fn dispatch_packet(&self, io: &mut dyn SyncIo, peer: PeerId, packet_id: u8, data: &[u8]) {
 		SyncSupplier::dispatch_packet(&self.sync, io, peer, packet_id, data)
 	}
 
+	/// Dispatch incoming `snap` requests and responses
+	pub fn dispatch_snap_packet(&self, io: &mut dyn SyncIo, peer: PeerId, packet_id: u8, data: &[u8]) {
+		SyncSupplier::dispatch_snap_packet(&self.sync, io, peer, packet_id, data)
+	}
+
 	/// Process a priority propagation queue.
 	/// This task is run from a timer and should be time constrained.
 	/// Hence we set up a deadline for the execution and cancel the task if the deadline is exceeded.
@@ -610,9 +756,14 @@ impl ChainSync {
 	///  - if warp sync is enabled, start looking for peers to sync a snapshot from
 	///  - if `--warp-barrier` is used, ensure we're not synced beyond the barrier and start
 	///    looking for peers to sync a snapshot from
+	///  - if snap sync is enabled and the chain is empty, download the state of a recent block
 	///  - otherwise, go `Idle`.
-	fn get_init_state(warp_sync: WarpSync, chain: &dyn BlockChainClient) -> SyncState {
+	fn get_init_state(warp_sync: WarpSync, snap_sync: bool, chain: &dyn BlockChainClient) -> SyncState {
 		let best_block = chain.chain_info().best_block_number;
+		if snap_sync && best_block == 0 {
+			debug!(target: "sync", "Setting the initial state to `SnapSync`.");
+			return SyncState::SnapSync;
+		}
 		match warp_sync {
 			WarpSync::Enabled => {
 				debug!(target: "sync", "Setting the initial state to `WaitingPeers`. Our best block: #{}; warp_sync: {:?}", best_block, warp_sync);
@@ -662,6 +813,11 @@ pub struct ChainSync {
 	network_id: u64,
 	/// Optional fork block to check
 	fork_block: Option<(BlockNumber, H256)>,
+	/// Fork id (EIP-2124) calculator and validator.
+	#[ignore_malloc_size_of = "only a few checksums and block numbers"]
+	fork_filter: ForkFilter,
+	/// Block number from which headers carry the EIP-1559 base fee.
+	eip1559_transition: BlockNumber,
 	/// Snapshot downloader.
 	snapshot: Snapshot,
 	/// Connected peers pending Status message.
@@ -678,6 +834,13 @@ pub struct ChainSync {
 	private_tx_handler: Option<Arc<dyn PrivateTxHandler>>,
 	/// Enable warp sync.
 	warp_sync: WarpSync,
+	/// Enable state download with the `snap` protocol.
+	snap_sync: bool,
+	/// State downloader for the `snap` protocol.
+	#[ignore_malloc_size_of = "the download is bounded by the request limits"]
+	snap: SnapSync,
+	/// Accounts found owning a code, to look up codes requested by hash.
+	snap_code_owners: H256FastMap<H256>,
 
 	#[ignore_malloc_size_of = "mpsc unmettered, ignoring"]
 	status_sinks: Vec<futures_mpsc::UnboundedSender<SyncState>>
@@ -692,7 +855,7 @@ impl ChainSync {
 	) -> Self {
 		let chain_info = chain.chain_info();
 		let best_block = chain.chain_info().best_block_number;
-		let state = Self::get_init_state(config.warp_sync, chain);
+		let state = Self::get_init_state(config.warp_sync, config.snap_sync, chain);
 
 		let mut sync = ChainSync {
 			state,
@@ -701,17 +864,27 @@ impl ChainSync {
 			peers: HashMap::new(),
 			handshaking_peers: HashMap::new(),
 			active_peers: HashSet::new(),
-			new_blocks: BlockDownloader::new(BlockSet::NewBlocks, &chain_info.best_block_hash, chain_info.best_block_number),
+			new_blocks: BlockDownloader::new(
+				BlockSet::NewBlocks,
+				&chain_info.best_block_hash,
+				chain_info.best_block_number,
+				config.eip1559_transition,
+			),
 			old_blocks: None,
 			last_sent_block_number: 0,
 			network_id: config.network_id,
 			fork_block: config.fork_block,
+			fork_filter: ForkFilter::new(chain_info.genesis_hash, &config.hard_forks),
+			eip1559_transition: config.eip1559_transition,
 			download_old_blocks: config.download_old_blocks,
 			snapshot: Snapshot::new(),
 			sync_start_time: None,
 			transactions_stats: TransactionsStats::default(),
 			private_tx_handler,
 			warp_sync: config.warp_sync,
+			snap_sync: config.snap_sync,
+			snap: SnapSync::new(config.eip1559_transition),
+			snap_code_owners: H256FastMap::default(),
 			status_sinks: Vec::new()
 		};
 		sync.update_targets(chain);
@@ -723,7 +896,7 @@ impl ChainSync {
 		let last_imported_number = self.new_blocks.last_imported_block_number();
 		SyncStatus {
 			state: self.state.clone(),
-			protocol_version: ETH_PROTOCOL_VERSION_63.0,
+			protocol_version: ETH_PROTOCOL_VERSION_66.0,
 			network_id: self.network_id,
 			start_block_number: self.starting_block,
 			last_imported_block_number: Some(last_imported_number),
@@ -797,6 +970,7 @@ impl ChainSync {
 		self.new_blocks.reset();
 		let chain_info = io.chain().chain_info();
 		for (_, mut p) in &mut self.peers {
+			p.reset_pipelined_requests(BlockSet::NewBlocks);
 			if p.block_set != Some(BlockSet::OldBlocks) {
 				p.reset_asking();
 				if p.difficulty.is_none() {
@@ -806,9 +980,13 @@ impl ChainSync {
 			}
 		}
 
+		// the requests for the pivot block were dropped along with the peers' requests
+		self.snap.clear_pivot_request();
+
 		let warp_sync = self.warp_sync;
+		let snap_sync = self.snap_sync;
 
-		self.set_state(state.unwrap_or_else(|| Self::get_init_state(warp_sync, io.chain())));
+		self.set_state(state.unwrap_or_else(|| Self::get_init_state(warp_sync, snap_sync, io.chain())));
 		// Reactivate peers only if some progress has been made
 		// since the last sync round of if starting fresh.
 		self.active_peers = self.peers.keys().cloned().collect();
@@ -968,13 +1146,23 @@ impl ChainSync {
 	pub fn update_targets(&mut self, chain: &dyn BlockChainClient) {
 		// Do not assume that the block queue/chain still has our last_imported_block
 		let chain = chain.chain_info();
-		self.new_blocks = BlockDownloader::new(BlockSet::NewBlocks, &chain.best_block_hash, chain.best_block_number);
+		self.new_blocks = BlockDownloader::new(
+			BlockSet::NewBlocks,
+			&chain.best_block_hash,
+			chain.best_block_number,
+			self.eip1559_transition,
+		);
 		self.old_blocks = None;
 		if self.download_old_blocks {
 			if let (Some(ancient_block_hash), Some(ancient_block_number)) = (chain.ancient_block_hash, chain.ancient_block_number) {
 
 				trace!(target: "sync", "Downloading old blocks from {:?} (#{}) till {:?} (#{:?})", ancient_block_hash, ancient_block_number, chain.first_block_hash, chain.first_block_number);
-				let mut downloader = BlockDownloader::new(BlockSet::OldBlocks, &ancient_block_hash, ancient_block_number);
+				let mut downloader = BlockDownloader::new(
+					BlockSet::OldBlocks,
+					&ancient_block_hash,
+					ancient_block_number,
+					self.eip1559_transition,
+				);
 				if let Some(hash) = chain.first_block_hash {
 					trace!(target: "sync", "Downloader target set to {:?}", hash);
 					downloader.set_target(&hash);
@@ -994,7 +1182,7 @@ impl ChainSync {
 		} else {
 			// Collect active peers that can sync
 			let mut peers: Vec<(PeerId, u8)> = self.peers.iter().filter_map(|(peer_id, peer)|
-				if peer.can_sync() && peer.asking == PeerAsking::Nothing && self.active_peers.contains(&peer_id) {
+				if peer.can_sync() && peer.can_request() && self.active_peers.contains(&peer_id) {
 					Some((*peer_id, peer.protocol_version))
 				} else {
 					None
@@ -1014,12 +1202,16 @@ impl ChainSync {
 
 				for (peer_id, _) in peers {
 					self.sync_peer(io, peer_id, false);
+					SyncRequester::request_pooled_transactions(self, io, peer_id);
 				}
 			}
 		}
 
 		if (self.state == SyncState::Blocks || self.state == SyncState::NewBlocks)
-			&& !self.peers.values().any(|p| p.asking != PeerAsking::Nothing && p.block_set != Some(BlockSet::OldBlocks) && p.can_sync())
+			&& !self.peers.values().any(|p| p.can_sync() && (
+				(p.asking != PeerAsking::Nothing && p.block_set != Some(BlockSet::OldBlocks))
+				|| p.pipelined_requests.values().any(|r| r.block_set != Some(BlockSet::OldBlocks))
+			))
 		{
 			self.complete_sync(io);
 		}
@@ -1043,17 +1235,22 @@ impl ChainSync {
 			trace!(target: "sync", "Skipping deactivated peer {}", peer_id);
 			return;
 		}
-		let (peer_latest, peer_difficulty, peer_snapshot_number, peer_snapshot_hash) = {
+		let (peer_latest, peer_difficulty, peer_snapshot_number, peer_snapshot_hash, pipelined) = {
 			if let Some(peer) = self.peers.get_mut(&peer_id) {
-				if peer.asking != PeerAsking::Nothing || !peer.can_sync() {
+				if !peer.can_request() || !peer.can_sync() {
 					trace!(target: "sync", "Skipping busy peer {}", peer_id);
 					return;
 				}
-				(peer.latest_hash.clone(), peer.difficulty.clone(), peer.snapshot_number.as_ref().cloned().unwrap_or(0), peer.snapshot_hash.as_ref().cloned())
+				let pipelined = peer.asking != PeerAsking::Nothing;
+				(peer.latest_hash.clone(), peer.difficulty.clone(), peer.snapshot_number.as_ref().cloned().unwrap_or(0), peer.snapshot_hash.as_ref().cloned(), pipelined)
 			} else {
 				return;
 			}
 		};
+		// only block downloads are pipelined
+		if pipelined && self.state != SyncState::Idle && self.state != SyncState::Blocks && self.state != SyncState::NewBlocks {
+			return;
+		}
 		let chain_info = io.chain().chain_info();
 		let syncing_difficulty = chain_info.pending_total_difficulty;
 		let num_active_peers = self.peers.values().filter(|p| p.asking != PeerAsking::Nothing).count();
@@ -1083,7 +1280,7 @@ impl ChainSync {
 					if !have_latest && (higher_difficulty || force || self.state == SyncState::NewBlocks) {
 						// check if got new blocks to download
 						trace!(target: "sync", "Syncing with peer {}, force={}, td={:?}, our td={}, state={:?}", peer_id, force, peer_difficulty, syncing_difficulty, self.state);
-						if let Some(request) = self.new_blocks.request_blocks(peer_id, io, num_active_peers) {
+						if let Some(request) = self.new_blocks.request_blocks(peer_id, io, num_active_peers, pipelined) {
 							SyncRequester::request_blocks(self, io, peer_id, request, BlockSet::NewBlocks);
 							if self.state == SyncState::Idle {
 								self.set_state(SyncState::Blocks);
@@ -1096,7 +1293,7 @@ impl ChainSync {
 					let equal_or_higher_difficulty = peer_difficulty.map_or(true, |pd| pd >= syncing_difficulty);
 
 					if force || equal_or_higher_difficulty {
-						if let Some(request) = self.old_blocks.as_mut().and_then(|d| d.request_blocks(peer_id, io, num_active_peers)) {
+						if let Some(request) = self.old_blocks.as_mut().and_then(|d| d.request_blocks(peer_id, io, num_active_peers, pipelined)) {
 							SyncRequester::request_blocks(self, io, peer_id, request, BlockSet::OldBlocks);
 							return;
 						}
@@ -1137,6 +1334,7 @@ impl ChainSync {
 						SyncRequester::request_snapshot_data(self, io, peer_id);
 					}
 				},
+				SyncState::SnapSync => self.snap_sync_peer(io, peer_id, peer_latest, peer_difficulty),
 				SyncState::SnapshotManifest | //already downloading from other peer
 					SyncState::Waiting |
 					SyncState::SnapshotWaiting => (),
@@ -1147,40 +1345,139 @@ impl ChainSync {
 		}
 	}
 
+	/// Find some state to download with the `snap` protocol from a peer. The peer with the most
+	/// difficult chain provides the pivot block.
+	fn snap_sync_peer(&mut self, io: &mut dyn SyncIo, peer_id: PeerId, peer_latest: H256, peer_difficulty: Option<U256>) {
+		if io.protocol_version(&SNAP_PROTOCOL, peer_id) == 0 {
+			trace!(target: "snap_sync", "Skipping peer {} without snap support", peer_id);
+			return;
+		}
+		let best_difficulty = self.peers.iter()
+			.filter(|&(id, _)| io.protocol_version(&SNAP_PROTOCOL, *id) != 0)
+			.filter_map(|(_, peer)| peer.difficulty)
+			.max();
+		if peer_difficulty.is_some() && peer_difficulty == best_difficulty {
+			if let Some(request) = self.snap.pivot_request(peer_id, &peer_latest, peer_difficulty) {
+				SyncRequester::request_snap_pivot(self, io, peer_id, request);
+				return;
+			}
+		}
+		if let Some((packet_id, data)) = self.snap.next_request(peer_id) {
+			SyncRequester::request_snap_data(io, peer_id, packet_id, data);
+		}
+	}
+
+	/// Called when a peer answers a `snap` request.
+	pub fn on_snap_packet(&mut self, io: &mut dyn SyncIo, peer_id: PeerId, packet_id: SnapPacket, r: &Rlp) {
+		if self.state != SyncState::SnapSync {
+			trace!(target: "snap_sync", "{}: Ignored {:?} while not downloading the state", peer_id, packet_id);
+			return;
+		}
+		match self.snap.on_response(io, peer_id, packet_id, r) {
+			Ok(()) => {},
+			Err(SnapError::Invalid) => {
+				debug!(target: "snap_sync", "{}: Invalid {:?}", peer_id, packet_id);
+				io.disable_peer(peer_id);
+				self.deactivate_peer(io, peer_id);
+			},
+			Err(SnapError::Import(e)) => {
+				error!(target: "snap_sync", "Error writing the downloaded state: {}", e);
+				self.snap.reset_pivot();
+			},
+		}
+		self.continue_snap_sync(io);
+	}
+
+	/// Remembers the accounts owning the given codes, to serve them by hash.
+	pub fn note_code_owners<I: IntoIterator<Item = (H256, H256)>>(&mut self, owners: I) {
+		for (code_hash, account_hash) in owners {
+			if self.snap_code_owners.len() >= MAX_SNAP_CODE_OWNERS {
+				self.snap_code_owners.clear();
+			}
+			self.snap_code_owners.insert(code_hash, account_hash);
+		}
+	}
+
+	/// Moves on to a new pivot once the peers stopped serving the state of the current one,
+	/// and switches to block sync once the state is complete.
+	fn continue_snap_sync(&mut self, io: &mut dyn SyncIo) {
+		let snap_peers = self.peers.keys().filter(|&&id| io.protocol_version(&SNAP_PROTOCOL, id) != 0).count();
+		if snap_peers > 0 && self.snap.stateless_peers() >= snap_peers {
+			self.snap.reset_pivot();
+		}
+		match self.snap.complete(io) {
+			Ok(true) => {
+				info!(target: "snap_sync", "State download complete");
+				self.snap = SnapSync::new(self.eip1559_transition);
+				self.restart(io);
+				return;
+			},
+			Ok(false) => {},
+			Err(e) => {
+				warn!(target: "snap_sync", "Error importing the downloaded state: {}", e);
+				self.snap.reset_pivot();
+			},
+		}
+		self.continue_sync(io);
+	}
+
 	/// Clear all blocks/headers marked as being downloaded by us from a peer.
 	fn clear_peer_download(&mut self, peer_id: PeerId) {
 		if let Some(peer) = self.peers.get(&peer_id) {
 			match peer.asking {
-				PeerAsking::BlockHeaders => {
-					if let Some(ref hash) = peer.asking_hash {
-						self.new_blocks.clear_header_download(hash);
-						if let Some(ref mut old) = self.old_blocks {
-							old.clear_header_download(hash);
-						}
-					}
-				},
-				PeerAsking::BlockBodies => {
-					self.new_blocks.clear_body_download(&peer.asking_blocks);
-					if let Some(ref mut old) = self.old_blocks {
-						old.clear_body_download(&peer.asking_blocks);
-					}
-				},
-				PeerAsking::BlockReceipts => {
-					self.new_blocks.clear_receipt_download(&peer.asking_blocks);
-					if let Some(ref mut old) = self.old_blocks {
-						old.clear_receipt_download(&peer.asking_blocks);
-					}
-				},
 				PeerAsking::SnapshotData => {
 					if let Some(hash) = peer.asking_snapshot_data {
 						self.snapshot.clear_chunk_download(&hash);
 					}
 				},
-				_ => (),
+				ref asking => {
+					ChainSync::clear_block_download(&mut self.new_blocks, &mut self.old_blocks, asking, &peer.asking_hash, &peer.asking_blocks);
+				},
 			}
 		}
 	}
 
+	/// Clear all blocks/headers marked as being downloaded by the requests kept in flight to a peer.
+	fn clear_pipelined_downloads(&mut self, peer_id: PeerId) {
+		if let Some(peer) = self.peers.get(&peer_id) {
+			for request in peer.pipelined_requests.values() {
+				ChainSync::clear_block_download(&mut self.new_blocks, &mut self.old_blocks, &request.asking, &request.asking_hash, &request.asking_blocks);
+			}
+		}
+	}
+
+	fn clear_block_download(
+		new_blocks: &mut BlockDownloader,
+		old_blocks: &mut Option<BlockDownloader>,
+		asking: &PeerAsking,
+		asking_hash: &Option<H256>,
+		asking_blocks: &[H256],
+	) {
+		match *asking {
+			PeerAsking::BlockHeaders => {
+				if let Some(ref hash) = *asking_hash {
+					new_blocks.clear_header_download(hash);
+					if let Some(ref mut old) = *old_blocks {
+						old.clear_header_download(hash);
+					}
+				}
+			},
+			PeerAsking::BlockBodies => {
+				new_blocks.clear_body_download(asking_blocks);
+				if let Some(ref mut old) = *old_blocks {
+					old.clear_body_download(asking_blocks);
+				}
+			},
+			PeerAsking::BlockReceipts => {
+				new_blocks.clear_receipt_download(asking_blocks);
+				if let Some(ref mut old) = *old_blocks {
+					old.clear_receipt_download(asking_blocks);
+				}
+			},
+			_ => (),
+		}
+	}
+
 	/// Checks if there are blocks fully downloaded that can be imported into the blockchain and does the import.
 	fn collect_blocks(&mut self, io: &mut dyn SyncIo, block_set: BlockSet) {
 		match block_set {
@@ -1216,8 +1513,11 @@ impl ChainSync {
 	/// Mark all outstanding requests as expired
 	fn reset_downloads(&mut self, block_set: BlockSet) {
 		trace!(target: "sync", "Resetting downloads for {:?}", block_set);
-		for (_, ref mut p) in self.peers.iter_mut().filter(|&(_, ref p)| p.block_set == Some(block_set)) {
-			p.reset_asking();
+		for (_, ref mut p) in self.peers.iter_mut() {
+			p.reset_pipelined_requests(block_set);
+			if p.block_set == Some(block_set) {
+				p.reset_asking();
+			}
 		}
 	}
 
@@ -1226,6 +1526,7 @@ impl ChainSync {
 		if let Some(ref mut peer) = self.peers.get_mut(&peer_id) {
 			peer.expired = false;
 			peer.block_set = None;
+			peer.request_id = None;
 			if peer.asking != asking {
 				trace!(target:"sync", "{}: Asking {:?} while expected {:?}", peer_id, peer.asking, asking);
 				peer.asking = PeerAsking::Nothing;
@@ -1243,7 +1544,8 @@ impl ChainSync {
 		let warp_protocol_version = io.protocol_version(&WARP_SYNC_PROTOCOL_ID, peer);
 		let warp_protocol = warp_protocol_version != 0;
 		let private_tx_protocol = warp_protocol_version >= PAR_PROTOCOL_VERSION_3.0;
-		let protocol = if warp_protocol { warp_protocol_version } else { ETH_PROTOCOL_VERSION_63.0 };
+		let eth_protocol_version = io.eth_protocol_version(peer);
+		let protocol = if warp_protocol { warp_protocol_version } else { eth_protocol_version };
 		trace!(target: "sync", "Sending status to {}, protocol version {}", peer, protocol);
 		let mut packet = RlpStream::new();
 		packet.begin_unbounded_list();
@@ -1253,6 +1555,9 @@ impl ChainSync {
 		packet.append(&chain.total_difficulty);
 		packet.append(&chain.best_block_hash);
 		packet.append(&chain.genesis_hash);
+		if !warp_protocol && eth_protocol_version >= ETH_PROTOCOL_VERSION_64.0 {
+			packet.append(&self.fork_filter.current(chain.best_block_number));
+		}
 		if warp_protocol {
 			let manifest = io.snapshot_service().manifest();
 			let block_number = manifest.as_ref().map_or(0, |m| m.block_number);
@@ -1273,24 +1578,32 @@ impl ChainSync {
 	pub fn maintain_peers(&mut self, io: &mut dyn SyncIo) {
 		let tick = Instant::now();
 		let mut aborting = Vec::new();
+		let timed_out = |asking: &PeerAsking, elapsed: Duration| match *asking {
+			PeerAsking::BlockHeaders => elapsed > HEADERS_TIMEOUT,
+			PeerAsking::BlockBodies => elapsed > BODIES_TIMEOUT,
+			PeerAsking::BlockReceipts => elapsed > RECEIPTS_TIMEOUT,
+			PeerAsking::Nothing => false,
+			PeerAsking::ForkHeader => elapsed > FORK_HEADER_TIMEOUT,
+			PeerAsking::SnapshotManifest => elapsed > SNAPSHOT_MANIFEST_TIMEOUT,
+			PeerAsking::SnapshotData => elapsed > SNAPSHOT_DATA_TIMEOUT,
+			PeerAsking::PrivateState => elapsed > PRIVATE_STATE_TIMEOUT,
+			PeerAsking::PooledTransactions => elapsed > POOLED_TRANSACTIONS_TIMEOUT,
+			PeerAsking::SnapPivot => elapsed > SNAP_PIVOT_TIMEOUT,
+		};
 		for (peer_id, peer) in &self.peers {
-			let elapsed = tick - peer.ask_time;
-			let timeout = match peer.asking {
-				PeerAsking::BlockHeaders => elapsed > HEADERS_TIMEOUT,
-				PeerAsking::BlockBodies => elapsed > BODIES_TIMEOUT,
-				PeerAsking::BlockReceipts => elapsed > RECEIPTS_TIMEOUT,
-				PeerAsking::Nothing => false,
-				PeerAsking::ForkHeader => elapsed > FORK_HEADER_TIMEOUT,
-				PeerAsking::SnapshotManifest => elapsed > SNAPSHOT_MANIFEST_TIMEOUT,
-				PeerAsking::SnapshotData => elapsed > SNAPSHOT_DATA_TIMEOUT,
-				PeerAsking::PrivateState => elapsed > PRIVATE_STATE_TIMEOUT,
-			};
+			let timeout = timed_out(&peer.asking, tick - peer.ask_time)
+				|| peer.pipelined_requests.values().any(|r| timed_out(&r.asking, tick - r.ask_time));
 			if timeout {
 				debug!(target:"sync", "Peer {} timeout while we were asking them for {:?}; disconnecting.", peer_id, peer.asking);
 				io.disconnect_peer(*peer_id);
 				aborting.push(*peer_id);
 			}
 		}
+		for peer_id in self.snap.timed_out_peers(tick) {
+			debug!(target: "snap_sync", "Peer {} timeout while we were asking them for state; disconnecting.", peer_id);
+			io.disconnect_peer(peer_id);
+			aborting.push(peer_id);
+		}
 		for p in aborting {
 			SyncHandler::on_peer_aborting(self, io, p);
 		}
@@ -1344,6 +1657,7 @@ impl ChainSync {
 					},
 				}
 			},
+			SyncState::SnapSync => self.continue_snap_sync(io),
 			_ => (),
 		}
 	}
@@ -1490,15 +1804,17 @@ impl ChainSync {
 
 #[cfg(test)]
 pub mod tests {
-	use std::{collections::VecDeque, time::Instant};
+	use std::{collections::{BTreeMap, VecDeque}, time::Instant};
 
 	use super::{
-		BlockId, BlockQueueInfo, ChainSync, ClientVersion, PeerInfo, PeerAsking,
-		SyncHandler, SyncState, SyncStatus, SyncPropagator, UnverifiedTransaction
+		BlockId, BlockQueueInfo, BlockSet, ChainSync, ClientVersion, PacketInfo, PeerInfo, PeerAsking,
+		SyncHandler, SyncPacket::BlockBodiesPacket, SyncRequester, SyncState, SyncStatus, SyncPropagator,
+		UnverifiedTransaction
 	};
 
 	use crate::{
 		api::SyncConfig,
+		block_sync::BlockRequest,
 		tests::{helpers::TestIo, snapshot::TestSnapshotService},
 	};
 
@@ -1621,6 +1937,12 @@ pub mod tests {
 				snapshot_number: None,
 				snapshot_hash: None,
 				asking_snapshot_data: None,
+				asking_pooled_transactions: Vec::new(),
+				unfetched_pooled_transactions: Default::default(),
+				request_ids: false,
+				request_id: None,
+				next_request_id: 0,
+				pipelined_requests: BTreeMap::new(),
 				block_set: None,
 				client_version: ClientVersion::from(""),
 			});
@@ -1770,4 +2092,48 @@ pub mod tests {
 		let status = io.chain.miner.queue_status();
 		assert_eq!(status.status.transaction_count, 0);
 	}
+
+	#[test]
+	fn pipelines_requests_to_peers_with_request_ids() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(10, EachBlockWith::Uncle);
+		let mut sync = dummy_sync_with_peer(client.block_hash_delta_minus(5), &client);
+		sync.peers.get_mut(&0).unwrap().request_ids = true;
+		let queue = RwLock::new(VecDeque::new());
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &queue, None, None);
+
+		let first = vec![H256::from_low_u64_be(1)];
+		let second = vec![H256::from_low_u64_be(2)];
+		SyncRequester::request_blocks(&mut sync, &mut io, 0, BlockRequest::Bodies { hashes: first }, BlockSet::NewBlocks);
+		assert!(sync.peers[&0].can_request());
+		SyncRequester::request_blocks(&mut sync, &mut io, 0, BlockRequest::Bodies { hashes: second }, BlockSet::NewBlocks);
+
+		// both requests are sent with their own id
+		assert_eq!(2, io.packets.len());
+		assert_eq!(Ok(0u64), Rlp::new(&io.packets[0].data).val_at(0));
+		assert_eq!(Ok(1u64), Rlp::new(&io.packets[1].data).val_at(0));
+
+		// a response to an unknown request is dropped, the peer is kept
+		let mut late_response = RlpStream::new_list(2);
+		late_response.append(&5u64);
+		late_response.begin_list(0);
+		SyncHandler::on_packet(&mut sync, &mut io, 0, BlockBodiesPacket.id(), &late_response.out());
+		assert!(io.to_disconnect.is_empty());
+		assert_eq!(sync.peers[&0].asking, PeerAsking::BlockBodies);
+
+		// responses are matched in any order, the other request stays in flight
+		let empty_response = |request_id: u64| {
+			let mut response = RlpStream::new_list(2);
+			response.append(&request_id);
+			response.begin_list(0);
+			response.out()
+		};
+		SyncHandler::on_packet(&mut sync, &mut io, 0, BlockBodiesPacket.id(), &empty_response(0));
+		assert_eq!(sync.peers[&0].asking, PeerAsking::Nothing);
+		assert_eq!(sync.peers[&0].pipelined_requests.keys().collect::<Vec<_>>(), vec![&1]);
+		SyncHandler::on_packet(&mut sync, &mut io, 0, BlockBodiesPacket.id(), &empty_response(1));
+		assert!(sync.peers[&0].pipelined_requests.is_empty());
+		assert!(io.to_disconnect.is_empty());
+	}
 }
@@ -33,12 +33,15 @@ use super::sync_packet::SyncPacket::{
 	NewBlockHashesPacket,
 	TransactionsPacket,
 	NewBlockPacket,
+	NewPooledTransactionHashesPacket,
 	ConsensusDataPacket,
 };
 
 use super::{
 	random,
 	ChainSync,
+	ETH_PROTOCOL_VERSION_65,
+	MAX_TRANSACTION_HASHES_TO_ANNOUNCE,
 	MAX_TRANSACTION_PACKET_SIZE,
 	MAX_PEER_LAG_PROPAGATION,
 	MAX_PEERS_PROPAGATION,
@@ -119,20 +122,28 @@ impl SyncPropagator {
 		// usual transactions could be propagated to all peers
 		let mut affected_peers = HashSet::new();
 		if !transactions.is_empty() {
-			let peers = SyncPropagator::select_peers_for_transactions(sync, |_| true);
+			let (peers, announce_peers) = SyncPropagator::select_peers_for_transactions(sync, |_| true);
 			affected_peers = SyncPropagator::propagate_transactions_to_peers(
-				sync, io, peers, transactions, &mut should_continue,
+				sync, io, peers, &transactions, &mut should_continue,
 			);
+			let announced_peers = SyncPropagator::announce_transactions_to_peers(
+				sync, io, announce_peers, &transactions, &mut should_continue,
+			);
+			affected_peers.extend(&announced_peers);
 		}
 
 		// most of times service_transactions will be empty
 		// => there's no need to merge packets
 		if !service_transactions.is_empty() {
-			let service_transactions_peers = SyncPropagator::select_peers_for_transactions(sync, |peer_id| io.peer_version(*peer_id).accepts_service_transaction());
+			let (service_transactions_peers, service_transactions_announce_peers) = SyncPropagator::select_peers_for_transactions(sync, |peer_id| io.peer_version(*peer_id).accepts_service_transaction());
 			let service_transactions_affected_peers = SyncPropagator::propagate_transactions_to_peers(
-				sync, io, service_transactions_peers, service_transactions, &mut should_continue
+				sync, io, service_transactions_peers, &service_transactions, &mut should_continue
 			);
 			affected_peers.extend(&service_transactions_affected_peers);
+			let service_transactions_announced_peers = SyncPropagator::announce_transactions_to_peers(
+				sync, io, service_transactions_announce_peers, &service_transactions, &mut should_continue
+			);
+			affected_peers.extend(&service_transactions_announced_peers);
 		}
 
 		affected_peers.len()
@@ -142,7 +153,7 @@ impl SyncPropagator {
 		sync: &mut ChainSync,
 		io: &mut dyn SyncIo,
 		peers: Vec<PeerId>,
-		transactions: Vec<&SignedTransaction>,
+		transactions: &[&SignedTransaction],
 		mut should_continue: F,
 	) -> HashSet<PeerId> {
 		let all_transactions_hashes = transactions.iter()
@@ -150,7 +161,7 @@ impl SyncPropagator {
 			.collect::<H256FastSet>();
 		let all_transactions_rlp = {
 			let mut packet = RlpStream::new_list(transactions.len());
-			for tx in &transactions { packet.append(&**tx); }
+			for tx in transactions { packet.append(&**tx); }
 			packet.out()
 		};
 
@@ -207,7 +218,7 @@ impl SyncPropagator {
 				let mut packet = RlpStream::new();
 				packet.begin_unbounded_list();
 				let mut pushed = 0;
-				for tx in &transactions {
+				for tx in transactions {
 					let hash = tx.hash();
 					if to_send.contains(&hash) {
 						let mut transaction = RlpStream::new();
@@ -248,6 +259,64 @@ impl SyncPropagator {
 		sent_to_peers
 	}
 
+	/// Announces hashes of the transactions to the peers supporting eth/65, they will fetch the ones they don't have.
+	fn announce_transactions_to_peers<F: FnMut() -> bool>(
+		sync: &mut ChainSync,
+		io: &mut dyn SyncIo,
+		peers: Vec<PeerId>,
+		transactions: &[&SignedTransaction],
+		mut should_continue: F,
+	) -> HashSet<PeerId> {
+		let all_transactions_hashes = transactions.iter()
+			.map(|tx| tx.hash())
+			.collect::<H256FastSet>();
+
+		let block_number = io.chain().chain_info().best_block_number;
+		let mut announced_to_peers = HashSet::new();
+
+		for peer_id in peers {
+			if !should_continue() {
+				break;
+			}
+			if io.eth_protocol_version(peer_id) < ETH_PROTOCOL_VERSION_65.0 {
+				continue;
+			}
+
+			let stats = &mut sync.transactions_stats;
+			let peer_info = sync.peers.get_mut(&peer_id)
+				.expect("peer_id is form peers; peers is result of select_peers_for_transactions; select_peers_for_transactions selects peers from self.peers; qed");
+
+			// Keep the order of the pool, most valuable transactions first
+			let to_announce = transactions.iter()
+				.map(|tx| tx.hash())
+				.filter(|hash| !peer_info.last_sent_transactions.contains(hash))
+				.take(MAX_TRANSACTION_HASHES_TO_ANNOUNCE)
+				.collect::<Vec<_>>();
+			if to_announce.is_empty() {
+				continue;
+			}
+
+			let id = io.peer_session_info(peer_id).and_then(|info| info.id);
+			let mut packet = RlpStream::new_list(to_announce.len());
+			for hash in &to_announce {
+				stats.propagated(hash, id, block_number);
+				packet.append(hash);
+			}
+
+			peer_info.last_sent_transactions = all_transactions_hashes
+				.intersection(&peer_info.last_sent_transactions)
+				.chain(&to_announce)
+				.cloned()
+				.collect();
+			SyncPropagator::send_packet(io, peer_id, NewPooledTransactionHashesPacket, packet.out());
+			trace!(target: "sync", "{:02} <- NewPooledTransactionHashes ({} entries)", peer_id, to_announce.len());
+			announced_to_peers.insert(peer_id);
+		}
+
+		debug!(target: "sync", "Announced transactions to {} peers.", announced_to_peers.len());
+		announced_to_peers
+	}
+
 	pub fn propagate_latest_blocks(sync: &mut ChainSync, io: &mut dyn SyncIo, sealed: &[H256]) {
 		let chain_info = io.chain().chain_info();
 		if (((chain_info.best_block_number as i64) - (sync.last_sent_block_number as i64)).abs() as BlockNumber) < MAX_PEER_LAG_PROPAGATION {
@@ -308,19 +377,24 @@ impl SyncPropagator {
 		}
 	}
 
-	fn select_peers_for_transactions<F>(sync: &ChainSync, filter: F) -> Vec<PeerId>
+	/// Splits the peers into the ones getting full transactions and the ones getting only their hashes.
+	fn select_peers_for_transactions<F>(sync: &ChainSync, filter: F) -> (Vec<PeerId>, Vec<PeerId>)
 		where F: Fn(&PeerId) -> bool {
 		// sqrt(x)/x scaled to max u32
 		let fraction = ((sync.peers.len() as f64).powf(-0.5) * (u32::max_value() as f64).round()) as u32;
 		let small = sync.peers.len() < MIN_PEERS_PROPAGATION;
 
 		let mut random = random::new();
-		sync.peers.keys()
-			.cloned()
-			.filter(filter)
-			.filter(|_| small || random.next_u32() < fraction)
-			.take(MAX_PEERS_PROPAGATION)
-			.collect()
+		let mut peers = Vec::new();
+		let mut announce_peers = Vec::new();
+		for peer_id in sync.peers.keys().cloned().filter(filter) {
+			if peers.len() < MAX_PEERS_PROPAGATION && (small || random.next_u32() < fraction) {
+				peers.push(peer_id);
+			} else {
+				announce_peers.push(peer_id);
+			}
+		}
+		(peers, announce_peers)
 	}
 
 	/// Generic packet sender
@@ -334,11 +408,11 @@ impl SyncPropagator {
 
 #[cfg(test)]
 mod tests {
-	use std::{collections::VecDeque, time::Instant};
+	use std::{collections::{BTreeMap, VecDeque}, time::Instant};
 
 	use crate::{
 		api::SyncConfig,
-		chain::{ChainSync, ForkConfirmation, PeerAsking, PeerInfo},
+		chain::{ChainSync, ForkConfirmation, PeerAsking, PeerInfo, ETH_PROTOCOL_VERSION_65},
 		tests::{helpers::TestIo, snapshot::TestSnapshotService},
 	};
 
@@ -445,6 +519,12 @@ mod tests {
 				snapshot_number: None,
 				snapshot_hash: None,
 				asking_snapshot_data: None,
+				asking_pooled_transactions: Vec::new(),
+				unfetched_pooled_transactions: Default::default(),
+				request_ids: false,
+				request_id: None,
+				next_request_id: 0,
+				pipelined_requests: BTreeMap::new(),
 				block_set: None,
 				client_version: ClientVersion::from(""),
 			});
@@ -648,4 +728,39 @@ mod tests {
 		assert!(sent_transactions.iter().any(|tx| tx.hash() == tx1_hash));
 		assert!(sent_transactions.iter().any(|tx| tx.hash() == tx2_hash));
 	}
+
+	#[test]
+	fn announces_transactions_to_peers_not_receiving_them() {
+		let mut client = TestBlockChainClient::new();
+		let tx_hash = client.insert_transaction_to_queue();
+		let block_hash = client.block_hash_delta_minus(1);
+		let mut sync = ChainSync::new(SyncConfig::default(), &client, None);
+		let queue = RwLock::new(VecDeque::new());
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &queue, None, None);
+
+		// when there are enough eth/65 peers to propagate to a subset of them
+		for peer_id in 0..16 {
+			insert_dummy_peer(&mut sync, peer_id, block_hash);
+			io.eth_protocol_versions.insert(peer_id, ETH_PROTOCOL_VERSION_65.0);
+		}
+		let peer_count = SyncPropagator::propagate_new_transactions(&mut sync, &mut io, || true);
+
+		// every peer either gets the transaction or its hash
+		assert_eq!(16, peer_count);
+		assert_eq!(16, io.packets.len());
+		for peer_id in 0..16 {
+			assert_eq!(1, io.packets.iter().filter(|p| p.recipient == peer_id).count());
+		}
+		let announcements: Vec<_> = io.packets.iter()
+			.filter(|p| p.packet_id == 0x08) // NEW_POOLED_TRANSACTION_HASHES_PACKET
+			.map(|p| Rlp::new(&p.data).as_list::<H256>().unwrap())
+			.collect();
+		assert!(!announcements.is_empty());
+		assert!(announcements.iter().all(|hashes| hashes == &vec![tx_hash]));
+		assert_eq!(16 - announcements.len(), io.packets.iter().filter(|p| p.packet_id == 0x02).count());
+
+		// and nothing is sent again
+		assert_eq!(0, SyncPropagator::propagate_new_transactions(&mut sync, &mut io, || true));
+	}
 }
@@ -17,18 +17,21 @@
 use std::time::Instant;
 
 use crate::{
+	api::ETH_PROTOCOL,
 	block_sync::BlockRequest,
+	snap_sync::PivotRequest,
 	sync_io::SyncIo
 };
 
 use bytes::Bytes;
 use ethereum_types::H256;
+use fastmap::H256FastSet;
 use log::{debug, trace, warn};
 use network::{PeerId};
 use rlp::RlpStream;
 use common_types::BlockNumber;
 
-use super::sync_packet::SyncPacket;
+use super::sync_packet::{PacketInfo, SnapPacket, SyncPacket};
 use super::sync_packet::SyncPacket::{
 	GetBlockHeadersPacket,
 	GetBlockBodiesPacket,
@@ -36,12 +39,15 @@ use super::sync_packet::SyncPacket::{
 	GetSnapshotManifestPacket,
 	GetSnapshotDataPacket,
 	GetPrivateStatePacket,
+	GetPooledTransactionsPacket,
 };
 
 use super::{
 	BlockSet,
 	ChainSync,
 	PeerAsking,
+	SyncState,
+	MAX_POOLED_TRANSACTIONS_TO_REQUEST,
 };
 
 /// The Chain Sync Requester: requesting data to other peers
@@ -113,6 +119,82 @@ impl SyncRequester {
 		peer.asking_private_state = Some(hash.clone());
 	}
 
+	/// Request transactions announced by a peer which are not in our pool yet.
+	/// Transactions already being requested from other peers are left for later.
+	pub fn request_pooled_transactions(sync: &mut ChainSync, io: &mut dyn SyncIo, peer_id: PeerId) {
+		if !io.is_chain_queue_empty() || (sync.state != SyncState::Idle && sync.state != SyncState::NewBlocks) {
+			return;
+		}
+		let in_flight: H256FastSet = sync.peers.values()
+			.flat_map(|peer| peer.asking_pooled_transactions.iter()
+				.chain(peer.pipelined_requests.values().flat_map(|r| r.asking_pooled_transactions.iter())))
+			.cloned()
+			.collect();
+		let hashes = match sync.peers.get_mut(&peer_id) {
+			Some(peer) if peer.asking == PeerAsking::Nothing && peer.can_sync() => {
+				let mut hashes = Vec::new();
+				peer.unfetched_pooled_transactions.retain(|hash| {
+					if hashes.len() >= MAX_POOLED_TRANSACTIONS_TO_REQUEST || in_flight.contains(hash) {
+						return true;
+					}
+					if io.chain().pooled_transaction(hash).is_none() {
+						hashes.push(*hash);
+					}
+					false
+				});
+				hashes
+			},
+			_ => return,
+		};
+		if hashes.is_empty() {
+			return;
+		}
+
+		trace!(target: "sync", "{} <- GetPooledTransactions: {} entries", peer_id, hashes.len());
+		let mut rlp = RlpStream::new_list(hashes.len());
+		for h in &hashes {
+			rlp.append(h);
+		}
+		SyncRequester::send_request(sync, io, peer_id, PeerAsking::PooledTransactions, GetPooledTransactionsPacket, rlp.out());
+		let peer = sync.peers.get_mut(&peer_id).expect("peer_id may originate either from on_packet, where it is already validated or from enumerating self.peers. qed");
+		peer.asking_pooled_transactions = hashes;
+	}
+
+	/// Request a part of the block whose state is downloaded with `snap`.
+	pub fn request_snap_pivot(sync: &mut ChainSync, io: &mut dyn SyncIo, peer_id: PeerId, request: PivotRequest) {
+		trace!(target: "snap_sync", "{} <- {:?}", peer_id, request);
+		let (packet_id, rlp) = match request {
+			PivotRequest::Header(hash) => {
+				let mut rlp = RlpStream::new_list(4);
+				rlp.append(&hash);
+				rlp.append(&1u32);
+				rlp.append(&0u32);
+				rlp.append(&0u32);
+				(GetBlockHeadersPacket, rlp)
+			},
+			PivotRequest::Body(hash) => {
+				let mut rlp = RlpStream::new_list(1);
+				rlp.append(&hash);
+				(GetBlockBodiesPacket, rlp)
+			},
+			PivotRequest::Receipts(hash) => {
+				let mut rlp = RlpStream::new_list(1);
+				rlp.append(&hash);
+				(GetReceiptsPacket, rlp)
+			},
+		};
+		SyncRequester::send_request(sync, io, peer_id, PeerAsking::SnapPivot, packet_id, rlp.out());
+	}
+
+	/// Send a `snap` request, the `snap` downloader keeps track of it.
+	pub fn request_snap_data(io: &mut dyn SyncIo, peer_id: PeerId, packet_id: SnapPacket, packet: Bytes) {
+		trace!(target: "snap_sync", "{} <- {:?}", peer_id, packet_id);
+		if let Err(e) = io.send_snap(peer_id, packet_id, packet) {
+			debug!(target: "snap_sync", "Error sending request: {:?}", e);
+			io.disconnect_peer(peer_id);
+		}
+	}
+
 	/// Request headers from a peer by block hash
 	fn request_headers_by_hash(sync: &mut ChainSync, io: &mut dyn SyncIo, peer_id: PeerId, h: &H256, count: u64, skip: u64, reverse: bool, set: BlockSet) {
 		trace!(target: "sync", "{} <- GetBlockHeaders: {} entries starting from {}, set = {:?}", peer_id, count, h, set);
@@ -151,6 +233,21 @@ impl SyncRequester {
 	/// Generic request sender
 	fn send_request(sync: &mut ChainSync, io: &mut dyn SyncIo, peer_id: PeerId, asking: PeerAsking, packet_id: SyncPacket, packet: Bytes) {
 		if let Some(ref mut peer) = sync.peers.get_mut(&peer_id) {
+			let packet = if peer.request_ids && packet_id.protocol() == ETH_PROTOCOL {
+				// the current request stays in flight, its response is matched by its id
+				peer.park_request();
+				let request_id = peer.next_request_id;
+				peer.next_request_id += 1;
+				peer.request_id = Some(request_id);
+
+				let mut rlp = RlpStream::new_list(2);
+				rlp.append(&request_id);
+				rlp.append_raw(&packet, 1);
+				rlp.out()
+			} else {
+				peer.request_id = None;
+				packet
+			};
 			if peer.asking != PeerAsking::Nothing {
 				warn!(target:"sync", "Asking {:?} while requesting {:?}", peer.asking, asking);
 			}
@@ -22,13 +22,25 @@ use crate::sync_io::SyncIo;
 use bytes::Bytes;
 use enum_primitive::FromPrimitive;
 use ethereum_types::H256;
+use ethtrie::range_proof::{self, NodeReference};
+use keccak_hash::KECCAK_EMPTY;
 use log::{debug, trace, warn};
 use network::{self, PeerId};
 use parking_lot::RwLock;
 use rlp::{Rlp, RlpStream};
-use common_types::{ids::BlockId, BlockNumber};
-
-use super::sync_packet::{PacketInfo, SyncPacket};
+use common_types::{basic_account::BasicAccount, ids::BlockId, BlockNumber};
+
+use super::sync_packet::{PacketInfo, SnapPacket, SyncPacket};
+use super::sync_packet::SnapPacket::{
+	GetAccountRangePacket,
+	AccountRangePacket,
+	GetStorageRangesPacket,
+	StorageRangesPacket,
+	GetByteCodesPacket,
+	ByteCodesPacket,
+	GetTrieNodesPacket,
+	TrieNodesPacket,
+};
 use super::sync_packet::SyncPacket::{
 	StatusPacket,
 	TransactionsPacket,
@@ -47,6 +59,8 @@ use super::sync_packet::SyncPacket::{
 	ConsensusDataPacket,
 	GetPrivateStatePacket,
 	PrivateStatePacket,
+	GetPooledTransactionsPacket,
+	PooledTransactionsPacket,
 };
 
 use super::{
@@ -60,6 +74,8 @@ use super::{
 	MAX_NODE_DATA_TOTAL_DURATION,
 	MAX_NODE_DATA_SINGLE_DURATION,
 	MAX_RECEIPTS_HEADERS_TO_SEND,
+	MAX_POOLED_TRANSACTIONS_TO_SEND,
+	ETH_PROTOCOL_VERSION_66,
 };
 
 /// The Chain Sync Supplier: answers requests from peers with available data
@@ -74,22 +90,22 @@ impl SyncSupplier {
 
 		if let Some(id) = SyncPacket::from_u8(packet_id) {
 			let result = match id {
-				GetBlockBodiesPacket => SyncSupplier::return_rlp(
+				GetBlockBodiesPacket => SyncSupplier::return_eth_rlp(
 					io, &rlp, peer,
 					SyncSupplier::return_block_bodies,
 					|e| format!("Error sending block bodies: {:?}", e)),
 
-				GetBlockHeadersPacket => SyncSupplier::return_rlp(
+				GetBlockHeadersPacket => SyncSupplier::return_eth_rlp(
 					io, &rlp, peer,
 					SyncSupplier::return_block_headers,
 					|e| format!("Error sending block headers: {:?}", e)),
 
-				GetReceiptsPacket => SyncSupplier::return_rlp(
+				GetReceiptsPacket => SyncSupplier::return_eth_rlp(
 					io, &rlp, peer,
 					SyncSupplier::return_receipts,
 					|e| format!("Error sending receipts: {:?}", e)),
 
-				GetNodeDataPacket => SyncSupplier::return_rlp(
+				GetNodeDataPacket => SyncSupplier::return_eth_rlp(
 					io, &rlp, peer,
 					SyncSupplier::return_node_data,
 					|e| format!("Error sending nodes: {:?}", e)),
@@ -109,6 +125,11 @@ impl SyncSupplier {
 					SyncSupplier::return_private_state,
 					|e| format!("Error sending private state data: {:?}", e)),
 
+				GetPooledTransactionsPacket => SyncSupplier::return_eth_rlp(
+					io, &rlp, peer,
+					SyncSupplier::return_pooled_transactions,
+					|e| format!("Error sending pooled transactions: {:?}", e)),
+
 				StatusPacket => {
 					sync.write().on_packet(io, peer, packet_id, data);
 					Ok(())
@@ -151,6 +172,54 @@ impl SyncSupplier {
 		}
 	}
 
+	/// Dispatch incoming requests and responses of the `snap` protocol
+	pub fn dispatch_snap_packet(sync: &RwLock<ChainSync>, io: &mut dyn SyncIo, peer: PeerId, packet_id: u8, data: &[u8]) {
+		let rlp = Rlp::new(data);
+
+		let id = match SnapPacket::from_u8(packet_id) {
+			Some(id) => id,
+			None => {
+				trace!(target: "snap_sync", "{}: Unknown packet {}", peer, packet_id);
+				return;
+			},
+		};
+		let result = match id {
+			GetAccountRangePacket => SyncSupplier::return_rlp(
+				io, &rlp, peer,
+				|io, rlp, peer| SyncSupplier::return_account_range(sync, io, rlp, peer),
+				|e| format!("Error sending account range: {:?}", e)),
+
+			GetStorageRangesPacket => SyncSupplier::return_rlp(
+				io, &rlp, peer,
+				SyncSupplier::return_storage_ranges,
+				|e| format!("Error sending storage ranges: {:?}", e)),
+
+			GetByteCodesPacket => SyncSupplier::return_rlp(
+				io, &rlp, peer,
+				|io, rlp, peer| SyncSupplier::return_byte_codes(sync, io, rlp, peer),
+				|e| format!("Error sending codes: {:?}", e)),
+
+			GetTrieNodesPacket => SyncSupplier::return_rlp(
+				io, &rlp, peer,
+				|io, rlp, peer| SyncSupplier::return_trie_nodes(sync, io, rlp, peer),
+				|e| format!("Error sending trie nodes: {:?}", e)),
+
+			// Responses, from peers known by the eth protocol
+			_ => {
+				if !sync.read().peers.contains_key(&peer) {
+					debug!(target: "snap_sync", "Unexpected packet {} from unregistered peer: {}:{}", packet_id, peer, io.peer_version(peer));
+					return;
+				}
+				sync.write().on_snap_packet(io, peer, id, &rlp);
+				Ok(())
+			},
+		};
+
+		result.unwrap_or_else(|e| {
+			debug!(target: "snap_sync", "{} -> Malformed packet {} : {}", peer, packet_id, e);
+		})
+	}
+
 	/// Respond to GetBlockHeaders request
 	fn return_block_headers(io: &dyn SyncIo, r: &Rlp, peer_id: PeerId) -> RlpResponseResult {
 		let payload_soft_limit = io.payload_soft_limit();
@@ -302,6 +371,34 @@ impl SyncSupplier {
 		Ok(Some((NodeDataPacket.id(), rlp)))
 	}
 
+	/// Respond to GetPooledTransactions request
+	fn return_pooled_transactions(io: &dyn SyncIo, r: &Rlp, peer_id: PeerId) -> RlpResponseResult {
+		let payload_soft_limit = io.payload_soft_limit();
+		let mut count = r.item_count().unwrap_or(0);
+		trace!(target: "sync", "{} -> GetPooledTransactions: {} entries", peer_id, count);
+		if count == 0 {
+			debug!(target: "sync", "Empty GetPooledTransactions request, ignoring.");
+			return Ok(None);
+		}
+		count = cmp::min(count, MAX_POOLED_TRANSACTIONS_TO_SEND);
+		let mut added = 0usize;
+		let mut data = Bytes::new();
+		for i in 0..count {
+			if let Some(tx) = io.chain().pooled_transaction(&r.val_at::<H256>(i)?) {
+				data.append(&mut ::rlp::encode(tx.signed()));
+				added += 1;
+				// Check that the packet won't be oversized
+				if data.len() > payload_soft_limit {
+					break;
+				}
+			}
+		}
+		let mut rlp = RlpStream::new_list(added);
+		rlp.append_raw(&data, added);
+		trace!(target: "sync", "{} -> GetPooledTransactions: returned {} entries", peer_id, added);
+		Ok(Some((PooledTransactionsPacket.id(), rlp)))
+	}
+
 	fn return_receipts(io: &dyn SyncIo, rlp: &Rlp, peer_id: PeerId) -> RlpResponseResult {
 		let payload_soft_limit = io.payload_soft_limit();
 		let mut count = rlp.item_count().unwrap_or(0);
@@ -405,6 +502,227 @@ impl SyncSupplier {
 			_ => Ok(())
 		}
 	}
+
+	/// Respond to GetAccountRange request
+	fn return_account_range(sync: &RwLock<ChainSync>, io: &dyn SyncIo, r: &Rlp, peer_id: PeerId) -> RlpResponseResult {
+		// Packet layout:
+		// [ reqID: P, rootHash: B_32, startingHash: B_32, limitHash: B_32, responseBytes: P ]
+		let request_id: u64 = r.val_at(0)?;
+		let root: H256 = r.val_at(1)?;
+		let origin: H256 = r.val_at(2)?;
+		let limit: H256 = r.val_at(3)?;
+		let max_bytes = cmp::min(r.val_at::<usize>(4)?, io.payload_soft_limit());
+		trace!(target: "snap_sync", "{} -> GetAccountRange (root: {}, origin: {}, limit: {})", peer_id, root, origin, limit);
+
+		let (accounts, proof) = io.chain().account_range(&root, &origin, &limit, max_bytes).unwrap_or_default();
+		let mut code_owners = Vec::new();
+		let mut rlp = RlpStream::new_list(3);
+		rlp.append(&request_id);
+		rlp.begin_list(accounts.len());
+		for (hash, account) in &accounts {
+			if let Ok(account) = BasicAccount::from_rlp_slim(account) {
+				if account.code_hash != KECCAK_EMPTY {
+					code_owners.push((account.code_hash, *hash));
+				}
+			}
+			rlp.begin_list(2).append(hash).append_raw(account, 1);
+		}
+		SyncSupplier::append_nodes(&mut rlp, &proof);
+		sync.write().note_code_owners(code_owners);
+		trace!(target: "snap_sync", "{} <- AccountRange: {} accounts, {} proof nodes", peer_id, accounts.len(), proof.len());
+		Ok(Some((AccountRangePacket.id(), rlp)))
+	}
+
+	/// Respond to GetStorageRanges request
+	fn return_storage_ranges(io: &dyn SyncIo, r: &Rlp, peer_id: PeerId) -> RlpResponseResult {
+		// Packet layout:
+		// [ reqID: P, rootHash: B_32, accountHashes: [B_32], startingHash: B, limitHash: B, responseBytes: P ]
+		// An empty starting or limit hash stands for the first or the last slot, they only apply
+		// to the first account.
+		let request_id: u64 = r.val_at(0)?;
+		let root: H256 = r.val_at(1)?;
+		let accounts: Vec<H256> = r.list_at(2)?;
+		let origin = SyncSupplier::optional_hash(&r.at(3)?)?.unwrap_or_else(H256::zero);
+		let limit = SyncSupplier::optional_hash(&r.at(4)?)?.unwrap_or_else(|| H256::repeat_byte(0xff));
+		let max_bytes = cmp::min(r.val_at::<usize>(5)?, io.payload_soft_limit());
+		trace!(target: "snap_sync", "{} -> GetStorageRanges (root: {}, {} accounts, origin: {})", peer_id, root, accounts.len(), origin);
+
+		let mut ranges = Vec::new();
+		let mut proof = Vec::new();
+		let mut size = 0;
+		for (index, account) in accounts.iter().enumerate() {
+			let (origin, limit) = if index == 0 { (origin, limit) } else { (H256::zero(), H256::repeat_byte(0xff)) };
+			let (slots, range_proof) = match io.chain().storage_range(&root, account, &origin, &limit, max_bytes - size) {
+				Some(range) => range,
+				None => break,
+			};
+			size += slots.iter().map(|(_, value)| H256::len_bytes() + value.len()).sum::<usize>();
+			ranges.push(slots);
+			// an incomplete range comes with a proof and ends the response
+			if !range_proof.is_empty() {
+				proof = range_proof;
+				break;
+			}
+			if size >= max_bytes {
+				break;
+			}
+		}
+
+		let mut rlp = RlpStream::new_list(3);
+		rlp.append(&request_id);
+		rlp.begin_list(ranges.len());
+		for slots in &ranges {
+			rlp.begin_list(slots.len());
+			for (hash, value) in slots {
+				rlp.begin_list(2).append(hash).append(value);
+			}
+		}
+		SyncSupplier::append_nodes(&mut rlp, &proof);
+		trace!(target: "snap_sync", "{} <- StorageRanges: {} accounts, {} proof nodes", peer_id, ranges.len(), proof.len());
+		Ok(Some((StorageRangesPacket.id(), rlp)))
+	}
+
+	/// Respond to GetByteCodes request
+	fn return_byte_codes(sync: &RwLock<ChainSync>, io: &dyn SyncIo, r: &Rlp, peer_id: PeerId) -> RlpResponseResult {
+		// Packet layout:
+		// [ reqID: P, hashes: [B_32], responseBytes: P ]
+		let request_id: u64 = r.val_at(0)?;
+		let hashes: Vec<H256> = r.list_at(1)?;
+		let max_bytes = cmp::min(r.val_at::<usize>(2)?, io.payload_soft_limit());
+		trace!(target: "snap_sync", "{} -> GetByteCodes ({} entries)", peer_id, hashes.len());
+
+		let mut codes = Vec::new();
+		let mut size = 0;
+		{
+			// codes written before they were stored by hash are found through a known owner
+			let sync = sync.read();
+			for hash in &hashes {
+				let code = io.chain().code_by_hash(hash).or_else(|| sync.snap_code_owners.get(hash)
+					.and_then(|account_hash| io.chain().account_code(account_hash, hash)));
+				if let Some(code) = code {
+					size += code.len();
+					codes.push(code);
+					if size >= max_bytes {
+						break;
+					}
+				}
+			}
+		}
+
+		let mut rlp = RlpStream::new_list(2);
+		rlp.append(&request_id);
+		SyncSupplier::append_nodes(&mut rlp, &codes);
+		trace!(target: "snap_sync", "{} <- ByteCodes: {} entries", peer_id, codes.len());
+		Ok(Some((ByteCodesPacket.id(), rlp)))
+	}
+
+	/// Respond to GetTrieNodes request
+	fn return_trie_nodes(sync: &RwLock<ChainSync>, io: &dyn SyncIo, r: &Rlp, peer_id: PeerId) -> RlpResponseResult {
+		// Packet layout:
+		// [ reqID: P, rootHash: B_32, paths: [[accPath: B, slotPath1: B, slotPath2: B, ...]...], responseBytes: P ]
+		// A single path is an account trie path, otherwise the first one is the account hash and
+		// the following ones are paths in its storage trie.
+		let request_id: u64 = r.val_at(0)?;
+		let root: H256 = r.val_at(1)?;
+		let paths = r.at(2)?;
+		let max_bytes = cmp::min(r.val_at::<usize>(3)?, io.payload_soft_limit());
+		trace!(target: "snap_sync", "{} -> GetTrieNodes (root: {}, {} path sets)", peer_id, root, paths.item_count()?);
+
+		let mut nodes = Vec::new();
+		let mut code_owners = Vec::new();
+		let mut size = 0;
+		'paths: for path_set in paths.iter() {
+			let node_paths: Vec<Bytes> = path_set.as_list()?;
+			let (account_hash, node_paths) = match node_paths.len() {
+				0 => continue,
+				1 => (None, &node_paths[..]),
+				_ => {
+					if node_paths[0].len() != H256::len_bytes() {
+						return Err(PacketDecodeError::Custom("Invalid account hash"));
+					}
+					(Some(H256::from_slice(&node_paths[0])), &node_paths[1..])
+				},
+			};
+			for path in node_paths {
+				let path = range_proof::decode_path(path)?;
+				// nodes are sent in order, the response ends at the first missing one
+				let node = match io.chain().state_node_at_path(&root, account_hash.as_ref(), &path) {
+					Some(node) => node,
+					None => break 'paths,
+				};
+				if account_hash.is_none() {
+					SyncSupplier::collect_code_owners(&path, &node, &mut code_owners);
+				}
+				size += node.len();
+				nodes.push(node);
+				if size >= max_bytes {
+					break 'paths;
+				}
+			}
+		}
+		sync.write().note_code_owners(code_owners);
+
+		let mut rlp = RlpStream::new_list(2);
+		rlp.append(&request_id);
+		SyncSupplier::append_nodes(&mut rlp, &nodes);
+		trace!(target: "snap_sync", "{} <- TrieNodes: {} entries", peer_id, nodes.len());
+		Ok(Some((TrieNodesPacket.id(), rlp)))
+	}
+
+	/// Finds the accounts with a code among the leaves of an account trie node at the given path.
+	fn collect_code_owners(path: &[u8], node: &[u8], code_owners: &mut Vec<(H256, H256)>) {
+		for reference in range_proof::node_references(node).unwrap_or_default() {
+			if let NodeReference::Leaf(leaf_path, value) = reference {
+				let leaf_path = [path, &leaf_path[..]].concat();
+				if leaf_path.len() != 2 * H256::len_bytes() {
+					continue;
+				}
+				if let Ok(account) = rlp::decode::<BasicAccount>(&value) {
+					if account.code_hash != KECCAK_EMPTY {
+						code_owners.push((account.code_hash, H256::from_slice(&range_proof::from_nibbles(&leaf_path))));
+					}
+				}
+			}
+		}
+	}
+
+	/// Decodes a hash which may be left empty.
+	fn optional_hash(r: &Rlp) -> Result<Option<H256>, PacketDecodeError> {
+		if r.is_empty() {
+			Ok(None)
+		} else {
+			r.as_val().map(Some)
+		}
+	}
+
+	fn append_nodes(rlp: &mut RlpStream, nodes: &[Bytes]) {
+		rlp.begin_list(nodes.len());
+		for node in nodes {
+			rlp.append(node);
+		}
+	}
+
+	/// Responds to a request of the eth protocol. Since eth/66 these requests carry an identifier,
+	/// which is sent back with the response.
+	fn return_eth_rlp<FRlp, FError>(io: &mut dyn SyncIo, rlp: &Rlp, peer: PeerId, rlp_func: FRlp, error_func: FError) -> Result<(), PacketDecodeError>
+		where FRlp : Fn(&dyn SyncIo, &Rlp, PeerId) -> RlpResponseResult,
+			FError : FnOnce(network::Error) -> String
+	{
+		if io.eth_protocol_version(peer) < ETH_PROTOCOL_VERSION_66.0 {
+			return SyncSupplier::return_rlp(io, rlp, peer, rlp_func, error_func);
+		}
+
+		let request_id: u64 = rlp.val_at(0)?;
+		let request = rlp.at(1)?;
+		SyncSupplier::return_rlp(io, &request, peer, |io, rlp, peer| {
+			Ok(rlp_func(io, rlp, peer)?.map(|(packet_id, response)| {
+				let mut rlp = RlpStream::new_list(2);
+				rlp.append(&request_id);
+				rlp.append_raw(&response.out(), 1);
+				(packet_id, rlp)
+			}))
+		}, error_func)
+	}
 }
 
 #[cfg(test)]
@@ -418,7 +736,9 @@ mod test {
 	};
 
 	use super::{
-		SyncPacket::{GetReceiptsPacket, GetNodeDataPacket},
+		ETH_PROTOCOL_VERSION_66,
+		SyncPacket::{GetReceiptsPacket, GetNodeDataPacket, GetPooledTransactionsPacket, PooledTransactionsPacket, GetBlockHeadersPacket},
+		SnapPacket::{AccountRangePacket, ByteCodesPacket, GetAccountRangePacket, GetByteCodesPacket},
 		BlockNumber, BlockId, SyncSupplier, PacketInfo
 	};
 
@@ -426,8 +746,10 @@ mod test {
 
 	use bytes::Bytes;
 	use client_traits::BlockChainClient;
+	use common_types::transaction::UnverifiedTransaction;
 	use ethcore::test_helpers::{EachBlockWith, TestBlockChainClient};
-	use ethereum_types::H256;
+	use ethereum_types::{Address, H256};
+	use keccak_hash::keccak;
 	use parking_lot::RwLock;
 	use rlp::{Rlp, RlpStream};
 
@@ -452,14 +774,14 @@ mod test {
 		}
 
 		fn to_header_vec(rlp: RlpResponseResult) -> Vec<SyncHeader> {
-			Rlp::new(&rlp.unwrap().unwrap().1.out()).iter().map(|r| SyncHeader::from_rlp(r.as_raw().to_vec()).unwrap()).collect()
+			Rlp::new(&rlp.unwrap().unwrap().1.out()).iter().map(|r| SyncHeader::from_rlp(r.as_raw().to_vec(), BlockNumber::max_value()).unwrap()).collect()
 		}
 
 		let mut client = TestBlockChainClient::new();
 		client.add_blocks(100, EachBlockWith::Nothing);
 		let blocks: Vec<_> = (0 .. 100)
 			.map(|i| (&client as &dyn BlockChainClient).block(BlockId::Number(i as BlockNumber)).map(|b| b.into_inner()).unwrap()).collect();
-		let headers: Vec<_> = blocks.iter().map(|b| SyncHeader::from_rlp(Rlp::new(b).at(0).unwrap().as_raw().to_vec()).unwrap()).collect();
+		let headers: Vec<_> = blocks.iter().map(|b| SyncHeader::from_rlp(Rlp::new(b).at(0).unwrap().as_raw().to_vec(), BlockNumber::max_value()).unwrap()).collect();
 		let hashes: Vec<_> = headers.iter().map(|h| h.header.hash()).collect();
 
 		let queue = RwLock::new(VecDeque::new());
@@ -606,4 +928,105 @@ mod test {
 		SyncSupplier::dispatch_packet(&RwLock::new(sync), &mut io, 0usize, GetReceiptsPacket.id(), &receipts_request);
 		assert_eq!(1, io.packets.len());
 	}
+
+	#[test]
+	fn return_pooled_transactions() {
+		let mut client = TestBlockChainClient::new();
+		let tx_hash = client.insert_transaction_to_queue();
+		let queue = RwLock::new(VecDeque::new());
+		let sync = dummy_sync_with_peer(H256::zero(), &client);
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &queue, None, None);
+
+		let mut hash_list = RlpStream::new_list(2);
+		hash_list.append(&H256::from_low_u64_be(1));
+		hash_list.append(&tx_hash);
+		let request = hash_list.out();
+
+		// unknown transactions are skipped
+		let result = SyncSupplier::return_pooled_transactions(&io, &Rlp::new(&request), 0).unwrap().unwrap();
+		assert_eq!(result.0, PooledTransactionsPacket.id());
+		let data = result.1.out();
+		let rlp = Rlp::new(&data);
+		assert_eq!(Ok(1), rlp.item_count());
+		let tx: UnverifiedTransaction = rlp.val_at(0).unwrap();
+		assert_eq!(tx.hash(), tx_hash);
+
+		io.sender = Some(2usize);
+		SyncSupplier::dispatch_packet(&RwLock::new(sync), &mut io, 0usize, GetPooledTransactionsPacket.id(), &request);
+		assert_eq!(1, io.packets.len());
+	}
+
+	#[test]
+	fn returns_request_id_to_eth66_peers() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(10, EachBlockWith::Nothing);
+		let queue = RwLock::new(VecDeque::new());
+		let sync = dummy_sync_with_peer(H256::zero(), &client);
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &queue, Some(0), None);
+		io.eth_protocol_versions.insert(0, ETH_PROTOCOL_VERSION_66.0);
+
+		let mut request = RlpStream::new_list(2);
+		request.append(&42u64);
+		request.begin_list(4);
+		request.append(&1u64);
+		request.append(&2u64);
+		request.append(&0u64);
+		request.append(&false);
+		SyncSupplier::dispatch_packet(&RwLock::new(sync), &mut io, 0, GetBlockHeadersPacket.id(), &request.out());
+
+		assert_eq!(1, io.packets.len());
+		let response = Rlp::new(&io.packets[0].data);
+		assert_eq!(Ok(42u64), response.val_at(0));
+		assert_eq!(Ok(2), response.at(1).and_then(|headers| headers.item_count()));
+	}
+
+	#[test]
+	fn returns_empty_account_range_for_unknown_state() {
+		let mut client = TestBlockChainClient::new();
+		let queue = RwLock::new(VecDeque::new());
+		let sync = dummy_sync_with_peer(H256::zero(), &client);
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &queue, Some(0), None);
+
+		let mut request = RlpStream::new_list(5);
+		request.append(&7u64);
+		request.append(&H256::from_low_u64_be(1));
+		request.append(&H256::zero());
+		request.append(&H256::repeat_byte(0xff));
+		request.append(&(512 * 1024usize));
+		SyncSupplier::dispatch_snap_packet(&RwLock::new(sync), &mut io, 0, GetAccountRangePacket.id(), &request.out());
+
+		assert_eq!(1, io.packets.len());
+		assert_eq!(AccountRangePacket.id(), io.packets[0].packet_id);
+		let response = Rlp::new(&io.packets[0].data);
+		assert_eq!(Ok(7u64), response.val_at(0));
+		assert_eq!(Ok(0), response.at(1).and_then(|accounts| accounts.item_count()));
+		assert_eq!(Ok(0), response.at(2).and_then(|proof| proof.item_count()));
+	}
+
+	#[test]
+	fn returns_byte_codes_by_hash() {
+		let mut client = TestBlockChainClient::new();
+		let code: Bytes = vec![0x60, 0x00, 0x60, 0x00, 0xf3];
+		client.set_code(Address::from_low_u64_be(1), code.clone());
+		let queue = RwLock::new(VecDeque::new());
+		let sync = dummy_sync_with_peer(H256::zero(), &client);
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &queue, Some(0), None);
+
+		// no account owning the code was served before
+		let mut request = RlpStream::new_list(3);
+		request.append(&7u64);
+		request.append_list(&[keccak(&code), H256::from_low_u64_be(1)]);
+		request.append(&(512 * 1024usize));
+		SyncSupplier::dispatch_snap_packet(&RwLock::new(sync), &mut io, 0, GetByteCodesPacket.id(), &request.out());
+
+		assert_eq!(1, io.packets.len());
+		assert_eq!(ByteCodesPacket.id(), io.packets[0].packet_id);
+		let response = Rlp::new(&io.packets[0].data);
+		assert_eq!(Ok(7u64), response.val_at(0));
+		assert_eq!(Ok(vec![code]), response.list_at::<Bytes>(1));
+	}
 }
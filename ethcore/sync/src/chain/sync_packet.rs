@@ -22,7 +22,7 @@
 //! to convert to/from the packet id values transmitted over the
 //! wire.
 
-use crate::api::{ETH_PROTOCOL, WARP_SYNC_PROTOCOL_ID};
+use crate::api::{ETH_PROTOCOL, SNAP_PROTOCOL, WARP_SYNC_PROTOCOL_ID};
 use self::SyncPacket::*;
 
 use enum_primitive::{enum_from_primitive, enum_from_primitive_impl, enum_from_primitive_impl_ty};
@@ -45,6 +45,9 @@ enum_from_primitive! {
 		GetBlockBodiesPacket = 0x05,
 		BlockBodiesPacket = 0x06,
 		NewBlockPacket = 0x07,
+		NewPooledTransactionHashesPacket = 0x08,
+		GetPooledTransactionsPacket = 0x09,
+		PooledTransactionsPacket = 0x0a,
 
 		GetNodeDataPacket = 0x0d,
 		NodeDataPacket = 0x0e,
@@ -63,6 +66,20 @@ enum_from_primitive! {
 	}
 }
 
+enum_from_primitive! {
+	/// Packet ids of the `snap` subprotocol, which has an id space of its own.
+	#[derive(Clone, Copy, Debug, PartialEq)]
+	pub enum SnapPacket {
+		GetAccountRangePacket = 0x00,
+		AccountRangePacket = 0x01,
+		GetStorageRangesPacket = 0x02,
+		StorageRangesPacket = 0x03,
+		GetByteCodesPacket = 0x04,
+		ByteCodesPacket = 0x05,
+		GetTrieNodesPacket = 0x06,
+		TrieNodesPacket = 0x07,
+	}
+}
 
 /// Provide both subprotocol and packet id information within the
 /// same object.
@@ -87,6 +104,9 @@ impl PacketInfo for SyncPacket {
 			GetBlockBodiesPacket |
 			BlockBodiesPacket |
 			NewBlockPacket |
+			NewPooledTransactionHashesPacket |
+			GetPooledTransactionsPacket |
+			PooledTransactionsPacket |
 
 			GetNodeDataPacket|
 			NodeDataPacket |
@@ -114,6 +134,16 @@ impl PacketInfo for SyncPacket {
 	}
 }
 
+impl PacketInfo for SnapPacket {
+	fn protocol(&self) -> ProtocolId {
+		SNAP_PROTOCOL
+	}
+
+	fn id(&self) -> PacketId {
+		(*self) as PacketId
+	}
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -141,9 +171,25 @@ mod tests {
 		assert_eq!(StatusPacket.protocol(), ETH_PROTOCOL);
 	}
 
+	#[test]
+	fn when_pooled_transactions_packet_then_id_and_protocol_match() {
+		assert_eq!(SyncPacket::from_u8(0x0a), Some(PooledTransactionsPacket));
+		assert_eq!(PooledTransactionsPacket.protocol(), ETH_PROTOCOL);
+	}
+
 	#[test]
 	fn when_consensus_data_packet_then_id_and_protocol_match() {
 		assert_eq!(ConsensusDataPacket.id(), ConsensusDataPacket as PacketId);
 		assert_eq!(ConsensusDataPacket.protocol(), WARP_SYNC_PROTOCOL_ID);
 	}
+
+	#[test]
+	fn when_snap_packet_then_id_and_protocol_match() {
+		assert_eq!(SnapPacket::from_u8(0x00), Some(SnapPacket::GetAccountRangePacket));
+		assert_eq!(SnapPacket::from_u8(0x07), Some(SnapPacket::TrieNodesPacket));
+		assert!(SnapPacket::from_u8(0x08).is_none());
+		assert_eq!(SnapPacket::TrieNodesPacket.protocol(), SNAP_PROTOCOL);
+		assert_eq!(SNAP_PROTOCOL, network::protocol_id(b"snap").unwrap());
+		assert_eq!(ETH_PROTOCOL, network::protocol_id(b"eth").unwrap());
+	}
 }
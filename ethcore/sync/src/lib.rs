@@ -17,7 +17,7 @@
 #![warn(missing_docs, unused_extern_crates)]
 
 //! Blockchain sync module
-//! Implements ethereum protocol version 63 as specified here:
+//! Implements ethereum protocol version 66 as specified here:
 //! https://github.com/ethereum/wiki/wiki/Ethereum-Wire-Protocol
 //!
 
@@ -30,6 +30,7 @@ mod blocks;
 mod block_sync;
 mod sync_io;
 mod private_tx;
+mod snap_sync;
 mod snapshot_sync;
 mod transactions_stats;
 
@@ -40,6 +41,6 @@ mod tests;
 
 pub use api::*;
 pub use chain::{SyncStatus, SyncState};
-pub use devp2p::validate_node_url;
+pub use devp2p::{validate_node_url, Enr, NodeTree, TreeLink};
 pub use network::{NonReservedPeerMode, Error, ConnectionFilter, ConnectionDirection};
 pub use private_tx::{PrivateTxHandler, NoopPrivateTxHandler, SimplePrivateTxHandler};
@@ -16,7 +16,7 @@
 
 //! Helpers for decoding and verifying responses for headers.
 
-use common_types::{encoded, header::Header};
+use common_types::{encoded, header::Header, BlockNumber};
 use ethereum_types::H256;
 use light::request::{HashOrNumber, CompleteHeadersRequest as HeadersRequest};
 use rlp::DecoderError;
@@ -71,8 +71,12 @@ pub trait Constraint {
 }
 
 /// Do basic verification of provided headers against a request.
-pub fn verify(headers: &[encoded::Header], request: &HeadersRequest) -> Result<Vec<Header>, BasicError> {
-	let headers: Result<Vec<_>, _> = headers.iter().map(|h| h.decode() ).collect();
+pub fn verify(
+	headers: &[encoded::Header],
+	request: &HeadersRequest,
+	eip1559_transition: BlockNumber,
+) -> Result<Vec<Header>, BasicError> {
+	let headers: Result<Vec<_>, _> = headers.iter().map(|h| h.decode(eip1559_transition) ).collect();
 	match headers {
 		Ok(headers) => {
 			let reverse = request.reverse;
@@ -182,7 +186,7 @@ mod tests {
 			encoded::Header::new(::rlp::encode(&header))
 		}).collect();
 
-		assert!(verify(&headers, &request).is_ok());
+		assert!(verify(&headers, &request, BlockNumber::max_value()).is_ok());
 	}
 
 	#[test]
@@ -208,7 +212,7 @@ mod tests {
 			encoded::Header::new(::rlp::encode(&header))
 		}).collect();
 
-		assert!(verify(&headers, &request).is_ok());
+		assert!(verify(&headers, &request, BlockNumber::max_value()).is_ok());
 	}
 
 	#[test]
@@ -234,7 +238,7 @@ mod tests {
 			encoded::Header::new(::rlp::encode(&header))
 		}).collect();
 
-		assert_eq!(verify(&headers, &request), Err(BasicError::TooManyHeaders(20, 25)));
+		assert_eq!(verify(&headers, &request, BlockNumber::max_value()), Err(BasicError::TooManyHeaders(20, 25)));
 	}
 
 	#[test]
@@ -253,6 +257,6 @@ mod tests {
 			encoded::Header::new(::rlp::encode(&header))
 		}).collect();
 
-		assert_eq!(verify(&headers, &request), Err(BasicError::WrongSkip(5, Some(2))));
+		assert_eq!(verify(&headers, &request, BlockNumber::max_value()), Err(BasicError::WrongSkip(5, Some(2))));
 	}
 }
@@ -20,7 +20,7 @@ use std::cmp::Ordering;
 use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
 use std::fmt;
 
-use common_types::{encoded, header::Header};
+use common_types::{encoded, header::Header, BlockNumber};
 
 use light::net::ReqId;
 use light::request::CompleteHeadersRequest as HeadersRequest;
@@ -172,7 +172,7 @@ impl Fetcher {
 		trace!(target: "sync", "{} headers ready to drain", self.ready.len());
 	}
 
-	fn process_response<R: ResponseContext>(mut self, ctx: &R) -> SyncRound {
+	fn process_response<R: ResponseContext>(mut self, ctx: &R, eip1559_transition: BlockNumber) -> SyncRound {
 		let mut request = match self.pending.remove(ctx.req_id()) {
 			Some(request) => request,
 			None => return SyncRound::Fetch(self),
@@ -191,7 +191,7 @@ impl Fetcher {
 			return SyncRound::Fetch(self);
 		}
 
-		match response::verify(headers, &request.headers_request) {
+		match response::verify(headers, &request.headers_request, eip1559_transition) {
 			Err(e) => {
 				trace!(target: "sync", "Punishing peer {} for invalid response ({})", ctx.responder(), e);
 				ctx.punish_responder();
@@ -367,7 +367,7 @@ impl RoundStart {
 		}
 	}
 
-	fn process_response<R: ResponseContext>(mut self, ctx: &R) -> SyncRound {
+	fn process_response<R: ResponseContext>(mut self, ctx: &R, eip1559_transition: BlockNumber) -> SyncRound {
 		let req = match self.pending_req.take() {
 			Some((id, ref req)) if ctx.req_id() == &id => { req.clone() }
 			other => {
@@ -376,7 +376,7 @@ impl RoundStart {
 			}
 		};
 
-		match response::verify(ctx.data(), &req) {
+		match response::verify(ctx.data(), &req, eip1559_transition) {
 			Ok(headers) => {
 				if self.sparse_headers.is_empty()
 					&& headers.get(0).map_or(false, |x| x.parent_hash() != &self.start_block.1) {
@@ -481,10 +481,10 @@ impl SyncRound {
 	}
 
 	/// Process an answer to a request. Unknown requests will be ignored.
-	pub fn process_response<R: ResponseContext>(self, ctx: &R) -> Self {
+	pub fn process_response<R: ResponseContext>(self, ctx: &R, eip1559_transition: BlockNumber) -> Self {
 		match self {
-			SyncRound::Start(round_start) => round_start.process_response(ctx),
-			SyncRound::Fetch(fetcher) => fetcher.process_response(ctx),
+			SyncRound::Start(round_start) => round_start.process_response(ctx, eip1559_transition),
+			SyncRound::Fetch(fetcher) => fetcher.process_response(ctx, eip1559_transition),
 			other => other,
 		}
 	}
@@ -0,0 +1,920 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! State download with the `snap` protocol.
+//!
+//! The accounts and storage slots of a recent block (the pivot) are downloaded as consecutive
+//! ranges of the tries, each range checked with its range proof before it's inserted into the
+//! local tries. Peers only keep the state of recent blocks, so the pivot moves on during the
+//! download and the ranges end up taken from different blocks: the tries are then healed by
+//! downloading, top-down, the nodes missing under the state root of the latest pivot.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::mem;
+use std::time::{Duration, Instant};
+
+use crate::{
+	blocks::{SyncBody, SyncHeader, unverified_from_sync},
+	chain::sync_packet::{
+		SnapPacket::{
+			self, AccountRangePacket, ByteCodesPacket, GetAccountRangePacket, GetByteCodesPacket,
+			GetStorageRangesPacket, GetTrieNodesPacket, StorageRangesPacket, TrieNodesPacket,
+		},
+		SyncPacket::{self, BlockBodiesPacket, BlockHeadersPacket, ReceiptsPacket},
+	},
+	sync_io::SyncIo,
+};
+
+use bytes::Bytes;
+use common_types::{
+	BlockNumber,
+	basic_account::BasicAccount,
+	errors::EthcoreError,
+	transaction::raw_item_bytes,
+};
+use ethereum_types::{H256, U256};
+use ethtrie::range_proof::{self, NodeReference, RangeProofError};
+use keccak_hash::{keccak, KECCAK_EMPTY, KECCAK_NULL_RLP};
+use log::{debug, trace};
+use network::PeerId;
+use rlp::{DecoderError, Rlp, RlpStream};
+use triehash_ethereum::ordered_trie_root;
+
+/// Number of ranges the account trie is split into, to download them from several peers at once.
+const ACCOUNT_RANGES: u8 = 16;
+/// Size of the responses we ask for.
+const RESPONSE_BYTES: usize = 512 * 1024;
+/// Maximum number of accounts whose storage is requested at once.
+const MAX_STORAGE_ACCOUNTS_TO_REQUEST: usize = 128;
+/// Maximum number of codes requested at once.
+const MAX_CODES_TO_REQUEST: usize = 64;
+/// Maximum number of trie nodes requested at once.
+const MAX_TRIE_NODES_TO_REQUEST: usize = 256;
+/// Max time to wait for the response to a `snap` request.
+const SNAP_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Error while handling a response.
+#[derive(Debug)]
+pub enum SnapError {
+	/// The response is malformed or doesn't match what was asked for.
+	Invalid,
+	/// The downloaded state could not be written to the database.
+	Import(EthcoreError),
+}
+
+impl From<DecoderError> for SnapError {
+	fn from(_: DecoderError) -> SnapError {
+		SnapError::Invalid
+	}
+}
+
+impl From<RangeProofError> for SnapError {
+	fn from(e: RangeProofError) -> SnapError {
+		trace!(target: "snap_sync", "Range proof verification failed: {}", e);
+		SnapError::Invalid
+	}
+}
+
+impl From<EthcoreError> for SnapError {
+	fn from(e: EthcoreError) -> SnapError {
+		SnapError::Import(e)
+	}
+}
+
+/// Part of the pivot block, downloaded with the `eth` protocol.
+#[derive(Debug, PartialEq)]
+pub enum PivotRequest {
+	/// Header of the block with the given hash.
+	Header(H256),
+	/// Body of the block with the given hash.
+	Body(H256),
+	/// Receipts of the block with the given hash.
+	Receipts(H256),
+}
+
+/// The block whose state is downloaded.
+struct Pivot {
+	header: SyncHeader,
+	total_difficulty: U256,
+	body: Option<SyncBody>,
+	receipts: Option<Bytes>,
+}
+
+/// A range of the account trie still to be downloaded.
+struct AccountTask {
+	/// First account hash of the range not downloaded yet.
+	next: H256,
+	/// Last account hash of the range.
+	last: H256,
+	/// Whether the range is being requested from a peer.
+	requested: bool,
+}
+
+/// The storage of an account still to be downloaded.
+#[derive(Clone)]
+struct StorageTask {
+	account: H256,
+	/// Storage root of the account in the pivot state.
+	root: H256,
+	/// First slot hash not downloaded yet.
+	next: H256,
+	/// Root of the slots downloaded so far.
+	local_root: H256,
+}
+
+/// Trie node or code missing from the database: the account owning the storage trie or the
+/// code, none for the account trie, and the hash.
+type HealKey = (Option<H256>, H256);
+
+/// A trie node or a code missing from the database, with the nodes waiting for it.
+struct HealTask {
+	/// Path of the node in its trie, in nibbles.
+	path: Vec<u8>,
+	/// The node once downloaded, kept until all of its children are in the database.
+	data: Option<Bytes>,
+	/// Number of children still missing from the database.
+	missing: usize,
+	/// Nodes waiting for this one.
+	parents: Vec<HealKey>,
+}
+
+/// Data requested from a peer.
+enum Request {
+	AccountRange { origin: H256, last: H256 },
+	StorageRanges(Vec<StorageTask>),
+	ByteCodes(Vec<(H256, H256)>),
+	TrieNodes(Vec<HealKey>),
+}
+
+struct PeerRequest {
+	id: u64,
+	request: Request,
+	ask_time: Instant,
+}
+
+/// `snap` state downloader.
+pub struct SnapSync {
+	eip1559_transition: BlockNumber,
+	pivot: Option<Pivot>,
+	/// Hash and total difficulty of the pivot block being requested.
+	pivot_candidate: Option<(H256, U256)>,
+	/// Peer the pivot block is being requested from.
+	pivot_peer: Option<PeerId>,
+	account_tasks: Vec<AccountTask>,
+	/// Root of the accounts downloaded so far.
+	account_root: H256,
+	storage_tasks: VecDeque<StorageTask>,
+	/// Downloaded accounts waiting for their storage or code, with the number of those missing.
+	pending_accounts: HashMap<H256, (Bytes, usize)>,
+	/// Downloaded accounts ready to be written.
+	ready_accounts: Vec<(H256, Bytes)>,
+	/// Codes to download with the account they belong to.
+	code_queue: VecDeque<(H256, H256)>,
+	/// Whether the ranges are all downloaded and the tries are being healed.
+	healing: bool,
+	heal_tasks: HashMap<HealKey, HealTask>,
+	heal_queue: VecDeque<HealKey>,
+	requests: HashMap<PeerId, PeerRequest>,
+	/// Peers which don't have the pivot state anymore.
+	stateless_peers: HashSet<PeerId>,
+	next_request_id: u64,
+}
+
+impl SnapSync {
+	/// Create a new instance.
+	pub fn new(eip1559_transition: BlockNumber) -> Self {
+		let account_tasks = (0..ACCOUNT_RANGES).map(|i| {
+			let step = 0x100 / ACCOUNT_RANGES as usize;
+			let mut next = H256::zero();
+			next.as_bytes_mut()[0] = (i as usize * step) as u8;
+			let mut last = H256::repeat_byte(0xff);
+			last.as_bytes_mut()[0] = ((i as usize + 1) * step - 1) as u8;
+			AccountTask { next, last, requested: false }
+		}).collect();
+
+		SnapSync {
+			eip1559_transition,
+			pivot: None,
+			pivot_candidate: None,
+			pivot_peer: None,
+			account_tasks,
+			account_root: KECCAK_NULL_RLP,
+			storage_tasks: VecDeque::new(),
+			pending_accounts: HashMap::new(),
+			ready_accounts: Vec::new(),
+			code_queue: VecDeque::new(),
+			healing: false,
+			heal_tasks: HashMap::new(),
+			heal_queue: VecDeque::new(),
+			requests: HashMap::new(),
+			stateless_peers: HashSet::new(),
+			next_request_id: 0,
+		}
+	}
+
+	/// Number of peers known not to have the pivot state.
+	pub fn stateless_peers(&self) -> usize {
+		self.stateless_peers.len()
+	}
+
+	/// Forget the pivot, whose state isn't served anymore. The ranges downloaded so far are kept,
+	/// the download goes on with the next pivot and the tries get healed afterwards.
+	pub fn reset_pivot(&mut self) {
+		debug!(target: "snap_sync", "Moving the pivot, {} peers don't have its state", self.stateless_peers.len());
+		self.pivot = None;
+		self.pivot_candidate = None;
+		self.stateless_peers.clear();
+		self.requests.clear();
+		for task in &mut self.account_tasks {
+			task.requested = false;
+		}
+		// the storage roots have changed along with the state root, the accounts left out
+		// are downloaded again while healing
+		self.storage_tasks.clear();
+		self.pending_accounts.clear();
+		self.code_queue.clear();
+		self.healing = false;
+		self.heal_tasks.clear();
+		self.heal_queue.clear();
+	}
+
+	/// Returns the part of the pivot block to request from a peer, if any.
+	pub fn pivot_request(&mut self, peer_id: PeerId, latest_hash: &H256, difficulty: Option<U256>) -> Option<PivotRequest> {
+		if self.pivot_peer.is_some() {
+			return None;
+		}
+		let request = match self.pivot {
+			None => {
+				self.pivot_candidate = Some((*latest_hash, difficulty?));
+				PivotRequest::Header(*latest_hash)
+			},
+			Some(ref pivot) if pivot.body.is_none() => PivotRequest::Body(pivot.header.header.hash()),
+			Some(ref pivot) if pivot.receipts.is_none() => PivotRequest::Receipts(pivot.header.header.hash()),
+			Some(_) => return None,
+		};
+		self.pivot_peer = Some(peer_id);
+		Some(request)
+	}
+
+	/// Forget the peer the pivot block is being requested from.
+	pub fn clear_pivot_request(&mut self) {
+		self.pivot_peer = None;
+	}
+
+	/// Called when a peer sends a part of the pivot block.
+	pub fn on_pivot_response(&mut self, peer_id: PeerId, packet_id: SyncPacket, r: &Rlp) -> Result<(), SnapError> {
+		if self.pivot_peer != Some(peer_id) {
+			trace!(target: "snap_sync", "{}: Ignored unexpected pivot data", peer_id);
+			return Ok(());
+		}
+		self.pivot_peer = None;
+		if r.item_count()? != 1 {
+			trace!(target: "snap_sync", "{}: Pivot block not available", peer_id);
+			return Ok(());
+		}
+
+		match (packet_id, self.pivot.as_mut()) {
+			(BlockHeadersPacket, None) => {
+				let (hash, total_difficulty) = match self.pivot_candidate.take() {
+					Some(candidate) => candidate,
+					None => return Ok(()),
+				};
+				let bytes = r.at(0)?.as_raw().to_vec();
+				if keccak(&bytes) != hash {
+					return Err(SnapError::Invalid);
+				}
+				let header = SyncHeader::from_rlp(bytes, self.eip1559_transition)?;
+				debug!(target: "snap_sync", "Downloading the state of block #{} ({})", header.header.number(), hash);
+				self.pivot = Some(Pivot { header, total_difficulty, body: None, receipts: None });
+				// the ranges taken from the previous pivots are healed with the new one
+				if self.ranges_done() {
+					self.start_healing();
+				}
+			},
+			(BlockBodiesPacket, Some(pivot)) => {
+				let body = SyncBody::from_rlp(r.at(0)?.as_raw(), self.eip1559_transition)?;
+				let transactions_root = ordered_trie_root(Rlp::new(&body.transactions_bytes).iter().map(|r| raw_item_bytes(&r)));
+				if transactions_root != *pivot.header.header.transactions_root() || keccak(&body.uncles_bytes) != *pivot.header.header.uncles_hash() {
+					return Err(SnapError::Invalid);
+				}
+				pivot.body = Some(body);
+			},
+			(ReceiptsPacket, Some(pivot)) => {
+				let receipts = r.at(0)?.as_raw().to_vec();
+				let receipts_root = ordered_trie_root(Rlp::new(&receipts).iter().map(|r| raw_item_bytes(&r)));
+				if receipts_root != *pivot.header.header.receipts_root() {
+					return Err(SnapError::Invalid);
+				}
+				pivot.receipts = Some(receipts);
+			},
+			_ => trace!(target: "snap_sync", "{}: Ignored unexpected pivot data", peer_id),
+		}
+		Ok(())
+	}
+
+	/// Returns the next `snap` request to send to a peer, if there is anything to download from it.
+	pub fn next_request(&mut self, peer_id: PeerId) -> Option<(SnapPacket, Bytes)> {
+		if self.requests.contains_key(&peer_id) || self.stateless_peers.contains(&peer_id) {
+			return None;
+		}
+		let root = *self.pivot.as_ref()?.header.header.state_root();
+		let request_id = self.next_request_id;
+
+		let (packet_id, request, packet) = if !self.code_queue.is_empty() {
+			let count = ::std::cmp::min(self.code_queue.len(), MAX_CODES_TO_REQUEST);
+			let codes: Vec<_> = self.code_queue.drain(..count).collect();
+			let hashes: HashSet<_> = codes.iter().map(|&(_, code_hash)| code_hash).collect();
+			let mut rlp = RlpStream::new_list(3);
+			rlp.append(&request_id);
+			rlp.begin_list(hashes.len());
+			for hash in &hashes {
+				rlp.append(hash);
+			}
+			rlp.append(&RESPONSE_BYTES);
+			(GetByteCodesPacket, Request::ByteCodes(codes), rlp)
+		} else if let Some(first) = self.storage_tasks.pop_front() {
+			// only the storage of a single account is continued from the middle
+			let origin = first.next;
+			let mut tasks = vec![first];
+			while origin.is_zero() && tasks.len() < MAX_STORAGE_ACCOUNTS_TO_REQUEST {
+				match self.storage_tasks.front() {
+					Some(task) if task.next.is_zero() => tasks.extend(self.storage_tasks.pop_front()),
+					_ => break,
+				}
+			}
+			let mut rlp = RlpStream::new_list(6);
+			rlp.append(&request_id);
+			rlp.append(&root);
+			rlp.begin_list(tasks.len());
+			for task in &tasks {
+				rlp.append(&task.account);
+			}
+			rlp.append(&origin);
+			rlp.append(&H256::repeat_byte(0xff));
+			rlp.append(&RESPONSE_BYTES);
+			(GetStorageRangesPacket, Request::StorageRanges(tasks), rlp)
+		} else if let Some(task) = self.account_tasks.iter_mut().find(|task| !task.requested) {
+			task.requested = true;
+			let mut rlp = RlpStream::new_list(5);
+			rlp.append(&request_id);
+			rlp.append(&root);
+			rlp.append(&task.next);
+			rlp.append(&task.last);
+			rlp.append(&RESPONSE_BYTES);
+			(GetAccountRangePacket, Request::AccountRange { origin: task.next, last: task.last }, rlp)
+		} else if !self.heal_queue.is_empty() {
+			let mut keys = Vec::new();
+			while keys.len() < MAX_TRIE_NODES_TO_REQUEST {
+				match self.heal_queue.pop_front() {
+					Some(key) => if self.heal_tasks.contains_key(&key) {
+						keys.push(key);
+					},
+					None => break,
+				}
+			}
+			if keys.is_empty() {
+				return None;
+			}
+			let mut rlp = RlpStream::new_list(4);
+			rlp.append(&request_id);
+			rlp.append(&root);
+			rlp.begin_list(keys.len());
+			for key in &keys {
+				let path = range_proof::encode_path(&self.heal_tasks[key].path);
+				match key.0 {
+					None => {
+						rlp.begin_list(1).append(&path);
+					},
+					Some(ref account) => {
+						rlp.begin_list(2).append(account).append(&path);
+					},
+				}
+			}
+			rlp.append(&RESPONSE_BYTES);
+			(GetTrieNodesPacket, Request::TrieNodes(keys), rlp)
+		} else {
+			return None;
+		};
+
+		self.next_request_id += 1;
+		self.requests.insert(peer_id, PeerRequest { id: request_id, request, ask_time: Instant::now() });
+		Some((packet_id, packet.out()))
+	}
+
+	/// Called when a peer answers a `snap` request.
+	pub fn on_response(&mut self, io: &mut dyn SyncIo, peer_id: PeerId, packet_id: SnapPacket, r: &Rlp) -> Result<(), SnapError> {
+		let request_id: u64 = r.val_at(0)?;
+		let request = match self.requests.get(&peer_id) {
+			Some(request) if request.id == request_id => self.requests.remove(&peer_id).expect("the request was just found; qed").request,
+			_ => {
+				trace!(target: "snap_sync", "{}: Ignored response {:?} to unknown request {}", peer_id, packet_id, request_id);
+				return Ok(());
+			},
+		};
+
+		// responses are fully checked before anything is written, so that a bad response leaves
+		// the download as it was
+		let served = match (packet_id, &request) {
+			(AccountRangePacket, &Request::AccountRange { ref origin, ref last }) => self.on_account_range(io, r, origin, last),
+			(StorageRangesPacket, &Request::StorageRanges(ref tasks)) => self.on_storage_ranges(io, r, tasks),
+			(ByteCodesPacket, &Request::ByteCodes(ref codes)) => self.on_byte_codes(io, r, codes),
+			(TrieNodesPacket, &Request::TrieNodes(ref keys)) => self.on_trie_nodes(io, r, keys),
+			_ => Err(SnapError::Invalid),
+		};
+		match served {
+			Ok(true) => {},
+			Ok(false) => {
+				trace!(target: "snap_sync", "{}: Pivot state not available", peer_id);
+				self.stateless_peers.insert(peer_id);
+				self.restore(request);
+			},
+			Err(SnapError::Invalid) => {
+				self.restore(request);
+				return Err(SnapError::Invalid);
+			},
+			Err(e) => return Err(e),
+		}
+		self.update(io)
+	}
+
+	/// Called when a peer disconnects.
+	pub fn on_peer_aborting(&mut self, peer_id: PeerId) {
+		if self.pivot_peer == Some(peer_id) {
+			self.pivot_peer = None;
+		}
+		self.stateless_peers.remove(&peer_id);
+		if let Some(request) = self.requests.remove(&peer_id) {
+			self.restore(request.request);
+		}
+	}
+
+	/// Returns the peers which didn't answer a request in time.
+	pub fn timed_out_peers(&self, now: Instant) -> Vec<PeerId> {
+		self.requests.iter()
+			.filter(|&(_, request)| now - request.ask_time > SNAP_REQUEST_TIMEOUT)
+			.map(|(peer_id, _)| *peer_id)
+			.collect()
+	}
+
+	/// Imports the pivot block once its state is complete. Returns `false` if there's still
+	/// something to download.
+	pub fn complete(&mut self, io: &mut dyn SyncIo) -> Result<bool, EthcoreError> {
+		let ready = self.healing && self.heal_tasks.is_empty() && self.requests.is_empty() && self.pivot.as_ref()
+			.map_or(false, |pivot| pivot.body.is_some() && pivot.receipts.is_some());
+		if !ready {
+			return Ok(false);
+		}
+		let pivot = self.pivot.take().expect("the pivot was checked above; qed");
+		let receipts = pivot.receipts.expect("the receipts were checked above; qed");
+		let block = unverified_from_sync(pivot.header, pivot.body);
+		io.chain().complete_state_sync(block, receipts, pivot.total_difficulty)?;
+		Ok(true)
+	}
+
+	fn on_account_range(&mut self, io: &mut dyn SyncIo, r: &Rlp, origin: &H256, last: &H256) -> Result<bool, SnapError> {
+		let mut accounts = Vec::new();
+		for item in r.at(1)?.iter() {
+			let hash: H256 = item.val_at(0)?;
+			let account = BasicAccount::from_rlp_slim(item.at(1)?.as_raw())?;
+			accounts.push((hash, account));
+		}
+		let proof: Vec<Bytes> = r.list_at(2)?;
+		if accounts.is_empty() && proof.is_empty() {
+			return Ok(false);
+		}
+
+		let root = *self.pivot.as_ref().ok_or(SnapError::Invalid)?.header.header.state_root();
+		let entries: Vec<(H256, Bytes)> = accounts.iter().map(|(hash, account)| (*hash, rlp::encode(account))).collect();
+		let has_more = range_proof::verify_range_proof(&root, origin, &entries, &proof)?;
+		trace!(target: "snap_sync", "{} accounts from {}", entries.len(), origin);
+
+		for ((hash, account), (_, body)) in accounts.into_iter().zip(entries.iter().cloned()) {
+			// the range may run over into the next one
+			if hash > *last {
+				break;
+			}
+			self.add_account(io, hash, account, body);
+		}
+
+		let position = self.account_tasks.iter().position(|task| task.last == *last).ok_or(SnapError::Invalid)?;
+		match entries.last() {
+			Some((hash, _)) if has_more && hash < last => {
+				let task = &mut self.account_tasks[position];
+				task.next = next_hash(hash);
+				task.requested = false;
+			},
+			_ => {
+				self.account_tasks.remove(position);
+			},
+		}
+		Ok(true)
+	}
+
+	fn on_storage_ranges(&mut self, io: &mut dyn SyncIo, r: &Rlp, tasks: &[StorageTask]) -> Result<bool, SnapError> {
+		let slots = r.at(1)?;
+		let proof: Vec<Bytes> = r.list_at(2)?;
+		let count = slots.item_count()?;
+		if count == 0 {
+			return Ok(false);
+		}
+		if count > tasks.len() {
+			return Err(SnapError::Invalid);
+		}
+
+		let mut ranges = Vec::with_capacity(count);
+		for (index, (task, account_slots)) in tasks.iter().zip(slots.iter()).enumerate() {
+			let entries = account_slots.iter()
+				.map(|slot| Ok((slot.val_at(0)?, slot.val_at(1)?)))
+				.collect::<Result<Vec<(H256, Bytes)>, DecoderError>>()?;
+			// only the last range may be incomplete
+			let proof: &[Bytes] = if index + 1 == count { &proof } else { &[] };
+			let has_more = range_proof::verify_range_proof(&task.root, &task.next, &entries, proof)?;
+			ranges.push((entries, has_more));
+		}
+
+		for task in tasks[count..].iter().rev() {
+			self.storage_tasks.push_front(task.clone());
+		}
+		for (task, (entries, has_more)) in tasks.iter().zip(ranges) {
+			let mut task = task.clone();
+			if !entries.is_empty() {
+				task.local_root = io.chain().import_state_entries(Some(&task.account), &task.local_root, &entries)?;
+			}
+			match entries.last() {
+				Some((hash, _)) if has_more => {
+					task.next = next_hash(hash);
+					self.storage_tasks.push_front(task);
+				},
+				_ => self.dependency_done(io, (Some(task.account), task.root))?,
+			}
+		}
+		Ok(true)
+	}
+
+	fn on_byte_codes(&mut self, io: &mut dyn SyncIo, r: &Rlp, codes: &[(H256, H256)]) -> Result<bool, SnapError> {
+		let received: Vec<Bytes> = r.list_at(1)?;
+		if received.is_empty() {
+			return Ok(false);
+		}
+		let received: HashMap<H256, Bytes> = received.into_iter().map(|code| (keccak(&code), code)).collect();
+		if received.keys().any(|hash| !codes.iter().any(|&(_, code_hash)| code_hash == *hash)) {
+			return Err(SnapError::Invalid);
+		}
+
+		for &(account, code_hash) in codes {
+			match received.get(&code_hash) {
+				Some(code) => {
+					io.chain().import_state_nodes(Some(&account), &[code.clone()])?;
+					// stored by hash alone too, to serve it to other peers
+					io.chain().import_state_nodes(None, &[code.clone()])?;
+					self.dependency_done(io, (Some(account), code_hash))?;
+				},
+				None => self.code_queue.push_back((account, code_hash)),
+			}
+		}
+		Ok(true)
+	}
+
+	fn on_trie_nodes(&mut self, io: &mut dyn SyncIo, r: &Rlp, keys: &[HealKey]) -> Result<bool, SnapError> {
+		let nodes: Vec<Bytes> = r.list_at(1)?;
+		if nodes.is_empty() {
+			return Ok(false);
+		}
+		if nodes.len() > keys.len() || keys.iter().zip(&nodes).any(|(key, node)| keccak(node) != key.1) {
+			return Err(SnapError::Invalid);
+		}
+		for key in keys[nodes.len()..].iter().rev() {
+			self.heal_queue.push_front(*key);
+		}
+		for (key, node) in keys.iter().zip(nodes) {
+			self.add_heal_node(io, key, node)?;
+		}
+		Ok(true)
+	}
+
+	/// Adds a downloaded account, to be written once its storage and code are downloaded too.
+	fn add_account(&mut self, io: &mut dyn SyncIo, hash: H256, account: BasicAccount, body: Bytes) {
+		let mut missing = 0;
+		if account.storage_root != KECCAK_NULL_RLP && !io.chain().has_state_node(Some(&hash), &account.storage_root) {
+			self.storage_tasks.push_back(StorageTask {
+				account: hash,
+				root: account.storage_root,
+				next: H256::zero(),
+				local_root: KECCAK_NULL_RLP,
+			});
+			missing += 1;
+		}
+		if account.code_hash != KECCAK_EMPTY && !io.chain().has_state_node(Some(&hash), &account.code_hash) {
+			self.code_queue.push_back((hash, account.code_hash));
+			missing += 1;
+		}
+		if missing == 0 {
+			self.ready_accounts.push((hash, body));
+		} else {
+			self.pending_accounts.insert(hash, (body, missing));
+		}
+	}
+
+	/// Called once the storage or code of an account is in the database.
+	fn dependency_done(&mut self, io: &mut dyn SyncIo, key: HealKey) -> Result<(), SnapError> {
+		if self.heal_tasks.contains_key(&key) {
+			return self.commit_heal_node(io, key);
+		}
+		let account = key.0.expect("only storage and codes are dependencies of accounts; qed");
+		let ready = match self.pending_accounts.get_mut(&account) {
+			Some(pending) => {
+				pending.1 -= 1;
+				pending.1 == 0
+			},
+			None => false,
+		};
+		if ready {
+			let (body, _) = self.pending_accounts.remove(&account).expect("the account was just found; qed");
+			self.ready_accounts.push((account, body));
+		}
+		Ok(())
+	}
+
+	/// Keeps a downloaded trie node until its children are all in the database.
+	fn add_heal_node(&mut self, io: &mut dyn SyncIo, key: &HealKey, node: Bytes) -> Result<(), SnapError> {
+		let path = match self.heal_tasks.get(key) {
+			Some(task) => task.path.clone(),
+			None => return Ok(()),
+		};
+
+		let mut missing = 0;
+		for reference in range_proof::node_references(&node)? {
+			match reference {
+				NodeReference::Child(child_path, hash) => {
+					let child_path = [&path[..], &child_path[..]].concat();
+					if self.add_heal_dependency(io, key, (key.0, hash), child_path, false) {
+						missing += 1;
+					}
+				},
+				NodeReference::Leaf(leaf_path, value) if key.0.is_none() => {
+					// the storage and the code of an account are needed before the account is complete
+					let leaf_path = [&path[..], &leaf_path[..]].concat();
+					if leaf_path.len() != 64 {
+						return Err(SnapError::Invalid);
+					}
+					let account_hash = H256::from_slice(&range_proof::from_nibbles(&leaf_path));
+					let account: BasicAccount = rlp::decode(&value)?;
+					if account.storage_root != KECCAK_NULL_RLP && self.add_heal_dependency(io, key, (Some(account_hash), account.storage_root), Vec::new(), false) {
+						missing += 1;
+					}
+					if account.code_hash != KECCAK_EMPTY && self.add_heal_dependency(io, key, (Some(account_hash), account.code_hash), Vec::new(), true) {
+						missing += 1;
+					}
+				},
+				NodeReference::Leaf(..) => {},
+			}
+		}
+
+		let task = self.heal_tasks.get_mut(key).expect("the task was just found; qed");
+		task.data = Some(node);
+		task.missing += missing;
+		if task.missing == 0 {
+			self.commit_heal_node(io, *key)?;
+		}
+		Ok(())
+	}
+
+	/// Registers a node or a code needed by a downloaded node. Returns `false` if it's in the
+	/// database already.
+	fn add_heal_dependency(&mut self, io: &mut dyn SyncIo, parent: &HealKey, key: HealKey, path: Vec<u8>, is_code: bool) -> bool {
+		if let Some(task) = self.heal_tasks.get_mut(&key) {
+			task.parents.push(*parent);
+			return true;
+		}
+		if io.chain().has_state_node(key.0.as_ref(), &key.1) {
+			return false;
+		}
+		self.heal_tasks.insert(key, HealTask { path, data: None, missing: 0, parents: vec![*parent] });
+		match key.0 {
+			Some(account) if is_code => self.code_queue.push_back((account, key.1)),
+			_ => self.heal_queue.push_back(key),
+		}
+		true
+	}
+
+	/// Writes a healed node, whose children are all in the database, and the nodes waiting for it
+	/// in turn.
+	fn commit_heal_node(&mut self, io: &mut dyn SyncIo, key: HealKey) -> Result<(), SnapError> {
+		let mut ready = vec![key];
+		while let Some(key) = ready.pop() {
+			let task = match self.heal_tasks.remove(&key) {
+				Some(task) => task,
+				None => continue,
+			};
+			if let Some(ref data) = task.data {
+				io.chain().import_state_nodes(key.0.as_ref(), &[data.clone()])?;
+			}
+			for parent in task.parents {
+				if let Some(parent_task) = self.heal_tasks.get_mut(&parent) {
+					parent_task.missing -= 1;
+					if parent_task.missing == 0 && parent_task.data.is_some() {
+						ready.push(parent);
+					}
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// Starts healing the tries from the state root of the pivot.
+	fn start_healing(&mut self) {
+		self.healing = true;
+		self.heal_tasks.clear();
+		self.heal_queue.clear();
+		if let Some(ref pivot) = self.pivot {
+			let key = (None, *pivot.header.header.state_root());
+			self.heal_tasks.insert(key, HealTask { path: Vec::new(), data: None, missing: 0, parents: Vec::new() });
+			self.heal_queue.push_back(key);
+		}
+	}
+
+	/// Writes the accounts that are ready and starts healing once all the ranges are downloaded.
+	fn update(&mut self, io: &mut dyn SyncIo) -> Result<(), SnapError> {
+		if !self.ready_accounts.is_empty() {
+			let accounts = mem::replace(&mut self.ready_accounts, Vec::new());
+			self.account_root = io.chain().import_state_entries(None, &self.account_root, &accounts)?;
+		}
+
+		if self.ranges_done() && !self.healing {
+			let state_root = match self.pivot {
+				Some(ref pivot) => *pivot.header.header.state_root(),
+				None => return Ok(()),
+			};
+			if io.chain().has_state_node(None, &state_root) {
+				debug!(target: "snap_sync", "State ranges downloaded, the state is complete");
+				self.healing = true;
+			} else {
+				debug!(target: "snap_sync", "State ranges downloaded, healing the tries");
+				self.start_healing();
+			}
+		}
+		Ok(())
+	}
+
+	fn ranges_done(&self) -> bool {
+		self.account_tasks.is_empty()
+			&& self.storage_tasks.is_empty()
+			&& self.pending_accounts.is_empty()
+			&& self.code_queue.is_empty()
+	}
+
+	/// Puts back the data of a request which was not answered.
+	fn restore(&mut self, request: Request) {
+		match request {
+			Request::AccountRange { last, .. } => {
+				if let Some(task) = self.account_tasks.iter_mut().find(|task| task.last == last) {
+					task.requested = false;
+				}
+			},
+			Request::StorageRanges(tasks) => {
+				for task in tasks.into_iter().rev() {
+					self.storage_tasks.push_front(task);
+				}
+			},
+			Request::ByteCodes(codes) => {
+				for code in codes.into_iter().rev() {
+					self.code_queue.push_front(code);
+				}
+			},
+			Request::TrieNodes(keys) => {
+				for key in keys.into_iter().rev() {
+					self.heal_queue.push_front(key);
+				}
+			},
+		}
+	}
+}
+
+/// Returns the hash following the given one.
+fn next_hash(hash: &H256) -> H256 {
+	let next = U256::from_big_endian(hash.as_bytes()).overflowing_add(U256::one()).0;
+	let mut result = H256::zero();
+	next.to_big_endian(result.as_bytes_mut());
+	result
+}
+
+#[cfg(test)]
+mod test {
+	use std::collections::VecDeque;
+	use std::time::{Duration, Instant};
+
+	use super::{next_hash, PivotRequest, SnapSync, ACCOUNT_RANGES, SNAP_REQUEST_TIMEOUT};
+	use crate::{
+		chain::sync_packet::{PacketInfo, SnapPacket, SyncPacket::BlockHeadersPacket},
+		tests::{helpers::TestIo, snapshot::TestSnapshotService},
+	};
+
+	use ethcore::test_helpers::TestBlockChainClient;
+	use ethereum_types::{H256, U256};
+	use keccak_hash::keccak;
+	use parking_lot::RwLock;
+	use rlp::{Rlp, RlpStream};
+	use common_types::header::Header;
+
+	fn sync_with_pivot() -> SnapSync {
+		let mut snap = SnapSync::new(u64::max_value());
+		let header = ::rlp::encode(&Header::default());
+		let hash = keccak(&header);
+		assert_eq!(snap.pivot_request(0, &hash, Some(U256::from(100))), Some(PivotRequest::Header(hash)));
+
+		let mut rlp = RlpStream::new_list(1);
+		rlp.append_raw(&header, 1);
+		snap.on_pivot_response(0, BlockHeadersPacket, &Rlp::new(&rlp.out())).unwrap();
+		snap
+	}
+
+	#[test]
+	fn increments_hashes() {
+		assert_eq!(next_hash(&H256::zero()), H256::from_low_u64_be(1));
+		assert_eq!(next_hash(&H256::from_low_u64_be(0xff)), H256::from_low_u64_be(0x100));
+	}
+
+	#[test]
+	fn requests_the_pivot_block_one_part_at_a_time() {
+		let mut snap = sync_with_pivot();
+		let hash = keccak(::rlp::encode(&Header::default()));
+
+		assert_eq!(snap.pivot_request(1, &H256::zero(), None), Some(PivotRequest::Body(hash)));
+		assert_eq!(snap.pivot_request(2, &H256::zero(), None), None);
+		snap.on_peer_aborting(1);
+		assert_eq!(snap.pivot_request(2, &H256::zero(), None), Some(PivotRequest::Body(hash)));
+	}
+
+	#[test]
+	fn splits_the_account_trie_between_peers() {
+		let mut snap = sync_with_pivot();
+
+		let mut origins = Vec::new();
+		for peer in 0..ACCOUNT_RANGES as usize {
+			let (packet_id, packet) = snap.next_request(peer).unwrap();
+			assert_eq!(packet_id.id(), SnapPacket::GetAccountRangePacket.id());
+			let rlp = Rlp::new(&packet);
+			assert_eq!(rlp.val_at::<u64>(0).unwrap(), peer as u64);
+			origins.push(rlp.val_at::<H256>(2).unwrap());
+		}
+		assert_eq!(origins[0], H256::zero());
+		assert_eq!(origins[1].as_bytes()[0], 0x10);
+		assert!(snap.next_request(ACCOUNT_RANGES as usize).is_none());
+		// a request stays in flight until answered
+		assert!(snap.next_request(0).is_none());
+
+		// the range of a peer going away is requested again
+		snap.on_peer_aborting(1);
+		let (_, packet) = snap.next_request(ACCOUNT_RANGES as usize).unwrap();
+		assert_eq!(Rlp::new(&packet).val_at::<H256>(2).unwrap(), origins[1]);
+	}
+
+	#[test]
+	fn marks_peers_without_the_pivot_state() {
+		let mut client = TestBlockChainClient::new();
+		let queue = RwLock::new(VecDeque::new());
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &queue, None, None);
+		let mut snap = sync_with_pivot();
+
+		let (_, packet) = snap.next_request(0).unwrap();
+		let request_id: u64 = Rlp::new(&packet).val_at(0).unwrap();
+		let mut response = RlpStream::new_list(3);
+		response.append(&request_id);
+		response.begin_list(0);
+		response.begin_list(0);
+		snap.on_response(&mut io, 0, SnapPacket::AccountRangePacket, &Rlp::new(&response.out())).unwrap();
+
+		assert_eq!(snap.stateless_peers(), 1);
+		assert!(snap.next_request(0).is_none());
+		// the range goes to another peer
+		let (_, packet) = snap.next_request(1).unwrap();
+		assert_eq!(Rlp::new(&packet).val_at::<H256>(2).unwrap(), H256::zero());
+
+		snap.reset_pivot();
+		assert_eq!(snap.stateless_peers(), 0);
+		assert!(snap.next_request(0).is_none());
+	}
+
+	#[test]
+	fn times_out_requests() {
+		let mut snap = sync_with_pivot();
+		snap.next_request(3).unwrap();
+
+		assert!(snap.timed_out_peers(Instant::now()).is_empty());
+		assert_eq!(snap.timed_out_peers(Instant::now() + SNAP_REQUEST_TIMEOUT + Duration::from_secs(1)), vec![3]);
+	}
+}
@@ -17,7 +17,7 @@
 use std::sync::Arc;
 use std::collections::HashMap;
 
-use crate::chain::sync_packet::{PacketInfo, SyncPacket};
+use crate::chain::sync_packet::{PacketInfo, SnapPacket, SyncPacket};
 
 use bytes::Bytes;
 use client_traits::BlockChainClient;
@@ -40,6 +40,8 @@ pub trait SyncIo {
 	fn respond(&mut self, packet_id: PacketId, data: Vec<u8>) -> Result<(), Error>;
 	/// Send a packet to a peer using specified protocol.
 	fn send(&mut self, peer_id: PeerId, packet_id: SyncPacket, data: Vec<u8>) -> Result<(), Error>;
+	/// Send a `snap` protocol packet to a peer.
+	fn send_snap(&mut self, peer_id: PeerId, packet_id: SnapPacket, data: Vec<u8>) -> Result<(), Error>;
 	/// Get the blockchain
 	fn chain(&self) -> &dyn BlockChainClient;
 	/// Get the snapshot service.
@@ -113,6 +115,10 @@ impl<'s> SyncIo for NetSyncIo<'s> {
 		self.network.send_protocol(packet_id.protocol(), peer_id, packet_id.id(), data)
 	}
 
+	fn send_snap(&mut self, peer_id: PeerId, packet_id: SnapPacket, data: Vec<u8>) -> Result<(), Error>{
+		self.network.send_protocol(packet_id.protocol(), peer_id, packet_id.id(), data)
+	}
+
 	fn chain(&self) -> &dyn BlockChainClient {
 		self.chain
 	}
@@ -22,6 +22,7 @@ use crate::{
 	chain::{
 		sync_packet::{
 			PacketInfo,
+			SnapPacket,
 			SyncPacket::{self, PrivateTransactionPacket, SignedPrivateTransactionPacket}
 		},
 		ChainSync, SyncSupplier, ETH_PROTOCOL_VERSION_63, PAR_PROTOCOL_VERSION_4
@@ -73,6 +74,7 @@ pub struct TestIo<'p, C> where C: FlushingBlockChainClient, C: 'p {
 	pub to_disconnect: HashSet<PeerId>,
 	pub packets: Vec<TestPacket>,
 	pub peers_info: HashMap<PeerId, String>,
+	pub eth_protocol_versions: HashMap<PeerId, u8>,
 	pub private_state_db: Option<Arc<PrivateStateDB>>,
 	overlay: RwLock<HashMap<BlockNumber, Bytes>>,
 }
@@ -93,6 +95,7 @@ impl<'p, C> TestIo<'p, C> where C: FlushingBlockChainClient, C: 'p {
 			to_disconnect: HashSet::new(),
 			packets: Vec::new(),
 			peers_info: HashMap::new(),
+			eth_protocol_versions: HashMap::new(),
 			private_state_db,
 			overlay: RwLock::new(HashMap::new()),
 		}
@@ -128,6 +131,13 @@ impl<'p, C> SyncIo for TestIo<'p, C> where C: FlushingBlockChainClient, C: 'p {
 		Ok(())
 	}
 
+	fn send_snap(&mut self, peer_id: PeerId, packet_id: SnapPacket, data: Vec<u8>) -> Result<(), network::Error> {
+		self.packets.push(
+			TestPacket { data, packet_id: packet_id.id(), recipient: peer_id }
+		);
+		Ok(())
+	}
+
 	fn chain(&self) -> &dyn BlockChainClient {
 		&*self.chain
 	}
@@ -155,8 +165,8 @@ impl<'p, C> SyncIo for TestIo<'p, C> where C: FlushingBlockChainClient, C: 'p {
 		None
 	}
 
-	fn eth_protocol_version(&self, _peer: PeerId) -> u8 {
-		ETH_PROTOCOL_VERSION_63.0
+	fn eth_protocol_version(&self, peer_id: PeerId) -> u8 {
+		self.eth_protocol_versions.get(&peer_id).cloned().unwrap_or(ETH_PROTOCOL_VERSION_63.0)
 	}
 
 	fn protocol_version(&self, protocol: &ProtocolId, peer_id: PeerId) -> u8 {
@@ -27,6 +27,7 @@ mod db;
 mod executive_tracer;
 mod import;
 mod noop_tracer;
+mod struct_log;
 mod types;
 
 pub use crate::{
@@ -36,6 +37,7 @@ pub use crate::{
 	executive_tracer::{ExecutiveTracer, ExecutiveVMTracer},
 	import::ImportRequest,
 	noop_tracer::{NoopTracer, NoopVMTracer},
+	struct_log::{StructLog, StructLogOptions, struct_logs},
 	types::{
 		Tracing,
 		error::Error as TraceError,
@@ -99,6 +101,9 @@ pub trait VMTracer: Send {
 	/// Trace the finalised execution of a single valid instruction.
 	fn trace_executed(&mut self, _gas_used: U256, _stack_push: &[U256], _mem: &[u8]) {}
 
+	/// Trace a change of the refund counter by `delta`.
+	fn trace_refund(&mut self, _delta: i128) {}
+
 	/// Spawn subtracer which will be used to trace deeper levels of execution.
 	fn prepare_subtrace(&mut self, _code: &[u8]) {}
 
@@ -0,0 +1,259 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Geth-style struct logs, rebuilt from the VM traces produced by `ExecutiveVMTracer`.
+
+use std::collections::BTreeMap;
+
+use ethereum_types::{BigEndianHash, H256, U256};
+use evm::Instruction;
+
+use crate::trace::VMTrace;
+
+/// Parts of the machine state captured for every step.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct StructLogOptions {
+	/// Don't capture the stack.
+	pub disable_stack: bool,
+	/// Don't capture the memory.
+	pub disable_memory: bool,
+	/// Don't capture the storage.
+	pub disable_storage: bool,
+}
+
+/// State of the machine right before an instruction is executed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructLog {
+	/// Program counter.
+	pub pc: usize,
+	/// Mnemonic of the instruction.
+	pub op: String,
+	/// Gas left before the instruction.
+	pub gas: U256,
+	/// Gas cost of the instruction.
+	pub gas_cost: U256,
+	/// Call depth, starting at 1.
+	pub depth: usize,
+	/// Stack, bottom first.
+	pub stack: Option<Vec<U256>>,
+	/// Memory.
+	pub memory: Option<Vec<u8>>,
+	/// Storage slots of the executing contract accessed so far.
+	pub storage: Option<BTreeMap<H256, H256>>,
+}
+
+/// Replays a VM trace, returning the machine state before every executed instruction.
+pub fn struct_logs(trace: &VMTrace, options: StructLogOptions) -> Vec<StructLog> {
+	let mut logs = Vec::new();
+	replay(trace, 1, options, &mut logs);
+	logs
+}
+
+fn replay(trace: &VMTrace, depth: usize, options: StructLogOptions, logs: &mut Vec<StructLog>) {
+	let mut stack: Vec<U256> = Vec::new();
+	let mut memory: Vec<u8> = Vec::new();
+	let mut storage: BTreeMap<H256, H256> = BTreeMap::new();
+	let mut subs = trace.subs.iter().peekable();
+
+	// Only the gas left after each step is traced, so the gas available to the frame
+	// is derived from its first step.
+	let mut gas = trace.operations.first()
+		.and_then(|op| op.executed.as_ref().map(|ex| ex.gas_used.saturating_add(op.gas_cost)))
+		.unwrap_or_default();
+
+	for (step, op) in trace.operations.iter().enumerate() {
+		let instruction = Instruction::from_u8(op.instruction);
+		// like geth, log the memory already expanded by the instruction.
+		if op.executed.is_some() {
+			if let Some(end) = instruction.and_then(|i| memory_end(i, &stack)) {
+				if end > U256::from(memory.len()) && end <= U256::from(usize::max_value()) {
+					memory.resize(end.as_usize().saturating_add(31) / 32 * 32, 0);
+				}
+			}
+		}
+
+		let mut log = StructLog {
+			pc: op.pc,
+			op: instruction.map_or_else(
+				|| format!("opcode {:#x} not defined", op.instruction),
+				|i| i.info().name.to_owned(),
+			),
+			gas,
+			gas_cost: op.gas_cost,
+			depth,
+			stack: if options.disable_stack { None } else { Some(stack.clone()) },
+			memory: if options.disable_memory { None } else { Some(memory.clone()) },
+			storage: None,
+		};
+
+		if let Some(ref ex) = op.executed {
+			if let Some(ref diff) = ex.store_diff {
+				storage.insert(BigEndianHash::from_uint(&diff.location), BigEndianHash::from_uint(&diff.value));
+			}
+			if instruction == Some(Instruction::SLOAD) {
+				if let (Some(key), Some(value)) = (stack.last(), ex.stack_push.first()) {
+					storage.insert(BigEndianHash::from_uint(key), BigEndianHash::from_uint(value));
+				}
+			}
+			if let Some(ref diff) = ex.mem_diff {
+				let end = diff.offset + diff.data.len();
+				if memory.len() < end {
+					memory.resize((end + 31) / 32 * 32, 0);
+				}
+				memory[diff.offset..end].copy_from_slice(&diff.data);
+			}
+
+			let args = instruction.map_or(0, |i| i.info().args);
+			let len = stack.len().saturating_sub(args);
+			stack.truncate(len);
+			stack.extend_from_slice(&ex.stack_push);
+			gas = ex.gas_used;
+		}
+
+		if !options.disable_storage {
+			log.storage = Some(storage.clone());
+		}
+		logs.push(log);
+
+		while subs.peek().map_or(false, |sub| sub.parent_step == step) {
+			let sub = subs.next().expect("peeked above; qed");
+			replay(sub, depth + 1, options, logs);
+		}
+	}
+}
+
+/// End of the memory range accessed by an instruction, given the stack before it's executed.
+fn memory_end(instruction: Instruction, stack: &[U256]) -> Option<U256> {
+	let arg = |n: usize| stack.len().checked_sub(n + 1).map(|i| stack[i]);
+	let end = |offset: U256, size: U256| if size.is_zero() { U256::zero() } else { offset.saturating_add(size) };
+	let range = |offset: usize, size: usize| Some(end(arg(offset)?, arg(size)?));
+
+	match instruction {
+		Instruction::MLOAD | Instruction::MSTORE => Some(end(arg(0)?, 32.into())),
+		Instruction::MSTORE8 => Some(end(arg(0)?, 1.into())),
+		Instruction::SHA3 | Instruction::RETURN | Instruction::REVERT
+			| Instruction::LOG0 | Instruction::LOG1 | Instruction::LOG2 | Instruction::LOG3 | Instruction::LOG4 => range(0, 1),
+		Instruction::CALLDATACOPY | Instruction::CODECOPY | Instruction::RETURNDATACOPY => range(0, 2),
+		Instruction::EXTCODECOPY => range(1, 3),
+		Instruction::CREATE | Instruction::CREATE2 => range(1, 2),
+		Instruction::CALL | Instruction::CALLCODE => Some(::std::cmp::max(range(3, 4)?, range(5, 6)?)),
+		Instruction::DELEGATECALL | Instruction::STATICCALL => Some(::std::cmp::max(range(2, 3)?, range(4, 5)?)),
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::trace::{VMOperation, VMExecutedOperation, MemoryDiff, StorageDiff};
+
+	fn op(pc: usize, instruction: Instruction, gas_cost: u64, gas_left: u64, stack_push: Vec<U256>) -> VMOperation {
+		VMOperation {
+			pc,
+			instruction: instruction as u8,
+			gas_cost: gas_cost.into(),
+			executed: Some(VMExecutedOperation {
+				gas_used: gas_left.into(),
+				stack_push,
+				mem_diff: None,
+				store_diff: None,
+			}),
+		}
+	}
+
+	#[test]
+	fn should_rebuild_stack_memory_and_storage() {
+		// PUSH1 0x2a PUSH1 0 MSTORE PUSH1 0x2a PUSH1 1 SSTORE
+		let mut mstore = op(4, Instruction::MSTORE, 6, 985, vec![]);
+		mstore.executed.as_mut().unwrap().mem_diff = Some(MemoryDiff { offset: 0, data: H256::from_low_u64_be(0x2a).as_bytes().to_vec() });
+		let mut sstore = op(9, Instruction::SSTORE, 20_000, 0, vec![]);
+		sstore.executed.as_mut().unwrap().store_diff = Some(StorageDiff { location: 1.into(), value: 0x2a.into() });
+		let trace = VMTrace {
+			parent_step: 0,
+			code: vec![],
+			operations: vec![
+				op(0, Instruction::PUSH1, 3, 997, vec![0x2a.into()]),
+				op(2, Instruction::PUSH1, 3, 994, vec![0.into()]),
+				mstore,
+				op(5, Instruction::PUSH1, 3, 982, vec![0x2a.into()]),
+				op(7, Instruction::PUSH1, 3, 979, vec![1.into()]),
+				sstore,
+			],
+			subs: vec![],
+		};
+
+		let logs = struct_logs(&trace, Default::default());
+
+		assert_eq!(logs.len(), 6);
+		assert_eq!(logs[0].op, "PUSH1");
+		assert_eq!(logs[0].gas, 1000.into());
+		assert_eq!(logs[2].op, "MSTORE");
+		assert_eq!(logs[2].gas, 994.into());
+		assert_eq!(logs[2].stack, Some(vec![0x2a.into(), 0.into()]));
+		assert_eq!(logs[2].memory, Some(vec![0; 32]));
+		assert_eq!(logs[3].stack, Some(vec![]));
+		assert_eq!(logs[3].memory.as_ref().map(|m| m.len()), Some(32));
+		assert_eq!(logs[5].gas_cost, 20_000.into());
+		assert_eq!(logs[5].storage.as_ref().unwrap().get(&H256::from_low_u64_be(1)), Some(&H256::from_low_u64_be(0x2a)));
+		assert!(logs.iter().all(|log| log.depth == 1));
+	}
+
+	#[test]
+	fn should_log_expanded_memory() {
+		// PUSH1 0x20 MLOAD STOP
+		let trace = VMTrace {
+			parent_step: 0,
+			code: vec![],
+			operations: vec![
+				op(0, Instruction::PUSH1, 3, 997, vec![0x20.into()]),
+				op(2, Instruction::MLOAD, 9, 988, vec![0.into()]),
+				op(3, Instruction::STOP, 0, 988, vec![]),
+			],
+			subs: vec![],
+		};
+
+		let logs = struct_logs(&trace, Default::default());
+
+		assert_eq!(logs[0].memory, Some(vec![]));
+		assert_eq!(logs[1].memory, Some(vec![0; 64]));
+		assert_eq!(logs[2].memory, Some(vec![0; 64]));
+	}
+
+	#[test]
+	fn should_respect_options_and_depth() {
+		let trace = VMTrace {
+			parent_step: 0,
+			code: vec![],
+			operations: vec![
+				op(0, Instruction::CALL, 100, 900, vec![1.into()]),
+				op(1, Instruction::STOP, 0, 900, vec![]),
+			],
+			subs: vec![VMTrace {
+				parent_step: 0,
+				code: vec![],
+				operations: vec![op(0, Instruction::STOP, 0, 50, vec![])],
+				subs: vec![],
+			}],
+		};
+
+		let options = StructLogOptions { disable_stack: true, disable_memory: true, disable_storage: true };
+		let logs = struct_logs(&trace, options);
+
+		assert_eq!(logs.iter().map(|log| log.depth).collect::<Vec<_>>(), vec![1, 2, 1]);
+		assert_eq!(logs[1].op, "STOP");
+		assert!(logs.iter().all(|log| log.stack.is_none() && log.memory.is_none() && log.storage.is_none()));
+	}
+}
@@ -17,6 +17,7 @@
 //! Basic account type -- the decoded RLP from the state trie.
 
 use ethereum_types::{U256, H256};
+use hash::{KECCAK_EMPTY, KECCAK_NULL_RLP};
 
 /// Basic account type.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -33,6 +34,56 @@ pub struct BasicAccount {
 	pub code_version: U256,
 }
 
+impl BasicAccount {
+	/// Encodes the account in the "slim" format of the `snap` protocol, where the empty storage
+	/// root and the empty code hash are left out.
+	pub fn rlp_slim(&self) -> Vec<u8> {
+		let mut stream = rlp::RlpStream::new_list(if self.code_version.is_zero() { 4 } else { 5 });
+		stream.append(&self.nonce);
+		stream.append(&self.balance);
+		if self.storage_root == KECCAK_NULL_RLP {
+			stream.append_empty_data();
+		} else {
+			stream.append(&self.storage_root);
+		}
+		if self.code_hash == KECCAK_EMPTY {
+			stream.append_empty_data();
+		} else {
+			stream.append(&self.code_hash);
+		}
+		if !self.code_version.is_zero() {
+			stream.append(&self.code_version);
+		}
+		stream.out()
+	}
+
+	/// Decodes an account encoded in the "slim" format of the `snap` protocol.
+	pub fn from_rlp_slim(bytes: &[u8]) -> Result<Self, rlp::DecoderError> {
+		let rlp = rlp::Rlp::new(bytes);
+		let hash_or = |index: usize, empty: H256| -> Result<H256, rlp::DecoderError> {
+			let item = rlp.at(index)?;
+			if item.is_empty() {
+				Ok(empty)
+			} else {
+				item.as_val()
+			}
+		};
+		let code_version = match rlp.item_count()? {
+			4 => U256::zero(),
+			5 => rlp.val_at(4)?,
+			_ => return Err(rlp::DecoderError::RlpIncorrectListLen),
+		};
+
+		Ok(BasicAccount {
+			nonce: rlp.val_at(0)?,
+			balance: rlp.val_at(1)?,
+			storage_root: hash_or(2, KECCAK_NULL_RLP)?,
+			code_hash: hash_or(3, KECCAK_EMPTY)?,
+			code_version,
+		})
+	}
+}
+
 impl rlp::Encodable for BasicAccount {
 	fn rlp_append(&self, stream: &mut rlp::RlpStream) {
 		let use_short_version = self.code_version == U256::zero();
@@ -74,3 +125,28 @@ impl rlp::Decodable for BasicAccount {
 		})
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use hash::{KECCAK_EMPTY, KECCAK_NULL_RLP};
+	use ethereum_types::H256;
+	use super::BasicAccount;
+
+	#[test]
+	fn slim_encoding_roundtrip() {
+		let account = BasicAccount {
+			nonce: 1.into(),
+			balance: 100.into(),
+			storage_root: KECCAK_NULL_RLP,
+			code_hash: KECCAK_EMPTY,
+			code_version: 0.into(),
+		};
+		let slim = account.rlp_slim();
+		assert!(slim.len() < rlp::encode(&account).len());
+		assert_eq!(BasicAccount::from_rlp_slim(&slim).unwrap(), account);
+
+		let contract = BasicAccount { code_hash: H256::from_low_u64_be(5), storage_root: H256::from_low_u64_be(7), ..account };
+		assert_eq!(contract.rlp_slim(), rlp::encode(&contract));
+		assert_eq!(BasicAccount::from_rlp_slim(&contract.rlp_slim()).unwrap(), contract);
+	}
+}
@@ -51,8 +51,9 @@ impl Block {
 	}
 }
 
-impl Decodable for Block {
-	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+impl Block {
+	/// Decode a block, see `Header::decode_rlp`.
+	pub fn decode_rlp(rlp: &Rlp, eip1559_transition: BlockNumber) -> Result<Self, DecoderError> {
 		if rlp.as_raw().len() != rlp.payload_info()?.total() {
 			return Err(DecoderError::RlpIsTooBig);
 		}
@@ -60,13 +61,25 @@ impl Decodable for Block {
 			return Err(DecoderError::RlpIncorrectListLen);
 		}
 		Ok(Block {
-			header: rlp.val_at(0)?,
+			header: Header::decode_rlp(&rlp.at(0)?, eip1559_transition)?,
 			transactions: rlp.list_at(1)?,
-			uncles: rlp.list_at(2)?,
+			uncles: decode_uncles(&rlp.at(2)?, eip1559_transition)?,
 		})
 	}
 }
 
+/// Decode a list of uncle headers, see `Header::decode_rlp`.
+pub fn decode_uncles(rlp: &Rlp, eip1559_transition: BlockNumber) -> Result<Vec<Header>, DecoderError> {
+	rlp.iter().map(|uncle| Header::decode_rlp(&uncle, eip1559_transition)).collect()
+}
+
+/// Decodes blocks of chains without EIP-1559, see `Block::decode_rlp`.
+impl Decodable for Block {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		Block::decode_rlp(rlp, BlockNumber::max_value())
+	}
+}
+
 /// Preprocessed block data gathered in `verify_block_unordered` call
 #[derive(MallocSizeOf)]
 pub struct PreverifiedBlock {
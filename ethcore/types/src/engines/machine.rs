@@ -23,6 +23,7 @@ use crate::{
 	log_entry::LogEntry,
 	receipt,
 	state_diff::StateDiff,
+	transaction::AccessList,
 };
 
 /// Type alias for a function we can make calls through synchronously.
@@ -92,4 +93,8 @@ pub struct Executed<T, V> {
 	pub vm_trace: Option<V>,
 	/// The state diff, if we traced it.
 	pub state_diff: Option<StateDiff>,
+	/// The addresses and storage keys accessed by the transaction, if we recorded them.
+	/// The sender, the recipient, precompiles and created contracts are only listed when
+	/// some of their storage has been accessed.
+	pub access_list: Option<AccessList>,
 }
@@ -102,6 +102,29 @@ pub struct CommonParams {
 	pub eip2028_transition: BlockNumber,
 	/// Number of first block where EIP-2200 advance transition begin.
 	pub eip2200_advance_transition: BlockNumber,
+	/// Number of first block where EIP-2929 rules begin.
+	pub eip2929_transition: BlockNumber,
+	/// Number of first block where EIP-2718 typed transactions and EIP-2930 access list transactions are accepted.
+	pub eip2930_transition: BlockNumber,
+	/// Number of first block where EIP-1559 rules begin: headers carry a base fee which is burnt,
+	/// and EIP-1559 transactions are accepted.
+	pub eip1559_transition: BlockNumber,
+	/// Bound divisor of the base fee change between blocks (EIP-1559).
+	pub eip1559_base_fee_max_change_denominator: U256,
+	/// Ratio of the block gas limit to the block gas target (EIP-1559).
+	pub eip1559_elasticity_multiplier: U256,
+	/// Base fee of the first EIP-1559 block.
+	pub eip1559_base_fee_initial_value: U256,
+	/// Number of first block where EIP-3198 rules begin (`BASEFEE` opcode).
+	pub eip3198_transition: BlockNumber,
+	/// Number of first block where EIP-3529 rules begin (reduced refunds).
+	pub eip3529_transition: BlockNumber,
+	/// Number of first block where EIP-3541 rules begin (new code starting with 0xEF is rejected).
+	pub eip3541_transition: BlockNumber,
+	/// Number of first block where EIP-3855 rules begin (`PUSH0` opcode).
+	pub eip3855_transition: BlockNumber,
+	/// Number of first block where EIP-3860 rules begin (init code size limit and metering).
+	pub eip3860_transition: BlockNumber,
 	/// Number of first block where dust cleanup rules (EIP-168 and EIP169) begin.
 	pub dust_protection_transition: BlockNumber,
 	/// Nonce cap increase per block. Nonce cap is only checked if dust protection is enabled.
@@ -193,6 +216,18 @@ impl CommonParams {
 		if block_number >= self.eip210_transition {
 			schedule.blockhash_gas = 800;
 		}
+		if block_number >= self.eip2929_transition {
+			schedule.enable_eip2929();
+		}
+		schedule.have_basefee = block_number >= self.eip3198_transition;
+		if block_number >= self.eip3529_transition {
+			schedule.enable_eip3529();
+		}
+		schedule.eip3541 = block_number >= self.eip3541_transition;
+		schedule.have_push0 = block_number >= self.eip3855_transition;
+		if block_number >= self.eip3860_transition {
+			schedule.enable_eip3860();
+		}
 		if block_number >= self.dust_protection_transition {
 			schedule.kill_dust = match self.remove_dust_contracts {
 				true => vm::CleanDustMode::WithCodeAndStorage,
@@ -331,6 +366,41 @@ impl From<ethjson::spec::Params> for CommonParams {
 				BlockNumber::max_value,
 				Into::into,
 			),
+			eip2929_transition: p.eip2929_transition.map_or_else(
+				BlockNumber::max_value,
+				Into::into,
+			),
+			eip2930_transition: p.eip2930_transition.map_or_else(
+				BlockNumber::max_value,
+				Into::into,
+			),
+			eip1559_transition: p.eip1559_transition.map_or_else(
+				BlockNumber::max_value,
+				Into::into,
+			),
+			eip1559_base_fee_max_change_denominator: p.eip1559_base_fee_max_change_denominator.map_or(8.into(), Into::into),
+			eip1559_elasticity_multiplier: p.eip1559_elasticity_multiplier.map_or(2.into(), Into::into),
+			eip1559_base_fee_initial_value: p.eip1559_base_fee_initial_value.map_or(1_000_000_000.into(), Into::into),
+			eip3198_transition: p.eip3198_transition.map_or_else(
+				BlockNumber::max_value,
+				Into::into,
+			),
+			eip3529_transition: p.eip3529_transition.map_or_else(
+				BlockNumber::max_value,
+				Into::into,
+			),
+			eip3541_transition: p.eip3541_transition.map_or_else(
+				BlockNumber::max_value,
+				Into::into,
+			),
+			eip3855_transition: p.eip3855_transition.map_or_else(
+				BlockNumber::max_value,
+				Into::into,
+			),
+			eip3860_transition: p.eip3860_transition.map_or_else(
+				BlockNumber::max_value,
+				Into::into,
+			),
 			dust_protection_transition: p.dust_protection_transition.map_or_else(
 				BlockNumber::max_value,
 				Into::into,
@@ -101,6 +101,9 @@ pub enum BlockError {
 	/// Number field of header is invalid.
 	#[display(fmt = "Invalid number in header: {}", _0)]
 	InvalidNumber(Mismatch<BlockNumber>),
+	/// Base fee field of the header is missing, unexpected or incorrect (EIP-1559).
+	#[display(fmt = "Invalid base fee in header: {:?}", _0)]
+	InvalidBaseFee(Mismatch<Option<U256>>),
 	/// Block number isn't sensible.
 	#[display(fmt = "Implausible block number. {}", _0)]
 	RidiculousNumber(OutOfBounds<BlockNumber>),
@@ -160,6 +160,20 @@ pub enum ExecutionError {
 	Internal(String),
 	/// Returned when generic transaction occurs
 	TransactionMalformed(String),
+	/// Returned when the maximum fee per gas of a transaction is lower than the block's base fee.
+	GasPriceLowerThanBaseFee {
+		/// Maximum fee per gas of the transaction.
+		gas_price: U256,
+		/// Base fee of the block.
+		base_fee: U256,
+	},
+	/// Returned when the init code of a contract creation exceeds the limit (EIP-3860).
+	InitcodeSizeExceeded {
+		/// Maximum init code size.
+		limit: usize,
+		/// Init code size of the transaction.
+		got: usize,
+	},
 }
 
 impl error::Error for ExecutionError {
@@ -198,6 +212,10 @@ impl fmt::Display for ExecutionError {
 			SenderMustExist => "Transacting from an empty account".to_owned(),
 			Internal(ref msg) => msg.clone(),
 			TransactionMalformed(ref err) => format!("Malformed transaction: {}", err),
+			GasPriceLowerThanBaseFee { ref gas_price, ref base_fee } =>
+				format!("Transaction max fee per gas {} is lower than the block base fee {}", gas_price, base_fee),
+			InitcodeSizeExceeded { ref limit, ref got } =>
+				format!("Init code size {} exceeds the limit of {} bytes", got, limit),
 		};
 
 		f.write_fmt(format_args!("Transaction execution error ({}).", msg))
@@ -82,6 +82,8 @@ pub struct Header {
 	difficulty: U256,
 	/// Vector of post-RLP-encoded fields.
 	seal: Vec<Bytes>,
+	/// Base fee per gas, present since EIP-1559.
+	base_fee_per_gas: Option<U256>,
 
 	/// Memoized hash of that header and the seal.
 	hash: Option<H256>,
@@ -108,7 +110,8 @@ impl PartialEq for Header {
 		self.gas_used == c.gas_used &&
 		self.gas_limit == c.gas_limit &&
 		self.difficulty == c.difficulty &&
-		self.seal == c.seal
+		self.seal == c.seal &&
+		self.base_fee_per_gas == c.base_fee_per_gas
 	}
 }
 
@@ -132,6 +135,7 @@ impl Default for Header {
 
 			difficulty: U256::default(),
 			seal: vec![],
+			base_fee_per_gas: None,
 			hash: None,
 		}
 	}
@@ -183,6 +187,9 @@ impl Header {
 	/// Get the seal field of the header.
 	pub fn seal(&self) -> &[Bytes] { &self.seal }
 
+	/// Get the base fee field of the header.
+	pub fn base_fee(&self) -> Option<U256> { self.base_fee_per_gas }
+
 	/// Get the seal field with RLP-decoded values as bytes.
 	pub fn decode_seal<'a, T: ::std::iter::FromIterator<&'a [u8]>>(&'a self) -> Result<T, DecoderError> {
 		self.seal.iter().map(|rlp| {
@@ -260,6 +267,11 @@ impl Header {
 		change_field(&mut self.hash, &mut self.seal, a)
 	}
 
+	/// Set the base fee field of the header.
+	pub fn set_base_fee(&mut self, a: Option<U256>) {
+		change_field(&mut self.hash, &mut self.base_fee_per_gas, a)
+	}
+
 	/// Get & memoize the hash of this header (keccak of the RLP with seal).
 	pub fn compute_hash(&mut self) -> H256 {
 		let hash = self.hash();
@@ -291,10 +303,11 @@ impl Header {
 
 	/// Place this header into an RLP stream `s`, optionally `with_seal`.
 	fn stream_rlp(&self, s: &mut RlpStream, with_seal: Seal) {
+		let base_fee_len = if self.base_fee_per_gas.is_some() { 1 } else { 0 };
 		if let Seal::With = with_seal {
-			s.begin_list(13 + self.seal.len());
+			s.begin_list(13 + self.seal.len() + base_fee_len);
 		} else {
-			s.begin_list(13);
+			s.begin_list(13 + base_fee_len);
 		}
 
 		s.append(&self.parent_hash);
@@ -316,19 +329,15 @@ impl Header {
 				s.append_raw(b, 1);
 			}
 		}
-	}
-}
 
-/// Alter value of given field, reset memoised hash if changed.
-fn change_field<T>(hash: &mut Option<H256>, field: &mut T, value: T) where T: PartialEq<T> {
-	if field != &value {
-		*field = value;
-		*hash = None;
+		if let Some(ref base_fee) = self.base_fee_per_gas {
+			s.append(base_fee);
+		}
 	}
-}
 
-impl Decodable for Header {
-	fn decode(r: &Rlp) -> Result<Self, DecoderError> {
+	/// Decode a header. The base fee follows the seal fields, so whether the last item is
+	/// a base fee or a seal field depends on the block number and `eip1559_transition`.
+	pub fn decode_rlp(r: &Rlp, eip1559_transition: BlockNumber) -> Result<Self, DecoderError> {
 		let mut blockheader = Header {
 			parent_hash: r.val_at(0)?,
 			uncles_hash: r.val_at(1)?,
@@ -344,10 +353,20 @@ impl Decodable for Header {
 			timestamp: r.val_at(11)?,
 			extra_data: r.val_at(12)?,
 			seal: vec![],
+			base_fee_per_gas: None,
 			hash: keccak(r.as_raw()).into(),
 		};
 
-		for i in 13..r.item_count()? {
+		let mut seal_end = r.item_count()?;
+		if blockheader.number >= eip1559_transition {
+			if seal_end <= 13 {
+				return Err(DecoderError::RlpIncorrectListLen);
+			}
+			seal_end -= 1;
+			blockheader.base_fee_per_gas = Some(r.val_at(seal_end)?);
+		}
+
+		for i in 13..seal_end {
 			blockheader.seal.push(r.at(i)?.as_raw().to_vec())
 		}
 
@@ -355,6 +374,21 @@ impl Decodable for Header {
 	}
 }
 
+/// Alter value of given field, reset memoised hash if changed.
+fn change_field<T>(hash: &mut Option<H256>, field: &mut T, value: T) where T: PartialEq<T> {
+	if field != &value {
+		*field = value;
+		*hash = None;
+	}
+}
+
+/// Decodes headers of chains without EIP-1559, see `Header::decode_rlp`.
+impl Decodable for Header {
+	fn decode(r: &Rlp) -> Result<Self, DecoderError> {
+		Header::decode_rlp(r, BlockNumber::max_value())
+	}
+}
+
 impl Encodable for Header {
 	fn rlp_append(&self, s: &mut RlpStream) {
 		self.stream_rlp(s, Seal::With);
@@ -371,7 +405,8 @@ impl ExtendedHeader {
 #[cfg(test)]
 mod tests {
 	use rustc_hex::FromHex;
-	use rlp;
+	use ethereum_types::U256;
+	use rlp::{self, Rlp};
 	use super::Header;
 
 	#[test]
@@ -416,4 +451,24 @@ mod tests {
 		let header: Result<Header, _> = rlp::decode(&header_rlp);
 		assert_eq!(header.unwrap_err(), rlp::DecoderError::RlpIsTooBig);
 	}
+
+	#[test]
+	fn decode_and_encode_header_with_base_fee() {
+		let mut header = Header::new();
+		header.set_number(10);
+		header.set_seal(vec![rlp::encode(&vec![1u8]), rlp::encode(&vec![2u8])]);
+		header.set_base_fee(Some(U256::from(1_000_000_000u64)));
+
+		let encoded = rlp::encode(&header);
+		let decoded = Header::decode_rlp(&Rlp::new(&encoded), 10).unwrap();
+		assert_eq!(decoded, header);
+		assert_eq!(decoded.seal().len(), 2);
+		assert_eq!(decoded.base_fee(), Some(U256::from(1_000_000_000u64)));
+		assert_eq!(decoded.hash(), header.hash());
+
+		// before the transition the base fee is taken for a seal field
+		let legacy: Header = rlp::decode(&encoded).unwrap();
+		assert_eq!(legacy.base_fee(), None);
+		assert_eq!(legacy.seal().len(), 3);
+	}
 }
@@ -37,6 +37,11 @@ pub enum ClientIoMessage<C> {
 	FeedBlockChunk(H256, Bytes),
 	/// Take a snapshot for the block with given number.
 	TakeSnapshot(u64),
+	/// Prune the state unreachable from the given number of latest blocks, using a bloom
+	/// filter of the given size in bytes.
+	PruneState(u64, usize),
+	/// Generate the missing flat state at the best block.
+	GenerateFlatState,
 	/// Execute wrapped closure
 	Execute(Callback<C>),
 }
@@ -16,12 +16,14 @@
 
 //! Receipt
 
+use bytes::Bytes;
 use ethereum_types::{H160, H256, U256, Address, Bloom};
 use parity_util_mem::MallocSizeOf;
 use rlp::{Rlp, RlpStream, Encodable, Decodable, DecoderError};
 
 use BlockNumber;
 use log_entry::{LogEntry, LocalizedLogEntry};
+use transaction::TypedTxId;
 
 /// Transaction outcome store in the receipt.
 #[derive(Debug, Clone, PartialEq, Eq, MallocSizeOf)]
@@ -45,6 +47,8 @@ pub struct Receipt {
 	pub logs: Vec<LogEntry>,
 	/// Transaction outcome.
 	pub outcome: TransactionOutcome,
+	/// Type of the transaction this receipt belongs to.
+	pub tx_type: TypedTxId,
 }
 
 impl Receipt {
@@ -58,12 +62,50 @@ impl Receipt {
 			}),
 			logs,
 			outcome,
+			tx_type: TypedTxId::Legacy,
 		}
 	}
-}
 
-impl Encodable for Receipt {
-	fn rlp_append(&self, s: &mut RlpStream) {
+	/// Set the type of the transaction this receipt belongs to.
+	pub fn with_tx_type(mut self, tx_type: TypedTxId) -> Self {
+		self.tx_type = tx_type;
+		self
+	}
+
+	/// Canonical encoding of the receipt: the RLP list for receipts of legacy transactions
+	/// and the `type || payload` envelope for typed ones. The receipts root is computed over these.
+	pub fn encode_raw(&self) -> Bytes {
+		let mut s = RlpStream::new();
+		self.rlp_append_fields(&mut s);
+		match self.tx_type.type_byte() {
+			None => s.out(),
+			Some(type_byte) => {
+				let mut out = vec![type_byte];
+				out.extend_from_slice(s.as_raw());
+				out
+			},
+		}
+	}
+
+	/// Decode a receipt from its canonical encoding, see `encode_raw`.
+	pub fn decode_raw(bytes: &[u8]) -> Result<Self, DecoderError> {
+		let first = *bytes.first().ok_or(DecoderError::RlpIsTooShort)?;
+		if first >= 0xc0 {
+			return Receipt::decode_fields(&Rlp::new(bytes));
+		}
+		Receipt::decode_typed(bytes)
+	}
+
+	/// Decode a typed envelope. Legacy receipts are never wrapped into one.
+	fn decode_typed(bytes: &[u8]) -> Result<Self, DecoderError> {
+		let first = *bytes.first().ok_or(DecoderError::RlpIsTooShort)?;
+		match TypedTxId::from_type_byte(first) {
+			Some(tx_type) => Ok(Receipt::decode_fields(&Rlp::new(&bytes[1..]))?.with_tx_type(tx_type)),
+			None => Err(DecoderError::Custom("Unknown transaction type")),
+		}
+	}
+
+	fn rlp_append_fields(&self, s: &mut RlpStream) {
 		match self.outcome {
 			TransactionOutcome::Unknown => {
 				s.begin_list(3);
@@ -81,16 +123,15 @@ impl Encodable for Receipt {
 		s.append(&self.log_bloom);
 		s.append_list(&self.logs);
 	}
-}
 
-impl Decodable for Receipt {
-	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+	fn decode_fields(rlp: &Rlp) -> Result<Self, DecoderError> {
 		if rlp.item_count()? == 3 {
 			Ok(Receipt {
 				outcome: TransactionOutcome::Unknown,
 				gas_used: rlp.val_at(0)?,
 				log_bloom: rlp.val_at(1)?,
 				logs: rlp.list_at(2)?,
+				tx_type: TypedTxId::Legacy,
 			})
 		} else {
 			Ok(Receipt {
@@ -104,12 +145,33 @@ impl Decodable for Receipt {
 					} else {
 						TransactionOutcome::StateRoot(first.as_val()?)
 					}
-				}
+				},
+				tx_type: TypedTxId::Legacy,
 			})
 		}
 	}
 }
 
+/// Inside of RLP lists receipts of typed transactions are wrapped into an RLP string.
+impl Encodable for Receipt {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		match self.tx_type {
+			TypedTxId::Legacy => self.rlp_append_fields(s),
+			_ => { s.append(&self.encode_raw()); },
+		}
+	}
+}
+
+impl Decodable for Receipt {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		if rlp.is_list() {
+			Receipt::decode_fields(rlp)
+		} else {
+			Receipt::decode_typed(rlp.data()?)
+		}
+	}
+}
+
 /// Receipt with additional info.
 #[derive(Debug, Clone, PartialEq)]
 pub struct RichReceipt {
@@ -134,7 +196,9 @@ pub struct RichReceipt {
 	/// NOTE: It is an Option because only `Action::Call` transactions has a receiver address
 	pub to: Option<H160>,
 	/// Sender
-	pub from: H160
+	pub from: H160,
+	/// Type of the transaction.
+	pub tx_type: TypedTxId,
 }
 
 /// Receipt with additional info.
@@ -165,7 +229,9 @@ pub struct LocalizedReceipt {
 	/// NOTE: It is an Option because only `Action::Call` transactions has a receiver address
 	pub to: Option<H160>,
 	/// Sender
-	pub from: H160
+	pub from: H160,
+	/// Type of the transaction.
+	pub tx_type: TypedTxId,
 }
 
 #[cfg(test)]
@@ -174,6 +240,7 @@ mod tests {
 
 	use super::{Receipt, TransactionOutcome, Address, H256};
 	use log_entry::LogEntry;
+	use transaction::TypedTxId;
 	use rustc_hex::FromHex;
 
 	#[test]
@@ -226,4 +293,31 @@ mod tests {
 		let decoded: Receipt = rlp::decode(&encoded).expect("decoding receipt failed");
 		assert_eq!(decoded, r);
 	}
+
+	#[test]
+	fn test_typed_receipt() {
+		let r = Receipt::new(
+			TransactionOutcome::StatusCode(1),
+			0x40cae.into(),
+			vec![LogEntry {
+				address: Address::from_str("dcf421d093428b096ca501a7cd1a740855a7976f").unwrap(),
+				topics: vec![],
+				data: vec![0u8; 32]
+			}]
+		).with_tx_type(TypedTxId::AccessList);
+
+		let raw = r.encode_raw();
+		assert_eq!(raw[0], 0x01);
+		assert_eq!(&raw[1..], &r.clone().with_tx_type(TypedTxId::Legacy).encode_raw()[..]);
+		assert_eq!(Receipt::decode_raw(&raw).expect("decoding receipt failed"), r);
+
+		let encoded = rlp::encode(&r);
+		assert_eq!(::rlp::Rlp::new(&encoded).data().unwrap(), &raw[..]);
+		let decoded: Receipt = rlp::decode(&encoded).expect("decoding receipt failed");
+		assert_eq!(decoded, r);
+
+		// legacy receipts are never wrapped into a string
+		let legacy = rlp::encode(&r.with_tx_type(TypedTxId::Legacy).encode_raw());
+		assert!(rlp::decode::<Receipt>(&legacy).is_err());
+	}
 }
@@ -86,6 +86,22 @@ pub enum Error {
 	TooBig,
 	/// Invalid RLP encoding
 	InvalidRlp(String),
+	/// Transaction type is not enabled on this chain yet.
+	TransactionTypeNotEnabled,
+	/// Transaction's max priority fee per gas is higher than its max fee per gas (EIP-1559).
+	MaxPriorityFeeTooHigh {
+		/// Max fee per gas
+		max_fee: U256,
+		/// Max priority fee per gas
+		max_priority_fee: U256,
+	},
+	/// Transaction's max fee per gas is below the base fee of the block (EIP-1559).
+	GasPriceLowerThanBaseFee {
+		/// Transaction max fee per gas
+		gas_price: U256,
+		/// Base fee of the block
+		base_fee: U256,
+	},
 }
 
 impl From<EthPublicKeyCryptoError> for Error {
@@ -129,6 +145,11 @@ impl fmt::Display for Error {
 			NotAllowed => "Sender does not have permissions to execute this type of transaction".into(),
 			TooBig => "Transaction too big".into(),
 			InvalidRlp(ref err) => format!("Transaction has invalid RLP structure: {}.", err),
+			TransactionTypeNotEnabled => "Transaction type is not enabled for the current block".into(),
+			MaxPriorityFeeTooHigh { max_fee, max_priority_fee } =>
+				format!("Max priority fee per gas higher than max fee per gas. MaxFee={}, MaxPriorityFee={}", max_fee, max_priority_fee),
+			GasPriceLowerThanBaseFee { gas_price, base_fee } =>
+				format!("Max fee per gas lower than block base fee. MaxFee={}, BaseFee={}", gas_price, base_fee),
 		};
 
 		f.write_fmt(format_args!("Transaction error ({})", msg))
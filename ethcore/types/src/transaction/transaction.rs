@@ -16,7 +16,8 @@
 
 //! Transaction data structure.
 
-use std::ops::Deref;
+use std::cmp;
+use std::ops::{Deref, DerefMut};
 
 use ethereum_types::{H256, H160, Address, U256, BigEndianHash};
 use ethjson;
@@ -24,7 +25,7 @@ use parity_crypto::publickey::{Signature, Secret, Public, recover, public_to_add
 use hash::keccak;
 use parity_util_mem::MallocSizeOf;
 
-use rlp::{self, RlpStream, Rlp, DecoderError, Encodable};
+use rlp::{self, RlpStream, Rlp, DecoderError};
 
 use transaction::error;
 
@@ -74,6 +75,96 @@ impl rlp::Encodable for Action {
 	}
 }
 
+/// Transaction type as defined by EIP-2718.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, MallocSizeOf)]
+pub enum TypedTxId {
+	/// Untyped transaction predating EIP-2718, encoded as a bare RLP list.
+	Legacy,
+	/// Transaction with an access list (EIP-2930).
+	AccessList,
+	/// Transaction with a fee cap and a priority fee (EIP-1559).
+	EIP1559,
+}
+
+impl Default for TypedTxId {
+	fn default() -> TypedTxId { TypedTxId::Legacy }
+}
+
+impl TypedTxId {
+	/// Returns the envelope type byte, `None` for legacy transactions.
+	pub fn type_byte(&self) -> Option<u8> {
+		match *self {
+			TypedTxId::Legacy => None,
+			TypedTxId::AccessList => Some(0x01),
+			TypedTxId::EIP1559 => Some(0x02),
+		}
+	}
+
+	/// Returns the transaction type for the given envelope type byte, if known.
+	pub fn from_type_byte(n: u8) -> Option<TypedTxId> {
+		match n {
+			0x01 => Some(TypedTxId::AccessList),
+			0x02 => Some(TypedTxId::EIP1559),
+			_ => None,
+		}
+	}
+
+	/// Numeric representation of the type; legacy transactions are type `0`.
+	pub fn to_u64(&self) -> u64 {
+		self.type_byte().map_or(0, Into::into)
+	}
+
+	/// Returns the transaction type for its numeric representation, if known.
+	pub fn from_u64(n: u64) -> Option<TypedTxId> {
+		match n {
+			0 => Some(TypedTxId::Legacy),
+			n if n <= 0x7f => TypedTxId::from_type_byte(n as u8),
+			_ => None,
+		}
+	}
+}
+
+/// Address and storage keys a transaction declares it is going to access (EIP-2930).
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash, RlpEncodable, RlpDecodable, MallocSizeOf)]
+pub struct AccessListItem {
+	/// Accessed address.
+	pub address: Address,
+	/// Accessed storage keys of `address`.
+	pub storage_keys: Vec<H256>,
+}
+
+/// List of accessed addresses and storage keys.
+pub type AccessList = Vec<AccessListItem>;
+
+/// Returns the canonical encoding of an item of a block's transaction or receipt list:
+/// the RLP list itself for legacy items, the `type || payload` envelope for typed ones.
+/// Hashes and trie roots are computed over these bytes.
+pub fn raw_item_bytes<'a>(rlp: &Rlp<'a>) -> &'a [u8] {
+	if rlp.is_list() {
+		rlp.as_raw()
+	} else {
+		rlp.data().unwrap_or_else(|_| rlp.as_raw())
+	}
+}
+
+/// Prepends the type byte to an RLP encoded typed payload.
+fn typed_envelope(tx_type: TypedTxId, payload: &[u8]) -> Bytes {
+	let mut out = Vec::with_capacity(payload.len() + 1);
+	out.extend(tx_type.type_byte());
+	out.extend_from_slice(payload);
+	out
+}
+
+/// Folds the chain id and y-parity of a typed transaction into an EIP-155 `v`.
+fn typed_signature_v(chain_id: u64, y_parity: u8) -> Result<u64, DecoderError> {
+	if y_parity > 1 {
+		return Err(DecoderError::Custom("Invalid signature y-parity"));
+	}
+	chain_id.checked_mul(2)
+		.and_then(|n| n.checked_add(35 + y_parity as u64))
+		.ok_or(DecoderError::Custom("Chain id out of range"))
+}
+
 /// Transaction activation condition.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Condition {
@@ -165,7 +256,7 @@ impl From<ethjson::transaction::Transaction> for UnverifiedTransaction {
 	fn from(t: ethjson::transaction::Transaction) -> Self {
 		let to: Option<ethjson::hash::Address> = t.to.into();
 		UnverifiedTransaction {
-			unsigned: Transaction {
+			unsigned: TypedTransaction::Legacy(Transaction {
 				nonce: t.nonce.into(),
 				gas_price: t.gas_price.into(),
 				gas: t.gas_limit.into(),
@@ -175,7 +266,7 @@ impl From<ethjson::transaction::Transaction> for UnverifiedTransaction {
 				},
 				value: t.value.into(),
 				data: t.data.into(),
-			},
+			}),
 			r: t.r.into(),
 			s: t.s.into(),
 			v: t.v.into(),
@@ -192,6 +283,227 @@ impl Transaction {
 		keccak(stream.as_raw())
 	}
 
+	/// Signs the transaction as coming from `sender`.
+	pub fn sign(self, secret: &Secret, chain_id: Option<u64>) -> SignedTransaction {
+		TypedTransaction::Legacy(self).sign(secret, chain_id)
+	}
+
+	/// Signs the transaction with signature.
+	pub fn with_signature(self, sig: Signature, chain_id: Option<u64>) -> UnverifiedTransaction {
+		TypedTransaction::Legacy(self).with_signature(sig, chain_id)
+	}
+
+	/// Useful for test incorrectly signed transactions.
+	#[cfg(test)]
+	pub fn invalid_sign(self) -> UnverifiedTransaction {
+		TypedTransaction::Legacy(self).invalid_sign()
+	}
+
+	/// Specify the sender; this won't survive the serialize/deserialize process, but can be cloned.
+	pub fn fake_sign(self, from: Address) -> SignedTransaction {
+		TypedTransaction::Legacy(self).fake_sign(from)
+	}
+
+	/// Legacy EIP-86 compatible empty signature.
+	/// This method is used in json tests as well as
+	/// signature verification tests.
+	#[cfg(any(test, feature = "test-helpers"))]
+	pub fn null_sign(self, chain_id: u64) -> SignedTransaction {
+		SignedTransaction {
+			transaction: UnverifiedTransaction {
+				unsigned: TypedTransaction::Legacy(self),
+				r: U256::zero(),
+				s: U256::zero(),
+				v: chain_id,
+				hash: H256::zero(),
+			}.compute_hash(),
+			sender: UNSIGNED_SENDER,
+			public: None,
+		}
+	}
+}
+
+/// EIP-2930 transaction: the legacy fields extended with an access list.
+#[derive(Default, Debug, Clone, PartialEq, Eq, MallocSizeOf)]
+pub struct AccessListTx {
+	/// Fields shared with legacy transactions.
+	pub transaction: Transaction,
+	/// Addresses and storage keys the transaction is going to access.
+	pub access_list: AccessList,
+}
+
+impl AccessListTx {
+	/// Create a new access list transaction.
+	pub fn new(transaction: Transaction, access_list: AccessList) -> Self {
+		AccessListTx { transaction, access_list }
+	}
+
+	/// Append the typed payload into RLP stream, with the signature if given.
+	fn rlp_append_payload(&self, s: &mut RlpStream, chain_id: u64, signature: Option<(u8, &U256, &U256)>) {
+		s.begin_list(if signature.is_none() { 8 } else { 11 });
+		s.append(&chain_id);
+		s.append(&self.transaction.nonce);
+		s.append(&self.transaction.gas_price);
+		s.append(&self.transaction.gas);
+		s.append(&self.transaction.action);
+		s.append(&self.transaction.value);
+		s.append(&self.transaction.data);
+		s.append_list(&self.access_list);
+		if let Some((v, r, sig_s)) = signature {
+			s.append(&v);
+			s.append(r);
+			s.append(sig_s);
+		}
+	}
+}
+
+/// EIP-1559 transaction: an access list transaction paying a base fee and a priority fee.
+///
+/// The `gas_price` of the wrapped transaction is the maximum fee per gas the sender is
+/// willing to pay, base fee included.
+#[derive(Default, Debug, Clone, PartialEq, Eq, MallocSizeOf)]
+pub struct EIP1559Tx {
+	/// Fields shared with access list transactions.
+	pub transaction: AccessListTx,
+	/// Maximum fee per gas paid to the block author on top of the base fee.
+	pub max_priority_fee_per_gas: U256,
+}
+
+impl EIP1559Tx {
+	/// Create a new EIP-1559 transaction, `transaction.gas_price` being the max fee per gas.
+	pub fn new(transaction: AccessListTx, max_priority_fee_per_gas: U256) -> Self {
+		EIP1559Tx { transaction, max_priority_fee_per_gas }
+	}
+
+	/// Append the typed payload into RLP stream, with the signature if given.
+	fn rlp_append_payload(&self, s: &mut RlpStream, chain_id: u64, signature: Option<(u8, &U256, &U256)>) {
+		let tx = &self.transaction.transaction;
+		s.begin_list(if signature.is_none() { 9 } else { 12 });
+		s.append(&chain_id);
+		s.append(&tx.nonce);
+		s.append(&self.max_priority_fee_per_gas);
+		s.append(&tx.gas_price);
+		s.append(&tx.gas);
+		s.append(&tx.action);
+		s.append(&tx.value);
+		s.append(&tx.data);
+		s.append_list(&self.transaction.access_list);
+		if let Some((v, r, sig_s)) = signature {
+			s.append(&v);
+			s.append(r);
+			s.append(sig_s);
+		}
+	}
+}
+
+/// Unsigned transaction of any of the supported EIP-2718 types.
+#[derive(Debug, Clone, PartialEq, Eq, MallocSizeOf)]
+pub enum TypedTransaction {
+	/// Legacy transaction.
+	Legacy(Transaction),
+	/// EIP-2930 access list transaction.
+	AccessList(AccessListTx),
+	/// EIP-1559 transaction.
+	EIP1559(EIP1559Tx),
+}
+
+impl Default for TypedTransaction {
+	fn default() -> Self {
+		TypedTransaction::Legacy(Transaction::default())
+	}
+}
+
+impl From<Transaction> for TypedTransaction {
+	fn from(t: Transaction) -> Self {
+		TypedTransaction::Legacy(t)
+	}
+}
+
+impl Deref for TypedTransaction {
+	type Target = Transaction;
+
+	fn deref(&self) -> &Self::Target {
+		match *self {
+			TypedTransaction::Legacy(ref tx) => tx,
+			TypedTransaction::AccessList(ref tx) => &tx.transaction,
+			TypedTransaction::EIP1559(ref tx) => &tx.transaction.transaction,
+		}
+	}
+}
+
+impl DerefMut for TypedTransaction {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		match *self {
+			TypedTransaction::Legacy(ref mut tx) => tx,
+			TypedTransaction::AccessList(ref mut tx) => &mut tx.transaction,
+			TypedTransaction::EIP1559(ref mut tx) => &mut tx.transaction.transaction,
+		}
+	}
+}
+
+impl TypedTransaction {
+	/// Type of the transaction.
+	pub fn tx_type(&self) -> TypedTxId {
+		match *self {
+			TypedTransaction::Legacy(_) => TypedTxId::Legacy,
+			TypedTransaction::AccessList(_) => TypedTxId::AccessList,
+			TypedTransaction::EIP1559(_) => TypedTxId::EIP1559,
+		}
+	}
+
+	/// The access list of the transaction, `None` for transaction types without one.
+	pub fn access_list(&self) -> Option<&AccessList> {
+		match *self {
+			TypedTransaction::Legacy(_) => None,
+			TypedTransaction::AccessList(ref tx) => Some(&tx.access_list),
+			TypedTransaction::EIP1559(ref tx) => Some(&tx.transaction.access_list),
+		}
+	}
+
+	/// Maximum fee per gas paid to the block author; the gas price for transactions predating EIP-1559.
+	pub fn max_priority_fee_per_gas(&self) -> U256 {
+		match *self {
+			TypedTransaction::EIP1559(ref tx) => tx.max_priority_fee_per_gas,
+			_ => self.gas_price,
+		}
+	}
+
+	/// The gas price paid in a block with the given base fee (`None` before EIP-1559).
+	///
+	/// Transactions predating EIP-1559 always pay their gas price, EIP-1559 transactions pay
+	/// the base fee plus their priority fee, capped at their max fee per gas.
+	pub fn effective_gas_price(&self, block_base_fee: Option<U256>) -> U256 {
+		match *self {
+			TypedTransaction::EIP1559(ref tx) => cmp::min(
+				self.gas_price,
+				block_base_fee.unwrap_or_default().saturating_add(tx.max_priority_fee_per_gas),
+			),
+			_ => self.gas_price,
+		}
+	}
+
+	/// The fee per gas received by the block author in a block with the given base fee.
+	pub fn effective_priority_fee(&self, block_base_fee: Option<U256>) -> U256 {
+		self.effective_gas_price(block_base_fee).saturating_sub(block_base_fee.unwrap_or_default())
+	}
+
+	/// The message hash of the transaction.
+	pub fn hash(&self, chain_id: Option<u64>) -> H256 {
+		match *self {
+			TypedTransaction::Legacy(ref tx) => tx.hash(chain_id),
+			TypedTransaction::AccessList(ref tx) => {
+				let mut stream = RlpStream::new();
+				tx.rlp_append_payload(&mut stream, chain_id.unwrap_or_default(), None);
+				keccak(typed_envelope(self.tx_type(), stream.as_raw()))
+			},
+			TypedTransaction::EIP1559(ref tx) => {
+				let mut stream = RlpStream::new();
+				tx.rlp_append_payload(&mut stream, chain_id.unwrap_or_default(), None);
+				keccak(typed_envelope(self.tx_type(), stream.as_raw()))
+			},
+		}
+	}
+
 	/// Signs the transaction as coming from `sender`.
 	pub fn sign(self, secret: &Secret, chain_id: Option<u64>) -> SignedTransaction {
 		let sig = parity_crypto::publickey::sign(secret, &self.hash(chain_id))
@@ -201,7 +513,13 @@ impl Transaction {
 	}
 
 	/// Signs the transaction with signature.
+	///
+	/// Typed transactions always commit to a chain id, `None` is signed as chain id `0`.
 	pub fn with_signature(self, sig: Signature, chain_id: Option<u64>) -> UnverifiedTransaction {
+		let chain_id = match self {
+			TypedTransaction::Legacy(_) => chain_id,
+			_ => Some(chain_id.unwrap_or_default()),
+		};
 		UnverifiedTransaction {
 			unsigned: self,
 			r: sig.r().into(),
@@ -237,33 +555,17 @@ impl Transaction {
 			public: None,
 		}
 	}
-
-	/// Legacy EIP-86 compatible empty signature.
-	/// This method is used in json tests as well as
-	/// signature verification tests.
-	#[cfg(any(test, feature = "test-helpers"))]
-	pub fn null_sign(self, chain_id: u64) -> SignedTransaction {
-		SignedTransaction {
-			transaction: UnverifiedTransaction {
-				unsigned: self,
-				r: U256::zero(),
-				s: U256::zero(),
-				v: chain_id,
-				hash: H256::zero(),
-			}.compute_hash(),
-			sender: UNSIGNED_SENDER,
-			public: None,
-		}
-	}
 }
 
 /// Signed transaction information without verified signature.
 #[derive(Debug, Clone, Eq, PartialEq, MallocSizeOf)]
 pub struct UnverifiedTransaction {
 	/// Plain Transaction.
-	unsigned: Transaction,
+	unsigned: TypedTransaction,
 	/// The V field of the signature; the LS bit described which half of the curve our point falls
 	/// in. The MS bits describe which chain this transaction is for. If 27/28, its for all chains.
+	/// Typed transactions carry the chain id and y-parity separately; they are folded in here
+	/// the EIP-155 way, so they always describe a chain.
 	v: u64,
 	/// The R field of the signature; helps describe the point on the curve.
 	r: U256,
@@ -274,44 +576,162 @@ pub struct UnverifiedTransaction {
 }
 
 impl Deref for UnverifiedTransaction {
-	type Target = Transaction;
+	type Target = TypedTransaction;
 
 	fn deref(&self) -> &Self::Target {
 		&self.unsigned
 	}
 }
 
+/// Inside of RLP lists (block bodies, network packets) typed transactions are wrapped into
+/// an RLP string, whereas legacy transactions are embedded as a list.
 impl rlp::Decodable for UnverifiedTransaction {
 	fn decode(d: &Rlp) -> Result<Self, DecoderError> {
+		if d.is_list() {
+			UnverifiedTransaction::decode_legacy(d, keccak(d.as_raw()))
+		} else {
+			UnverifiedTransaction::decode_typed(d.data()?)
+		}
+	}
+}
+
+impl rlp::Encodable for UnverifiedTransaction {
+	fn rlp_append(&self, s: &mut RlpStream) { self.rlp_append_sealed_transaction(s) }
+}
+
+impl UnverifiedTransaction {
+	/// Decode a transaction from its canonical encoding: a legacy RLP list or a typed envelope.
+	/// This is the format of `eth_sendRawTransaction` and of transaction trie values.
+	pub fn decode_raw(bytes: &[u8]) -> Result<Self, DecoderError> {
+		let first = *bytes.first().ok_or(DecoderError::RlpIsTooShort)?;
+		if first >= 0xc0 {
+			let rlp = Rlp::new(bytes);
+			return UnverifiedTransaction::decode_legacy(&rlp, keccak(bytes));
+		}
+
+		UnverifiedTransaction::decode_typed(bytes)
+	}
+
+	/// Decode a typed envelope. Legacy transactions are never wrapped into one.
+	fn decode_typed(bytes: &[u8]) -> Result<Self, DecoderError> {
+		let first = *bytes.first().ok_or(DecoderError::RlpIsTooShort)?;
+		match TypedTxId::from_type_byte(first) {
+			Some(TypedTxId::AccessList) => {
+				UnverifiedTransaction::decode_access_list(&Rlp::new(&bytes[1..]), keccak(bytes))
+			},
+			Some(TypedTxId::EIP1559) => {
+				UnverifiedTransaction::decode_eip1559(&Rlp::new(&bytes[1..]), keccak(bytes))
+			},
+			_ => Err(DecoderError::Custom("Unknown transaction type")),
+		}
+	}
+
+	fn decode_legacy(d: &Rlp, hash: H256) -> Result<Self, DecoderError> {
 		if d.item_count()? != 9 {
 			return Err(DecoderError::RlpIncorrectListLen);
 		}
-		let hash = keccak(d.as_raw());
 		Ok(UnverifiedTransaction {
-			unsigned: Transaction {
+			unsigned: TypedTransaction::Legacy(Transaction {
 				nonce: d.val_at(0)?,
 				gas_price: d.val_at(1)?,
 				gas: d.val_at(2)?,
 				action: d.val_at(3)?,
 				value: d.val_at(4)?,
 				data: d.val_at(5)?,
-			},
+			}),
 			v: d.val_at(6)?,
 			r: d.val_at(7)?,
 			s: d.val_at(8)?,
 			hash,
 		})
 	}
-}
 
-impl rlp::Encodable for UnverifiedTransaction {
-	fn rlp_append(&self, s: &mut RlpStream) { self.rlp_append_sealed_transaction(s) }
-}
+	fn decode_access_list(d: &Rlp, hash: H256) -> Result<Self, DecoderError> {
+		if d.item_count()? != 11 {
+			return Err(DecoderError::RlpIncorrectListLen);
+		}
+		let v = typed_signature_v(d.val_at(0)?, d.val_at(8)?)?;
+
+		Ok(UnverifiedTransaction {
+			unsigned: TypedTransaction::AccessList(AccessListTx {
+				transaction: Transaction {
+					nonce: d.val_at(1)?,
+					gas_price: d.val_at(2)?,
+					gas: d.val_at(3)?,
+					action: d.val_at(4)?,
+					value: d.val_at(5)?,
+					data: d.val_at(6)?,
+				},
+				access_list: d.list_at(7)?,
+			}),
+			v,
+			r: d.val_at(9)?,
+			s: d.val_at(10)?,
+			hash,
+		})
+	}
+
+	fn decode_eip1559(d: &Rlp, hash: H256) -> Result<Self, DecoderError> {
+		if d.item_count()? != 12 {
+			return Err(DecoderError::RlpIncorrectListLen);
+		}
+		let v = typed_signature_v(d.val_at(0)?, d.val_at(9)?)?;
+
+		Ok(UnverifiedTransaction {
+			unsigned: TypedTransaction::EIP1559(EIP1559Tx {
+				transaction: AccessListTx {
+					transaction: Transaction {
+						nonce: d.val_at(1)?,
+						gas_price: d.val_at(3)?,
+						gas: d.val_at(4)?,
+						action: d.val_at(5)?,
+						value: d.val_at(6)?,
+						data: d.val_at(7)?,
+					},
+					access_list: d.list_at(8)?,
+				},
+				max_priority_fee_per_gas: d.val_at(2)?,
+			}),
+			v,
+			r: d.val_at(10)?,
+			s: d.val_at(11)?,
+			hash,
+		})
+	}
+
+	/// Canonical encoding of the transaction: the RLP list for legacy transactions and
+	/// the `type || payload` envelope for typed ones. See `decode_raw`.
+	pub fn encode_raw(&self) -> Bytes {
+		match self.unsigned {
+			TypedTransaction::Legacy(ref tx) => {
+				let mut s = RlpStream::new();
+				self.rlp_append_legacy(tx, &mut s);
+				s.out()
+			},
+			TypedTransaction::AccessList(ref tx) => {
+				let mut s = RlpStream::new();
+				tx.rlp_append_payload(
+					&mut s,
+					self.chain_id().unwrap_or_default(),
+					Some((self.standard_v(), &self.r, &self.s)),
+				);
+				typed_envelope(self.unsigned.tx_type(), s.as_raw())
+			},
+			TypedTransaction::EIP1559(ref tx) => {
+				let mut s = RlpStream::new();
+				tx.rlp_append_payload(
+					&mut s,
+					self.chain_id().unwrap_or_default(),
+					Some((self.standard_v(), &self.r, &self.s)),
+				);
+				typed_envelope(self.unsigned.tx_type(), s.as_raw())
+			},
+		}
+	}
 
-impl UnverifiedTransaction {
 	/// Used to compute hash of created transactions
 	fn compute_hash(mut self) -> UnverifiedTransaction {
-		let hash = keccak(&*self.rlp_bytes());
+		let hash = keccak(&*self.encode_raw());
 		self.hash = hash;
 		self
 	}
@@ -331,28 +751,40 @@ impl UnverifiedTransaction {
 
 	/// Append object with a signature into RLP stream
 	fn rlp_append_sealed_transaction(&self, s: &mut RlpStream) {
+		match self.unsigned {
+			TypedTransaction::Legacy(ref tx) => self.rlp_append_legacy(tx, s),
+			_ => { s.append(&self.encode_raw()); },
+		}
+	}
+
+	fn rlp_append_legacy(&self, tx: &Transaction, s: &mut RlpStream) {
 		s.begin_list(9);
-		s.append(&self.nonce);
-		s.append(&self.gas_price);
-		s.append(&self.gas);
-		s.append(&self.action);
-		s.append(&self.value);
-		s.append(&self.data);
+		s.append(&tx.nonce);
+		s.append(&tx.gas_price);
+		s.append(&tx.gas);
+		s.append(&tx.action);
+		s.append(&tx.value);
+		s.append(&tx.data);
 		s.append(&self.v);
 		s.append(&self.r);
 		s.append(&self.s);
 	}
 
 	///	Reference to unsigned part of this transaction.
-	pub fn as_unsigned(&self) -> &Transaction {
+	pub fn as_unsigned(&self) -> &TypedTransaction {
 		&self.unsigned
 	}
 
 	/// Returns standardized `v` value (0, 1 or 4 (invalid))
 	pub fn standard_v(&self) -> u8 { signature::check_replay_protection(self.v) }
 
-	/// The `v` value that appears in the RLP.
-	pub fn original_v(&self) -> u64 { self.v }
+	/// The `v` value that appears in the RLP; the y-parity for typed transactions.
+	pub fn original_v(&self) -> u64 {
+		match self.unsigned {
+			TypedTransaction::Legacy(_) => self.v,
+			_ => self.standard_v() as u64,
+		}
+	}
 
 	/// The chain ID, or `None` if this is a global transaction.
 	pub fn chain_id(&self) -> Option<u64> {
@@ -675,6 +1107,125 @@ mod tests {
 		assert_eq!(t.chain_id(), Some(69));
 	}
 
+	#[test]
+	fn should_encode_and_decode_access_list_transaction() {
+		use parity_crypto::publickey::{Random, Generator};
+
+		let key = Random.generate().unwrap();
+		let t = TypedTransaction::AccessList(AccessListTx::new(
+			Transaction {
+				action: Action::Call(Address::from_low_u64_be(0x1234)),
+				nonce: U256::from(42),
+				gas_price: U256::from(3000),
+				gas: U256::from(50_000),
+				value: U256::from(1),
+				data: b"Hello!".to_vec()
+			},
+			vec![AccessListItem {
+				address: Address::from_low_u64_be(0x1234),
+				storage_keys: vec![H256::from_low_u64_be(1), H256::from_low_u64_be(2)],
+			}],
+		)).sign(&key.secret(), Some(69));
+
+		assert_eq!(Address::from(keccak(key.public())), t.sender());
+		assert_eq!(t.chain_id(), Some(69));
+		assert_eq!(t.tx_type(), TypedTxId::AccessList);
+		assert!(t.original_v() <= 1);
+
+		let raw = t.encode_raw();
+		assert_eq!(raw[0], 0x01);
+		assert_eq!(t.hash(), keccak(&raw));
+
+		let decoded = UnverifiedTransaction::decode_raw(&raw).unwrap();
+		assert_eq!(decoded, *t);
+		assert_eq!(SignedTransaction::new(decoded).unwrap().sender(), t.sender());
+
+		// inside of an RLP list the envelope is wrapped into a string
+		let wrapped = rlp::encode(&t);
+		assert_eq!(Rlp::new(&wrapped).data().unwrap(), &raw[..]);
+		let decoded: UnverifiedTransaction = rlp::decode(&wrapped).unwrap();
+		assert_eq!(decoded, *t);
+		assert_eq!(raw_item_bytes(&Rlp::new(&wrapped)), &raw[..]);
+	}
+
+	#[test]
+	fn should_encode_and_decode_eip1559_transaction() {
+		use parity_crypto::publickey::{Random, Generator};
+
+		let key = Random.generate().unwrap();
+		let t = TypedTransaction::EIP1559(EIP1559Tx::new(
+			AccessListTx::new(
+				Transaction {
+					action: Action::Create,
+					nonce: U256::from(42),
+					gas_price: U256::from(3000),
+					gas: U256::from(50_000),
+					value: U256::from(1),
+					data: b"Hello!".to_vec()
+				},
+				vec![],
+			),
+			U256::from(200),
+		)).sign(&key.secret(), Some(69));
+
+		assert_eq!(t.tx_type(), TypedTxId::EIP1559);
+		assert_eq!(t.chain_id(), Some(69));
+
+		let raw = t.encode_raw();
+		assert_eq!(raw[0], 0x02);
+		assert_eq!(t.hash(), keccak(&raw));
+
+		let decoded = UnverifiedTransaction::decode_raw(&raw).unwrap();
+		assert_eq!(decoded, *t);
+		assert_eq!(SignedTransaction::new(decoded).unwrap().sender(), t.sender());
+	}
+
+	#[test]
+	fn should_compute_effective_gas_price() {
+		let legacy = TypedTransaction::Legacy(Transaction {
+			gas_price: U256::from(3000),
+			..Default::default()
+		});
+		let eip1559 = TypedTransaction::EIP1559(EIP1559Tx::new(
+			AccessListTx::new(Transaction { gas_price: U256::from(3000), ..Default::default() }, vec![]),
+			U256::from(200),
+		));
+
+		assert_eq!(legacy.effective_gas_price(Some(U256::from(1000))), U256::from(3000));
+		assert_eq!(legacy.effective_priority_fee(Some(U256::from(1000))), U256::from(2000));
+		assert_eq!(eip1559.effective_gas_price(Some(U256::from(1000))), U256::from(1200));
+		assert_eq!(eip1559.effective_priority_fee(Some(U256::from(1000))), U256::from(200));
+		// the max fee caps what is paid
+		assert_eq!(eip1559.effective_gas_price(Some(U256::from(2900))), U256::from(3000));
+		assert_eq!(eip1559.effective_priority_fee(Some(U256::from(2900))), U256::from(100));
+		assert_eq!(eip1559.effective_priority_fee(Some(U256::from(4000))), U256::zero());
+	}
+
+	#[test]
+	fn should_reject_unknown_transaction_type() {
+		let res = UnverifiedTransaction::decode_raw(&[0x7f, 0xc0]);
+		assert_eq!(res, Err(DecoderError::Custom("Unknown transaction type")));
+	}
+
+	#[test]
+	fn should_reject_legacy_transaction_wrapped_into_string() {
+		let bytes: Vec<u8> = FromHex::from_hex("f85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804").unwrap();
+		let wrapped = rlp::encode(&bytes);
+
+		let res: Result<UnverifiedTransaction, _> = rlp::decode(&wrapped);
+		assert_eq!(res, Err(DecoderError::Custom("Unknown transaction type")));
+	}
+
+	#[test]
+	fn legacy_raw_encoding_is_plain_rlp() {
+		let bytes: Vec<u8> = FromHex::from_hex("f85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804").unwrap();
+		let t = UnverifiedTransaction::decode_raw(&bytes).unwrap();
+		assert_eq!(t.tx_type(), TypedTxId::Legacy);
+		assert_eq!(t.encode_raw(), bytes);
+		assert_eq!(rlp::encode(&t), bytes);
+		assert_eq!(t.hash(), keccak(&bytes));
+	}
+
 	#[test]
 	fn should_agree_with_vitalik() {
 		let test_vector = |tx_data: &str, address: &'static str| {
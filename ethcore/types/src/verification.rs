@@ -17,6 +17,8 @@
 //! Verification types
 
 use crate::{
+	BlockNumber,
+	block::decode_uncles,
 	header::Header,
 	transaction::UnverifiedTransaction,
 };
@@ -71,13 +73,14 @@ pub struct Unverified {
 
 impl Unverified {
 	/// Create an `Unverified` from raw bytes.
-	pub fn from_rlp(bytes: Bytes) -> Result<Self, rlp::DecoderError> {
+	/// Blocks from `eip1559_transition` onwards are expected to carry a base fee.
+	pub fn from_rlp(bytes: Bytes, eip1559_transition: BlockNumber) -> Result<Self, rlp::DecoderError> {
 		use rlp::Rlp;
 		let (header, transactions, uncles) = {
 			let rlp = Rlp::new(&bytes);
-			let header = rlp.val_at(0)?;
+			let header = Header::decode_rlp(&rlp.at(0)?, eip1559_transition)?;
 			let transactions = rlp.list_at(1)?;
-			let uncles = rlp.list_at(2)?;
+			let uncles = decode_uncles(&rlp.at(2)?, eip1559_transition)?;
 			(header, transactions, uncles)
 		};
 
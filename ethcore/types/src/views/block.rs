@@ -20,9 +20,10 @@ use bytes::Bytes;
 use ethereum_types::H256;
 use hash::keccak;
 use header::Header;
-use transaction::{UnverifiedTransaction, LocalizedTransaction};
+use transaction::{raw_item_bytes, UnverifiedTransaction, LocalizedTransaction};
 use views::{TransactionView, HeaderView};
 use super::ViewRlp;
+use BlockNumber;
 
 /// View onto block rlp.
 pub struct BlockView<'a> {
@@ -63,8 +64,8 @@ impl<'a> BlockView<'a> {
 	}
 
 	/// Create new Header object from header rlp.
-	pub fn header(&self) -> Header {
-		self.rlp.val_at(0)
+	pub fn header(&self, eip1559_transition: BlockNumber) -> Header {
+		self.header_rlp().decode_with(|r| Header::decode_rlp(r, eip1559_transition))
 	}
 
 	/// Return header rlp.
@@ -116,7 +117,7 @@ impl<'a> BlockView<'a> {
 
 	/// Return transaction hashes.
 	pub fn transaction_hashes(&self) -> Vec<H256> {
-		self.transactions_rlp().iter().map(|rlp| keccak(rlp.as_raw())).collect()
+		self.transactions_rlp().iter().map(|rlp| keccak(raw_item_bytes(&rlp.rlp))).collect()
 	}
 
 	/// Returns transaction at given index without deserializing unnecessary data.
@@ -144,8 +145,8 @@ impl<'a> BlockView<'a> {
 	}
 
 	/// Return list of uncles of given block.
-	pub fn uncles(&self) -> Vec<Header> {
-		self.rlp.list_at(2)
+	pub fn uncles(&self, eip1559_transition: BlockNumber) -> Vec<Header> {
+		self.uncles_rlp().iter().map(|rlp| rlp.decode_with(|r| Header::decode_rlp(r, eip1559_transition))).collect()
 	}
 
 	/// Return number of uncles in given block, without deserializing them.
@@ -164,8 +165,8 @@ impl<'a> BlockView<'a> {
 	}
 
 	/// Return nth uncle.
-	pub fn uncle_at(&self, index: usize) -> Option<Header> {
-		self.uncles_rlp().iter().nth(index).map(|rlp| rlp.as_val())
+	pub fn uncle_at(&self, index: usize, eip1559_transition: BlockNumber) -> Option<Header> {
+		self.uncles_rlp().iter().nth(index).map(|rlp| rlp.decode_with(|r| Header::decode_rlp(r, eip1559_transition)))
 	}
 
 	/// Return nth uncle rlp.
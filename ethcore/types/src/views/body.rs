@@ -20,7 +20,7 @@ use bytes::Bytes;
 use ethereum_types::H256;
 use hash::keccak;
 use header::Header;
-use transaction::{LocalizedTransaction, UnverifiedTransaction};
+use transaction::{raw_item_bytes, LocalizedTransaction, UnverifiedTransaction};
 use views::{TransactionView, HeaderView};
 use super::ViewRlp;
 use BlockNumber;
@@ -93,7 +93,7 @@ impl<'a> BodyView<'a> {
 
 	/// Return transaction hashes.
 	pub fn transaction_hashes(&self) -> Vec<H256> {
-		self.transactions_rlp().iter().map(|rlp| keccak(rlp.as_raw())).collect()
+		self.transactions_rlp().iter().map(|rlp| keccak(raw_item_bytes(&rlp.rlp))).collect()
 	}
 
 	/// Returns transaction at given index without deserializing unnecessary data.
@@ -118,8 +118,8 @@ impl<'a> BodyView<'a> {
 	}
 
 	/// Return list of uncles of given block.
-	pub fn uncles(&self) -> Vec<Header> {
-		self.rlp.list_at(1)
+	pub fn uncles(&self, eip1559_transition: BlockNumber) -> Vec<Header> {
+		self.uncles_rlp().iter().map(|rlp| rlp.decode_with(|r| Header::decode_rlp(r, eip1559_transition))).collect()
 	}
 
 	/// Return number of uncles in given block, without deserializing them.
@@ -138,8 +138,8 @@ impl<'a> BodyView<'a> {
 	}
 
 	/// Return nth uncle.
-	pub fn uncle_at(&self, index: usize) -> Option<Header> {
-		self.uncles_rlp().iter().nth(index).map(|rlp| rlp.as_val())
+	pub fn uncle_at(&self, index: usize, eip1559_transition: BlockNumber) -> Option<Header> {
+		self.uncles_rlp().iter().nth(index).map(|rlp| rlp.decode_with(|r| Header::decode_rlp(r, eip1559_transition)))
 	}
 
 	/// Return nth uncle rlp.
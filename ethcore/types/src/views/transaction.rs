@@ -18,11 +18,14 @@
 use bytes::Bytes;
 use ethereum_types::{H256, U256};
 use hash::keccak;
+use transaction::{raw_item_bytes, TypedTxId};
 use super::ViewRlp;
 
 /// View onto transaction rlp.
 pub struct TransactionView<'a> {
-	rlp: ViewRlp<'a>
+	rlp: ViewRlp<'a>,
+	fields: ViewRlp<'a>,
+	tx_type: TypedTxId,
 }
 
 impl<'a> TransactionView<'a> {
@@ -43,8 +46,19 @@ impl<'a> TransactionView<'a> {
 	/// }
 	/// ```
 	pub fn new(rlp: ViewRlp<'a>) -> TransactionView<'a> {
+		let tx_type = if rlp.rlp.is_list() {
+			TypedTxId::Legacy
+		} else {
+			rlp.rlp.data().ok()
+				.and_then(|data| data.first().cloned())
+				.and_then(TypedTxId::from_type_byte)
+				.expect("View rlp is trusted and should be valid; typed transactions start with a known type byte; qed")
+		};
+
 		TransactionView {
-			rlp: rlp
+			fields: rlp.typed_payload(),
+			rlp,
+			tx_type,
 		}
 	}
 
@@ -55,32 +69,49 @@ impl<'a> TransactionView<'a> {
 
 	/// Returns transaction hash.
 	pub fn hash(&self) -> H256 {
-		keccak(self.rlp.as_raw())
+		keccak(raw_item_bytes(&self.rlp.rlp))
+	}
+
+	/// Returns the type of the transaction.
+	pub fn tx_type(&self) -> TypedTxId { self.tx_type }
+
+	/// Position of the field with the given legacy index in the payload of this transaction type.
+	fn index(&self, legacy_index: usize) -> usize {
+		match self.tx_type {
+			TypedTxId::Legacy => legacy_index,
+			// the chain id goes first and the access list precedes the signature
+			TypedTxId::AccessList if legacy_index < 6 => legacy_index + 1,
+			TypedTxId::AccessList => legacy_index + 2,
+			// the priority fee precedes the max fee, which takes the place of the gas price
+			TypedTxId::EIP1559 if legacy_index < 1 => legacy_index + 1,
+			TypedTxId::EIP1559 if legacy_index < 6 => legacy_index + 2,
+			TypedTxId::EIP1559 => legacy_index + 3,
+		}
 	}
 
 	/// Get the nonce field of the transaction.
-	pub fn nonce(&self) -> U256 { self.rlp.val_at(0) }
+	pub fn nonce(&self) -> U256 { self.fields.val_at(self.index(0)) }
 
-	/// Get the gas_price field of the transaction.
-	pub fn gas_price(&self) -> U256 { self.rlp.val_at(1) }
+	/// Get the gas_price field of the transaction; the max fee per gas for EIP-1559 transactions.
+	pub fn gas_price(&self) -> U256 { self.fields.val_at(self.index(1)) }
 
 	/// Get the gas field of the transaction.
-	pub fn gas(&self) -> U256 { self.rlp.val_at(2) }
+	pub fn gas(&self) -> U256 { self.fields.val_at(self.index(2)) }
 
 	/// Get the value field of the transaction.
-	pub fn value(&self) -> U256 { self.rlp.val_at(4) }
+	pub fn value(&self) -> U256 { self.fields.val_at(self.index(4)) }
 
 	/// Get the data field of the transaction.
-	pub fn data(&self) -> Bytes { self.rlp.val_at(5) }
+	pub fn data(&self) -> Bytes { self.fields.val_at(self.index(5)) }
 
-	/// Get the v field of the transaction.
-	pub fn v(&self) -> u8 { let r: u16 = self.rlp.val_at(6); r as u8 }
+	/// Get the v field of the transaction; the y-parity for typed transactions.
+	pub fn v(&self) -> u8 { let r: u16 = self.fields.val_at(self.index(6)); r as u8 }
 
 	/// Get the r field of the transaction.
-	pub fn r(&self) -> U256 { self.rlp.val_at(7) }
+	pub fn r(&self) -> U256 { self.fields.val_at(self.index(7)) }
 
 	/// Get the s field of the transaction.
-	pub fn s(&self) -> U256 { self.rlp.val_at(8) }
+	pub fn s(&self) -> U256 { self.fields.val_at(self.index(8)) }
 }
 
 #[cfg(test)]
@@ -102,4 +133,36 @@ mod tests {
 		assert_eq!(view.s(), "efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804".into());
 		assert_eq!(view.v(), 0x1b);
 	}
+
+	#[test]
+	fn test_access_list_transaction_view() {
+		use ethereum_types::{Address, U256};
+		use hash::keccak;
+		use parity_crypto::publickey::{Random, Generator};
+		use transaction::{AccessListTx, Action, Transaction, TypedTransaction, TypedTxId};
+
+		let key = Random.generate().unwrap();
+		let tx = TypedTransaction::AccessList(AccessListTx::new(
+			Transaction {
+				action: Action::Call(Address::from_low_u64_be(5)),
+				nonce: U256::from(1),
+				gas_price: U256::from(2),
+				gas: U256::from(30_000),
+				value: U256::from(4),
+				data: vec![1, 2, 3],
+			},
+			vec![],
+		)).sign(key.secret(), Some(1));
+		let rlp = ::rlp::encode(&tx);
+
+		let view = view!(TransactionView, &rlp);
+		assert_eq!(view.tx_type(), TypedTxId::AccessList);
+		assert_eq!(view.hash(), keccak(tx.encode_raw()));
+		assert_eq!(view.nonce(), 1.into());
+		assert_eq!(view.gas_price(), 2.into());
+		assert_eq!(view.gas(), 30_000.into());
+		assert_eq!(view.value(), 4.into());
+		assert_eq!(view.data(), vec![1, 2, 3]);
+		assert_eq!(view.v() as u64, tx.original_v());
+	}
 }
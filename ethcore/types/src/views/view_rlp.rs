@@ -67,6 +67,17 @@ impl<'a, 'view> ViewRlp<'a> where 'a : 'view {
 		self.new_from_rlp(rlp)
 	}
 
+	/// Returns the fields of an EIP-2718 encoded item (transaction or receipt): the list itself
+	/// for legacy items, the payload following the type byte for typed ones.
+	pub fn typed_payload(&self) -> ViewRlp<'a> {
+		if self.rlp.is_list() {
+			return self.new_from_rlp(Rlp::new(self.rlp.as_raw()));
+		}
+		let data = self.expect_valid_rlp(self.rlp.data());
+		let payload = self.expect_valid_rlp(data.get(1..).ok_or(DecoderError::RlpIsTooShort));
+		self.new_from_rlp(Rlp::new(payload))
+	}
+
 	/// Returns an iterator over all rlp values
 	pub fn iter(&'view self) -> ViewRlpIterator<'a, 'view> {
 		self.into_iter()
@@ -82,6 +93,11 @@ impl<'a, 'view> ViewRlp<'a> where 'a : 'view {
 		self.expect_valid_rlp(self.rlp.val_at(index))
 	}
 
+	/// Returns the value decoded by `decode`, panics if rlp not valid
+	pub fn decode_with<T, F>(&self, decode: F) -> T where F: FnOnce(&Rlp<'a>) -> Result<T, DecoderError> {
+		self.expect_valid_rlp(decode(&self.rlp))
+	}
+
 	/// Returns decoded list of values, panics if rlp is invalid
 	pub fn list_at<T>(&self, index: usize) -> Vec<T> where T: Decodable {
 		self.expect_valid_rlp(self.rlp.list_at(index))
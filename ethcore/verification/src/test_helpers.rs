@@ -38,7 +38,7 @@ impl TestBlockChain {
 	pub fn new() -> Self { TestBlockChain::default() }
 
 	pub fn insert(&mut self, bytes: Bytes) {
-		let header = Unverified::from_rlp(bytes.clone()).unwrap().header;
+		let header = Unverified::from_rlp(bytes.clone(), BlockNumber::max_value()).unwrap().header;
 		let hash = header.hash();
 		self.blocks.insert(hash, bytes);
 		self.numbers.insert(header.number(), hash);
@@ -66,7 +66,7 @@ impl BlockProvider for TestBlockChain {
 	/// Get the familial details concerning a block.
 	fn block_details(&self, hash: &H256) -> Option<BlockDetails> {
 		self.blocks.get(hash).map(|bytes| {
-			let header = Unverified::from_rlp(bytes.to_vec()).unwrap().header;
+			let header = Unverified::from_rlp(bytes.to_vec(), BlockNumber::max_value()).unwrap().header;
 			BlockDetails {
 				number: header.number(),
 				total_difficulty: *header.difficulty(),
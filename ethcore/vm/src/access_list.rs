@@ -0,0 +1,188 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Accessed addresses and storage keys of a transaction (EIP-2929).
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+use ethereum_types::{Address, H256};
+use parking_lot::Mutex;
+
+#[derive(Debug, Default)]
+struct Journal {
+	enabled: bool,
+	last_id: usize,
+	addresses: HashMap<Address, usize>,
+	storage_keys: HashMap<(Address, H256), usize>,
+	recorded: Option<RecordedAccesses>,
+}
+
+/// Addresses, with their storage keys, accessed since recording started.
+pub type RecordedAccesses = BTreeMap<Address, BTreeSet<H256>>;
+
+/// Addresses and storage keys accessed by a transaction.
+///
+/// All call frames of a transaction share one journal. Every frame works on its own handle
+/// (see `fork`), and each entry remembers the frame that inserted it, so that a reverted frame
+/// can drop whatever it and its children have warmed up.
+#[derive(Debug, Clone, Default)]
+pub struct AccessList {
+	id: usize,
+	journal: Arc<Mutex<Journal>>,
+}
+
+impl AccessList {
+	/// Create a new access list. A disabled access list does not record anything.
+	pub fn new(enabled: bool) -> Self {
+		AccessList {
+			id: 0,
+			journal: Arc::new(Mutex::new(Journal {
+				enabled,
+				..Default::default()
+			})),
+		}
+	}
+
+	/// Whether accesses are being recorded.
+	pub fn is_enabled(&self) -> bool {
+		self.journal.lock().enabled
+	}
+
+	/// Create a handle for a nested call frame, sharing the same journal.
+	pub fn fork(&self) -> Self {
+		let mut journal = self.journal.lock();
+		journal.last_id += 1;
+		AccessList {
+			id: journal.last_id,
+			journal: self.journal.clone(),
+		}
+	}
+
+	/// Check whether `address` has been accessed.
+	pub fn contains_address(&self, address: &Address) -> bool {
+		self.journal.lock().addresses.contains_key(address)
+	}
+
+	/// Record an access to `address`.
+	pub fn insert_address(&mut self, address: Address) {
+		let mut journal = self.journal.lock();
+		if journal.enabled {
+			journal.addresses.entry(address).or_insert(self.id);
+			if let Some(ref mut recorded) = journal.recorded {
+				recorded.entry(address).or_default();
+			}
+		}
+	}
+
+	/// Check whether storage `key` of `address` has been accessed.
+	pub fn contains_storage_key(&self, address: &Address, key: &H256) -> bool {
+		self.journal.lock().storage_keys.contains_key(&(*address, *key))
+	}
+
+	/// Record an access to storage `key` of `address`.
+	pub fn insert_storage_key(&mut self, address: Address, key: H256) {
+		let mut journal = self.journal.lock();
+		if journal.enabled {
+			journal.storage_keys.entry((address, key)).or_insert(self.id);
+			if let Some(ref mut recorded) = journal.recorded {
+				recorded.entry(address).or_default().insert(key);
+			}
+		}
+	}
+
+	/// Start recording every access, including accesses to entries that are already warm.
+	/// Recorded accesses are kept when a frame is rolled back.
+	pub fn start_recording(&mut self) {
+		self.journal.lock().recorded = Some(RecordedAccesses::new());
+	}
+
+	/// Accesses recorded since `start_recording`, if recording.
+	pub fn recorded(&self) -> Option<RecordedAccesses> {
+		self.journal.lock().recorded.clone()
+	}
+
+	/// Forget all accesses recorded by this frame and the frames forked from it.
+	pub fn rollback(&self) {
+		let id = self.id;
+		let mut journal = self.journal.lock();
+		journal.addresses.retain(|_, inserted_by| *inserted_by < id);
+		journal.storage_keys.retain(|_, inserted_by| *inserted_by < id);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use ethereum_types::{Address, H256};
+	use super::{AccessList, RecordedAccesses};
+
+	#[test]
+	fn disabled_access_list_records_nothing() {
+		let mut access_list = AccessList::new(false);
+		access_list.insert_address(Address::from_low_u64_be(1));
+		access_list.insert_storage_key(Address::from_low_u64_be(1), H256::from_low_u64_be(2));
+
+		assert!(!access_list.contains_address(&Address::from_low_u64_be(1)));
+		assert!(!access_list.contains_storage_key(&Address::from_low_u64_be(1), &H256::from_low_u64_be(2)));
+	}
+
+	#[test]
+	fn rollback_keeps_parent_accesses() {
+		let (a, b, c) = (Address::from_low_u64_be(1), Address::from_low_u64_be(2), Address::from_low_u64_be(3));
+		let key = H256::from_low_u64_be(4);
+
+		let mut parent = AccessList::new(true);
+		parent.insert_address(a);
+
+		let mut succeeded = parent.fork();
+		succeeded.insert_address(b);
+
+		let mut reverted = parent.fork();
+		reverted.insert_address(a);
+		reverted.insert_address(c);
+		reverted.insert_storage_key(c, key);
+		reverted.fork().insert_storage_key(b, key);
+		reverted.rollback();
+
+		assert!(parent.contains_address(&a));
+		assert!(parent.contains_address(&b));
+		assert!(!parent.contains_address(&c));
+		assert!(!parent.contains_storage_key(&c, &key));
+		assert!(!parent.contains_storage_key(&b, &key));
+	}
+
+	#[test]
+	fn recording_keeps_warm_and_reverted_accesses() {
+		let (a, b) = (Address::from_low_u64_be(1), Address::from_low_u64_be(2));
+		let key = H256::from_low_u64_be(3);
+
+		let mut access_list = AccessList::new(true);
+		access_list.insert_address(a);
+		access_list.insert_address(b);
+		assert_eq!(access_list.recorded(), None);
+
+		access_list.start_recording();
+		access_list.insert_address(a);
+		let mut reverted = access_list.fork();
+		reverted.insert_storage_key(b, key);
+		reverted.rollback();
+
+		let mut expected = RecordedAccesses::new();
+		expected.insert(a, Default::default());
+		expected.insert(b, vec![key].into_iter().collect());
+		assert_eq!(access_list.recorded(), Some(expected));
+		assert!(!access_list.contains_storage_key(&b, &key));
+	}
+}
@@ -45,6 +45,8 @@ pub struct EnvInfo {
 	pub last_hashes: Arc<LastHashes>,
 	/// The gas used.
 	pub gas_used: U256,
+	/// The block base fee, `None` before EIP-1559.
+	pub base_fee: Option<U256>,
 }
 
 impl Default for EnvInfo {
@@ -57,6 +59,7 @@ impl Default for EnvInfo {
 			gas_limit: 0.into(),
 			last_hashes: Arc::new(vec![]),
 			gas_used: 0.into(),
+			base_fee: None,
 		}
 	}
 }
@@ -72,6 +75,7 @@ impl From<ethjson::vm::Env> for EnvInfo {
 			timestamp: e.timestamp.into(),
 			last_hashes: Arc::new((1..cmp::min(number + 1, 257)).map(|i| keccak(format!("{}", number - i).as_bytes())).collect()),
 			gas_used: U256::default(),
+			base_fee: e.base_fee.map(Into::into),
 		}
 	}
 }
@@ -90,7 +94,8 @@ mod tests {
 			number: ethjson::uint::Uint(U256::from(1_112_339)),
 			difficulty: ethjson::uint::Uint(U256::from(50_000)),
 			gas_limit: ethjson::uint::Uint(U256::from(40_000)),
-			timestamp: ethjson::uint::Uint(U256::from(1_100))
+			timestamp: ethjson::uint::Uint(U256::from(1_100)),
+			base_fee: None,
 		});
 
 		assert_eq!(env_info.number, 1112339);
@@ -160,6 +160,18 @@ pub trait Ext {
 	/// Decrements sstore refunds counter.
 	fn sub_sstore_refund(&mut self, value: usize);
 
+	/// Check if an address has already been accessed by the current transaction (EIP-2929).
+	fn is_address_warm(&self, address: &Address) -> bool;
+
+	/// Mark an address as accessed by the current transaction (EIP-2929).
+	fn warm_address(&mut self, address: Address);
+
+	/// Check if a storage key of the current contract has already been accessed by the current transaction (EIP-2929).
+	fn is_storage_key_warm(&self, key: &H256) -> bool;
+
+	/// Mark a storage key of the current contract as accessed by the current transaction (EIP-2929).
+	fn warm_storage_key(&mut self, key: H256);
+
 	/// Decide if any more operations should be traced. Passthrough for the VM trace.
 	fn trace_next_instruction(&mut self, _pc: usize, _instruction: u8, _current_gas: U256) -> bool { false }
 
@@ -22,7 +22,9 @@ extern crate ethjson;
 extern crate rlp;
 extern crate keccak_hash as hash;
 extern crate patricia_trie_ethereum as ethtrie;
+extern crate parking_lot;
 
+mod access_list;
 mod action_params;
 mod action_type;
 mod env_info;
@@ -33,6 +35,7 @@ mod error;
 
 pub mod tests;
 
+pub use access_list::{AccessList, RecordedAccesses};
 pub use action_params::{ActionParams, ActionValue, ParamsType};
 pub use action_type::ActionType;
 pub use env_info::{EnvInfo, LastHashes};
@@ -81,6 +81,8 @@ pub struct Schedule {
 	pub call_new_account_gas: usize,
 	/// Refund for SUICIDE
 	pub suicide_refund_gas: usize,
+	/// Refunds are capped to `gas_used / max_refund_quotient` of the transaction
+	pub max_refund_quotient: usize,
 	/// Gas for used memory
 	pub memory_gas: usize,
 	/// Coefficient used to convert memory size to gas price for memory
@@ -89,6 +91,10 @@ pub struct Schedule {
 	pub create_data_gas: usize,
 	/// Maximum code size when creating a contract.
 	pub create_data_limit: usize,
+	/// Maximum size of the init code of a contract creation, if limited (EIP-3860).
+	pub max_initcode_size: Option<usize>,
+	/// Gas price for every word of the init code of a contract creation (EIP-3860)
+	pub initcode_word_gas: usize,
 	/// Transaction cost
 	pub tx_gas: usize,
 	/// `CREATE` transaction cost
@@ -97,6 +103,10 @@ pub struct Schedule {
 	pub tx_data_zero_gas: usize,
 	/// Additional cost for non-empty data transaction
 	pub tx_data_non_zero_gas: usize,
+	/// Additional cost for every address in the transaction's access list
+	pub tx_access_list_address_gas: usize,
+	/// Additional cost for every storage key in the transaction's access list
+	pub tx_access_list_storage_key_gas: usize,
 	/// Gas price for copying memory
 	pub copy_gas: usize,
 	/// Price of EXTCODESIZE
@@ -130,12 +140,26 @@ pub struct Schedule {
 	pub have_chain_id: bool,
 	/// SELFBALANCE opcode enabled.
 	pub have_selfbalance: bool,
+	/// BASEFEE opcode enabled.
+	pub have_basefee: bool,
+	/// PUSH0 opcode enabled.
+	pub have_push0: bool,
 	/// Kill basic accounts below this balance if touched.
 	pub kill_dust: CleanDustMode,
 	/// Enable EIP-1283 rules
 	pub eip1283: bool,
 	/// Enable EIP-1706 rules
 	pub eip1706: bool,
+	/// Enable EIP-2929 rules
+	pub eip2929: bool,
+	/// Enable EIP-3541 rules: reject new contract code starting with the 0xEF byte
+	pub eip3541: bool,
+	/// Gas price for loading a storage key not yet accessed by the transaction (EIP-2929)
+	pub cold_sload_cost: usize,
+	/// Gas price for accessing an account not yet accessed by the transaction (EIP-2929)
+	pub cold_account_access_cost: usize,
+	/// Gas price for accessing an account or storage key already accessed by the transaction (EIP-2929)
+	pub warm_storage_read_cost: usize,
 	/// VM execution does not increase null signed address nonce if this field is true.
 	pub keep_unsigned_nonce: bool,
 	/// Latest VM version for contract creation transaction.
@@ -233,6 +257,8 @@ impl Schedule {
 			have_bitwise_shifting: false,
 			have_chain_id: false,
 			have_selfbalance: false,
+			have_basefee: false,
+			have_push0: false,
 			have_extcodehash: false,
 			stack_limit: 1024,
 			max_depth: 1024,
@@ -256,14 +282,19 @@ impl Schedule {
 			call_value_transfer_gas: 9000,
 			call_new_account_gas: 25000,
 			suicide_refund_gas: 24000,
+			max_refund_quotient: 2,
 			memory_gas: 3,
 			quad_coeff_div: 512,
 			create_data_gas: 200,
 			create_data_limit: max_code_size,
+			max_initcode_size: None,
+			initcode_word_gas: 0,
 			tx_gas: 21000,
 			tx_create_gas: 53000,
 			tx_data_zero_gas: 4,
 			tx_data_non_zero_gas: 68,
+			tx_access_list_address_gas: 2400,
+			tx_access_list_storage_key_gas: 1900,
 			copy_gas: 3,
 			extcodesize_gas: 700,
 			extcodecopy_base_gas: 700,
@@ -279,6 +310,11 @@ impl Schedule {
 			kill_dust: CleanDustMode::Off,
 			eip1283: false,
 			eip1706: false,
+			eip2929: false,
+			eip3541: false,
+			cold_sload_cost: 2100,
+			cold_account_access_cost: 2600,
+			warm_storage_read_cost: 100,
 			keep_unsigned_nonce: false,
 			latest_version: U256::zero(),
 			versions: HashMap::new(),
@@ -315,6 +351,60 @@ impl Schedule {
 		schedule
 	}
 
+	/// Schedule for the Berlin fork of the Ethereum main net.
+	pub fn new_berlin() -> Schedule {
+		let mut schedule = Self::new_istanbul();
+		schedule.eip1283 = true; // EIP 2200
+		schedule.eip1706 = true; // EIP 2200
+		schedule.enable_eip2929();
+		schedule
+	}
+
+	/// Schedule for the London fork of the Ethereum main net.
+	pub fn new_london() -> Schedule {
+		let mut schedule = Self::new_berlin();
+		schedule.have_basefee = true; // EIP 3198
+		schedule.eip3541 = true;
+		schedule.enable_eip3529();
+		schedule
+	}
+
+	/// Schedule for the Shanghai fork of the Ethereum main net.
+	pub fn new_shanghai() -> Schedule {
+		let mut schedule = Self::new_london();
+		schedule.have_push0 = true; // EIP 3855
+		schedule.enable_eip3860();
+		schedule
+	}
+
+	/// Switch state access costs to the warm/cold pricing of EIP-2929.
+	pub fn enable_eip2929(&mut self) {
+		self.eip2929 = true;
+		self.sload_gas = self.warm_storage_read_cost;
+		self.sstore_dirty_gas = Some(self.warm_storage_read_cost);
+		self.sstore_reset_gas = 5000 - self.cold_sload_cost;
+		self.call_gas = self.warm_storage_read_cost;
+		self.balance_gas = self.warm_storage_read_cost;
+		self.extcodesize_gas = self.warm_storage_read_cost;
+		self.extcodecopy_base_gas = self.warm_storage_read_cost;
+		self.extcodehash_gas = self.warm_storage_read_cost;
+	}
+
+	/// Reduce the `SSTORE` clearing refund, remove the `SUICIDE` refund and lower the refund cap (EIP-3529).
+	///
+	/// Expects the EIP-2929 costs to be in place already.
+	pub fn enable_eip3529(&mut self) {
+		self.sstore_refund_gas = self.sstore_reset_gas + self.tx_access_list_storage_key_gas;
+		self.suicide_refund_gas = 0;
+		self.max_refund_quotient = 5;
+	}
+
+	/// Limit and meter the init code of contract creations (EIP-3860).
+	pub fn enable_eip3860(&mut self) {
+		self.max_initcode_size = Some(self.create_data_limit.saturating_mul(2));
+		self.initcode_word_gas = 2;
+	}
+
 	fn new(efcd: bool, hdc: bool, tcg: usize) -> Schedule {
 		Schedule {
 			exceptional_failed_code_deposit: efcd,
@@ -325,6 +415,8 @@ impl Schedule {
 			have_bitwise_shifting: false,
 			have_chain_id: false,
 			have_selfbalance: false,
+			have_basefee: false,
+			have_push0: false,
 			have_extcodehash: false,
 			stack_limit: 1024,
 			max_depth: 1024,
@@ -348,14 +440,19 @@ impl Schedule {
 			call_value_transfer_gas: 9000,
 			call_new_account_gas: 25000,
 			suicide_refund_gas: 24000,
+			max_refund_quotient: 2,
 			memory_gas: 3,
 			quad_coeff_div: 512,
 			create_data_gas: 200,
 			create_data_limit: usize::max_value(),
+			max_initcode_size: None,
+			initcode_word_gas: 0,
 			tx_gas: 21000,
 			tx_create_gas: tcg,
 			tx_data_zero_gas: 4,
 			tx_data_non_zero_gas: 68,
+			tx_access_list_address_gas: 2400,
+			tx_access_list_storage_key_gas: 1900,
 			copy_gas: 3,
 			extcodesize_gas: 20,
 			extcodecopy_base_gas: 20,
@@ -371,6 +468,11 @@ impl Schedule {
 			kill_dust: CleanDustMode::Off,
 			eip1283: false,
 			eip1706: false,
+			eip2929: false,
+			eip3541: false,
+			cold_sload_cost: 2100,
+			cold_account_access_cost: 2600,
+			warm_storage_read_cost: 100,
 			keep_unsigned_nonce: false,
 			latest_version: U256::zero(),
 			versions: HashMap::new(),
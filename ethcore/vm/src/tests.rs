@@ -67,6 +67,8 @@ pub struct FakeExt {
 	pub balances: HashMap<Address, U256>,
 	pub tracing: bool,
 	pub is_static: bool,
+	pub warm_addresses: HashSet<Address>,
+	pub warm_storage_keys: HashSet<H256>,
 
 	chain_id: u64,
 }
@@ -107,6 +109,27 @@ impl FakeExt {
 		ext
 	}
 
+	/// New fake externalities with Berlin schedule rules
+	pub fn new_berlin() -> Self {
+		let mut ext = FakeExt::default();
+		ext.schedule = Schedule::new_berlin();
+		ext
+	}
+
+	/// New fake externalities with London schedule rules
+	pub fn new_london() -> Self {
+		let mut ext = FakeExt::default();
+		ext.schedule = Schedule::new_london();
+		ext
+	}
+
+	/// New fake externalities with Shanghai schedule rules
+	pub fn new_shanghai() -> Self {
+		let mut ext = FakeExt::default();
+		ext.schedule = Schedule::new_shanghai();
+		ext
+	}
+
 	/// Alter fake externalities to allow wasm
 	pub fn with_wasm(mut self) -> Self {
 		self.schedule.wasm = Some(Default::default());
@@ -259,6 +282,22 @@ impl Ext for FakeExt {
 		self.sstore_clears -= value as i128;
 	}
 
+	fn is_address_warm(&self, address: &Address) -> bool {
+		self.warm_addresses.contains(address)
+	}
+
+	fn warm_address(&mut self, address: Address) {
+		self.warm_addresses.insert(address);
+	}
+
+	fn is_storage_key_warm(&self, key: &H256) -> bool {
+		self.warm_storage_keys.contains(key)
+	}
+
+	fn warm_storage_key(&mut self, key: H256) {
+		self.warm_storage_keys.insert(key);
+	}
+
 	fn trace_next_instruction(&mut self, _pc: usize, _instruction: u8, _gas: U256) -> bool {
 		self.tracing
 	}
@@ -0,0 +1,330 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Log EVM instruction traces in the format of EIP-3155, used for differential fuzzing
+//! against other clients.
+
+use std::io;
+use std::time::Duration;
+
+use ethereum_types::{H256, U256};
+use parity_bytes::ToPretty;
+use serde::Serialize;
+use trace;
+
+use crate::{
+	display::std_json::Writer,
+	info as vm,
+};
+
+/// EIP-3155 formatting informant.
+pub struct Informant<Trace, Out> {
+	depth: usize,
+	instruction: u8,
+	stack: Vec<U256>,
+	mem_size: usize,
+	/// Refund counter when this frame was entered.
+	refund_base: i128,
+	/// Refund counter change made by this frame and its successful subcalls.
+	refund: i128,
+	/// Step announced by `trace_next_instruction` but not written yet.
+	pending: Option<(usize, u8, U256)>,
+	failed: bool,
+	subinfos: Vec<Informant<Trace, Out>>,
+	subdepth: usize,
+	trace_sink: Trace,
+	out_sink: Out,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceData<'a> {
+	pc: usize,
+	op: u8,
+	gas: &'a str,
+	gas_cost: &'a str,
+	mem_size: usize,
+	stack: &'a [U256],
+	depth: usize,
+	refund: i64,
+	op_name: &'a str,
+}
+
+/// Summary line closing the trace of a transaction.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Summary<'a> {
+	state_root: &'a H256,
+	output: &'a str,
+	gas_used: &'a str,
+	pass: bool,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	error: Option<&'a str>,
+	time: u64,
+}
+
+impl Default for Informant<io::Stderr, io::Stdout> {
+	fn default() -> Self {
+		Self::new(io::stderr(), io::stdout())
+	}
+}
+
+impl Informant<io::Stdout, io::Stdout> {
+	/// EIP-3155 formatting informant using stdout only.
+	pub fn out_only() -> Self {
+		Self::new(io::stdout(), io::stdout())
+	}
+}
+
+impl Informant<io::Stderr, io::Stderr> {
+	/// EIP-3155 formatting informant using stderr only.
+	pub fn err_only() -> Self {
+		Self::new(io::stderr(), io::stderr())
+	}
+}
+
+impl<Trace: Writer, Out: Writer> Informant<Trace, Out> {
+
+	pub fn new(trace_sink: Trace, out_sink: Out) -> Self {
+		Informant {
+			depth: 1,
+			instruction: Default::default(),
+			stack: Default::default(),
+			mem_size: 0,
+			refund_base: 0,
+			refund: 0,
+			pending: None,
+			failed: false,
+			subinfos: Default::default(),
+			subdepth: 0,
+			trace_sink,
+			out_sink,
+		}
+	}
+
+	fn with_informant_in_depth<F: Fn(&mut Informant<Trace, Out>)>(informant: &mut Informant<Trace, Out>, depth: usize, f: F) {
+		if depth == 0 {
+			f(informant);
+		} else {
+			Self::with_informant_in_depth(informant.subinfos.last_mut().expect("prepare/done_trace are not balanced"), depth - 1, f);
+		}
+	}
+
+	/// Writes the pending step with the given cost.
+	fn write_step(&mut self, gas_cost: U256) {
+		if let Some((pc, op, gas)) = self.pending.take() {
+			let name = ::evm::Instruction::from_u8(op)
+				.map_or_else(|| format!("opcode {:#x} not defined", op), |i| i.info().name.to_owned());
+
+			let trace_data =
+				TraceData {
+					pc,
+					op,
+					gas: &format!("{:#x}", gas),
+					gas_cost: &format!("{:#x}", gas_cost),
+					mem_size: self.mem_size,
+					stack: &self.stack,
+					depth: self.depth,
+					refund: (self.refund_base + self.refund) as i64,
+					op_name: &name,
+				}
+			;
+
+			let s = serde_json::to_string(&trace_data).expect("Serialization cannot fail; qed");
+			writeln!(&mut self.trace_sink, "{}", s).expect("The sink must be writeable.");
+		}
+	}
+
+	/// Writes the summary line of a transaction.
+	pub fn write_summary(out_sink: &mut Out, state_root: &H256, output: &[u8], gas_used: U256, error: Option<&str>, time: &Duration) {
+		let summary =
+			Summary {
+				state_root,
+				output: &format!("0x{}", output.to_hex()),
+				gas_used: &format!("{:#x}", gas_used),
+				pass: error.is_none(),
+				error,
+				time: time.as_secs() * 1_000_000_000 + time.subsec_nanos() as u64,
+			}
+		;
+
+		let s = serde_json::to_string(&summary).expect("Serialization cannot fail; qed");
+		writeln!(out_sink, "{}", s).expect("The sink must be writeable.");
+	}
+}
+
+impl<Trace: Writer, Out: Writer> vm::Informant for Informant<Trace, Out> {
+
+	type Sink = (Trace, Out);
+
+	fn before_test(&mut self, _name: &str, _action: &str) {}
+
+	fn clone_sink(&self) -> Self::Sink {
+		(self.trace_sink.clone(), self.out_sink.clone())
+	}
+
+	fn finish(result: vm::RunResult<<Self as trace::VMTracer>::Output>, (_, ref mut out_sink): &mut Self::Sink) {
+		match result {
+			Ok(success) => {
+				Self::write_summary(out_sink, &success.state_root, &success.output, success.gas_used, None, &success.time);
+			},
+			Err(failure) => {
+				let error = failure.error.to_string();
+				Self::write_summary(out_sink, &failure.state_root, &[], failure.gas_used, Some(&error), &failure.time);
+			},
+		}
+	}
+}
+
+impl<Trace: Writer, Out: Writer> trace::VMTracer for Informant<Trace, Out> {
+	type Output = ();
+
+	fn trace_next_instruction(&mut self, pc: usize, instruction: u8, current_gas: U256) -> bool {
+		let subdepth = self.subdepth;
+		Self::with_informant_in_depth(self, subdepth, |informant: &mut Informant<Trace, Out>| {
+			// a step which failed before its cost was known
+			informant.write_step(U256::zero());
+			informant.pending = Some((pc, instruction, current_gas));
+		});
+		true
+	}
+
+	fn trace_prepare_execute(&mut self, _pc: usize, instruction: u8, gas_cost: U256, _mem_written: Option<(usize, usize)>, _store_written: Option<(U256, U256)>) {
+		let subdepth = self.subdepth;
+		Self::with_informant_in_depth(self, subdepth, |informant: &mut Informant<Trace, Out>| {
+			informant.instruction = instruction;
+			informant.write_step(gas_cost);
+		});
+	}
+
+	fn trace_failed(&mut self) {
+		let subdepth = self.subdepth;
+		Self::with_informant_in_depth(self, subdepth, |informant: &mut Informant<Trace, Out>| {
+			informant.failed = true;
+		});
+	}
+
+	fn trace_executed(&mut self, _gas_used: U256, stack_push: &[U256], mem: &[u8]) {
+		let subdepth = self.subdepth;
+		Self::with_informant_in_depth(self, subdepth, |informant: &mut Informant<Trace, Out>| {
+			let info = ::evm::Instruction::from_u8(informant.instruction).map(|i| i.info());
+
+			let len = informant.stack.len();
+			let info_args = info.map(|i| i.args).unwrap_or(0);
+			informant.stack.truncate(if len > info_args { len - info_args } else { 0 });
+			informant.stack.extend_from_slice(stack_push);
+			informant.mem_size = mem.len();
+		});
+	}
+
+	fn trace_refund(&mut self, delta: i128) {
+		let subdepth = self.subdepth;
+		Self::with_informant_in_depth(self, subdepth, |informant: &mut Informant<Trace, Out>| {
+			informant.refund += delta;
+		});
+	}
+
+	fn prepare_subtrace(&mut self, _code: &[u8]) {
+		let subdepth = self.subdepth;
+		Self::with_informant_in_depth(self, subdepth, |informant: &mut Informant<Trace, Out>| {
+			let mut vm = Informant::new(informant.trace_sink.clone(), informant.out_sink.clone());
+			vm.depth = informant.depth + 1;
+			vm.refund_base = informant.refund_base + informant.refund;
+			informant.subinfos.push(vm);
+		});
+		self.subdepth += 1;
+	}
+
+	fn done_subtrace(&mut self) {
+		self.subdepth -= 1;
+		let subdepth = self.subdepth;
+		Self::with_informant_in_depth(self, subdepth, |informant: &mut Informant<Trace, Out>| {
+			let mut sub = informant.subinfos.pop().expect("prepare/done_subtrace are not balanced");
+			let halted = sub.pending.is_some();
+			sub.write_step(U256::zero());
+			// refunds of reverted frames are discarded
+			let reverted = halted || sub.failed || sub.instruction == ::evm::Instruction::REVERT as u8;
+			if !reverted {
+				informant.refund += sub.refund;
+			}
+		});
+	}
+
+	fn drain(mut self) -> Option<Self::Output> {
+		self.write_step(U256::zero());
+		None
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::display::std_json::tests::TestWriter;
+	use crate::info::tests::run_test;
+
+	fn informant() -> (Informant<TestWriter, TestWriter>, TestWriter) {
+		let trace_writer: TestWriter = Default::default();
+		let trace_copy = Clone::clone(&trace_writer);
+		(Informant::new(trace_writer, Default::default()), trace_copy)
+	}
+
+	#[test]
+	fn should_trace_steps() {
+		let (inf, res) = informant();
+		run_test(
+			inf,
+			move |_, expected| {
+				let bytes = res.0.lock().unwrap();
+				assert_eq!(expected, &String::from_utf8_lossy(&**bytes))
+			},
+			"602a60005200",
+			0xffff,
+			r#"{"pc":0,"op":96,"gas":"0xffff","gasCost":"0x3","memSize":0,"stack":[],"depth":1,"refund":0,"opName":"PUSH1"}
+{"pc":2,"op":96,"gas":"0xfffc","gasCost":"0x3","memSize":0,"stack":["0x2a"],"depth":1,"refund":0,"opName":"PUSH1"}
+{"pc":4,"op":82,"gas":"0xfff9","gasCost":"0x6","memSize":0,"stack":["0x2a","0x0"],"depth":1,"refund":0,"opName":"MSTORE"}
+{"pc":5,"op":0,"gas":"0xfff3","gasCost":"0x0","memSize":32,"stack":[],"depth":1,"refund":0,"opName":"STOP"}
+"#,
+		);
+	}
+
+	#[test]
+	fn should_trace_undefined_instruction() {
+		let (inf, res) = informant();
+		run_test(
+			inf,
+			move |_, expected| {
+				let bytes = res.0.lock().unwrap();
+				assert_eq!(expected, &String::from_utf8_lossy(&**bytes))
+			},
+			"60F8d6",
+			0xffff,
+			r#"{"pc":0,"op":96,"gas":"0xffff","gasCost":"0x3","memSize":0,"stack":[],"depth":1,"refund":0,"opName":"PUSH1"}
+{"pc":2,"op":214,"gas":"0xfffc","gasCost":"0x0","memSize":0,"stack":["0xf8"],"depth":1,"refund":0,"opName":"opcode 0xd6 not defined"}
+"#,
+		);
+	}
+
+	#[test]
+	fn should_write_summary() {
+		let mut out: TestWriter = Default::default();
+		Informant::<TestWriter, TestWriter>::write_summary(&mut out, &H256::zero(), &[0x2a], 0x5208.into(), None, &Duration::from_nanos(1500));
+
+		assert_eq!(
+			&String::from_utf8_lossy(&**out.0.lock().unwrap()),
+			"{\"stateRoot\":\"0x0000000000000000000000000000000000000000000000000000000000000000\",\"output\":\"0x2a\",\"gasUsed\":\"0x5208\",\"pass\":true,\"time\":1500}\n",
+		);
+	}
+}
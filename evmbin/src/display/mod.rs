@@ -18,6 +18,7 @@
 
 use std::time::Duration;
 
+pub mod eip3155;
 pub mod json;
 pub mod std_json;
 pub mod simple;
@@ -42,13 +42,14 @@ use parity_bytes::Bytes;
 use docopt::Docopt;
 use rustc_hex::FromHex;
 use ethereum_types::{U256, Address};
-use ethcore::{json_tests, test_helpers::TrieSpec};
+use ethcore::{json_tests, test_helpers::{EvmTestClient, TrieSpec}};
 use spec;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use vm::{ActionParams, ActionType};
 
 mod info;
 mod display;
+mod transition;
 
 use crate::info::{Informant, TxInput};
 
@@ -57,7 +58,8 @@ EVM implementation for Parity.
   Copyright 2015-2020 Parity Technologies (UK) Ltd.
 
 Usage:
-    parity-evm state-test <file> [--chain CHAIN --only NAME --json --std-json --std-dump-json --std-out-only --std-err-only]
+    parity-evm state-test <file> [--chain CHAIN --only NAME --json --std-json --std-dump-json --eip3155 --std-out-only --std-err-only]
+    parity-evm t8n --input-alloc FILE --input-env FILE --input-txs FILE [--state-fork FORK --output-alloc FILE --output-result FILE --eip3155]
     parity-evm stats [options]
     parity-evm stats-jsontests-vm <file>
     parity-evm [options]
@@ -69,6 +71,8 @@ Commands:
     stats-jsontests-vm Execute standard json-tests on a provided state test JSON
                        file path, format VMTests, and return timing statistics
                        in tsv format.
+    t8n                Apply transactions to a pre-state and write the post-state
+                       and the receipts.
 
 Transaction options:
     --code CODE        Contract code as hex (without 0x).
@@ -85,12 +89,23 @@ State test options:
                        HomesteadToDaoAt5, HomesteadToEIP150At5).
     --only NAME        Runs only a single test matching the name.
 
+Transition options:
+    --input-alloc FILE     Pre-state accounts JSON file.
+    --input-env FILE       Block environment JSON file (as in state tests).
+    --input-txs FILE       JSON file with the list of transactions to apply.
+    --state-fork FORK      Fork rules to apply (one of the state test chain names)
+                           [default: Istanbul].
+    --output-alloc FILE    File to write the post-state accounts to [default: alloc.json].
+    --output-result FILE   File to write the state root, receipts and rejected
+                           transactions to [default: result.json].
+
 General options:
     --chain PATH       Path to chain spec file.
     --json             Display verbose results in JSON.
     --std-json         Display results in standardized JSON format.
     --std-dump-json    Display results in standardized JSON format
                        with additional state dump.
+    --eip3155          Display per-instruction traces in the EIP-3155 format.
     --std-err-only     With --std-json redirect to err output only.
     --std-out-only     With --std-json redirect to out output only.
     -h, --help         Display this message and exit.
@@ -106,8 +121,18 @@ fn main() {
 		run_state_test(args)
 	} else if args.cmd_stats_jsontests_vm {
 		run_stats_jsontests_vm(args)
+	} else if args.cmd_t8n {
+		run_t8n(args)
 	} else if args.flag_json {
 		run_call(args, display::json::Informant::default())
+	} else if args.flag_eip3155 {
+		if args.flag_std_err_only {
+			run_call(args, display::eip3155::Informant::err_only())
+		} else if args.flag_std_out_only {
+			run_call(args, display::eip3155::Informant::out_only())
+		} else {
+			run_call(args, display::eip3155::Informant::default())
+		};
 	} else if args.flag_std_dump_json || args.flag_std_json {
 		if args.flag_std_err_only {
 			run_call(args, display::std_json::Informant::err_only())
@@ -181,9 +206,55 @@ fn run_state_test(args: Args) {
 					TrieSpec::Secure
 				};
 
+				// Execute the given transaction and verify resulting state root
+				// for CLI option `--eip3155`.
+				if args.flag_eip3155 {
+					if args.flag_std_err_only {
+						let tx_input = TxInput {
+							state_test_name: &state_test_name,
+							tx_index,
+							fork_spec_name: &fork_spec_name,
+							pre_state: &pre,
+							post_root,
+							env_info: &env_info,
+							transaction,
+							informant: display::eip3155::Informant::err_only(),
+							trie_spec,
+						};
+						// Use EIP-3155 informant with err only
+						info::run_transaction(tx_input);
+					} else if args.flag_std_out_only {
+						let tx_input = TxInput {
+							state_test_name: &state_test_name,
+							tx_index,
+							fork_spec_name: &fork_spec_name,
+							pre_state: &pre,
+							post_root,
+							env_info: &env_info,
+							transaction,
+							informant: display::eip3155::Informant::out_only(),
+							trie_spec,
+						};
+						// Use EIP-3155 informant with out only
+						info::run_transaction(tx_input);
+					} else {
+						let tx_input = TxInput {
+							state_test_name: &state_test_name,
+							tx_index,
+							fork_spec_name: &fork_spec_name,
+							pre_state: &pre,
+							post_root,
+							env_info: &env_info,
+							transaction,
+							informant: display::eip3155::Informant::default(),
+							trie_spec,
+						};
+						// Use EIP-3155 informant default
+						info::run_transaction(tx_input);
+					}
 				// Execute the given transaction and verify resulting state root
 				// for CLI option `--std-dump-json` or `--std-json`.
-				if args.flag_std_dump_json || args.flag_std_json {
+				} else if args.flag_std_dump_json || args.flag_std_json {
 					if args.flag_std_err_only {
 						let tx_input = TxInput {
 							state_test_name: &state_test_name,
@@ -299,6 +370,39 @@ fn run_stats_jsontests_vm(args: Args) {
 	}
 }
 
+// CLI command `t8n`
+fn run_t8n(args: Args) {
+	let alloc: ethjson::spec::State = read_json(&args.flag_input_alloc, "--input-alloc");
+	let env: ethjson::vm::Env = read_json(&args.flag_input_env, "--input-env");
+	let txs: Vec<ethjson::transaction::Transaction> = read_json(&args.flag_input_txs, "--input-txs");
+
+	let fork_spec_name: ethjson::spec::ForkSpec = serde_json::from_value(serde_json::Value::String(args.flag_state_fork.clone()))
+		.unwrap_or_else(|e| die(format!("Invalid --state-fork: {}", e)));
+	let spec = match EvmTestClient::fork_spec_from_json(&fork_spec_name) {
+		Some(spec) => spec,
+		None => die(format!("Fork specification {:?} is not supported", fork_spec_name)),
+	};
+
+	let (post, result) = match transition::run(&spec, alloc.into(), &env.into(), txs, args.flag_eip3155) {
+		Ok(outcome) => outcome,
+		Err(err) => die(format!("State transition failed: {}", err)),
+	};
+
+	write_json(&args.flag_output_alloc, &post);
+	write_json(&args.flag_output_result, &result);
+}
+
+fn read_json<T: DeserializeOwned>(path: &Option<PathBuf>, param: &str) -> T {
+	let path = path.as_ref().unwrap_or_else(|| die(format!("{} is required", param)));
+	let file = fs::File::open(path).unwrap_or_else(|e| die(format!("Unable to open path: {:?}: {}", path, e)));
+	serde_json::from_reader(file).unwrap_or_else(|e| die(format!("Invalid {}: {}", param, e)))
+}
+
+fn write_json<T: Serialize>(path: &PathBuf, value: &T) {
+	let file = fs::File::create(path).unwrap_or_else(|e| die(format!("Unable to create path: {:?}: {}", path, e)));
+	serde_json::to_writer_pretty(file, value).unwrap_or_else(|e| die(format!("Unable to write {:?}: {}", path, e)));
+}
+
 // CLI command `stats`
 fn run_call<T: Informant>(args: Args, informant: T) {
 	let code = arg(args.code(), "--code");
@@ -338,6 +442,7 @@ struct Args {
 	cmd_stats: bool,
 	cmd_state_test: bool,
 	cmd_stats_jsontests_vm: bool,
+	cmd_t8n: bool,
 	arg_file: Option<PathBuf>,
 	flag_code: Option<String>,
 	flag_to: Option<String>,
@@ -352,6 +457,13 @@ struct Args {
 	flag_std_dump_json: bool,
 	flag_std_err_only: bool,
 	flag_std_out_only: bool,
+	flag_eip3155: bool,
+	flag_input_alloc: Option<PathBuf>,
+	flag_input_env: Option<PathBuf>,
+	flag_input_txs: Option<PathBuf>,
+	flag_state_fork: String,
+	flag_output_alloc: PathBuf,
+	flag_output_result: PathBuf,
 }
 
 impl Args {
@@ -447,6 +559,7 @@ mod tests {
 	use ethjson::test_helpers::state::State;
 	use serde::Deserialize;
 
+	use std::path::PathBuf;
 	use super::{Args, USAGE, Address, run_call};
 	use crate::{
 		display::std_json::tests::informant,
@@ -527,6 +640,27 @@ mod tests {
 		assert_eq!(args.flag_std_err_only, true);
 	}
 
+	#[test]
+	fn should_parse_t8n_command() {
+		let args = run(&[
+			"parity-evm",
+			"t8n",
+			"--input-alloc", "./alloc.json",
+			"--input-env", "./env.json",
+			"--input-txs", "./txs.json",
+			"--eip3155",
+		]);
+
+		assert_eq!(args.cmd_t8n, true);
+		assert_eq!(args.flag_input_alloc, Some("./alloc.json".into()));
+		assert_eq!(args.flag_input_env, Some("./env.json".into()));
+		assert_eq!(args.flag_input_txs, Some("./txs.json".into()));
+		assert_eq!(args.flag_state_fork, "Istanbul");
+		assert_eq!(args.flag_output_alloc, PathBuf::from("alloc.json"));
+		assert_eq!(args.flag_output_result, PathBuf::from("result.json"));
+		assert_eq!(args.flag_eip3155, true);
+	}
+
 	#[test]
 	#[should_panic]
 	fn should_not_parse_only_flag_without_state_test() {
@@ -0,0 +1,276 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! State transition tool (`t8n`): applies a list of transactions on top of a pre-state
+//! and reports the post-state together with the receipts.
+
+use std::io;
+use std::time::Instant;
+
+use common_types::{
+	log_entry::LogEntry,
+	receipt::{Receipt, TransactionOutcome},
+	transaction::{SignedTransaction, UnverifiedTransaction},
+};
+use ethcore::test_helpers::{EvmTestClient, EvmTestError, TransactSuccess, TrieSpec};
+use ethereum_types::{Address, Bloom, H256, U256};
+use pod::PodState;
+use rustc_hex::ToHex;
+use serde::Serialize;
+use trace;
+use triehash_ethereum::ordered_trie_root;
+use vm::EnvInfo;
+
+use crate::display::eip3155;
+
+/// Result of a state transition.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransitionResult {
+	/// State root after all transactions were applied.
+	pub state_root: H256,
+	/// Root of the applied transactions.
+	pub tx_root: H256,
+	/// Root of the receipts of the applied transactions.
+	pub receipts_root: H256,
+	/// Bloom of all logs.
+	pub logs_bloom: Bloom,
+	/// Receipts of the applied transactions.
+	pub receipts: Vec<TransitionReceipt>,
+	/// Transactions which could not be applied.
+	pub rejected: Vec<RejectedTransaction>,
+	/// Gas used by all applied transactions.
+	pub gas_used: U256,
+}
+
+/// Receipt of an applied transaction.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransitionReceipt {
+	/// Hash of the transaction.
+	pub transaction_hash: H256,
+	/// Index of the transaction in the input.
+	pub transaction_index: usize,
+	/// State root after the transaction, before EIP-658.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub root: Option<H256>,
+	/// Status code of the transaction, since EIP-658.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub status: Option<U256>,
+	/// Gas used by this and all preceding transactions.
+	pub cumulative_gas_used: U256,
+	/// Gas used by the transaction.
+	pub gas_used: U256,
+	/// Bloom of the logs of the transaction.
+	pub logs_bloom: Bloom,
+	/// Logs of the transaction.
+	pub logs: Vec<TransitionLog>,
+	/// Address of the created contract, if any.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub contract_address: Option<Address>,
+}
+
+/// Log emitted by an applied transaction.
+#[derive(Debug, Serialize)]
+pub struct TransitionLog {
+	/// Address of the emitting contract.
+	pub address: Address,
+	/// Topics.
+	pub topics: Vec<H256>,
+	/// Data as hex.
+	pub data: String,
+}
+
+impl From<LogEntry> for TransitionLog {
+	fn from(log: LogEntry) -> Self {
+		TransitionLog {
+			address: log.address,
+			topics: log.topics,
+			data: format!("0x{}", log.data.to_hex()),
+		}
+	}
+}
+
+/// Transaction which could not be applied.
+#[derive(Debug, Serialize)]
+pub struct RejectedTransaction {
+	/// Index of the transaction in the input.
+	pub index: usize,
+	/// Reason of the rejection.
+	pub error: String,
+}
+
+/// Effects of an applied transaction.
+struct Applied {
+	gas_used: U256,
+	outcome: TransactionOutcome,
+	logs: Vec<LogEntry>,
+	contract_address: Option<Address>,
+}
+
+/// Applies `txs` in the block described by `env` on top of `alloc`, returning the post-state
+/// and the result of the transition. Every transaction is traced to stderr in the EIP-3155
+/// format if `trace_steps` is set.
+pub fn run(
+	spec: &spec::Spec,
+	alloc: PodState,
+	env: &EnvInfo,
+	txs: Vec<ethjson::transaction::Transaction>,
+	trace_steps: bool,
+) -> Result<(PodState, TransitionResult), EvmTestError> {
+	let mut client = EvmTestClient::from_pod_state_with_trie(spec, alloc, TrieSpec::Fat)?;
+	let mut env = env.clone();
+	let mut result = TransitionResult::default();
+	let mut transactions = Vec::new();
+	let mut receipts = Vec::new();
+
+	for (index, tx) in txs.into_iter().enumerate() {
+		let tx = match sign(tx) {
+			Ok(tx) => tx,
+			Err(error) => {
+				result.rejected.push(RejectedTransaction { index, error });
+				continue;
+			},
+		};
+
+		let applied = if trace_steps {
+			transact(&mut client, &env, tx.clone(), eip3155::Informant::err_only(), true)
+		} else {
+			transact(&mut client, &env, tx.clone(), trace::NoopVMTracer, false)
+		};
+
+		match applied {
+			Ok(applied) => {
+				env.gas_used = env.gas_used + applied.gas_used;
+				let receipt = Receipt::new(applied.outcome, env.gas_used, applied.logs).with_tx_type(tx.tx_type());
+				result.logs_bloom.accrue_bloom(&receipt.log_bloom);
+				result.receipts.push(TransitionReceipt {
+					transaction_hash: tx.hash(),
+					transaction_index: index,
+					root: match receipt.outcome {
+						TransactionOutcome::StateRoot(root) => Some(root),
+						_ => None,
+					},
+					status: match receipt.outcome {
+						TransactionOutcome::StatusCode(status) => Some(status.into()),
+						_ => None,
+					},
+					cumulative_gas_used: receipt.gas_used,
+					gas_used: applied.gas_used,
+					logs_bloom: receipt.log_bloom,
+					logs: receipt.logs.iter().cloned().map(Into::into).collect(),
+					contract_address: applied.contract_address,
+				});
+				transactions.push(tx);
+				receipts.push(receipt);
+			},
+			Err(error) => result.rejected.push(RejectedTransaction { index, error }),
+		}
+	}
+
+	result.state_root = *client.state().root();
+	result.tx_root = ordered_trie_root(transactions.iter().map(|tx| tx.encode_raw()));
+	result.receipts_root = ordered_trie_root(receipts.iter().map(|receipt| receipt.encode_raw()));
+	result.gas_used = env.gas_used;
+
+	let post = client.state().to_pod_full()?;
+	Ok((post, result))
+}
+
+/// Signs the transaction with its secret key if present, otherwise recovers the sender
+/// from its signature.
+fn sign(tx: ethjson::transaction::Transaction) -> Result<SignedTransaction, String> {
+	if tx.secret.is_some() {
+		Ok(tx.into())
+	} else {
+		SignedTransaction::new(UnverifiedTransaction::from(tx)).map_err(|e| e.to_string())
+	}
+}
+
+fn transact<V: trace::VMTracer>(
+	client: &mut EvmTestClient,
+	env: &EnvInfo,
+	tx: SignedTransaction,
+	vm_tracer: V,
+	summary: bool,
+) -> Result<Applied, String> {
+	let gas = tx.gas;
+	let start = Instant::now();
+	let result = client.transact(env, tx, trace::NoopTracer, vm_tracer);
+	let time = start.elapsed();
+
+	match result {
+		Ok(TransactSuccess { state_root, gas_left, output, logs, outcome, contract_address, .. }) => {
+			if summary {
+				eip3155::Informant::<io::Stderr, io::Stderr>::write_summary(&mut io::stderr(), &state_root, &output, gas - gas_left, None, &time);
+			}
+			Ok(Applied { gas_used: gas - gas_left, outcome, logs, contract_address })
+		},
+		Err(err) => Err(err.error.to_string()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+	use super::*;
+
+	#[test]
+	fn should_apply_transactions_and_reject_invalid_ones() {
+		let alloc: ethjson::spec::State = serde_json::from_str(r#"{
+			"a94f5374fce5edbc8e2a8697c15331677e6ebf0b": { "balance": "0x0de0b6b3a7640000", "nonce": "0x00", "code": "0x", "storage": {} },
+			"1000000000000000000000000000000000000000": { "balance": "0x00", "nonce": "0x00", "code": "0x602a600055", "storage": {} }
+		}"#).unwrap();
+		let env: ethjson::vm::Env = serde_json::from_str(r#"{
+			"currentCoinbase": "2adc25665018aa1fe0e6bc666dac8fc2697ff9ba",
+			"currentDifficulty": "0x020000",
+			"currentGasLimit": "0x0f4240",
+			"currentNumber": "0x01",
+			"currentTimestamp": "0x03e8"
+		}"#).unwrap();
+		let txs: Vec<ethjson::transaction::Transaction> = serde_json::from_str(r#"[
+			{
+				"data": "0x", "gasLimit": "0x0186a0", "gasPrice": "0x01", "nonce": "0x00",
+				"to": "1000000000000000000000000000000000000000", "value": "0x00",
+				"secretKey": "0x45a915e4d060149eb4365960e6a7a45f334393093061116b197e3240065ff2d8"
+			},
+			{
+				"data": "0x", "gasLimit": "0x5208", "gasPrice": "0x01", "nonce": "0x05",
+				"to": "1000000000000000000000000000000000000000", "value": "0x00",
+				"secretKey": "0x45a915e4d060149eb4365960e6a7a45f334393093061116b197e3240065ff2d8"
+			},
+			{
+				"data": "0x", "gasLimit": "0x5208", "gasPrice": "0x01", "nonce": "0x01",
+				"to": "1000000000000000000000000000000000000000", "value": "0x00"
+			}
+		]"#).unwrap();
+
+		let spec = spec::new_istanbul_test();
+		let (post, result) = run(&spec, alloc.into(), &env.into(), txs, false).unwrap();
+
+		// SSTORE of a fresh slot under Istanbul: 21000 + 3 + 3 + 20000
+		assert_eq!(result.gas_used, U256::from(41_006));
+		assert_eq!(result.receipts.len(), 1);
+		assert_eq!(result.receipts[0].status, Some(U256::one()));
+		assert_eq!(result.receipts[0].cumulative_gas_used, U256::from(41_006));
+		assert_eq!(result.rejected.iter().map(|r| r.index).collect::<Vec<_>>(), vec![1, 2]);
+
+		let contract = Address::from_str("1000000000000000000000000000000000000000").unwrap();
+		let storage = &post.get()[&contract].storage;
+		assert_eq!(storage[&H256::zero()], H256::from_low_u64_be(0x2a));
+		assert_eq!(result.state_root, post.root());
+	}
+}
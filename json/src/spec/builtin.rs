@@ -57,6 +57,32 @@ pub struct AltBn128Pairing {
 	pub pair: u64,
 }
 
+/// Pricing for constant BLS12-381 operations (additions, multiplications and mappings to the curve)
+#[derive(Debug, PartialEq, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Bls12ConstOperations {
+	/// price
+	pub price: u64,
+}
+
+/// Pricing for BLS12-381 multiexponentiation in G1 or G2.
+#[derive(Debug, PartialEq, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Bls12Multiexp {
+	/// Price of a single multiplication, discounted depending on the number of point-scalar pairs.
+	pub base: u64,
+}
+
+/// Pricing for BLS12-381 pairing.
+#[derive(Debug, PartialEq, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Bls12Pairing {
+	/// Base price.
+	pub base: u64,
+	/// Price per point pair.
+	pub pair: u64,
+}
+
 /// Pricing variants.
 #[derive(Debug, PartialEq, Deserialize, Clone)]
 #[serde(deny_unknown_fields)]
@@ -75,6 +101,14 @@ pub enum Pricing {
 	AltBn128Pairing(AltBn128Pairing),
 	/// Pricing for constant alt_bn128 operations
 	AltBn128ConstOperations(AltBn128ConstOperations),
+	/// Pricing for constant BLS12-381 operations
+	Bls12ConstOperations(Bls12ConstOperations),
+	/// Pricing for BLS12-381 multiexponentiation in G1
+	Bls12G1Multiexp(Bls12Multiexp),
+	/// Pricing for BLS12-381 multiexponentiation in G2
+	Bls12G2Multiexp(Bls12Multiexp),
+	/// Pricing for BLS12-381 pairing
+	Bls12Pairing(Bls12Pairing),
 }
 
 /// Builtin compability layer
@@ -139,7 +173,10 @@ pub struct PricingAt {
 
 #[cfg(test)]
 mod tests {
-	use super::{Builtin, BuiltinCompat, BTreeMap, Pricing, PricingAt, Linear, Modexp, AltBn128ConstOperations};
+	use super::{
+		Builtin, BuiltinCompat, BTreeMap, Pricing, PricingAt, Linear, Modexp, AltBn128ConstOperations,
+		Bls12Multiexp, Bls12Pairing,
+	};
 	use macros::map;
 
 	#[test]
@@ -225,6 +262,34 @@ mod tests {
 		]);
 	}
 
+	#[test]
+	fn deserialization_bls12_pricing() {
+		let s = r#"{
+			"name": "bls12_381_g2_multiexp",
+			"pricing": { "bls12_g2_multiexp": { "base": 55000 } }
+		}"#;
+		let builtin: Builtin = serde_json::from_str::<BuiltinCompat>(s).unwrap().into();
+		assert_eq!(builtin.name, "bls12_381_g2_multiexp");
+		assert_eq!(builtin.pricing, map![
+			0 => PricingAt {
+				info: None,
+				price: Pricing::Bls12G2Multiexp(Bls12Multiexp { base: 55000 })
+			}
+		]);
+
+		let s = r#"{
+			"name": "bls12_381_pairing",
+			"pricing": { "bls12_pairing": { "base": 115000, "pair": 23000 } }
+		}"#;
+		let builtin: Builtin = serde_json::from_str::<BuiltinCompat>(s).unwrap().into();
+		assert_eq!(builtin.pricing, map![
+			0 => PricingAt {
+				info: None,
+				price: Pricing::Bls12Pairing(Bls12Pairing { base: 115000, pair: 23000 })
+			}
+		]);
+	}
+
 	#[test]
 	fn activate_at() {
 		let s = r#"{
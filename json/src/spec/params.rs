@@ -109,6 +109,28 @@ pub struct Params {
 	/// See `CommonParams` docs.
 	pub eip2200_advance_transition: Option<Uint>,
 	/// See `CommonParams` docs.
+	pub eip2929_transition: Option<Uint>,
+	/// See `CommonParams` docs.
+	pub eip2930_transition: Option<Uint>,
+	/// See `CommonParams` docs.
+	pub eip1559_transition: Option<Uint>,
+	/// See `CommonParams` docs.
+	pub eip1559_base_fee_max_change_denominator: Option<Uint>,
+	/// See `CommonParams` docs.
+	pub eip1559_elasticity_multiplier: Option<Uint>,
+	/// See `CommonParams` docs.
+	pub eip1559_base_fee_initial_value: Option<Uint>,
+	/// See `CommonParams` docs.
+	pub eip3198_transition: Option<Uint>,
+	/// See `CommonParams` docs.
+	pub eip3529_transition: Option<Uint>,
+	/// See `CommonParams` docs.
+	pub eip3541_transition: Option<Uint>,
+	/// See `CommonParams` docs.
+	pub eip3855_transition: Option<Uint>,
+	/// See `CommonParams` docs.
+	pub eip3860_transition: Option<Uint>,
+	/// See `CommonParams` docs.
 	pub dust_protection_transition: Option<Uint>,
 	/// See `CommonParams` docs.
 	pub nonce_cap_increment: Option<Uint>,
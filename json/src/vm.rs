@@ -117,6 +117,9 @@ pub struct Env {
 	/// Timestamp.
 	#[serde(rename = "currentTimestamp")]
 	pub timestamp: Uint,
+	/// Base fee.
+	#[serde(rename = "currentBaseFee")]
+	pub base_fee: Option<Uint>,
 }
 
 #[cfg(test)]
@@ -186,7 +189,8 @@ mod tests {
 			difficulty: Uint(0x0100.into()),
 			gas_limit: Uint(0x0f4240.into()),
 			number: Uint(0.into()),
-			timestamp: Uint(1.into())
+			timestamp: Uint(1.into()),
+			base_fee: None,
 		});
 		assert_eq!(vm.transaction, Transaction {
 			address: Address(Hash160::from_str("0f572e5295c57f15886f9b263e2f6d2d6c7b5ec6").unwrap()),
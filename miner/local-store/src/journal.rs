@@ -0,0 +1,327 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! On-disk journal of all the transactions in the queue, so that the pool
+//! doesn't start empty after a restart.
+//!
+//! The journal is a single RLP list of `[first_seen, transaction]` entries, where
+//! `first_seen` is the unix timestamp at which the transaction got journaled first.
+//! It is rewritten with the current contents of the queue periodically and on shutdown.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use common_types::transaction::{SignedTransaction, UnverifiedTransaction};
+use ethcore_io::{IoHandler, TimerToken, IoContext};
+use ethereum_types::H256;
+use log::{debug, trace, warn};
+use parking_lot::Mutex;
+use rlp::{Rlp, RlpStream};
+
+const ROTATE_TIMER: TimerToken = 0;
+
+/// Journal settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+	/// How often the journal is rewritten with the current contents of the queue.
+	pub rotation_interval: Duration,
+	/// Transactions journaled earlier than that are not reloaded.
+	pub max_age: Duration,
+	/// Maximal number of transactions reloaded.
+	pub max_count: usize,
+}
+
+impl Default for Options {
+	fn default() -> Self {
+		Options {
+			rotation_interval: Duration::from_secs(60 * 60),
+			max_age: Duration::from_secs(3 * 60 * 60),
+			max_count: 8_192,
+		}
+	}
+}
+
+/// Something which can provide the transactions of the queue.
+pub trait PoolInfo: Send + Sync {
+	/// Get all the transactions in the queue which should be journaled.
+	fn queued_transactions(&self) -> Vec<SignedTransaction>;
+}
+
+/// Open the journal at given path. Nothing is read until `load` is called.
+pub fn open<T: PoolInfo>(path: PathBuf, options: Options, pool: T) -> TransactionJournal<T> {
+	TransactionJournal {
+		path,
+		options,
+		pool,
+		first_seen: Mutex::new(HashMap::new()),
+	}
+}
+
+/// Journal of the transaction queue.
+pub struct TransactionJournal<T: PoolInfo> {
+	path: PathBuf,
+	options: Options,
+	pool: T,
+	/// Journaling time of the transactions, preserved across rotations.
+	first_seen: Mutex<HashMap<H256, u64>>,
+}
+
+impl<T: PoolInfo> TransactionJournal<T> {
+	/// Read transactions out of the journal, skipping the malformed and expired ones.
+	///
+	/// At most `max_count` transactions are returned. They are not verified in any way
+	/// and should be imported to the queue as external transactions.
+	pub fn load(&self) -> io::Result<Vec<UnverifiedTransaction>> {
+		let bytes = match fs::read(&self.path) {
+			Ok(bytes) => bytes,
+			Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+			Err(e) => return Err(e),
+		};
+
+		let rlp = Rlp::new(&bytes);
+		if !rlp.is_list() {
+			return Err(io::Error::new(io::ErrorKind::InvalidData, "Transaction journal is not an RLP list"));
+		}
+
+		let oldest = unix_now().saturating_sub(self.options.max_age.as_secs());
+		let mut first_seen = self.first_seen.lock();
+		let mut transactions = Vec::new();
+		let (mut expired, mut dropped) = (0, 0);
+
+		for entry in rlp.iter() {
+			let (timestamp, tx) = match decode_entry(&entry) {
+				Ok(entry) => entry,
+				Err(e) => {
+					warn!(target: "local_store", "Invalid journaled transaction: {}", e);
+					continue
+				}
+			};
+
+			if timestamp < oldest {
+				expired += 1;
+			} else if transactions.len() >= self.options.max_count {
+				dropped += 1;
+			} else {
+				first_seen.insert(tx.hash(), timestamp);
+				transactions.push(tx);
+			}
+		}
+
+		debug!(target: "local_store", "Loaded {} journaled transactions, {} expired, {} over the limit.", transactions.len(), expired, dropped);
+		Ok(transactions)
+	}
+
+	/// Rewrite the journal with the current contents of the queue.
+	pub fn rotate(&self) -> io::Result<()> {
+		trace!(target: "local_store", "Rotating transaction journal.");
+
+		let now = unix_now();
+		let transactions = self.pool.queued_transactions();
+		let mut first_seen = self.first_seen.lock();
+		let mut journaled = HashMap::with_capacity(transactions.len());
+
+		let mut stream = RlpStream::new_list(transactions.len());
+		for tx in &transactions {
+			let timestamp = first_seen.get(&tx.hash()).cloned().unwrap_or(now);
+			journaled.insert(tx.hash(), timestamp);
+			stream.begin_list(2).append(&timestamp).append(tx);
+		}
+		*first_seen = journaled;
+
+		// write the new journal aside first, so that it's never left half-written.
+		let tmp_path = self.path.with_extension("tmp");
+		fs::write(&tmp_path, stream.out())?;
+		fs::rename(&tmp_path, &self.path)
+	}
+
+	/// Remove the journal.
+	pub fn clear(&self) -> io::Result<()> {
+		trace!(target: "local_store", "Clearing transaction journal.");
+
+		self.first_seen.lock().clear();
+		match fs::remove_file(&self.path) {
+			Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+			result => result,
+		}
+	}
+}
+
+fn decode_entry(rlp: &Rlp) -> Result<(u64, UnverifiedTransaction), rlp::DecoderError> {
+	if rlp.item_count()? != 2 {
+		return Err(rlp::DecoderError::RlpIncorrectListLen);
+	}
+
+	Ok((rlp.val_at(0)?, rlp.val_at(1)?))
+}
+
+fn unix_now() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+impl<T: PoolInfo, M: Send + Sync + 'static> IoHandler<M> for TransactionJournal<T> {
+	fn initialize(&self, io: &IoContext<M>) {
+		if let Err(e) = io.register_timer(ROTATE_TIMER, self.options.rotation_interval) {
+			warn!(target: "local_store", "Error registering transaction journal rotation timer: {}", e);
+		}
+	}
+
+	fn timeout(&self, _io: &IoContext<M>, timer: TimerToken) {
+		if let ROTATE_TIMER = timer {
+			if let Err(e) = self.rotate() {
+				debug!(target: "local_store", "Error rotating transaction journal: {}", e);
+			}
+		}
+	}
+}
+
+impl<T: PoolInfo> Drop for TransactionJournal<T> {
+	fn drop(&mut self) {
+		debug!(target: "local_store", "Flushing transaction journal on shutdown.");
+
+		if let Err(e) = self.rotate() {
+			warn!(target: "local_store", "Error flushing transaction journal: {}", e);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{Options, PoolInfo};
+
+	use std::fs;
+	use std::time::Duration;
+	use common_types::transaction::{Transaction, SignedTransaction, UnverifiedTransaction};
+	use ethkey::Brain;
+	use parity_crypto::publickey::Generator;
+	use tempdir::TempDir;
+
+	struct Dummy(Vec<SignedTransaction>);
+	impl PoolInfo for Dummy {
+		fn queued_transactions(&self) -> Vec<SignedTransaction> { self.0.clone() }
+	}
+
+	fn transactions(count: u64) -> Vec<SignedTransaction> {
+		let keypair = Brain::new("abcd".into()).generate().unwrap();
+		(0..count).map(|nonce| {
+			let mut tx = Transaction::default();
+			tx.nonce = nonce.into();
+			tx.sign(keypair.secret(), None)
+		}).collect()
+	}
+
+	fn unverified(txs: Vec<SignedTransaction>) -> Vec<UnverifiedTransaction> {
+		txs.into_iter().map(Into::into).collect()
+	}
+
+	#[test]
+	fn empty_without_journal() {
+		let dir = TempDir::new("").unwrap();
+		let journal = super::open(dir.path().join("transactions.rlp"), Options::default(), Dummy(vec![]));
+		assert_eq!(journal.load().unwrap(), vec![]);
+	}
+
+	#[test]
+	fn flushed_on_drop_and_reloaded() {
+		let dir = TempDir::new("").unwrap();
+		let path = dir.path().join("transactions.rlp");
+		let transactions = transactions(10);
+
+		{
+			// nothing written yet, will flush the queue.
+			let journal = super::open(path.clone(), Options::default(), Dummy(transactions.clone()));
+			assert_eq!(journal.load().unwrap(), vec![]);
+		}
+		{
+			// queue flushed, will flush an empty queue.
+			let journal = super::open(path.clone(), Options::default(), Dummy(vec![]));
+			assert_eq!(journal.load().unwrap(), unverified(transactions));
+		}
+		{
+			let journal = super::open(path.clone(), Options::default(), Dummy(vec![]));
+			assert_eq!(journal.load().unwrap(), vec![]);
+		}
+	}
+
+	#[test]
+	fn limits_number_of_reloaded_transactions() {
+		let dir = TempDir::new("").unwrap();
+		let path = dir.path().join("transactions.rlp");
+		let transactions = transactions(10);
+
+		super::open(path.clone(), Options::default(), Dummy(transactions.clone())).rotate().unwrap();
+
+		let options = Options { max_count: 4, ..Options::default() };
+		let journal = super::open(path, options, Dummy(vec![]));
+		assert_eq!(journal.load().unwrap(), unverified(transactions[..4].to_vec()));
+	}
+
+	#[test]
+	fn skips_expired_transactions() {
+		let dir = TempDir::new("").unwrap();
+		let path = dir.path().join("transactions.rlp");
+
+		let old = transactions(3);
+		{
+			let journal = super::open(path.clone(), Options::default(), Dummy(old.clone()));
+			journal.rotate().unwrap();
+			// pretend the transactions were journaled long ago.
+			for timestamp in journal.first_seen.lock().values_mut() {
+				*timestamp -= 2 * 60 * 60;
+			}
+		}
+
+		// both journals are loaded before either of them gets flushed on drop.
+		let short = Options { max_age: Duration::from_secs(60 * 60), ..Options::default() };
+		let long = Options { max_age: Duration::from_secs(3 * 60 * 60), ..Options::default() };
+		let expiring = super::open(path.clone(), short, Dummy(vec![]));
+		let keeping = super::open(path, long, Dummy(vec![]));
+
+		assert_eq!(expiring.load().unwrap(), vec![]);
+		assert_eq!(keeping.load().unwrap(), unverified(old));
+	}
+
+	#[test]
+	fn keeps_first_seen_across_rotations() {
+		let dir = TempDir::new("").unwrap();
+		let path = dir.path().join("transactions.rlp");
+		let transactions = transactions(2);
+
+		let journal = super::open(path.clone(), Options::default(), Dummy(transactions.clone()));
+		journal.rotate().unwrap();
+		let first = journal.first_seen.lock().clone();
+		*journal.first_seen.lock().get_mut(&transactions[0].hash()).unwrap() -= 100;
+		journal.rotate().unwrap();
+
+		let second = journal.first_seen.lock().clone();
+		assert_eq!(second[&transactions[0].hash()], first[&transactions[0].hash()] - 100);
+		assert_eq!(second[&transactions[1].hash()], first[&transactions[1].hash()]);
+	}
+
+	#[test]
+	fn rejects_corrupted_journal() {
+		let dir = TempDir::new("").unwrap();
+		let path = dir.path().join("transactions.rlp");
+		fs::write(&path, b"\x01\x02\x03").unwrap();
+
+		let journal = super::open(path, Options::default(), Dummy(vec![]));
+		assert!(journal.load().is_err());
+		journal.clear().unwrap();
+		assert_eq!(journal.load().unwrap(), vec![]);
+	}
+}
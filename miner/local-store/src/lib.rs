@@ -34,6 +34,8 @@ use rlp::Rlp;
 use serde_derive::{Serialize, Deserialize};
 use serde_json;
 
+pub mod journal;
+
 const LOCAL_TRANSACTIONS_KEY: &'static [u8] = &*b"LOCAL_TXS";
 
 const UPDATE_TIMER: TimerToken = 0;
@@ -0,0 +1,352 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Bundles of transactions which are included at the top of a specific block
+//! atomically, or not at all.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+
+use ethereum_types::{H256, U256, Address};
+use hash::keccak;
+use parking_lot::RwLock;
+use types::BlockNumber;
+use types::transaction::SignedTransaction;
+
+/// Maximal number of blocks after the best block a bundle can target.
+pub const MAX_BLOCKS_AHEAD: BlockNumber = 25;
+
+/// Ordered group of transactions targeting a single block.
+///
+/// Bundles are submitted with unverified transactions, which are verified before the bundle is imported to the pool.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bundle<T = SignedTransaction> {
+	/// Transactions in the order they are executed in.
+	pub transactions: Vec<T>,
+	/// Number of the only block the bundle can be included in.
+	pub block_number: BlockNumber,
+	/// Minimal timestamp of the block the bundle can be included in.
+	pub min_timestamp: Option<u64>,
+	/// Maximal timestamp of the block the bundle can be included in.
+	pub max_timestamp: Option<u64>,
+	/// Transactions which may revert without invalidating the whole bundle.
+	pub reverting_hashes: HashSet<H256>,
+}
+
+impl Bundle {
+	/// Hash identifying the bundle.
+	pub fn hash(&self) -> H256 {
+		hash(&self.transactions)
+	}
+
+	/// Checks if the bundle can be included in a block with given number and timestamp.
+	pub fn is_includable(&self, block_number: BlockNumber, timestamp: u64) -> bool {
+		self.block_number == block_number
+			&& self.min_timestamp.map_or(true, |min| timestamp >= min)
+			&& self.max_timestamp.map_or(true, |max| timestamp <= max)
+	}
+}
+
+/// Computes a bundle hash: keccak of the concatenated hashes of its transactions.
+pub fn hash(transactions: &[SignedTransaction]) -> H256 {
+	let hashes = transactions.iter()
+		.flat_map(|tx| tx.hash().as_bytes().to_vec())
+		.collect::<Vec<_>>();
+	keccak(hashes)
+}
+
+/// Bundle errors.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+	/// Bundle has no transactions.
+	Empty,
+	/// Bundle targets a block which has already been imported.
+	Outdated {
+		/// Block targeted by the bundle.
+		block_number: BlockNumber,
+		/// Current best block.
+		best_block: BlockNumber,
+	},
+	/// Bundle targets a block too far after the best block.
+	TooFarAhead {
+		/// Block targeted by the bundle.
+		block_number: BlockNumber,
+		/// Current best block.
+		best_block: BlockNumber,
+	},
+	/// Bundle has already been submitted.
+	AlreadyImported,
+	/// Maximal number of bundles kept in the pool has been reached.
+	LimitReached,
+	/// Maximal number of bundles targeting the block has been reached.
+	BlockLimitReached(BlockNumber),
+	/// One of the transactions can't be included in the block.
+	Transaction(H256, String),
+	/// Block to simulate the bundle in couldn't be prepared.
+	Block(String),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			Error::Empty => write!(f, "Bundle has no transactions"),
+			Error::Outdated { block_number, best_block } =>
+				write!(f, "Bundle targets block #{}, but the best block is already #{}", block_number, best_block),
+			Error::TooFarAhead { block_number, best_block } =>
+				write!(f, "Bundle targets block #{}, more than {} blocks after the best block #{}", block_number, MAX_BLOCKS_AHEAD, best_block),
+			Error::AlreadyImported => write!(f, "Bundle has already been submitted"),
+			Error::LimitReached => write!(f, "Too many pending bundles"),
+			Error::BlockLimitReached(block_number) => write!(f, "Too many pending bundles for block #{}", block_number),
+			Error::Transaction(ref hash, ref err) => write!(f, "Transaction {:?} can't be included: {}", hash, err),
+			Error::Block(ref err) => write!(f, "Unable to prepare block: {}", err),
+		}
+	}
+}
+
+/// Outcome of a transaction executed as part of a bundle.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulatedTransaction {
+	/// Transaction hash.
+	pub hash: H256,
+	/// Transaction sender.
+	pub sender: Address,
+	/// Transaction recipient, `None` for contract creation.
+	pub to: Option<Address>,
+	/// Gas used by the transaction.
+	pub gas_used: U256,
+	/// Gas price paid by the transaction.
+	pub gas_price: U256,
+	/// Fees paid to the block author for the gas used.
+	pub gas_fees: U256,
+	/// Increase of the block author's balance, including the fees.
+	pub coinbase_diff: U256,
+	/// Whether the transaction has reverted.
+	pub reverted: bool,
+}
+
+/// Outcome of a bundle executed on top of some state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Simulation {
+	/// Hash of the simulated bundle.
+	pub bundle_hash: H256,
+	/// Outcomes of all the transactions of the bundle.
+	pub transactions: Vec<SimulatedTransaction>,
+}
+
+impl Simulation {
+	/// Total gas used by the bundle.
+	pub fn gas_used(&self) -> U256 {
+		self.transactions.iter().fold(U256::zero(), |sum, tx| sum + tx.gas_used)
+	}
+
+	/// Total fees paid to the block author for the gas used.
+	pub fn gas_fees(&self) -> U256 {
+		self.transactions.iter().fold(U256::zero(), |sum, tx| sum + tx.gas_fees)
+	}
+
+	/// Total increase of the block author's balance.
+	pub fn coinbase_diff(&self) -> U256 {
+		self.transactions.iter().fold(U256::zero(), |sum, tx| sum + tx.coinbase_diff)
+	}
+
+	/// Payment to the block author per unit of gas, used to order bundles.
+	pub fn score(&self) -> U256 {
+		let gas_used = self.gas_used();
+		if gas_used.is_zero() {
+			U256::zero()
+		} else {
+			self.coinbase_diff() / gas_used
+		}
+	}
+
+	/// Checks if any transaction which is not allowed to revert has reverted.
+	pub fn has_forbidden_reverts(&self, reverting_hashes: &HashSet<H256>) -> bool {
+		self.transactions.iter().any(|tx| tx.reverted && !reverting_hashes.contains(&tx.hash))
+	}
+}
+
+/// Pool of bundles waiting for the blocks they target.
+pub struct BundlePool {
+	max_count: usize,
+	max_per_block: usize,
+	bundles: RwLock<BTreeMap<BlockNumber, Vec<Arc<Bundle>>>>,
+}
+
+impl BundlePool {
+	/// Creates new pool keeping at most `max_count` bundles and at most `max_per_block` of them for a single block.
+	pub fn new(max_count: usize, max_per_block: usize) -> Self {
+		BundlePool {
+			max_count,
+			max_per_block,
+			bundles: RwLock::new(BTreeMap::new()),
+		}
+	}
+
+	/// Checks if a bundle targeting given block could be imported, without verifying its transactions.
+	pub fn check<T>(&self, bundle: &Bundle<T>, best_block: BlockNumber) -> Result<(), Error> {
+		if bundle.transactions.is_empty() {
+			return Err(Error::Empty);
+		}
+		if bundle.block_number <= best_block {
+			return Err(Error::Outdated { block_number: bundle.block_number, best_block });
+		}
+		if bundle.block_number > best_block + MAX_BLOCKS_AHEAD {
+			return Err(Error::TooFarAhead { block_number: bundle.block_number, best_block });
+		}
+		Ok(())
+	}
+
+	/// Adds a bundle to the pool. It has to target one of the next `MAX_BLOCKS_AHEAD` blocks after `best_block`.
+	pub fn import(&self, bundle: Bundle, best_block: BlockNumber) -> Result<H256, Error> {
+		self.check(&bundle, best_block)?;
+
+		let hash = bundle.hash();
+		let mut bundles = self.bundles.write();
+		if bundles.values().map(Vec::len).sum::<usize>() >= self.max_count {
+			return Err(Error::LimitReached);
+		}
+
+		let for_block = bundles.entry(bundle.block_number).or_insert_with(Vec::new);
+		if for_block.iter().any(|b| b.hash() == hash) {
+			return Err(Error::AlreadyImported);
+		}
+		if for_block.len() >= self.max_per_block {
+			return Err(Error::BlockLimitReached(bundle.block_number));
+		}
+
+		trace!(target: "miner", "Imported bundle {:?} for block #{}", hash, bundle.block_number);
+		for_block.push(Arc::new(bundle));
+		Ok(hash)
+	}
+
+	/// Returns bundles which can be included in a block with given number and timestamp,
+	/// in the order they were submitted in.
+	pub fn pending(&self, block_number: BlockNumber, timestamp: u64) -> Vec<Arc<Bundle>> {
+		self.bundles.read()
+			.get(&block_number)
+			.map(|bundles| bundles.iter().filter(|b| b.is_includable(block_number, timestamp)).cloned().collect())
+			.unwrap_or_default()
+	}
+
+	/// Checks if there are any bundles for a block with given number.
+	pub fn has_pending(&self, block_number: BlockNumber) -> bool {
+		self.bundles.read().contains_key(&block_number)
+	}
+
+	/// Removes bundles which target `best_block` or the blocks before it.
+	pub fn cull(&self, best_block: BlockNumber) {
+		let mut bundles = self.bundles.write();
+		let remaining = bundles.split_off(&(best_block + 1));
+		*bundles = remaining;
+	}
+
+	/// Returns the number of bundles in the pool.
+	pub fn len(&self) -> usize {
+		self.bundles.read().values().map(Vec::len).sum()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use types::transaction::Transaction;
+
+	fn bundle(block_number: BlockNumber, nonces: &[u64]) -> Bundle {
+		Bundle {
+			transactions: nonces.iter().map(|nonce| Transaction {
+				nonce: (*nonce).into(),
+				..Default::default()
+			}.fake_sign(Address::from_low_u64_be(1))).collect(),
+			block_number,
+			min_timestamp: None,
+			max_timestamp: None,
+			reverting_hashes: HashSet::new(),
+		}
+	}
+
+	#[test]
+	fn should_reject_invalid_bundles() {
+		let pool = BundlePool::new(4, 2);
+
+		assert_eq!(pool.import(bundle(11, &[]), 10), Err(Error::Empty));
+		assert_eq!(pool.import(bundle(10, &[0]), 10), Err(Error::Outdated { block_number: 10, best_block: 10 }));
+		assert_eq!(pool.import(bundle(36, &[0]), 10), Err(Error::TooFarAhead { block_number: 36, best_block: 10 }));
+		assert!(pool.import(bundle(35, &[0]), 10).is_ok());
+		assert!(pool.import(bundle(11, &[0]), 10).is_ok());
+		assert_eq!(pool.import(bundle(11, &[0]), 10), Err(Error::AlreadyImported));
+		assert!(pool.import(bundle(11, &[1]), 10).is_ok());
+		assert_eq!(pool.import(bundle(11, &[2]), 10), Err(Error::BlockLimitReached(11)));
+		assert!(pool.import(bundle(12, &[0]), 10).is_ok());
+		assert_eq!(pool.import(bundle(12, &[1]), 10), Err(Error::LimitReached));
+		assert_eq!(pool.len(), 4);
+	}
+
+	#[test]
+	fn should_return_bundles_for_block() {
+		let pool = BundlePool::new(16, 16);
+		let first = bundle(11, &[0]);
+		let second = Bundle { min_timestamp: Some(100), ..bundle(11, &[1]) };
+		let third = Bundle { max_timestamp: Some(50), ..bundle(11, &[2]) };
+		for b in vec![first.clone(), second.clone(), third.clone()] {
+			pool.import(b, 10).unwrap();
+		}
+		pool.import(bundle(12, &[3]), 10).unwrap();
+
+		let pending = |timestamp| pool.pending(11, timestamp).into_iter().map(|b| (*b).clone()).collect::<Vec<_>>();
+		assert_eq!(pending(10), vec![first.clone(), third]);
+		assert_eq!(pending(100), vec![first, second]);
+		assert!(pool.has_pending(12));
+		assert!(!pool.has_pending(13));
+	}
+
+	#[test]
+	fn should_cull_outdated_bundles() {
+		let pool = BundlePool::new(16, 16);
+		pool.import(bundle(11, &[0]), 10).unwrap();
+		pool.import(bundle(12, &[0]), 10).unwrap();
+
+		pool.cull(11);
+
+		assert_eq!(pool.len(), 1);
+		assert!(!pool.has_pending(11));
+		assert!(pool.has_pending(12));
+	}
+
+	#[test]
+	fn should_score_by_coinbase_payment_per_gas() {
+		let tx = |hash: u64, coinbase_diff: u64, reverted| SimulatedTransaction {
+			hash: H256::from_low_u64_be(hash),
+			sender: Address::zero(),
+			to: None,
+			gas_used: 21_000.into(),
+			gas_price: 1.into(),
+			gas_fees: 21_000.into(),
+			coinbase_diff: coinbase_diff.into(),
+			reverted,
+		};
+		let simulation = Simulation {
+			bundle_hash: H256::zero(),
+			transactions: vec![tx(1, 21_000, false), tx(2, 105_000, true)],
+		};
+
+		assert_eq!(simulation.gas_used(), 42_000.into());
+		assert_eq!(simulation.coinbase_diff(), 126_000.into());
+		assert_eq!(simulation.score(), 3.into());
+		assert!(simulation.has_forbidden_reverts(&HashSet::new()));
+		assert!(!simulation.has_forbidden_reverts(&vec![H256::from_low_u64_be(2)].into_iter().collect()));
+	}
+}
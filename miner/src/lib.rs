@@ -54,6 +54,7 @@ extern crate parity_crypto;
 #[cfg(test)]
 extern crate env_logger;
 
+pub mod bundle;
 pub mod external;
 #[cfg(feature = "price-info")]
 pub mod gas_price_calibrator;
@@ -16,6 +16,8 @@
 
 //! Transaction Pool
 
+use std::sync::atomic::{AtomicUsize, Ordering};
+
 use ethereum_types::{U256, H256, Address};
 use parity_util_mem::MallocSizeOfExt;
 use types::transaction;
@@ -119,8 +121,42 @@ pub trait ScoredTransaction {
 
 	/// Gets transaction nonce.
 	fn nonce(&self) -> U256;
+
+	/// Gets the fee per gas paid to the block author given the block base fee.
+	fn effective_priority_fee(&self, block_base_fee: Option<U256>) -> U256;
+
+	/// Gets the number of times the transaction has been penalized.
+	fn penalties(&self) -> usize;
+
+	/// Penalizes the transaction once more.
+	fn penalize(&self);
+}
+
+/// Number of times a transaction has been penalized,
+/// kept with the transaction so that recomputing its score doesn't undo it.
+#[derive(Debug, Default)]
+struct Penalties(AtomicUsize);
+
+impl Penalties {
+	fn get(&self) -> usize {
+		self.0.load(Ordering::Relaxed)
+	}
+}
+
+impl Clone for Penalties {
+	fn clone(&self) -> Self {
+		Penalties(AtomicUsize::new(self.get()))
+	}
 }
 
+impl PartialEq for Penalties {
+	fn eq(&self, other: &Self) -> bool {
+		self.get() == other.get()
+	}
+}
+
+impl Eq for Penalties {}
+
 /// Verified transaction stored in the pool.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VerifiedTransaction {
@@ -130,6 +166,7 @@ pub struct VerifiedTransaction {
 	sender: Address,
 	priority: Priority,
 	insertion_id: usize,
+	penalties: Penalties,
 }
 
 impl VerifiedTransaction {
@@ -147,6 +184,7 @@ impl VerifiedTransaction {
 			sender,
 			priority: Priority::Retracted,
 			insertion_id: 0,
+			penalties: Default::default(),
 		}
 	}
 
@@ -198,6 +236,19 @@ impl ScoredTransaction for VerifiedTransaction {
 	fn nonce(&self) -> U256 {
 		self.transaction.nonce
 	}
+
+	/// Gets the fee per gas paid to the block author given the block base fee.
+	fn effective_priority_fee(&self, block_base_fee: Option<U256>) -> U256 {
+		self.transaction.effective_priority_fee(block_base_fee)
+	}
+
+	fn penalties(&self) -> usize {
+		self.penalties.get()
+	}
+
+	fn penalize(&self) {
+		self.penalties.0.fetch_add(1, Ordering::Relaxed);
+	}
 }
 
 /// Pool transactions status
@@ -204,6 +204,7 @@ pub struct TransactionQueue {
 	options: RwLock<verifier::Options>,
 	cached_pending: RwLock<CachedPending>,
 	recently_rejected: RecentlyRejected,
+	block_base_fee: Arc<RwLock<Option<U256>>>,
 }
 
 impl TransactionQueue {
@@ -214,9 +215,11 @@ impl TransactionQueue {
 		strategy: PrioritizationStrategy,
 	) -> Self {
 		let max_count = limits.max_count;
+		let scoring = scoring::NonceAndGasPrice::new(strategy);
 		TransactionQueue {
 			insertion_id: Default::default(),
-			pool: RwLock::new(txpool::Pool::new(Default::default(), scoring::NonceAndGasPrice(strategy), limits)),
+			block_base_fee: scoring.block_base_fee.clone(),
+			pool: RwLock::new(txpool::Pool::new(Default::default(), scoring, limits)),
 			options: RwLock::new(verification_options),
 			cached_pending: RwLock::new(CachedPending::none()),
 			recently_rejected: RecentlyRejected::new(cmp::max(MIN_REJECTED_CACHE_SIZE, max_count / 4)),
@@ -268,6 +271,7 @@ impl TransactionQueue {
 			options,
 			self.insertion_id.clone(),
 			transaction_to_replace,
+			*self.block_base_fee.read(),
 		);
 
 		let mut replace = replace::ReplaceByScoreAndReadiness::new(self.pool.read().scoring().clone(), client);
@@ -527,10 +531,28 @@ impl TransactionQueue {
 	pub fn penalize<'a, T: IntoIterator<Item = &'a Address>>(&self, senders: T) {
 		let mut pool = self.pool.write();
 		for sender in senders {
-			pool.update_scores(sender, ());
+			pool.update_scores(sender, scoring::ScoringEvent::Penalize);
 		}
 	}
 
+	/// Update the base fee of the next block (`None` before EIP-1559).
+	///
+	/// Transactions are ordered by the priority fee they pay on top of the base fee,
+	/// so all scores are recomputed when it changes.
+	pub fn set_block_base_fee(&self, block_base_fee: Option<U256>) {
+		if *self.block_base_fee.read() == block_base_fee {
+			return;
+		}
+
+		let mut pool = self.pool.write();
+		*self.block_base_fee.write() = block_base_fee;
+		let senders: Vec<_> = pool.senders().cloned().collect();
+		for sender in &senders {
+			pool.update_scores(sender, scoring::ScoringEvent::BlockBaseFee);
+		}
+		self.cached_pending.write().clear();
+	}
+
 	/// Returns gas price of currently the worst transaction in the pool.
 	pub fn current_worst_gas_price(&self) -> U256 {
 		match self.pool.read().worst_transaction() {
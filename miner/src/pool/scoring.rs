@@ -26,10 +26,15 @@
 //! Transactions between senders are prioritized using `gas price`. Higher `gas price`
 //! yields more profits for miners. Additionally we prioritize transactions that originate
 //! from our local node (own transactions).
+//!
+//! After EIP-1559 the `gas price` used for prioritization is the effective priority fee
+//! (tip) the miner would receive given the base fee of the pending block.
 
 use std::cmp;
+use std::sync::Arc;
 
 use ethereum_types::U256;
+use parking_lot::RwLock;
 use txpool::{self, scoring};
 use super::{verifier, PrioritizationStrategy, VerifiedTransaction, ScoredTransaction};
 
@@ -43,14 +48,49 @@ fn bump_gas_price(old_gp: U256) -> U256 {
 	old_gp.saturating_add(old_gp >> GAS_PRICE_BUMP_SHIFT)
 }
 
+/// Events which cause the scores of a sender's transactions to be recomputed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScoringEvent {
+	/// Lower the priority of all non-local transactions.
+	Penalize,
+	/// The base fee of the pending block has changed.
+	BlockBaseFee,
+}
+
 /// Simple, gas-price based scoring for transactions.
 ///
 /// NOTE: Currently penalization does not apply to new transactions that enter the pool.
 /// We might want to store penalization status in some persistent state.
 #[derive(Debug, Clone)]
-pub struct NonceAndGasPrice(pub PrioritizationStrategy);
+pub struct NonceAndGasPrice {
+	/// Prioritization strategy.
+	pub strategy: PrioritizationStrategy,
+	/// Base fee of the pending block (`None` before EIP-1559), shared with the queue.
+	pub block_base_fee: Arc<RwLock<Option<U256>>>,
+}
 
 impl NonceAndGasPrice {
+	/// Create new scoring with no block base fee.
+	pub fn new(strategy: PrioritizationStrategy) -> Self {
+		NonceAndGasPrice {
+			strategy,
+			block_base_fee: Default::default(),
+		}
+	}
+
+	/// Calculate the score of a transaction given the current block base fee.
+	fn score<P: ScoredTransaction>(&self, tx: &P) -> U256 {
+		let score = tx.effective_priority_fee(*self.block_base_fee.read());
+		let boost = match tx.priority() {
+			super::Priority::Local => 15,
+			super::Priority::Retracted => 10,
+			super::Priority::Regular => 0,
+		};
+		// every penalization divides the score by 8.
+		let penalty = cmp::min(tx.penalties() * 3, 255);
+		(score << boost) >> penalty
+	}
+
 	/// Decide if the transaction should even be considered into the pool (if the pool is full).
 	///
 	/// Used by Verifier to quickly reject transactions that don't have any chance to get into the pool later on,
@@ -69,7 +109,7 @@ impl NonceAndGasPrice {
 
 impl<P> txpool::Scoring<P> for NonceAndGasPrice where P: ScoredTransaction + txpool::VerifiedTransaction {
 	type Score = U256;
-	type Event = ();
+	type Event = ScoringEvent;
 
 	fn compare(&self, old: &P, other: &P) -> cmp::Ordering {
 		old.nonce().cmp(&other.nonce())
@@ -85,9 +125,16 @@ impl<P> txpool::Scoring<P> for NonceAndGasPrice where P: ScoredTransaction + txp
 
 		let min_required_gp = bump_gas_price(*old_gp);
 
-		match min_required_gp.cmp(&new_gp) {
-			cmp::Ordering::Greater => scoring::Choice::RejectNew,
-			_ => scoring::Choice::ReplaceOld,
+		// EIP-1559 replacements must bump both the max fee and the priority fee.
+		let old_tip = old.effective_priority_fee(None);
+		let new_tip = new.effective_priority_fee(None);
+
+		let min_required_tip = bump_gas_price(old_tip);
+
+		if min_required_gp > *new_gp || min_required_tip > new_tip {
+			scoring::Choice::RejectNew
+		} else {
+			scoring::Choice::ReplaceOld
 		}
 	}
 
@@ -101,24 +148,24 @@ impl<P> txpool::Scoring<P> for NonceAndGasPrice where P: ScoredTransaction + txp
 				assert!(i < txs.len());
 				assert!(i < scores.len());
 
-				scores[i] = *txs[i].transaction.gas_price();
-				let boost = match txs[i].priority() {
-					super::Priority::Local => 15,
-					super::Priority::Retracted => 10,
-					super::Priority::Regular => 0,
-				};
-				scores[i] = scores[i] << boost;
+				scores[i] = self.score(&*txs[i].transaction);
 			},
-			// We are only sending an event in case of penalization.
-			// So just lower the priority of all non-local transactions.
-			Change::Event(_) => {
+			// Lower the priority of all non-local transactions.
+			Change::Event(ScoringEvent::Penalize) => {
 				for (score, tx) in scores.iter_mut().zip(txs) {
 					// Never penalize local transactions.
 					if !tx.priority().is_local() {
+						tx.penalize();
 						*score = *score >> 3;
 					}
 				}
 			},
+			// Effective priority fees depend on the base fee, so recompute all scores, keeping the penalties.
+			Change::Event(ScoringEvent::BlockBaseFee) => {
+				for (score, tx) in scores.iter_mut().zip(txs) {
+					*score = self.score(&*tx.transaction);
+				}
+			},
 		}
 	}
 
@@ -138,7 +185,7 @@ mod tests {
 	#[test]
 	fn should_calculate_score_correctly() {
 		// given
-		let scoring = NonceAndGasPrice(PrioritizationStrategy::GasPriceOnly);
+		let scoring = NonceAndGasPrice::new(PrioritizationStrategy::GasPriceOnly);
 		let (tx1, tx2, tx3) = Tx::default().signed_triple();
 		let transactions = vec![tx1, tx2, tx3].into_iter().enumerate().map(|(i, tx)| {
 			let mut verified = tx.verified();
@@ -184,7 +231,36 @@ mod tests {
 		assert_eq!(scores, vec![32768.into(), 1024.into(), 1.into()]);
 
 		// Check penalization
-		scoring.update_scores(&transactions, &mut *scores, scoring::Change::Event(()));
+		scoring.update_scores(&transactions, &mut *scores, scoring::Change::Event(ScoringEvent::Penalize));
+		assert_eq!(scores, vec![32768.into(), 128.into(), 0.into()]);
+
+		// Penalization is kept when scores are recomputed
+		scoring.update_scores(&transactions, &mut *scores, scoring::Change::Event(ScoringEvent::BlockBaseFee));
 		assert_eq!(scores, vec![32768.into(), 128.into(), 0.into()]);
 	}
+
+	#[test]
+	fn should_score_by_effective_priority_fee() {
+		// given
+		let scoring = NonceAndGasPrice::new(PrioritizationStrategy::GasPriceOnly);
+		let legacy = Tx::gas_price(10).signed();
+		let dynamic_fee = Tx::gas_price(10).signed_eip1559(3);
+		let transactions = vec![legacy, dynamic_fee].into_iter().map(|tx| txpool::Transaction {
+			insertion_id: 0,
+			transaction: Arc::new(tx.verified()),
+		}).collect::<Vec<_>>();
+		let mut scores = vec![U256::from(0), 0.into()];
+
+		// without base fee both pay their gas price
+		scoring.update_scores(&transactions, &mut *scores, scoring::Change::InsertedAt(0));
+		scoring.update_scores(&transactions, &mut *scores, scoring::Change::InsertedAt(1));
+		assert_eq!(scores, vec![10.into(), 3.into()]);
+
+		// when
+		*scoring.block_base_fee.write() = Some(5.into());
+		scoring.update_scores(&transactions, &mut *scores, scoring::Change::Event(ScoringEvent::BlockBaseFee));
+
+		// then
+		assert_eq!(scores, vec![5.into(), 3.into()]);
+	}
 }
@@ -237,6 +237,31 @@ fn should_not_import_transaction_below_min_gas_price_threshold_if_external() {
 	assert_eq!(txq.status().status.transaction_count, 0);
 }
 
+#[test]
+fn should_not_import_transaction_below_block_base_fee() {
+	// given
+	let txq = new_queue();
+	let tx = Tx::gas_price(2);
+	txq.set_block_base_fee(Some(3.into()));
+
+	// when
+	let res = txq.import(TestClient::new(), vec![tx.signed().unverified()]);
+
+	// then
+	assert_eq!(res, vec![Err(transaction::Error::GasPriceLowerThanBaseFee {
+		gas_price: U256::from(2),
+		base_fee: U256::from(3),
+	})]);
+	assert_eq!(txq.status().status.transaction_count, 0);
+
+	// when
+	txq.set_block_base_fee(Some(2.into()));
+	let res = txq.import(TestClient::new(), vec![Tx::gas_price(2).signed().unverified()]);
+
+	// then
+	assert_eq!(res, vec![Ok(())]);
+}
+
 #[test]
 fn should_import_transaction_below_min_gas_price_threshold_if_local() {
 	// given
@@ -17,7 +17,9 @@
 use ethereum_types::{U256, H256};
 use parity_crypto::publickey::{Random, Generator};
 use rustc_hex::FromHex;
-use types::transaction::{self, Transaction, SignedTransaction, UnverifiedTransaction};
+use types::transaction::{
+	self, AccessListTx, EIP1559Tx, Transaction, TypedTransaction, SignedTransaction, UnverifiedTransaction,
+};
 
 use pool::{verifier, VerifiedTransaction};
 
@@ -51,6 +53,18 @@ impl Tx {
 		self.unsigned().sign(keypair.secret(), None)
 	}
 
+	pub fn signed_eip1559(self, max_priority_fee_per_gas: u64) -> SignedTransaction {
+		let keypair = Random.generate().unwrap();
+		let tx = TypedTransaction::EIP1559(EIP1559Tx {
+			transaction: AccessListTx {
+				transaction: self.unsigned(),
+				access_list: Default::default(),
+			},
+			max_priority_fee_per_gas: max_priority_fee_per_gas.into(),
+		});
+		tx.sign(keypair.secret(), None)
+	}
+
 	pub fn signed_pair(self) -> (SignedTransaction, SignedTransaction) {
 		let (tx1, tx2, _) = self.signed_triple();
 		(tx1, tx2)
@@ -105,7 +105,7 @@ impl Transaction {
 		}
 	}
 
-	fn transaction(&self) -> &transaction::Transaction {
+	fn transaction(&self) -> &transaction::TypedTransaction {
 		match *self {
 			Transaction::Unverified(ref tx) => &*tx,
 			Transaction::Retracted(ref tx) => &*tx,
@@ -137,6 +137,7 @@ pub struct Verifier<C, S, V> {
 	options: Options,
 	id: Arc<AtomicUsize>,
 	transaction_to_replace: Option<(S, Arc<V>)>,
+	block_base_fee: Option<U256>,
 }
 
 impl<C, S, V> Verifier<C, S, V> {
@@ -146,12 +147,14 @@ impl<C, S, V> Verifier<C, S, V> {
 		options: Options,
 		id: Arc<AtomicUsize>,
 		transaction_to_replace: Option<(S, Arc<V>)>,
+		block_base_fee: Option<U256>,
 	) -> Self {
 		Verifier {
 			client,
 			options,
 			id,
 			transaction_to_replace,
+			block_base_fee,
 		}
 	}
 }
@@ -202,6 +205,23 @@ impl<C: Client> txpool::Verifier<Transaction> for Verifier<C, ::pool::scoring::N
 			})
 		}
 
+		// Transactions from retracted blocks might get included again once the base fee drops.
+		if let Some(base_fee) = self.block_base_fee {
+			if tx.gas_price() < &base_fee && !tx.is_retracted() {
+				trace!(
+					target: "txqueue",
+					"[{:?}] Rejected tx with max fee below the base fee: {} < {}",
+					hash,
+					tx.gas_price(),
+					base_fee,
+				);
+				return Err(transaction::Error::GasPriceLowerThanBaseFee {
+					gas_price: *tx.gas_price(),
+					base_fee,
+				});
+			}
+		}
+
 		let is_own = tx.is_local();
 		// Quick exit for non-service and non-local transactions
 		//
@@ -338,6 +358,7 @@ impl<C: Client> txpool::Verifier<Transaction> for Verifier<C, ::pool::scoring::N
 			hash,
 			sender,
 			insertion_id: self.id.fetch_add(1, atomic::Ordering::AcqRel),
+			penalties: Default::default(),
 		})
 	}
 }
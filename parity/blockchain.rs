@@ -26,7 +26,7 @@ use hash::{keccak, KECCAK_NULL_RLP};
 use ethereum_types::{U256, H256, Address};
 use bytes::ToPretty;
 use rlp::PayloadInfo;
-use client_traits::{BlockChainReset, Nonce, Balance, BlockChainClient, ImportExportBlocks};
+use client_traits::{BlockChainReset, Nonce, Balance, BlockChainClient, ImportExportBlocks, PruneState};
 use ethcore::{
 	client::{DatabaseCompactionProfile},
 	miner::Miner,
@@ -55,7 +55,8 @@ pub enum BlockchainCmd {
 	Import(ImportBlockchain),
 	Export(ExportBlockchain),
 	ExportState(ExportState),
-	Reset(ResetBlockchain)
+	Reset(ResetBlockchain),
+	Prune(PruneBlockchain),
 }
 
 #[derive(Debug, PartialEq)]
@@ -72,6 +73,21 @@ pub struct ResetBlockchain {
 	pub num: u32,
 }
 
+#[derive(Debug, PartialEq)]
+pub struct PruneBlockchain {
+	pub dirs: Directories,
+	pub spec: SpecType,
+	pub pruning: Pruning,
+	pub pruning_history: u64,
+	pub pruning_memory: usize,
+	pub tracing: Switch,
+	pub fat_db: Switch,
+	pub compaction: DatabaseCompactionProfile,
+	pub cache_config: CacheConfig,
+	pub keep: u64,
+	pub bloom_size: u32,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct KillBlockchain {
 	pub spec: SpecType,
@@ -152,6 +168,7 @@ pub fn execute(cmd: BlockchainCmd) -> Result<(), String> {
 		BlockchainCmd::Export(export_cmd) => execute_export(export_cmd),
 		BlockchainCmd::ExportState(export_cmd) => execute_export_state(export_cmd),
 		BlockchainCmd::Reset(reset_cmd) => execute_reset(reset_cmd),
+		BlockchainCmd::Prune(prune_cmd) => execute_prune(prune_cmd),
 	}
 }
 
@@ -184,7 +201,7 @@ fn execute_import_light(cmd: ImportBlockchain) -> Result<(), String> {
 	let client_path = db_dirs.client_path(algorithm);
 
 	// execute upgrades
-	execute_upgrades(&cmd.dirs.base, &db_dirs, algorithm, &cmd.compaction)?;
+	execute_upgrades(&cmd.dirs.base, &db_dirs, algorithm, &cmd.compaction, None)?;
 
 	// create dirs used by parity
 	cmd.dirs.create_dirs(false, false)?;
@@ -339,7 +356,7 @@ fn execute_import(cmd: ImportBlockchain) -> Result<(), String> {
 	let snapshot_path = db_dirs.snapshot_path();
 
 	// execute upgrades
-	execute_upgrades(&cmd.dirs.base, &db_dirs, algorithm, &cmd.compaction)?;
+	execute_upgrades(&cmd.dirs.base, &db_dirs, algorithm, &cmd.compaction, None)?;
 
 	// create dirs used by parity
 	cmd.dirs.create_dirs(false, false)?;
@@ -475,7 +492,7 @@ fn start_client(
 	let snapshot_path = db_dirs.snapshot_path();
 
 	// execute upgrades
-	execute_upgrades(&dirs.base, &db_dirs, algorithm, &compaction)?;
+	execute_upgrades(&dirs.base, &db_dirs, algorithm, &compaction, None)?;
 
 	// create dirs used by parity
 	dirs.create_dirs(false, false)?;
@@ -659,6 +676,29 @@ fn execute_reset(cmd: ResetBlockchain) -> Result<(), String> {
 	Ok(())
 }
 
+fn execute_prune(cmd: PruneBlockchain) -> Result<(), String> {
+	let service = start_client(
+		cmd.dirs,
+		cmd.spec,
+		cmd.pruning,
+		cmd.pruning_history,
+		cmd.pruning_memory,
+		cmd.tracing,
+		cmd.fat_db,
+		cmd.compaction,
+		cmd.cache_config,
+		false,
+		0,
+	)?;
+
+	let client = service.client();
+	client.prune_state(cmd.keep, cmd.bloom_size as usize * 1024 * 1024)
+		.map_err(|e| format!("Failed to prune state: {}", e))?;
+	info!("{}", Colour::Green.bold().paint("Successfully pruned state!"));
+
+	Ok(())
+}
+
 pub fn kill_db(cmd: KillBlockchain) -> Result<(), String> {
 	let spec = cmd.spec.spec(&cmd.dirs.cache)?;
 	let genesis_hash = spec.genesis_header().hash();
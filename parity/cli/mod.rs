@@ -211,6 +211,31 @@ usage! {
 				"<FILE>",
 				"File",
 			}
+
+			CMD cmd_tools_dns_tree
+			{
+				"Build a signed EIP-1459 node tree and print the TXT records publishing it",
+
+				ARG arg_tools_dns_tree_domain: (Option<String>) = None,
+				"--domain=[DOMAIN]",
+				"Domain the tree is published at.",
+
+				ARG arg_tools_dns_tree_key: (Option<String>) = None,
+				"--key=[KEY]",
+				"Secret key signing the tree, as a 64-character hex string.",
+
+				ARG arg_tools_dns_tree_seq: (u64) = 1u64,
+				"--seq=[NUM]",
+				"Sequence number of the tree, must be increased each time the tree is republished.",
+
+				ARG arg_tools_dns_tree_links: (Option<String>) = None,
+				"--links=[TREES]",
+				"Link to other trees. TREES should be comma-delimited enrtree:// locations.",
+
+				ARG arg_tools_dns_tree_file: (Option<String>) = None,
+				"<FILE>",
+				"File containing node records (enr:), one per line",
+			}
 		}
 
 		CMD cmd_db
@@ -229,6 +254,14 @@ usage! {
 				"Number of blocks to revert",
 			}
 
+			CMD cmd_db_prune {
+				"Removes the state which is not reachable from the latest blocks from the db",
+
+				ARG arg_db_prune_keep: (Option<u64>) = None,
+				"--keep=[NUM]",
+				"Number of latest blocks whose state is kept, at least --pruning-history (default: --pruning-history)",
+			}
+
 		}
 
 		CMD cmd_export_hardcoded_sync
@@ -321,7 +354,7 @@ usage! {
 		["Convenience Options"]
 			FLAG flag_unsafe_expose: (bool) = false, or |c: &Config| c.misc.as_ref()?.unsafe_expose,
 			"--unsafe-expose",
-			"All servers will listen on external interfaces and will be remotely accessible. It's equivalent with setting the following: --[ws,jsonrpc,ipfs-api,secretstore,stratum,dapps,secretstore-http]-interface=all --*-hosts=all    This option is UNSAFE and should be used with great care!",
+			"All servers will listen on external interfaces and will be remotely accessible. It's equivalent with setting the following: --[ws,jsonrpc,graphql,ipfs-api,secretstore,stratum,dapps,secretstore-http]-interface=all --*-hosts=all    This option is UNSAFE and should be used with great care!",
 
 			ARG arg_config: (String) = "$BASE/config.toml", or |_| None,
 			"-c, --config=[CONFIG]",
@@ -356,6 +389,10 @@ usage! {
 			"--password=[FILE]...",
 			"Provide a file containing a password for unlocking an account. Leading and trailing whitespace is trimmed.",
 
+			ARG arg_signer_endpoint: (Option<String>) = None, or |c: &Config| c.account.as_ref()?.signer_endpoint.clone(),
+			"--signer-endpoint=[ENDPOINT]",
+			"Forward signing requests to an external Clef-compatible signer instead of using the local accounts. ENDPOINT is either an HTTP URL or a path to an IPC socket.",
+
 		["Private Transactions Options"]
 			FLAG flag_private_enabled: (bool) = false, or |c: &Config| c.private_tx.as_ref()?.enabled,
 			"--private-tx-enabled",
@@ -399,6 +436,10 @@ usage! {
 			"--no-warp",
 			"Disable syncing from the snapshot over the network.",
 
+			FLAG flag_snap_sync: (bool) = false, or |c: &Config| c.network.as_ref()?.snap_sync.clone(),
+			"--snap-sync",
+			"Download the state of a recent block with the snap protocol instead of warping from a snapshot. Only used on a fresh database.",
+
 			FLAG flag_no_discovery: (bool) = false, or |c: &Config| c.network.as_ref()?.discovery.map(|d| !d).clone(),
 			"--no-discovery",
 			"Disable new peer discovery.",
@@ -457,7 +498,11 @@ usage! {
 
 			ARG arg_bootnodes: (Option<String>) = None, or |c: &Config| c.network.as_ref()?.bootnodes.as_ref().map(|vec| vec.join(",")),
 			"--bootnodes=[NODES]",
-			"Override the bootnodes from our chain. NODES should be comma-delimited enodes.",
+			"Override the bootnodes from our chain. NODES should be comma-delimited enodes or node records (enr:).",
+
+			ARG arg_dns_discovery: (Option<String>) = None, or |c: &Config| c.network.as_ref()?.dns_discovery.as_ref().map(|vec| vec.join(",")),
+			"--dns-discovery=[TREES]",
+			"Discover nodes from lists published in DNS (EIP-1459). TREES should be comma-delimited enrtree:// locations.",
 
 			ARG arg_node_key: (Option<String>) = None, or |c: &Config| c.network.as_ref()?.node_key.clone(),
 			"--node-key=[KEY]",
@@ -465,7 +510,7 @@ usage! {
 
 			ARG arg_reserved_peers: (Option<String>) = None, or |c: &Config| c.network.as_ref()?.reserved_peers.clone(),
 			"--reserved-peers=[FILE]",
-			"Provide a file containing enodes, one per line. These nodes will always have a reserved slot on top of the normal maximum peers.",
+			"Provide a file containing enodes or node records (enr:), one per line. These nodes will always have a reserved slot on top of the normal maximum peers.",
 
 			CHECK |args: &Args| {
 				if let (Some(max_peers), Some(min_peers)) = (args.arg_max_peers, args.arg_min_peers) {
@@ -504,7 +549,7 @@ usage! {
 
 			ARG arg_jsonrpc_apis: (String) = "web3,eth,pubsub,net,parity,private,parity_pubsub,traces,rpc,parity_transactions_pool", or |c: &Config| c.rpc.as_ref()?.apis.as_ref().map(|vec| vec.join(",")),
 			"--jsonrpc-apis=[APIS]",
-			"Specify the APIs available through the HTTP JSON-RPC interface using a comma-delimited list of API names. Possible names are: all, safe, debug, web3, net, eth, pubsub, personal, signer, parity, parity_pubsub, parity_accounts, parity_set, traces, rpc, secretstore. You can also disable a specific API by putting '-' in the front, example: all,-personal. 'safe' enables the following APIs: web3, net, eth, pubsub, parity, parity_pubsub, traces, rpc",
+			"Specify the APIs available through the HTTP JSON-RPC interface using a comma-delimited list of API names. Possible names are: all, safe, debug, web3, net, eth, pubsub, personal, signer, parity, parity_pubsub, parity_accounts, parity_set, traces, rpc, secretstore, bundle. You can also disable a specific API by putting '-' in the front, example: all,-personal. 'safe' enables the following APIs: web3, net, eth, pubsub, parity, parity_pubsub, traces, rpc",
 
 			ARG arg_jsonrpc_hosts: (String) = "none", or |c: &Config| c.rpc.as_ref()?.hosts.as_ref().map(|vec| vec.join(",")),
 			"--jsonrpc-hosts=[HOSTS]",
@@ -545,7 +590,7 @@ usage! {
 
 			ARG arg_ws_apis: (String) = "web3,eth,pubsub,net,parity,parity_pubsub,private,traces,rpc,parity_transactions_pool", or |c: &Config| c.websockets.as_ref()?.apis.as_ref().map(|vec| vec.join(",")),
 			"--ws-apis=[APIS]",
-			"Specify the JSON-RPC APIs available through the WebSockets interface using a comma-delimited list of API names. Possible names are: all, safe, web3, net, eth, pubsub, personal, signer, parity, parity_pubsub, parity_accounts, parity_set, traces, rpc, secretstore. You can also disable a specific API by putting '-' in the front, example: all,-personal. 'safe' enables the following APIs: web3, net, eth, pubsub, parity, parity_pubsub, traces, rpc",
+			"Specify the JSON-RPC APIs available through the WebSockets interface using a comma-delimited list of API names. Possible names are: all, safe, web3, net, eth, pubsub, personal, signer, parity, parity_pubsub, parity_accounts, parity_set, traces, rpc, secretstore, bundle. You can also disable a specific API by putting '-' in the front, example: all,-personal. 'safe' enables the following APIs: web3, net, eth, pubsub, parity, parity_pubsub, traces, rpc",
 
 			ARG arg_ws_origins: (String) = "parity://*,chrome-extension://*,moz-extension://*", or |c: &Config| c.websockets.as_ref()?.origins.as_ref().map(|vec| vec.join(",")),
 			"--ws-origins=[URL]",
@@ -574,7 +619,7 @@ usage! {
 
 			ARG arg_ipc_apis: (String) = "web3,eth,pubsub,net,parity,parity_pubsub,parity_accounts,private,traces,rpc,parity_transactions_pool", or |c: &Config| c.ipc.as_ref()?.apis.as_ref().map(|vec| vec.join(",")),
 			"--ipc-apis=[APIS]",
-			"Specify custom API set available via JSON-RPC over IPC using a comma-delimited list of API names. Possible names are: all, safe, web3, net, eth, pubsub, personal, signer, parity, parity_pubsub, parity_accounts, parity_set, traces, rpc, secretstore. You can also disable a specific API by putting '-' in the front, example: all,-personal. 'safe' enables the following APIs: web3, net, eth, pubsub, parity, parity_pubsub, traces, rpc",
+			"Specify custom API set available via JSON-RPC over IPC using a comma-delimited list of API names. Possible names are: all, safe, web3, net, eth, pubsub, personal, signer, parity, parity_pubsub, parity_accounts, parity_set, traces, rpc, secretstore, bundle. You can also disable a specific API by putting '-' in the front, example: all,-personal. 'safe' enables the following APIs: web3, net, eth, pubsub, parity, parity_pubsub, traces, rpc",
 
 		["API and Console Options – IPFS"]
 			FLAG flag_ipfs_api: (bool) = false, or |c: &Config| c.ipfs.as_ref()?.enable.clone(),
@@ -597,6 +642,27 @@ usage! {
 			"--ipfs-api-cors=[URL]",
 			"Specify CORS header for IPFS API responses. Special options: \"all\", \"none\".",
 
+		["API and Console Options – GraphQL"]
+			FLAG flag_graphql: (bool) = false, or |c: &Config| c.graphql.as_ref()?.enable.clone(),
+			"--graphql",
+			"Enable the GraphQL API (EIP-1767) over HTTP.",
+
+			ARG arg_graphql_port: (u16) = 8547u16, or |c: &Config| c.graphql.as_ref()?.port.clone(),
+			"--graphql-port=[PORT]",
+			"Configure on which port the GraphQL API should listen.",
+
+			ARG arg_graphql_interface: (String) = "local", or |c: &Config| c.graphql.as_ref()?.interface.clone(),
+			"--graphql-interface=[IP]",
+			"Specify the hostname portion of the GraphQL API server, IP should be an interface's IP address, or all (all interfaces) or local.",
+
+			ARG arg_graphql_hosts: (String) = "none", or |c: &Config| c.graphql.as_ref()?.hosts.as_ref().map(|vec| vec.join(",")),
+			"--graphql-hosts=[HOSTS]",
+			"List of allowed Host header values. This option will validate the Host header sent by the browser, it is additional security against some attack vectors. Special options: \"all\", \"none\".",
+
+			ARG arg_graphql_cors: (String) = "none", or |c: &Config| c.graphql.as_ref()?.cors.as_ref().map(|vec| vec.join(",")),
+			"--graphql-cors=[URL]",
+			"Specify CORS header for GraphQL API responses. Special options: \"all\", \"none\".",
+
 		["Light Client Options"]
 			ARG arg_on_demand_response_time_window: (Option<u64>) = None, or |c: &Config| c.light.as_ref()?.on_demand_response_time_window,
 			"--on-demand-time-window=[S]",
@@ -716,6 +782,10 @@ usage! {
 			"--tx-queue-no-early-reject",
 			"Disables transaction queue optimization to early reject transactions below minimal effective gas price. This allows local transactions to always enter the pool, despite it being full, but requires additional ecrecover on every transaction.",
 
+			FLAG flag_tx_queue_journal: (bool) = false, or |c: &Config| c.mining.as_ref()?.tx_queue_journal.clone(),
+			"--tx-queue-journal",
+			"Save all the transactions in the queue, not only the local ones, to disk periodically and on shutdown, and re-import them whenever the node restarts.",
+
 			FLAG flag_refuse_service_transactions: (bool) = false, or |c: &Config| c.mining.as_ref()?.refuse_service_transactions.clone(),
 			"--refuse-service-transactions",
 			"Always refuse service transactions.",
@@ -794,6 +864,18 @@ usage! {
 			"--tx-queue-strategy=[S]",
 			"Prioritization strategy used to order transactions in the queue. S may be: gas_price - Prioritize txs with high gas price",
 
+			ARG arg_tx_queue_journal_rotation: (u64) = 3600u64, or |c: &Config| c.mining.as_ref()?.tx_queue_journal_rotation.clone(),
+			"--tx-queue-journal-rotation=[SECS]",
+			"How often the transaction queue journal is rewritten with the current contents of the queue. Only with --tx-queue-journal.",
+
+			ARG arg_tx_queue_journal_max_age: (u64) = 10800u64, or |c: &Config| c.mining.as_ref()?.tx_queue_journal_max_age.clone(),
+			"--tx-queue-journal-max-age=[SECS]",
+			"Transactions journaled longer ago than SECS are not re-imported from the transaction queue journal on startup.",
+
+			ARG arg_tx_queue_journal_max_count: (usize) = 8_192usize, or |c: &Config| c.mining.as_ref()?.tx_queue_journal_max_count.clone(),
+			"--tx-queue-journal-max-count=[LIMIT]",
+			"Maximum amount of transactions re-imported from the transaction queue journal on startup.",
+
 			ARG arg_stratum_interface: (String) = "local", or |c: &Config| c.stratum.as_ref()?.interface.clone(),
 			"--stratum-interface=[IP]",
 			"Interface address for Stratum server.",
@@ -808,7 +890,15 @@ usage! {
 
 			ARG arg_gas_price_percentile: (usize) = 50usize, or |c: &Config| c.mining.as_ref()?.gas_price_percentile,
 			"--gas-price-percentile=[PCT]",
-			"Set PCT percentile gas price value from last 100 blocks as default gas price when sending transactions.",
+			"Set PCT percentile gas price value from recent blocks as default gas price when sending transactions.",
+
+			ARG arg_gas_price_oracle_blocks: (usize) = 100usize, or |c: &Config| c.mining.as_ref()?.gas_price_oracle_blocks,
+			"--gas-price-oracle-blocks=[BLOCKS]",
+			"Number of recent blocks whose gas prices are sampled for the default gas price.",
+
+			ARG arg_gas_price_oracle_ignore_below: (u64) = 0u64, or |c: &Config| c.mining.as_ref()?.gas_price_oracle_ignore_below,
+			"--gas-price-oracle-ignore-below=[WEI]",
+			"Ignore gas prices below WEI per gas when sampling the default gas price.",
 
 			ARG arg_author: (Option<String>) = None, or |c: &Config| c.mining.as_ref()?.author.clone(),
 			"--author=[ADDRESS]",
@@ -889,6 +979,18 @@ usage! {
 			"--pruning-memory=[MB]",
 			"The ideal amount of memory in megabytes to use to store recent states. As many states as possible will be kept within this limit, and at least --pruning-history states will always be kept.",
 
+			ARG arg_state_pruning_interval: (u64) = 0u64, or |c: &Config| c.footprint.as_ref()?.state_pruning_interval.clone(),
+			"--state-pruning-interval=[BLOCKS]",
+			"Remove the state which is not reachable from the latest --pruning-history blocks in the background every BLOCKS blocks. Only for --pruning=fast, use `parity db prune` for archive. 0 to disable.",
+
+			ARG arg_state_pruning_bloom_size: (u32) = 256u32, or |c: &Config| c.footprint.as_ref()?.state_pruning_bloom_size.clone(),
+			"--state-pruning-bloom-size=[MB]",
+			"Size in megabytes of the bloom filter marking the reachable state when pruning it. Too small a filter leaves more garbage behind.",
+
+			ARG arg_freezer_depth: (u64) = 90000u64, or |c: &Config| c.footprint.as_ref()?.freezer_depth.clone(),
+			"--freezer-depth=[BLOCKS]",
+			"Move the headers, bodies and receipts of the canonical blocks older than BLOCKS blocks from the database to the append-only freezer. 0 to keep all blocks in the database.",
+
 			ARG arg_cache_size_db: (u32) = 128u32, or |c: &Config| c.footprint.as_ref()?.cache_size_db.clone(),
 			"--cache-size-db=[MB]",
 			"Override database cache size.",
@@ -1164,6 +1266,7 @@ struct Config {
 	secretstore: Option<SecretStore>,
 	private_tx: Option<PrivateTransactions>,
 	ipfs: Option<Ipfs>,
+	graphql: Option<GraphQL>,
 	mining: Option<Mining>,
 	footprint: Option<Footprint>,
 	snapshots: Option<Snapshots>,
@@ -1207,6 +1310,7 @@ struct Account {
 	keys_iterations: Option<u32>,
 	refresh_time: Option<u64>,
 	fast_unlock: Option<bool>,
+	signer_endpoint: Option<String>,
 }
 
 #[derive(Default, Debug, PartialEq, Deserialize)]
@@ -1243,6 +1347,7 @@ struct Ui {
 #[serde(deny_unknown_fields)]
 struct Network {
 	warp: Option<bool>,
+	snap_sync: Option<bool>,
 	warp_barrier: Option<u64>,
 	port: Option<u16>,
 	interface: Option<String>,
@@ -1254,6 +1359,7 @@ struct Network {
 	allow_ips: Option<String>,
 	id: Option<u64>,
 	bootnodes: Option<Vec<String>>,
+	dns_discovery: Option<Vec<String>>,
 	discovery: Option<bool>,
 	node_key: Option<String>,
 	reserved_peers: Option<String>,
@@ -1354,6 +1460,16 @@ struct Ipfs {
 	hosts: Option<Vec<String>>,
 }
 
+#[derive(Default, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GraphQL {
+	enable: Option<bool>,
+	port: Option<u16>,
+	interface: Option<String>,
+	cors: Option<Vec<String>>,
+	hosts: Option<Vec<String>>,
+}
+
 #[derive(Default, Debug, PartialEq, Deserialize)]
 #[serde(deny_unknown_fields)]
 struct Mining {
@@ -1370,6 +1486,8 @@ struct Mining {
 	relay_set: Option<String>,
 	min_gas_price: Option<u64>,
 	gas_price_percentile: Option<usize>,
+	gas_price_oracle_blocks: Option<usize>,
+	gas_price_oracle_ignore_below: Option<u64>,
 	usd_per_tx: Option<String>,
 	usd_per_eth: Option<String>,
 	price_update_period: Option<String>,
@@ -1385,6 +1503,10 @@ struct Mining {
 	tx_queue_ban_time: Option<u16>,
 	tx_queue_no_unfamiliar_locals: Option<bool>,
 	tx_queue_no_early_reject: Option<bool>,
+	tx_queue_journal: Option<bool>,
+	tx_queue_journal_rotation: Option<u64>,
+	tx_queue_journal_max_age: Option<u64>,
+	tx_queue_journal_max_count: Option<usize>,
 	remove_solved: Option<bool>,
 	notify_work: Option<Vec<String>>,
 	refuse_service_transactions: Option<bool>,
@@ -1408,6 +1530,9 @@ struct Footprint {
 	pruning: Option<String>,
 	pruning_history: Option<u64>,
 	pruning_memory: Option<usize>,
+	state_pruning_interval: Option<u64>,
+	state_pruning_bloom_size: Option<u32>,
+	freezer_depth: Option<u64>,
 	fast_and_loose: Option<bool>,
 	cache_size: Option<u32>,
 	cache_size_db: Option<u32>,
@@ -1721,9 +1846,11 @@ mod tests {
 			cmd_restore: false,
 			cmd_tools: false,
 			cmd_tools_hash: false,
+			cmd_tools_dns_tree: false,
 			cmd_db: false,
 			cmd_db_kill: false,
 			cmd_db_reset: false,
+			cmd_db_prune: false,
 			cmd_export_hardcoded_sync: false,
 
 			// Arguments
@@ -1737,6 +1864,11 @@ mod tests {
 			arg_snapshot_file: None,
 			arg_restore_file: None,
 			arg_tools_hash_file: None,
+			arg_tools_dns_tree_domain: None,
+			arg_tools_dns_tree_key: None,
+			arg_tools_dns_tree_seq: 1,
+			arg_tools_dns_tree_links: None,
+			arg_tools_dns_tree_file: None,
 
 			arg_enable_signing_queue: false,
 			arg_signer_sign_id: None,
@@ -1745,6 +1877,7 @@ mod tests {
 			arg_account_import_path: None,
 			arg_wallet_import_path: None,
 			arg_db_reset_num: 10,
+			arg_db_prune_keep: None,
 
 			// -- Operating Options
 			arg_mode: "last".into(),
@@ -1778,6 +1911,7 @@ mod tests {
 			arg_keys_iterations: 10240u32,
 			arg_accounts_refresh: 5u64,
 			flag_fast_unlock: false,
+			arg_signer_endpoint: None,
 
 			// -- Private Transactions Options
 			flag_private_enabled: true,
@@ -1799,6 +1933,7 @@ mod tests {
 
 			// -- Networking Options
 			flag_no_warp: false,
+			flag_snap_sync: false,
 			arg_port: 30303u16,
 			arg_interface: "all".into(),
 			arg_min_peers: Some(25u16),
@@ -1809,6 +1944,7 @@ mod tests {
 			arg_nat: "any".into(),
 			arg_network_id: Some(1),
 			arg_bootnodes: Some("".into()),
+			arg_dns_discovery: None,
 			flag_no_discovery: false,
 			arg_node_key: None,
 			arg_reserved_peers: Some("./path_to_file".into()),
@@ -1878,6 +2014,13 @@ mod tests {
 			arg_ipfs_api_cors: "null".into(),
 			arg_ipfs_api_hosts: "none".into(),
 
+			// GraphQL
+			flag_graphql: false,
+			arg_graphql_port: 8547u16,
+			arg_graphql_interface: "local".into(),
+			arg_graphql_cors: "null".into(),
+			arg_graphql_hosts: "none".into(),
+
 			// -- Sealing/Mining Options
 			arg_author: Some("0xdeadbeefcafe0000000000000000000000000001".into()),
 			arg_engine_signer: Some("0xdeadbeefcafe0000000000000000000000000001".into()),
@@ -1893,6 +2036,8 @@ mod tests {
 			arg_min_gas_price: Some(0u64),
 			arg_usd_per_tx: "0.0001".into(),
 			arg_gas_price_percentile: 50usize,
+			arg_gas_price_oracle_blocks: 100usize,
+			arg_gas_price_oracle_ignore_below: 0u64,
 			arg_usd_per_eth: "auto".into(),
 			arg_price_update_period: "hourly".into(),
 			arg_gas_floor_target: "8000000".into(),
@@ -1900,11 +2045,15 @@ mod tests {
 			arg_extra_data: Some("Parity".into()),
 			flag_tx_queue_no_unfamiliar_locals: false,
 			flag_tx_queue_no_early_reject: false,
+			flag_tx_queue_journal: false,
 			arg_tx_queue_size: 8192usize,
 			arg_tx_queue_per_sender: None,
 			arg_tx_queue_mem_limit: 4u32,
 			arg_tx_queue_locals: Some("0xdeadbeefcafe0000000000000000000000000000".into()),
 			arg_tx_queue_strategy: "gas_factor".into(),
+			arg_tx_queue_journal_rotation: 3600u64,
+			arg_tx_queue_journal_max_age: 10800u64,
+			arg_tx_queue_journal_max_count: 8192usize,
 			arg_tx_queue_ban_count: Some(1u16),
 			arg_tx_queue_ban_time: Some(180u16),
 			flag_remove_solved: false,
@@ -1923,6 +2072,9 @@ mod tests {
 			arg_pruning: "auto".into(),
 			arg_pruning_history: 64u64,
 			arg_pruning_memory: 500usize,
+			arg_state_pruning_interval: 0u64,
+			arg_state_pruning_bloom_size: 256u32,
+			arg_freezer_depth: 90000u64,
 			arg_cache_size_db: 64u32,
 			arg_cache_size_blocks: 8u32,
 			arg_cache_size_queue: 50u32,
@@ -2062,6 +2214,7 @@ mod tests {
 				keys_iterations: None,
 				refresh_time: None,
 				fast_unlock: None,
+				signer_endpoint: None,
 			}),
 			ui: Some(Ui {
 				path: None,
@@ -2073,6 +2226,7 @@ mod tests {
 			}),
 			network: Some(Network {
 				warp: Some(false),
+				snap_sync: None,
 				warp_barrier: None,
 				port: None,
 				interface: None,
@@ -2084,6 +2238,7 @@ mod tests {
 				nat: Some("any".into()),
 				id: None,
 				bootnodes: None,
+				dns_discovery: None,
 				discovery: Some(true),
 				node_key: None,
 				reserved_peers: Some("./path/to/reserved_peers".into()),
@@ -2158,6 +2313,7 @@ mod tests {
 				cors: None,
 				hosts: None,
 			}),
+			graphql: None,
 			mining: Some(Mining {
 				author: Some("0xdeadbeefcafe0000000000000000000000000001".into()),
 				engine_signer: Some("0xdeadbeefcafe0000000000000000000000000001".into()),
@@ -2170,6 +2326,8 @@ mod tests {
 				relay_set: None,
 				min_gas_price: None,
 				gas_price_percentile: None,
+				gas_price_oracle_blocks: None,
+				gas_price_oracle_ignore_below: None,
 				usd_per_tx: None,
 				usd_per_eth: None,
 				price_update_period: Some("hourly".into()),
@@ -2184,6 +2342,10 @@ mod tests {
 				tx_queue_ban_time: None,
 				tx_queue_no_unfamiliar_locals: None,
 				tx_queue_no_early_reject: None,
+				tx_queue_journal: None,
+				tx_queue_journal_rotation: None,
+				tx_queue_journal_max_age: None,
+				tx_queue_journal_max_count: None,
 				tx_gas_limit: None,
 				tx_time_limit: None,
 				extra_data: None,
@@ -2198,6 +2360,9 @@ mod tests {
 				pruning: Some("fast".into()),
 				pruning_history: Some(64),
 				pruning_memory: None,
+				state_pruning_interval: None,
+				state_pruning_bloom_size: None,
+				freezer_depth: None,
 				fast_and_loose: None,
 				cache_size: None,
 				cache_size_db: Some(256),
@@ -32,12 +32,13 @@ use parity_crypto::publickey::{Secret, Public};
 use ethcore::miner::{stratum, MinerOptions};
 use snapshot::SnapshotConfiguration;
 use miner::pool;
+use local_store::journal::Options as TxQueueJournalOptions;
 use verification::queue::VerifierSettings;
 
-use rpc::{IpcConfiguration, HttpConfiguration, WsConfiguration};
-use parity_rpc::NetworkSettings;
+use rpc::{IpcConfiguration, HttpConfiguration, WsConfiguration, GraphQLConfiguration};
+use parity_rpc::{GasPriceOracleConfig, NetworkSettings};
 use cache::CacheConfig;
-use helpers::{to_duration, to_mode, to_block_id, to_u256, to_pending_set, to_price, geth_ipc_path, parity_ipc_path, to_bootnodes, to_addresses, to_address, to_queue_strategy, to_queue_penalization};
+use helpers::{to_duration, to_mode, to_block_id, to_u256, to_pending_set, to_price, geth_ipc_path, parity_ipc_path, to_bootnodes, to_dns_trees, to_addresses, to_address, to_queue_strategy, to_queue_penalization};
 use dir::helpers::{replace_home, replace_home_and_local};
 use params::{ResealPolicy, AccountsConfig, GasPricerConfig, MinerExtras, SpecType};
 use ethcore_logger::Config as LogConfig;
@@ -48,8 +49,9 @@ use secretstore::{NodeSecretKey, Configuration as SecretStoreConfiguration, Cont
 use updater::{UpdatePolicy, UpdateFilter, ReleaseTrack};
 use run::RunCmd;
 use types::data_format::DataFormat;
-use blockchain::{BlockchainCmd, ImportBlockchain, ExportBlockchain, KillBlockchain, ExportState, ResetBlockchain};
+use blockchain::{BlockchainCmd, ImportBlockchain, ExportBlockchain, KillBlockchain, ExportState, ResetBlockchain, PruneBlockchain};
 use export_hardcoded_sync::ExportHsyncCmd;
+use dns_tree::DnsTreeCmd;
 use presale::ImportWallet;
 use account::{AccountCmd, NewAccount, ListAccounts, ImportAccounts, ImportFromGethAccounts};
 use snapshot_cmd::{self, SnapshotCommand};
@@ -84,6 +86,7 @@ pub enum Cmd {
 	},
 	Snapshot(SnapshotCommand),
 	Hash(Option<String>),
+	DnsTree(DnsTreeCmd),
 	ExportHardcodedSync(ExportHsyncCmd),
 }
 
@@ -140,6 +143,7 @@ impl Configuration {
 		let geth_compatibility = self.args.flag_geth;
 		let experimental_rpcs = self.args.flag_jsonrpc_experimental;
 		let ipfs_conf = self.ipfs_config();
+		let graphql_conf = self.graphql_config();
 		let secretstore_conf = self.secretstore_config()?;
 		let format = self.format()?;
 
@@ -181,6 +185,17 @@ impl Configuration {
 			}
 		} else if self.args.cmd_tools && self.args.cmd_tools_hash {
 			Cmd::Hash(self.args.arg_tools_hash_file)
+		} else if self.args.cmd_tools && self.args.cmd_tools_dns_tree {
+			let key = self.args.arg_tools_dns_tree_key.ok_or("--key is required")?
+				.parse::<Secret>()
+				.map_err(|e| format!("Invalid key: {:?}", e))?;
+			Cmd::DnsTree(DnsTreeCmd {
+				domain: self.args.arg_tools_dns_tree_domain.ok_or("--domain is required")?,
+				key,
+				seq: self.args.arg_tools_dns_tree_seq,
+				links: to_dns_trees(&self.args.arg_tools_dns_tree_links)?,
+				file: self.args.arg_tools_dns_tree_file.ok_or("Node records file is required")?,
+			})
 		} else if self.args.cmd_db && self.args.cmd_db_reset {
 			Cmd::Blockchain(BlockchainCmd::Reset(ResetBlockchain {
 				dirs,
@@ -194,6 +209,20 @@ impl Configuration {
 				cache_config,
 				num: self.args.arg_db_reset_num,
 			}))
+		} else if self.args.cmd_db && self.args.cmd_db_prune {
+			Cmd::Blockchain(BlockchainCmd::Prune(PruneBlockchain {
+				dirs,
+				spec,
+				pruning,
+				pruning_history,
+				pruning_memory: self.args.arg_pruning_memory,
+				tracing,
+				fat_db,
+				compaction,
+				cache_config,
+				keep: self.args.arg_db_prune_keep.unwrap_or(pruning_history),
+				bloom_size: self.args.arg_state_pruning_bloom_size,
+			}))
 		} else if self.args.cmd_db && self.args.cmd_db_kill {
 			Cmd::Blockchain(BlockchainCmd::Kill(KillBlockchain {
 				spec: spec,
@@ -370,10 +399,13 @@ impl Configuration {
 				pruning,
 				pruning_history,
 				pruning_memory: self.args.arg_pruning_memory,
+				state_pruning_interval: self.args.arg_state_pruning_interval,
+				state_pruning_bloom_size: self.args.arg_state_pruning_bloom_size,
+				freezer_depth: self.args.arg_freezer_depth,
 				daemon,
 				logger_config: logger_config.clone(),
 				miner_options: self.miner_options()?,
-				gas_price_percentile: self.args.arg_gas_price_percentile,
+				gas_price_oracle: self.gas_price_oracle_config(),
 				poll_lifetime: self.args.arg_poll_lifetime,
 				ws_conf,
 				snapshot_conf,
@@ -393,10 +425,12 @@ impl Configuration {
 				compaction,
 				warp_sync,
 				warp_barrier: self.args.arg_warp_barrier,
+				snap_sync: self.args.flag_snap_sync,
 				geth_compatibility,
 				experimental_rpcs,
 				net_settings: self.network_settings()?,
 				ipfs_conf,
+				graphql_conf,
 				secretstore_conf,
 				private_provider_conf,
 				private_encryptor_conf: private_enc_conf,
@@ -409,6 +443,7 @@ impl Configuration {
 				serve_light: !self.args.flag_no_serve_light,
 				light: self.args.flag_light,
 				no_persistent_txqueue: self.args.flag_no_persistent_txqueue,
+				tx_queue_journal: self.tx_queue_journal_options(),
 				no_hardcoded_sync: self.args.flag_no_hardcoded_sync,
 				max_round_blocks_to_import: self.args.arg_max_round_blocks_to_import,
 				on_demand_response_time_window: self.args.arg_on_demand_response_time_window,
@@ -532,6 +567,7 @@ impl Configuration {
 			password_files: self.args.arg_password.iter().map(|s| replace_home(&self.directories().base, s)).collect(),
 			unlocked_accounts: to_addresses(&self.args.arg_unlock)?,
 			enable_fast_unlock: self.args.flag_fast_unlock,
+			signer_endpoint: self.args.arg_signer_endpoint.as_ref().map(|s| replace_home(&self.directories().base, s)),
 		};
 
 		Ok(cfg)
@@ -608,6 +644,16 @@ impl Configuration {
 		})
 	}
 
+	fn tx_queue_journal_options(&self) -> Option<TxQueueJournalOptions> {
+		if self.args.flag_tx_queue_journal {
+			Some(TxQueueJournalOptions {
+				rotation_interval: Duration::from_secs(self.args.arg_tx_queue_journal_rotation),
+				max_age: Duration::from_secs(self.args.arg_tx_queue_journal_max_age),
+				max_count: self.args.arg_tx_queue_journal_max_count,
+			})
+		} else { None }
+	}
+
 	fn secretstore_config(&self) -> Result<SecretStoreConfiguration, String> {
 		Ok(SecretStoreConfiguration {
 			enabled: self.secretstore_enabled(),
@@ -642,6 +688,17 @@ impl Configuration {
 		}
 	}
 
+	fn graphql_config(&self) -> GraphQLConfiguration {
+		GraphQLConfiguration {
+			enabled: self.args.flag_graphql,
+			port: self.args.arg_ports_shift + self.args.arg_graphql_port,
+			interface: self.graphql_interface(),
+			cors: self.graphql_cors(),
+			hosts: self.graphql_hosts(),
+			..Default::default()
+		}
+	}
+
 	fn gas_pricer_config(&self) -> Result<GasPricerConfig, String> {
 		fn wei_per_gas(usd_per_tx: f32, usd_per_eth: f32) -> U256 {
 			let wei_per_usd: f32 = 1.0e18 / usd_per_eth;
@@ -685,6 +742,14 @@ impl Configuration {
 		}
 	}
 
+	fn gas_price_oracle_config(&self) -> GasPriceOracleConfig {
+		GasPriceOracleConfig {
+			blocks: self.args.arg_gas_price_oracle_blocks,
+			percentile: self.args.arg_gas_price_percentile,
+			ignore_below: self.args.arg_gas_price_oracle_ignore_below.into(),
+		}
+	}
+
 	fn extra_data(&self) -> Result<Bytes, String> {
 		match self.args.arg_extradata.as_ref().or(self.args.arg_extra_data.as_ref()) {
 			Some(x) if x.len() <= 32 => Ok(x.as_bytes().to_owned()),
@@ -751,6 +816,7 @@ impl Configuration {
 			_ => NatType::Nothing,
 		};
 		ret.boot_nodes = to_bootnodes(&self.args.arg_bootnodes)?;
+		ret.dns_discovery = to_dns_trees(&self.args.arg_dns_discovery)?;
 		let (listen, public) = self.net_addresses()?;
 		ret.listen_address = Some(format!("{}", listen));
 		ret.public_address = public.map(|p| format!("{}", p));
@@ -821,6 +887,10 @@ impl Configuration {
 		Self::cors(self.args.arg_ipfs_api_cors.as_ref())
 	}
 
+	fn graphql_cors(&self) -> Option<Vec<String>> {
+		Self::cors(self.args.arg_graphql_cors.as_ref())
+	}
+
 	fn hosts(&self, hosts: &str, interface: &str) -> Option<Vec<String>> {
 		if self.args.flag_unsafe_expose {
 			return None;
@@ -863,6 +933,10 @@ impl Configuration {
 		self.hosts(&self.args.arg_ipfs_api_hosts, &self.ipfs_interface())
 	}
 
+	fn graphql_hosts(&self) -> Option<Vec<String>> {
+		self.hosts(&self.args.arg_graphql_hosts, &self.graphql_interface())
+	}
+
 	fn ipc_config(&self) -> Result<IpcConfiguration, String> {
 		let conf = IpcConfiguration {
 			chmod: self.args.arg_ipc_chmod.clone(),
@@ -1064,6 +1138,10 @@ impl Configuration {
 		self.interface(&self.args.arg_ipfs_api_interface)
 	}
 
+	fn graphql_interface(&self) -> String {
+		self.interface(&self.args.arg_graphql_interface)
+	}
+
 	fn secretstore_interface(&self) -> String {
 		self.interface(&self.args.arg_secretstore_interface)
 	}
@@ -1357,6 +1435,35 @@ mod tests {
 		})));
 	}
 
+	#[test]
+	fn test_command_db_prune() {
+		let args = vec!["parity", "db", "prune", "--keep", "256"];
+		let conf = parse(&args);
+		assert_eq!(conf.into_command().unwrap().cmd, Cmd::Blockchain(BlockchainCmd::Prune(PruneBlockchain {
+			dirs: Default::default(),
+			spec: Default::default(),
+			pruning: Default::default(),
+			pruning_history: 64,
+			pruning_memory: 32,
+			tracing: Default::default(),
+			fat_db: Default::default(),
+			compaction: Default::default(),
+			cache_config: Default::default(),
+			keep: 256,
+			bloom_size: 256,
+		})));
+	}
+
+	#[test]
+	fn test_command_db_prune_keeps_pruning_history_by_default() {
+		let args = vec!["parity", "--pruning-history", "100", "db", "prune"];
+		let conf = parse(&args);
+		match conf.into_command().unwrap().cmd {
+			Cmd::Blockchain(BlockchainCmd::Prune(prune)) => assert_eq!(prune.keep, 100),
+			cmd => panic!("unexpected command: {:?}", cmd),
+		}
+	}
+
 	#[test]
 	fn test_command_blockchain_export_with_custom_format() {
 		let args = vec!["parity", "export", "blocks", "--format", "hex", "blockchain.json"];
@@ -1425,10 +1532,13 @@ mod tests {
 			pruning: Default::default(),
 			pruning_history: 64,
 			pruning_memory: 32,
+			state_pruning_interval: 0,
+			state_pruning_bloom_size: 256,
+			freezer_depth: 90000,
 			daemon: None,
 			logger_config: Default::default(),
 			miner_options: Default::default(),
-			gas_price_percentile: 50,
+			gas_price_oracle: Default::default(),
 			poll_lifetime: 60,
 			ws_conf: Default::default(),
 			http_conf: Default::default(),
@@ -1437,6 +1547,7 @@ mod tests {
 			network_id: None,
 			warp_sync: true,
 			warp_barrier: None,
+			snap_sync: false,
 			acc_conf: Default::default(),
 			gas_pricer_conf: Default::default(),
 			miner_extras: Default::default(),
@@ -1457,6 +1568,7 @@ mod tests {
 			experimental_rpcs: false,
 			net_settings: Default::default(),
 			ipfs_conf: Default::default(),
+			graphql_conf: Default::default(),
 			secretstore_conf: Default::default(),
 			private_provider_conf: Default::default(),
 			private_encryptor_conf: Default::default(),
@@ -1473,6 +1585,7 @@ mod tests {
 			light: false,
 			no_hardcoded_sync: false,
 			no_persistent_txqueue: false,
+			tx_queue_journal: None,
 			max_round_blocks_to_import: 12,
 			on_demand_response_time_window: None,
 			on_demand_request_backoff_start: None,
@@ -1500,6 +1613,21 @@ mod tests {
 		assert_eq!(conf2.miner_options().unwrap(), mining_options);
 	}
 
+	#[test]
+	fn should_parse_tx_queue_journal_options() {
+		let conf0 = parse(&["parity"]);
+		let conf1 = parse(&["parity", "--tx-queue-journal"]);
+		let conf2 = parse(&["parity", "--tx-queue-journal", "--tx-queue-journal-max-age", "600", "--tx-queue-journal-max-count", "1024"]);
+
+		assert_eq!(conf0.tx_queue_journal_options(), None);
+		assert_eq!(conf1.tx_queue_journal_options(), Some(Default::default()));
+		assert_eq!(conf2.tx_queue_journal_options(), Some(TxQueueJournalOptions {
+			max_age: Duration::from_secs(600),
+			max_count: 1024,
+			..Default::default()
+		}));
+	}
+
 	#[test]
 	fn should_fail_on_force_reseal_and_reseal_min_period() {
 		let conf = parse(&["parity", "--chain", "dev", "--force-sealing", "--reseal-min-period", "0"]);
@@ -1831,6 +1959,7 @@ mod tests {
 		assert_eq!(conf0.secretstore_config().unwrap().port, 8084);
 		assert_eq!(conf0.secretstore_config().unwrap().http_port, 8083);
 		assert_eq!(conf0.ipfs_config().port, 5002);
+		assert_eq!(conf0.graphql_config().port, 8548);
 		assert_eq!(conf0.stratum_options().unwrap().unwrap().port, 8009);
 
 		assert_eq!(conf1.net_addresses().unwrap().0.port(), 30304);
@@ -1888,6 +2017,8 @@ mod tests {
 		assert_eq!(&conf0.secretstore_config().unwrap().http_interface, "0.0.0.0");
 		assert_eq!(&conf0.ipfs_config().interface, "0.0.0.0");
 		assert_eq!(conf0.ipfs_config().hosts, None);
+		assert_eq!(&conf0.graphql_config().interface, "0.0.0.0");
+		assert_eq!(conf0.graphql_config().hosts, None);
 	}
 
 	#[test]
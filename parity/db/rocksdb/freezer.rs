@@ -0,0 +1,53 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Migration of the ancient blocks from rocksdb to freezer-db
+
+use std::path::Path;
+use ethereum_types::H256;
+use ethcore_db::{self, keys::BlockDetails, Readable};
+use types::errors::EthcoreError as Error;
+use super::ethcore_blockchain::freeze_blocks;
+use super::kvdb_rocksdb::DatabaseConfig;
+use super::open_database;
+
+/// Number of blocks moved to the freezer at once.
+const BATCH_SIZE: u64 = 4096;
+
+/// Moves the canonical blocks older than `depth` blocks to the freezer.
+pub fn migrate_freezer<P: AsRef<Path>>(path: P, config: &DatabaseConfig, depth: u64) -> Result<(), Error> {
+	let db = open_database(&path.as_ref().to_string_lossy(), config)?;
+
+	let best_hash = match db.key_value().get(ethcore_db::COL_EXTRA, b"best")? {
+		Some(hash) => H256::from_slice(&hash),
+		None => return Ok(()),
+	};
+	let best_number = match db.key_value().read::<BlockDetails, _>(ethcore_db::COL_EXTRA, &best_hash) {
+		Some(details) => details.number,
+		None => return Ok(()),
+	};
+
+	let limit = (best_number + 1).saturating_sub(depth);
+	loop {
+		let moved = freeze_blocks(&*db, limit, BATCH_SIZE)?;
+		if moved == 0 {
+			break;
+		}
+		info!(target: "migration", "Moved {} blocks to the freezer", moved);
+	}
+
+	Ok(())
+}
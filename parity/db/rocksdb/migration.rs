@@ -25,6 +25,7 @@ use types::errors::EthcoreError;
 
 use super::helpers;
 use super::blooms::migrate_blooms;
+use super::freezer::migrate_freezer;
 
 /// The migration from v10 to v11.
 /// Adds a column for node info.
@@ -50,12 +51,22 @@ pub const TO_V14: ChangeColumns = ChangeColumns {
 	version: 14,
 };
 
+/// The migration from v14 to v15.
+/// Adds a column for the flat state layer.
+pub const TO_V15: ChangeColumns = ChangeColumns {
+	pre_columns: 9,
+	post_columns: 10,
+	version: 15,
+};
+
 /// Database is assumed to be at default version, when no version file is found.
 const DEFAULT_VERSION: u32 = 5;
 /// Current version of database models.
-const CURRENT_VERSION: u32 = 14;
+const CURRENT_VERSION: u32 = 16;
 /// A version of database at which blooms-db was introduced
 const BLOOMS_DB_VERSION: u32 = 13;
+/// A version of database at which freezer-db was introduced
+const FREEZER_DB_VERSION: u32 = 16;
 /// Defines how many items are migrated to the new version of database at once.
 const BATCH_SIZE: usize = 1024;
 /// Version file name.
@@ -72,6 +83,8 @@ pub enum Error {
 	MigrationImpossible,
 	/// Blooms-db migration error.
 	BloomsDB(EthcoreError),
+	/// Freezer-db migration error.
+	FreezerDB(EthcoreError),
 	/// Migration was completed succesfully,
 	/// but there was a problem with io.
 	Io(IoError),
@@ -84,6 +97,7 @@ impl Display for Error {
 			Error::FutureDBVersion => "Database was created with newer client version. Upgrade your client or delete DB and resync.".into(),
 			Error::MigrationImpossible => format!("Database migration to version {} is not possible.", CURRENT_VERSION),
 			Error::BloomsDB(ref err) => format!("blooms-db migration error: {}", err),
+			Error::FreezerDB(ref err) => format!("freezer-db migration error: {}", err),
 			Error::Io(ref err) => format!("Unexpected io error on DB migration: {}.", err),
 		};
 
@@ -156,6 +170,7 @@ fn consolidated_database_migrations(compaction_profile: &CompactionProfile) -> R
 	manager.add_migration(TO_V11).map_err(|_| Error::MigrationImpossible)?;
 	manager.add_migration(TO_V12).map_err(|_| Error::MigrationImpossible)?;
 	manager.add_migration(TO_V14).map_err(|_| Error::MigrationImpossible)?;
+	manager.add_migration(TO_V15).map_err(|_| Error::MigrationImpossible)?;
 	Ok(manager)
 }
 
@@ -195,8 +210,9 @@ fn exists(path: &Path) -> bool {
 	fs::metadata(path).is_ok()
 }
 
-/// Migrates the database.
-pub fn migrate(path: &Path, compaction_profile: &DatabaseCompactionProfile) -> Result<(), Error> {
+/// Migrates the database. The ancient blocks are moved to the freezer if `freezer_depth` is given,
+/// otherwise they're left to the client.
+pub fn migrate(path: &Path, compaction_profile: &DatabaseCompactionProfile, freezer_depth: Option<u64>) -> Result<(), Error> {
 	let compaction_profile = helpers::compaction_profile(&compaction_profile, path);
 
 	// read version file.
@@ -220,18 +236,26 @@ pub fn migrate(path: &Path, compaction_profile: &DatabaseCompactionProfile) -> R
 		info!(target: "migration", "Migrating database from version {} to {}", version, CURRENT_VERSION);
 		migrate_database(version, &db_path, consolidated_database_migrations(&compaction_profile)?)?;
 
+		let db_config = DatabaseConfig {
+			max_open_files: 64,
+			compaction: compaction_profile,
+			columns: ethcore_db::NUM_COLUMNS,
+			..Default::default()
+		};
+
 		if version < BLOOMS_DB_VERSION {
 			info!(target: "migration", "Migrating blooms to blooms-db...");
-			let db_config = DatabaseConfig {
-				max_open_files: 64,
-				compaction: compaction_profile,
-				columns: ethcore_db::NUM_COLUMNS,
-				..Default::default()
-			};
-
 			migrate_blooms(&db_path, &db_config).map_err(Error::BloomsDB)?;
 		}
 
+		match freezer_depth {
+			Some(depth) if version < FREEZER_DB_VERSION => {
+				info!(target: "migration", "Moving ancient blocks to freezer-db...");
+				migrate_freezer(&db_path, &db_config, depth).map_err(Error::FreezerDB)?;
+			},
+			_ => {},
+		}
+
 		info!(target: "migration", "Migration finished");
 	}
 
@@ -25,6 +25,7 @@ use std::{io, fs};
 use std::sync::Arc;
 use std::path::Path;
 use blooms_db;
+use freezer_db;
 use ethcore_db::NUM_COLUMNS;
 use ethcore::client::{ClientConfig, DatabaseCompactionProfile};
 use kvdb::KeyValueDB;
@@ -34,6 +35,7 @@ use self::kvdb_rocksdb::{Database, DatabaseConfig};
 use cache::CacheConfig;
 
 mod blooms;
+mod freezer;
 mod migration;
 mod helpers;
 
@@ -43,6 +45,7 @@ struct AppDB {
 	key_value: Arc<dyn KeyValueDB>,
 	blooms: blooms_db::Database,
 	trace_blooms: blooms_db::Database,
+	freezer: freezer_db::Database,
 }
 
 impl BlockChainDB for AppDB {
@@ -57,6 +60,10 @@ impl BlockChainDB for AppDB {
 	fn trace_blooms(&self) -> &blooms_db::Database {
 		&self.trace_blooms
 	}
+
+	fn freezer(&self) -> Option<&freezer_db::Database> {
+		Some(&self.freezer)
+	}
 }
 
 /// Create a restoration db handler using the config generated by `client_path` and `client_config`.
@@ -100,13 +107,16 @@ pub fn open_database(client_path: &str, config: &DatabaseConfig) -> io::Result<A
 
 	let blooms_path = path.join("blooms");
 	let trace_blooms_path = path.join("trace_blooms");
+	let freezer_path = path.join("freezer");
 	fs::create_dir_all(&blooms_path)?;
 	fs::create_dir_all(&trace_blooms_path)?;
+	fs::create_dir_all(&freezer_path)?;
 
 	let db = AppDB {
 		key_value: Arc::new(Database::open(&config, client_path)?),
 		blooms: blooms_db::Database::open(blooms_path)?,
 		trace_blooms: blooms_db::Database::open(trace_blooms_path)?,
+		freezer: freezer_db::Database::open(freezer_path)?,
 	};
 
 	Ok(Arc::new(db))
@@ -0,0 +1,60 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::fs;
+
+use parity_crypto::publickey::{KeyPair, Secret};
+use sync::{Enr, NodeTree, TreeLink};
+
+/// Maximal length of a single string of a TXT record.
+const MAX_TXT_STRING_LEN: usize = 255;
+
+#[derive(Debug, PartialEq)]
+pub struct DnsTreeCmd {
+	pub domain: String,
+	pub key: Secret,
+	pub seq: u64,
+	pub links: Vec<String>,
+	pub file: String,
+}
+
+/// Builds a node tree of the records listed in the file and returns the zone file entries
+/// publishing it, preceded by the location of the tree.
+pub fn execute(cmd: DnsTreeCmd) -> Result<String, String> {
+	let key = KeyPair::from_secret(cmd.key).map_err(|e| format!("Invalid key: {}", e))?;
+	let records = fs::read_to_string(&cmd.file)
+		.map_err(|e| format!("Unable to read {}: {}", cmd.file, e))?
+		.lines()
+		.map(str::trim)
+		.filter(|line| !line.is_empty() && !line.starts_with('#'))
+		.map(|line| line.parse::<Enr>().map_err(|e| format!("Invalid node record {}: {}", line, e)))
+		.collect::<Result<Vec<_>, _>>()?;
+	let links = cmd.links.iter()
+		.map(|link| link.parse::<TreeLink>().map_err(|e| format!("Invalid tree location {}: {}", link, e)))
+		.collect::<Result<Vec<_>, _>>()?;
+	let tree = NodeTree::new(&key, cmd.seq, &records, &links).map_err(|e| format!("Unable to sign the tree: {}", e))?;
+
+	let mut zone = vec![format!("; {}", TreeLink::new(key.public(), &cmd.domain))];
+	zone.extend(tree.txt_records(&cmd.domain).into_iter().map(|(name, txt)| {
+		// entries are ASCII, longer ones are split into several strings
+		let strings: Vec<_> = txt.as_bytes()
+			.chunks(MAX_TXT_STRING_LEN)
+			.map(|chunk| format!("\"{}\"", String::from_utf8_lossy(chunk)))
+			.collect();
+		format!("{}. IN TXT {}", name, strings.join(" "))
+	}));
+	Ok(zone.join("\n"))
+}
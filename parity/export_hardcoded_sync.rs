@@ -65,7 +65,7 @@ pub fn execute(cmd: ExportHsyncCmd) -> Result<String, String> {
 	let algorithm = cmd.pruning.to_algorithm(&user_defaults);
 
 	// execute upgrades
-	execute_upgrades(&cmd.dirs.base, &db_dirs, algorithm, &cmd.compaction)?;
+	execute_upgrades(&cmd.dirs.base, &db_dirs, algorithm, &cmd.compaction, None)?;
 
 	// create dirs used by parity
 	cmd.dirs.create_dirs(false, false)?;
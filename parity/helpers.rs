@@ -185,6 +185,18 @@ pub fn parity_ipc_path(base: &str, path: &str, shift: u16) -> String {
 	replace_home(base, &path)
 }
 
+/// Validates the locations of DNS node trees (EIP-1459).
+pub fn to_dns_trees(trees: &Option<String>) -> Result<Vec<String>, String> {
+	match *trees {
+		Some(ref x) if !x.is_empty() => x.split(',').map(|s| {
+			s.parse::<sync::TreeLink>()
+				.map(|_| s.to_owned())
+				.map_err(|_| format!("Invalid DNS node tree location given: {}", s))
+		}).collect(),
+		_ => Ok(vec![]),
+	}
+}
+
 /// Validates and formats bootnodes option.
 pub fn to_bootnodes(bootnodes: &Option<String>) -> Result<Vec<String>, String> {
 	match *bootnodes {
@@ -215,6 +227,7 @@ pub fn default_network_config() -> ::sync::NetworkConfiguration {
 		nat_type: NatType::Any,
 		discovery_enabled: true,
 		boot_nodes: Vec::new(),
+		dns_discovery: Vec::new(),
 		use_secret: None,
 		max_peers: 50,
 		min_peers: 25,
@@ -280,7 +293,8 @@ pub fn execute_upgrades(
 	base_path: &str,
 	dirs: &DatabaseDirectories,
 	pruning: Algorithm,
-	compaction_profile: &DatabaseCompactionProfile
+	compaction_profile: &DatabaseCompactionProfile,
+	freezer_depth: Option<u64>,
 ) -> Result<(), String> {
 
 	upgrade_data_paths(base_path, dirs, pruning);
@@ -296,7 +310,7 @@ pub fn execute_upgrades(
 	}
 
 	let client_path = dirs.db_path(pruning);
-	migrate(&client_path, compaction_profile).map_err(|e| format!("{}", e))
+	migrate(&client_path, compaction_profile, freezer_depth).map_err(|e| format!("{}", e))
 }
 
 /// Prompts user asking for password.
@@ -358,7 +372,7 @@ mod tests {
 		ids::BlockId,
 		client_types::Mode,
 	};
-	use super::{to_duration, to_mode, to_block_id, to_u256, to_pending_set, to_address, to_addresses, to_price, geth_ipc_path, to_bootnodes, join_set, password_from_file};
+	use super::{to_duration, to_mode, to_block_id, to_u256, to_pending_set, to_address, to_addresses, to_price, geth_ipc_path, to_bootnodes, to_dns_trees, join_set, password_from_file};
 
 	#[test]
 	fn test_to_duration() {
@@ -499,6 +513,15 @@ but the first password is trimmed
 		assert_eq!(to_bootnodes(&Some(two_bootnodes.into())), Ok(vec![one_bootnode.into(), one_bootnode.into()]));
 	}
 
+	#[test]
+	fn test_to_dns_trees() {
+		let tree = "enrtree://AKPYQIUQIL7PSIACI32J7FGZW56E5FKHEFCCOFHILBIMW3M6LWXS2@nodes.example.org";
+
+		assert_eq!(to_dns_trees(&None), Ok(vec![]));
+		assert_eq!(to_dns_trees(&Some(tree.into())), Ok(vec![tree.into()]));
+		assert!(to_dns_trees(&Some("enrtree://nodes.example.org".into())).is_err());
+	}
+
 	#[test]
 	fn test_join_set() {
 		let mut test_set = HashSet::new();
@@ -40,6 +40,7 @@ extern crate serde_derive;
 extern crate toml;
 
 extern crate blooms_db;
+extern crate freezer_db;
 extern crate cli_signer;
 
 extern crate client_traits;
@@ -108,6 +109,7 @@ mod configuration;
 mod export_hardcoded_sync;
 mod ipfs;
 mod deprecated;
+mod dns_tree;
 mod helpers;
 mod informant;
 mod light_helpers;
@@ -216,6 +218,7 @@ fn execute<Cr, Rr>(
 		},
 		Cmd::Version => Ok(ExecutionAction::Instant(Some(Args::print_version()))),
 		Cmd::Hash(maybe_file) => print_hash_of(maybe_file).map(|s| ExecutionAction::Instant(Some(s))),
+		Cmd::DnsTree(dns_tree_cmd) => dns_tree::execute(dns_tree_cmd).map(|s| ExecutionAction::Instant(Some(s))),
 		Cmd::Account(account_cmd) => account::execute(account_cmd).map(|s| ExecutionAction::Instant(Some(s))),
 		Cmd::ImportPresaleWallet(presale_cmd) => presale::execute(presale_cmd).map(|s| ExecutionAction::Instant(Some(s))),
 		Cmd::Blockchain(blockchain_cmd) => blockchain::execute(blockchain_cmd).map(|_| ExecutionAction::Instant(None)),
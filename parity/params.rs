@@ -245,6 +245,7 @@ pub struct AccountsConfig {
 	pub password_files: Vec<String>,
 	pub unlocked_accounts: Vec<Address>,
 	pub enable_fast_unlock: bool,
+	pub signer_endpoint: Option<String>,
 }
 
 impl Default for AccountsConfig {
@@ -256,6 +257,7 @@ impl Default for AccountsConfig {
 			password_files: Vec::new(),
 			unlocked_accounts: Vec::new(),
 			enable_fast_unlock: false,
+			signer_endpoint: None,
 		}
 	}
 }
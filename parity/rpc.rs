@@ -29,6 +29,7 @@ use parity_rpc::{self as rpc, Metadata, DomainsValidation};
 use rpc_apis::{self, ApiSet};
 
 pub use parity_rpc::{IpcServer, HttpServer, RequestMiddleware};
+pub use parity_rpc::graphql::Server as GraphQLServer;
 pub use parity_rpc::ws::{Server as WsServer, ws};
 
 pub const DAPPS_DOMAIN: &'static str = "web3.site";
@@ -126,6 +127,35 @@ impl Default for WsConfiguration {
 	}
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphQLConfiguration {
+	/// Is the GraphQL server enabled (default is false)?
+	pub enabled: bool,
+	/// The IP of the network interface used (default is 127.0.0.1).
+	pub interface: String,
+	/// The network port (default is 8547).
+	pub port: u16,
+	/// CORS headers
+	pub cors: Option<Vec<String>>,
+	/// Specify a list of valid hosts we accept requests from.
+	pub hosts: Option<Vec<String>>,
+	/// Number of threads executing queries.
+	pub server_threads: usize,
+}
+
+impl Default for GraphQLConfiguration {
+	fn default() -> Self {
+		GraphQLConfiguration {
+			enabled: false,
+			interface: "127.0.0.1".into(),
+			port: 8547,
+			cors: Some(vec![]),
+			hosts: Some(vec![]),
+			server_threads: 4,
+		}
+	}
+}
+
 impl WsConfiguration {
 	pub fn address(&self) -> Option<rpc::Host> {
 		address(self.enabled, &self.interface, self.port, &self.hosts)
@@ -278,6 +308,30 @@ pub fn new_ipc<D: rpc_apis::Dependencies>(
 	}
 }
 
+pub fn new_graphql(
+	conf: GraphQLConfiguration,
+	deps: &rpc_apis::FullDependencies,
+) -> Result<Option<GraphQLServer>, String> {
+	if !conf.enabled {
+		return Ok(None);
+	}
+
+	let url = format!("{}:{}", conf.interface, conf.port);
+	let addr = url.parse().map_err(|_| format!("Invalid GraphQL listen host/port given: {}", url))?;
+
+	let cors_domains = into_domains(conf.cors);
+	let allowed_hosts = into_domains(with_domain(conf.hosts, DAPPS_DOMAIN, &Some(url.clone().into())));
+	let backend = rpc::graphql::FullBackend::new(&deps.client, &deps.miner, deps.gas_price_oracle);
+
+	match rpc::graphql::start_server(&addr, cors_domains, allowed_hosts, conf.server_threads, Arc::new(backend)) {
+		Ok(server) => Ok(Some(server)),
+		Err(ref err) if err.kind() == io::ErrorKind::AddrInUse => Err(
+			format!("GraphQL address {} is already in use, make sure that another instance of an Ethereum client is not running or change the address using the --graphql-port and --graphql-interface options.", url)
+		),
+		Err(e) => Err(format!("GraphQL error: {:?}", e)),
+	}
+}
+
 fn into_domains<T: From<String>>(items: Option<Vec<String>>) -> DomainsValidation<T> {
 	items.map(|vals| vals.into_iter().map(T::from).collect()).into()
 }
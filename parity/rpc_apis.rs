@@ -35,9 +35,9 @@ use jsonrpc_core::{self as core, MetaIoHandler};
 use light::client::LightChainClient;
 use light::{Cache as LightDataCache, TransactionQueue as LightTransactionQueue};
 use miner::external::ExternalMiner;
-use parity_rpc::dispatch::{FullDispatcher, LightDispatcher};
+use parity_rpc::dispatch::{FullDispatcher, LightDispatcher, RemoteSigner};
 use parity_rpc::informant::{ActivityNotifier, ClientNotifier};
-use parity_rpc::{Host, Metadata, NetworkSettings};
+use parity_rpc::{GasPriceOracleConfig, Host, Metadata, NetworkSettings};
 use parity_rpc::v1::traits::TransactionsPool;
 use parity_runtime::Executor;
 use parking_lot::{Mutex, RwLock};
@@ -79,6 +79,8 @@ pub enum Api {
 	Debug,
 	/// Parity Transactions pool PubSub
 	ParityTransactionsPool,
+	/// Bundles - Private, atomic inclusion of transactions (UNSAFE: Side Effects affecting block production)
+	Bundle,
 	/// Deprecated api
 	Deprecated,
 }
@@ -90,6 +92,7 @@ impl FromStr for Api {
 		use self::Api::*;
 
 		match s {
+			"bundle" => Ok(Bundle),
 			"debug" => Ok(Debug),
 			"eth" => Ok(Eth),
 			"net" => Ok(Net),
@@ -173,6 +176,7 @@ fn to_modules(apis: &HashSet<Api>) -> BTreeMap<String, String> {
 	let mut modules = BTreeMap::new();
 	for api in apis {
 		let (name, version) = match *api {
+			Api::Bundle => ("bundle", "1.0"),
 			Api::Debug => ("debug", "1.0"),
 			Api::Eth => ("eth", "1.0"),
 			Api::EthPubSub => ("pubsub", "1.0"),
@@ -239,6 +243,8 @@ pub struct FullDependencies {
 	pub sync: Arc<dyn SyncProvider>,
 	pub net: Arc<dyn ManageNetwork>,
 	pub accounts: Arc<AccountProvider>,
+	/// External signer replacing the local accounts for signing, if configured.
+	pub remote_signer: Option<Arc<RemoteSigner>>,
 	pub private_tx_service: Option<Arc<PrivateTxService>>,
 	pub miner: Arc<Miner>,
 	pub external_miner: Arc<ExternalMiner>,
@@ -251,7 +257,7 @@ pub struct FullDependencies {
 	pub ws_address: Option<Host>,
 	pub fetch: FetchClient,
 	pub executor: Executor,
-	pub gas_price_percentile: usize,
+	pub gas_price_oracle: GasPriceOracleConfig,
 	pub poll_lifetime: u32,
 	pub allow_missing_blocks: bool,
 	pub no_ancient_blocks: bool,
@@ -275,13 +281,25 @@ impl FullDependencies {
 			self.client.clone(),
 			self.miner.clone(),
 			nonces.clone(),
-			self.gas_price_percentile,
+			self.gas_price_oracle,
 		);
-		let account_signer = Arc::new(dispatch::Signer::new(self.accounts.clone())) as _;
-		let accounts = account_utils::accounts_list(self.accounts.clone());
+		let (account_signer, accounts) = match self.remote_signer {
+			Some(ref signer) => {
+				let remote = signer.clone();
+				let accounts: Arc<dyn Fn() -> Vec<ethereum_types::Address> + Send + Sync> = Arc::new(move || remote.accounts().unwrap_or_default());
+				(signer.clone() as Arc<dyn dispatch::Accounts>, accounts)
+			},
+			None => (
+				Arc::new(dispatch::Signer::new(self.accounts.clone())) as Arc<dyn dispatch::Accounts>,
+				account_utils::accounts_list(self.accounts.clone()),
+			),
+		};
 
 		for api in apis {
 			match *api {
+				Api::Bundle => {
+					handler.extend_with(EthBundleClient::new(&self.client, &self.miner).to_delegate());
+				}
 				Api::Debug => {
 					handler.extend_with(DebugClient::new(self.client.clone()).to_delegate());
 				}
@@ -303,7 +321,7 @@ impl FullDependencies {
 							pending_nonce_from_queue: self.geth_compatibility,
 							allow_pending_receipt_query: !self.geth_compatibility,
 							send_block_number_in_get_work: !self.geth_compatibility,
-							gas_price_percentile: self.gas_price_percentile,
+							gas_price_oracle: self.gas_price_oracle,
 							allow_missing_blocks: self.allow_missing_blocks,
 							allow_experimental_rpcs: self.experimental_rpcs,
 							no_ancient_blocks: self.no_ancient_blocks
@@ -498,7 +516,7 @@ pub struct LightDependencies<T> {
 	pub experimental_rpcs: bool,
 	pub executor: Executor,
 	pub private_tx_service: Option<Arc<PrivateTransactionManager>>,
-	pub gas_price_percentile: usize,
+	pub gas_price_oracle: GasPriceOracleConfig,
 	pub poll_lifetime: u32,
 }
 
@@ -520,13 +538,16 @@ impl<C: LightChainClient + 'static> LightDependencies<C> {
 			Arc::new(Mutex::new(dispatch::Reservations::new(
 				self.executor.clone(),
 			))),
-			self.gas_price_percentile,
+			self.gas_price_oracle,
 		);
 		let account_signer = Arc::new(dispatch::Signer::new(self.accounts.clone())) as _;
 		let accounts = account_utils::accounts_list(self.accounts.clone());
 
 		for api in apis {
 			match *api {
+				Api::Bundle => {
+					warn!(target: "rpc", "Bundle API is not available in light client mode.")
+				}
 				Api::Debug => {
 					warn!(target: "rpc", "Debug API is not available in light client mode.")
 				}
@@ -544,7 +565,7 @@ impl<C: LightChainClient + 'static> LightDependencies<C> {
 						self.transaction_queue.clone(),
 						accounts.clone(),
 						self.cache.clone(),
-						self.gas_price_percentile,
+						self.gas_price_oracle,
 						self.poll_lifetime,
 					);
 					handler.extend_with(Eth::to_delegate(client.clone()));
@@ -563,7 +584,7 @@ impl<C: LightChainClient + 'static> LightDependencies<C> {
 						self.sync.clone(),
 						self.cache.clone(),
 						self.executor.clone(),
-						self.gas_price_percentile,
+						self.gas_price_oracle,
 						receiver
 					);
 
@@ -624,7 +645,7 @@ impl<C: LightChainClient + 'static> LightDependencies<C> {
 							self.settings.clone(),
 							signer,
 							self.ws_address.clone(),
-							self.gas_price_percentile,
+							self.gas_price_oracle,
 						).to_delegate(),
 					);
 					#[cfg(feature = "accounts")]
@@ -740,6 +761,7 @@ impl ApiSet {
 				public_list.insert(Api::Personal);
 				public_list.insert(Api::SecretStore);
 				public_list.insert(Api::ParityTransactionsPool);
+				public_list.insert(Api::Bundle);
 				public_list
 			}
 			ApiSet::PubSub => [
@@ -778,6 +800,7 @@ mod test {
 		assert_eq!(Api::SecretStore, "secretstore".parse().unwrap());
 		assert_eq!(Api::Private, "private".parse().unwrap());
 		assert_eq!(Api::ParityTransactionsPool, "parity_transactions_pool".parse().unwrap());
+		assert_eq!(Api::Bundle, "bundle".parse().unwrap());
 		assert!("rp".parse::<Api>().is_err());
 	}
 
@@ -856,6 +879,7 @@ mod test {
 					Api::Private,
 					Api::Debug,
 					Api::ParityTransactionsPool,
+					Api::Bundle,
 				].into_iter()
 				.collect()
 			)
@@ -883,6 +907,7 @@ mod test {
 					Api::Private,
 					Api::Debug,
 					Api::ParityTransactionsPool,
+					Api::Bundle,
 				].into_iter()
 				.collect()
 			)
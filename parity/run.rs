@@ -35,6 +35,7 @@ use journaldb::Algorithm;
 use light::Cache as LightDataCache;
 use miner::external::ExternalMiner;
 use miner::work_notify::WorkPoster;
+use network;
 use node_filter::NodeFilter;
 use parity_runtime::Runtime;
 use sync::{self, SyncConfig, PrivateTxHandler};
@@ -44,7 +45,8 @@ use types::{
 	snapshot::Snapshotting,
 };
 use parity_rpc::{
-	Origin, Metadata, NetworkSettings, informant, PubSubSession, FutureResult, FutureResponse, FutureOutput
+	Origin, Metadata, NetworkSettings, GasPriceOracleConfig, informant, PubSubSession, FutureResult, FutureResponse,
+	FutureOutput, dispatch
 };
 use updater::{UpdatePolicy, Updater};
 use parity_version::version;
@@ -92,11 +94,14 @@ pub struct RunCmd {
 	pub pruning: Pruning,
 	pub pruning_history: u64,
 	pub pruning_memory: usize,
+	pub state_pruning_interval: u64,
+	pub state_pruning_bloom_size: u32,
+	pub freezer_depth: u64,
 	/// Some if execution should be daemonized. Contains pid_file path.
 	pub daemon: Option<String>,
 	pub logger_config: LogConfig,
 	pub miner_options: MinerOptions,
-	pub gas_price_percentile: usize,
+	pub gas_price_oracle: GasPriceOracleConfig,
 	pub poll_lifetime: u32,
 	pub ws_conf: rpc::WsConfiguration,
 	pub http_conf: rpc::HttpConfiguration,
@@ -105,6 +110,7 @@ pub struct RunCmd {
 	pub network_id: Option<u64>,
 	pub warp_sync: bool,
 	pub warp_barrier: Option<u64>,
+	pub snap_sync: bool,
 	pub acc_conf: AccountsConfig,
 	pub gas_pricer_conf: GasPricerConfig,
 	pub miner_extras: MinerExtras,
@@ -117,6 +123,7 @@ pub struct RunCmd {
 	pub experimental_rpcs: bool,
 	pub net_settings: NetworkSettings,
 	pub ipfs_conf: ipfs::Configuration,
+	pub graphql_conf: rpc::GraphQLConfiguration,
 	pub secretstore_conf: secretstore::Configuration,
 	pub private_provider_conf: ProviderConfig,
 	pub private_encryptor_conf: EncryptorConfig,
@@ -132,6 +139,7 @@ pub struct RunCmd {
 	pub serve_light: bool,
 	pub light: bool,
 	pub no_persistent_txqueue: bool,
+	pub tx_queue_journal: Option<::local_store::journal::Options>,
 	pub no_hardcoded_sync: bool,
 	pub max_round_blocks_to_import: usize,
 	pub on_demand_response_time_window: Option<u64>,
@@ -163,6 +171,24 @@ impl ::local_store::NodeInfo for FullNodeInfo {
 	}
 }
 
+// transaction queue fetcher for the transaction journal.
+struct FullPoolInfo {
+	miner: Arc<Miner>,
+}
+
+impl ::local_store::journal::PoolInfo for FullPoolInfo {
+	fn queued_transactions(&self) -> Vec<::types::transaction::SignedTransaction> {
+		use miner::pool::{Priority, ScoredTransaction};
+
+		// local transactions are persisted by the local store.
+		self.miner.queued_transactions()
+			.into_iter()
+			.filter(|tx| tx.priority() != Priority::Local)
+			.map(|tx| tx.signed().clone())
+			.collect()
+	}
+}
+
 type LightClient = ::light::client::Client<::light_helpers::EpochFetch>;
 
 // helper for light execution.
@@ -192,7 +218,7 @@ fn execute_light_impl<Cr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq
 	let algorithm = cmd.pruning.to_algorithm(&user_defaults);
 
 	// execute upgrades
-	execute_upgrades(&cmd.dirs.base, &db_dirs, algorithm, &cmd.compaction)?;
+	execute_upgrades(&cmd.dirs.base, &db_dirs, algorithm, &cmd.compaction, None)?;
 
 	// create dirs used by parity
 	cmd.dirs.create_dirs(cmd.acc_conf.unlocked_accounts.len() == 0, cmd.secretstore_conf.enabled)?;
@@ -321,7 +347,7 @@ fn execute_light_impl<Cr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq
 		experimental_rpcs: cmd.experimental_rpcs,
 		executor: runtime.executor(),
 		private_tx_service: None, //TODO: add this to client.
-		gas_price_percentile: cmd.gas_price_percentile,
+		gas_price_oracle: cmd.gas_price_oracle,
 		poll_lifetime: cmd.poll_lifetime
 	});
 
@@ -337,6 +363,10 @@ fn execute_light_impl<Cr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq
 	let http_server = rpc::new_http("HTTP JSON-RPC", "jsonrpc", cmd.http_conf.clone(), &dependencies)?;
 	let ipc_server = rpc::new_ipc(cmd.ipc_conf, &dependencies)?;
 
+	if cmd.graphql_conf.enabled {
+		warn!("The GraphQL server is not available for the light client. Ignoring --graphql.");
+	}
+
 	// the informant
 	let informant = Arc::new(Informant::new(
 		LightNodeInformantData {
@@ -409,8 +439,14 @@ fn execute_impl<Cr, Rr>(
 	let client_path = db_dirs.client_path(algorithm);
 	let snapshot_path = db_dirs.snapshot_path();
 
+	// the freezer is only used by the full client, 0 disables it.
+	let freezer_depth = match cmd.freezer_depth {
+		0 => None,
+		depth => Some(depth),
+	};
+
 	// execute upgrades
-	execute_upgrades(&cmd.dirs.base, &db_dirs, algorithm, &cmd.compaction)?;
+	execute_upgrades(&cmd.dirs.base, &db_dirs, algorithm, &cmd.compaction, freezer_depth)?;
 
 	// create dirs used by parity
 	cmd.dirs.create_dirs(cmd.acc_conf.unlocked_accounts.len() == 0, cmd.secretstore_conf.enabled)?;
@@ -443,13 +479,14 @@ fn execute_impl<Cr, Rr>(
 		Some(id) => id,
 		None => spec.network_id(),
 	};
-	if spec.subprotocol_name().len() != 3 {
-		warn!("Your chain specification's subprotocol length is not 3. Ignoring.");
-	} else {
-		sync_config.subprotocol_name.clone_from_slice(spec.subprotocol_name().as_bytes());
+	match network::protocol_id(spec.subprotocol_name().as_bytes()) {
+		Some(subprotocol_name) if spec.subprotocol_name().len() == 3 => sync_config.subprotocol_name = subprotocol_name,
+		_ => warn!("Your chain specification's subprotocol length is not 3. Ignoring."),
 	}
 
 	sync_config.fork_block = spec.fork_block();
+	sync_config.hard_forks = spec.hard_forks.clone();
+	sync_config.eip1559_transition = spec.params().eip1559_transition;
 	let snapshot_supported =
 		if let Snapshotting::Unsupported = spec.engine.snapshot_mode() {
 			false
@@ -476,10 +513,22 @@ fn execute_impl<Cr, Rr>(
 		(true, _) => sync::WarpSync::Enabled,
 		_ => sync::WarpSync::Disabled,
 	};
+	let mut snap_sync = cmd.snap_sync;
+	if snap_sync && (fat_db || tracing || algorithm != Algorithm::OverlayRecent) {
+		warn!("Warning: Snap Sync is disabled because of Fat DB, tracing or non-default pruning mode.");
+		snap_sync = false;
+	}
+	sync_config.snap_sync = snap_sync;
+	let mut state_pruning_interval = cmd.state_pruning_interval;
+	if state_pruning_interval > 0 && algorithm != Algorithm::OverlayRecent {
+		warn!("Warning: Background state pruning is disabled because of the {} pruning mode.", algorithm.as_str());
+		state_pruning_interval = 0;
+	}
 	sync_config.download_old_blocks = cmd.download_old_blocks;
 	sync_config.serve_light = cmd.serve_light;
 
 	let passwords = passwords_from_files(&cmd.acc_conf.password_files)?;
+	let signer_endpoint = cmd.acc_conf.signer_endpoint.clone();
 
 	// prepare account provider
 	let account_provider = Arc::new(account_utils::prepare_account_provider(&cmd.spec, &cmd.dirs, &spec.data_dir, cmd.acc_conf, &passwords)?);
@@ -491,6 +540,15 @@ fn execute_impl<Cr, Rr>(
 	// fetch service
 	let fetch = fetch::Client::new(FETCH_FULL_NUM_DNS_THREADS).map_err(|e| format!("Error starting fetch client: {:?}", e))?;
 
+	// external signer handling the rpc signing requests instead of the local accounts
+	let remote_signer = match signer_endpoint {
+		Some(endpoint) => {
+			info!("Forwarding signing requests to the external signer at {}", Colour::White.bold().paint(endpoint.as_str()));
+			Some(Arc::new(dispatch::RemoteSigner::with_endpoint(&endpoint, fetch.clone())?))
+		},
+		None => None,
+	};
+
 	let txpool_size = cmd.miner_options.pool_limits.max_count;
 	// create miner
 	let miner = Arc::new(Miner::new(
@@ -543,6 +601,9 @@ fn execute_impl<Cr, Rr>(
 	client_config.queue.verifier_settings = cmd.verifier_settings;
 	client_config.transaction_verification_queue_size = ::std::cmp::max(2048, txpool_size / 4);
 	client_config.snapshot = cmd.snapshot_conf.clone();
+	client_config.state_pruning_interval = state_pruning_interval;
+	client_config.state_pruning_bloom_size = cmd.state_pruning_bloom_size as usize * 1024 * 1024;
+	client_config.blockchain.freezer_depth = freezer_depth;
 
 	// set up bootnodes
 	let mut net_conf = cmd.net_conf;
@@ -629,6 +690,27 @@ fn execute_impl<Cr, Rr>(
 	// register it as an IO service to update periodically.
 	service.register_io_handler(store).map_err(|_| "Unable to register local store handler".to_owned())?;
 
+	// initialize the journal of the whole transaction queue.
+	if let Some(journal_options) = cmd.tx_queue_journal.clone() {
+		let journal = ::local_store::journal::open(db_dirs.tx_queue_journal_path(), journal_options, FullPoolInfo { miner: miner.clone() });
+
+		// re-import journaled transactions, verifying them again.
+		match journal.load() {
+			Ok(transactions) => {
+				let count = transactions.len();
+				let imported = miner.import_external_transactions(&*client, transactions)
+					.into_iter()
+					.filter(Result::is_ok)
+					.count();
+				info!("Re-imported {} out of {} journaled transactions.", imported, count);
+			}
+			Err(e) => warn!("Error loading transaction queue journal: {}", e),
+		}
+
+		// register it as an IO service to rotate periodically.
+		service.register_io_handler(Arc::new(journal)).map_err(|_| "Unable to register transaction journal handler".to_owned())?;
+	}
+
 	// create external miner
 	let external_miner = Arc::new(ExternalMiner::default());
 
@@ -717,6 +799,7 @@ fn execute_impl<Cr, Rr>(
 		sync: sync_provider.clone(),
 		net: manage_network.clone(),
 		accounts: secret_store,
+		remote_signer,
 		miner: miner.clone(),
 		external_miner: external_miner.clone(),
 		logger: logger.clone(),
@@ -729,7 +812,7 @@ fn execute_impl<Cr, Rr>(
 		fetch: fetch.clone(),
 		executor: runtime.executor(),
 		private_tx_service: Some(private_tx_service.clone()),
-		gas_price_percentile: cmd.gas_price_percentile,
+		gas_price_oracle: cmd.gas_price_oracle,
 		poll_lifetime: cmd.poll_lifetime,
 		allow_missing_blocks: cmd.allow_missing_blocks,
 		no_ancient_blocks: !cmd.download_old_blocks,
@@ -746,6 +829,7 @@ fn execute_impl<Cr, Rr>(
 	let ws_server = rpc::new_ws(cmd.ws_conf.clone(), &dependencies)?;
 	let ipc_server = rpc::new_ipc(cmd.ipc_conf, &dependencies)?;
 	let http_server = rpc::new_http("HTTP JSON-RPC", "jsonrpc", cmd.http_conf.clone(), &dependencies)?;
+	let graphql_server = rpc::new_graphql(cmd.graphql_conf.clone(), &deps_for_rpc_apis)?;
 
 	// secret store key server
 	let secretstore_deps = secretstore::Dependencies {
@@ -817,7 +901,7 @@ fn execute_impl<Cr, Rr>(
 			informant,
 			client,
 			client_service: Arc::new(service),
-			keep_alive: Box::new((watcher, updater, ws_server, http_server, ipc_server, secretstore_key_server, ipfs_server, runtime)),
+			keep_alive: Box::new((watcher, updater, ws_server, http_server, ipc_server, secretstore_key_server, ipfs_server, graphql_server, runtime)),
 		}
 	})
 }
@@ -170,7 +170,7 @@ impl SnapshotCommand {
 		let snapshot_path = db_dirs.snapshot_path();
 
 		// execute upgrades
-		execute_upgrades(&self.dirs.base, &db_dirs, algorithm, &self.compaction)?;
+		execute_upgrades(&self.dirs.base, &db_dirs, algorithm, &self.compaction, None)?;
 
 		// prepare client config
 		let mut client_config = to_client_config(
@@ -0,0 +1,167 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! GraphQL interface to the chain as specified by EIP-1767.
+//!
+//! Lets clients fetch exactly the block, transaction, receipt and log fields they need in a
+//! single request instead of issuing a series of JSON-RPC calls.
+
+mod scalars;
+mod schema;
+mod server;
+#[cfg(test)]
+mod tests;
+
+use std::sync::Arc;
+
+use account_state::state::StateInfo;
+use client_traits::{BlockChainClient, StateClient};
+use ethcore::client::{Call, EngineInfo};
+use ethcore::miner::{self, MinerService};
+use ethereum_types::{H256, U256};
+use jsonrpc_core::Error;
+use juniper::{self, FieldError};
+use machine::executed::Executed;
+use serde_json;
+use types::{
+	BlockNumber,
+	ids::BlockId,
+	transaction::{SignedTransaction, UnverifiedTransaction},
+};
+
+use v1::helpers::{errors, GasPriceOracleConfig};
+use v1::helpers::dispatch::{FullDispatcher, default_gas_price};
+
+pub use self::schema::{Query, Mutation};
+pub use self::server::{Server, start_server};
+
+/// GraphQL schema served by the node.
+pub type Schema = juniper::RootNode<'static, Query, Mutation>;
+
+/// Creates the GraphQL schema.
+pub fn schema() -> Schema {
+	Schema::new(Query, Mutation)
+}
+
+/// Chain access needed to resolve GraphQL queries.
+pub trait Backend: Send + Sync {
+	/// Blockchain client used for reads.
+	fn client(&self) -> &dyn BlockChainClient;
+
+	/// Block number at which EIP-1559 headers are activated.
+	fn eip1559_transition(&self) -> BlockNumber;
+
+	/// Suggested gas price.
+	fn gas_price(&self) -> U256;
+
+	/// Executes a transaction on top of the state of the given block without committing it.
+	fn call(&self, transaction: &SignedTransaction, block: BlockId) -> Result<Executed, Error>;
+
+	/// Estimates the gas a transaction needs on top of the state of the given block.
+	fn estimate_gas(&self, transaction: &SignedTransaction, block: BlockId) -> Result<U256, Error>;
+
+	/// Imports a signed, RLP-encoded transaction into the queue and returns its hash.
+	fn send_raw_transaction(&self, raw: &[u8]) -> Result<H256, Error>;
+}
+
+/// `Backend` of a full node, backed by the same client and miner as `EthClient`.
+pub struct FullBackend<C, M> {
+	client: Arc<C>,
+	miner: Arc<M>,
+	gas_price_oracle: GasPriceOracleConfig,
+}
+
+impl<C, M> FullBackend<C, M> {
+	/// Creates new `FullBackend`.
+	pub fn new(client: &Arc<C>, miner: &Arc<M>, gas_price_oracle: GasPriceOracleConfig) -> Self {
+		FullBackend {
+			client: client.clone(),
+			miner: miner.clone(),
+			gas_price_oracle,
+		}
+	}
+}
+
+impl<C, M, T> FullBackend<C, M> where
+	C: miner::BlockChainClient + BlockChainClient + StateClient<State=T> + Call<State=T> + EngineInfo,
+	T: StateInfo + 'static,
+	M: MinerService<State=T>,
+{
+	fn state_and_header(&self, block: BlockId) -> Result<(T, ::types::header::Header), Error> {
+		let state = self.client.state_at(block).ok_or_else(errors::state_pruned)?;
+		let header = self.client.block_header(block).ok_or_else(errors::state_pruned)
+			.and_then(|h| h.decode(self.eip1559_transition()).map_err(errors::decode))?;
+		Ok((state, header))
+	}
+}
+
+impl<C, M, T> Backend for FullBackend<C, M> where
+	C: miner::BlockChainClient + BlockChainClient + StateClient<State=T> + Call<State=T> + EngineInfo + 'static,
+	T: StateInfo + 'static,
+	M: MinerService<State=T> + 'static,
+{
+	fn client(&self) -> &dyn BlockChainClient {
+		&*self.client
+	}
+
+	fn eip1559_transition(&self) -> BlockNumber {
+		self.client.engine().params().eip1559_transition
+	}
+
+	fn gas_price(&self) -> U256 {
+		default_gas_price(&*self.client, &*self.miner, &self.gas_price_oracle)
+	}
+
+	fn call(&self, transaction: &SignedTransaction, block: BlockId) -> Result<Executed, Error> {
+		let (mut state, header) = self.state_and_header(block)?;
+		self.client.call(transaction, Default::default(), &mut state, &header).map_err(errors::call)
+	}
+
+	fn estimate_gas(&self, transaction: &SignedTransaction, block: BlockId) -> Result<U256, Error> {
+		let (state, header) = self.state_and_header(block)?;
+		self.client.estimate_gas(transaction, &state, &header).map_err(errors::call)
+	}
+
+	fn send_raw_transaction(&self, raw: &[u8]) -> Result<H256, Error> {
+		UnverifiedTransaction::decode_raw(raw)
+			.map_err(errors::rlp)
+			.and_then(|tx| SignedTransaction::new(tx).map_err(errors::transaction))
+			.and_then(|signed| FullDispatcher::dispatch_transaction(&*self.client, &*self.miner, signed.into(), false))
+	}
+}
+
+/// Context shared by all resolvers of a request.
+pub struct Context {
+	backend: Arc<dyn Backend>,
+}
+
+impl Context {
+	/// Creates new `Context`.
+	pub fn new(backend: Arc<dyn Backend>) -> Self {
+		Context { backend }
+	}
+}
+
+impl juniper::Context for Context {}
+
+/// Converts a JSON-RPC error into a GraphQL field error.
+fn field_error(err: Error) -> FieldError {
+	let message = match err.data {
+		Some(serde_json::Value::String(ref data)) => format!("{} ({})", err.message, data),
+		_ => err.message,
+	};
+	FieldError::new(message, juniper::Value::null())
+}
@@ -0,0 +1,206 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Custom scalars of the EIP-1767 schema. All of them are represented as hex strings.
+
+use std::str::FromStr;
+
+use ethereum_types::{H160, H256, U256};
+use juniper::{DefaultScalarValue, InputValue, ParseScalarResult, ParseScalarValue, ScalarToken, Value};
+use rustc_hex::{FromHex, ToHex};
+
+/// 32 byte hash.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bytes32(pub H256);
+
+/// 20 byte account address.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Address(pub H160);
+
+/// Arbitrary length binary data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bytes(pub Vec<u8>);
+
+/// 256 bit unsigned integer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BigInt(pub U256);
+
+/// 64 bit unsigned integer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Long(pub u64);
+
+fn strip_hex_prefix(s: &str) -> Option<&str> {
+	if s.starts_with("0x") || s.starts_with("0X") {
+		Some(&s[2..])
+	} else {
+		None
+	}
+}
+
+/// Parses a hex string of a fixed length type, the `0x` prefix is mandatory.
+fn parse_fixed<T: FromStr>(s: &str, len: usize) -> Option<T> {
+	strip_hex_prefix(s)
+		.filter(|hex| hex.len() == len * 2)
+		.and_then(|hex| hex.parse().ok())
+}
+
+/// Parses an integer given either as a `0x` prefixed hex string or as a decimal string.
+fn parse_integer(s: &str) -> Option<U256> {
+	match strip_hex_prefix(s) {
+		Some(hex) if !hex.is_empty() && hex.len() <= 64 => U256::from_str(hex).ok(),
+		Some(_) => None,
+		None => U256::from_dec_str(s).ok(),
+	}
+}
+
+graphql_scalar!(Bytes32 {
+	description: "32 byte binary string, represented as 0x-prefixed hexadecimal."
+
+	resolve(&self) -> Value {
+		Value::scalar(format!("{:#x}", self.0))
+	}
+
+	from_input_value(v: &InputValue) -> Option<Bytes32> {
+		v.as_string_value().and_then(|s| parse_fixed(s, 32)).map(Bytes32)
+	}
+
+	from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, DefaultScalarValue> {
+		<String as ParseScalarValue>::from_str(value)
+	}
+});
+
+graphql_scalar!(Address {
+	description: "20 byte Ethereum address, represented as 0x-prefixed hexadecimal."
+
+	resolve(&self) -> Value {
+		Value::scalar(format!("{:#x}", self.0))
+	}
+
+	from_input_value(v: &InputValue) -> Option<Address> {
+		v.as_string_value().and_then(|s| parse_fixed(s, 20)).map(Address)
+	}
+
+	from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, DefaultScalarValue> {
+		<String as ParseScalarValue>::from_str(value)
+	}
+});
+
+graphql_scalar!(Bytes {
+	description: "Arbitrary length binary string, represented as 0x-prefixed hexadecimal. \
+		An empty byte string is represented as '0x'. Byte strings must have an even number of hexadecimal nybbles."
+
+	resolve(&self) -> Value {
+		Value::scalar(format!("0x{}", self.0.to_hex()))
+	}
+
+	from_input_value(v: &InputValue) -> Option<Bytes> {
+		v.as_string_value()
+			.and_then(strip_hex_prefix)
+			.and_then(|hex| hex.from_hex().ok())
+			.map(Bytes)
+	}
+
+	from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, DefaultScalarValue> {
+		<String as ParseScalarValue>::from_str(value)
+	}
+});
+
+graphql_scalar!(BigInt {
+	description: "Large integer. Input is accepted as either a JSON number or as a string. \
+		Strings may be either decimal or 0x-prefixed hexadecimal. Output values are all \
+		0x-prefixed hexadecimal."
+
+	resolve(&self) -> Value {
+		Value::scalar(format!("{:#x}", self.0))
+	}
+
+	from_input_value(v: &InputValue) -> Option<BigInt> {
+		match v.as_int_value() {
+			Some(n) if n >= 0 => Some(BigInt(n.into())),
+			Some(_) => None,
+			None => v.as_string_value().and_then(parse_integer).map(BigInt),
+		}
+	}
+
+	from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, DefaultScalarValue> {
+		match value {
+			ScalarToken::Int(_) => <i32 as ParseScalarValue>::from_str(value),
+			_ => <String as ParseScalarValue>::from_str(value),
+		}
+	}
+});
+
+graphql_scalar!(Long {
+	description: "64 bit unsigned integer. Input is accepted as either a JSON number or as a string. \
+		Strings may be either decimal or 0x-prefixed hexadecimal. Output values are all \
+		0x-prefixed hexadecimal."
+
+	resolve(&self) -> Value {
+		Value::scalar(format!("{:#x}", self.0))
+	}
+
+	from_input_value(v: &InputValue) -> Option<Long> {
+		match v.as_int_value() {
+			Some(n) if n >= 0 => Some(Long(n as u64)),
+			Some(_) => None,
+			None => v.as_string_value()
+				.and_then(parse_integer)
+				.filter(|n| *n <= U256::from(u64::max_value()))
+				.map(|n| Long(n.low_u64())),
+		}
+	}
+
+	from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, DefaultScalarValue> {
+		match value {
+			ScalarToken::Int(_) => <i32 as ParseScalarValue>::from_str(value),
+			_ => <String as ParseScalarValue>::from_str(value),
+		}
+	}
+});
+
+#[cfg(test)]
+mod tests {
+	use juniper::{FromInputValue, InputValue, ToInputValue};
+	use super::*;
+
+	#[test]
+	fn should_parse_integers() {
+		assert_eq!(parse_integer("0x2a"), Some(42.into()));
+		assert_eq!(parse_integer("42"), Some(42.into()));
+		assert_eq!(parse_integer("0x"), None);
+		assert_eq!(parse_integer("0xzz"), None);
+		assert_eq!(Long::from_input_value(&InputValue::scalar(42)), Some(Long(42)));
+		assert_eq!(Long::from_input_value(&InputValue::scalar(-1)), None);
+		assert_eq!(Long::from_input_value(&InputValue::scalar("0x10000000000000000")), None);
+	}
+
+	#[test]
+	fn should_parse_fixed_size_hex() {
+		let hash = "0x0000000000000000000000000000000000000000000000000000000000000001";
+		assert_eq!(Bytes32::from_input_value(&InputValue::scalar(hash)), Some(Bytes32(H256::from_low_u64_be(1))));
+		assert_eq!(Bytes32::from_input_value(&InputValue::scalar("0x01")), None);
+		assert_eq!(Address::from_input_value(&InputValue::scalar(&hash[..42])), Some(Address(H160::zero())));
+		assert_eq!(Bytes::from_input_value(&InputValue::scalar("0x")), Some(Bytes(vec![])));
+		assert_eq!(Bytes::from_input_value(&InputValue::scalar("0x123")), None);
+	}
+
+	#[test]
+	fn should_serialize_as_hex() {
+		assert_eq!(Long(42).to_input_value(), InputValue::scalar("0x2a"));
+		assert_eq!(BigInt(0.into()).to_input_value(), InputValue::scalar("0x0"));
+		assert_eq!(Bytes(vec![0xab, 0xcd]).to_input_value(), InputValue::scalar("0xabcd"));
+	}
+}
@@ -0,0 +1,634 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Objects of the EIP-1767 schema.
+
+use ethereum_types::{H160, H256};
+use juniper::{FieldError, FieldResult};
+use rlp;
+use types::{
+	encoded,
+	filter::Filter as EthcoreFilter,
+	ids::{BlockId, TransactionId, UncleId},
+	log_entry::LocalizedLogEntry,
+	receipt::{LocalizedReceipt, TransactionOutcome},
+	transaction::{Action, LocalizedTransaction},
+};
+
+use v1::helpers::{errors, fake_sign, CallRequest};
+use super::{Context, field_error};
+use super::scalars::{Address, BigInt, Bytes, Bytes32, Long};
+
+/// Maximal number of blocks a single `blocks` or `logs` query spans.
+const MAX_BLOCK_RANGE: u64 = 1000;
+
+impl Context {
+	fn block(&self, id: BlockId) -> Option<Block> {
+		self.backend.client().block_header(id).map(|header| Block { header })
+	}
+
+	fn transaction(&self, id: TransactionId) -> Option<Transaction> {
+		self.backend.client().transaction(id).map(Transaction::new)
+	}
+
+	fn receipt(&self, hash: H256) -> Option<LocalizedReceipt> {
+		self.backend.client().transaction_receipt(TransactionId::Hash(hash))
+	}
+
+	fn logs(&self, filter: EthcoreFilter) -> FieldResult<Vec<Log>> {
+		self.backend.client().logs(filter)
+			.map(|logs| logs.into_iter().map(|entry| Log { entry }).collect())
+			.map_err(|id| field_error(errors::filter_block_not_found(id)))
+	}
+
+	fn call(&self, data: CallData, block: BlockId) -> FieldResult<CallResult> {
+		let signed = fake_sign::sign_call(data.into()).map_err(field_error)?;
+		let executed = self.backend.call(&signed, block).map_err(field_error)?;
+
+		Ok(CallResult {
+			data: Bytes(executed.output),
+			gas_used: Long(executed.gas_used.low_u64()),
+			status: Long(executed.exception.is_none() as u64),
+		})
+	}
+
+	fn estimate_gas(&self, data: CallData, block: BlockId) -> FieldResult<Long> {
+		let signed = fake_sign::sign_call(data.into()).map_err(field_error)?;
+		self.backend.estimate_gas(&signed, block)
+			.map(|gas| Long(gas.low_u64()))
+			.map_err(field_error)
+	}
+}
+
+/// Block id for the state of an `Account`, latest if not given.
+fn state_block(block: Option<Long>) -> BlockId {
+	block.map_or(BlockId::Latest, |number| BlockId::Number(number.0))
+}
+
+/// Rejects block ranges spanning `MAX_BLOCK_RANGE` blocks or more.
+fn check_block_range(from: u64, to: u64) -> FieldResult<()> {
+	match to >= from && to - from >= MAX_BLOCK_RANGE {
+		true => Err(FieldError::from(format!("block range is limited to {} blocks", MAX_BLOCK_RANGE))),
+		false => Ok(()),
+	}
+}
+
+/// Converts the topics of a filter criteria, an empty list of alternatives matching anything.
+fn filter_topics(topics: Option<Vec<Vec<Bytes32>>>) -> Vec<Option<Vec<H256>>> {
+	let mut topics = topics.unwrap_or_default().into_iter()
+		.take(4)
+		.map(|alternatives| match alternatives.is_empty() {
+			true => None,
+			false => Some(alternatives.into_iter().map(|topic| topic.0).collect()),
+		})
+		.collect::<Vec<_>>();
+	topics.resize(4, None);
+	topics
+}
+
+fn filter_addresses(addresses: Option<Vec<Address>>) -> Option<Vec<H160>> {
+	addresses
+		.filter(|addresses| !addresses.is_empty())
+		.map(|addresses| addresses.into_iter().map(|address| address.0).collect())
+}
+
+/// Query root.
+pub struct Query;
+
+#[juniper::object(Context = Context)]
+impl Query {
+	/// Fetches an Ethereum block by number or by hash. If neither is supplied, the most recent
+	/// known block is returned.
+	fn block(context: &Context, number: Option<Long>, hash: Option<Bytes32>) -> FieldResult<Option<Block>> {
+		let id = match (number, hash) {
+			(Some(_), Some(_)) => return Err(FieldError::from("only one of number or hash must be specified")),
+			(Some(number), None) => BlockId::Number(number.0),
+			(None, Some(hash)) => BlockId::Hash(hash.0),
+			(None, None) => BlockId::Latest,
+		};
+		Ok(context.block(id))
+	}
+
+	/// Returns all the blocks between two numbers, inclusive. If `to` is not supplied, it
+	/// defaults to the most recent known block.
+	fn blocks(context: &Context, from: Long, to: Option<Long>) -> FieldResult<Vec<Block>> {
+		let best = context.backend.client().chain_info().best_block_number;
+		let to = to.map_or(best, |to| to.0).min(best);
+		check_block_range(from.0, to)?;
+
+		Ok((from.0..to.saturating_add(1))
+			.filter_map(|number| context.block(BlockId::Number(number)))
+			.collect())
+	}
+
+	/// Fetches an Ethereum transaction by hash.
+	fn transaction(context: &Context, hash: Bytes32) -> Option<Transaction> {
+		context.transaction(TransactionId::Hash(hash.0))
+	}
+
+	/// Returns log entries matching the provided filter.
+	fn logs(context: &Context, filter: FilterCriteria) -> FieldResult<Vec<Log>> {
+		let from_block = state_block(filter.from_block);
+		let to_block = state_block(filter.to_block);
+		let client = context.backend.client();
+		if let (Some(from), Some(to)) = (client.block_number(from_block), client.block_number(to_block)) {
+			check_block_range(from, to)?;
+		}
+
+		context.logs(EthcoreFilter {
+			from_block,
+			to_block,
+			address: filter_addresses(filter.addresses),
+			topics: filter_topics(filter.topics),
+			limit: None,
+		})
+	}
+
+	/// Returns the node's estimate of a gas price sufficient to ensure a transaction is mined
+	/// in a timely fashion.
+	fn gas_price(context: &Context) -> BigInt {
+		BigInt(context.backend.gas_price())
+	}
+}
+
+/// Mutation root.
+pub struct Mutation;
+
+#[juniper::object(Context = Context)]
+impl Mutation {
+	/// Sends an RLP-encoded transaction to the network.
+	fn send_raw_transaction(context: &Context, data: Bytes) -> FieldResult<Bytes32> {
+		context.backend.send_raw_transaction(&data.0)
+			.map(Bytes32)
+			.map_err(field_error)
+	}
+}
+
+/// An Ethereum block.
+pub struct Block {
+	header: encoded::Header,
+}
+
+impl Block {
+	fn id(&self) -> BlockId {
+		BlockId::Hash(self.header.hash())
+	}
+
+	fn seal_field<T: rlp::Decodable + Default>(&self, context: &Context, index: usize) -> T {
+		self.header.seal(context.backend.eip1559_transition())
+			.get(index)
+			.and_then(|field| rlp::decode(field).ok())
+			.unwrap_or_default()
+	}
+
+	fn body(&self, context: &Context) -> Option<encoded::Body> {
+		context.backend.client().block_body(self.id())
+	}
+}
+
+#[juniper::object(Context = Context)]
+impl Block {
+	/// The block number.
+	fn number(&self) -> Long {
+		Long(self.header.number())
+	}
+
+	/// The block hash.
+	fn hash(&self) -> Bytes32 {
+		Bytes32(self.header.hash())
+	}
+
+	/// The parent block, null for the genesis block.
+	fn parent(&self, context: &Context) -> Option<Block> {
+		match self.header.number() {
+			0 => None,
+			_ => context.block(BlockId::Hash(self.header.parent_hash())),
+		}
+	}
+
+	/// The block nonce, an 8 byte sequence determined by the miner. Empty for engines
+	/// other than Ethash.
+	fn nonce(&self, context: &Context) -> Bytes {
+		Bytes(self.seal_field(context, 1))
+	}
+
+	/// The keccak256 hash of the root of the trie of transactions in this block.
+	fn transactions_root(&self) -> Bytes32 {
+		Bytes32(self.header.transactions_root())
+	}
+
+	/// The number of transactions in this block, null if the block body is not available.
+	fn transaction_count(&self, context: &Context) -> Option<i32> {
+		self.body(context).map(|body| body.transactions_count() as i32)
+	}
+
+	/// The keccak256 hash of the state trie after this block was processed.
+	fn state_root(&self) -> Bytes32 {
+		Bytes32(self.header.state_root())
+	}
+
+	/// The keccak256 hash of the trie of transaction receipts in this block.
+	fn receipts_root(&self) -> Bytes32 {
+		Bytes32(self.header.receipts_root())
+	}
+
+	/// The account that mined this block, at the state of the given block (latest by default).
+	fn miner(&self, block: Option<Long>) -> Account {
+		Account::new(self.header.author(), state_block(block))
+	}
+
+	/// An arbitrary data field supplied by the miner.
+	fn extra_data(&self) -> Bytes {
+		Bytes(self.header.extra_data())
+	}
+
+	/// The maximum amount of gas that was available to transactions in this block.
+	fn gas_limit(&self) -> Long {
+		Long(self.header.gas_limit().low_u64())
+	}
+
+	/// The amount of gas that was used executing transactions in this block.
+	fn gas_used(&self) -> Long {
+		Long(self.header.gas_used().low_u64())
+	}
+
+	/// The base fee per gas of this block, null before EIP-1559.
+	fn base_fee_per_gas(&self, context: &Context) -> Option<BigInt> {
+		self.header.base_fee(context.backend.eip1559_transition()).map(BigInt)
+	}
+
+	/// The unix timestamp at which this block was mined.
+	fn timestamp(&self) -> Long {
+		Long(self.header.timestamp())
+	}
+
+	/// A bloom filter that can be used to check if a block may contain log entries matching
+	/// a filter.
+	fn logs_bloom(&self) -> Bytes {
+		Bytes(self.header.log_bloom().as_bytes().to_vec())
+	}
+
+	/// The hash that was used as an input to the PoW process. Zero for engines other than Ethash.
+	fn mix_hash(&self, context: &Context) -> Bytes32 {
+		Bytes32(self.seal_field(context, 0))
+	}
+
+	/// A measure of the difficulty of mining this block.
+	fn difficulty(&self) -> BigInt {
+		BigInt(self.header.difficulty())
+	}
+
+	/// The sum of all difficulty values up to and including this block.
+	fn total_difficulty(&self, context: &Context) -> FieldResult<BigInt> {
+		context.backend.client().block_total_difficulty(self.id())
+			.map(BigInt)
+			.ok_or_else(|| field_error(errors::unknown_block()))
+	}
+
+	/// The number of ommers (uncles) of this block, null if the block body is not available.
+	fn ommer_count(&self, context: &Context) -> Option<i32> {
+		self.body(context).map(|body| body.uncles_count() as i32)
+	}
+
+	/// The ommer (uncle) blocks of this block. Only the header fields of ommers are available.
+	fn ommers(&self, context: &Context) -> Option<Vec<Block>> {
+		self.body(context).map(|body| (0..body.uncles_count())
+			.filter_map(|position| context.backend.client().uncle(UncleId { block: self.id(), position }))
+			.map(|header| Block { header })
+			.collect())
+	}
+
+	/// The ommer (uncle) block at the given index.
+	fn ommer_at(&self, context: &Context, index: i32) -> Option<Block> {
+		if index < 0 {
+			return None;
+		}
+		context.backend.client().uncle(UncleId { block: self.id(), position: index as usize })
+			.map(|header| Block { header })
+	}
+
+	/// The keccak256 hash of the ommers list.
+	fn ommer_hash(&self) -> Bytes32 {
+		Bytes32(self.header.uncles_hash())
+	}
+
+	/// The transactions of this block, null if the block body is not available.
+	fn transactions(&self, context: &Context) -> Option<Vec<Transaction>> {
+		self.body(context).map(|body| (0..body.transactions_count())
+			.filter_map(|index| context.transaction(TransactionId::Location(self.id(), index)))
+			.collect())
+	}
+
+	/// The transaction at the given index.
+	fn transaction_at(&self, context: &Context, index: i32) -> Option<Transaction> {
+		if index < 0 {
+			return None;
+		}
+		context.transaction(TransactionId::Location(self.id(), index as usize))
+	}
+
+	/// Log entries emitted by the transactions of this block matching the filter.
+	fn logs(&self, context: &Context, filter: BlockFilterCriteria) -> FieldResult<Vec<Log>> {
+		context.logs(EthcoreFilter {
+			from_block: self.id(),
+			to_block: self.id(),
+			address: filter_addresses(filter.addresses),
+			topics: filter_topics(filter.topics),
+			limit: None,
+		})
+	}
+
+	/// An account at the state of this block.
+	fn account(&self, address: Address) -> Account {
+		Account::new(address.0, self.id())
+	}
+
+	/// Executes a local call on top of the state of this block.
+	fn call(&self, context: &Context, data: CallData) -> FieldResult<CallResult> {
+		context.call(data, self.id())
+	}
+
+	/// Estimates the amount of gas that will be required for a transaction to succeed on top
+	/// of the state of this block.
+	fn estimate_gas(&self, context: &Context, data: CallData) -> FieldResult<Long> {
+		context.estimate_gas(data, self.id())
+	}
+}
+
+/// An Ethereum transaction.
+pub struct Transaction {
+	transaction: LocalizedTransaction,
+	from: H160,
+}
+
+impl Transaction {
+	fn new(mut transaction: LocalizedTransaction) -> Self {
+		let from = transaction.sender();
+		Transaction { transaction, from }
+	}
+}
+
+#[juniper::object(Context = Context)]
+impl Transaction {
+	/// The hash of this transaction.
+	fn hash(&self) -> Bytes32 {
+		Bytes32(self.transaction.hash())
+	}
+
+	/// The nonce of the account this transaction was generated with.
+	fn nonce(&self) -> Long {
+		Long(self.transaction.nonce.low_u64())
+	}
+
+	/// The index of this transaction in the parent block.
+	fn index(&self) -> Option<i32> {
+		Some(self.transaction.transaction_index as i32)
+	}
+
+	/// The account that sent this transaction, at the state of the given block (latest by default).
+	fn from(&self, block: Option<Long>) -> Account {
+		Account::new(self.from, state_block(block))
+	}
+
+	/// The account this transaction was sent to, null for contract creations.
+	fn to(&self, block: Option<Long>) -> Option<Account> {
+		match self.transaction.action {
+			Action::Call(address) => Some(Account::new(address, state_block(block))),
+			Action::Create => None,
+		}
+	}
+
+	/// The value, in wei, sent along with this transaction.
+	fn value(&self) -> BigInt {
+		BigInt(self.transaction.value)
+	}
+
+	/// The price offered to miners for gas, in wei per unit.
+	fn gas_price(&self) -> BigInt {
+		BigInt(self.transaction.gas_price)
+	}
+
+	/// The maximum amount of gas this transaction can consume.
+	fn gas(&self) -> Long {
+		Long(self.transaction.gas.low_u64())
+	}
+
+	/// The data supplied to the target of the transaction.
+	fn input_data(&self) -> Bytes {
+		Bytes(self.transaction.data.clone())
+	}
+
+	/// The block this transaction was mined in.
+	fn block(&self, context: &Context) -> Option<Block> {
+		context.block(BlockId::Hash(self.transaction.block_hash))
+	}
+
+	/// The status of the transaction, 1 for success and 0 for failure. Null before EIP-658.
+	fn status(&self, context: &Context) -> Option<Long> {
+		context.receipt(self.transaction.hash()).and_then(|receipt| match receipt.outcome {
+			TransactionOutcome::StatusCode(status) => Some(Long(status as u64)),
+			_ => None,
+		})
+	}
+
+	/// The amount of gas that was used processing this transaction.
+	fn gas_used(&self, context: &Context) -> Option<Long> {
+		context.receipt(self.transaction.hash()).map(|receipt| Long(receipt.gas_used.low_u64()))
+	}
+
+	/// The total amount of gas used in the block up to and including this transaction.
+	fn cumulative_gas_used(&self, context: &Context) -> Option<Long> {
+		context.receipt(self.transaction.hash()).map(|receipt| Long(receipt.cumulative_gas_used.low_u64()))
+	}
+
+	/// The contract created by this transaction, at the state of the given block (latest
+	/// by default).
+	fn created_contract(&self, context: &Context, block: Option<Long>) -> Option<Account> {
+		context.receipt(self.transaction.hash())
+			.and_then(|receipt| receipt.contract_address)
+			.map(|address| Account::new(address, state_block(block)))
+	}
+
+	/// The log entries emitted by this transaction.
+	fn logs(&self, context: &Context) -> Option<Vec<Log>> {
+		context.receipt(self.transaction.hash())
+			.map(|receipt| receipt.logs.into_iter().map(|entry| Log { entry }).collect())
+	}
+
+	/// The R field of the signature.
+	fn r(&self) -> BigInt {
+		BigInt(self.transaction.signature().r().into())
+	}
+
+	/// The S field of the signature.
+	fn s(&self) -> BigInt {
+		BigInt(self.transaction.signature().s().into())
+	}
+
+	/// The V field of the signature.
+	fn v(&self) -> BigInt {
+		BigInt(self.transaction.original_v().into())
+	}
+}
+
+/// An Ethereum event log.
+pub struct Log {
+	entry: LocalizedLogEntry,
+}
+
+#[juniper::object(Context = Context)]
+impl Log {
+	/// The index of this log in the block.
+	fn index(&self) -> i32 {
+		self.entry.log_index as i32
+	}
+
+	/// The account which emitted this log, at the state of the given block (latest by default).
+	fn account(&self, block: Option<Long>) -> Account {
+		Account::new(self.entry.address, state_block(block))
+	}
+
+	/// The list of 0-4 indexed topics for the log.
+	fn topics(&self) -> Vec<Bytes32> {
+		self.entry.topics.iter().cloned().map(Bytes32).collect()
+	}
+
+	/// Unindexed data for this log.
+	fn data(&self) -> Bytes {
+		Bytes(self.entry.data.clone())
+	}
+
+	/// The transaction that generated this log entry.
+	fn transaction(&self, context: &Context) -> Option<Transaction> {
+		context.transaction(TransactionId::Hash(self.entry.transaction_hash))
+	}
+}
+
+/// An Ethereum account at a particular block.
+pub struct Account {
+	address: H160,
+	block: BlockId,
+}
+
+impl Account {
+	fn new(address: H160, block: BlockId) -> Self {
+		Account { address, block }
+	}
+}
+
+#[juniper::object(Context = Context)]
+impl Account {
+	/// The address of this account.
+	fn address(&self) -> Address {
+		Address(self.address)
+	}
+
+	/// The balance of the account, in wei.
+	fn balance(&self, context: &Context) -> FieldResult<BigInt> {
+		context.backend.client().balance(&self.address, self.block.into())
+			.map(BigInt)
+			.ok_or_else(|| field_error(errors::state_pruned()))
+	}
+
+	/// The number of transactions sent from this account.
+	fn transaction_count(&self, context: &Context) -> FieldResult<Long> {
+		context.backend.client().nonce(&self.address, self.block)
+			.map(|nonce| Long(nonce.low_u64()))
+			.ok_or_else(|| field_error(errors::state_pruned()))
+	}
+
+	/// The code of the contract deployed at this address, empty for plain accounts.
+	fn code(&self, context: &Context) -> FieldResult<Bytes> {
+		match context.backend.client().code(&self.address, self.block.into()) {
+			::types::client_types::StateResult::Some(code) => Ok(Bytes(code.unwrap_or_default())),
+			::types::client_types::StateResult::Missing => Err(field_error(errors::state_pruned())),
+		}
+	}
+
+	/// The value of a storage slot of the account.
+	fn storage(&self, context: &Context, slot: Bytes32) -> FieldResult<Bytes32> {
+		context.backend.client().storage_at(&self.address, &slot.0, self.block.into())
+			.map(Bytes32)
+			.ok_or_else(|| field_error(errors::state_pruned()))
+	}
+}
+
+/// The result of a local call.
+#[derive(GraphQLObject)]
+pub struct CallResult {
+	/// The return data from the call.
+	data: Bytes,
+	/// The amount of gas used by the call, after any refunds.
+	gas_used: Long,
+	/// The return status of the call, 0 for failure or 1 for success.
+	status: Long,
+}
+
+/// The arguments of a local call or gas estimation.
+#[derive(GraphQLInputObject)]
+pub struct CallData {
+	/// The sender of the call, the zero address if absent.
+	from: Option<Address>,
+	/// The destination of the call, a contract creation if absent.
+	to: Option<Address>,
+	/// The amount of gas provided to the call.
+	gas: Option<Long>,
+	/// The price of each unit of gas, in wei.
+	gas_price: Option<BigInt>,
+	/// The value sent along with the call, in wei.
+	value: Option<BigInt>,
+	/// The data sent along with the call.
+	data: Option<Bytes>,
+}
+
+impl From<CallData> for CallRequest {
+	fn from(data: CallData) -> Self {
+		CallRequest {
+			from: data.from.map(|address| address.0),
+			to: data.to.map(|address| address.0),
+			gas_price: data.gas_price.map(|price| price.0),
+			gas: data.gas.map(|gas| gas.0.into()),
+			value: data.value.map(|value| value.0),
+			data: data.data.map(|data| data.0),
+			nonce: None,
+			access_list: None,
+			max_fee_per_gas: None,
+			max_priority_fee_per_gas: None,
+		}
+	}
+}
+
+/// Filter for log entries within a range of blocks.
+#[derive(GraphQLInputObject)]
+pub struct FilterCriteria {
+	/// The beginning of the range, the latest block if absent.
+	from_block: Option<Long>,
+	/// The end of the range, the latest block if absent.
+	to_block: Option<Long>,
+	/// Addresses the logs must originate from, any address if absent or empty.
+	addresses: Option<Vec<Address>>,
+	/// Topic alternatives per position; an empty list matches any topic.
+	topics: Option<Vec<Vec<Bytes32>>>,
+}
+
+/// Filter for log entries within a single block.
+#[derive(GraphQLInputObject)]
+pub struct BlockFilterCriteria {
+	/// Addresses the logs must originate from, any address if absent or empty.
+	addresses: Option<Vec<Address>>,
+	/// Topic alternatives per position; an empty list matches any topic.
+	topics: Option<Vec<Vec<Bytes32>>>,
+}
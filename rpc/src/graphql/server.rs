@@ -0,0 +1,188 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! HTTP server answering GraphQL queries sent as `POST` requests.
+
+use std::io;
+use std::net::{SocketAddr, TcpListener};
+use std::sync::Arc;
+use std::thread;
+
+use futures::{self, future, Future, Stream};
+use futures_cpupool::{self, CpuPool};
+use http::{self, AccessControlAllowOrigin, DomainsValidation, Host};
+use http::hyper::{self, header::{self, HeaderValue}, service::service_fn, Body, Method, Request, Response, StatusCode};
+use juniper::http::GraphQLRequest;
+use serde_json;
+
+use super::{Backend, Context, Schema, schema};
+
+/// Maximal size of a request body.
+const MAX_PAYLOAD: usize = 5 * 1024 * 1024;
+
+type ResponseFuture = Box<dyn Future<Item = Response<Body>, Error = hyper::Error> + Send>;
+
+#[derive(Clone)]
+struct Handler {
+	schema: Arc<Schema>,
+	context: Arc<Context>,
+	cors_domains: Option<Vec<AccessControlAllowOrigin>>,
+	allowed_hosts: Option<Vec<Host>>,
+	pool: CpuPool,
+}
+
+impl Handler {
+	fn on_request(&self, req: Request<Body>) -> ResponseFuture {
+		if !http::is_host_allowed(&req, &self.allowed_hosts) {
+			return Box::new(future::ok(text_response(StatusCode::FORBIDDEN, "Disallowed Host header")));
+		}
+
+		let cors_header = http::cors_allow_origin(&req, &self.cors_domains);
+		if cors_header == http::AllowCors::Invalid {
+			return Box::new(future::ok(text_response(StatusCode::FORBIDDEN, "Disallowed Origin header")));
+		}
+		let cors_header: Option<HeaderValue> = cors_header.into();
+
+		match *req.method() {
+			Method::OPTIONS => {
+				let mut res = text_response(StatusCode::OK, "");
+				res.headers_mut().append(header::ACCESS_CONTROL_ALLOW_METHODS, HeaderValue::from_static("OPTIONS, POST"));
+				res.headers_mut().append(header::ACCESS_CONTROL_ALLOW_HEADERS, HeaderValue::from_static("content-type"));
+				Box::new(future::ok(with_cors(res, cors_header)))
+			},
+			Method::POST => {
+				let handler = self.clone();
+				let pool = self.pool.clone();
+				let body = req.into_body()
+					.map_err(|_| StatusCode::BAD_REQUEST)
+					.fold(Vec::new(), |mut body, chunk| {
+						body.extend_from_slice(&chunk);
+						match body.len() > MAX_PAYLOAD {
+							true => Err(StatusCode::PAYLOAD_TOO_LARGE),
+							false => Ok(body),
+						}
+					});
+				Box::new(body.then(move |body| match body {
+					// resolvers hit the database, keep them off the server's event loop
+					Ok(body) => future::Either::A(pool.spawn_fn(move || Ok(handler.execute(&body)))),
+					Err(status) => future::Either::B(future::ok(text_response(status, "Unable to read request body"))),
+				}).then(move |res: Result<_, ()>| {
+					let res = res.unwrap_or_else(|_| text_response(StatusCode::INTERNAL_SERVER_ERROR, "Unable to execute request"));
+					Ok::<_, hyper::Error>(with_cors(res, cors_header))
+				}))
+			},
+			_ => Box::new(future::ok(text_response(StatusCode::METHOD_NOT_ALLOWED, "Only POST requests are supported"))),
+		}
+	}
+
+	fn execute(&self, body: &[u8]) -> Response<Body> {
+		let request: GraphQLRequest = match serde_json::from_slice(body) {
+			Ok(request) => request,
+			Err(err) => return text_response(StatusCode::BAD_REQUEST, &format!("Invalid GraphQL request: {}", err)),
+		};
+
+		let response = request.execute(&*self.schema, &*self.context);
+		let status = match response.is_ok() {
+			true => StatusCode::OK,
+			false => StatusCode::BAD_REQUEST,
+		};
+		let body = serde_json::to_vec(&response).expect("GraphQL responses are always serializable; qed");
+
+		Response::builder()
+			.status(status)
+			.header(header::CONTENT_TYPE, HeaderValue::from_static("application/json"))
+			.body(body.into())
+			.expect("Response builder: Parsing 'content-type' header name will not fail; qed")
+	}
+}
+
+fn text_response(status: StatusCode, text: &str) -> Response<Body> {
+	Response::builder()
+		.status(status)
+		.header(header::CONTENT_TYPE, HeaderValue::from_static("text/plain; charset=utf-8"))
+		.body(text.to_owned().into())
+		.expect("Response builder: Parsing 'content-type' header name will not fail; qed")
+}
+
+fn with_cors(mut res: Response<Body>, cors_header: Option<HeaderValue>) -> Response<Body> {
+	if let Some(cors_header) = cors_header {
+		res.headers_mut().append(header::ACCESS_CONTROL_ALLOW_ORIGIN, cors_header);
+		res.headers_mut().append(header::VARY, HeaderValue::from_static("origin"));
+	}
+	res
+}
+
+/// GraphQL server handle. The server is stopped when the handle is dropped.
+pub struct Server {
+	close: Option<futures::sync::oneshot::Sender<()>>,
+	thread: Option<thread::JoinHandle<()>>,
+}
+
+impl Drop for Server {
+	fn drop(&mut self) {
+		if let Some(close) = self.close.take() {
+			let _ = close.send(());
+		}
+		if let Some(thread) = self.thread.take() {
+			let _ = thread.join();
+		}
+	}
+}
+
+/// Starts the GraphQL server on `addr`, returns an error if the address can't be bound.
+/// Queries are executed on a pool of `threads` worker threads.
+pub fn start_server(
+	addr: &SocketAddr,
+	cors_domains: DomainsValidation<AccessControlAllowOrigin>,
+	allowed_hosts: DomainsValidation<Host>,
+	threads: usize,
+	backend: Arc<dyn Backend>,
+) -> io::Result<Server> {
+	let listener = TcpListener::bind(addr)?;
+	let server = hyper::Server::from_tcp(listener).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+
+	let handler = Handler {
+		schema: Arc::new(schema()),
+		context: Arc::new(Context::new(backend)),
+		cors_domains: cors_domains.into(),
+		allowed_hosts: allowed_hosts.into(),
+		pool: futures_cpupool::Builder::new()
+			.pool_size(threads)
+			.name_prefix("graphql-worker-")
+			.create(),
+	};
+
+	let (close, shutdown_signal) = futures::sync::oneshot::channel::<()>();
+	let thread = thread::Builder::new()
+		.name("graphql".into())
+		.spawn(move || {
+			let server = server
+				.serve(move || {
+					let handler = handler.clone();
+					service_fn(move |req| handler.on_request(req))
+				})
+				.map_err(|e| warn!("GraphQL server error: {}", e))
+				.select(shutdown_signal.map_err(|_| ()))
+				.then(|_| Ok(()));
+
+			hyper::rt::run(server);
+		})?;
+
+	Ok(Server {
+		close: Some(close),
+		thread: Some(thread),
+	})
+}
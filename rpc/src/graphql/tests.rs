@@ -0,0 +1,158 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use ethcore::test_helpers::{TestBlockChainClient, EachBlockWith};
+use ethereum_types::{Address, H256};
+use juniper::{self, Variables};
+use serde_json;
+use types::log_entry::{LocalizedLogEntry, LogEntry};
+
+use v1::helpers::GasPriceOracleConfig;
+use v1::tests::helpers::TestMinerService;
+use super::{Context, FullBackend, schema};
+
+struct Tester {
+	client: Arc<TestBlockChainClient>,
+	context: Context,
+}
+
+impl Tester {
+	fn new() -> Self {
+		let client = Arc::new(TestBlockChainClient::default());
+		let miner = Arc::new(TestMinerService::default());
+		let backend = FullBackend::new(&client, &miner, GasPriceOracleConfig::default());
+		Tester {
+			client,
+			context: Context::new(Arc::new(backend)),
+		}
+	}
+
+	/// Executes the query, returning its data or the messages of its errors.
+	fn query(&self, query: &str) -> Result<String, Vec<String>> {
+		let (value, errors) = juniper::execute(query, None, &schema(), &Variables::new(), &self.context)
+			.map_err(|err| vec![format!("{:?}", err)])?;
+		match errors.is_empty() {
+			true => Ok(serde_json::to_string(&value).unwrap()),
+			false => Err(errors.iter().map(|err| err.error().message().to_owned()).collect()),
+		}
+	}
+}
+
+#[test]
+fn should_return_block_by_number_and_its_parent() {
+	let tester = Tester::new();
+	tester.client.add_blocks(3, EachBlockWith::Transaction);
+
+	assert_eq!(
+		tester.query("{ block(number: 2) { number transactionCount gasLimit parent { number parent { number parent { number } } } } }"),
+		Ok(r#"{"block":{"number":"0x2","transactionCount":1,"gasLimit":"0xf4240","parent":{"number":"0x1","parent":{"number":"0x0","parent":null}}}}"#.into())
+	);
+	assert_eq!(tester.query("{ block { number } }"), Ok(r#"{"block":{"number":"0x3"}}"#.into()));
+	assert_eq!(tester.query("{ block(number: \"0x4\") { number } }"), Ok(r#"{"block":null}"#.into()));
+}
+
+#[test]
+fn should_reject_block_by_number_and_hash() {
+	let tester = Tester::new();
+	let hash = format!("{:#x}", H256::zero());
+
+	assert_eq!(
+		tester.query(&format!("{{ block(number: 0, hash: \"{}\") {{ number }} }}", hash)),
+		Err(vec!["only one of number or hash must be specified".into()])
+	);
+}
+
+#[test]
+fn should_return_block_range() {
+	let tester = Tester::new();
+	tester.client.add_blocks(5, EachBlockWith::Nothing);
+
+	assert_eq!(
+		tester.query("{ blocks(from: 2, to: 4) { number } }"),
+		Ok(r#"{"blocks":[{"number":"0x2"},{"number":"0x3"},{"number":"0x4"}]}"#.into())
+	);
+	assert_eq!(
+		tester.query("{ blocks(from: 4) { number } }"),
+		Ok(r#"{"blocks":[{"number":"0x4"},{"number":"0x5"}]}"#.into())
+	);
+}
+
+#[test]
+fn should_return_logs() {
+	let tester = Tester::new();
+	tester.client.set_logs(vec![LocalizedLogEntry {
+		entry: LogEntry {
+			address: Address::from_low_u64_be(1),
+			topics: vec![H256::from_low_u64_be(2)],
+			data: vec![1, 2, 3],
+		},
+		block_hash: H256::zero(),
+		block_number: 1,
+		transaction_hash: H256::zero(),
+		transaction_index: 0,
+		log_index: 3,
+		transaction_log_index: 0,
+	}]);
+
+	assert_eq!(
+		tester.query("{ logs(filter: { fromBlock: 0 }) { index data topics account { address } } }"),
+		Ok(r#"{"logs":[{"index":3,"data":"0x010203","topics":["0x0000000000000000000000000000000000000000000000000000000000000002"],"account":{"address":"0x0000000000000000000000000000000000000001"}}]}"#.into())
+	);
+}
+
+#[test]
+fn should_reject_too_large_ranges() {
+	let tester = Tester::new();
+	tester.client.add_blocks(1001, EachBlockWith::Nothing);
+
+	let error = Err(vec!["block range is limited to 1000 blocks".into()]);
+	assert_eq!(tester.query("{ blocks(from: 0) { number } }"), error);
+	assert_eq!(tester.query("{ logs(filter: { fromBlock: 0 }) { index } }"), error);
+	assert_eq!(tester.query("{ logs(filter: { fromBlock: 2 }) { index } }"), Ok(r#"{"logs":[]}"#.into()));
+}
+
+#[test]
+fn should_return_account_state() {
+	let tester = Tester::new();
+	tester.client.set_balance(Address::zero(), 5.into());
+	tester.client.set_nonce(Address::zero(), 7.into());
+
+	assert_eq!(
+		tester.query("{ block { miner { address balance transactionCount } } }"),
+		Ok(r#"{"block":{"miner":{"address":"0x0000000000000000000000000000000000000000","balance":"0x5","transactionCount":"0x7"}}}"#.into())
+	);
+}
+
+#[test]
+fn should_estimate_gas_and_suggest_gas_price() {
+	let tester = Tester::new();
+
+	assert_eq!(
+		tester.query("{ gasPrice block { estimateGas(data: { to: \"0x0000000000000000000000000000000000000001\" }) } }"),
+		Ok(r#"{"gasPrice":"0x4a817c800","block":{"estimateGas":"0x5208"}}"#.into())
+	);
+}
+
+#[test]
+fn should_reject_invalid_raw_transaction() {
+	let tester = Tester::new();
+
+	let errors = tester.query("mutation { sendRawTransaction(data: \"0x\") }").unwrap_err();
+	assert_eq!(errors.len(), 1);
+	assert!(errors[0].starts_with("Invalid RLP."), "unexpected error: {}", errors[0]);
+}
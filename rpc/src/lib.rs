@@ -39,10 +39,14 @@
 
 #[macro_use]
 extern crate futures;
+extern crate futures_cpupool;
 
 extern crate ansi_term;
 extern crate cid;
+extern crate hyper;
 extern crate itertools;
+#[macro_use]
+extern crate juniper;
 extern crate machine;
 extern crate multihash;
 extern crate order_stat;
@@ -137,6 +141,7 @@ pub extern crate jsonrpc_ws_server as ws;
 
 mod authcodes;
 mod http_common;
+pub mod graphql;
 pub mod v1;
 
 pub mod tests;
@@ -155,7 +160,7 @@ pub use http::{
 	AccessControlAllowOrigin, Host, DomainsValidation, cors::AccessControlAllowHeaders
 };
 
-pub use v1::{NetworkSettings, Metadata, Origin, informant, dispatch, signer};
+pub use v1::{NetworkSettings, GasPriceOracleConfig, Metadata, Origin, informant, dispatch, signer};
 pub use v1::block_import::{is_major_importing_or_waiting};
 pub use v1::PubSubSyncStatus;
 pub use v1::extractors::{RpcExtractor, WsExtractor, WsStats, WsDispatcher};
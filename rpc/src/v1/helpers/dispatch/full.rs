@@ -24,11 +24,11 @@ use parking_lot::Mutex;
 
 use jsonrpc_core::{BoxFuture, Result};
 use jsonrpc_core::futures::{future, Future, IntoFuture};
-use v1::helpers::{errors, nonce, TransactionRequest, FilledTransactionRequest};
+use v1::helpers::{errors, nonce, GasPriceOracleConfig, TransactionRequest, FilledTransactionRequest};
 use v1::types::{RichRawTransaction as RpcRichRawTransaction};
 
 use super::prospective_signer::ProspectiveSigner;
-use super::{Dispatcher, Accounts, SignWith, PostSign, default_gas_price};
+use super::{Dispatcher, Accounts, SignWith, PostSign, default_gas_price, dynamic_fees};
 
 /// A dispatcher which uses references to a client and miner in order to sign
 /// requests locally.
@@ -37,7 +37,7 @@ pub struct FullDispatcher<C, M> {
 	client: Arc<C>,
 	miner: Arc<M>,
 	nonces: Arc<Mutex<nonce::Reservations>>,
-	gas_price_percentile: usize,
+	gas_price_oracle: GasPriceOracleConfig,
 }
 
 impl<C, M> FullDispatcher<C, M> {
@@ -46,13 +46,13 @@ impl<C, M> FullDispatcher<C, M> {
 		client: Arc<C>,
 		miner: Arc<M>,
 		nonces: Arc<Mutex<nonce::Reservations>>,
-		gas_price_percentile: usize,
+		gas_price_oracle: GasPriceOracleConfig,
 	) -> Self {
 		FullDispatcher {
 			client,
 			miner,
 			nonces,
-			gas_price_percentile,
+			gas_price_oracle,
 		}
 	}
 }
@@ -63,7 +63,7 @@ impl<C, M> Clone for FullDispatcher<C, M> {
 			client: self.client.clone(),
 			miner: self.miner.clone(),
 			nonces: self.nonces.clone(),
-			gas_price_percentile: self.gas_price_percentile,
+			gas_price_oracle: self.gas_price_oracle,
 		}
 	}
 }
@@ -100,18 +100,24 @@ impl<C: miner::BlockChainClient + BlockChainClient, M: MinerService> Dispatcher
 			request.nonce
 		};
 
+		let gas_price = request.gas_price.unwrap_or_else(|| {
+			default_gas_price(&*self.client, &*self.miner, &self.gas_price_oracle)
+		});
+		let (max_fee_per_gas, max_priority_fee_per_gas) = dynamic_fees(&request, gas_price);
+
 		Box::new(future::ok(FilledTransactionRequest {
 			from,
 			used_default_from: request.from.is_none(),
 			to: request.to,
 			nonce,
-			gas_price: request.gas_price.unwrap_or_else(|| {
-				default_gas_price(&*self.client, &*self.miner, self.gas_price_percentile)
-			}),
+			gas_price,
 			gas: request.gas.unwrap_or_else(|| self.miner.sensible_gas_limit()),
 			value: request.value.unwrap_or_else(|| 0.into()),
 			data: request.data.unwrap_or_else(Vec::new),
 			condition: request.condition,
+			access_list: request.access_list,
+			max_fee_per_gas,
+			max_priority_fee_per_gas,
 		}))
 	}
 
@@ -130,7 +136,6 @@ impl<C: miner::BlockChainClient + BlockChainClient, M: MinerService> Dispatcher
 
 		if let Some(nonce) = filled.nonce {
 			let future = signer.sign_transaction(filled, chain_id, nonce, password)
-				.into_future()
 				.and_then(move |signed| post_sign.execute(signed));
 			Box::new(future)
 		} else {
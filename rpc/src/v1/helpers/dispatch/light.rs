@@ -29,12 +29,12 @@ use types::ids::BlockId;
 use types::transaction::{SignedTransaction, PendingTransaction, Error as TransactionError};
 
 use jsonrpc_core::{BoxFuture, Result};
-use jsonrpc_core::futures::{future, Future, IntoFuture};
+use jsonrpc_core::futures::{future, Future};
 use jsonrpc_core::futures::future::Either;
-use v1::helpers::{errors, nonce, TransactionRequest, FilledTransactionRequest};
+use v1::helpers::{errors, nonce, GasPriceOracleConfig, TransactionRequest, FilledTransactionRequest};
 use v1::types::{RichRawTransaction as RpcRichRawTransaction,};
 
-use super::{Dispatcher, Accounts, SignWith, PostSign};
+use super::{Dispatcher, Accounts, SignWith, PostSign, dynamic_fees};
 
 /// Dispatcher for light clients -- fetches default gas price, next nonce, etc. from network.
 pub struct LightDispatcher<S, OD>
@@ -54,8 +54,8 @@ where
 	pub transaction_queue: Arc<RwLock<LightTransactionQueue>>,
 	/// Nonce reservations
 	pub nonces: Arc<Mutex<nonce::Reservations>>,
-	/// Gas Price percentile value used as default gas price.
-	pub gas_price_percentile: usize,
+	/// Gas price oracle used to suggest the default gas price.
+	pub gas_price_oracle: GasPriceOracleConfig,
 }
 
 impl<S, OD> LightDispatcher<S, OD>
@@ -73,7 +73,7 @@ where
 		cache: Arc<Mutex<LightDataCache>>,
 		transaction_queue: Arc<RwLock<LightTransactionQueue>>,
 		nonces: Arc<Mutex<nonce::Reservations>>,
-		gas_price_percentile: usize,
+		gas_price_oracle: GasPriceOracleConfig,
 	) -> Self {
 		LightDispatcher {
 			sync,
@@ -82,7 +82,7 @@ where
 			cache,
 			transaction_queue,
 			nonces,
-			gas_price_percentile,
+			gas_price_oracle,
 		}
 	}
 
@@ -94,6 +94,7 @@ where
 			self.client.clone(),
 			self.on_demand.clone(),
 			self.cache.clone(),
+			self.gas_price_oracle.blocks,
 		)
 	}
 
@@ -135,7 +136,7 @@ where
 			cache: self.cache.clone(),
 			transaction_queue: self.transaction_queue.clone(),
 			nonces: self.nonces.clone(),
-			gas_price_percentile: self.gas_price_percentile
+			gas_price_oracle: self.gas_price_oracle,
 		}
 	}
 }
@@ -159,6 +160,7 @@ where
 
 		let with_gas_price = move |gas_price| {
 			let request = request;
+			let (max_fee_per_gas, max_priority_fee_per_gas) = dynamic_fees(&request, gas_price);
 			FilledTransactionRequest {
 				from,
 				used_default_from: request.from.is_none(),
@@ -169,20 +171,24 @@ where
 				value: request.value.unwrap_or_default(),
 				data: request.data.unwrap_or_else(Vec::new),
 				condition: request.condition,
+				access_list: request.access_list,
+				max_fee_per_gas,
+				max_priority_fee_per_gas,
 			}
 		};
 
 		// fast path for known gas price.
-		let gas_price_percentile = self.gas_price_percentile;
+		let gas_price_oracle = self.gas_price_oracle;
 		let gas_price = match request_gas_price {
 			Some(gas_price) => Either::A(future::ok(with_gas_price(gas_price))),
 			None => Either::B(fetch_gas_price_corpus(
 				self.sync.clone(),
 				self.client.clone(),
 				self.on_demand.clone(),
-				self.cache.clone()
-			).and_then(move |corp| match corp.percentile(gas_price_percentile) {
-				Some(percentile) => Ok(*percentile),
+				self.cache.clone(),
+				gas_price_oracle.blocks,
+			).and_then(move |corp| match gas_price_oracle.suggest(&corp) {
+				Some(gas_price) => Ok(gas_price),
 				None => Ok(DEFAULT_GAS_PRICE), // fall back to default on error.
 			}).map(with_gas_price))
 		};
@@ -226,7 +232,6 @@ where
 		let chain_id = self.client.signing_chain_id();
 		let nonce = filled.nonce.expect("nonce is always provided; qed");
 		let future = signer.sign_transaction(filled, chain_id, nonce, password)
-			.into_future()
 			.and_then(move |signed| post_sign.execute(signed));
 		Box::new(future)
 	}
@@ -251,13 +256,12 @@ pub fn fetch_gas_price_corpus<S, OD>(
 	client: Arc<dyn LightChainClient>,
 	on_demand: Arc<OD>,
 	cache: Arc<Mutex<LightDataCache>>,
+	sample_size: usize,
 ) -> BoxFuture<Corpus<U256>>
 where
 	S: LightSyncProvider + LightNetworkDispatcher + ManageNetwork + 'static,
 	OD: OnDemandRequester + 'static
 {
-	const GAS_PRICE_SAMPLE_SIZE: usize = 100;
-
 	if let Some(cached) = { cache.lock().gas_price_corpus() } {
 		return Box::new(future::ok(cached))
 	}
@@ -268,7 +272,7 @@ where
 		// and request each of the blocks from the network.
 		let block_requests = client.ancestry_iter(BlockId::Latest)
 			.filter(|hdr| hdr.gas_used() != U256::default())
-			.take(GAS_PRICE_SAMPLE_SIZE)
+			.take(sample_size)
 			.map(|hdr| request::Body(hdr.into()))
 			.collect::<Vec<_>>();
 
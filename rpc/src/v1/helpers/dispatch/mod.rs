@@ -17,6 +17,7 @@
 //! Utilities and helpers for transaction dispatch.
 
 pub(crate) mod light;
+pub(crate) mod remote_signer;
 mod full;
 mod prospective_signer;
 
@@ -39,16 +40,16 @@ mod signing {
 	}
 
 	impl super::Accounts for Signer {
-		fn sign_transaction(&self, _filled: FilledTransactionRequest, _chain_id: Option<u64>, _nonce: U256, _password: SignWith) -> Result<WithToken<SignedTransaction>> {
-			Err(errors::account("Signing unsupported", "See #9997"))
+		fn sign_transaction(&self, _filled: FilledTransactionRequest, _chain_id: Option<u64>, _nonce: U256, _password: SignWith) -> BoxFuture<WithToken<SignedTransaction>> {
+			Box::new(future::err(errors::account("Signing unsupported", "See #9997")))
 		}
 
-		fn sign_message(&self, _address: Address, _password: SignWith, _hash: SignMessage) -> Result<WithToken<Signature>> {
-			Err(errors::account("Signing unsupported", "See #9997"))
+		fn sign_message(&self, _address: Address, _password: SignWith, _hash: SignMessage) -> BoxFuture<WithToken<Signature>> {
+			Box::new(future::err(errors::account("Signing unsupported", "See #9997")))
 		}
 
-		fn decrypt(&self, _address: Address, _password: SignWith, _data: Bytes) -> Result<WithToken<Bytes>> {
-			Err(errors::account("Signing unsupported", "See #9997"))
+		fn decrypt(&self, _address: Address, _password: SignWith, _data: Bytes) -> BoxFuture<WithToken<Bytes>> {
+			Box::new(future::err(errors::account("Signing unsupported", "See #9997")))
 		}
 
 		fn supports_prospective_signing(&self, _address: &Address, _password: &SignWith) -> bool {
@@ -67,9 +68,11 @@ mod signing {
 
 pub use self::light::LightDispatcher;
 pub use self::full::FullDispatcher;
+pub use self::remote_signer::{RemoteSigner, SignerTransport, TransportFuture, HttpTransport, IpcTransport};
 pub use self::signing::Signer;
 pub use v1::helpers::nonce::Reservations;
 
+use std::cmp;
 use std::fmt::Debug;
 use std::ops::Deref;
 use std::sync::Arc;
@@ -85,9 +88,10 @@ use types::transaction::{SignedTransaction, PendingTransaction};
 
 use jsonrpc_core::{BoxFuture, Result, Error};
 use jsonrpc_core::futures::{future, Future, IntoFuture};
-use v1::helpers::{TransactionRequest, FilledTransactionRequest, ConfirmationPayload};
+use v1::helpers::{GasPriceOracleConfig, TransactionRequest, FilledTransactionRequest, ConfirmationPayload};
 use v1::types::{
 	Bytes as RpcBytes,
+	EIP191Message,
 	RichRawTransaction as RpcRichRawTransaction,
 	ConfirmationPayload as RpcConfirmationPayload,
 	ConfirmationResponse,
@@ -132,6 +136,8 @@ pub enum SignMessage {
 	Data(Bytes),
 	/// Prefixed data hash
 	Hash(H256),
+	/// Hash of an EIP-191 message along with the message
+	EIP191(H256, EIP191Message),
 }
 
 /// Abstract transaction signer.
@@ -140,13 +146,13 @@ pub enum SignMessage {
 /// If accounts are ultimately removed all password-dealing endpoints will be wiped out.
 pub trait Accounts: Send + Sync {
 	/// Sign given filled transaction request for the specified chain_id.
-	fn sign_transaction(&self, filled: FilledTransactionRequest, chain_id: Option<u64>, nonce: U256, password: SignWith) -> Result<WithToken<SignedTransaction>>;
+	fn sign_transaction(&self, filled: FilledTransactionRequest, chain_id: Option<u64>, nonce: U256, password: SignWith) -> BoxFuture<WithToken<SignedTransaction>>;
 
 	/// Sign given message.
-	fn sign_message(&self, address: Address, password: SignWith, hash: SignMessage) -> Result<WithToken<Signature>>;
+	fn sign_message(&self, address: Address, password: SignWith, hash: SignMessage) -> BoxFuture<WithToken<Signature>>;
 
 	/// Decrypt given message.
-	fn decrypt(&self, address: Address, password: SignWith, data: Bytes) -> Result<WithToken<Bytes>>;
+	fn decrypt(&self, address: Address, password: SignWith, data: Bytes) -> BoxFuture<WithToken<Bytes>>;
 
 	/// Returns `true` if the accounts can sign multiple times.
 	fn supports_prospective_signing(&self, address: &Address, password: &SignWith) -> bool;
@@ -307,30 +313,29 @@ pub fn execute<D: Dispatcher + 'static>(
 				))
 		},
 		ConfirmationPayload::EthSignMessage(address, data) => {
-			let res = signer.sign_message(address, pass, SignMessage::Data(data))
+			Box::new(signer.sign_message(address, pass, SignMessage::Data(data))
 				.map(|result| result
 					.map(|s| H520(s.into_electrum()))
 					.map(ConfirmationResponse::Signature)
-				);
-
-			Box::new(future::done(res))
+				))
 		},
-		ConfirmationPayload::SignMessage(address, data) => {
-			let res = signer.sign_message(address, pass, SignMessage::Hash(data))
+		ConfirmationPayload::SignMessage(address, data, message) => {
+			let message = match message {
+				Some(message) => SignMessage::EIP191(data, message),
+				None => SignMessage::Hash(data),
+			};
+			Box::new(signer.sign_message(address, pass, message)
 				.map(|result| result
 					.map(|rsv| H520(rsv.into_electrum()))
 					.map(ConfirmationResponse::Signature)
-				);
-
-			Box::new(future::done(res))
+				))
 		},
 		ConfirmationPayload::Decrypt(address, data) => {
-			let res = signer.decrypt(address, pass, data)
+			Box::new(signer.decrypt(address, pass, data)
 				.map(|result| result
 					.map(RpcBytes)
 					.map(ConfirmationResponse::Decrypt)
-				);
-			Box::new(future::done(res))
+				))
 		},
 	}
 }
@@ -347,11 +352,25 @@ pub fn eth_data_hash(mut data: Bytes) -> H256 {
 }
 
 /// Extract the default gas price from a client and miner.
-pub fn default_gas_price<C, M>(client: &C, miner: &M, percentile: usize) -> U256 where
+pub fn default_gas_price<C, M>(client: &C, miner: &M, oracle: &GasPriceOracleConfig) -> U256 where
 	C: BlockChainClient,
 	M: MinerService,
 {
-	client.gas_price_corpus(100).percentile(percentile).cloned().unwrap_or_else(|| miner.sensible_gas_price())
+	oracle.suggest(&client.gas_price_corpus(oracle.blocks)).unwrap_or_else(|| miner.sensible_gas_price())
+}
+
+/// Fills in the max fee and max priority fee of an EIP-1559 request from the gas price,
+/// returns `None`s for other requests.
+pub fn dynamic_fees(request: &TransactionRequest, gas_price: U256) -> (Option<U256>, Option<U256>) {
+	if request.max_fee_per_gas.is_none() && request.max_priority_fee_per_gas.is_none() {
+		return (None, None);
+	}
+
+	let max_fee_per_gas = request.max_fee_per_gas
+		.unwrap_or_else(|| cmp::max(gas_price, request.max_priority_fee_per_gas.unwrap_or_default()));
+	let max_priority_fee_per_gas = request.max_priority_fee_per_gas
+		.unwrap_or_else(|| cmp::min(gas_price, max_fee_per_gas));
+	(Some(max_fee_per_gas), Some(max_priority_fee_per_gas))
 }
 
 /// Convert RPC confirmation payload to signer confirmation payload.
@@ -374,8 +393,8 @@ pub fn from_rpc<D>(payload: RpcConfirmationPayload, default_account: Address, di
 		RpcConfirmationPayload::EthSignMessage(RpcEthSignRequest { address, data }) => {
 			Box::new(future::ok(ConfirmationPayload::EthSignMessage(address, data.into())))
 		},
-		RpcConfirmationPayload::EIP191SignMessage(RpcSignRequest { address, data }) => {
-			Box::new(future::ok(ConfirmationPayload::SignMessage(address, data)))
+		RpcConfirmationPayload::EIP191SignMessage(RpcSignRequest { address, data, message }) => {
+			Box::new(future::ok(ConfirmationPayload::SignMessage(address, data, message)))
 		},
 	}
 }
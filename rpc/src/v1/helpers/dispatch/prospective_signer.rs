@@ -17,7 +17,7 @@
 use std::sync::Arc;
 
 use ethereum_types::U256;
-use jsonrpc_core::{Result, Error};
+use jsonrpc_core::{BoxFuture, Error};
 use jsonrpc_core::futures::{Future, Poll, Async, IntoFuture};
 use types::transaction::SignedTransaction;
 
@@ -28,6 +28,7 @@ use super::{Accounts, SignWith, WithToken, PostSign};
 enum ProspectiveSignerState {
 	TryProspectiveSign,
 	WaitForPostSign,
+	WaitForSign,
 	WaitForNonce,
 }
 
@@ -38,8 +39,9 @@ pub struct ProspectiveSigner<P: PostSign> {
 	reserved: nonce::Reserved,
 	password: SignWith,
 	state: ProspectiveSignerState,
-	prospective: Option<WithToken<SignedTransaction>>,
+	prospective: Option<BoxFuture<WithToken<SignedTransaction>>>,
 	ready: Option<nonce::Ready>,
+	sign_future: Option<BoxFuture<WithToken<SignedTransaction>>>,
 	post_sign: Option<P>,
 	post_sign_future: Option<<P::Out as IntoFuture>::Future>
 }
@@ -68,12 +70,13 @@ impl<P: PostSign> ProspectiveSigner<P> {
 			},
 			prospective: None,
 			ready: None,
+			sign_future: None,
 			post_sign: Some(post_sign),
 			post_sign_future: None
 		}
 	}
 
-	fn sign(&self, nonce: &U256) -> Result<WithToken<SignedTransaction>> {
+	fn sign(&self, nonce: &U256) -> BoxFuture<WithToken<SignedTransaction>> {
 		self.signer.sign_transaction(
 			self.filled.clone(),
 			self.chain_id,
@@ -101,31 +104,35 @@ impl<P: PostSign> Future for ProspectiveSigner<P> {
 					match self.poll_reserved()? {
 						Async::NotReady => {
 							self.state = WaitForNonce;
-							self.prospective = Some(self.sign(self.reserved.prospective_value())?);
+							self.prospective = Some(self.sign(self.reserved.prospective_value()));
 						},
 						Async::Ready(nonce) => {
-							self.state = WaitForPostSign;
-							self.post_sign_future = Some(
-								self.post_sign.take()
-									.expect("post_sign is set on creation; qed")
-									.execute(self.sign(nonce.value())?)
-									.into_future()
-							);
+							self.state = WaitForSign;
+							self.sign_future = Some(self.sign(nonce.value()));
 							self.ready = Some(nonce);
 						},
 					}
 				},
 				WaitForNonce => {
 					let nonce = try_ready!(self.poll_reserved());
-					let prospective = match (self.prospective.take(), nonce.matches_prospective()) {
+					let sign_future = match (self.prospective.take(), nonce.matches_prospective()) {
 						(Some(prospective), true) => prospective,
-						_ => self.sign(nonce.value())?,
+						_ => self.sign(nonce.value()),
 					};
 					self.ready = Some(nonce);
+					self.state = WaitForSign;
+					self.sign_future = Some(sign_future);
+				},
+				WaitForSign => {
+					let signed = try_ready!(self.sign_future
+						.as_mut()
+						.expect("sign_future is set before state transitions to WaitForSign; qed")
+						.poll());
+					self.sign_future = None;
 					self.state = WaitForPostSign;
 					self.post_sign_future = Some(self.post_sign.take()
 						.expect("post_sign is set on creation; qed")
-						.execute(prospective)
+						.execute(signed)
 						.into_future());
 				},
 				WaitForPostSign => {
@@ -0,0 +1,353 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Signer forwarding requests to an external, Clef-compatible signer process
+//! over HTTP or IPC, so that no keys are kept in the node process.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use bytes::Bytes;
+use crypto::publickey::{self, Signature};
+use ethereum_types::{Address, U256};
+use fetch::{self, Fetch, Method, Url};
+use hyper::header::{self, HeaderValue};
+use jsonrpc_core::{BoxFuture, Error, Result};
+use jsonrpc_core::futures::{future, Future, Stream};
+use jsonrpc_core::futures::sync::oneshot;
+use serde::de::DeserializeOwned;
+use serde_json::{self, Value};
+use types::transaction::{SignedTransaction, UnverifiedTransaction};
+
+use v1::helpers::{errors, FilledTransactionRequest};
+use v1::types::{AccessListItem, Bytes as RpcBytes, EIP191Message};
+
+use super::{eth_data_hash, WithToken, SignWith, SignMessage};
+
+/// Maximal time to wait for the external signer; requests might require
+/// a manual confirmation on the signer side.
+const REQUEST_TIMEOUT_SEC: u64 = 60;
+/// Maximal size of a response from the external signer.
+const MAX_RESPONSE_SIZE: usize = 4 * 1024 * 1024;
+
+/// Content type of `account_signData` requests signing an `eth_sign` message.
+const CONTENT_TYPE_TEXT: &str = "text/plain";
+/// Content type of `account_signData` requests signing data for an intended validator.
+const CONTENT_TYPE_VALIDATOR: &str = "data/validator";
+
+/// Serialized response of the external signer, or the reason it couldn't be obtained.
+pub type TransportFuture = Box<dyn Future<Item = String, Error = String> + Send>;
+
+/// Transport used to exchange JSON-RPC messages with the external signer.
+pub trait SignerTransport: Send + Sync {
+	/// Sends a serialized request. The response must not be waited for on the calling thread.
+	fn send(&self, request: String) -> TransportFuture;
+}
+
+/// Reaches the external signer through its HTTP endpoint.
+pub struct HttpTransport<F = fetch::Client> {
+	url: Url,
+	fetch: F,
+}
+
+impl<F: Fetch> HttpTransport<F> {
+	/// Creates new transport posting requests to given url.
+	pub fn new(url: Url, fetch: F) -> Self {
+		HttpTransport { url, fetch }
+	}
+}
+
+impl<F: Fetch> SignerTransport for HttpTransport<F> {
+	fn send(&self, request: String) -> TransportFuture {
+		let abort = fetch::Abort::default()
+			.with_max_duration(Duration::from_secs(REQUEST_TIMEOUT_SEC))
+			.with_max_size(MAX_RESPONSE_SIZE);
+		let request = fetch::Request::new(self.url.clone(), Method::POST)
+			.with_header(header::CONTENT_TYPE, HeaderValue::from_static("application/json"))
+			.with_body(request);
+
+		Box::new(self.fetch.fetch(request, abort)
+			.map_err(|e| format!("{:?}", e))
+			.and_then(|response| {
+				if !response.is_success() {
+					return future::Either::A(future::err(format!("Unexpected HTTP status: {}", response.status())));
+				}
+				future::Either::B(response.concat2().map_err(|e| format!("{:?}", e)))
+			})
+			.and_then(|body| String::from_utf8(body.to_vec()).map_err(|e| e.to_string())))
+	}
+}
+
+/// Reaches the external signer through its IPC socket.
+pub struct IpcTransport {
+	path: PathBuf,
+}
+
+impl IpcTransport {
+	/// Creates new transport connecting to the socket at given path.
+	pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+		IpcTransport { path: path.into() }
+	}
+}
+
+impl SignerTransport for IpcTransport {
+	fn send(&self, request: String) -> TransportFuture {
+		// the socket is used with blocking I/O, on a thread of its own.
+		let (tx, rx) = oneshot::channel();
+		let path = self.path.clone();
+		let spawned = thread::Builder::new().name("Remote Signer IPC".into()).spawn(move || {
+			let _ = tx.send(ipc_request(&path, &request));
+		});
+		if let Err(e) = spawned {
+			return Box::new(future::err(format!("Failed to spawn the IPC thread: {}", e)));
+		}
+
+		Box::new(rx.then(|response| match response {
+			Ok(response) => response,
+			Err(_) => Err("IPC request has been canceled".to_owned()),
+		}))
+	}
+}
+
+#[cfg(unix)]
+fn ipc_request(path: &PathBuf, request: &str) -> ::std::result::Result<String, String> {
+	use std::io::{Read, Write};
+	use std::os::unix::net::UnixStream;
+
+	let timeout = Some(Duration::from_secs(REQUEST_TIMEOUT_SEC));
+	let mut stream = UnixStream::connect(path).map_err(|e| e.to_string())?;
+	stream.set_read_timeout(timeout).map_err(|e| e.to_string())?;
+	stream.set_write_timeout(timeout).map_err(|e| e.to_string())?;
+	stream.write_all(request.as_bytes()).map_err(|e| e.to_string())?;
+
+	// responses aren't delimited, read a single JSON value off the stream.
+	let response = serde_json::Deserializer::from_reader(stream.take(MAX_RESPONSE_SIZE as u64))
+		.into_iter::<Value>()
+		.next()
+		.ok_or_else(|| "Connection closed by the external signer".to_owned())?
+		.map_err(|e| e.to_string())?;
+	Ok(response.to_string())
+}
+
+#[cfg(not(unix))]
+fn ipc_request(path: &PathBuf, _request: &str) -> ::std::result::Result<String, String> {
+	Err(format!("IPC signer endpoints are not supported on this platform: {}", path.display()))
+}
+
+#[derive(Serialize)]
+struct JsonRpcRequest<'a> {
+	jsonrpc: &'static str,
+	id: usize,
+	method: &'a str,
+	params: Vec<Value>,
+}
+
+#[derive(Deserialize)]
+struct JsonRpcResponse {
+	result: Option<Value>,
+	error: Option<Error>,
+}
+
+/// Transaction arguments of `account_signTransaction`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendTxArgs {
+	/// Sender
+	pub from: Address,
+	/// Recipient, `None` for contract creation
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub to: Option<Address>,
+	/// Gas
+	pub gas: U256,
+	/// Gas Price
+	pub gas_price: U256,
+	/// Value of transaction in wei
+	pub value: U256,
+	/// Transaction's nonce
+	pub nonce: U256,
+	/// Additional data sent with transaction
+	pub data: RpcBytes,
+	/// Chain id the transaction is replay protected for
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub chain_id: Option<U256>,
+	/// Access list, if this is an EIP-2930 or EIP-1559 transaction
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub access_list: Option<Vec<AccessListItem>>,
+	/// Max fee per gas, if this is an EIP-1559 transaction
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub max_fee_per_gas: Option<U256>,
+	/// Max priority fee per gas, if this is an EIP-1559 transaction
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub max_priority_fee_per_gas: Option<U256>,
+}
+
+/// Result of `account_signTransaction`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignTransactionResult {
+	/// Canonical encoding of the signed transaction
+	pub raw: RpcBytes,
+}
+
+/// Data of `account_signData` requests for an intended validator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorData {
+	/// Intended validator
+	pub address: Address,
+	/// Data to sign
+	pub message: RpcBytes,
+}
+
+/// Signer delegating to an external signer process.
+pub struct RemoteSigner {
+	transport: Box<dyn SignerTransport>,
+	next_id: AtomicUsize,
+}
+
+impl RemoteSigner {
+	/// Creates new signer using given transport.
+	pub fn new(transport: Box<dyn SignerTransport>) -> Self {
+		RemoteSigner {
+			transport,
+			next_id: AtomicUsize::new(1),
+		}
+	}
+
+	/// Creates new signer for given endpoint: either an `http(s)://` url or a path to an IPC socket.
+	pub fn with_endpoint<F: Fetch>(endpoint: &str, fetch: F) -> ::std::result::Result<Self, String> {
+		let transport: Box<dyn SignerTransport> = if endpoint.starts_with("http://") || endpoint.starts_with("https://") {
+			let url = Url::parse(endpoint).map_err(|e| format!("Invalid signer endpoint {}: {}", endpoint, e))?;
+			Box::new(HttpTransport::new(url, fetch))
+		} else {
+			Box::new(IpcTransport::new(endpoint))
+		};
+		Ok(RemoteSigner::new(transport))
+	}
+
+	/// Returns accounts managed by the external signer.
+	pub fn accounts(&self) -> Result<Vec<Address>> {
+		// listing the accounts is synchronous for the callers, the response is received
+		// by the transport so waiting for it here can't stall it.
+		self.call("account_list", vec![]).wait()
+	}
+
+	fn call<T: DeserializeOwned + Send + 'static>(&self, method: &str, params: Vec<Value>) -> BoxFuture<T> {
+		let request = JsonRpcRequest {
+			jsonrpc: "2.0",
+			id: self.next_id.fetch_add(1, Ordering::SeqCst),
+			method,
+			params,
+		};
+		let request = serde_json::to_string(&request).expect("Request consists of serializable values only; qed");
+		trace!(target: "remote_signer", "Request: {}", request);
+
+		Box::new(self.transport.send(request)
+			.map_err(errors::external_signer)
+			.and_then(|response| {
+				trace!(target: "remote_signer", "Response: {}", response);
+
+				let response: JsonRpcResponse = serde_json::from_str(&response).map_err(errors::external_signer)?;
+				match (response.result, response.error) {
+					(_, Some(error)) => Err(errors::external_signer_rejected(error)),
+					(Some(result), None) => serde_json::from_value(result).map_err(errors::external_signer),
+					(None, None) => Err(errors::external_signer("Response without result")),
+				}
+			}))
+	}
+}
+
+impl super::Accounts for RemoteSigner {
+	fn sign_transaction(&self, filled: FilledTransactionRequest, chain_id: Option<u64>, nonce: U256, _password: SignWith) -> BoxFuture<WithToken<SignedTransaction>> {
+		let from = filled.from;
+		let args = SendTxArgs {
+			from,
+			to: filled.to,
+			gas: filled.gas,
+			gas_price: filled.gas_price,
+			value: filled.value,
+			nonce,
+			data: filled.data.into(),
+			chain_id: chain_id.map(Into::into),
+			access_list: filled.access_list.map(|list| list.into_iter().map(Into::into).collect()),
+			max_fee_per_gas: filled.max_fee_per_gas,
+			max_priority_fee_per_gas: filled.max_priority_fee_per_gas,
+		};
+		let args = serde_json::to_value(args).expect("SendTxArgs is serializable; qed");
+
+		Box::new(self.call("account_signTransaction", vec![args]).and_then(move |result: SignTransactionResult| {
+			let signed = UnverifiedTransaction::decode_raw(&result.raw.into_vec())
+				.map_err(errors::rlp)
+				.and_then(|tx| SignedTransaction::new(tx).map_err(errors::transaction))?;
+			if signed.sender() != from {
+				return Err(errors::external_signer(format!("Transaction signed by {:?} instead of {:?}", signed.sender(), from)));
+			}
+
+			Ok(WithToken::No(signed))
+		}))
+	}
+
+	fn sign_message(&self, address: Address, _password: SignWith, hash: SignMessage) -> BoxFuture<WithToken<Signature>> {
+		let address_param = serde_json::to_value(address).expect("Address is serializable; qed");
+		let bytes_param = |data: Bytes| serde_json::to_value(RpcBytes::new(data)).expect("Bytes are serializable; qed");
+		let (expected_hash, method, params) = match hash {
+			SignMessage::Data(data) | SignMessage::EIP191(_, EIP191Message::PersonalMessage(data)) => {
+				(eth_data_hash(data.clone()), "account_signData", vec![Value::String(CONTENT_TYPE_TEXT.into()), address_param, bytes_param(data)])
+			},
+			SignMessage::EIP191(hash, EIP191Message::PresignedTransaction(validator, data)) => {
+				let data = ValidatorData { address: validator, message: RpcBytes::new(data) };
+				let data = serde_json::to_value(data).expect("ValidatorData is serializable; qed");
+				(hash, "account_signData", vec![Value::String(CONTENT_TYPE_VALIDATOR.into()), address_param, data])
+			},
+			SignMessage::EIP191(hash, EIP191Message::StructuredData(typed_data)) => {
+				let typed_data = try_bf!(serde_json::from_str(&typed_data).map_err(errors::external_signer));
+				(hash, "account_signTypedData", vec![address_param, typed_data])
+			},
+			SignMessage::Hash(_) => return Box::new(future::err(errors::unsupported("Signing a message hash is not supported by the external signer", None))),
+		};
+
+		Box::new(self.call(method, params).and_then(move |signature: RpcBytes| {
+			if signature.0.len() != 65 {
+				return Err(errors::external_signer(format!("Invalid signature length: {}", signature.0.len())));
+			}
+			let signature = Signature::from_electrum(&signature.0);
+			// the signer hashes the message on its own, make sure it signed what was requested.
+			match publickey::verify_address(&address, &signature, &expected_hash) {
+				Ok(true) => Ok(WithToken::No(signature)),
+				Ok(false) => Err(errors::external_signer("Signature doesn't match the requested message")),
+				Err(e) => Err(errors::external_signer(e)),
+			}
+		}))
+	}
+
+	fn decrypt(&self, _address: Address, _password: SignWith, _data: Bytes) -> BoxFuture<WithToken<Bytes>> {
+		Box::new(future::err(errors::unsupported("Decryption is not supported by the external signer", None)))
+	}
+
+	fn supports_prospective_signing(&self, _address: &Address, _password: &SignWith) -> bool {
+		// every request might have to be confirmed in the external signer
+		false
+	}
+
+	fn default_account(&self) -> Address {
+		self.accounts().ok().and_then(|accounts| accounts.into_iter().next()).unwrap_or_default()
+	}
+
+	fn is_unlocked(&self, address: &Address) -> bool {
+		// the external signer takes care of confirming requests for the accounts it manages
+		self.accounts().map(|accounts| accounts.contains(address)).unwrap_or(false)
+	}
+}
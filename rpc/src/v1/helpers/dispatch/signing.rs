@@ -21,9 +21,10 @@ use bytes::Bytes;
 use crypto::DEFAULT_MAC;
 use ethereum_types::{H256, U256, Address};
 use crypto::publickey::Signature;
-use types::transaction::{Transaction, Action, SignedTransaction};
+use types::transaction::{Transaction, TypedTransaction, AccessListTx, EIP1559Tx, Action, SignedTransaction};
 
-use jsonrpc_core::Result;
+use jsonrpc_core::{BoxFuture, Result};
+use jsonrpc_core::futures::future;
 use v1::helpers::{errors, FilledTransactionRequest};
 
 use super::{eth_data_hash, WithToken, SignWith, SignMessage};
@@ -41,46 +42,51 @@ impl Signer {
 }
 
 impl super::Accounts for Signer {
-	fn sign_transaction(&self, filled: FilledTransactionRequest, chain_id: Option<u64>, nonce: U256, password: SignWith) -> Result<WithToken<SignedTransaction>> {
+	fn sign_transaction(&self, filled: FilledTransactionRequest, chain_id: Option<u64>, nonce: U256, password: SignWith) -> BoxFuture<WithToken<SignedTransaction>> {
 		let t = Transaction {
 			nonce: nonce,
 			action: filled.to.map_or(Action::Create, Action::Call),
 			gas: filled.gas,
-			gas_price: filled.gas_price,
+			gas_price: filled.max_fee_per_gas.unwrap_or(filled.gas_price),
 			value: filled.value,
 			data: filled.data,
 		};
+		let t = match (filled.max_priority_fee_per_gas, filled.access_list) {
+			(Some(max_priority_fee_per_gas), access_list) => TypedTransaction::EIP1559(EIP1559Tx::new(
+				AccessListTx::new(t, access_list.unwrap_or_default()),
+				max_priority_fee_per_gas,
+			)),
+			(None, Some(access_list)) => TypedTransaction::AccessList(AccessListTx::new(t, access_list)),
+			(None, None) => TypedTransaction::Legacy(t),
+		};
 
 		let hash = t.hash(chain_id);
-		let signature = signature(&*self.accounts, filled.from, hash, password)?;
+		let signature = signature(&*self.accounts, filled.from, hash, password);
 
-		Ok(signature.map(|sig| {
+		Box::new(future::done(signature.map(|signature| signature.map(|sig| {
 			SignedTransaction::new(t.with_signature(sig, chain_id))
 				.expect("Transaction was signed by AccountsProvider; it never produces invalid signatures; qed")
-		}))
+		}))))
 	}
 
-	fn sign_message(&self, address: Address, password: SignWith, hash: SignMessage) -> Result<WithToken<Signature>> {
-		match hash {
-			SignMessage::Data(data) => {
-				let hash = eth_data_hash(data);
-				signature(&self.accounts, address, hash, password)
-			},
-			SignMessage::Hash(hash) => {
-				signature(&self.accounts, address, hash, password)
-			}
-		}
+	fn sign_message(&self, address: Address, password: SignWith, hash: SignMessage) -> BoxFuture<WithToken<Signature>> {
+		let hash = match hash {
+			SignMessage::Data(data) => eth_data_hash(data),
+			SignMessage::Hash(hash) | SignMessage::EIP191(hash, _) => hash,
+		};
+		Box::new(future::done(signature(&self.accounts, address, hash, password)))
 	}
 
-	fn decrypt(&self, address: Address, password: SignWith, data: Bytes) -> Result<WithToken<Bytes>> {
-		match password.clone() {
+	fn decrypt(&self, address: Address, password: SignWith, data: Bytes) -> BoxFuture<WithToken<Bytes>> {
+		let result = match password.clone() {
 			SignWith::Nothing => self.accounts.decrypt(address, None, &DEFAULT_MAC, &data).map(WithToken::No),
 			SignWith::Password(pass) => self.accounts.decrypt(address, Some(pass), &DEFAULT_MAC, &data).map(WithToken::No),
 			SignWith::Token(token) => self.accounts.decrypt_with_token(address, token, &DEFAULT_MAC, &data).map(Into::into),
 		}.map_err(|e| match password {
 			SignWith::Nothing => errors::signing(e),
 			_ => errors::password(e),
-		})
+		});
+		Box::new(future::done(result))
 	}
 
 	fn supports_prospective_signing(&self, address: &Address, password: &SignWith) -> bool {
@@ -15,9 +15,9 @@
 // along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
 //! EIP-191 compliant decoding + hashing
-use v1::types::{EIP191Version, Bytes, PresignedTransaction};
-use eip_712::{hash_structured_data, EIP712};
-use serde_json::{Value, from_value};
+use v1::types::{EIP191Message, EIP191Version, Bytes, PresignedTransaction};
+use eip_712::{hash_structured_data, hash_structured_data_with_version, EIP712, Version};
+use serde_json::{self, Value, from_value};
 use v1::helpers::errors;
 use jsonrpc_core::Error;
 use v1::helpers::dispatch::eth_data_hash;
@@ -25,35 +25,51 @@ use hash::keccak;
 use std::fmt::Display;
 use ethereum_types::H256;
 
-/// deserializes and hashes the message depending on the version specifier
-pub fn hash_message(version: EIP191Version, message: Value) -> Result<H256, Error> {
+/// deserializes and hashes the message depending on the version specifier,
+/// returns the hash along with the deserialized message
+pub fn hash_message(version: EIP191Version, message: Value) -> Result<(H256, EIP191Message), Error> {
 	let data = match version {
 		EIP191Version::StructuredData => {
 			let typed_data = from_value::<EIP712>(message)
 				.map_err(map_serde_err("StructuredData"))?;
+			let message = structured_data(&typed_data);
 
-			hash_structured_data(typed_data)
-				.map_err(|err| errors::invalid_call_data(err.kind()))?
+			let hash = hash_structured_data(typed_data)
+				.map_err(|err| errors::invalid_call_data(err.kind()))?;
+			(hash, message)
 		}
 
 		EIP191Version::PresignedTransaction => {
 			let data = from_value::<PresignedTransaction>(message)
 				.map_err(map_serde_err("WithValidator"))?;
 			let prefix = b"\x19\x00";
-			let data = [&prefix[..], &data.validator.0[..], &data.data.0[..]].concat();
-			keccak(data)
+			let hash = keccak([&prefix[..], &data.validator.0[..], &data.data.0[..]].concat());
+			(hash, EIP191Message::PresignedTransaction(data.validator, data.data.0))
 		}
 
 		EIP191Version::PersonalMessage => {
 			let bytes = from_value::<Bytes>(message)
 				.map_err(map_serde_err("Bytes"))?;
-			eth_data_hash(bytes.0)
+			(eth_data_hash(bytes.0.clone()), EIP191Message::PersonalMessage(bytes.0))
 		}
 	};
 
 	Ok(data)
 }
 
+/// hashes EIP-712 structured data following the rules of the given `eth_signTypedData` version,
+/// returns the hash along with the message
+pub fn hash_typed_data(typed_data: EIP712, version: Version) -> Result<(H256, EIP191Message), Error> {
+	let message = structured_data(&typed_data);
+	let hash = hash_structured_data_with_version(typed_data, version)
+		.map_err(|err| errors::invalid_call_data(err.kind()))?;
+	Ok((hash, message))
+}
+
+fn structured_data(typed_data: &EIP712) -> EIP191Message {
+	EIP191Message::StructuredData(serde_json::to_string(typed_data).expect("EIP712 is serializable; qed"))
+}
+
 fn map_serde_err<T: Display>(struct_name: &'static str) -> impl Fn(T) -> Error {
 	move |error: T| {
 		errors::invalid_call_data(format!("Error deserializing '{}': {}", struct_name, error))
@@ -24,6 +24,7 @@ use types::transaction::Error as TransactionError;
 use ethcore_private_tx::Error as PrivateTransactionError;
 use vm::Error as VMError;
 use light::on_demand::error::{Error as OnDemandError};
+use miner::bundle::Error as BundleError;
 use client_traits::BlockChainClient;
 use types::{
 	ids::BlockId,
@@ -54,6 +55,7 @@ mod codes {
 	pub const PASSWORD_INVALID: i64 = -32021;
 	pub const ACCOUNT_ERROR: i64 = -32023;
 	pub const PRIVATE_ERROR: i64 = -32024;
+	pub const EXTERNAL_SIGNER_ERROR: i64 = -32025;
 	pub const REQUEST_REJECTED: i64 = -32040;
 	pub const REQUEST_REJECTED_LIMIT: i64 = -32041;
 	pub const REQUEST_NOT_FOUND: i64 = -32042;
@@ -107,6 +109,22 @@ pub fn request_rejected() -> Error {
 	}
 }
 
+pub fn external_signer_rejected(error: Error) -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::REQUEST_REJECTED),
+		message: "Request has been rejected by the external signer.".into(),
+		data: Some(Value::String(error.message)),
+	}
+}
+
+pub fn external_signer<T: fmt::Display>(error: T) -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::EXTERNAL_SIGNER_ERROR),
+		message: "Error communicating with the external signer.".into(),
+		data: Some(Value::String(format!("{}", error))),
+	}
+}
+
 pub fn request_rejected_limit() -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::REQUEST_REJECTED_LIMIT),
@@ -460,6 +478,13 @@ pub fn transaction_message(error: &TransactionError) -> String {
 		NotAllowed => "Transaction is not permitted.".into(),
 		TooBig => "Transaction is too big, see chain specification for the limit.".into(),
 		InvalidRlp(ref descr) => format!("Invalid RLP data: {}", descr),
+		TransactionTypeNotEnabled => "Transaction type is not enabled on this chain yet.".into(),
+		MaxPriorityFeeTooHigh { max_fee, max_priority_fee } => {
+			format!("Max priority fee per gas ({}) is higher than max fee per gas ({}).", max_priority_fee, max_fee)
+		}
+		GasPriceLowerThanBaseFee { gas_price, base_fee } => {
+			format!("Max fee per gas ({}) is lower than the block base fee ({}). Try increasing the max fee.", gas_price, base_fee)
+		}
 	}
 }
 
@@ -480,6 +505,22 @@ pub fn transaction<T: Into<EthcoreError>>(error: T) -> Error {
 	}
 }
 
+pub fn bundle(error: BundleError) -> Error {
+	let code = match error {
+		BundleError::Outdated { .. } | BundleError::TooFarAhead { .. } | BundleError::AlreadyImported
+			| BundleError::LimitReached | BundleError::BlockLimitReached(_)
+			| BundleError::Transaction(..) => ErrorCode::ServerError(codes::TRANSACTION_ERROR),
+		BundleError::Empty => ErrorCode::InvalidParams,
+		BundleError::Block(_) => ErrorCode::ServerError(codes::UNKNOWN_ERROR),
+	};
+
+	Error {
+		code,
+		message: format!("{}.", error),
+		data: None,
+	}
+}
+
 pub fn decode<T: Into<EthcoreError>>(error: T) -> Error {
 	match error.into() {
 		EthcoreError::Decoder(ref dec_err) => rlp(dec_err.clone()),
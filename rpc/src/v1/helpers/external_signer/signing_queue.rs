@@ -231,6 +231,9 @@ mod test {
 			data: vec![],
 			nonce: None,
 			condition: None,
+			access_list: None,
+			max_fee_per_gas: None,
+			max_priority_fee_per_gas: None,
 		})
 	}
 
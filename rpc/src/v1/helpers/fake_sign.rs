@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
-use types::transaction::{Transaction, SignedTransaction, Action};
+use types::transaction::{Transaction, TypedTransaction, AccessListTx, EIP1559Tx, SignedTransaction, Action};
 use std::cmp::min;
 
 use ethereum_types::U256;
@@ -26,12 +26,24 @@ pub fn sign_call(request: CallRequest) -> Result<SignedTransaction, Error> {
 	let gas = min(request.gas.unwrap_or(max_gas), max_gas);
 	let from = request.from.unwrap_or_default();
 
-	Ok(Transaction {
+	let tx = Transaction {
 		nonce: request.nonce.unwrap_or_default(),
 		action: request.to.map_or(Action::Create, Action::Call),
 		gas,
-		gas_price: request.gas_price.unwrap_or_default(),
+		gas_price: request.max_fee_per_gas.or(request.gas_price).unwrap_or_default(),
 		value: request.value.unwrap_or_default(),
 		data: request.data.unwrap_or_default(),
-	}.fake_sign(from))
+	};
+
+	let dynamic_fee = request.max_fee_per_gas.is_some() || request.max_priority_fee_per_gas.is_some();
+	let tx = match (dynamic_fee, request.access_list) {
+		(true, access_list) => TypedTransaction::EIP1559(EIP1559Tx::new(
+			AccessListTx::new(tx, access_list.unwrap_or_default()),
+			request.max_priority_fee_per_gas.unwrap_or_default(),
+		)),
+		(false, Some(access_list)) => TypedTransaction::AccessList(AccessListTx::new(tx, access_list)),
+		(false, None) => TypedTransaction::Legacy(tx),
+	};
+
+	Ok(tx.fake_sign(from))
 }
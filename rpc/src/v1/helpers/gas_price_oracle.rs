@@ -0,0 +1,278 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Gas price oracle and fee history, shared by the full and light RPC implementations.
+
+use ethereum_types::U256;
+use jsonrpc_core::Error;
+use stats::Corpus;
+use types::encoded;
+use types::receipt::Receipt;
+
+use v1::helpers::errors;
+use v1::types::FeeHistory;
+
+/// Maximal number of blocks a single `eth_feeHistory` call reports on.
+pub const MAX_FEE_HISTORY_BLOCKS: u64 = 1024;
+
+/// Configuration of the gas price oracle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GasPriceOracleConfig {
+	/// Number of recent blocks whose transactions are sampled.
+	pub blocks: usize,
+	/// Percentile of the sampled gas prices that is suggested.
+	pub percentile: usize,
+	/// Gas prices below this value are not sampled.
+	pub ignore_below: U256,
+}
+
+impl Default for GasPriceOracleConfig {
+	fn default() -> Self {
+		GasPriceOracleConfig {
+			blocks: 100,
+			percentile: 50,
+			ignore_below: U256::zero(),
+		}
+	}
+}
+
+impl GasPriceOracleConfig {
+	/// Suggests a gas price from a corpus of recently paid gas prices.
+	pub fn suggest(&self, corpus: &Corpus<U256>) -> Option<U256> {
+		let sampled: Corpus<U256> = corpus.iter()
+			.filter(|price| **price >= self.ignore_below)
+			.cloned()
+			.collect();
+		sampled.percentile(self.percentile).cloned()
+	}
+}
+
+/// Fees paid in a single block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockFees {
+	/// Base fee per gas of the block, zero before EIP-1559.
+	pub base_fee: U256,
+	/// Ratio of gas used to gas limit.
+	pub gas_used_ratio: f64,
+	/// Effective priority fees at the requested percentiles of gas used.
+	pub rewards: Vec<U256>,
+}
+
+impl BlockFees {
+	/// Computes the fees paid in `block`. Receipts are only used to weigh the rewards by gas used
+	/// and can be left empty when no percentiles are requested.
+	pub fn new(block: &encoded::Block, base_fee: Option<U256>, receipts: &[Receipt], percentiles: &[f64]) -> Self {
+		let gas_used = block.gas_used();
+		let gas_used_ratio = gas_used_ratio(gas_used, block.gas_limit());
+
+		// (effective priority fee, gas used) of every transaction, cheapest first
+		let mut tips = block.transactions().iter()
+			.zip(receipts)
+			.scan(U256::zero(), |cumulative_gas_used, (tx, receipt)| {
+				let tx_gas_used = receipt.gas_used.saturating_sub(*cumulative_gas_used);
+				*cumulative_gas_used = receipt.gas_used;
+				Some((tx.effective_priority_fee(base_fee), tx_gas_used))
+			})
+			.collect::<Vec<_>>();
+		tips.sort_by_key(|&(tip, _)| tip);
+
+		let rewards = percentiles.iter()
+			.map(|percentile| {
+				let threshold = gas_used.low_u64() as f64 * percentile / 100.0;
+				let mut sum = U256::zero();
+				tips.iter()
+					.find(|&&(_, tx_gas_used)| {
+						sum = sum.saturating_add(tx_gas_used);
+						sum.low_u64() as f64 >= threshold
+					})
+					.or_else(|| tips.last())
+					.map_or_else(U256::zero, |&(tip, _)| tip)
+			})
+			.collect();
+
+		BlockFees {
+			base_fee: base_fee.unwrap_or_default(),
+			gas_used_ratio,
+			rewards,
+		}
+	}
+
+	/// Computes the fees of a block from its header alone, without any rewards.
+	/// Enough when no percentiles are requested.
+	pub fn from_header(header: &encoded::Header, base_fee: Option<U256>) -> Self {
+		BlockFees {
+			base_fee: base_fee.unwrap_or_default(),
+			gas_used_ratio: gas_used_ratio(header.gas_used(), header.gas_limit()),
+			rewards: Vec::new(),
+		}
+	}
+}
+
+fn gas_used_ratio(gas_used: U256, gas_limit: U256) -> f64 {
+	if gas_limit.is_zero() {
+		0.0
+	} else {
+		gas_used.low_u64() as f64 / gas_limit.low_u64() as f64
+	}
+}
+
+/// Validates the parameters of `eth_feeHistory`, returning the number of blocks to report on.
+pub fn fee_history_block_count(block_count: U256, percentiles: &[f64]) -> Result<u64, Error> {
+	if block_count.is_zero() || block_count > U256::from(MAX_FEE_HISTORY_BLOCKS) {
+		return Err(errors::invalid_params("blockCount", format!("Expected between 1 and {} blocks", MAX_FEE_HISTORY_BLOCKS)));
+	}
+
+	let in_range = percentiles.iter().all(|p| *p >= 0.0 && *p <= 100.0);
+	let increasing = percentiles.windows(2).all(|w| w[0] <= w[1]);
+	if !in_range || !increasing {
+		return Err(errors::invalid_params("rewardPercentiles", "Expected increasing percentiles between 0 and 100"));
+	}
+
+	Ok(block_count.low_u64())
+}
+
+/// Assembles the fee history of consecutive blocks, oldest first.
+pub fn fee_history(oldest_block: u64, blocks: Vec<BlockFees>, next_base_fee: U256, with_rewards: bool) -> FeeHistory {
+	let mut history = FeeHistory {
+		oldest_block: oldest_block.into(),
+		base_fee_per_gas: Vec::with_capacity(blocks.len() + 1),
+		gas_used_ratio: Vec::with_capacity(blocks.len()),
+		reward: if with_rewards { Some(Vec::with_capacity(blocks.len())) } else { None },
+	};
+
+	for block in blocks {
+		history.base_fee_per_gas.push(block.base_fee);
+		history.gas_used_ratio.push(block.gas_used_ratio);
+		if let Some(ref mut reward) = history.reward {
+			reward.push(block.rewards);
+		}
+	}
+	history.base_fee_per_gas.push(next_base_fee);
+
+	history
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ethkey::{Generator, Random};
+	use rlp::RlpStream;
+	use types::header::Header;
+	use types::receipt::TransactionOutcome;
+	use types::transaction::{AccessListTx, EIP1559Tx, Action, Transaction, TypedTransaction};
+
+	fn block(gas_used: u64, transactions: Vec<TypedTransaction>) -> encoded::Block {
+		let mut header = Header::new();
+		header.set_gas_limit(1_000_000.into());
+		header.set_gas_used(gas_used.into());
+
+		let keypair = Random.generate().unwrap();
+		let mut stream = RlpStream::new_list(3);
+		stream.append(&header);
+		stream.begin_list(transactions.len());
+		for tx in transactions {
+			stream.append(&tx.sign(keypair.secret(), None));
+		}
+		stream.append_raw(&::rlp::EMPTY_LIST_RLP, 1);
+		encoded::Block::new(stream.out())
+	}
+
+	fn transaction(gas_price: u64) -> Transaction {
+		Transaction {
+			action: Action::Create,
+			nonce: 0.into(),
+			gas_price: gas_price.into(),
+			gas: 100_000.into(),
+			value: 0.into(),
+			data: vec![],
+		}
+	}
+
+	fn legacy(gas_price: u64) -> TypedTransaction {
+		TypedTransaction::Legacy(transaction(gas_price))
+	}
+
+	fn dynamic_fee(max_fee: u64, max_priority_fee: u64) -> TypedTransaction {
+		let transaction = AccessListTx::new(transaction(max_fee), vec![]);
+		TypedTransaction::EIP1559(EIP1559Tx::new(transaction, max_priority_fee.into()))
+	}
+
+	fn receipts(cumulative_gas_used: &[u64]) -> Vec<Receipt> {
+		cumulative_gas_used.iter()
+			.map(|gas| Receipt::new(TransactionOutcome::Unknown, (*gas).into(), vec![]))
+			.collect()
+	}
+
+	#[test]
+	fn should_ignore_cheap_prices() {
+		let corpus: Corpus<U256> = vec![1.into(), 2.into(), 10.into(), 20.into(), 30.into()].into();
+		let config = GasPriceOracleConfig { ignore_below: 10.into(), ..Default::default() };
+		assert_eq!(config.suggest(&corpus), Some(10.into()));
+		assert_eq!(GasPriceOracleConfig::default().suggest(&corpus), Some(2.into()));
+
+		let config = GasPriceOracleConfig { ignore_below: 100.into(), ..Default::default() };
+		assert_eq!(config.suggest(&corpus), None);
+	}
+
+	#[test]
+	fn should_weigh_rewards_by_gas_used() {
+		// tips: 5 (gas 21000), 1 (gas 100000), 3 (gas 29000)
+		let block = block(150_000, vec![legacy(15), dynamic_fee(20, 1), dynamic_fee(13, 5)]);
+		let receipts = receipts(&[21_000, 121_000, 150_000]);
+
+		let fees = BlockFees::new(&block, Some(10.into()), &receipts, &[0.0, 50.0, 70.0, 100.0]);
+		assert_eq!(fees.base_fee, 10.into());
+		assert_eq!(fees.gas_used_ratio, 0.15);
+		assert_eq!(fees.rewards, vec![1.into(), 1.into(), 3.into(), 5.into()]);
+	}
+
+	#[test]
+	fn should_report_zero_rewards_for_empty_blocks() {
+		let fees = BlockFees::new(&block(0, vec![]), None, &[], &[25.0, 75.0]);
+		assert_eq!(fees, BlockFees { base_fee: 0.into(), gas_used_ratio: 0.0, rewards: vec![0.into(), 0.into()] });
+	}
+
+	#[test]
+	fn should_compute_fees_from_header() {
+		let mut header = Header::new();
+		header.set_gas_limit(1_000_000.into());
+		header.set_gas_used(250_000.into());
+
+		let fees = BlockFees::from_header(&header.encoded(), Some(7.into()));
+		assert_eq!(fees, BlockFees { base_fee: 7.into(), gas_used_ratio: 0.25, rewards: vec![] });
+	}
+
+	#[test]
+	fn should_validate_fee_history_params() {
+		assert_eq!(fee_history_block_count(4.into(), &[10.0, 10.0, 90.0]), Ok(4));
+		assert!(fee_history_block_count(0.into(), &[]).is_err());
+		assert!(fee_history_block_count(1025.into(), &[]).is_err());
+		assert!(fee_history_block_count(1.into(), &[50.0, 10.0]).is_err());
+		assert!(fee_history_block_count(1.into(), &[101.0]).is_err());
+	}
+
+	#[test]
+	fn should_assemble_fee_history() {
+		let fees = |base_fee: u64| BlockFees { base_fee: base_fee.into(), gas_used_ratio: 0.5, rewards: vec![1.into()] };
+		let history = fee_history(7, vec![fees(10), fees(11)], 12.into(), false);
+		assert_eq!(history, FeeHistory {
+			oldest_block: 7.into(),
+			base_fee_per_gas: vec![10.into(), 11.into(), 12.into()],
+			gas_used_ratio: vec![0.5, 0.5],
+			reward: None,
+		});
+	}
+}
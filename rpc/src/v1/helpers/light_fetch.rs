@@ -52,9 +52,12 @@ use ethereum_types::{Address, U256};
 use hash::H256;
 use parking_lot::{Mutex, RwLock};
 use fastmap::H256FastMap;
-use types::transaction::{Action, Transaction as EthTransaction, PendingTransaction, SignedTransaction, LocalizedTransaction};
+use types::transaction::{
+	Action, Transaction as EthTransaction, TypedTransaction, AccessListTx, EIP1559Tx, PendingTransaction,
+	SignedTransaction, LocalizedTransaction,
+};
 
-use v1::helpers::{CallRequest as CallRequestHelper, errors, dispatch};
+use v1::helpers::{CallRequest as CallRequestHelper, GasPriceOracleConfig, errors, dispatch};
 use v1::types::{BlockNumber, CallRequest, Log, Transaction};
 
 const NO_INVALID_BACK_REFS_PROOF: &str = "Fails only on invalid back-references; back-references here known to be valid; qed";
@@ -89,8 +92,8 @@ where
 	pub sync: Arc<S>,
 	/// The light data cache.
 	pub cache: Arc<Mutex<Cache>>,
-	/// Gas Price percentile
-	pub gas_price_percentile: usize,
+	/// Gas price oracle
+	pub gas_price_oracle: GasPriceOracleConfig,
 }
 
 impl<S, OD> Clone for LightFetch<S, OD>
@@ -104,7 +107,7 @@ where
 			on_demand: self.on_demand.clone(),
 			sync: self.sync.clone(),
 			cache: self.cache.clone(),
-			gas_price_percentile: self.gas_price_percentile,
+			gas_price_oracle: self.gas_price_oracle,
 		}
 	}
 }
@@ -279,7 +282,7 @@ where
 			None => Either::B(self.account(from, id, txq).map(|acc| acc.map(|a| a.nonce))),
 		};
 
-		let gas_price_fut = match req.gas_price {
+		let gas_price_fut = match req.max_fee_per_gas.or(req.gas_price) {
 			Some(price) => Either::A(future::ok(price)),
 			None => Either::B(self.gas_price()),
 		};
@@ -289,16 +292,24 @@ where
 
 		// fetch missing transaction fields from the network.
 		Box::new(nonce_fut.join(gas_price_fut).and_then(move |(nonce, gas_price)| {
-			future::done(
-				Ok((req.gas.is_some(), EthTransaction {
-					nonce: nonce.unwrap_or_default(),
-					action: req.to.map_or(Action::Create, Action::Call),
-					gas: req.gas.unwrap_or_else(|| START_GAS.into()),
-					gas_price,
-					value: req.value.unwrap_or_default(),
-					data: req.data.unwrap_or_default(),
-				}))
-			)
+			let tx = EthTransaction {
+				nonce: nonce.unwrap_or_default(),
+				action: req.to.map_or(Action::Create, Action::Call),
+				gas: req.gas.unwrap_or_else(|| START_GAS.into()),
+				gas_price,
+				value: req.value.unwrap_or_default(),
+				data: req.data.unwrap_or_default(),
+			};
+			let dynamic_fee = req.max_fee_per_gas.is_some() || req.max_priority_fee_per_gas.is_some();
+			let tx = match (dynamic_fee, req.access_list) {
+				(true, access_list) => TypedTransaction::EIP1559(EIP1559Tx::new(
+					AccessListTx::new(tx, access_list.unwrap_or_default()),
+					req.max_priority_fee_per_gas.unwrap_or_default(),
+				)),
+				(false, Some(access_list)) => TypedTransaction::AccessList(AccessListTx::new(tx, access_list)),
+				(false, None) => TypedTransaction::Legacy(tx),
+			};
+			future::done(Ok((req.gas.is_some(), tx)))
 		}).join(header_fut).and_then(move |((gas_known, tx), hdr)| {
 			// then request proved execution.
 			// TODO: get last-hashes from network.
@@ -320,20 +331,21 @@ where
 		}))
 	}
 
-	/// Helper to fetch the corpus gas price from 1) the cache 2) the network then it tries to estimate the percentile
-	/// using `gas_price_percentile` if the estimated percentile is zero the `DEFAULT_GAS_PRICE` is returned
+	/// Helper to fetch the corpus gas price from 1) the cache 2) the network then it asks the
+	/// `gas_price_oracle` for a price; if it has no suggestion the `DEFAULT_GAS_PRICE` is returned
 	pub fn gas_price(&self) -> impl Future<Item = U256, Error = Error> + Send {
-		let gas_price_percentile = self.gas_price_percentile;
+		let gas_price_oracle = self.gas_price_oracle;
 
 		dispatch::light::fetch_gas_price_corpus(
 			self.sync.clone(),
 			self.client.clone(),
 			self.on_demand.clone(),
 			self.cache.clone(),
+			gas_price_oracle.blocks,
 		)
 		.map(move |corp| {
-			corp.percentile(gas_price_percentile)
-				.map_or_else(|| DEFAULT_GAS_PRICE.into(), |percentile| *percentile)
+			gas_price_oracle.suggest(&corp)
+				.unwrap_or_else(|| DEFAULT_GAS_PRICE.into())
 		})
 	}
 
@@ -738,7 +750,7 @@ where
 	OD: OnDemandRequester + 'static
 {
 	from: Address,
-	tx: EthTransaction,
+	tx: TypedTransaction,
 	hdr: encoded::Header,
 	env_info: ::vm::EnvInfo,
 	engine: Arc<dyn engine::Engine>,
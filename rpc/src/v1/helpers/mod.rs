@@ -26,6 +26,7 @@ pub mod eip191;
 pub mod engine_signer;
 pub mod external_signer;
 pub mod fake_sign;
+pub mod gas_price_oracle;
 pub mod ipfs;
 pub mod light_fetch;
 pub mod nonce;
@@ -42,6 +43,7 @@ mod work;
 mod signature;
 
 pub use self::dispatch::{Dispatcher, FullDispatcher, LightDispatcher};
+pub use self::gas_price_oracle::GasPriceOracleConfig;
 pub use self::signature::verify_signature;
 pub use self::network_settings::NetworkSettings;
 pub use self::poll_manager::PollManager;
@@ -16,8 +16,9 @@
 
 use ethereum_types::{U256, H256, Address};
 use bytes::Bytes;
+use types::transaction::AccessList;
 
-use v1::types::{Origin, TransactionCondition};
+use v1::types::{EIP191Message, Origin, TransactionCondition};
 
 /// Transaction request coming from RPC
 #[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
@@ -38,6 +39,12 @@ pub struct TransactionRequest {
 	pub nonce: Option<U256>,
 	/// Delay until this condition is met.
 	pub condition: Option<TransactionCondition>,
+	/// Access list, if this is an EIP-2930 transaction.
+	pub access_list: Option<AccessList>,
+	/// Max fee per gas, either fee being set makes this an EIP-1559 transaction.
+	pub max_fee_per_gas: Option<U256>,
+	/// Max priority fee per gas, either fee being set makes this an EIP-1559 transaction.
+	pub max_priority_fee_per_gas: Option<U256>,
 }
 
 /// Transaction request coming from RPC with default values filled in.
@@ -61,6 +68,12 @@ pub struct FilledTransactionRequest {
 	pub nonce: Option<U256>,
 	/// Delay until this condition is met.
 	pub condition: Option<TransactionCondition>,
+	/// Access list, if this is an EIP-2930 transaction.
+	pub access_list: Option<AccessList>,
+	/// Max fee per gas, if this is an EIP-1559 transaction.
+	pub max_fee_per_gas: Option<U256>,
+	/// Max priority fee per gas, if this is an EIP-1559 transaction.
+	pub max_priority_fee_per_gas: Option<U256>,
 }
 
 impl From<FilledTransactionRequest> for TransactionRequest {
@@ -74,12 +87,15 @@ impl From<FilledTransactionRequest> for TransactionRequest {
 			data: Some(r.data),
 			nonce: r.nonce,
 			condition: r.condition,
+			access_list: r.access_list,
+			max_fee_per_gas: r.max_fee_per_gas,
+			max_priority_fee_per_gas: r.max_priority_fee_per_gas,
 		}
 	}
 }
 
 /// Call request
-#[derive(Debug, Default, PartialEq)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct CallRequest {
 	/// From
 	pub from: Option<Address>,
@@ -95,6 +111,12 @@ pub struct CallRequest {
 	pub data: Option<Vec<u8>>,
 	/// Nonce
 	pub nonce: Option<U256>,
+	/// Access list, if this is an EIP-2930 transaction.
+	pub access_list: Option<AccessList>,
+	/// Max fee per gas, either fee being set makes this an EIP-1559 transaction.
+	pub max_fee_per_gas: Option<U256>,
+	/// Max priority fee per gas, either fee being set makes this an EIP-1559 transaction.
+	pub max_priority_fee_per_gas: Option<U256>,
 }
 
 /// Confirmation object
@@ -117,8 +139,8 @@ pub enum ConfirmationPayload {
 	SignTransaction(FilledTransactionRequest),
 	/// Sign a message with an Ethereum specific security prefix.
 	EthSignMessage(Address, Bytes),
-	/// Sign a message
-	SignMessage(Address, H256),
+	/// Sign a message, given its hash and, if known, the message
+	SignMessage(Address, H256, Option<EIP191Message>),
 	/// Decrypt request
 	Decrypt(Address, Bytes),
 }
@@ -129,7 +151,7 @@ impl ConfirmationPayload {
 			ConfirmationPayload::SendTransaction(ref request) => request.from,
 			ConfirmationPayload::SignTransaction(ref request) => request.from,
 			ConfirmationPayload::EthSignMessage(ref address, _) => *address,
-			ConfirmationPayload::SignMessage(ref address, _) => *address,
+			ConfirmationPayload::SignMessage(ref address, _, _) => *address,
 			ConfirmationPayload::Decrypt(ref address, _) => *address,
 		}
 	}
@@ -16,15 +16,124 @@
 
 //! Debug APIs RPC implementation
 
+use std::collections::BTreeMap;
+use std::fmt;
 use std::sync::Arc;
 
-use client_traits::BlockChainClient;
-use types::header::Header;
-use types::transaction::LocalizedTransaction;
+use account_state::state::StateInfo;
+use client_traits::{BlockChainClient, StateClient};
+use ethcore::client::{Call, EngineInfo};
+use ethereum_types::{H160, H256};
+use trace::StructLogOptions;
+use types::{
+	account_diff::{AccountDiff, Diff},
+	call_analytics::CallAnalytics,
+	header::Header,
+	ids::{BlockId, TransactionId},
+	state_diff::StateDiff,
+	transaction::LocalizedTransaction,
+};
 
 use jsonrpc_core::Result;
+use machine::executed::Executed;
+use v1::helpers::{errors, fake_sign};
 use v1::traits::Debug;
-use v1::types::{Block, Bytes, RichBlock, BlockTransactions, Transaction};
+use v1::types::{
+	Block, Bytes, RichBlock, BlockTransactions, Transaction, BlockNumber, CallRequest, CallFrame,
+	DebugTrace, DebugTraceOptions, DebugTraceWithTransactionHash, ExecutionResult, PrestateAccount,
+};
+
+/// Tracer selected by the `tracer` option of `debug_trace*` calls.
+#[derive(Clone, Copy)]
+enum Tracer {
+	StructLogs(StructLogOptions),
+	Call,
+	Prestate,
+}
+
+impl Tracer {
+	fn new(options: Option<DebugTraceOptions>) -> Result<Self> {
+		let options = options.unwrap_or_default();
+		match options.tracer.as_ref().map(String::as_str) {
+			None => Ok(Tracer::StructLogs((&options).into())),
+			Some("callTracer") => Ok(Tracer::Call),
+			Some("prestateTracer") => Ok(Tracer::Prestate),
+			Some(tracer) => Err(errors::invalid_params("tracer", format!("Unsupported tracer: {}", tracer))),
+		}
+	}
+
+	fn analytics(&self) -> CallAnalytics {
+		CallAnalytics {
+			transaction_tracing: match *self { Tracer::Call => true, _ => false },
+			vm_tracing: match *self { Tracer::StructLogs(_) => true, _ => false },
+			state_diffing: match *self { Tracer::Prestate => true, _ => false },
+			access_list_recording: false,
+		}
+	}
+}
+
+/// Rebuilds the state of accounts touched by a transaction as it was before the transaction,
+/// from the state at the beginning of the block and the state diffs of the transactions
+/// preceding it.
+struct Prestate<S> {
+	state: S,
+	diffs: Vec<StateDiff>,
+}
+
+impl<S: StateInfo> Prestate<S> {
+	fn accounts(&self, diff: &StateDiff) -> Result<BTreeMap<H160, PrestateAccount>> {
+		let mut accounts = BTreeMap::new();
+		for (address, account) in &diff.raw {
+			// the account didn't exist before the transaction
+			if let Diff::Born(_) = account.balance {
+				continue;
+			}
+
+			let balance = self.before(address, &account.balance, |a| &a.balance, |s| s.balance(address))?;
+			let nonce = self.before(address, &account.nonce, |a| &a.nonce, |s| s.nonce(address))?;
+			let code = self.before(address, &account.code, |a| &a.code, |s| {
+				s.code(address).map(|code| code.map_or_else(Vec::new, |code| (*code).clone()))
+			})?;
+			let storage = account.storage.iter()
+				.filter_map(|(key, diff)| match *diff {
+					Diff::Changed(pre, _) | Diff::Died(pre) => Some((*key, pre)),
+					Diff::Born(_) | Diff::Same => None,
+				})
+				.collect();
+
+			accounts.insert(*address, PrestateAccount {
+				balance,
+				nonce: nonce.low_u64(),
+				code: if code.is_empty() { None } else { Some(code.into()) },
+				storage,
+			});
+		}
+		Ok(accounts)
+	}
+
+	fn before<T, E, F, G>(&self, address: &H160, diff: &Diff<T>, field: F, base: G) -> Result<T> where
+		T: Clone + Default,
+		F: Fn(&AccountDiff) -> &Diff<T>,
+		E: fmt::Debug,
+		G: FnOnce(&S) -> ::std::result::Result<T, E>,
+	{
+		match *diff {
+			Diff::Changed(ref pre, _) | Diff::Died(ref pre) => return Ok(pre.clone()),
+			Diff::Born(_) => return Ok(T::default()),
+			Diff::Same => {},
+		}
+
+		for prior in self.diffs.iter().rev() {
+			match prior.raw.get(address).map(&field) {
+				Some(&Diff::Changed(_, ref post)) | Some(&Diff::Born(ref post)) => return Ok(post.clone()),
+				Some(&Diff::Died(_)) => return Ok(T::default()),
+				Some(&Diff::Same) | None => {},
+			}
+		}
+
+		base(&self.state).map_err(errors::database)
+	}
+}
 
 /// Debug rpc implementation.
 pub struct DebugClient<C> {
@@ -40,7 +149,47 @@ impl<C> DebugClient<C> {
 	}
 }
 
-impl<C: BlockChainClient + 'static> Debug for DebugClient<C> {
+impl<C, S> DebugClient<C> where
+	S: StateInfo + 'static,
+	C: BlockChainClient + StateClient<State=S> + Call<State=S> + EngineInfo + 'static
+{
+	fn trace_block(&self, id: BlockId, tracer: Tracer, limit: usize) -> Result<Vec<DebugTraceWithTransactionHash>> {
+		let mut prestate = match tracer {
+			Tracer::Prestate => {
+				let header = self.client.block_header(id).ok_or_else(errors::unknown_block)?;
+				let parent = match header.number() {
+					0 => id,
+					_ => BlockId::Hash(header.parent_hash()),
+				};
+				let state = self.client.state_at(parent).ok_or_else(errors::state_pruned)?;
+				Some(Prestate { state, diffs: Vec::new() })
+			},
+			_ => None,
+		};
+
+		self.client.replay_block_transactions(id, tracer.analytics())
+			.map_err(errors::call)?
+			.take(limit)
+			.map(|(tx_hash, executed)| -> Result<DebugTraceWithTransactionHash> {
+				let result = match prestate {
+					Some(ref mut prestate) => {
+						let diff = executed.state_diff.unwrap_or_else(empty_state_diff);
+						let accounts = prestate.accounts(&diff)?;
+						prestate.diffs.push(diff);
+						DebugTrace::Prestate(accounts)
+					},
+					None => debug_trace(tracer, executed),
+				};
+				Ok(DebugTraceWithTransactionHash { tx_hash, result })
+			})
+			.collect()
+	}
+}
+
+impl<C, S> Debug for DebugClient<C> where
+	S: StateInfo + 'static,
+	C: BlockChainClient + StateClient<State=S> + Call<State=S> + EngineInfo + 'static
+{
 	fn bad_blocks(&self) -> Result<Vec<RichBlock>> {
 		fn cast<O, T: Copy + Into<O>>(t: &T) -> O {
 			(*t).into()
@@ -62,6 +211,7 @@ impl<C: BlockChainClient + 'static> Debug for DebugClient<C> {
 					number: Some(number.into()),
 					gas_used: cast(block.header.gas_used()),
 					gas_limit: cast(block.header.gas_limit()),
+					base_fee_per_gas: block.header.base_fee(),
 					logs_bloom: Some(cast(block.header.log_bloom())),
 					timestamp: block.header.timestamp().into(),
 					difficulty: cast(block.header.difficulty()),
@@ -90,6 +240,80 @@ impl<C: BlockChainClient + 'static> Debug for DebugClient<C> {
 			}
 		}).collect())
 	}
+
+	fn trace_transaction(&self, transaction_hash: H256, options: Option<DebugTraceOptions>) -> Result<DebugTrace> {
+		let tracer = Tracer::new(options)?;
+		let transaction = self.client.transaction(TransactionId::Hash(transaction_hash))
+			.ok_or_else(|| errors::invalid_params("transaction_hash", "Transaction not found"))?;
+
+		self.trace_block(BlockId::Hash(transaction.block_hash), tracer, transaction.transaction_index + 1)?
+			.pop()
+			.map(|trace| trace.result)
+			.ok_or_else(errors::state_pruned)
+	}
+
+	fn trace_call(&self, request: CallRequest, block: Option<BlockNumber>, options: Option<DebugTraceOptions>) -> Result<DebugTrace> {
+		let tracer = Tracer::new(options)?;
+		let block = block.unwrap_or_default();
+
+		let request = CallRequest::into(request);
+		let signed = fake_sign::sign_call(request)?;
+
+		let id = match block {
+			BlockNumber::Hash { hash, .. } => BlockId::Hash(hash),
+			BlockNumber::Num(num) => BlockId::Number(num),
+			BlockNumber::Earliest => BlockId::Earliest,
+			BlockNumber::Latest => BlockId::Latest,
+
+			BlockNumber::Pending => return Err(errors::invalid_params("`BlockNumber::Pending` is not supported", ())),
+		};
+
+		let mut state = self.client.state_at(id).ok_or_else(errors::state_pruned)?;
+		let header = self.client.block_header(id).ok_or_else(errors::state_pruned)?;
+
+		let executed = self.client.call(&signed, tracer.analytics(), &mut state, &header.decode(self.client.engine().params().eip1559_transition).map_err(errors::decode)?)
+			.map_err(errors::call)?;
+
+		match tracer {
+			Tracer::Prestate => {
+				let state = self.client.state_at(id).ok_or_else(errors::state_pruned)?;
+				let prestate = Prestate { state, diffs: Vec::new() };
+				prestate.accounts(&executed.state_diff.unwrap_or_else(empty_state_diff)).map(DebugTrace::Prestate)
+			},
+			_ => Ok(debug_trace(tracer, executed)),
+		}
+	}
+
+	fn trace_block_by_number(&self, block_number: BlockNumber, options: Option<DebugTraceOptions>) -> Result<Vec<DebugTraceWithTransactionHash>> {
+		let tracer = Tracer::new(options)?;
+		let id = match block_number {
+			BlockNumber::Hash { hash, .. } => BlockId::Hash(hash),
+			BlockNumber::Num(num) => BlockId::Number(num),
+			BlockNumber::Earliest => BlockId::Earliest,
+			BlockNumber::Latest => BlockId::Latest,
+
+			BlockNumber::Pending => return Err(errors::invalid_params("`BlockNumber::Pending` is not supported", ())),
+		};
+
+		self.trace_block(id, tracer, usize::max_value())
+	}
+
+	fn trace_block_by_hash(&self, block_hash: H256, options: Option<DebugTraceOptions>) -> Result<Vec<DebugTraceWithTransactionHash>> {
+		let tracer = Tracer::new(options)?;
+		self.trace_block(BlockId::Hash(block_hash), tracer, usize::max_value())
+	}
+}
+
+/// Builds the struct logs or call tree of an execution.
+fn debug_trace(tracer: Tracer, executed: Executed) -> DebugTrace {
+	match tracer {
+		Tracer::StructLogs(options) => DebugTrace::StructLogs(ExecutionResult::new(&executed, options)),
+		_ => DebugTrace::Call(CallFrame::from_flat_traces(executed.trace)),
+	}
+}
+
+fn empty_state_diff() -> StateDiff {
+	StateDiff { raw: BTreeMap::new() }
 }
 
 fn serialize<T: ::serde::Serialize>(t: &T) -> String {
@@ -20,7 +20,6 @@ use std::thread;
 use std::time::{Instant, Duration, SystemTime, UNIX_EPOCH};
 use std::sync::Arc;
 
-use rlp::Rlp;
 use ethereum_types::{Address, H64, H160, H256, U64, U256, BigEndianHash};
 use parking_lot::Mutex;
 
@@ -40,26 +39,31 @@ use types::{
 	header::Header,
 	ids::{BlockId, TransactionId, UncleId},
 	filter::Filter as EthcoreFilter,
-	transaction::{SignedTransaction, LocalizedTransaction},
+	call_analytics::CallAnalytics,
+	transaction::{self, SignedTransaction, LocalizedTransaction, UnverifiedTransaction},
 	snapshot::RestorationStatus,
 };
 
 use jsonrpc_core::{BoxFuture, Result};
 use jsonrpc_core::futures::future;
 
-use v1::helpers::{self, errors, limit_logs, fake_sign};
+use v1::helpers::{self, errors, limit_logs, fake_sign, GasPriceOracleConfig};
+use v1::helpers::gas_price_oracle::{self, BlockFees};
 use v1::helpers::deprecated::{self, DeprecationNotice};
 use v1::helpers::dispatch::{FullDispatcher, default_gas_price};
 use v1::traits::Eth;
 use v1::types::{
 	RichBlock, Block, BlockTransactions, BlockNumber, Bytes, SyncStatus, SyncInfo,
-	Transaction, CallRequest, Index, Filter, Log, Receipt, Work, EthAccount, StorageProof,
-	block_number_to_id
+	Transaction, CallRequest, Index, Filter, Log, Receipt, Work, EthAccount, StorageProof, FeeHistory,
+	AccessListWithGasUsed, block_number_to_id
 };
 use v1::metadata::Metadata;
 
 const EXTRA_INFO_PROOF: &str = "Object exists in blockchain (fetched earlier), extra_info is always available if object exists; qed";
 
+/// Maximal number of calls made to find the access list of a call.
+const MAX_ACCESS_LIST_ITERATIONS: usize = 10;
+
 /// Eth RPC options
 #[derive(Copy, Clone)]
 pub struct EthClientOptions {
@@ -69,8 +73,8 @@ pub struct EthClientOptions {
 	pub allow_pending_receipt_query: bool,
 	/// Send additional block number when asking for work
 	pub send_block_number_in_get_work: bool,
-	/// Gas Price Percentile used as default gas price.
-	pub gas_price_percentile: usize,
+	/// Gas price oracle used to suggest the default gas price.
+	pub gas_price_oracle: GasPriceOracleConfig,
 	/// Return 'null' instead of an error if ancient block sync is still in
 	/// progress and the block information requested could not be found.
 	pub allow_missing_blocks: bool,
@@ -96,7 +100,7 @@ impl Default for EthClientOptions {
 			pending_nonce_from_queue: false,
 			allow_pending_receipt_query: true,
 			send_block_number_in_get_work: true,
-			gas_price_percentile: 50,
+			gas_price_oracle: Default::default(),
 			allow_missing_blocks: false,
 			allow_experimental_rpcs: false,
 			no_ancient_blocks: false,
@@ -266,6 +270,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T> EthClient<C, SN, S, M, EM> where
 		match (block, difficulty) {
 			(Some(block), Some(total_difficulty)) => {
 				let view = block.header_view();
+				let eip1559_transition = self.client.engine().params().eip1559_transition;
 				Ok(Some(RichBlock {
 					inner: Block {
 						hash: match is_pending {
@@ -286,6 +291,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T> EthClient<C, SN, S, M, EM> where
 						},
 						gas_used: view.gas_used(),
 						gas_limit: view.gas_limit(),
+						base_fee_per_gas: view.base_fee(eip1559_transition),
 						logs_bloom: match is_pending {
 							true => None,
 							false => Some(view.log_bloom()),
@@ -293,7 +299,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T> EthClient<C, SN, S, M, EM> where
 						timestamp: view.timestamp().into(),
 						difficulty: view.difficulty(),
 						total_difficulty: Some(total_difficulty),
-						seal_fields: view.seal().into_iter().map(Into::into).collect(),
+						seal_fields: view.seal(eip1559_transition).into_iter().map(Into::into).collect(),
 						uncles: block.uncle_hashes(),
 						transactions: match include_txs {
 							true => BlockTransactions::Full(block.view().localized_transactions().into_iter().map(Transaction::from_localized).collect()),
@@ -391,7 +397,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T> EthClient<C, SN, S, M, EM> where
 				let uncle_id = UncleId { block: block_id, position };
 
 				let uncle = match client.uncle(uncle_id) {
-					Some(hdr) => match hdr.decode() {
+					Some(hdr) => match hdr.decode(client.engine().params().eip1559_transition) {
 						Ok(h) => h,
 						Err(e) => return Err(errors::decode(e))
 					},
@@ -426,6 +432,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T> EthClient<C, SN, S, M, EM> where
 				number: Some(uncle.number().into()),
 				gas_used: *uncle.gas_used(),
 				gas_limit: *uncle.gas_limit(),
+				base_fee_per_gas: uncle.base_fee(),
 				logs_bloom: Some(*uncle.log_bloom()),
 				timestamp: uncle.timestamp().into(),
 				difficulty: *uncle.difficulty(),
@@ -484,6 +491,41 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T> EthClient<C, SN, S, M, EM> where
 			}
 		}
 	}
+
+	/// Get the state and header to execute calls on top of at the given block.
+	fn state_and_header(&self, num: BlockNumber) -> Result<(T, Header)> {
+		let id = match num {
+			BlockNumber::Hash { hash, .. } => BlockId::Hash(hash),
+			BlockNumber::Num(num) => BlockId::Number(num),
+			BlockNumber::Earliest => BlockId::Earliest,
+			BlockNumber::Latest => BlockId::Latest,
+			BlockNumber::Pending => return Ok(self.pending_state_and_header_with_fallback()),
+		};
+
+		let state = self.client.state_at(id).ok_or_else(errors::state_pruned)?;
+		let header = self.client.block_header(id)
+			.ok_or_else(errors::state_pruned)
+			.and_then(|h| h.decode(self.client.engine().params().eip1559_transition).map_err(errors::decode))?;
+
+		Ok((state, header))
+	}
+}
+
+/// Adds the entries of `other` missing from `list` to it.
+fn merge_access_lists(mut list: transaction::AccessList, other: transaction::AccessList) -> transaction::AccessList {
+	for item in other {
+		match list.iter_mut().find(|existing| existing.address == item.address) {
+			Some(existing) => {
+				for key in item.storage_keys {
+					if !existing.storage_keys.contains(&key) {
+						existing.storage_keys.push(key);
+					}
+				}
+			},
+			None => list.push(item),
+		}
+	}
+	list
 }
 
 pub fn pending_logs<M>(miner: &M, best_block: EthBlockNumber, filter: &EthcoreFilter) -> Vec<Log> where M: MinerService {
@@ -602,7 +644,38 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 	}
 
 	fn gas_price(&self) -> BoxFuture<U256> {
-		Box::new(future::ok(default_gas_price(&*self.client, &*self.miner, self.options.gas_price_percentile)))
+		Box::new(future::ok(default_gas_price(&*self.client, &*self.miner, &self.options.gas_price_oracle)))
+	}
+
+	fn fee_history(&self, block_count: U256, newest_block: BlockNumber, reward_percentiles: Option<Vec<f64>>) -> BoxFuture<FeeHistory> {
+		let percentiles = reward_percentiles.unwrap_or_default();
+		let block_count = try_bf!(gas_price_oracle::fee_history_block_count(block_count, &percentiles));
+
+		let newest_id = match newest_block {
+			BlockNumber::Pending => BlockId::Latest,
+			num => block_number_to_id(num),
+		};
+		let newest_number = try_bf!(self.client.block_number(newest_id).ok_or_else(errors::unknown_block));
+		let oldest_number = (newest_number + 1).saturating_sub(block_count);
+		let eip1559_transition = self.client.engine().params().eip1559_transition;
+
+		let mut fees = Vec::with_capacity((newest_number + 1 - oldest_number) as usize);
+		let mut newest = None;
+		for number in oldest_number..=newest_number {
+			let block = try_bf!(self.client.block(BlockId::Number(number)).ok_or_else(errors::unknown_block));
+			let receipts = if percentiles.is_empty() {
+				Vec::new()
+			} else {
+				try_bf!(self.client.block_receipts(&block.hash()).ok_or_else(errors::unknown_block)).receipts
+			};
+			fees.push(BlockFees::new(&block, block.base_fee(eip1559_transition), &receipts, &percentiles));
+			newest = Some(block);
+		}
+
+		let newest_header = newest.expect("at least one block is requested; qed").decode_header(eip1559_transition);
+		let next_base_fee = self.client.engine().machine().calc_base_fee(&newest_header).unwrap_or_default();
+
+		Box::new(future::ok(gas_price_oracle::fee_history(oldest_number, fees, next_base_fee, !percentiles.is_empty())))
 	}
 
 	fn accounts(&self) -> Result<Vec<H160>> {
@@ -941,7 +1014,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 	}
 
 	fn send_raw_transaction(&self, raw: Bytes) -> Result<H256> {
-		Rlp::new(&raw.into_vec()).as_val()
+		UnverifiedTransaction::decode_raw(&raw.into_vec())
 			.map_err(errors::rlp)
 			.and_then(|tx| SignedTransaction::new(tx).map_err(errors::transaction))
 			.and_then(|signed_transaction| {
@@ -981,7 +1054,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 				let state = try_bf!(self.client.state_at(id).ok_or_else(errors::state_pruned));
 				let header = try_bf!(
 					self.client.block_header(id).ok_or_else(errors::state_pruned)
-						.and_then(|h| h.decode().map_err(errors::decode))
+						.and_then(|h| h.decode(self.client.engine().params().eip1559_transition).map_err(errors::decode))
 				);
 
 				(state, header)
@@ -1021,7 +1094,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 								.ok_or_else(errors::state_pruned));
 			let header = try_bf!(self.client.block_header(id)
 								 .ok_or_else(errors::state_pruned)
-								 .and_then(|h| h.decode().map_err(errors::decode)));
+								 .and_then(|h| h.decode(self.client.engine().params().eip1559_transition).map_err(errors::decode)));
 			(state, header)
 		};
 
@@ -1030,6 +1103,46 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 		))
 	}
 
+	fn create_access_list(&self, request: CallRequest, num: Option<BlockNumber>) -> BoxFuture<AccessListWithGasUsed> {
+		let mut request = CallRequest::into(request);
+		let num = num.unwrap_or_default();
+		try_bf!(check_known(&*self.client, num.clone()));
+
+		let analytics = CallAnalytics {
+			access_list_recording: true,
+			..Default::default()
+		};
+
+		// Every run may touch new entries (e.g. a slot read from another slot), so the call
+		// is repeated with the recorded accesses applied until it doesn't access anything new.
+		// The accesses may depend on the gas left though, so they are not guaranteed to settle.
+		let mut access_list = request.access_list.take().unwrap_or_default();
+		let mut gas_used = U256::zero();
+		for _ in 0..MAX_ACCESS_LIST_ITERATIONS {
+			request.access_list = Some(access_list.clone());
+			let signed = try_bf!(fake_sign::sign_call(request.clone()));
+			let (mut state, header) = try_bf!(self.state_and_header(num.clone()));
+			let executed = try_bf!(self.client.call(&signed, analytics, &mut state, &header).map_err(errors::call));
+
+			let recorded = merge_access_lists(access_list.clone(), executed.access_list.unwrap_or_default());
+			if recorded == access_list {
+				return Box::new(future::ok(AccessListWithGasUsed {
+					access_list: access_list.into_iter().map(Into::into).collect(),
+					gas_used: executed.gas_used,
+					error: executed.exception.map(|e| e.to_string()),
+				}));
+			}
+			access_list = recorded;
+			gas_used = executed.gas_used;
+		}
+
+		Box::new(future::ok(AccessListWithGasUsed {
+			access_list: access_list.into_iter().map(Into::into).collect(),
+			gas_used,
+			error: Some(format!("Access list didn't settle after {} calls", MAX_ACCESS_LIST_ITERATIONS)),
+		}))
+	}
+
 	fn compile_lll(&self, _: String) -> Result<Bytes> {
 		Err(errors::deprecated("Compilation of LLL via RPC is deprecated".to_string()))
 	}
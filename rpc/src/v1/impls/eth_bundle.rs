@@ -0,0 +1,89 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Eth bundles rpc implementation.
+
+use std::sync::Arc;
+
+use client_traits::ChainInfo;
+use ethcore::miner::{self, MinerService, Bundle};
+use ethereum_types::U64;
+use types::transaction::UnverifiedTransaction;
+
+use jsonrpc_core::Result;
+use v1::helpers::errors;
+use v1::traits::EthBundle;
+use v1::types::{Bytes, BlockNumber, SendBundleRequest, SendBundleResponse, CallBundleRequest, CallBundleResponse};
+
+/// Eth bundles rpc implementation.
+pub struct EthBundleClient<C, M> {
+	client: Arc<C>,
+	miner: Arc<M>,
+}
+
+impl<C, M> EthBundleClient<C, M> {
+	/// Creates new `EthBundleClient`.
+	pub fn new(client: &Arc<C>, miner: &Arc<M>) -> Self {
+		EthBundleClient {
+			client: client.clone(),
+			miner: miner.clone(),
+		}
+	}
+}
+
+fn decode_transactions(txs: Vec<Bytes>) -> Result<Vec<UnverifiedTransaction>> {
+	// transactions are verified by the miner.
+	txs.into_iter()
+		.map(|raw| UnverifiedTransaction::decode_raw(&raw.into_vec()).map_err(errors::rlp))
+		.collect()
+}
+
+impl<C, M> EthBundle for EthBundleClient<C, M> where
+	C: miner::BlockChainClient + 'static,
+	M: MinerService + 'static,
+{
+	fn send_bundle(&self, request: SendBundleRequest) -> Result<SendBundleResponse> {
+		let bundle = Bundle {
+			transactions: decode_transactions(request.txs)?,
+			block_number: request.block_number.as_u64(),
+			min_timestamp: request.min_timestamp,
+			max_timestamp: request.max_timestamp,
+			reverting_hashes: request.reverting_tx_hashes.into_iter().collect(),
+		};
+
+		self.miner.import_bundle(&*self.client, bundle)
+			.map(|bundle_hash| SendBundleResponse { bundle_hash })
+			.map_err(errors::bundle)
+	}
+
+	fn call_bundle(&self, request: CallBundleRequest) -> Result<CallBundleResponse> {
+		let best_block = self.client.chain_info().best_block_number;
+		// bundles are only simulated at the top of the next block.
+		match request.state_block_number.unwrap_or_default() {
+			BlockNumber::Latest => {},
+			BlockNumber::Num(num) if num == best_block => {},
+			_ => return Err(errors::unsupported("Bundles can only be simulated on top of the latest block.", None)),
+		}
+		if request.block_number.map_or(false, |num| num != U64::from(best_block + 1)) {
+			return Err(errors::unsupported("Bundles can only be simulated in the next block.", None));
+		}
+
+		let transactions = decode_transactions(request.txs)?;
+		self.miner.call_bundle(&*self.client, transactions, request.timestamp)
+			.map(|simulation| CallBundleResponse::new(simulation, best_block))
+			.map_err(errors::bundle)
+	}
+}
@@ -24,14 +24,15 @@ use jsonrpc_core::futures::{self, Future, IntoFuture, Stream, sync::mpsc};
 use jsonrpc_pubsub::typed::{Sink, Subscriber};
 use jsonrpc_pubsub::SubscriptionId;
 
-use v1::helpers::{errors, limit_logs, Subscribers, };
+use v1::helpers::{errors, limit_logs, GasPriceOracleConfig, Subscribers, };
 use v1::helpers::light_fetch::LightFetch;
 use v1::metadata::Metadata;
 use v1::traits::EthPubSub;
-use v1::types::{pubsub, RichHeader, Log};
+use v1::types::{pubsub, Header, RichHeader, Log};
 
 use sync::{SyncState, Notification};
 use client_traits::{BlockChainClient, ChainNotify};
+use ethcore::client::EngineInfo;
 use ethereum_types::H256;
 use light::cache::Cache;
 use light::client::{LightChainClient, LightChainNotify};
@@ -42,6 +43,7 @@ use parking_lot::{RwLock, Mutex};
 use sync::{LightSyncProvider, LightNetworkDispatcher, ManageNetwork};
 
 use types::{
+	BlockNumber,
 	chain_notify::{NewBlocks, ChainRouteType},
 	ids::BlockId,
 	encoded,
@@ -142,7 +144,7 @@ where
 		sync: Arc<S>,
 		cache: Arc<Mutex<Cache>>,
 		executor: Executor,
-		gas_price_percentile: usize,
+		gas_price_oracle: GasPriceOracleConfig,
 		pool_receiver: mpsc::UnboundedReceiver<Arc<Vec<H256>>>
 	) -> Self {
 		let fetch = LightFetch {
@@ -150,7 +152,7 @@ where
 			on_demand,
 			sync,
 			cache,
-			gas_price_percentile,
+			gas_price_oracle,
 		};
 		EthPubSubClient::new(Arc::new(fetch), executor, pool_receiver)
 	}
@@ -175,11 +177,11 @@ impl<C> ChainNotificationHandler<C> {
 		);
 	}
 
-	fn notify_heads(&self, headers: &[(encoded::Header, BTreeMap<String, String>)]) {
+	fn notify_heads(&self, headers: &[(encoded::Header, BTreeMap<String, String>)], eip1559_transition: BlockNumber) {
 		for subscriber in self.heads_subscribers.read().values() {
 			for &(ref header, ref extra_info) in headers {
 				Self::notify(&self.executor, subscriber, pubsub::Result::Header(Box::new(RichHeader {
-					inner: header.into(),
+					inner: Header::new(header, eip1559_transition),
 					extra_info: extra_info.clone(),
 				})));
 			}
@@ -242,6 +244,9 @@ pub trait LightClient: Send + Sync {
 
 	/// Fetch logs.
 	fn logs(&self, filter: EthFilter) -> BoxFuture<Vec<Log>>;
+
+	/// Number of the first block with a base fee in its header (EIP-1559).
+	fn eip1559_transition(&self) -> BlockNumber;
 }
 
 impl<S, OD> LightClient for LightFetch<S, OD>
@@ -256,6 +261,10 @@ where
 	fn logs(&self, filter: EthFilter) -> BoxFuture<Vec<Log>> {
 		Box::new(LightFetch::logs(self, filter)) as BoxFuture<_>
 	}
+
+	fn eip1559_transition(&self) -> BlockNumber {
+		self.client.engine().params().eip1559_transition
+	}
 }
 
 impl<C: LightClient> LightChainNotify for ChainNotificationHandler<C> {
@@ -266,12 +275,12 @@ impl<C: LightClient> LightChainNotify for ChainNotificationHandler<C> {
 			.map(|header| (header, Default::default()))
 			.collect::<Vec<_>>();
 
-		self.notify_heads(&headers);
+		self.notify_heads(&headers, self.client.eip1559_transition());
 		self.notify_logs(&enacted.iter().map(|h| (*h, ())).collect::<Vec<_>>(), |filter, _| self.client.logs(filter))
 	}
 }
 
-impl<C: BlockChainClient> ChainNotify for ChainNotificationHandler<C> {
+impl<C: BlockChainClient + EngineInfo> ChainNotify for ChainNotificationHandler<C> {
 	fn new_blocks(&self, new_blocks: NewBlocks) {
 		if self.heads_subscribers.read().is_empty() && self.logs_subscribers.read().is_empty() { return }
 		const EXTRA_INFO_PROOF: &str = "Object exists in in blockchain (fetched earlier), extra_info is always available if object exists; qed";
@@ -290,7 +299,7 @@ impl<C: BlockChainClient> ChainNotify for ChainNotificationHandler<C> {
 			.collect::<Vec<_>>();
 
 		// Headers
-		self.notify_heads(&headers);
+		self.notify_heads(&headers, self.client.engine().params().eip1559_transition);
 
 		// We notify logs enacting and retracting as the order in route.
 		self.notify_logs(new_blocks.route.route(), |filter, ex| {
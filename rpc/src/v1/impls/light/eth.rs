@@ -31,20 +31,21 @@ use light::on_demand::{request, OnDemandRequester};
 use ethereum_types::{Address, H64, H160, H256, U64, U256};
 use hash::{KECCAK_NULL_RLP, KECCAK_EMPTY_LIST_RLP};
 use parking_lot::{RwLock, Mutex};
-use rlp::Rlp;
-use types::transaction::SignedTransaction;
+use types::transaction::{SignedTransaction, UnverifiedTransaction};
 use types::encoded;
 use types::filter::Filter as EthcoreFilter;
 use types::ids::BlockId;
 
 use v1::impls::eth_filter::Filterable;
-use v1::helpers::{errors, limit_logs, SyncPollFilter, PollManager};
+use v1::helpers::{errors, limit_logs, GasPriceOracleConfig, SyncPollFilter, PollManager};
+use v1::helpers::gas_price_oracle::{self, BlockFees};
 use v1::helpers::deprecated::{self, DeprecationNotice};
 use v1::helpers::light_fetch::{self, LightFetch};
 use v1::traits::Eth;
 use v1::types::{
 	RichBlock, Block, BlockTransactions, BlockNumber, LightBlockNumber, Bytes, SyncStatus as RpcSyncStatus,
-	SyncInfo as RpcSyncInfo, Transaction, CallRequest, Index, Filter, Log, Receipt, Work, EthAccount
+	SyncInfo as RpcSyncInfo, Transaction, CallRequest, Index, Filter, Log, Receipt, Work, EthAccount, FeeHistory,
+	AccessListWithGasUsed,
 };
 use v1::metadata::Metadata;
 
@@ -62,7 +63,7 @@ pub struct EthClient<C, S: LightSyncProvider + LightNetworkDispatcher + 'static,
 	cache: Arc<Mutex<LightDataCache>>,
 	polls: Mutex<PollManager<SyncPollFilter>>,
 	poll_lifetime: u32,
-	gas_price_percentile: usize,
+	gas_price_oracle: GasPriceOracleConfig,
 	deprecation_notice: DeprecationNotice,
 }
 
@@ -82,7 +83,7 @@ where
 			cache: self.cache.clone(),
 			polls: Mutex::new(PollManager::new(self.poll_lifetime)),
 			poll_lifetime: self.poll_lifetime,
-			gas_price_percentile: self.gas_price_percentile,
+			gas_price_oracle: self.gas_price_oracle,
 			deprecation_notice: Default::default(),
 		}
 	}
@@ -103,7 +104,7 @@ where
 		transaction_queue: Arc<RwLock<TransactionQueue>>,
 		accounts: Arc<dyn Fn() -> Vec<Address> + Send + Sync>,
 		cache: Arc<Mutex<LightDataCache>>,
-		gas_price_percentile: usize,
+		gas_price_oracle: GasPriceOracleConfig,
 		poll_lifetime: u32
 	) -> Self {
 		EthClient {
@@ -115,7 +116,7 @@ where
 			cache,
 			polls: Mutex::new(PollManager::new(poll_lifetime)),
 			poll_lifetime,
-			gas_price_percentile,
+			gas_price_oracle,
 			deprecation_notice: Default::default(),
 		}
 	}
@@ -128,7 +129,7 @@ where
 			on_demand: self.on_demand.clone(),
 			sync: self.sync.clone(),
 			cache: self.cache.clone(),
-			gas_price_percentile: self.gas_price_percentile,
+			gas_price_oracle: self.gas_price_oracle,
 		}
 	}
 
@@ -139,7 +140,7 @@ where
 
 		// helper for filling out a rich block once we've got a block and a score.
 		let fill_rich = move |block: encoded::Block, score: Option<U256>| {
-			let header = block.decode_header();
+			let header = block.decode_header(engine.params().eip1559_transition);
 			let extra_info = engine.extra_info(&header);
 			RichBlock {
 				inner: Block {
@@ -155,6 +156,7 @@ where
 					number: Some(header.number().into()),
 					gas_used: *header.gas_used(),
 					gas_limit: *header.gas_limit(),
+					base_fee_per_gas: header.base_fee(),
 					logs_bloom: Some(*header.log_bloom()),
 					timestamp: header.timestamp().into(),
 					difficulty: *header.difficulty(),
@@ -274,6 +276,47 @@ where
 		Box::new(self.fetcher().gas_price())
 	}
 
+	fn fee_history(&self, block_count: U256, newest_block: BlockNumber, reward_percentiles: Option<Vec<f64>>) -> BoxFuture<FeeHistory> {
+		let percentiles = reward_percentiles.unwrap_or_default();
+		let block_count = try_bf!(gas_price_oracle::fee_history_block_count(block_count, &percentiles));
+		let engine = self.client.engine().clone();
+		let fetcher = self.fetcher();
+
+		Box::new(fetcher.header(newest_block.to_block_id()).and_then(move |newest| {
+			let newest_number = newest.number();
+			let oldest_number = (newest_number + 1).saturating_sub(block_count);
+			let with_rewards = !percentiles.is_empty();
+
+			let eip1559_transition = engine.params().eip1559_transition;
+
+			// block bodies and receipts are only needed to compute the rewards
+			let blocks = (oldest_number..=newest_number)
+				.map(|number| {
+					let id = BlockId::Number(number);
+					if with_rewards {
+						let percentiles = percentiles.clone();
+						Either::A(fetcher.block(id).join(fetcher.receipts(id)).map(move |(block, receipts)| {
+							BlockFees::new(&block, block.base_fee(eip1559_transition), &receipts, &percentiles)
+						}))
+					} else {
+						Either::B(fetcher.header(id).map(move |header| {
+							BlockFees::from_header(&header, header.base_fee(eip1559_transition))
+						}))
+					}
+				})
+				.collect::<Vec<_>>();
+
+			future::join_all(blocks).and_then(move |fees| {
+				newest.decode(eip1559_transition)
+					.map_err(errors::decode)
+					.map(|newest_header| {
+						let next_base_fee = engine.machine().calc_base_fee(&newest_header).unwrap_or_default();
+						gas_price_oracle::fee_history(oldest_number, fees, next_base_fee, with_rewards)
+					})
+			})
+		}))
+	}
+
 	fn accounts(&self) -> Result<Vec<H160>> {
 		self.deprecation_notice.print("eth_accounts", deprecated::msgs::ACCOUNTS);
 
@@ -378,9 +421,9 @@ where
 	}
 
 	fn send_raw_transaction(&self, raw: Bytes) -> Result<H256> {
-		let best_header = self.client.best_block_header().decode().map_err(errors::decode)?;
+		let best_header = self.client.best_block_header().decode(self.client.engine().params().eip1559_transition).map_err(errors::decode)?;
 
-		Rlp::new(&raw.into_vec()).as_val()
+		UnverifiedTransaction::decode_raw(&raw.into_vec())
 			.map_err(errors::rlp)
 			.and_then(|tx| {
 				self.client.engine().verify_transaction_basic(&tx, &best_header)
@@ -419,6 +462,10 @@ where
 		}))
 	}
 
+	fn create_access_list(&self, _req: CallRequest, _num: Option<BlockNumber>) -> BoxFuture<AccessListWithGasUsed> {
+		Box::new(future::err(errors::light_unimplemented(None)))
+	}
+
 	fn transaction_by_hash(&self, hash: H256) -> BoxFuture<Option<Transaction>> {
 		let in_txqueue = self.transaction_queue.read().get(&hash).is_some();
 
@@ -573,7 +620,7 @@ where
 }
 
 fn extract_uncle_at_index<T: LightChainClient>(block: encoded::Block, index: Index, client: Arc<T>) -> Option<RichBlock> {
-		let uncle = match block.uncles().into_iter().nth(index.value()) {
+		let uncle = match block.uncles(client.engine().params().eip1559_transition).into_iter().nth(index.value()) {
 			Some(u) => u,
 			None => return None,
 		};
@@ -592,6 +639,7 @@ fn extract_uncle_at_index<T: LightChainClient>(block: encoded::Block, index: Ind
 				number: Some(uncle.number().into()),
 				gas_used: *uncle.gas_used(),
 				gas_limit: *uncle.gas_limit(),
+				base_fee_per_gas: uncle.base_fee(),
 				logs_bloom: Some(*uncle.log_bloom()),
 				timestamp: uncle.timestamp().into(),
 				difficulty: *uncle.difficulty(),
@@ -33,7 +33,7 @@ use ethcore_logger::RotatingLogger;
 use jsonrpc_core::{Result, BoxFuture};
 use jsonrpc_core::futures::{future, Future};
 use light::on_demand::OnDemandRequester;
-use v1::helpers::{self, errors, ipfs, NetworkSettings, verify_signature};
+use v1::helpers::{self, errors, ipfs, GasPriceOracleConfig, NetworkSettings, verify_signature};
 use v1::helpers::external_signer::{SignerService, SigningQueue};
 use v1::helpers::dispatch::LightDispatcher;
 use v1::helpers::light_fetch::{LightFetch, light_all_transactions};
@@ -63,7 +63,7 @@ where
 	settings: Arc<NetworkSettings>,
 	signer: Option<Arc<SignerService>>,
 	ws_address: Option<Host>,
-	gas_price_percentile: usize,
+	gas_price_oracle: GasPriceOracleConfig,
 }
 
 impl<S, OD> ParityClient<S, OD>
@@ -78,7 +78,7 @@ where
 		settings: Arc<NetworkSettings>,
 		signer: Option<Arc<SignerService>>,
 		ws_address: Option<Host>,
-		gas_price_percentile: usize,
+		gas_price_oracle: GasPriceOracleConfig,
 	) -> Self {
 		ParityClient {
 			light_dispatch,
@@ -86,7 +86,7 @@ where
 			settings,
 			signer,
 			ws_address,
-			gas_price_percentile,
+			gas_price_oracle,
 		}
 	}
 
@@ -98,7 +98,7 @@ where
 			on_demand: self.light_dispatch.on_demand.clone(),
 			sync: self.light_dispatch.sync.clone(),
 			cache: self.light_dispatch.cache.clone(),
-			gas_price_percentile: self.gas_price_percentile,
+			gas_price_oracle: self.gas_price_oracle,
 		}
 	}
 }
@@ -301,6 +301,10 @@ where
 		self.light_dispatch.sync.enode().ok_or_else(errors::network_disabled)
 	}
 
+	fn enr(&self) -> Result<String> {
+		self.light_dispatch.sync.enr().ok_or_else(errors::network_disabled)
+	}
+
 	fn consensus_capability(&self) -> Result<ConsensusCapability> {
 		Err(errors::light_unimplemented(None))
 	}
@@ -338,7 +342,7 @@ where
 
 		let engine = self.light_dispatch.client.engine().clone();
 		let from_encoded = move |encoded: encoded::Header| {
-			let header = encoded.decode().map_err(errors::decode)?;
+			let header = encoded.decode(engine.params().eip1559_transition).map_err(errors::decode)?;
 			let extra_info = engine.extra_info(&header);
 			Ok(RichHeader {
 				inner: Header {
@@ -354,6 +358,7 @@ where
 					number: Some(header.number().into()),
 					gas_used: *header.gas_used(),
 					gas_limit: *header.gas_limit(),
+					base_fee_per_gas: header.base_fee(),
 					logs_bloom: *header.log_bloom(),
 					timestamp: header.timestamp().into(),
 					difficulty: *header.difficulty(),
@@ -20,7 +20,7 @@ use std::collections::BTreeMap;
 
 use crypto::DEFAULT_MAC;
 use ethereum_types::{H64, H160, H256, H512, U64, U256};
-use ethcore::client::Call;
+use ethcore::client::{Call, EngineInfo};
 use client_traits::{BlockChainClient, StateClient};
 use ethcore::miner::{self, MinerService, FilterOptions};
 use snapshot::SnapshotService;
@@ -50,7 +50,7 @@ use v1::types::{
 	TransactionStats, LocalTransactionStatus,
 	BlockNumber, ConsensusCapability, VersionInfo,
 	OperationsInfo, ChainStatus, Log, Filter,
-	RichHeader, Receipt, RecoveredAccount,
+	Header, RichHeader, Receipt, RecoveredAccount,
 	block_number_to_id
 };
 use Host;
@@ -102,7 +102,7 @@ impl<C, M, U> ParityClient<C, M, U> where
 
 impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 	S: StateInfo + 'static,
-	C: miner::BlockChainClient + BlockChainClient + StateClient<State=S> + Call<State=S> + 'static,
+	C: miner::BlockChainClient + BlockChainClient + StateClient<State=S> + Call<State=S> + EngineInfo + 'static,
 	M: MinerService<State=S> + 'static,
 	U: UpdateService + 'static,
 {
@@ -311,6 +311,10 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 		self.sync.enode().ok_or_else(errors::network_disabled)
 	}
 
+	fn enr(&self) -> Result<String> {
+		self.sync.enr().ok_or_else(errors::network_disabled)
+	}
+
 	fn consensus_capability(&self) -> Result<ConsensusCapability> {
 		Ok(self.updater.capability().into())
 	}
@@ -369,7 +373,7 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 		};
 
 		Box::new(future::ok(RichHeader {
-			inner: header.into(),
+			inner: Header::new(&header, self.client.engine().params().eip1559_transition),
 			extra_info: extra.unwrap_or_default(),
 		}))
 	}
@@ -427,7 +431,8 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 			};
 
 			let state = self.client.state_at(id).ok_or_else(errors::state_pruned)?;
-			let header = self.client.block_header(id).ok_or_else(errors::state_pruned)?.decode().map_err(errors::decode)?;
+			let header = self.client.block_header(id).ok_or_else(errors::state_pruned)?
+				.decode(self.client.engine().params().eip1559_transition).map_err(errors::decode)?;
 
 			(state, header)
 		};
@@ -480,7 +485,7 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 
 	fn submit_raw_block(&self, block: Bytes) -> Result<H256> {
 		let result = self.client.import_block(
-			Unverified::from_rlp(block.into_vec()).map_err(errors::rlp)?
+			Unverified::from_rlp(block.into_vec(), self.client.engine().params().eip1559_transition).map_err(errors::rlp)?
 		);
 		Ok(result.map_err(errors::cannot_submit_block)?)
 	}
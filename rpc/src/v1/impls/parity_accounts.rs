@@ -124,6 +124,33 @@ impl ParityAccounts for ParityAccountsClient {
 			.map_err(|e| errors::account("Could not create account.", e))
 	}
 
+	fn new_hd_wallet(&self, words: u64, pass: Password) -> Result<String> {
+		self.deprecation_notice("parity_newHdWallet");
+		self.accounts.new_hd_wallet(words as usize, &pass)
+			.map_err(|e| errors::account("Could not create HD wallet.", e))
+	}
+
+	fn import_hd_wallet(&self, phrase: String, pass: Password, count: u32, passphrase: Option<String>) -> Result<Vec<H160>> {
+		self.deprecation_notice("parity_importHdWallet");
+		self.accounts.import_hd_wallet(&phrase, &passphrase.unwrap_or_default(), &pass, count)
+			.map(into_vec)
+			.map_err(|e| errors::account("Could not import HD wallet.", e))
+	}
+
+	fn list_hd_accounts(&self) -> Result<Vec<H160>> {
+		self.deprecation_notice("parity_listHdAccounts");
+		self.accounts.hd_accounts()
+			.map(into_vec)
+			.map_err(|e| errors::account("Could not fetch HD wallet accounts.", e))
+	}
+
+	fn derive_hd_accounts(&self, pass: Password, count: u32) -> Result<Vec<H160>> {
+		self.deprecation_notice("parity_deriveHdAccounts");
+		self.accounts.derive_hd_accounts(&pass, count)
+			.map(into_vec)
+			.map_err(|e| errors::account("Could not derive HD wallet accounts.", e))
+	}
+
 	fn new_account_from_wallet(&self, json: String, pass: Password) -> Result<H160> {
 		self.deprecation_notice("parity_newAccountFromWallet");
 		self.accounts.import_presale(json.as_bytes(), &pass)
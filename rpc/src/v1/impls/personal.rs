@@ -176,11 +176,11 @@ impl<D: Dispatcher + 'static> Personal for PersonalClient<D> {
 		self.deprecation_notice.print("personal_sign191", deprecated::msgs::ACCOUNTS);
 		try_bf!(errors::require_experimental(self.allow_experimental_rpcs, "191"));
 
-		let data = try_bf!(eip191::hash_message(version, data));
+		let (data, message) = try_bf!(eip191::hash_message(version, data));
 		let dispatcher = self.dispatcher.clone();
 		let accounts = Arc::new(dispatch::Signer::new(self.accounts.clone())) as _;
 
-		let payload = RpcConfirmationPayload::EIP191SignMessage((account.clone(), data.into()).into());
+		let payload = RpcConfirmationPayload::EIP191SignMessage((account.clone(), data, message).into());
 
 		Box::new(dispatch::from_rpc(payload, account.into(), &dispatcher)
 			.and_then(move |payload| {
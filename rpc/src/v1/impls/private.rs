@@ -18,11 +18,10 @@
 
 use std::sync::Arc;
 
-use rlp::Rlp;
 
 use ethcore_private_tx::Provider as PrivateTransactionManager;
 use ethereum_types::{Address, H160, H256, U256};
-use types::transaction::SignedTransaction;
+use types::transaction::{SignedTransaction, UnverifiedTransaction};
 
 use jsonrpc_core::{Error};
 use v1::types::{Bytes, PrivateTransactionReceipt, TransactionRequest,
@@ -57,7 +56,7 @@ impl Private for PrivateClient {
 	type Metadata = Metadata;
 
 	fn send_transaction(&self, request: Bytes) -> Result<PrivateTransactionReceipt, Error> {
-		let signed_transaction = Rlp::new(&request.into_vec()).as_val()
+		let signed_transaction = UnverifiedTransaction::decode_raw(&request.into_vec())
 			.map_err(errors::rlp)
 			.and_then(|tx| SignedTransaction::new(tx).map_err(errors::transaction))?;
 		let client = self.unwrap_manager()?;
@@ -66,7 +65,7 @@ impl Private for PrivateClient {
 	}
 
 	fn compose_deployment_transaction(&self, block_number: BlockNumber, request: Bytes, validators: Vec<H160>, gas_price: U256) -> Result<PrivateTransactionReceiptAndTransaction, Error> {
-		let signed_transaction = Rlp::new(&request.into_vec()).as_val()
+		let signed_transaction = UnverifiedTransaction::decode_raw(&request.into_vec())
 			.map_err(errors::rlp)
 			.and_then(|tx| SignedTransaction::new(tx).map_err(errors::transaction))?;
 		let client = self.unwrap_manager()?;
@@ -90,6 +89,10 @@ impl Private for PrivateClient {
 			value: Some(transaction.value),
 			data: Some(transaction.data.into()),
 			condition: None,
+			transaction_type: None,
+			access_list: None,
+			max_fee_per_gas: None,
+			max_priority_fee_per_gas: None,
 		};
 
 		Ok(PrivateTransactionReceiptAndTransaction {
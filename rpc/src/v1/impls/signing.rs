@@ -20,6 +20,7 @@ use std::sync::Arc;
 use transient_hashmap::TransientHashMap;
 use parking_lot::Mutex;
 
+use eip_712::{EIP712, Version};
 use ethereum_types::{H160, H256, H520, U256};
 
 use jsonrpc_core::{BoxFuture, Result, Error};
@@ -28,7 +29,7 @@ use jsonrpc_core::futures::future::Either;
 
 use v1::helpers::deprecated::{self, DeprecationNotice};
 use v1::helpers::dispatch::{self, Dispatcher};
-use v1::helpers::errors;
+use v1::helpers::{eip191, errors};
 use v1::helpers::external_signer::{
 	SignerService, SigningQueue,
 	ConfirmationReceiver as RpcConfirmationReceiver,
@@ -141,6 +142,38 @@ impl<D: Dispatcher + 'static> SigningQueueClient<D> {
 				}
 			}))
 	}
+
+	fn post_sign_typed_data(&self, origin: Origin, address: H160, typed_data: EIP712, version: Version) -> BoxFuture<RpcEither<U256, RpcConfirmationResponse>> {
+		let (hash, message) = try_bf!(eip191::hash_typed_data(typed_data, version));
+		let executor = self.executor.clone();
+		let confirmations = self.confirmations.clone();
+
+		Box::new(self.dispatch(
+			RpcConfirmationPayload::EIP191SignMessage((address, hash, message).into()),
+			origin
+		).map(move |result| match result {
+			DispatchResult::Value(v) => RpcEither::Or(v),
+			DispatchResult::Future(id, future) => {
+				schedule(executor, confirmations, id, future);
+				RpcEither::Either(id)
+			},
+		}))
+	}
+
+	fn sign_typed_data(&self, origin: Origin, address: H160, typed_data: EIP712, version: Version) -> BoxFuture<H520> {
+		let (hash, message) = try_bf!(eip191::hash_typed_data(typed_data, version));
+		let res = self.dispatch(
+			RpcConfirmationPayload::EIP191SignMessage((address, hash, message).into()),
+			origin,
+		);
+
+		Box::new(res.flatten().and_then(move |response| {
+			match response {
+				RpcConfirmationResponse::Signature(sig) => Ok(sig),
+				e => Err(errors::internal("Unexpected result.", e)),
+			}
+		}))
+	}
 }
 
 impl<D: Dispatcher + 'static> ParitySigning for SigningQueueClient<D> {
@@ -183,6 +216,16 @@ impl<D: Dispatcher + 'static> ParitySigning for SigningQueueClient<D> {
 			}))
 	}
 
+	fn post_sign_typed_data_v3(&self, meta: Metadata, address: H160, typed_data: EIP712) -> BoxFuture<RpcEither<U256, RpcConfirmationResponse>> {
+		self.deprecation_notice.print("parity_postSignTypedData_v3", deprecated::msgs::ACCOUNTS);
+		self.post_sign_typed_data(meta.origin, address, typed_data, Version::V3)
+	}
+
+	fn post_sign_typed_data_v4(&self, meta: Metadata, address: H160, typed_data: EIP712) -> BoxFuture<RpcEither<U256, RpcConfirmationResponse>> {
+		self.deprecation_notice.print("parity_postSignTypedData_v4", deprecated::msgs::ACCOUNTS);
+		self.post_sign_typed_data(meta.origin, address, typed_data, Version::V4)
+	}
+
 	fn check_request(&self, id: U256) -> Result<Option<RpcConfirmationResponse>> {
 		self.deprecation_notice.print("parity_checkRequest", deprecated::msgs::ACCOUNTS);
 		match self.confirmations.lock().get(&id) {
@@ -257,4 +300,14 @@ impl<D: Dispatcher + 'static> EthSigning for SigningQueueClient<D> {
 			}
 		}))
 	}
+
+	fn sign_typed_data_v3(&self, meta: Metadata, address: H160, typed_data: EIP712) -> BoxFuture<H520> {
+		self.deprecation_notice.print("eth_signTypedData_v3", deprecated::msgs::ACCOUNTS);
+		self.sign_typed_data(meta.origin, address, typed_data, Version::V3)
+	}
+
+	fn sign_typed_data_v4(&self, meta: Metadata, address: H160, typed_data: EIP712) -> BoxFuture<H520> {
+		self.deprecation_notice.print("eth_signTypedData_v4", deprecated::msgs::ACCOUNTS);
+		self.sign_typed_data(meta.origin, address, typed_data, Version::V4)
+	}
 }
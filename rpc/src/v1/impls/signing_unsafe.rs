@@ -18,10 +18,11 @@
 
 use std::sync::Arc;
 
+use eip_712::{EIP712, Version};
 use ethereum_types::{Address, H160, H256, H520, U256};
 use jsonrpc_core::{BoxFuture, Result};
 use jsonrpc_core::futures::{future, Future};
-use v1::helpers::{errors};
+use v1::helpers::{eip191, errors};
 use v1::helpers::deprecated::{self, DeprecationNotice};
 use v1::helpers::dispatch::{self, Dispatcher};
 use v1::metadata::Metadata;
@@ -62,6 +63,16 @@ impl<D: Dispatcher + 'static> SigningUnsafeClient<D> {
 			})
 			.map(dispatch::WithToken::into_value))
 	}
+
+	fn sign_typed_data(&self, address: H160, typed_data: EIP712, version: Version) -> BoxFuture<H520> {
+		let (hash, message) = try_bf!(eip191::hash_typed_data(typed_data, version));
+		Box::new(self.handle(RpcConfirmationPayload::EIP191SignMessage((address, hash, message).into()), address)
+			.then(|res| match res {
+				Ok(RpcConfirmationResponse::Signature(signature)) => Ok(signature),
+				Err(e) => Err(e),
+				e => Err(errors::internal("Unexpected result", e)),
+			}))
+	}
 }
 
 impl<D: Dispatcher + 'static> EthSigning for SigningUnsafeClient<D>
@@ -98,6 +109,16 @@ impl<D: Dispatcher + 'static> EthSigning for SigningUnsafeClient<D>
 				e => Err(errors::internal("Unexpected result", e)),
 			}))
 	}
+
+	fn sign_typed_data_v3(&self, _: Metadata, address: H160, typed_data: EIP712) -> BoxFuture<H520> {
+		self.deprecation_notice.print("eth_signTypedData_v3", deprecated::msgs::ACCOUNTS);
+		self.sign_typed_data(address, typed_data, Version::V3)
+	}
+
+	fn sign_typed_data_v4(&self, _: Metadata, address: H160, typed_data: EIP712) -> BoxFuture<H520> {
+		self.deprecation_notice.print("eth_signTypedData_v4", deprecated::msgs::ACCOUNTS);
+		self.sign_typed_data(address, typed_data, Version::V4)
+	}
 }
 
 impl<D: Dispatcher + 'static> ParitySigning for SigningUnsafeClient<D> {
@@ -129,6 +150,16 @@ impl<D: Dispatcher + 'static> ParitySigning for SigningUnsafeClient<D> {
 		Box::new(future::err(errors::signer_disabled()))
 	}
 
+	fn post_sign_typed_data_v3(&self, _: Metadata, _: H160, _: EIP712) -> BoxFuture<RpcEither<U256, RpcConfirmationResponse>> {
+		// We don't support this in non-signer mode.
+		Box::new(future::err(errors::signer_disabled()))
+	}
+
+	fn post_sign_typed_data_v4(&self, _: Metadata, _: H160, _: EIP712) -> BoxFuture<RpcEither<U256, RpcConfirmationResponse>> {
+		// We don't support this in non-signer mode.
+		Box::new(future::err(errors::signer_disabled()))
+	}
+
 	fn check_request(&self, _: U256) -> Result<Option<RpcConfirmationResponse>> {
 		// We don't support this in non-signer mode.
 		Err(errors::signer_disabled())
@@ -94,8 +94,9 @@ impl EthTester {
 			Self::from_spec(make_spec(chain))
 		};
 
+		let eip1559_transition = tester.client.engine().params().eip1559_transition;
 		for b in chain.blocks_rlp() {
-			if let Ok(block) = Unverified::from_rlp(b) {
+			if let Ok(block) = Unverified::from_rlp(b, eip1559_transition) {
 				let _ = tester.client.import_block(block);
 				tester.client.flush_queue();
 			}
@@ -141,7 +142,7 @@ impl EthTester {
 				pending_nonce_from_queue: false,
 				allow_pending_receipt_query: true,
 				send_block_number_in_get_work: true,
-				gas_price_percentile: 50,
+				gas_price_oracle: Default::default(),
 				allow_experimental_rpcs: true,
 				allow_missing_blocks: false,
 				no_ancient_blocks: false
@@ -150,7 +151,7 @@ impl EthTester {
 
 		let reservations = Arc::new(Mutex::new(nonce::Reservations::new(runtime.executor())));
 
-		let dispatcher = FullDispatcher::new(client.clone(), miner_service.clone(), reservations, 50);
+		let dispatcher = FullDispatcher::new(client.clone(), miner_service.clone(), reservations, Default::default());
 		let signer = Arc::new(dispatch::Signer::new(account_provider.clone())) as _;
 		let eth_sign = SigningUnsafeClient::new(
 			&signer,
@@ -474,7 +475,8 @@ fn verify_transaction_counts(name: String, chain: BlockChain) {
 	let tester = EthTester::from_chain(&chain);
 
 	let mut id = 1;
-	for b in chain.blocks_rlp().into_iter().filter_map(|b| Unverified::from_rlp(b).ok()) {
+	let eip1559_transition = tester.client.engine().params().eip1559_transition;
+	for b in chain.blocks_rlp().into_iter().filter_map(|b| Unverified::from_rlp(b, eip1559_transition).ok()) {
 		let count = b.transactions.len();
 
 		let hash = b.header.hash();
@@ -24,7 +24,7 @@ use client_traits::{Nonce, StateClient, ForceUpdateSealing};
 use engine::{Engine, signer::EngineSigner};
 use ethcore::block::SealedBlock;
 use ethcore::client::{PrepareOpenBlock, EngineInfo};
-use ethcore::miner::{self, MinerService, AuthoringParams, FilterOptions};
+use ethcore::miner::{self, MinerService, AuthoringParams, FilterOptions, Bundle, BundleError, BundleSimulation, SimulatedBundleTransaction};
 use ethcore::test_helpers::TestState;
 use ethereum_types::{H256, U256, Address};
 use miner::pool::local_transactions::Status as LocalTransactionStatus;
@@ -57,6 +57,8 @@ pub struct TestMinerService {
 	pub min_gas_price: RwLock<Option<U256>>,
 	/// Signer (if any)
 	pub signer: RwLock<Option<Box<dyn EngineSigner>>>,
+	/// Imported bundles.
+	pub imported_bundles: Mutex<Vec<Bundle>>,
 
 	authoring_params: RwLock<AuthoringParams>,
 }
@@ -76,6 +78,7 @@ impl Default for TestMinerService {
 				extra_data: vec![1, 2, 3, 4],
 			}),
 			signer: RwLock::new(None),
+			imported_bundles: Default::default(),
 		}
 	}
 }
@@ -186,6 +189,54 @@ impl MinerService for TestMinerService {
 		Ok(())
 	}
 
+	fn import_bundle<C>(&self, _chain: &C, bundle: Bundle<UnverifiedTransaction>) -> Result<H256, BundleError> {
+		if bundle.transactions.is_empty() {
+			return Err(BundleError::Empty);
+		}
+
+		// lets assume that all txs are valid
+		let bundle = Bundle {
+			transactions: bundle.transactions.into_iter().map(|tx| SignedTransaction::new(tx).unwrap()).collect(),
+			block_number: bundle.block_number,
+			min_timestamp: bundle.min_timestamp,
+			max_timestamp: bundle.max_timestamp,
+			reverting_hashes: bundle.reverting_hashes,
+		};
+		let hash = bundle.hash();
+		self.imported_bundles.lock().push(bundle);
+		Ok(hash)
+	}
+
+	fn call_bundle<C>(&self, _chain: &C, transactions: Vec<UnverifiedTransaction>, _timestamp: Option<u64>)
+		-> Result<BundleSimulation, BundleError> {
+		if transactions.is_empty() {
+			return Err(BundleError::Empty);
+		}
+		let transactions: Vec<_> = transactions.into_iter().map(|tx| SignedTransaction::new(tx).unwrap()).collect();
+
+		// lets assume that all txs are simple transfers paying their gas price to the author
+		Ok(BundleSimulation {
+			bundle_hash: miner::bundle_hash(&transactions),
+			transactions: transactions.iter().map(|tx| {
+				let gas_used = U256::from(21_000);
+				let gas_price = tx.gas_price;
+				SimulatedBundleTransaction {
+					hash: tx.hash(),
+					sender: tx.sender(),
+					to: match tx.action {
+						transaction::Action::Call(ref address) => Some(*address),
+						transaction::Action::Create => None,
+					},
+					gas_used,
+					gas_price,
+					gas_fees: gas_used * gas_price,
+					coinbase_diff: gas_used * gas_price,
+					reverted: false,
+				}
+			}).collect(),
+		})
+	}
+
 	/// Called when blocks are imported to chain, updates transactions queue.
 	fn chain_new_blocks<C>(&self, _chain: &C, _imported: &[H256], _invalid: &[H256], _enacted: &[H256], _retracted: &[H256], _is_internal: bool) {
 		unimplemented!();
@@ -17,11 +17,13 @@
 //! Test rpc services.
 
 mod miner_service;
+mod remote_signer;
 mod snapshot_service;
 mod sync_provider;
 mod update_service;
 
 pub use self::miner_service::TestMinerService;
+pub use self::remote_signer::TestRemoteSigner;
 pub use self::snapshot_service::TestSnapshotService;
 pub use self::sync_provider::{Config, TestSyncProvider};
 pub use self::update_service::TestUpdater;
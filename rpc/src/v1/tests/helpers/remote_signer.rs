@@ -0,0 +1,132 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Test implementation of an external, Clef-compatible signer.
+
+use std::sync::Arc;
+
+use crypto::publickey::{self, KeyPair};
+use eip_712::{hash_structured_data_with_version, EIP712, Version};
+use hash::keccak;
+use jsonrpc_core::{Error, ErrorCode, IoHandler, Params};
+use jsonrpc_core::futures::future;
+use ethereum_types::{Address, H256};
+use serde_json::{self, Value};
+use types::transaction::{Action, AccessListTx, EIP1559Tx, Transaction, TypedTransaction};
+
+use v1::helpers::dispatch::{eth_data_hash, SignerTransport, TransportFuture};
+use v1::helpers::dispatch::remote_signer::{SendTxArgs, SignTransactionResult, ValidatorData};
+use v1::types::Bytes;
+
+/// Test external signer holding the keys in memory.
+pub struct TestRemoteSigner {
+	io: IoHandler,
+}
+
+impl TestRemoteSigner {
+	/// Creates new signer managing given keys.
+	/// If `reject` is set every signing request is declined, as if the user rejected it.
+	pub fn new(keys: Vec<KeyPair>, reject: bool) -> Self {
+		let keys = Arc::new(keys);
+		let mut io = IoHandler::default();
+
+		let accounts = keys.clone();
+		io.add_method("account_list", move |_params: Params| -> Result<Value, Error> {
+			let addresses = accounts.iter().map(KeyPair::address).collect::<Vec<_>>();
+			Ok(serde_json::to_value(addresses).expect("Addresses are serializable; qed"))
+		});
+
+		let accounts = keys.clone();
+		io.add_method("account_signTransaction", move |params: Params| -> Result<Value, Error> {
+			let (args,): (SendTxArgs,) = params.parse()?;
+			let key = find_key(&accounts, reject, &args.from)?;
+
+			let chain_id = args.chain_id.map(|id| id.low_u64());
+			let tx = Transaction {
+				nonce: args.nonce,
+				action: args.to.map_or(Action::Create, Action::Call),
+				gas: args.gas,
+				gas_price: args.max_fee_per_gas.unwrap_or(args.gas_price),
+				value: args.value,
+				data: args.data.into_vec(),
+			};
+			let access_list = args.access_list.map(|list| list.into_iter().map(Into::into).collect());
+			let tx = match (args.max_priority_fee_per_gas, access_list) {
+				(Some(fee), list) => TypedTransaction::EIP1559(EIP1559Tx::new(AccessListTx::new(tx, list.unwrap_or_default()), fee)),
+				(None, Some(list)) => TypedTransaction::AccessList(AccessListTx::new(tx, list)),
+				(None, None) => TypedTransaction::Legacy(tx),
+			};
+			let signature = publickey::sign(key.secret(), &tx.hash(chain_id)).expect("Hash is valid; qed");
+			let raw = tx.with_signature(signature, chain_id).encode_raw();
+
+			Ok(serde_json::to_value(SignTransactionResult { raw: raw.into() }).expect("Result is serializable; qed"))
+		});
+
+		let accounts = keys.clone();
+		io.add_method("account_signData", move |params: Params| -> Result<Value, Error> {
+			let (content_type, address, data): (String, Address, Value) = params.parse()?;
+			let hash = match content_type.as_str() {
+				"text/plain" => {
+					let data: Bytes = serde_json::from_value(data).map_err(|e| Error::invalid_params(e.to_string()))?;
+					eth_data_hash(data.into_vec())
+				},
+				"data/validator" => {
+					let data: ValidatorData = serde_json::from_value(data).map_err(|e| Error::invalid_params(e.to_string()))?;
+					keccak([&b"\x19\x00"[..], &data.address.0[..], &data.message.0[..]].concat())
+				},
+				_ => return Err(Error::invalid_params(format!("Unsupported content type: {}", content_type))),
+			};
+			let key = find_key(&accounts, reject, &address)?;
+			sign(key, &hash)
+		});
+
+		let accounts = keys;
+		io.add_method("account_signTypedData", move |params: Params| -> Result<Value, Error> {
+			let (address, typed_data): (Address, EIP712) = params.parse()?;
+			let hash = hash_structured_data_with_version(typed_data, Version::V4)
+				.map_err(|e| Error::invalid_params(format!("{:?}", e.kind())))?;
+			let key = find_key(&accounts, reject, &address)?;
+			sign(key, &hash)
+		});
+
+		TestRemoteSigner { io }
+	}
+}
+
+fn sign(key: &KeyPair, hash: &H256) -> Result<Value, Error> {
+	let signature = publickey::sign(key.secret(), hash).expect("Hash is valid; qed");
+	Ok(serde_json::to_value(Bytes::new(signature.into_electrum().to_vec())).expect("Bytes are serializable; qed"))
+}
+
+fn find_key<'a>(keys: &'a [KeyPair], reject: bool, address: &Address) -> Result<&'a KeyPair, Error> {
+	if reject {
+		return Err(Error {
+			code: ErrorCode::ServerError(-32000),
+			message: "Request denied".into(),
+			data: None,
+		});
+	}
+
+	keys.iter()
+		.find(|key| key.address() == *address)
+		.ok_or_else(|| Error::invalid_params(format!("Unknown account: {:?}", address)))
+}
+
+impl SignerTransport for TestRemoteSigner {
+	fn send(&self, request: String) -> TransportFuture {
+		Box::new(future::done(self.io.handle_request_sync(&request).ok_or_else(|| "No response".to_owned())))
+	}
+}
@@ -112,6 +112,10 @@ impl SyncProvider for TestSyncProvider {
 		None
 	}
 
+	fn enr(&self) -> Option<String> {
+		None
+	}
+
 	fn transactions_stats(&self) -> BTreeMap<H256, TransactionStats> {
 		map![
 			H256::from_low_u64_be(1) => TransactionStats {
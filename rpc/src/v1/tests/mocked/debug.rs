@@ -17,6 +17,10 @@
 use std::sync::Arc;
 
 use ethcore::test_helpers::TestBlockChainClient;
+use ethereum_types::{Address, H256};
+use machine::executed::Executed;
+use trace::LocalizedTrace;
+use trace::trace::{Action, Call, CallType, Res};
 
 use jsonrpc_core::IoHandler;
 use v1::{Debug, DebugClient};
@@ -35,3 +39,58 @@ fn rpc_debug_get_bad_blocks() {
 	let response = "{\"jsonrpc\":\"2.0\",\"result\":[{\"author\":\"0x0000000000000000000000000000000000000000\",\"difficulty\":\"0x0\",\"extraData\":\"0x\",\"gasLimit\":\"0x0\",\"gasUsed\":\"0x0\",\"hash\":\"0x27bfb37e507ce90da141307204b1c6ba24194380613590ac50ca4b1d7198ff65\",\"logsBloom\":\"0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000\",\"miner\":\"0x0000000000000000000000000000000000000000\",\"number\":\"0x0\",\"parentHash\":\"0x0000000000000000000000000000000000000000000000000000000000000000\",\"reason\":\"Invalid block\",\"receiptsRoot\":\"0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421\",\"rlp\":\"\\\"0x010203\\\"\",\"sealFields\":[],\"sha3Uncles\":\"0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347\",\"size\":\"0x3\",\"stateRoot\":\"0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421\",\"timestamp\":\"0x0\",\"totalDifficulty\":null,\"transactions\":[],\"transactionsRoot\":\"0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421\",\"uncles\":[]}],\"id\":1}";
 	assert_eq!(io().handle_request_sync(request), Some(response.to_owned()));
 }
+
+fn io_with_replay() -> IoHandler {
+	let client = Arc::new(TestBlockChainClient::new());
+	*client.traces.write() = Some(vec![LocalizedTrace {
+		action: Action::Call(Call {
+			from: Address::from_low_u64_be(0xf),
+			to: Address::from_low_u64_be(0x10),
+			value: 0x1.into(),
+			gas: 0x100.into(),
+			input: vec![1, 2, 3],
+			call_type: Some(CallType::Call).into(),
+		}),
+		result: Res::None,
+		subtraces: 0,
+		trace_address: vec![0],
+		transaction_number: Some(0),
+		transaction_hash: Some(H256::from_low_u64_be(5)),
+		block_number: 10,
+		block_hash: H256::from_low_u64_be(10),
+	}]);
+	*client.execution_result.write() = Some(Ok(Executed {
+		exception: None,
+		gas: 20_000.into(),
+		gas_used: 10_000.into(),
+		refunded: 0.into(),
+		cumulative_gas_used: 10_000.into(),
+		logs: vec![],
+		contracts_created: vec![],
+		output: vec![1, 2, 3],
+		trace: vec![],
+		vm_trace: None,
+		state_diff: None,
+		access_list: None,
+		max_fee_per_gas: None,
+		max_priority_fee_per_gas: None,
+	}));
+
+	let mut io = IoHandler::new();
+	io.extend_with(DebugClient::new(client).to_delegate());
+	io
+}
+
+#[test]
+fn rpc_debug_trace_block_by_number() {
+	let request = r#"{"jsonrpc": "2.0", "method": "debug_traceBlockByNumber", "params": ["0x10", {"disableStack": true}], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[{"result":{"failed":false,"gas":10000,"returnValue":"010203","structLogs":[]},"txHash":"0x0000000000000000000000000000000000000000000000000000000000000005"}],"id":1}"#;
+	assert_eq!(io_with_replay().handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_debug_trace_block_by_hash_rejects_unknown_tracer() {
+	let request = r#"{"jsonrpc": "2.0", "method": "debug_traceBlockByHash", "params": ["0x000000000000000000000000000000000000000000000000000000000000000a", {"tracer": "4byteTracer"}], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: tracer","data":"\"Unsupported tracer: 4byteTracer\""},"id":1}"#;
+	assert_eq!(io_with_replay().handle_request_sync(request), Some(response.to_owned()));
+}
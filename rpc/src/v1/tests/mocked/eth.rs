@@ -33,7 +33,7 @@ use rustc_hex::{FromHex, ToHex};
 use sync::SyncState;
 use types::{
 	ids::{BlockId, TransactionId},
-	transaction::{Transaction, Action},
+	transaction::{Transaction, Action, AccessListItem, TypedTxId},
 	log_entry::{LocalizedLogEntry, LogEntry},
 	receipt::{LocalizedReceipt, RichReceipt, TransactionOutcome},
 	snapshot::RestorationStatus,
@@ -410,6 +410,17 @@ fn rpc_eth_gas_price() {
 	assert_eq!(EthTester::default().io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_eth_fee_history_rejects_invalid_params() {
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_feeHistory", "params": ["0x0", "latest"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: blockCount","data":"\"Expected between 1 and 1024 blocks\""},"id":1}"#;
+	assert_eq!(EthTester::default().io.handle_request_sync(request), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_feeHistory", "params": ["0x4", "latest", [50, 10]], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: rewardPercentiles","data":"\"Expected increasing percentiles between 0 and 100\""},"id":1}"#;
+	assert_eq!(EthTester::default().io.handle_request_sync(request), Some(response.to_owned()));
+}
+
 #[test]
 fn rpc_eth_accounts() {
 	let tester = EthTester::default();
@@ -575,7 +586,7 @@ fn rpc_eth_pending_transaction_by_hash() {
 		tester.miner.pending_transactions.lock().insert(H256::zero(), tx);
 	}
 
-	let response = r#"{"jsonrpc":"2.0","result":{"blockHash":null,"blockNumber":null,"chainId":null,"condition":null,"creates":null,"from":"0x0f65fe9276bc9a24ae7083ae28e2660ef72df99e","gas":"0x5208","gasPrice":"0x1","hash":"0x41df922fd0d4766fcc02e161f8295ec28522f329ae487f14d811e4b64c8d6e31","input":"0x","nonce":"0x0","publicKey":"0x7ae46da747962c2ee46825839c1ef9298e3bd2e70ca2938495c3693a485ec3eaa8f196327881090ff64cf4fbb0a48485d4f83098e189ed3b7a87d5941b59f789","r":"0x48b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353","raw":"0xf85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804","s":"0xefffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804","standardV":"0x0","to":"0x095e7baea6a6c7c4c2dfeb977efac326af552d87","transactionIndex":null,"v":"0x1b","value":"0xa"},"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"blockHash":null,"blockNumber":null,"chainId":null,"condition":null,"creates":null,"from":"0x0f65fe9276bc9a24ae7083ae28e2660ef72df99e","gas":"0x5208","gasPrice":"0x1","hash":"0x41df922fd0d4766fcc02e161f8295ec28522f329ae487f14d811e4b64c8d6e31","input":"0x","nonce":"0x0","publicKey":"0x7ae46da747962c2ee46825839c1ef9298e3bd2e70ca2938495c3693a485ec3eaa8f196327881090ff64cf4fbb0a48485d4f83098e189ed3b7a87d5941b59f789","r":"0x48b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353","raw":"0xf85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804","s":"0xefffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804","standardV":"0x0","to":"0x095e7baea6a6c7c4c2dfeb977efac326af552d87","transactionIndex":null,"type":"0x0","v":"0x1b","value":"0xa"},"id":1}"#;
 	let request = r#"{
 		"jsonrpc": "2.0",
 		"method": "eth_getTransactionByHash",
@@ -642,6 +653,7 @@ fn rpc_eth_call_latest() {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		access_list: None,
 	}));
 
 	let request = r#"{
@@ -678,6 +690,7 @@ fn rpc_eth_call_pending() {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		access_list: None,
 	}));
 
 	let request = r#"{
@@ -715,6 +728,7 @@ fn rpc_eth_call() {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		access_list: None,
 	}));
 
 	let request = r#"{
@@ -751,6 +765,7 @@ fn rpc_eth_call_default_block() {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		access_list: None,
 	}));
 
 	let request = r#"{
@@ -771,6 +786,49 @@ fn rpc_eth_call_default_block() {
 	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_eth_call_dynamic_fee() {
+	let tester = EthTester::default();
+	tester.client.set_execution_result(Ok(Executed {
+		exception: None,
+		gas: U256::zero(),
+		gas_used: U256::from(0xff30),
+		refunded: U256::from(0x5),
+		cumulative_gas_used: U256::zero(),
+		logs: vec![],
+		contracts_created: vec![],
+		output: vec![0x12, 0x34, 0xff],
+		trace: vec![],
+		vm_trace: None,
+		state_diff: None,
+		access_list: None,
+	}));
+
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_call",
+		"params": [{
+			"from": "0xb60e8dd61c5d32be8058bb8eb970870f07233155",
+			"to": "0xd46e8dd67c5d32be8058bb8eb970870f07244567",
+			"gas": "0x76c0",
+			"type": "0x2",
+			"maxFeePerGas": "0x9184e72a000",
+			"maxPriorityFeePerGas": "0x3b9aca00",
+			"value": "0x9184e72a",
+			"data": "0xd46e8dd67c5d32be8d46e8dd67c5d32be8058bb8eb970870f072445675058bb8eb970870f072445675"
+		},
+		"latest"],
+		"id": 1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x1234ff","id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+	let call = tester.client.last_call.read().clone().unwrap();
+	assert_eq!(call.tx_type(), TypedTxId::EIP1559);
+	assert_eq!(call.gas_price, U256::from_str("9184e72a000").unwrap());
+	assert_eq!(call.max_priority_fee_per_gas(), U256::from(1_000_000_000));
+}
+
 #[test]
 fn rpc_eth_estimate_gas() {
 	let tester = EthTester::default();
@@ -786,6 +844,7 @@ fn rpc_eth_estimate_gas() {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		access_list: None,
 	}));
 
 	let request = r#"{
@@ -822,6 +881,7 @@ fn rpc_eth_estimate_gas_pending() {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		access_list: None,
 	}));
 
 	let request = r#"{
@@ -859,6 +919,7 @@ fn rpc_eth_estimate_gas_default_block() {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		access_list: None,
 	}));
 
 	let request = r#"{
@@ -879,6 +940,138 @@ fn rpc_eth_estimate_gas_default_block() {
 	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_eth_estimate_gas_dynamic_fee() {
+	let tester = EthTester::default();
+
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_estimateGas",
+		"params": [{
+			"from": "0xb60e8dd61c5d32be8058bb8eb970870f07233155",
+			"to": "0xd46e8dd67c5d32be8058bb8eb970870f07244567",
+			"type": "0x2",
+			"maxFeePerGas": "0x9184e72a000",
+			"value": "0x9184e72a"
+		},
+		"latest"],
+		"id": 1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x5208","id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+	let call = tester.client.last_call.read().clone().unwrap();
+	assert_eq!(call.tx_type(), TypedTxId::EIP1559);
+	assert_eq!(call.gas_price, U256::from_str("9184e72a000").unwrap());
+	assert_eq!(call.max_priority_fee_per_gas(), U256::zero());
+}
+
+#[test]
+fn rpc_eth_create_access_list() {
+	let tester = EthTester::default();
+	tester.client.set_execution_result(Ok(Executed {
+		exception: None,
+		gas: U256::zero(),
+		gas_used: U256::from(0xff30),
+		refunded: U256::from(0x5),
+		cumulative_gas_used: U256::zero(),
+		logs: vec![],
+		contracts_created: vec![],
+		output: vec![],
+		trace: vec![],
+		vm_trace: None,
+		state_diff: None,
+		access_list: Some(vec![
+			AccessListItem { address: H160::from_low_u64_be(1), storage_keys: vec![H256::from_low_u64_be(2)] },
+			AccessListItem { address: H160::from_low_u64_be(3), storage_keys: vec![] },
+		]),
+	}));
+
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_createAccessList",
+		"params": [{
+			"from": "0xb60e8dd61c5d32be8058bb8eb970870f07233155",
+			"to": "0xd46e8dd67c5d32be8058bb8eb970870f07244567",
+			"accessList": [{
+				"address": "0x0000000000000000000000000000000000000001",
+				"storageKeys": ["0x0000000000000000000000000000000000000000000000000000000000000001"]
+			}]
+		},
+		"latest"],
+		"id": 1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"accessList":[{"address":"0x0000000000000000000000000000000000000001","storageKeys":["0x0000000000000000000000000000000000000000000000000000000000000001","0x0000000000000000000000000000000000000000000000000000000000000002"]},{"address":"0x0000000000000000000000000000000000000003","storageKeys":[]}],"gasUsed":"0xff30"},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+fn executed_with_access_list(access_list: Vec<AccessListItem>) -> Executed {
+	Executed {
+		exception: None,
+		gas: U256::zero(),
+		gas_used: U256::from(0xff30),
+		refunded: U256::zero(),
+		cumulative_gas_used: U256::zero(),
+		logs: vec![],
+		contracts_created: vec![],
+		output: vec![],
+		trace: vec![],
+		vm_trace: None,
+		state_diff: None,
+		access_list: Some(access_list),
+	}
+}
+
+const CREATE_ACCESS_LIST_REQUEST: &str = r#"{
+	"jsonrpc": "2.0",
+	"method": "eth_createAccessList",
+	"params": [{
+		"from": "0xb60e8dd61c5d32be8058bb8eb970870f07233155",
+		"to": "0xd46e8dd67c5d32be8058bb8eb970870f07244567"
+	},
+	"latest"],
+	"id": 1
+}"#;
+
+#[test]
+fn rpc_eth_create_access_list_repeats_call_until_nothing_new_is_accessed() {
+	let tester = EthTester::default();
+	// the second slot is only read with the first one warm, and the third with the second one.
+	let slot = |key| AccessListItem { address: H160::from_low_u64_be(1), storage_keys: vec![H256::from_low_u64_be(key)] };
+	tester.client.queue_execution_result(Ok(executed_with_access_list(vec![slot(1)])));
+	tester.client.queue_execution_result(Ok(executed_with_access_list(vec![slot(1), slot(2)])));
+	tester.client.set_execution_result(Ok(executed_with_access_list(vec![slot(1), slot(2), slot(3)])));
+
+	let response = r#"{"jsonrpc":"2.0","result":{"accessList":[{"address":"0x0000000000000000000000000000000000000001","storageKeys":["0x0000000000000000000000000000000000000000000000000000000000000001","0x0000000000000000000000000000000000000000000000000000000000000002","0x0000000000000000000000000000000000000000000000000000000000000003"]}],"gasUsed":"0xff30"},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(CREATE_ACCESS_LIST_REQUEST), Some(response.to_owned()));
+	assert!(tester.client.next_execution_results.read().is_empty());
+}
+
+#[test]
+fn rpc_eth_create_access_list_gives_up_if_accesses_dont_settle() {
+	let tester = EthTester::default();
+	// every call touches a new address, e.g. one derived from the gas left.
+	for i in 1..=11 {
+		tester.client.queue_execution_result(Ok(executed_with_access_list(vec![
+			AccessListItem { address: H160::from_low_u64_be(i), storage_keys: vec![] },
+		])));
+	}
+
+	let addresses = (1..=10)
+		.map(|i| format!(r#"{{"address":"0x{:x}","storageKeys":[]}}"#, H160::from_low_u64_be(i)))
+		.collect::<Vec<_>>()
+		.join(",");
+	let response = format!(
+		r#"{{"jsonrpc":"2.0","result":{{"accessList":[{}],"gasUsed":"0xff30","error":"Access list didn't settle after 10 calls"}},"id":1}}"#,
+		addresses,
+	);
+
+	assert_eq!(tester.io.handle_request_sync(CREATE_ACCESS_LIST_REQUEST), Some(response));
+	assert_eq!(tester.client.next_execution_results.read().len(), 1);
+}
+
 #[test]
 fn rpc_eth_send_raw_transaction_error() {
 	let tester = EthTester::default();
@@ -959,6 +1152,7 @@ fn rpc_eth_transaction_receipt() {
 		}],
 		log_bloom: Bloom::zero(),
 		outcome: TransactionOutcome::StateRoot(H256::zero()),
+		tx_type: Default::default(),
 	};
 
 	let hash = H256::from_str("b903239f8543d04b5dc1ba6579132b143087c68db1b2168786408fcbce568238").unwrap();
@@ -971,7 +1165,7 @@ fn rpc_eth_transaction_receipt() {
 		"params": ["0xb903239f8543d04b5dc1ba6579132b143087c68db1b2168786408fcbce568238"],
 		"id": 1
 	}"#;
-	let response = r#"{"jsonrpc":"2.0","result":{"blockHash":"0xed76641c68a1c641aee09a94b3b471f4dc0316efe5ac19cf488e2674cf8d05b5","blockNumber":"0x4510c","contractAddress":null,"cumulativeGasUsed":"0x20","from":"0xb60e8dd61c5d32be8058bb8eb970870f07233155","gasUsed":"0x10","logs":[{"address":"0x33990122638b9132ca29c723bdf037f1a891a70c","blockHash":"0xed76641c68a1c641aee09a94b3b471f4dc0316efe5ac19cf488e2674cf8d05b5","blockNumber":"0x4510c","data":"0x","logIndex":"0x1","removed":false,"topics":["0xa6697e974e6a320f454390be03f74955e8978f1a6971ea6730542e37b66179bc","0x4861736852656700000000000000000000000000000000000000000000000000"],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","transactionLogIndex":"0x0","type":"mined"}],"logsBloom":"0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000","root":"0x0000000000000000000000000000000000000000000000000000000000000000","to":"0xd46e8dd67c5d32be8058bb8eb970870f07244567","transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0"},"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"blockHash":"0xed76641c68a1c641aee09a94b3b471f4dc0316efe5ac19cf488e2674cf8d05b5","blockNumber":"0x4510c","contractAddress":null,"cumulativeGasUsed":"0x20","from":"0xb60e8dd61c5d32be8058bb8eb970870f07233155","gasUsed":"0x10","logs":[{"address":"0x33990122638b9132ca29c723bdf037f1a891a70c","blockHash":"0xed76641c68a1c641aee09a94b3b471f4dc0316efe5ac19cf488e2674cf8d05b5","blockNumber":"0x4510c","data":"0x","logIndex":"0x1","removed":false,"topics":["0xa6697e974e6a320f454390be03f74955e8978f1a6971ea6730542e37b66179bc","0x4861736852656700000000000000000000000000000000000000000000000000"],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","transactionLogIndex":"0x0","type":"mined"}],"logsBloom":"0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000","root":"0x0000000000000000000000000000000000000000000000000000000000000000","to":"0xd46e8dd67c5d32be8058bb8eb970870f07244567","transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","type":"0x0"},"id":1}"#;
 
 	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
 }
@@ -1004,6 +1198,7 @@ fn rpc_eth_pending_receipt() {
 		logs: Vec::new(),
 		log_bloom: Bloom::zero(),
 		outcome: TransactionOutcome::Unknown,
+		tx_type: Default::default(),
 	};
 	let tester = EthTester::default();
 
@@ -1015,7 +1210,7 @@ fn rpc_eth_pending_receipt() {
 		"params": ["0xb903239f8543d04b5dc1ba6579132b143087c68db1b2168786408fcbce568238"],
 		"id": 1
 	}"#;
-	let response = r#"{"jsonrpc":"2.0","result":{"blockHash":null,"blockNumber":null,"contractAddress":null,"cumulativeGasUsed":"0x20","from":"0xb60e8dd61c5d32be8058bb8eb970870f07233155","gasUsed":"0x10","logs":[],"logsBloom":"0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000","to":"0xd46e8dd67c5d32be8058bb8eb970870f07244567","transactionHash":"0xb903239f8543d04b5dc1ba6579132b143087c68db1b2168786408fcbce568238","transactionIndex":"0x0"},"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"blockHash":null,"blockNumber":null,"contractAddress":null,"cumulativeGasUsed":"0x20","from":"0xb60e8dd61c5d32be8058bb8eb970870f07233155","gasUsed":"0x10","logs":[],"logsBloom":"0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000","to":"0xd46e8dd67c5d32be8058bb8eb970870f07244567","transactionHash":"0xb903239f8543d04b5dc1ba6579132b143087c68db1b2168786408fcbce568238","transactionIndex":"0x0","type":"0x0"},"id":1}"#;
 	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
 }
 
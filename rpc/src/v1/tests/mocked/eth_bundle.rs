@@ -0,0 +1,134 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crypto::publickey::{Generator, Random};
+use ethcore::miner::bundle_hash;
+use ethcore::test_helpers::TestBlockChainClient;
+use ethereum_types::{U256, Address};
+use rlp;
+use rustc_hex::ToHex;
+use types::transaction::{Transaction, Action, SignedTransaction};
+
+use jsonrpc_core::IoHandler;
+use v1::{EthBundle, EthBundleClient};
+use v1::tests::helpers::TestMinerService;
+
+struct EthBundleTester {
+	miner: Arc<TestMinerService>,
+	io: IoHandler,
+}
+
+impl Default for EthBundleTester {
+	fn default() -> Self {
+		let client = Arc::new(TestBlockChainClient::default());
+		let miner = Arc::new(TestMinerService::default());
+		let mut io = IoHandler::new();
+		io.extend_with(EthBundleClient::new(&client, &miner).to_delegate());
+
+		EthBundleTester { miner, io }
+	}
+}
+
+fn transaction(nonce: u64) -> SignedTransaction {
+	let keypair = Random.generate().unwrap();
+	Transaction {
+		nonce: nonce.into(),
+		gas_price: U256::from(2),
+		gas: U256::from(21_000),
+		action: Action::Call(Address::from_str("d46e8dd67c5d32be8058bb8eb970870f07244567").unwrap()),
+		value: U256::from(1),
+		data: vec![],
+	}.sign(keypair.secret(), None)
+}
+
+fn raw(tx: &SignedTransaction) -> String {
+	format!("0x{}", rlp::encode(tx).to_hex())
+}
+
+#[test]
+fn rpc_eth_send_bundle() {
+	let tester = EthBundleTester::default();
+	let (first, second) = (transaction(0), transaction(1));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_sendBundle", "params": [{
+		"txs": [""#.to_owned() + &raw(&first) + r#"", ""# + &raw(&second) + r#""],
+		"blockNumber": "0x1",
+		"maxTimestamp": 100,
+		"revertingTxHashes": [""# + &format!("0x{:x}", second.hash()) + r#""]
+	}], "id": 1}"#;
+	let hash = bundle_hash(&[first.clone(), second.clone()]);
+	let response = r#"{"jsonrpc":"2.0","result":{"bundleHash":""#.to_owned() + &format!("0x{:x}", hash) + r#""},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response));
+
+	let bundles = tester.miner.imported_bundles.lock();
+	assert_eq!(bundles.len(), 1);
+	assert_eq!(bundles[0].transactions, vec![first, second.clone()]);
+	assert_eq!(bundles[0].block_number, 1);
+	assert_eq!(bundles[0].min_timestamp, None);
+	assert_eq!(bundles[0].max_timestamp, Some(100));
+	assert_eq!(bundles[0].reverting_hashes.len(), 1);
+	assert!(bundles[0].reverting_hashes.contains(&second.hash()));
+}
+
+#[test]
+fn rpc_eth_send_bundle_rejects_empty_bundle() {
+	let tester = EthBundleTester::default();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_sendBundle", "params": [{"txs": [], "blockNumber": "0x1"}], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Bundle has no transactions."},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+	assert!(tester.miner.imported_bundles.lock().is_empty());
+}
+
+#[test]
+fn rpc_eth_call_bundle() {
+	let tester = EthBundleTester::default();
+	let tx = transaction(0);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_callBundle", "params": [{
+		"txs": [""#.to_owned() + &raw(&tx) + r#""],
+		"blockNumber": "0x1",
+		"stateBlockNumber": "latest"
+	}], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"bundleHash":""#.to_owned()
+		+ &format!("0x{:x}", bundle_hash(&[tx.clone()]))
+		+ r#"","bundleGasPrice":"0x2","coinbaseDiff":"0xa410","ethSentToCoinbase":"0x0","gasFees":"0xa410","totalGasUsed":"0x5208","stateBlockNumber":"0x0","results":[{"txHash":""#
+		+ &format!("0x{:x}", tx.hash())
+		+ r#"","fromAddress":""# + &format!("0x{:x}", tx.sender())
+		+ r#"","toAddress":"0xd46e8dd67c5d32be8058bb8eb970870f07244567","gasUsed":"0x5208","gasPrice":"0x2","gasFees":"0xa410","coinbaseDiff":"0xa410","ethSentToCoinbase":"0x0","reverted":false}]},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response));
+	assert!(tester.miner.imported_bundles.lock().is_empty());
+}
+
+#[test]
+fn rpc_eth_call_bundle_only_on_top_of_latest_block() {
+	let tester = EthBundleTester::default();
+	let tx = raw(&transaction(0));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_callBundle", "params": [{"txs": [""#.to_owned() + &tx + r#""], "stateBlockNumber": "0x5"}], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"Bundles can only be simulated on top of the latest block."},"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_callBundle", "params": [{"txs": [""#.to_owned() + &tx + r#""], "blockNumber": "0x5"}], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"Bundles can only be simulated in the next block."},"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response.to_owned()));
+}
@@ -19,6 +19,7 @@
 
 mod debug;
 mod eth;
+mod eth_bundle;
 mod eth_pubsub;
 mod manage_network;
 mod net;
@@ -29,6 +30,7 @@ mod parity_set;
 #[cfg(any(test, feature = "accounts"))]
 mod personal;
 mod pubsub;
+mod remote_signer;
 mod rpc;
 #[cfg(any(test, feature = "accounts"))]
 mod secretstore;
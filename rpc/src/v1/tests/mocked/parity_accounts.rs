@@ -418,6 +418,29 @@ fn derive_key_index() {
 	assert_eq!(res, Some(response.into()));
 }
 
+// name: parity_importHdWallet
+// example: {"jsonrpc": "2.0", "method": "parity_importHdWallet", "params": ["abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about", "password1", 1], "id": 1}
+#[test]
+fn import_and_derive_hd_wallet() {
+	let tester = setup();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_importHdWallet", "params": ["abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about", "password1", 1], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":["0x9858effd232b4033e47d90003d41ec34ecaeda94"],"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response.into()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_deriveHdAccounts", "params": ["password1", 1], "id": 2}"#;
+	let derived = tester.accounts.hd_accounts().unwrap().len();
+	assert_eq!(derived, 1);
+	let res = tester.io.handle_request_sync(&request).unwrap();
+	let second = tester.accounts.hd_accounts().unwrap()[1];
+	assert_eq!(res, format!(r#"{{"jsonrpc":"2.0","result":["0x{:x}"],"id":2}}"#, second));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_listHdAccounts", "params": [], "id": 3}"#;
+	let response = format!(r#"{{"jsonrpc":"2.0","result":["0x9858effd232b4033e47d90003d41ec34ecaeda94","0x{:x}"],"id":3}}"#, second);
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response));
+	assert_eq!(tester.accounts.accounts().unwrap().len(), 2);
+}
+
 #[test]
 fn should_export_account() {
 	// given
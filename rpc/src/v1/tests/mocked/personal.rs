@@ -73,7 +73,7 @@ fn setup_with(c: Config) -> PersonalTester {
 	let miner = miner_service();
 	let reservations = Arc::new(Mutex::new(nonce::Reservations::new(runtime.executor())));
 
-	let dispatcher = FullDispatcher::new(client, miner.clone(), reservations, 50);
+	let dispatcher = FullDispatcher::new(client, miner.clone(), reservations, Default::default());
 	let personal = PersonalClient::new(&accounts, dispatcher, false, c.allow_experimental_rpcs);
 
 	let mut io = IoHandler::default();
@@ -365,7 +365,7 @@ fn sign_eip191_with_validator() {
 		validator: address.into(),
 		data: keccak("hello world").as_bytes().to_vec().into()
 	}).unwrap();
-	let result = eip191::hash_message(EIP191Version::PresignedTransaction, with_validator).unwrap();
+	let (result, _) = eip191::hash_message(EIP191Version::PresignedTransaction, with_validator).unwrap();
 	let result = tester.accounts.sign(address, Some("password123".into()), result).unwrap().into_electrum();
 	let expected = r#"{"jsonrpc":"2.0","result":""#.to_owned() +  &format!("0x{}", result.to_hex()) + r#"","id":1}"#;
 	let response = tester.io.handle_request_sync(&request).unwrap();
@@ -0,0 +1,199 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crypto::publickey::{self, KeyPair, Secret};
+use eip_712::{hash_structured_data_with_version, Version};
+use ethcore::test_helpers::TestBlockChainClient;
+use ethereum_types::{Address, U256};
+use jsonrpc_core::IoHandler;
+use parity_runtime::Runtime;
+use parking_lot::Mutex;
+use rustc_hex::{FromHex, ToHex};
+use serde_json;
+use types::transaction::{Action, Transaction};
+
+use v1::{EthSigning, SigningUnsafeClient};
+use v1::helpers::nonce;
+use v1::helpers::dispatch::{self, eth_data_hash, FullDispatcher, RemoteSigner};
+use v1::metadata::Metadata;
+use v1::tests::helpers::{TestMinerService, TestRemoteSigner};
+
+struct RemoteSignerTester {
+	_runtime: Runtime,
+	key: KeyPair,
+	miner: Arc<TestMinerService>,
+	io: IoHandler<Metadata>,
+}
+
+impl RemoteSignerTester {
+	fn new(reject: bool) -> Self {
+		let runtime = Runtime::with_thread_count(1);
+		let key = KeyPair::from_secret(Secret::from([42u8; 32])).unwrap();
+		let client = Arc::new(TestBlockChainClient::default());
+		let miner = Arc::new(TestMinerService::default());
+		let reservations = Arc::new(Mutex::new(nonce::Reservations::new(runtime.executor())));
+
+		let transport = TestRemoteSigner::new(vec![key.clone()], reject);
+		let signer = Arc::new(RemoteSigner::new(Box::new(transport))) as Arc<dyn dispatch::Accounts>;
+		let dispatcher = FullDispatcher::new(client, miner.clone(), reservations, Default::default());
+
+		let mut io = IoHandler::default();
+		io.extend_with(SigningUnsafeClient::new(&signer, dispatcher).to_delegate());
+
+		RemoteSignerTester {
+			_runtime: runtime,
+			key,
+			miner,
+			io,
+		}
+	}
+}
+
+#[test]
+fn should_list_accounts_of_remote_signer() {
+	let key = KeyPair::from_secret(Secret::from([42u8; 32])).unwrap();
+	let signer = RemoteSigner::new(Box::new(TestRemoteSigner::new(vec![key.clone()], false)));
+
+	assert_eq!(signer.accounts().unwrap(), vec![key.address()]);
+	assert_eq!(dispatch::Accounts::default_account(&signer), key.address());
+	assert!(dispatch::Accounts::is_unlocked(&signer, &key.address()));
+	assert!(!dispatch::Accounts::is_unlocked(&signer, &Address::from_low_u64_be(1)));
+}
+
+#[test]
+fn should_sign_message_with_remote_signer() {
+	let tester = RemoteSignerTester::new(false);
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_sign",
+		"params": [
+			""#.to_owned() + &format!("0x{:x}", tester.key.address()) + r#"",
+			"0x0cc175b9c0f1b6a831c399e26977266192eb5ffee6ae2fec3ad71c777531578f"
+		],
+		"id": 1
+	}"#;
+
+	let data = "0cc175b9c0f1b6a831c399e26977266192eb5ffee6ae2fec3ad71c777531578f".from_hex().unwrap();
+	let signature = publickey::sign(tester.key.secret(), &eth_data_hash(data)).unwrap();
+	let response = r#"{"jsonrpc":"2.0","result":"0x"#.to_owned()
+		+ &signature.into_electrum().to_hex()
+		+ r#"","id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response));
+}
+
+#[test]
+fn should_sign_typed_data_with_remote_signer() {
+	let tester = RemoteSignerTester::new(false);
+	let typed_data = r#"{
+		"primaryType": "Mail",
+		"domain": {
+			"name": "Ether Mail",
+			"version": "1",
+			"chainId": 1,
+			"verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+		},
+		"message": {
+			"from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+			"to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+			"contents": "Hello, Bob!"
+		},
+		"types": {
+			"EIP712Domain": [
+				{ "name": "name", "type": "string" },
+				{ "name": "version", "type": "string" },
+				{ "name": "chainId", "type": "uint256" },
+				{ "name": "verifyingContract", "type": "address" }
+			],
+			"Person": [
+				{ "name": "name", "type": "string" },
+				{ "name": "wallet", "type": "address" }
+			],
+			"Mail": [
+				{ "name": "from", "type": "Person" },
+				{ "name": "to", "type": "Person" },
+				{ "name": "contents", "type": "string" }
+			]
+		}
+	}"#;
+	let request = format!(
+		r#"{{"jsonrpc":"2.0","method":"eth_signTypedData_v4","params":["0x{:x}",{}],"id":1}}"#,
+		tester.key.address(),
+		typed_data,
+	);
+
+	let hash = hash_structured_data_with_version(serde_json::from_str(typed_data).unwrap(), Version::V4).unwrap();
+	let signature = publickey::sign(tester.key.secret(), &hash).unwrap();
+	let response = r#"{"jsonrpc":"2.0","result":"0x"#.to_owned()
+		+ &signature.into_electrum().to_hex()
+		+ r#"","id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response));
+}
+
+#[test]
+fn should_send_transaction_signed_by_remote_signer() {
+	let tester = RemoteSignerTester::new(false);
+	let address = tester.key.address();
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_sendTransaction",
+		"params": [{
+			"from": ""#.to_owned() + &format!("0x{:x}", address) + r#"",
+			"to": "0xd46e8dd67c5d32be8058bb8eb970870f07244567",
+			"gas": "0x76c0",
+			"gasPrice": "0x9184e72a000",
+			"value": "0x9184e72a"
+		}],
+		"id": 1
+	}"#;
+
+	let t = Transaction {
+		nonce: U256::zero(),
+		gas_price: U256::from(0x9184e72a000u64),
+		gas: U256::from(0x76c0),
+		action: Action::Call(Address::from_str("d46e8dd67c5d32be8058bb8eb970870f07244567").unwrap()),
+		value: U256::from(0x9184e72au64),
+		data: vec![]
+	};
+	let signature = publickey::sign(tester.key.secret(), &t.hash(None)).unwrap();
+	let t = t.with_signature(signature, None);
+
+	let response = r#"{"jsonrpc":"2.0","result":""#.to_owned() + &format!("0x{:x}", t.hash()) + r#"","id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response));
+	assert_eq!(tester.miner.imported_transactions.lock().len(), 1);
+}
+
+#[test]
+fn should_report_rejection_by_remote_signer() {
+	let tester = RemoteSignerTester::new(true);
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_sign",
+		"params": [
+			""#.to_owned() + &format!("0x{:x}", tester.key.address()) + r#"",
+			"0x0cc175b9"
+		],
+		"id": 1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32040,"message":"Request has been rejected by the external signer.","data":"Request denied"},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response.to_owned()));
+}
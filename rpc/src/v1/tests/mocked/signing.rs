@@ -35,6 +35,7 @@ use accounts::AccountProvider;
 use bytes::ToPretty;
 use ethcore::test_helpers::TestBlockChainClient;
 use ethereum_types::{U256, Address, Signature, H256};
+use hash::keccak;
 use crypto::publickey::{Generator, Random, Secret};
 use parity_runtime::{Runtime, Executor};
 use parking_lot::Mutex;
@@ -61,7 +62,7 @@ impl SigningTester {
 		let reservations = Arc::new(Mutex::new(nonce::Reservations::new(runtime.executor())));
 		let mut io = IoHandler::default();
 
-		let dispatcher = FullDispatcher::new(client.clone(), miner.clone(), reservations, 50);
+		let dispatcher = FullDispatcher::new(client.clone(), miner.clone(), reservations, Default::default());
 
 		let executor = Executor::new_thread_per_future();
 
@@ -396,7 +397,7 @@ fn should_add_sign_transaction_to_the_queue() {
 		&format!("\"raw\":\"0x{}\",", rlp.to_hex()) +
 		&format!("\"s\":\"0x{:x}\",", U256::from(signature.s())) +
 		&format!("\"standardV\":\"0x{:x}\",", U256::from(t.standard_v())) +
-		r#""to":"0xd46e8dd67c5d32be8058bb8eb970870f07244567","transactionIndex":null,"# +
+		r#""to":"0xd46e8dd67c5d32be8058bb8eb970870f07244567","transactionIndex":null,"type":"0x0","# +
 		&format!("\"v\":\"0x{:x}\",", U256::from(t.original_v())) +
 		r#""value":"0x9184e72a""# +
 		r#"}},"id":1}"#;
@@ -553,3 +554,157 @@ fn should_compose_transaction() {
 	let res = tester.io.handle_request(&request).wait().unwrap();
 	assert_eq!(res, Some(response.to_owned()));
 }
+
+const MAIL_TYPED_DATA: &str = r#"{
+	"primaryType": "Mail",
+	"domain": {
+		"name": "Ether Mail",
+		"version": "1",
+		"chainId": 1,
+		"verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+	},
+	"message": {
+		"from": {
+			"name": "Cow",
+			"wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"
+		},
+		"to": {
+			"name": "Bob",
+			"wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"
+		},
+		"contents": "Hello, Bob!"
+	},
+	"types": {
+		"EIP712Domain": [
+			{ "name": "name", "type": "string" },
+			{ "name": "version", "type": "string" },
+			{ "name": "chainId", "type": "uint256" },
+			{ "name": "verifyingContract", "type": "address" }
+		],
+		"Person": [
+			{ "name": "name", "type": "string" },
+			{ "name": "wallet", "type": "address" }
+		],
+		"Mail": [
+			{ "name": "from", "type": "Person" },
+			{ "name": "to", "type": "Person" },
+			{ "name": "contents", "type": "string" }
+		]
+	}
+}"#;
+
+const ARRAYS_TYPED_DATA: &str = r#"{
+	"primaryType": "Mail",
+	"domain": {
+		"name": "Ether Mail",
+		"version": "1",
+		"chainId": 1,
+		"verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+	},
+	"message": {
+		"from": {
+			"name": "Cow",
+			"wallets": [
+				"0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826",
+				"0xDeaDbeefdEAdbeefdEadbEEFdeadbeEFdEaDbeeF"
+			]
+		},
+		"to": [
+			{
+				"name": "Bob",
+				"wallets": [
+					"0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB",
+					"0xB0BdaBea57B0BDABeA57b0bdABEA57b0BDabEa57",
+					"0xB0B0b0b0b0b0B000000000000000000000000000"
+				]
+			}
+		],
+		"contents": "Hello, Bob!"
+	},
+	"types": {
+		"EIP712Domain": [
+			{ "name": "name", "type": "string" },
+			{ "name": "version", "type": "string" },
+			{ "name": "chainId", "type": "uint256" },
+			{ "name": "verifyingContract", "type": "address" }
+		],
+		"Person": [
+			{ "name": "name", "type": "string" },
+			{ "name": "wallets", "type": "address[]" }
+		],
+		"Mail": [
+			{ "name": "from", "type": "Person" },
+			{ "name": "to", "type": "Person[]" },
+			{ "name": "contents", "type": "string" }
+		],
+		"Group": [
+			{ "name": "name", "type": "string" },
+			{ "name": "members", "type": "Person[]" }
+		]
+	}
+}"#;
+
+fn sign_typed_data_request(method: &str, address: Address, typed_data: &str) -> String {
+	format!(
+		r#"{{"jsonrpc":"2.0","method":"{}","params":["0x{:x}",{}],"id":1}}"#,
+		method,
+		address,
+		typed_data,
+	)
+}
+
+#[test]
+fn rpc_eth_sign_typed_data_v3() {
+	let tester = eth_signing(true);
+	let secret: Secret = keccak("cow").into();
+	let address = tester.accounts.insert_account(secret, &"cow".into()).unwrap();
+	tester.accounts.unlock_account_permanently(address, "cow".into()).unwrap();
+
+	let request = sign_typed_data_request("eth_signTypedData_v3", address, MAIL_TYPED_DATA);
+	let response = r#"{"jsonrpc":"2.0","result":"0x4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b915621c","id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response.to_owned()));
+	assert_eq!(tester.signer.requests().len(), 0);
+}
+
+#[test]
+fn rpc_eth_sign_typed_data_v4() {
+	let tester = eth_signing(true);
+	let secret: Secret = keccak("cow").into();
+	let address = tester.accounts.insert_account(secret, &"cow".into()).unwrap();
+	tester.accounts.unlock_account_permanently(address, "cow".into()).unwrap();
+
+	let request = sign_typed_data_request("eth_signTypedData_v4", address, ARRAYS_TYPED_DATA);
+	let response = r#"{"jsonrpc":"2.0","result":"0x65cbd956f2fae28a601bebc9b906cea0191744bd4c4247bcd27cd08f8eb6b71c78efdf7a31dc9abee78f492292721f362d296cf86b4538e07b51303b67f749061b","id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response.to_owned()));
+}
+
+#[test]
+fn should_reject_arrays_in_sign_typed_data_v3() {
+	let tester = eth_signing(true);
+	let secret: Secret = keccak("cow").into();
+	let address = tester.accounts.insert_account(secret, &"cow".into()).unwrap();
+	tester.accounts.unlock_account_permanently(address, "cow".into()).unwrap();
+
+	let request = sign_typed_data_request("eth_signTypedData_v3", address, ARRAYS_TYPED_DATA);
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32058,"message":"Arrays are not supported by eth_signTypedData_v3, found array field 'wallets'"},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response.to_owned()));
+}
+
+#[test]
+fn should_post_sign_typed_data_to_queue() {
+	// given
+	let tester = eth_signing(true);
+	let address = Address::random();
+	assert_eq!(tester.signer.requests().len(), 0);
+
+	// when
+	let request = sign_typed_data_request("parity_postSignTypedData_v4", address, ARRAYS_TYPED_DATA);
+	let response = r#"{"jsonrpc":"2.0","result":"0x1","id":1}"#;
+
+	// then
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response.to_owned()));
+	assert_eq!(tester.signer.requests().len(), 1);
+}
@@ -67,10 +67,10 @@ impl EthTester {
 		let accounts_provider = accounts_provider();
 		let ap = Arc::new(dispatch::Signer::new(accounts_provider.clone())) as _;
 		let miner = miner_service();
-		let gas_price_percentile = options.gas_price_percentile;
+		let gas_price_oracle = options.gas_price_oracle;
 		let reservations = Arc::new(Mutex::new(nonce::Reservations::new(runtime.executor())));
 
-		let dispatcher = FullDispatcher::new(client.clone(), miner.clone(), reservations, gas_price_percentile);
+		let dispatcher = FullDispatcher::new(client.clone(), miner.clone(), reservations, gas_price_oracle);
 		let sign = SigningUnsafeClient::new(&ap, dispatcher).to_delegate();
 		let mut io: IoHandler<Metadata> = IoHandler::default();
 		io.extend_with(sign);
@@ -183,7 +183,7 @@ fn rpc_eth_sign_transaction() {
 		&format!("\"raw\":\"0x{}\",", rlp.to_hex()) +
 		&format!("\"s\":\"0x{:x}\",", U256::from(signature.s())) +
 		&format!("\"standardV\":\"0x{:x}\",", U256::from(t.standard_v())) +
-		r#""to":"0xd46e8dd67c5d32be8058bb8eb970870f07244567","transactionIndex":null,"# +
+		r#""to":"0xd46e8dd67c5d32be8058bb8eb970870f07244567","transactionIndex":null,"type":"0x0","# +
 		&format!("\"v\":\"0x{:x}\",", U256::from(t.original_v())) +
 		r#""value":"0x9184e72a""# +
 		r#"}},"id":1}"#;
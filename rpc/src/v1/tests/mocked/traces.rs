@@ -66,6 +66,9 @@ fn io() -> Tester {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		access_list: None,
+		max_fee_per_gas: None,
+		max_priority_fee_per_gas: None,
 	}));
 	let miner = Arc::new(TestMinerService::default());
 	let traces = TracesClient::new(&client);
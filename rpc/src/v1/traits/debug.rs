@@ -19,7 +19,10 @@
 use jsonrpc_core::Result;
 use jsonrpc_derive::rpc;
 
-use v1::types::RichBlock;
+use ethereum_types::H256;
+use v1::types::{
+	BlockNumber, CallRequest, DebugTrace, DebugTraceOptions, DebugTraceWithTransactionHash, RichBlock,
+};
 
 /// Debug RPC interface.
 #[rpc(server)]
@@ -27,4 +30,20 @@ pub trait Debug {
 	/// Returns recently seen bad blocks.
 	#[rpc(name = "debug_getBadBlocks")]
 	fn bad_blocks(&self) -> Result<Vec<RichBlock>>;
+
+	/// Replays a transaction and returns its trace.
+	#[rpc(name = "debug_traceTransaction")]
+	fn trace_transaction(&self, _: H256, _: Option<DebugTraceOptions>) -> Result<DebugTrace>;
+
+	/// Executes a call on top of the given block and returns its trace.
+	#[rpc(name = "debug_traceCall")]
+	fn trace_call(&self, _: CallRequest, _: Option<BlockNumber>, _: Option<DebugTraceOptions>) -> Result<DebugTrace>;
+
+	/// Replays all transactions of the block with the given number and returns their traces.
+	#[rpc(name = "debug_traceBlockByNumber")]
+	fn trace_block_by_number(&self, _: BlockNumber, _: Option<DebugTraceOptions>) -> Result<Vec<DebugTraceWithTransactionHash>>;
+
+	/// Replays all transactions of the block with the given hash and returns their traces.
+	#[rpc(name = "debug_traceBlockByHash")]
+	fn trace_block_by_hash(&self, _: H256, _: Option<DebugTraceOptions>) -> Result<Vec<DebugTraceWithTransactionHash>>;
 }
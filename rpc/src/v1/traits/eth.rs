@@ -19,7 +19,8 @@ use jsonrpc_core::{Result, BoxFuture};
 use jsonrpc_derive::rpc;
 use ethereum_types::{H64, H160, H256, U64, U256};
 
-use v1::types::{RichBlock, BlockNumber, Bytes, CallRequest, Filter, FilterChanges, Index, EthAccount};
+use v1::types::{RichBlock, BlockNumber, Bytes, CallRequest, Filter, FilterChanges, Index, EthAccount, FeeHistory};
+use v1::types::AccessListWithGasUsed;
 use v1::types::{Log, Receipt, SyncStatus, Transaction, Work};
 
 /// Eth rpc interface.
@@ -58,6 +59,10 @@ pub trait Eth {
 	#[rpc(name = "eth_gasPrice")]
 	fn gas_price(&self) -> BoxFuture<U256>;
 
+	/// Returns base fees, gas used ratios and effective priority fee percentiles of a range of blocks.
+	#[rpc(name = "eth_feeHistory")]
+	fn fee_history(&self, _: U256, _: BlockNumber, _: Option<Vec<f64>>) -> BoxFuture<FeeHistory>;
+
 	/// Returns accounts list.
 	#[rpc(name = "eth_accounts")]
 	fn accounts(&self) -> Result<Vec<H160>>;
@@ -126,6 +131,10 @@ pub trait Eth {
 	#[rpc(name = "eth_estimateGas")]
 	fn estimate_gas(&self, _: CallRequest, _: Option<BlockNumber>) -> BoxFuture<U256>;
 
+	/// Create an access list for the given call, along with the gas the call uses with it.
+	#[rpc(name = "eth_createAccessList")]
+	fn create_access_list(&self, _: CallRequest, _: Option<BlockNumber>) -> BoxFuture<AccessListWithGasUsed>;
+
 	/// Get transaction by its hash.
 	#[rpc(name = "eth_getTransactionByHash")]
 	fn transaction_by_hash(&self, _: H256) -> BoxFuture<Option<Transaction>>;
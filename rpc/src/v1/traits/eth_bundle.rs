@@ -0,0 +1,34 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Eth bundles rpc interface.
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+
+use v1::types::{SendBundleRequest, SendBundleResponse, CallBundleRequest, CallBundleResponse};
+
+/// Eth bundles rpc interface.
+#[rpc(server)]
+pub trait EthBundle {
+	/// Submits a bundle of signed transactions to be included atomically at the top of the block it targets,
+	/// which has to be one of the next 25 blocks.
+	#[rpc(name = "eth_sendBundle")]
+	fn send_bundle(&self, _: SendBundleRequest) -> Result<SendBundleResponse>;
+
+	/// Executes a bundle of signed transactions at the top of the next block, without submitting it.
+	#[rpc(name = "eth_callBundle")]
+	fn call_bundle(&self, _: CallBundleRequest) -> Result<CallBundleResponse>;
+}
@@ -19,6 +19,7 @@
 use jsonrpc_core::BoxFuture;
 use jsonrpc_derive::rpc;
 
+use eip_712::EIP712;
 use ethereum_types::{H160, H256, H520};
 use v1::types::{Bytes, TransactionRequest, RichRawTransaction};
 
@@ -43,4 +44,14 @@ pub trait EthSigning {
 	/// It can be later submitted using `eth_sendRawTransaction/eth_submitTransaction`.
 	#[rpc(meta, name = "eth_signTransaction")]
 	fn sign_transaction(&self, _: Self::Metadata, _: TransactionRequest) -> BoxFuture<RichRawTransaction>;
+
+	/// Signs EIP-712 structured data with the `eth_signTypedData_v3` encoding,
+	/// which doesn't support arrays.
+	#[rpc(meta, name = "eth_signTypedData_v3")]
+	fn sign_typed_data_v3(&self, _: Self::Metadata, _: H160, _: EIP712) -> BoxFuture<H520>;
+
+	/// Signs EIP-712 structured data with the `eth_signTypedData_v4` encoding,
+	/// which supports arrays of structs, nested arrays and recursive types.
+	#[rpc(meta, name = "eth_signTypedData_v4")]
+	fn sign_typed_data_v4(&self, _: Self::Metadata, _: H160, _: EIP712) -> BoxFuture<H520>;
 }
@@ -176,6 +176,10 @@ pub trait Parity {
 	#[rpc(name = "parity_enode")]
 	fn enode(&self) -> Result<String>;
 
+	/// Get the node record of this node, as defined by EIP-778.
+	#[rpc(name = "parity_enr")]
+	fn enr(&self) -> Result<String>;
+
 	/// Returns information on current consensus capability.
 	#[rpc(name = "parity_consensusCapability")]
 	fn consensus_capability(&self) -> Result<ConsensusCapability>;
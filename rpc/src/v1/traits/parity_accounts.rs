@@ -49,6 +49,27 @@ pub trait ParityAccounts {
 	#[rpc(name = "parity_newAccountFromPhrase")]
 	fn new_account_from_phrase(&self, _: String, _: Password) -> Result<H160>;
 
+	/// Creates the HD wallet from a new random BIP-39 mnemonic with given number of words
+	/// (12, 15, 18, 21 or 24) and derives its first account. Returns the mnemonic phrase.
+	/// Second parameter is password for the wallet and its accounts.
+	#[rpc(name = "parity_newHdWallet")]
+	fn new_hd_wallet(&self, _: u64, _: Password) -> Result<String>;
+
+	/// Creates the HD wallet from the given BIP-39 mnemonic phrase and derives given number of
+	/// its accounts along `m/44'/60'/0'/0/i`. Second parameter is password for the wallet and its
+	/// accounts, the optional last one is the BIP-39 passphrase.
+	#[rpc(name = "parity_importHdWallet")]
+	fn import_hd_wallet(&self, _: String, _: Password, _: u32, _: Option<String>) -> Result<Vec<H160>>;
+
+	/// Returns the HD wallet accounts, in the order of their BIP-44 index.
+	#[rpc(name = "parity_listHdAccounts")]
+	fn list_hd_accounts(&self) -> Result<Vec<H160>>;
+
+	/// Derives given number of further HD wallet accounts, at most 1000 at once.
+	/// First parameter is password of the wallet.
+	#[rpc(name = "parity_deriveHdAccounts")]
+	fn derive_hd_accounts(&self, _: Password, _: u32) -> Result<Vec<H160>>;
+
 	/// Creates new account from the given JSON wallet.
 	/// Second parameter is password for the wallet and the new account.
 	#[rpc(name = "parity_newAccountFromWallet")]
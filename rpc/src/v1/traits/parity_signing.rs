@@ -18,6 +18,7 @@
 use jsonrpc_core::{BoxFuture, Result};
 use jsonrpc_derive::rpc;
 
+use eip_712::EIP712;
 use ethereum_types::{H160, U256};
 use v1::types::{Bytes, ConfirmationResponse, TransactionRequest, Either};
 
@@ -42,6 +43,16 @@ pub trait ParitySigning {
 	#[rpc(meta, name = "parity_postTransaction")]
 	fn post_transaction(&self, _: Self::Metadata, _: TransactionRequest) -> BoxFuture<Either<U256, ConfirmationResponse>>;
 
+	/// Posts `eth_signTypedData_v3` request asynchronously.
+	/// Will return a confirmation ID for later use with check_transaction.
+	#[rpc(meta, name = "parity_postSignTypedData_v3")]
+	fn post_sign_typed_data_v3(&self, _: Self::Metadata, _: H160, _: EIP712) -> BoxFuture<Either<U256, ConfirmationResponse>>;
+
+	/// Posts `eth_signTypedData_v4` request asynchronously.
+	/// Will return a confirmation ID for later use with check_transaction.
+	#[rpc(meta, name = "parity_postSignTypedData_v4")]
+	fn post_sign_typed_data_v4(&self, _: Self::Metadata, _: H160, _: EIP712) -> BoxFuture<Either<U256, ConfirmationResponse>>;
+
 	/// Checks the progress of a previously posted request (transaction/sign).
 	/// Should be given a valid send_transaction ID.
 	#[rpc(name = "parity_checkRequest")]
@@ -0,0 +1,97 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+use ethereum_types::{H160, H256, U256};
+use types::transaction;
+
+/// EIP-2930 access list entry
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessListItem {
+	/// Accessed address
+	pub address: H160,
+	/// Accessed storage keys
+	pub storage_keys: Vec<H256>,
+}
+
+/// EIP-2930 access list
+pub type AccessList = Vec<AccessListItem>;
+
+/// Access list of a call along with the gas the call uses with it, as returned by `eth_createAccessList`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessListWithGasUsed {
+	/// Accessed addresses and storage keys
+	pub access_list: AccessList,
+	/// Gas used by the call with the access list applied
+	pub gas_used: U256,
+	/// Error of the call, if it failed
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+}
+
+impl From<transaction::AccessListItem> for AccessListItem {
+	fn from(item: transaction::AccessListItem) -> Self {
+		AccessListItem {
+			address: item.address,
+			storage_keys: item.storage_keys,
+		}
+	}
+}
+
+impl Into<transaction::AccessListItem> for AccessListItem {
+	fn into(self) -> transaction::AccessListItem {
+		transaction::AccessListItem {
+			address: self.address,
+			storage_keys: self.storage_keys,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use ethereum_types::{H160, H256};
+	use super::{AccessListItem, AccessListWithGasUsed};
+
+	#[test]
+	fn access_list_item_deserialize() {
+		let s = r#"{"address":"0x0000000000000000000000000000000000000001","storageKeys":["0x0000000000000000000000000000000000000000000000000000000000000002"]}"#;
+		let deserialized: AccessListItem = serde_json::from_str(s).unwrap();
+
+		assert_eq!(deserialized, AccessListItem {
+			address: H160::from_low_u64_be(1),
+			storage_keys: vec![H256::from_low_u64_be(2)],
+		});
+		assert_eq!(serde_json::to_string(&deserialized).unwrap(), s);
+	}
+
+	#[test]
+	fn access_list_with_gas_used_serialize() {
+		let result = AccessListWithGasUsed {
+			access_list: vec![AccessListItem { address: H160::from_low_u64_be(1), storage_keys: vec![] }],
+			gas_used: 0x5208.into(),
+			error: None,
+		};
+		let serialized = serde_json::to_string(&result).unwrap();
+		assert_eq!(serialized, r#"{"accessList":[{"address":"0x0000000000000000000000000000000000000001","storageKeys":[]}],"gasUsed":"0x5208"}"#);
+
+		let result = AccessListWithGasUsed { error: Some("Reverted".into()), ..result };
+		let serialized = serde_json::to_string(&result).unwrap();
+		assert!(serialized.ends_with(r#""gasUsed":"0x5208","error":"Reverted"}"#));
+	}
+}
@@ -20,6 +20,7 @@ use std::collections::BTreeMap;
 use ethereum_types::{H160, H256, U256, Bloom as H2048};
 use serde::ser::Error;
 use serde::{Serialize, Serializer};
+use types::BlockNumber;
 use types::encoded::Header as EthHeader;
 use v1::types::{Bytes, Transaction};
 
@@ -69,6 +70,9 @@ pub struct Block {
 	pub gas_used: U256,
 	/// Gas Limit
 	pub gas_limit: U256,
+	/// Base fee per gas, since EIP-1559
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub base_fee_per_gas: Option<U256>,
 	/// Extra data
 	pub extra_data: Bytes,
 	/// Logs bloom
@@ -116,6 +120,9 @@ pub struct Header {
 	pub gas_used: U256,
 	/// Gas Limit
 	pub gas_limit: U256,
+	/// Base fee per gas, since EIP-1559
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub base_fee_per_gas: Option<U256>,
 	/// Extra data
 	pub extra_data: Bytes,
 	/// Logs bloom
@@ -130,14 +137,9 @@ pub struct Header {
 	pub size: Option<U256>,
 }
 
-impl From<EthHeader> for Header {
-	fn from(h: EthHeader) -> Self {
-		(&h).into()
-	}
-}
-
-impl<'a> From<&'a EthHeader> for Header {
-	fn from(h: &'a EthHeader) -> Self {
+impl Header {
+	/// Create the RPC representation of a header of a chain activating EIP-1559 at `eip1559_transition`.
+	pub fn new(h: &EthHeader, eip1559_transition: BlockNumber) -> Self {
 		Header {
 			hash: Some(h.hash()),
 			size: Some(h.rlp().as_raw().len().into()),
@@ -151,11 +153,12 @@ impl<'a> From<&'a EthHeader> for Header {
 			number: Some(h.number().into()),
 			gas_used: h.gas_used(),
 			gas_limit: h.gas_limit(),
+			base_fee_per_gas: h.base_fee(eip1559_transition),
 			logs_bloom: h.log_bloom(),
 			timestamp: h.timestamp().into(),
 			difficulty: h.difficulty(),
 			extra_data: h.extra_data().into(),
-			seal_fields: h.view().decode_seal()
+			seal_fields: h.view().decode_seal(eip1559_transition)
 				.expect("Client/Miner returns only valid headers. We only serialize headers from Client/Miner; qed")
 				.into_iter().map(Into::into).collect(),
 		}
@@ -214,7 +217,7 @@ mod tests {
 	fn test_serialize_block_transactions() {
 		let t = BlockTransactions::Full(vec![Transaction::default()]);
 		let serialized = serde_json::to_string(&t).unwrap();
-		assert_eq!(serialized, r#"[{"hash":"0x0000000000000000000000000000000000000000000000000000000000000000","nonce":"0x0","blockHash":null,"blockNumber":null,"transactionIndex":null,"from":"0x0000000000000000000000000000000000000000","to":null,"value":"0x0","gasPrice":"0x0","gas":"0x0","input":"0x","creates":null,"raw":"0x","publicKey":null,"chainId":null,"standardV":"0x0","v":"0x0","r":"0x0","s":"0x0","condition":null}]"#);
+		assert_eq!(serialized, r#"[{"type":"0x0","hash":"0x0000000000000000000000000000000000000000000000000000000000000000","nonce":"0x0","blockHash":null,"blockNumber":null,"transactionIndex":null,"from":"0x0000000000000000000000000000000000000000","to":null,"value":"0x0","gasPrice":"0x0","gas":"0x0","input":"0x","creates":null,"raw":"0x","publicKey":null,"chainId":null,"standardV":"0x0","v":"0x0","r":"0x0","s":"0x0","condition":null}]"#);
 
 		let t = BlockTransactions::Hashes(vec![H256::zero().into()]);
 		let serialized = serde_json::to_string(&t).unwrap();
@@ -235,6 +238,7 @@ mod tests {
 			number: Some(U256::default()),
 			gas_used: U256::default(),
 			gas_limit: U256::default(),
+			base_fee_per_gas: None,
 			extra_data: Bytes::default(),
 			logs_bloom: Some(H2048::default()),
 			timestamp: U256::default(),
@@ -273,6 +277,7 @@ mod tests {
 			number: Some(U256::default()),
 			gas_used: U256::default(),
 			gas_limit: U256::default(),
+			base_fee_per_gas: None,
 			extra_data: Bytes::default(),
 			logs_bloom: Some(H2048::default()),
 			timestamp: U256::default(),
@@ -311,6 +316,7 @@ mod tests {
 			number: Some(U256::default()),
 			gas_used: U256::default(),
 			gas_limit: U256::default(),
+			base_fee_per_gas: None,
 			extra_data: Bytes::default(),
 			logs_bloom: H2048::default(),
 			timestamp: U256::default(),
@@ -0,0 +1,181 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+use ethereum_types::{H160, H256, U64, U256};
+use miner::bundle::{Simulation, SimulatedTransaction};
+use v1::types::{BlockNumber, Bytes};
+
+/// Bundle submitted with `eth_sendBundle`.
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct SendBundleRequest {
+	/// Signed transactions, in the order they are executed in.
+	pub txs: Vec<Bytes>,
+	/// Number of the only block the bundle can be included in.
+	pub block_number: U64,
+	/// Minimal timestamp of the block the bundle can be included in.
+	pub min_timestamp: Option<u64>,
+	/// Maximal timestamp of the block the bundle can be included in.
+	pub max_timestamp: Option<u64>,
+	/// Hashes of the transactions which may revert without invalidating the bundle.
+	#[serde(default)]
+	pub reverting_tx_hashes: Vec<H256>,
+}
+
+/// Response to `eth_sendBundle`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendBundleResponse {
+	/// Hash of the submitted bundle.
+	pub bundle_hash: H256,
+}
+
+/// Bundle simulated with `eth_callBundle`.
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct CallBundleRequest {
+	/// Signed transactions, in the order they are executed in.
+	pub txs: Vec<Bytes>,
+	/// Number of the block to simulate the bundle in, the next block by default.
+	pub block_number: Option<U64>,
+	/// Block which state the bundle is executed on top of, `latest` by default.
+	pub state_block_number: Option<BlockNumber>,
+	/// Timestamp of the block to simulate the bundle in.
+	pub timestamp: Option<u64>,
+}
+
+/// Response to `eth_callBundle`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallBundleResponse {
+	/// Hash of the simulated bundle.
+	pub bundle_hash: H256,
+	/// Payment to the block author per unit of gas.
+	pub bundle_gas_price: U256,
+	/// Total increase of the block author's balance.
+	pub coinbase_diff: U256,
+	/// Part of `coinbase_diff` paid directly rather than through the fees.
+	pub eth_sent_to_coinbase: U256,
+	/// Total fees paid to the block author for the gas used.
+	pub gas_fees: U256,
+	/// Total gas used by the bundle.
+	pub total_gas_used: U256,
+	/// Block which state the bundle was executed on top of.
+	pub state_block_number: U64,
+	/// Outcomes of the transactions.
+	pub results: Vec<CallBundleResult>,
+}
+
+impl CallBundleResponse {
+	/// Converts a simulation executed on top of given block.
+	pub fn new(simulation: Simulation, state_block_number: u64) -> Self {
+		let coinbase_diff = simulation.coinbase_diff();
+		let gas_fees = simulation.gas_fees();
+
+		CallBundleResponse {
+			bundle_hash: simulation.bundle_hash,
+			bundle_gas_price: simulation.score(),
+			coinbase_diff,
+			eth_sent_to_coinbase: coinbase_diff.saturating_sub(gas_fees),
+			gas_fees,
+			total_gas_used: simulation.gas_used(),
+			state_block_number: state_block_number.into(),
+			results: simulation.transactions.into_iter().map(Into::into).collect(),
+		}
+	}
+}
+
+/// Outcome of a single transaction of a bundle simulated with `eth_callBundle`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallBundleResult {
+	/// Transaction hash.
+	pub tx_hash: H256,
+	/// Transaction sender.
+	pub from_address: H160,
+	/// Transaction recipient, `None` for contract creation.
+	pub to_address: Option<H160>,
+	/// Gas used by the transaction.
+	pub gas_used: U256,
+	/// Gas price paid by the transaction.
+	pub gas_price: U256,
+	/// Fees paid to the block author for the gas used.
+	pub gas_fees: U256,
+	/// Increase of the block author's balance.
+	pub coinbase_diff: U256,
+	/// Part of `coinbase_diff` paid directly rather than through the fees.
+	pub eth_sent_to_coinbase: U256,
+	/// Whether the transaction has reverted.
+	pub reverted: bool,
+}
+
+impl From<SimulatedTransaction> for CallBundleResult {
+	fn from(tx: SimulatedTransaction) -> Self {
+		CallBundleResult {
+			tx_hash: tx.hash,
+			from_address: tx.sender,
+			to_address: tx.to,
+			gas_used: tx.gas_used,
+			gas_price: tx.gas_price,
+			gas_fees: tx.gas_fees,
+			coinbase_diff: tx.coinbase_diff,
+			eth_sent_to_coinbase: tx.coinbase_diff.saturating_sub(tx.gas_fees),
+			reverted: tx.reverted,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use ethereum_types::{H256, U64};
+	use v1::types::BlockNumber;
+	use super::{SendBundleRequest, CallBundleRequest};
+
+	#[test]
+	fn send_bundle_request_deserialization() {
+		let s = r#"{
+			"txs": ["0x01", "0x0203"],
+			"blockNumber": "0xb",
+			"minTimestamp": 100,
+			"revertingTxHashes": ["0x0000000000000000000000000000000000000000000000000000000000000005"]
+		}"#;
+		let deserialized: SendBundleRequest = serde_json::from_str(s).unwrap();
+
+		assert_eq!(deserialized, SendBundleRequest {
+			txs: vec![vec![1].into(), vec![2, 3].into()],
+			block_number: U64::from(11),
+			min_timestamp: Some(100),
+			max_timestamp: None,
+			reverting_tx_hashes: vec![H256::from_low_u64_be(5)],
+		});
+	}
+
+	#[test]
+	fn call_bundle_request_deserialization() {
+		let s = r#"{"txs": ["0x01"], "stateBlockNumber": "latest", "timestamp": 100}"#;
+		let deserialized: CallBundleRequest = serde_json::from_str(s).unwrap();
+
+		assert_eq!(deserialized, CallBundleRequest {
+			txs: vec![vec![1].into()],
+			block_number: None,
+			state_block_number: Some(BlockNumber::Latest),
+			timestamp: Some(100),
+		});
+	}
+}
@@ -14,9 +14,11 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
-use ethereum_types::{H160, U256};
+use ethereum_types::{H160, U64, U256};
+use serde::{Deserialize, Deserializer};
+use serde::de::Error;
 use v1::helpers::CallRequest as Request;
-use v1::types::Bytes;
+use v1::types::{AccessList, Bytes};
 
 /// Call request
 #[derive(Debug, Default, PartialEq, Deserialize)]
@@ -37,6 +39,15 @@ pub struct CallRequest {
 	pub data: Option<Bytes>,
 	/// Nonce
 	pub nonce: Option<U256>,
+	/// Transaction type (EIP-2718)
+	#[serde(rename = "type", default, deserialize_with = "transaction_type")]
+	pub transaction_type: Option<U64>,
+	/// Access list (EIP-2930)
+	pub access_list: Option<AccessList>,
+	/// Max fee per gas (EIP-1559)
+	pub max_fee_per_gas: Option<U256>,
+	/// Max priority fee per gas (EIP-1559)
+	pub max_priority_fee_per_gas: Option<U256>,
 }
 
 impl Into<Request> for CallRequest {
@@ -49,17 +60,54 @@ impl Into<Request> for CallRequest {
 			value: self.value.map(Into::into),
 			data: self.data.map(Into::into),
 			nonce: self.nonce.map(Into::into),
+			access_list: access_list(self.transaction_type, self.access_list),
+			max_fee_per_gas: self.max_fee_per_gas.map(Into::into),
+			max_priority_fee_per_gas: max_priority_fee_per_gas(self.transaction_type, self.max_priority_fee_per_gas),
 		}
 	}
 }
 
+/// Deserializes the transaction type of a request, rejecting the types a request can't express.
+pub(crate) fn transaction_type<'de, D>(deserializer: D) -> Result<Option<U64>, D::Error>
+	where D: Deserializer<'de>
+{
+	match Option::<U64>::deserialize(deserializer)? {
+		Some(ty) if ty > U64::from(2) => Err(D::Error::custom(format!("Unsupported transaction type: {}", ty))),
+		ty => Ok(ty),
+	}
+}
+
+/// Resolves the access list of a request, an explicit EIP-2930 `type` implying an empty list.
+pub(crate) fn access_list(
+	transaction_type: Option<U64>,
+	access_list: Option<AccessList>,
+) -> Option<::types::transaction::AccessList> {
+	match (transaction_type, access_list) {
+		(_, Some(list)) => Some(list.into_iter().map(Into::into).collect()),
+		(Some(ty), None) if ty == U64::from(1) => Some(Vec::new()),
+		_ => None,
+	}
+}
+
+/// Resolves the priority fee of a request, an explicit EIP-1559 `type` implying no priority fee.
+pub(crate) fn max_priority_fee_per_gas(
+	transaction_type: Option<U64>,
+	max_priority_fee_per_gas: Option<U256>,
+) -> Option<U256> {
+	match (transaction_type, max_priority_fee_per_gas) {
+		(_, Some(fee)) => Some(fee),
+		(Some(ty), None) if ty == U64::from(2) => Some(U256::zero()),
+		_ => None,
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use std::str::FromStr;
 	use rustc_hex::FromHex;
 	use serde_json;
 	use ethereum_types::{U256, H160};
-	use super::CallRequest;
+	use super::{CallRequest, Request};
 
 	#[test]
 	fn call_request_deserialize() {
@@ -82,6 +130,10 @@ mod tests {
 			value: Some(U256::from(3)),
 			data: Some(vec![0x12, 0x34, 0x56].into()),
 			nonce: Some(U256::from(4)),
+			transaction_type: None,
+			access_list: None,
+			max_fee_per_gas: None,
+			max_priority_fee_per_gas: None,
 		});
 	}
 
@@ -104,10 +156,38 @@ mod tests {
 			gas: Some(U256::from_str("76c0").unwrap()),
 			value: Some(U256::from_str("9184e72a").unwrap()),
 			data: Some("d46e8dd67c5d32be8d46e8dd67c5d32be8058bb8eb970870f072445675058bb8eb970870f072445675".from_hex().unwrap().into()),
-			nonce: None
+			nonce: None,
+			transaction_type: None,
+			access_list: None,
+			max_fee_per_gas: None,
+			max_priority_fee_per_gas: None,
 		});
 	}
 
+	#[test]
+	fn call_request_deserialize_transaction_type() {
+		let s = r#"{"type":"0x1"}"#;
+		let deserialized: CallRequest = serde_json::from_str(s).unwrap();
+		assert_eq!(deserialized.transaction_type, Some(1.into()));
+
+		let s = r#"{"type":"0x3"}"#;
+		assert!(serde_json::from_str::<CallRequest>(s).is_err());
+	}
+
+	#[test]
+	fn call_request_deserialize_dynamic_fee() {
+		let s = r#"{"type":"0x2","maxFeePerGas":"0x2","maxPriorityFeePerGas":"0x1"}"#;
+		let deserialized: CallRequest = serde_json::from_str(s).unwrap();
+		assert_eq!(deserialized.transaction_type, Some(2.into()));
+		assert_eq!(deserialized.max_fee_per_gas, Some(2.into()));
+		assert_eq!(deserialized.max_priority_fee_per_gas, Some(1.into()));
+
+		let s = r#"{"type":"0x2"}"#;
+		let request: Request = serde_json::from_str::<CallRequest>(s).unwrap().into();
+		assert_eq!(request.max_fee_per_gas, None);
+		assert_eq!(request.max_priority_fee_per_gas, Some(0.into()));
+	}
+
 	#[test]
 	fn call_request_deserialize_empty() {
 		let s = r#"{"from":"0x0000000000000000000000000000000000000001"}"#;
@@ -121,6 +201,10 @@ mod tests {
 			value: None,
 			data: None,
 			nonce: None,
+			transaction_type: None,
+			access_list: None,
+			max_fee_per_gas: None,
+			max_priority_fee_per_gas: None,
 		});
 	}
 }
@@ -22,7 +22,7 @@ use ansi_term::Colour;
 use bytes::ToPretty;
 
 use ethereum_types::{H160, H256, H520, U256};
-use v1::types::{TransactionRequest, RichRawTransaction, Bytes, TransactionCondition, Origin};
+use v1::types::{TransactionRequest, RichRawTransaction, Bytes, TransactionCondition, Origin, EIP191Message};
 use v1::helpers;
 use ethkey::Password;
 
@@ -84,6 +84,9 @@ pub struct EIP191SignRequest {
 	pub address: H160,
 	/// Hash to sign
 	pub data: H256,
+	/// Message the hash is of, for external signers
+	#[serde(skip)]
+	pub message: Option<EIP191Message>,
 }
 
 impl From<(H160, H256)> for EIP191SignRequest {
@@ -91,6 +94,17 @@ impl From<(H160, H256)> for EIP191SignRequest {
 		EIP191SignRequest {
 			address: tuple.0,
 			data: tuple.1,
+			message: None,
+		}
+	}
+}
+
+impl From<(H160, H256, EIP191Message)> for EIP191SignRequest {
+	fn from(tuple: (H160, H256, EIP191Message)) -> Self {
+		EIP191SignRequest {
+			address: tuple.0,
+			data: tuple.1,
+			message: Some(tuple.2),
 		}
 	}
 }
@@ -217,9 +231,10 @@ impl From<helpers::ConfirmationPayload> for ConfirmationPayload {
 				address,
 				data: data.into(),
 			}),
-			helpers::ConfirmationPayload::SignMessage(address, data) => ConfirmationPayload::EIP191SignMessage(EIP191SignRequest {
+			helpers::ConfirmationPayload::SignMessage(address, data, message) => ConfirmationPayload::EIP191SignMessage(EIP191SignRequest {
 				address,
 				data,
+				message,
 			}),
 			helpers::ConfirmationPayload::Decrypt(address, msg) => ConfirmationPayload::Decrypt(DecryptRequest {
 				address,
@@ -332,6 +347,9 @@ mod tests {
 				data: vec![1, 2, 3],
 				nonce: Some(1.into()),
 				condition: None,
+				access_list: None,
+				max_fee_per_gas: None,
+				max_priority_fee_per_gas: None,
 			}),
 			origin: Origin::Signer {
 				session: H256::from_low_u64_be(5),
@@ -361,6 +379,9 @@ mod tests {
 				data: vec![1, 2, 3],
 				nonce: Some(1.into()),
 				condition: None,
+				access_list: None,
+				max_fee_per_gas: None,
+				max_priority_fee_per_gas: None,
 			}),
 			origin: Origin::Unknown,
 		};
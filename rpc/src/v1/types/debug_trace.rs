@@ -0,0 +1,374 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Geth-compatible `debug_trace*` types.
+
+use std::collections::BTreeMap;
+
+use ethereum_types::{H160, H256, U256};
+use machine::executed::Executed;
+use rustc_hex::ToHex;
+use trace::{FlatTrace, StructLog, StructLogOptions, trace};
+
+use v1::types::Bytes;
+
+/// Options of the `debug_trace*` calls.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugTraceOptions {
+	/// Don't capture the stack.
+	#[serde(default)]
+	pub disable_stack: bool,
+	/// Don't capture the memory.
+	#[serde(default)]
+	pub disable_memory: bool,
+	/// Don't capture the storage.
+	#[serde(default)]
+	pub disable_storage: bool,
+	/// Built-in tracer to use instead of struct logs (`callTracer` or `prestateTracer`).
+	pub tracer: Option<String>,
+}
+
+impl<'a> From<&'a DebugTraceOptions> for StructLogOptions {
+	fn from(options: &'a DebugTraceOptions) -> Self {
+		StructLogOptions {
+			disable_stack: options.disable_stack,
+			disable_memory: options.disable_memory,
+			disable_storage: options.disable_storage,
+		}
+	}
+}
+
+/// Result of a `debug_trace*` call, depending on the selected tracer.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum DebugTrace {
+	/// Struct logs of every executed instruction.
+	StructLogs(ExecutionResult),
+	/// Call tree built by `callTracer`.
+	Call(Option<CallFrame>),
+	/// Accounts touched by the transaction, as they were before it, built by `prestateTracer`.
+	Prestate(BTreeMap<H160, PrestateAccount>),
+}
+
+/// Trace of a single transaction within a block.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugTraceWithTransactionHash {
+	/// The transaction hash.
+	pub tx_hash: H256,
+	/// The trace.
+	pub result: DebugTrace,
+}
+
+/// Outcome of an execution together with its struct logs.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionResult {
+	/// Gas used.
+	pub gas: u64,
+	/// Whether the execution failed.
+	pub failed: bool,
+	/// Returned data, hex encoded without prefix.
+	pub return_value: String,
+	/// Struct logs.
+	pub struct_logs: Vec<StructLogItem>,
+}
+
+impl ExecutionResult {
+	/// Builds the result from a replayed execution, using its VM trace.
+	pub fn new(executed: &Executed, options: StructLogOptions) -> Self {
+		ExecutionResult {
+			gas: saturating_u64(executed.gas_used),
+			failed: executed.exception.is_some(),
+			return_value: executed.output.to_hex(),
+			struct_logs: executed.vm_trace.as_ref()
+				.map(|trace| ::trace::struct_logs(trace, options).into_iter().map(Into::into).collect())
+				.unwrap_or_default(),
+		}
+	}
+}
+
+/// State of the machine right before an instruction is executed.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLogItem {
+	/// Program counter.
+	pub pc: usize,
+	/// Mnemonic of the instruction.
+	pub op: String,
+	/// Gas left.
+	pub gas: u64,
+	/// Gas cost of the instruction.
+	pub gas_cost: u64,
+	/// Call depth.
+	pub depth: usize,
+	/// Stack, bottom first.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub stack: Option<Vec<U256>>,
+	/// Memory, as 32-byte words.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub memory: Option<Vec<String>>,
+	/// Storage of the executing contract.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub storage: Option<BTreeMap<String, String>>,
+}
+
+impl From<StructLog> for StructLogItem {
+	fn from(log: StructLog) -> Self {
+		StructLogItem {
+			pc: log.pc,
+			op: log.op,
+			gas: saturating_u64(log.gas),
+			gas_cost: saturating_u64(log.gas_cost),
+			depth: log.depth,
+			stack: log.stack,
+			memory: log.memory.map(|memory| memory.chunks(32).map(|word| word.to_hex()).collect()),
+			storage: log.storage.map(|storage| storage.into_iter()
+				.map(|(key, value)| (format!("{:x}", key), format!("{:x}", value)))
+				.collect()),
+		}
+	}
+}
+
+/// A call frame built by `callTracer`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFrame {
+	/// Kind of the frame (`CALL`, `CREATE`, `SELFDESTRUCT`, ...).
+	#[serde(rename = "type")]
+	pub call_type: String,
+	/// Sender.
+	pub from: H160,
+	/// Recipient or created contract.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub to: Option<H160>,
+	/// Transferred value.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub value: Option<U256>,
+	/// Gas provided.
+	pub gas: U256,
+	/// Gas used.
+	pub gas_used: U256,
+	/// Input data or init code.
+	pub input: Bytes,
+	/// Output data or deployed code.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub output: Option<Bytes>,
+	/// Error, if the frame failed.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+	/// Nested frames.
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub calls: Vec<CallFrame>,
+}
+
+impl CallFrame {
+	/// Rebuilds the call tree from flat traces ordered depth first.
+	pub fn from_flat_traces(traces: Vec<FlatTrace>) -> Option<Self> {
+		let mut traces = traces.into_iter();
+		traces.next().map(|root| Self::build(root, &mut traces))
+	}
+
+	fn build<I: Iterator<Item = FlatTrace>>(trace: FlatTrace, rest: &mut I) -> Self {
+		let subtraces = trace.subtraces;
+		let mut frame = CallFrame::from(trace);
+		for _ in 0..subtraces {
+			match rest.next() {
+				Some(sub) => frame.calls.push(Self::build(sub, rest)),
+				None => break,
+			}
+		}
+		frame
+	}
+}
+
+impl From<FlatTrace> for CallFrame {
+	fn from(t: FlatTrace) -> Self {
+		let mut frame = match t.action {
+			trace::Action::Call(call) => CallFrame {
+				call_type: match call.call_type.0 {
+					Some(trace::CallType::CallCode) => "CALLCODE",
+					Some(trace::CallType::DelegateCall) => "DELEGATECALL",
+					Some(trace::CallType::StaticCall) => "STATICCALL",
+					Some(trace::CallType::Call) | None => "CALL",
+				}.into(),
+				from: call.from,
+				to: Some(call.to),
+				value: Some(call.value),
+				gas: call.gas,
+				gas_used: U256::zero(),
+				input: call.input.into(),
+				output: None,
+				error: None,
+				calls: Vec::new(),
+			},
+			trace::Action::Create(create) => CallFrame {
+				call_type: match create.creation_method {
+					Some(trace::CreationMethod::Create2) => "CREATE2",
+					_ => "CREATE",
+				}.into(),
+				from: create.from,
+				to: None,
+				value: Some(create.value),
+				gas: create.gas,
+				gas_used: U256::zero(),
+				input: create.init.into(),
+				output: None,
+				error: None,
+				calls: Vec::new(),
+			},
+			trace::Action::Suicide(suicide) => CallFrame {
+				call_type: "SELFDESTRUCT".into(),
+				from: suicide.address,
+				to: Some(suicide.refund_address),
+				value: Some(suicide.balance),
+				gas: U256::zero(),
+				gas_used: U256::zero(),
+				input: Bytes::default(),
+				output: None,
+				error: None,
+				calls: Vec::new(),
+			},
+			trace::Action::Reward(reward) => CallFrame {
+				call_type: "REWARD".into(),
+				from: H160::zero(),
+				to: Some(reward.author),
+				value: Some(reward.value),
+				gas: U256::zero(),
+				gas_used: U256::zero(),
+				input: Bytes::default(),
+				output: None,
+				error: None,
+				calls: Vec::new(),
+			},
+		};
+
+		match t.result {
+			trace::Res::Call(result) => {
+				frame.gas_used = result.gas_used;
+				frame.output = Some(result.output.into());
+			},
+			trace::Res::Create(result) => {
+				frame.gas_used = result.gas_used;
+				frame.to = Some(result.address);
+				frame.output = Some(result.code.into());
+			},
+			trace::Res::FailedCall(error) | trace::Res::FailedCreate(error) => {
+				frame.gas_used = frame.gas;
+				frame.error = Some(error.to_string());
+			},
+			trace::Res::None => {},
+		}
+
+		frame
+	}
+}
+
+/// State of an account before a transaction, as reported by `prestateTracer`.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrestateAccount {
+	/// Balance.
+	pub balance: U256,
+	/// Nonce.
+	pub nonce: u64,
+	/// Code, if any.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub code: Option<Bytes>,
+	/// Storage slots modified by the transaction.
+	#[serde(skip_serializing_if = "BTreeMap::is_empty")]
+	pub storage: BTreeMap<H256, H256>,
+}
+
+fn saturating_u64(value: U256) -> u64 {
+	if value > U256::from(u64::max_value()) {
+		u64::max_value()
+	} else {
+		value.low_u64()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json;
+	use trace::{TraceError, trace::{Action, Call, CallResult, CallType, Res}};
+
+	fn call(from: u64, to: u64, subtraces: usize, trace_address: Vec<usize>, result: Res) -> FlatTrace {
+		FlatTrace {
+			action: Action::Call(Call {
+				from: H160::from_low_u64_be(from),
+				to: H160::from_low_u64_be(to),
+				value: 0.into(),
+				gas: 100.into(),
+				input: vec![],
+				call_type: Some(CallType::Call).into(),
+			}),
+			result,
+			subtraces,
+			trace_address,
+		}
+	}
+
+	#[test]
+	fn should_deserialize_options() {
+		let s = r#"{"disableStack":true,"tracer":"callTracer"}"#;
+		let deserialized: DebugTraceOptions = serde_json::from_str(s).unwrap();
+		assert_eq!(deserialized, DebugTraceOptions {
+			disable_stack: true,
+			tracer: Some("callTracer".into()),
+			..Default::default()
+		});
+	}
+
+	#[test]
+	fn should_rebuild_call_tree() {
+		let ok = || Res::Call(CallResult { gas_used: 10.into(), output: vec![] });
+		let traces = vec![
+			call(1, 2, 2, vec![], ok()),
+			call(2, 3, 1, vec![0], ok()),
+			call(3, 4, 0, vec![0, 0], Res::FailedCall(TraceError::Reverted)),
+			call(2, 5, 0, vec![1], ok()),
+		];
+
+		let frame = CallFrame::from_flat_traces(traces).unwrap();
+		assert_eq!(frame.calls.len(), 2);
+		assert_eq!(frame.calls[0].calls.len(), 1);
+		assert_eq!(frame.calls[0].calls[0].error, Some("Reverted".into()));
+		assert_eq!(frame.calls[1].to, Some(H160::from_low_u64_be(5)));
+
+		let serialized = serde_json::to_string(&frame.calls[1]).unwrap();
+		assert_eq!(serialized, r#"{"type":"CALL","from":"0x0000000000000000000000000000000000000002","to":"0x0000000000000000000000000000000000000005","value":"0x0","gas":"0x64","gasUsed":"0xa","input":"0x","output":"0x"}"#);
+	}
+
+	#[test]
+	fn should_serialize_struct_log() {
+		let log = StructLog {
+			pc: 2,
+			op: "MSTORE".into(),
+			gas: 994.into(),
+			gas_cost: 6.into(),
+			depth: 1,
+			stack: Some(vec![0x2a.into(), 0.into()]),
+			memory: Some(vec![0; 32]),
+			storage: None,
+		};
+
+		let serialized = serde_json::to_string(&StructLogItem::from(log)).unwrap();
+		assert_eq!(serialized, r#"{"pc":2,"op":"MSTORE","gas":994,"gasCost":6,"depth":1,"stack":["0x2a","0x0"],"memory":["0000000000000000000000000000000000000000000000000000000000000000"]}"#);
+	}
+}
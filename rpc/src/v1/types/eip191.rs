@@ -16,6 +16,7 @@
 
 //! EIP-191 specific types
 
+use bytes::Bytes as RawBytes;
 use ethereum_types::H160;
 use serde::{Deserialize, Deserializer};
 use serde::de;
@@ -42,6 +43,17 @@ pub struct PresignedTransaction {
 	pub data: Bytes
 }
 
+/// EIP-191 message to sign, kept along with its hash for signers which hash it themselves
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum EIP191Message {
+	/// structured data (0x01), as EIP-712 JSON
+	StructuredData(String),
+	/// personal message (0x45)
+	PersonalMessage(RawBytes),
+	/// data with intended validator (0x00)
+	PresignedTransaction(H160, RawBytes),
+}
+
 impl<'de> Deserialize<'de> for EIP191Version {
 	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
 		where
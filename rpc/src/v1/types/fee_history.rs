@@ -0,0 +1,55 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+use ethereum_types::U256;
+
+/// Fee history of a range of blocks, as returned by `eth_feeHistory`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeHistory {
+	/// Number of the oldest block in the range.
+	pub oldest_block: U256,
+	/// Base fee per gas of every block in the range, followed by the one of the next block.
+	pub base_fee_per_gas: Vec<U256>,
+	/// Ratio of gas used to gas limit of every block in the range.
+	pub gas_used_ratio: Vec<f64>,
+	/// Effective priority fees paid at the requested percentiles of gas used, for every block in the range.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub reward: Option<Vec<Vec<U256>>>,
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use super::FeeHistory;
+
+	#[test]
+	fn fee_history_serialization() {
+		let history = FeeHistory {
+			oldest_block: 10.into(),
+			base_fee_per_gas: vec![7.into(), 8.into()],
+			gas_used_ratio: vec![0.5],
+			reward: Some(vec![vec![1.into(), 2.into()]]),
+		};
+
+		let serialized = serde_json::to_string(&history).unwrap();
+		assert_eq!(serialized, r#"{"oldestBlock":"0xa","baseFeePerGas":["0x7","0x8"],"gasUsedRatio":[0.5],"reward":[["0x1","0x2"]]}"#);
+
+		let history = FeeHistory { reward: None, ..history };
+		let serialized = serde_json::to_string(&history).unwrap();
+		assert_eq!(serialized, r#"{"oldestBlock":"0xa","baseFeePerGas":["0x7","0x8"],"gasUsedRatio":[0.5]}"#);
+	}
+}
@@ -19,14 +19,18 @@
 #[cfg(test)]
 mod eth_types;
 
+mod access_list;
 mod account_info;
 mod block;
 mod block_number;
+mod bundle;
 mod bytes;
 mod call_request;
 mod confirmations;
 mod consensus_status;
+mod debug_trace;
 mod derivation;
+mod fee_history;
 mod filter;
 mod histogram;
 mod index;
@@ -49,18 +53,25 @@ mod eip191;
 
 pub mod pubsub;
 
-pub use self::eip191::{EIP191Version, PresignedTransaction};
+pub use self::eip191::{EIP191Message, EIP191Version, PresignedTransaction};
+pub use self::access_list::{AccessList, AccessListItem, AccessListWithGasUsed};
 pub use self::account_info::{AccountInfo, ExtAccountInfo, EthAccount, StorageProof, RecoveredAccount};
 pub use self::bytes::Bytes;
 pub use self::block::{RichBlock, Block, BlockTransactions, Header, RichHeader, Rich};
 pub use self::block_number::{BlockNumber, LightBlockNumber, block_number_to_id};
+pub use self::bundle::{SendBundleRequest, SendBundleResponse, CallBundleRequest, CallBundleResponse, CallBundleResult};
 pub use self::call_request::CallRequest;
 pub use self::confirmations::{
 	ConfirmationPayload, ConfirmationRequest, ConfirmationResponse, ConfirmationResponseWithToken,
 	TransactionModification, EIP191SignRequest, EthSignRequest, DecryptRequest, Either
 };
 pub use self::consensus_status::*;
+pub use self::debug_trace::{
+	DebugTraceOptions, DebugTrace, DebugTraceWithTransactionHash, ExecutionResult, StructLogItem,
+	CallFrame, PrestateAccount,
+};
 pub use self::derivation::{DeriveHash, DeriveHierarchical, Derive};
+pub use self::fee_history::FeeHistory;
 pub use self::filter::{Filter, FilterChanges};
 pub use self::histogram::Histogram;
 pub use self::index::Index;
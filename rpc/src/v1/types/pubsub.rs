@@ -169,6 +169,7 @@ mod tests {
 				number: Some(Default::default()),
 				gas_used: Default::default(),
 				gas_limit: Default::default(),
+				base_fee_per_gas: None,
 				extra_data: Default::default(),
 				logs_bloom: Default::default(),
 				timestamp: Default::default(),
@@ -22,13 +22,16 @@ use machine::executive::{contract_address};
 use vm::CreateContractAddress;
 use ethereum_types::{H160, H256, H512, U64, U256};
 use miner;
-use types::transaction::{LocalizedTransaction, Action, PendingTransaction, SignedTransaction};
-use v1::types::{Bytes, TransactionCondition};
+use types::transaction::{LocalizedTransaction, Action, PendingTransaction, SignedTransaction, TypedTxId};
+use v1::types::{AccessList, Bytes, TransactionCondition};
 
 /// Transaction
 #[derive(Debug, Default, Clone, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Transaction {
+	/// Transaction type (EIP-2718)
+	#[serde(rename = "type")]
+	pub transaction_type: U64,
 	/// Hash
 	pub hash: H256,
 	/// Nonce
@@ -69,6 +72,15 @@ pub struct Transaction {
 	pub s: U256,
 	/// Transaction activates at specified block.
 	pub condition: Option<TransactionCondition>,
+	/// Access list (EIP-2930)
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub access_list: Option<AccessList>,
+	/// Max fee per gas (EIP-1559), equal to `gas_price`
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub max_fee_per_gas: Option<U256>,
+	/// Max priority fee per gas (EIP-1559)
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub max_priority_fee_per_gas: Option<U256>,
 }
 
 /// Local Transaction Status
@@ -178,6 +190,7 @@ impl Transaction {
 		let signature = t.signature();
 		let scheme = CreateContractAddress::FromSenderAndNonce;
 		Transaction {
+			transaction_type: t.tx_type().to_u64().into(),
 			hash: t.hash(),
 			nonce: t.nonce,
 			block_hash: Some(t.block_hash),
@@ -196,7 +209,7 @@ impl Transaction {
 				Action::Create => Some(contract_address(scheme, &t.sender(), &t.nonce, &t.data).0),
 				Action::Call(_) => None,
 			},
-			raw: ::rlp::encode(&t.signed).into(),
+			raw: t.encode_raw().into(),
 			public_key: t.recover_public().ok().map(Into::into),
 			chain_id: t.chain_id().map(U64::from),
 			standard_v: t.standard_v().into(),
@@ -204,6 +217,15 @@ impl Transaction {
 			r: signature.r().into(),
 			s: signature.s().into(),
 			condition: None,
+			access_list: t.access_list().map(|list| list.iter().cloned().map(Into::into).collect()),
+			max_fee_per_gas: match t.tx_type() {
+				TypedTxId::EIP1559 => Some(t.gas_price),
+				_ => None,
+			},
+			max_priority_fee_per_gas: match t.tx_type() {
+				TypedTxId::EIP1559 => Some(t.max_priority_fee_per_gas()),
+				_ => None,
+			},
 		}
 	}
 
@@ -212,6 +234,7 @@ impl Transaction {
 		let signature = t.signature();
 		let scheme = CreateContractAddress::FromSenderAndNonce;
 		Transaction {
+			transaction_type: t.tx_type().to_u64().into(),
 			hash: t.hash(),
 			nonce: t.nonce,
 			block_hash: None,
@@ -230,7 +253,7 @@ impl Transaction {
 				Action::Create => Some(contract_address(scheme, &t.sender(), &t.nonce, &t.data).0),
 				Action::Call(_) => None,
 			},
-			raw: ::rlp::encode(&t).into(),
+			raw: t.encode_raw().into(),
 			public_key: t.public_key().map(Into::into),
 			chain_id: t.chain_id().map(U64::from),
 			standard_v: t.standard_v().into(),
@@ -238,6 +261,15 @@ impl Transaction {
 			r: signature.r().into(),
 			s: signature.s().into(),
 			condition: None,
+			access_list: t.access_list().map(|list| list.iter().cloned().map(Into::into).collect()),
+			max_fee_per_gas: match t.tx_type() {
+				TypedTxId::EIP1559 => Some(t.gas_price),
+				_ => None,
+			},
+			max_priority_fee_per_gas: match t.tx_type() {
+				TypedTxId::EIP1559 => Some(t.max_priority_fee_per_gas()),
+				_ => None,
+			},
 		}
 	}
 
@@ -282,7 +314,7 @@ mod tests {
 	fn test_transaction_serialize() {
 		let t = Transaction::default();
 		let serialized = serde_json::to_string(&t).unwrap();
-		assert_eq!(serialized, r#"{"hash":"0x0000000000000000000000000000000000000000000000000000000000000000","nonce":"0x0","blockHash":null,"blockNumber":null,"transactionIndex":null,"from":"0x0000000000000000000000000000000000000000","to":null,"value":"0x0","gasPrice":"0x0","gas":"0x0","input":"0x","creates":null,"raw":"0x","publicKey":null,"chainId":null,"standardV":"0x0","v":"0x0","r":"0x0","s":"0x0","condition":null}"#);
+		assert_eq!(serialized, r#"{"type":"0x0","hash":"0x0000000000000000000000000000000000000000000000000000000000000000","nonce":"0x0","blockHash":null,"blockNumber":null,"transactionIndex":null,"from":"0x0000000000000000000000000000000000000000","to":null,"value":"0x0","gasPrice":"0x0","gas":"0x0","input":"0x","creates":null,"raw":"0x","publicKey":null,"chainId":null,"standardV":"0x0","v":"0x0","r":"0x0","s":"0x0","condition":null}"#);
 	}
 
 	#[test]
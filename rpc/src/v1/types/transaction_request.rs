@@ -16,8 +16,9 @@
 
 //! `TransactionRequest` type
 
-use ethereum_types::{H160, U256};
-use v1::types::{Bytes, TransactionCondition};
+use ethereum_types::{H160, U64, U256};
+use v1::types::{AccessList, AccessListItem, Bytes, TransactionCondition};
+use v1::types::call_request::{access_list, max_priority_fee_per_gas, transaction_type};
 use v1::helpers;
 use ansi_term::Colour;
 
@@ -44,6 +45,19 @@ pub struct TransactionRequest {
 	pub nonce: Option<U256>,
 	/// Delay until this block condition.
 	pub condition: Option<TransactionCondition>,
+	/// Transaction type (EIP-2718)
+	#[serde(rename = "type", default, deserialize_with = "transaction_type")]
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub transaction_type: Option<U64>,
+	/// Access list (EIP-2930)
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub access_list: Option<AccessList>,
+	/// Max fee per gas (EIP-1559)
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub max_fee_per_gas: Option<U256>,
+	/// Max priority fee per gas (EIP-1559)
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub max_priority_fee_per_gas: Option<U256>,
 }
 
 pub fn format_ether(i: U256) -> String {
@@ -99,6 +113,10 @@ impl From<helpers::TransactionRequest> for TransactionRequest {
 			data: r.data.map(Into::into),
 			nonce: r.nonce.map(Into::into),
 			condition: r.condition.map(Into::into),
+			transaction_type: request_type(&r.access_list, &r.max_fee_per_gas, &r.max_priority_fee_per_gas),
+			access_list: r.access_list.map(|list| list.into_iter().map(AccessListItem::from).collect()),
+			max_fee_per_gas: r.max_fee_per_gas,
+			max_priority_fee_per_gas: r.max_priority_fee_per_gas,
 		}
 	}
 }
@@ -114,6 +132,10 @@ impl From<helpers::FilledTransactionRequest> for TransactionRequest {
 			data: Some(r.data.into()),
 			nonce: r.nonce,
 			condition: r.condition,
+			transaction_type: request_type(&r.access_list, &r.max_fee_per_gas, &r.max_priority_fee_per_gas),
+			access_list: r.access_list.map(|list| list.into_iter().map(AccessListItem::from).collect()),
+			max_fee_per_gas: r.max_fee_per_gas,
+			max_priority_fee_per_gas: r.max_priority_fee_per_gas,
 		}
 	}
 }
@@ -129,10 +151,26 @@ impl Into<helpers::TransactionRequest> for TransactionRequest {
 			data: self.data.map(Into::into),
 			nonce: self.nonce.map(Into::into),
 			condition: self.condition.map(Into::into),
+			access_list: access_list(self.transaction_type, self.access_list),
+			max_fee_per_gas: self.max_fee_per_gas,
+			max_priority_fee_per_gas: max_priority_fee_per_gas(self.transaction_type, self.max_priority_fee_per_gas),
 		}
 	}
 }
 
+/// The transaction type implied by the access list and fees of a request, `None` for legacy transactions.
+fn request_type(
+	access_list: &Option<::types::transaction::AccessList>,
+	max_fee_per_gas: &Option<U256>,
+	max_priority_fee_per_gas: &Option<U256>,
+) -> Option<U64> {
+	if max_fee_per_gas.is_some() || max_priority_fee_per_gas.is_some() {
+		Some(U64::from(2))
+	} else {
+		access_list.as_ref().map(|_| U64::from(1))
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use std::str::FromStr;
@@ -165,6 +203,10 @@ mod tests {
 			data: Some(vec![0x12, 0x34, 0x56].into()),
 			nonce: Some(U256::from(4)),
 			condition: Some(TransactionCondition::Number(0x13)),
+			transaction_type: None,
+			access_list: None,
+			max_fee_per_gas: None,
+			max_priority_fee_per_gas: None,
 		});
 	}
 
@@ -189,6 +231,10 @@ mod tests {
 			data: Some("d46e8dd67c5d32be8d46e8dd67c5d32be8058bb8eb970870f072445675058bb8eb970870f072445675".from_hex().unwrap().into()),
 			nonce: None,
 			condition: None,
+			transaction_type: None,
+			access_list: None,
+			max_fee_per_gas: None,
+			max_priority_fee_per_gas: None,
 		});
 	}
 
@@ -206,6 +252,10 @@ mod tests {
 			data: None,
 			nonce: None,
 			condition: None,
+			transaction_type: None,
+			access_list: None,
+			max_fee_per_gas: None,
+			max_priority_fee_per_gas: None,
 		});
 	}
 
@@ -230,9 +280,32 @@ mod tests {
 			data: Some(vec![0x85, 0x95, 0xba, 0xb1].into()),
 			nonce: None,
 			condition: None,
+			transaction_type: None,
+			access_list: None,
+			max_fee_per_gas: None,
+			max_priority_fee_per_gas: None,
 		});
 	}
 
+	#[test]
+	fn transaction_request_deserialize_access_list() {
+		let s = r#"{
+			"from":"0x0000000000000000000000000000000000000001",
+			"type":"0x1",
+			"accessList":[{"address":"0x0000000000000000000000000000000000000002","storageKeys":[]}]
+		}"#;
+		let deserialized: TransactionRequest = serde_json::from_str(s).unwrap();
+
+		assert_eq!(deserialized.transaction_type, Some(1.into()));
+		assert_eq!(deserialized.access_list, Some(vec![AccessListItem {
+			address: H160::from_low_u64_be(2),
+			storage_keys: vec![],
+		}]));
+
+		let request: helpers::TransactionRequest = deserialized.into();
+		assert_eq!(request.access_list.map(|list| list.len()), Some(1));
+	}
+
 	#[test]
 	fn transaction_request_deserialize_error() {
 		let s = r#"{
@@ -15,8 +15,10 @@
 // along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
 //! EIP712 structs
+use serde::{Deserialize, Deserializer, de::Error as _};
 use serde_json::{Value};
 use std::collections::HashMap;
+use std::str::FromStr;
 use ethereum_types::{U256, H256, Address};
 use regex::Regex;
 use validator::{Validate, ValidationError, ValidationErrors};
@@ -38,7 +40,7 @@ pub(crate) struct EIP712Domain {
 	pub(crate) name: Option<String>,
 	#[serde(skip_serializing_if="Option::is_none")]
 	pub(crate) version: Option<String>,
-	#[serde(skip_serializing_if="Option::is_none")]
+	#[serde(default, skip_serializing_if="Option::is_none", deserialize_with = "deserialize_chain_id")]
 	pub(crate) chain_id: Option<U256>,
 	#[serde(skip_serializing_if="Option::is_none")]
 	pub(crate) verifying_contract: Option<Address>,
@@ -46,6 +48,25 @@ pub(crate) struct EIP712Domain {
 	pub(crate) salt: Option<H256>,
 }
 
+/// wallets send the `chainId` as a json number, a decimal string or a 0x-prefixed hex string
+fn deserialize_chain_id<'de, D>(deserializer: D) -> Result<Option<U256>, D::Error>
+	where D: Deserializer<'de>
+{
+	match Option::<Value>::deserialize(deserializer)? {
+		None | Some(Value::Null) => Ok(None),
+		Some(Value::Number(number)) => number.as_u64()
+			.map(|id| Some(U256::from(id)))
+			.ok_or_else(|| D::Error::custom(format!("invalid chainId {}", number))),
+		Some(Value::String(ref string)) if string.starts_with("0x") => U256::from_str(&string[2..])
+			.map(Some)
+			.map_err(|_| D::Error::custom(format!("invalid chainId {}", string))),
+		Some(Value::String(string)) => U256::from_dec_str(&string)
+			.map(Some)
+			.map_err(|_| D::Error::custom(format!("invalid chainId {}", string))),
+		Some(other) => Err(D::Error::custom(format!("invalid chainId {}", other))),
+	}
+}
+
 fn validate_domain(domain: &EIP712Domain) -> Result<(), ValidationError> {
 	match (domain.name.as_ref(), domain.version.as_ref(), domain.chain_id, domain.verifying_contract, domain.salt) {
 		(None, None, None, None, None) => Err(ValidationError::new("EIP712Domain must include at least one field")),
@@ -56,7 +77,7 @@ fn validate_domain(domain: &EIP712Domain) -> Result<(), ValidationError> {
 /// EIP-712 struct
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct EIP712 {
 	pub(crate) types: MessageTypes,
 	pub(crate) primary_type: String,
@@ -30,6 +30,16 @@ use rustc_hex::FromHex;
 use validator::Validate;
 use std::collections::HashSet;
 
+/// Version of the `eth_signTypedData` encoding rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Version {
+	/// `eth_signTypedData_v3`: fields without a value are skipped and arrays are rejected.
+	V3,
+	/// `eth_signTypedData_v4`: arrays of any type and depth are supported, unset
+	/// struct references encode as zero and every other field must have a value.
+	V4,
+}
+
 fn check_hex(string: &str) -> Result<()> {
 	if string.len() >= 2 && &string[..2] == "0x" {
 		return Ok(())
@@ -63,7 +73,7 @@ fn build_dependencies<'a>(message_type: &'a str, message_types: &'a MessageTypes
 					&field.type_
 				};
 				// seen this type before? or not a custom type skip
-				if !deps.contains(field_type) || message_types.contains_key(field_type) {
+				if message_types.contains_key(field_type) && !deps.contains(field_type) {
 					types.insert(field_type);
 				}
 			}
@@ -104,11 +114,46 @@ fn type_hash(message_type: &str, typed_data: &MessageTypes) -> Result<H256> {
 	Ok(keccak(encode_type(message_type, typed_data)?))
 }
 
+/// parses an int/uint value given either as a json number, a decimal string or a 0x-prefixed hex string.
+/// negative values of signed types are returned in two's complement.
+fn parse_int(value: &Value, signed: bool, field_name: Option<&str>) -> Result<U256> {
+	let (negative, uint) = match value {
+		Value::Number(number) => match (number.as_u64(), number.as_i64()) {
+			(Some(uint), _) => (false, U256::from(uint)),
+			(None, Some(int)) => (true, U256::from(int.wrapping_neg() as u64)),
+			_ => return Err(serde_error("int/uint", field_name))?,
+		},
+		Value::String(string) => {
+			let (negative, string) = if string.starts_with('-') {
+				(true, &string[1..])
+			} else {
+				(false, &string[..])
+			};
+			let uint = if string.starts_with("0x") {
+				U256::from_str(&string[2..])
+					.map_err(|err| ErrorKind::HexParseError(format!("{}", err)))?
+			} else {
+				U256::from_dec_str(string)
+					.map_err(|_| serde_error("int/uint", field_name))?
+			};
+			(negative, uint)
+		},
+		_ => return Err(serde_error("int/uint", field_name))?,
+	};
+
+	match (negative, signed) {
+		(false, _) => Ok(uint),
+		(true, true) => Ok(U256::zero().overflowing_sub(uint).0),
+		(true, false) => Err(serde_error("uint", field_name).into()),
+	}
+}
+
 fn encode_data(
 	message_type: &Type,
 	message_types: &MessageTypes,
 	value: &Value,
-	field_name: Option<&str>
+	field_name: Option<&str>,
+	version: Version,
 ) -> Result<Vec<u8>>
 {
 	let encoded = match message_type {
@@ -134,7 +179,8 @@ fn encode_data(
 					&*inner,
 					&message_types,
 					item,
-					field_name
+					field_name,
+					version,
 				)?;
 				items.append(&mut encoded);
 			}
@@ -149,11 +195,28 @@ fn encode_data(
 			for field in message_types.get(ident).expect("Already checked in match guard; qed") {
 				let value = &value[&field.name];
 				let type_ = parse_type(&*field.type_)?;
+
+				match (version, &type_) {
+					(Version::V3, _) if value.is_null() => continue,
+					(Version::V3, Type::Array { .. }) => {
+						return Err(ErrorKind::UnsupportedArray(field.name.clone()))?
+					}
+					(Version::V4, Type::Custom(ident)) if value.is_null() && message_types.contains_key(ident) => {
+						tokens.extend_from_slice(&[0u8; 32]);
+						continue;
+					}
+					(Version::V4, _) if value.is_null() => {
+						return Err(ErrorKind::MissingValue(field.name.clone(), field.type_.clone()))?
+					}
+					_ => {}
+				}
+
 				let mut encoded = encode_data(
 					&type_,
 					&message_types,
 					&value,
-					Some(&*field.name)
+					Some(&*field.name),
+					version,
 				)?;
 				tokens.append(&mut encoded);
 			}
@@ -210,13 +273,7 @@ fn encode_data(
 		}
 
 		Type::Uint | Type::Int => {
-			let string = value.as_str()
-				.ok_or(serde_error("int/uint", field_name))?;
-
-			check_hex(&string)?;
-
-			let uint = U256::from_str(&string[2..])
-				.map_err(|err| ErrorKind::HexParseError(format!("{}", err)))?;
+			let uint = parse_int(value, *message_type == Type::Int, field_name)?;
 
 			let token = if *message_type == Type::Uint {
 				EthAbiToken::Uint(uint)
@@ -239,6 +296,11 @@ fn encode_data(
 
 /// encodes and hashes the given EIP712 struct
 pub fn hash_structured_data(typed_data: EIP712) -> Result<H256> {
+	hash_structured_data_with_version(typed_data, Version::V4)
+}
+
+/// encodes and hashes the given EIP712 struct following the rules of the given `eth_signTypedData` version
+pub fn hash_structured_data_with_version(typed_data: EIP712, version: Version) -> Result<H256> {
 	// validate input
 	typed_data.validate()?;
 	// EIP-191 compliant
@@ -249,13 +311,15 @@ pub fn hash_structured_data(typed_data: EIP712) -> Result<H256> {
 			&Type::Custom("EIP712Domain".into()),
 			&typed_data.types,
 			&domain,
-			None
+			None,
+			version,
 		)?,
 		encode_data(
 			&Type::Custom(typed_data.primary_type),
 			&typed_data.types,
 			&typed_data.message,
-			None
+			None,
+			version,
 		)?
 	);
 	let concat = [&prefix[..], &domain_hash[..], &data_hash[..]].concat();
@@ -367,6 +431,38 @@ mod tests {
 		)
 	}
 
+	#[test]
+	fn test_encode_recursive_type() {
+		let string = r#"{
+			"Person": [
+				{ "name": "name", "type": "string" },
+				{ "name": "mother", "type": "Person" },
+				{ "name": "father", "type": "Person" }
+			]
+		}"#;
+
+		let value = from_str::<MessageTypes>(string).expect("alas error!");
+		assert_eq!(
+			"Person(string name,Person mother,Person father)",
+			encode_type("Person", &value).expect("alas error!")
+		);
+		assert_eq!(
+			(type_hash("Person", &value).expect("alas error!").0).to_hex::<String>(),
+			"7c5c8e90cb92c8da53b893b24962513be98afcf1b57b00327ae4cc14e3a64116"
+		);
+	}
+
+	#[test]
+	fn test_parse_int() {
+		assert_eq!(parse_int(&Value::from(42), false, None).unwrap(), U256::from(42));
+		assert_eq!(parse_int(&Value::from("42"), false, None).unwrap(), U256::from(42));
+		assert_eq!(parse_int(&Value::from("0x2a"), false, None).unwrap(), U256::from(42));
+		assert_eq!(parse_int(&Value::from(-1), true, None).unwrap(), U256::max_value());
+		assert_eq!(parse_int(&Value::from("-0x1"), true, None).unwrap(), U256::max_value());
+		assert!(parse_int(&Value::from(-1), false, None).is_err());
+		assert!(parse_int(&Value::from("forty two"), false, None).is_err());
+	}
+
 	#[test]
 	fn test_encode_type_hash() {
 		let string = r#"{
@@ -59,7 +59,13 @@ pub enum ErrorKind {
 	UnequalArrayItems(u64, String, u64),
 	/// Typed array length doesn't fit into a u64
 	#[fail(display = "Attempted to declare fixed size with length {}", _0)]
-	InvalidArraySize(String)
+	InvalidArraySize(String),
+	/// a field without a value was encountered while encoding with the v4 rules
+	#[fail(display = "Missing value for field '{}' of type '{}'", _0, _1)]
+	MissingValue(String, String),
+	/// an array field was encountered while encoding with the v3 rules
+	#[fail(display = "Arrays are not supported by eth_signTypedData_v3, found array field '{}'", _0)]
+	UnsupportedArray(String),
 }
 
 pub(crate) fn serde_error(expected: &str, field: Option<&str>) -> ErrorKind {
@@ -169,7 +169,7 @@ mod parser;
 mod encode;
 
 /// the EIP-712 encoding function
-pub use crate::encode::hash_structured_data;
+pub use crate::encode::{hash_structured_data, hash_structured_data_with_version, Version};
 /// encoding Error types
 pub use crate::error::{ErrorKind, Error};
 /// EIP712 struct
@@ -0,0 +1,244 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Conformance tests against the `signTypedData_v3` and `signTypedData_v4`
+//! vectors used by MetaMask's `eth-sig-util`.
+
+use eip_712::{hash_structured_data_with_version, ErrorKind, Version, EIP712};
+use serde_json::from_str;
+
+const MAIL: &str = r#"{
+	"types": {
+		"EIP712Domain": [
+			{ "name": "name", "type": "string" },
+			{ "name": "version", "type": "string" },
+			{ "name": "chainId", "type": "uint256" },
+			{ "name": "verifyingContract", "type": "address" }
+		],
+		"Person": [
+			{ "name": "name", "type": "string" },
+			{ "name": "wallet", "type": "address" }
+		],
+		"Mail": [
+			{ "name": "from", "type": "Person" },
+			{ "name": "to", "type": "Person" },
+			{ "name": "contents", "type": "string" }
+		]
+	},
+	"primaryType": "Mail",
+	"domain": {
+		"name": "Ether Mail",
+		"version": "1",
+		"chainId": 1,
+		"verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+	},
+	"message": {
+		"from": {
+			"name": "Cow",
+			"wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"
+		},
+		"to": {
+			"name": "Bob",
+			"wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"
+		},
+		"contents": "Hello, Bob!"
+	}
+}"#;
+
+const ARRAYS_OF_STRUCTS: &str = r#"{
+	"types": {
+		"EIP712Domain": [
+			{ "name": "name", "type": "string" },
+			{ "name": "version", "type": "string" },
+			{ "name": "chainId", "type": "uint256" },
+			{ "name": "verifyingContract", "type": "address" }
+		],
+		"Person": [
+			{ "name": "name", "type": "string" },
+			{ "name": "wallets", "type": "address[]" }
+		],
+		"Mail": [
+			{ "name": "from", "type": "Person" },
+			{ "name": "to", "type": "Person[]" },
+			{ "name": "contents", "type": "string" }
+		],
+		"Group": [
+			{ "name": "name", "type": "string" },
+			{ "name": "members", "type": "Person[]" }
+		]
+	},
+	"primaryType": "Mail",
+	"domain": {
+		"name": "Ether Mail",
+		"version": "1",
+		"chainId": 1,
+		"verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+	},
+	"message": {
+		"from": {
+			"name": "Cow",
+			"wallets": [
+				"0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826",
+				"0xDeaDbeefdEAdbeefdEadbEEFdeadbeEFdEaDbeeF"
+			]
+		},
+		"to": [
+			{
+				"name": "Bob",
+				"wallets": [
+					"0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB",
+					"0xB0BdaBea57B0BDABeA57b0bdABEA57b0BDabEa57",
+					"0xB0B0b0b0b0b0B000000000000000000000000000"
+				]
+			}
+		],
+		"contents": "Hello, Bob!"
+	}
+}"#;
+
+const RECURSIVE: &str = r#"{
+	"types": {
+		"EIP712Domain": [
+			{ "name": "name", "type": "string" },
+			{ "name": "version", "type": "string" },
+			{ "name": "chainId", "type": "uint256" }
+		],
+		"Person": [
+			{ "name": "name", "type": "string" },
+			{ "name": "mother", "type": "Person" },
+			{ "name": "father", "type": "Person" }
+		]
+	},
+	"primaryType": "Person",
+	"domain": {
+		"name": "Family Tree",
+		"version": "1",
+		"chainId": 1
+	},
+	"message": {
+		"name": "Jon",
+		"mother": {
+			"name": "Lyanna",
+			"father": { "name": "Rickard" }
+		},
+		"father": {
+			"name": "Rhaegar",
+			"father": { "name": "Aeris II" }
+		}
+	}
+}"#;
+
+const NESTED_ARRAYS: &str = r#"{
+	"types": {
+		"EIP712Domain": [
+			{ "name": "name", "type": "string" },
+			{ "name": "chainId", "type": "uint256" }
+		],
+		"Matrix": [
+			{ "name": "label", "type": "string" },
+			{ "name": "rows", "type": "int16[][]" },
+			{ "name": "payload", "type": "bytes" },
+			{ "name": "tag", "type": "bytes4" },
+			{ "name": "flag", "type": "bool" },
+			{ "name": "amount", "type": "uint256" }
+		]
+	},
+	"primaryType": "Matrix",
+	"domain": {
+		"name": "Grid",
+		"chainId": "42"
+	},
+	"message": {
+		"label": "identity",
+		"rows": [[1, 0], [0, -1]],
+		"payload": "0xdeadbeef",
+		"tag": "0xcafebabe",
+		"flag": true,
+		"amount": "1000000000000000000"
+	}
+}"#;
+
+fn hash(json: &str, version: Version) -> Result<String, ErrorKind> {
+	let typed_data = from_str::<EIP712>(json).expect("test vectors are valid json; qed");
+	hash_structured_data_with_version(typed_data, version)
+		.map(|hash| format!("{:x}", hash))
+		.map_err(|err| err.kind())
+}
+
+#[test]
+fn mail_v3() {
+	assert_eq!(
+		hash(MAIL, Version::V3).unwrap(),
+		"be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2",
+	);
+}
+
+#[test]
+fn mail_v4() {
+	assert_eq!(
+		hash(MAIL, Version::V4).unwrap(),
+		"be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2",
+	);
+}
+
+#[test]
+fn arrays_of_structs_v3() {
+	assert_eq!(
+		hash(ARRAYS_OF_STRUCTS, Version::V3).unwrap_err(),
+		ErrorKind::UnsupportedArray("wallets".into()),
+	);
+}
+
+#[test]
+fn arrays_of_structs_v4() {
+	assert_eq!(
+		hash(ARRAYS_OF_STRUCTS, Version::V4).unwrap(),
+		"a85c2e2b118698e88db68a8105b794a8cc7cec074e89ef991cb4f5f533819cc2",
+	);
+}
+
+#[test]
+fn recursive_types_v3() {
+	assert_eq!(
+		hash(RECURSIVE, Version::V3).unwrap(),
+		"2f0431b704ff1ec3c111838b6ea285676b6b3856cc00841c8a70068cae3cc006",
+	);
+}
+
+#[test]
+fn recursive_types_v4() {
+	assert_eq!(
+		hash(RECURSIVE, Version::V4).unwrap(),
+		"af5b8b575e8fea609c1af5b0672651f7a36e6af0bd814f3d05006241763195b5",
+	);
+}
+
+#[test]
+fn nested_arrays_v4() {
+	assert_eq!(
+		hash(NESTED_ARRAYS, Version::V4).unwrap(),
+		"d5ec4c432e32ac692ea9b46f64c35bc2b4d532da159f736f2fa2a215d22290e2",
+	);
+}
+
+#[test]
+fn missing_atomic_value_v4() {
+	let json = MAIL.replace(r#""contents": "Hello, Bob!""#, r#""contents": null"#);
+	assert_eq!(
+		hash(&json, Version::V4).unwrap_err(),
+		ErrorKind::MissingValue("contents".into(), "string".into()),
+	);
+}
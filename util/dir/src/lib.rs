@@ -224,6 +224,11 @@ impl DatabaseDirectories {
 	pub fn network_path(&self) -> PathBuf {
 		self.spec_root_path().join("network")
 	}
+
+	/// Get the path for the transaction queue journal.
+	pub fn tx_queue_journal_path(&self) -> PathBuf {
+		self.db_root_path().join("transactions.rlp")
+	}
 }
 
 /// Default data path
@@ -0,0 +1,165 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::Table as TableId;
+use crate::table::Table;
+
+/// Freezer database files, one table for every kind of item.
+struct Tables {
+	headers: Table,
+	bodies: Table,
+	receipts: Table,
+}
+
+impl Tables {
+	/// Opens the tables, dropping the items which are not in all of them.
+	fn open(path: &Path, max_segment_size: u64) -> io::Result<Tables> {
+		let mut tables = Tables {
+			headers: Table::open(path, "headers", max_segment_size)?,
+			bodies: Table::open(path, "bodies", max_segment_size)?,
+			receipts: Table::open(path, "receipts", max_segment_size)?,
+		};
+		let len = tables.len();
+		tables.truncate(len)?;
+		Ok(tables)
+	}
+
+	fn table(&mut self, table: TableId) -> &mut Table {
+		match table {
+			TableId::Headers => &mut self.headers,
+			TableId::Bodies => &mut self.bodies,
+			TableId::Receipts => &mut self.receipts,
+		}
+	}
+
+	fn len(&self) -> u64 {
+		self.headers.len().min(self.bodies.len()).min(self.receipts.len())
+	}
+
+	fn truncate(&mut self, len: u64) -> io::Result<()> {
+		self.headers.truncate(len)?;
+		self.bodies.truncate(len)?;
+		self.receipts.truncate(len)
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		self.headers.flush()?;
+		self.bodies.flush()?;
+		self.receipts.flush()
+	}
+}
+
+/// Freezer database.
+pub struct Database {
+	/// Database files
+	tables: Option<Tables>,
+	/// Database path
+	path: PathBuf,
+	/// Size at which the tables start a new segment file
+	max_segment_size: u64,
+}
+
+impl Database {
+	/// Opens freezer database.
+	pub fn open<P>(path: P, max_segment_size: u64) -> io::Result<Database> where P: AsRef<Path> {
+		let path = path.as_ref().to_path_buf();
+		let database = Database {
+			tables: Some(Tables::open(&path, max_segment_size)?),
+			path,
+			max_segment_size,
+		};
+
+		Ok(database)
+	}
+
+	/// Close the inner-files
+	pub fn close(&mut self) -> io::Result<()> {
+		self.tables = None;
+		Ok(())
+	}
+
+	/// Reopens the database at the same location.
+	pub fn reopen(&mut self) -> io::Result<()> {
+		self.tables = Some(Tables::open(&self.path, self.max_segment_size)?);
+		Ok(())
+	}
+
+	fn tables(&mut self) -> io::Result<&mut Tables> {
+		self.tables.as_mut().ok_or_else(|| io::Error::new(io::ErrorKind::Other, "Freezer database is closed"))
+	}
+
+	/// Number of blocks in the database.
+	pub fn len(&self) -> u64 {
+		self.tables.as_ref().map_or(0, Tables::len)
+	}
+
+	/// Appends the data of the next block.
+	pub fn append(&mut self, header: &[u8], body: &[u8], receipts: &[u8]) -> io::Result<()> {
+		let tables = self.tables()?;
+		tables.headers.append(header)?;
+		tables.bodies.append(body)?;
+		tables.receipts.append(receipts)
+	}
+
+	/// Reads an item of the block with the given number.
+	pub fn get(&mut self, table: TableId, number: u64) -> io::Result<Option<Vec<u8>>> {
+		let tables = self.tables()?;
+		if number >= tables.len() {
+			return Ok(None);
+		}
+		tables.table(table).get(number)
+	}
+
+	/// Drops the blocks from the given number on.
+	pub fn truncate(&mut self, len: u64) -> io::Result<()> {
+		self.tables()?.truncate(len)
+	}
+
+	/// Writes the appended blocks to the disk.
+	pub fn flush(&mut self) -> io::Result<()> {
+		self.tables()?.flush()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use tempdir::TempDir;
+	use crate::Table;
+	use super::Database;
+
+	#[test]
+	fn drops_incomplete_blocks_on_open() {
+		let tempdir = TempDir::new("").unwrap();
+		let mut db = Database::open(tempdir.path(), 1024).unwrap();
+		db.append(b"header0", b"body0", b"receipts0").unwrap();
+		db.append(b"header1", b"body1", b"receipts1").unwrap();
+		// interrupted while appending the third block.
+		db.tables().unwrap().headers.append(b"header2").unwrap();
+		db.flush().unwrap();
+		assert_eq!(db.len(), 2);
+
+		db.close().unwrap();
+		db.reopen().unwrap();
+		assert_eq!(db.len(), 2);
+		assert_eq!(db.get(Table::Headers, 1).unwrap(), Some(b"header1".to_vec()));
+		assert_eq!(db.get(Table::Headers, 2).unwrap(), None);
+		db.append(b"header2", b"body2", b"receipts2").unwrap();
+		assert_eq!(db.get(Table::Receipts, 2).unwrap(), Some(b"receipts2".to_vec()));
+	}
+}
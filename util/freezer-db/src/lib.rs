@@ -0,0 +1,107 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Append-only store of ancient blocks.
+//!
+//! Headers, bodies and receipts of the blocks which are old enough never to be reverted are
+//! moved out of the key-value database into flat files, one table for each kind of item.
+//! Items are numbered by block number and snappy-compressed.
+
+mod db;
+mod table;
+
+use std::io;
+use std::path::Path;
+use parking_lot::Mutex;
+
+/// Size of the segment files of the tables.
+const MAX_SEGMENT_SIZE: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Kind of a frozen item.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Table {
+	/// RLP-encoded block headers.
+	Headers,
+	/// RLP-encoded block bodies.
+	Bodies,
+	/// RLP-encoded block receipts.
+	Receipts,
+}
+
+/// Threadsafe API for freezer database.
+///
+/// # Warning
+///
+/// Appended blocks are only guaranteed to be on disk after `flush`.
+pub struct Database {
+	database: Mutex<db::Database>,
+}
+
+impl Database {
+	/// Creates new database handle.
+	///
+	/// # Arguments
+	///
+	/// * `path` - database directory
+	pub fn open<P>(path: P) -> io::Result<Database> where P: AsRef<Path> {
+		let result = Database {
+			database: Mutex::new(db::Database::open(path, MAX_SEGMENT_SIZE)?),
+		};
+
+		Ok(result)
+	}
+
+	/// Closes the inner database
+	pub fn close(&self) -> io::Result<()> {
+		self.database.lock().close()
+	}
+
+	/// Reopens database at the same location.
+	pub fn reopen(&self) -> io::Result<()> {
+		self.database.lock().reopen()
+	}
+
+	/// Number of frozen blocks, i.e. the number of the first block which is not frozen.
+	pub fn len(&self) -> u64 {
+		self.database.lock().len()
+	}
+
+	/// Appends the next block, with number `len()`.
+	///
+	/// # Arguments
+	///
+	/// * `header` - encoded header
+	/// * `body` - encoded body
+	/// * `receipts` - encoded receipts
+	pub fn append(&self, header: &[u8], body: &[u8], receipts: &[u8]) -> io::Result<()> {
+		self.database.lock().append(header, body, receipts)
+	}
+
+	/// Returns an item of the frozen block with the given number.
+	pub fn get(&self, table: Table, number: u64) -> io::Result<Option<Vec<u8>>> {
+		self.database.lock().get(table, number)
+	}
+
+	/// Removes the blocks from the given number on.
+	pub fn truncate(&self, len: u64) -> io::Result<()> {
+		self.database.lock().truncate(len)
+	}
+
+	/// Writes the appended blocks to the disk.
+	pub fn flush(&self) -> io::Result<()> {
+		self.database.lock().flush()
+	}
+}
@@ -0,0 +1,279 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+/// Size of an index entry: segment number (4 bytes) and end offset in the segment (8 bytes).
+const INDEX_ENTRY_SIZE: u64 = 12;
+
+/// Position of the end of an item.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct IndexEntry {
+	segment: u32,
+	offset: u64,
+}
+
+impl IndexEntry {
+	fn encode(&self) -> [u8; INDEX_ENTRY_SIZE as usize] {
+		let mut bytes = [0u8; INDEX_ENTRY_SIZE as usize];
+		bytes[..4].copy_from_slice(&self.segment.to_be_bytes());
+		bytes[4..].copy_from_slice(&self.offset.to_be_bytes());
+		bytes
+	}
+
+	fn decode(bytes: &[u8; INDEX_ENTRY_SIZE as usize]) -> Self {
+		let mut segment = [0u8; 4];
+		let mut offset = [0u8; 8];
+		segment.copy_from_slice(&bytes[..4]);
+		offset.copy_from_slice(&bytes[4..]);
+		IndexEntry {
+			segment: u32::from_be_bytes(segment),
+			offset: u64::from_be_bytes(offset),
+		}
+	}
+}
+
+/// Append-only table of snappy-compressed items.
+///
+/// Items are written one after another into segment files of bounded size. The index file
+/// holds the end position of every item, preceded by an entry for the start of the table,
+/// so item `n` lies between index entries `n` and `n + 1`.
+pub struct Table {
+	/// Directory of the table files.
+	dir: PathBuf,
+	/// Prefix of the table file names.
+	name: &'static str,
+	/// Index file.
+	index: fs::File,
+	/// Open segment files.
+	segments: HashMap<u32, fs::File>,
+	/// Position of the end of the last item.
+	head: IndexEntry,
+	/// Number of items.
+	items: u64,
+	/// Size at which a new segment is started.
+	max_segment_size: u64,
+}
+
+impl Table {
+	/// Opens the table, dropping any data written past the last complete item.
+	pub fn open(dir: &Path, name: &'static str, max_segment_size: u64) -> io::Result<Table> {
+		let index = fs::OpenOptions::new()
+			.read(true)
+			.write(true)
+			.create(true)
+			.open(dir.join(format!("{}.idx", name)))?;
+
+		let mut table = Table {
+			dir: dir.to_path_buf(),
+			name,
+			index,
+			segments: HashMap::new(),
+			head: IndexEntry { segment: 0, offset: 0 },
+			items: 0,
+			max_segment_size,
+		};
+
+		let index_len = table.index.metadata()?.len();
+		if index_len < INDEX_ENTRY_SIZE {
+			table.index.set_len(0)?;
+			table.index.write_all(&table.head.encode())?;
+			table.set_len(0)?;
+			return Ok(table);
+		}
+
+		// an interrupted append may leave an entry without its data.
+		let mut items = index_len / INDEX_ENTRY_SIZE - 1;
+		loop {
+			let entry = table.entry(items)?;
+			let segment_len = match fs::metadata(table.segment_path(entry.segment)) {
+				Ok(metadata) => metadata.len(),
+				Err(ref e) if e.kind() == io::ErrorKind::NotFound => 0,
+				Err(e) => return Err(e),
+			};
+			if entry.offset <= segment_len || items == 0 {
+				break;
+			}
+			items -= 1;
+		}
+		table.set_len(items)?;
+		Ok(table)
+	}
+
+	/// Number of items in the table.
+	pub fn len(&self) -> u64 {
+		self.items
+	}
+
+	/// Appends an item at the end of the table.
+	pub fn append(&mut self, data: &[u8]) -> io::Result<()> {
+		let compressed = snappy::compress(data);
+		let len = compressed.len() as u64;
+		if self.head.offset > 0 && self.head.offset + len > self.max_segment_size {
+			self.segment(self.head.segment)?.sync_data()?;
+			self.head = IndexEntry { segment: self.head.segment + 1, offset: 0 };
+		}
+
+		let offset = self.head.offset;
+		let segment = self.segment(self.head.segment)?;
+		segment.seek(SeekFrom::Start(offset))?;
+		segment.write_all(&compressed)?;
+
+		self.head.offset += len;
+		self.items += 1;
+		self.index.seek(SeekFrom::Start(self.items * INDEX_ENTRY_SIZE))?;
+		self.index.write_all(&self.head.encode())
+	}
+
+	/// Reads the item at the given position.
+	pub fn get(&mut self, number: u64) -> io::Result<Option<Vec<u8>>> {
+		if number >= self.items {
+			return Ok(None);
+		}
+
+		let start = self.entry(number)?;
+		let end = self.entry(number + 1)?;
+		// an item never spans segments.
+		let start = if start.segment == end.segment { start.offset } else { 0 };
+		let mut compressed = vec![0u8; (end.offset - start) as usize];
+		let segment = self.segment(end.segment)?;
+		segment.seek(SeekFrom::Start(start))?;
+		segment.read_exact(&mut compressed)?;
+		snappy::decompress(&compressed)
+			.map(Some)
+			.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}", e)))
+	}
+
+	/// Drops all the items from the given position on.
+	pub fn truncate(&mut self, items: u64) -> io::Result<()> {
+		if items < self.items {
+			self.set_len(items)?;
+		}
+		Ok(())
+	}
+
+	/// Flushes the written items to the disk.
+	pub fn flush(&mut self) -> io::Result<()> {
+		self.segment(self.head.segment)?.sync_data()?;
+		self.index.sync_data()
+	}
+
+	/// Sets the number of items, given that the index has entries for them.
+	fn set_len(&mut self, items: u64) -> io::Result<()> {
+		self.head = self.entry(items)?;
+		self.items = items;
+		self.index.set_len((items + 1) * INDEX_ENTRY_SIZE)?;
+
+		let head_segment = self.head.segment;
+		self.segments.retain(|segment, _| *segment <= head_segment);
+		self.segment(head_segment)?.set_len(self.head.offset)?;
+		for segment in head_segment + 1.. {
+			match fs::remove_file(self.segment_path(segment)) {
+				Ok(()) => {},
+				Err(ref e) if e.kind() == io::ErrorKind::NotFound => break,
+				Err(e) => return Err(e),
+			}
+		}
+		Ok(())
+	}
+
+	fn entry(&mut self, number: u64) -> io::Result<IndexEntry> {
+		let mut bytes = [0u8; INDEX_ENTRY_SIZE as usize];
+		self.index.seek(SeekFrom::Start(number * INDEX_ENTRY_SIZE))?;
+		self.index.read_exact(&mut bytes)?;
+		Ok(IndexEntry::decode(&bytes))
+	}
+
+	fn segment_path(&self, segment: u32) -> PathBuf {
+		self.dir.join(format!("{}.{:04}.sdat", self.name, segment))
+	}
+
+	fn segment(&mut self, segment: u32) -> io::Result<&mut fs::File> {
+		if !self.segments.contains_key(&segment) {
+			let file = fs::OpenOptions::new()
+				.read(true)
+				.write(true)
+				.create(true)
+				.open(self.segment_path(segment))?;
+			self.segments.insert(segment, file);
+		}
+		Ok(self.segments.get_mut(&segment).expect("inserted above if missing; qed"))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs;
+	use tempdir::TempDir;
+	use super::Table;
+
+	fn item(n: u64) -> Vec<u8> {
+		vec![n as u8; 10 + n as usize]
+	}
+
+	#[test]
+	fn appends_and_reads_across_segments() {
+		let tempdir = TempDir::new("").unwrap();
+		let mut table = Table::open(tempdir.path(), "headers", 32).unwrap();
+		for n in 0..10 {
+			table.append(&item(n)).unwrap();
+		}
+		assert_eq!(table.len(), 10);
+		assert!(tempdir.path().join("headers.0001.sdat").exists());
+		for n in 0..10 {
+			assert_eq!(table.get(n).unwrap(), Some(item(n)));
+		}
+		assert_eq!(table.get(10).unwrap(), None);
+
+		table.flush().unwrap();
+		drop(table);
+		let mut table = Table::open(tempdir.path(), "headers", 32).unwrap();
+		assert_eq!(table.len(), 10);
+		assert_eq!(table.get(9).unwrap(), Some(item(9)));
+	}
+
+	#[test]
+	fn truncates_and_repairs() {
+		let tempdir = TempDir::new("").unwrap();
+		let mut table = Table::open(tempdir.path(), "bodies", 32).unwrap();
+		for n in 0..10 {
+			table.append(&item(n)).unwrap();
+		}
+		table.truncate(4).unwrap();
+		assert_eq!(table.len(), 4);
+		assert_eq!(table.get(4).unwrap(), None);
+		table.append(&item(7)).unwrap();
+		assert_eq!(table.get(4).unwrap(), Some(item(7)));
+		table.flush().unwrap();
+		drop(table);
+
+		// lose the data of the last item, as if the write was interrupted.
+		let index = fs::read(tempdir.path().join("bodies.idx")).unwrap();
+		let head_segment = u32::from_be_bytes([index[index.len() - 12], index[index.len() - 11], index[index.len() - 10], index[index.len() - 9]]);
+		let path = tempdir.path().join(format!("bodies.{:04}.sdat", head_segment));
+		let len = fs::metadata(&path).unwrap().len();
+		fs::OpenOptions::new().write(true).open(&path).unwrap().set_len(len - 1).unwrap();
+
+		let mut table = Table::open(tempdir.path(), "bodies", 32).unwrap();
+		assert_eq!(table.len(), 4);
+		assert_eq!(table.get(3).unwrap(), Some(item(3)));
+		table.append(&item(8)).unwrap();
+		assert_eq!(table.get(4).unwrap(), Some(item(8)));
+	}
+}
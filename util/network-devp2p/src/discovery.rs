@@ -31,6 +31,7 @@ use parity_crypto::publickey::{KeyPair, recover, Secret, sign};
 use network::Error;
 use network::IpFilter;
 
+use crate::enr::Enr;
 use crate::node_table::*;
 use crate::PROTOCOL_VERSION;
 
@@ -45,9 +46,12 @@ const PACKET_PING: u8 = 1;
 const PACKET_PONG: u8 = 2;
 const PACKET_FIND_NODE: u8 = 3;
 const PACKET_NEIGHBOURS: u8 = 4;
+const PACKET_ENR_REQUEST: u8 = 5;
+const PACKET_ENR_RESPONSE: u8 = 6;
 
 const PING_TIMEOUT: Duration = Duration::from_millis(500);
 const FIND_NODE_TIMEOUT: Duration = Duration::from_secs(2);
+const ENR_REQUEST_TIMEOUT: Duration = Duration::from_millis(500);
 const EXPIRY_TIME: Duration = Duration::from_secs(20);
 const MAX_NODES_PING: usize = 32; // Max nodes to add/ping at once
 const REQUEST_BACKOFF: [Duration; 4] = [
@@ -137,6 +141,13 @@ struct PingRequest {
 	reason: PingReason
 }
 
+struct EnrRequest {
+	// Time when the request was sent
+	sent_at: Instant,
+	// The hash of the request packet, echoed in the response
+	hash: H256,
+}
+
 #[derive(Debug)]
 pub struct NodeBucket {
 	nodes: VecDeque<BucketEntry>, //sorted by last active
@@ -166,6 +177,7 @@ pub struct Discovery<'a> {
 	id_hash: H256,
 	secret: Secret,
 	public_endpoint: NodeEndpoint,
+	enr: Enr,
 	discovery_initiated: bool,
 	discovery_round: Option<u16>,
 	discovery_id: NodeId,
@@ -175,9 +187,12 @@ pub struct Discovery<'a> {
 	// keep track of them to avoid excessive pinging (happens when an unknown node sends
 	// a discovery request to us -- the node might be on a different net).
 	other_observed_nodes: LruCache<NodeId, (NodeEndpoint, Instant)>,
+	// Sequence numbers of the latest records fetched from other nodes.
+	enr_seqs: LruCache<NodeId, u64>,
 
 	in_flight_pings: HashMap<NodeId, PingRequest>,
 	in_flight_find_nodes: HashMap<NodeId, FindNodeRequest>,
+	in_flight_enr_requests: HashMap<NodeId, EnrRequest>,
 	send_queue: VecDeque<Datagram>,
 	check_timestamps: bool,
 	adding_nodes: Vec<NodeEntry>,
@@ -188,6 +203,8 @@ pub struct Discovery<'a> {
 pub struct TableUpdates {
 	pub added: HashMap<NodeId, NodeEntry>,
 	pub removed: HashSet<NodeId>,
+	/// Records fetched from other nodes.
+	pub records: HashMap<NodeId, Enr>,
 }
 
 impl<'a> Discovery<'a> {
@@ -196,6 +213,7 @@ impl<'a> Discovery<'a> {
 			id: *key.public(),
 			id_hash: keccak(key.public()),
 			secret: key.secret().clone(),
+			enr: Enr::new(key, &public).expect("the key pair is valid and a record with an endpoint is small enough; qed"),
 			public_endpoint: public,
 			discovery_initiated: false,
 			discovery_round: None,
@@ -203,8 +221,10 @@ impl<'a> Discovery<'a> {
 			discovery_nodes: HashSet::new(),
 			node_buckets: (0..ADDRESS_BITS).map(|_| NodeBucket::new()).collect(),
 			other_observed_nodes: LruCache::new(OBSERVED_NODES_MAX_SIZE),
+			enr_seqs: LruCache::new(OBSERVED_NODES_MAX_SIZE),
 			in_flight_pings: HashMap::new(),
 			in_flight_find_nodes: HashMap::new(),
+			in_flight_enr_requests: HashMap::new(),
 			send_queue: VecDeque::new(),
 			check_timestamps: true,
 			adding_nodes: Vec::new(),
@@ -213,6 +233,11 @@ impl<'a> Discovery<'a> {
 		}
 	}
 
+	/// Replaces our node record, sent to nodes asking for it.
+	pub fn set_enr(&mut self, enr: Enr) {
+		self.enr = enr;
+	}
+
 	/// Add a new node to discovery table. Pings the node.
 	pub fn add_node(&mut self, e: NodeEntry) {
 		// If distance returns None, then we are trying to add ourself.
@@ -279,7 +304,7 @@ impl<'a> Discovery<'a> {
 			};
 
             if node_entry.endpoint.is_valid_sync_node() {
-				Some(TableUpdates { added, removed: HashSet::new() })
+				Some(TableUpdates { added, removed: HashSet::new(), records: HashMap::new() })
 			} else {
 				None
 			}
@@ -383,11 +408,12 @@ impl<'a> Discovery<'a> {
 	}
 
 	fn ping(&mut self, node: &NodeEntry, reason: PingReason) -> Result<(), Error> {
-		let mut rlp = RlpStream::new_list(4);
+		let mut rlp = RlpStream::new_list(5);
 		rlp.append(&PROTOCOL_VERSION);
 		self.public_endpoint.to_rlp_list(&mut rlp);
 		node.endpoint.to_rlp_list(&mut rlp);
 		append_expiration(&mut rlp);
+		rlp.append(&self.enr.seq());
 		let old_parity_hash = keccak(rlp.as_raw());
 		let hash = self.send_packet(PACKET_PING, &node.endpoint.udp_address(), &rlp.drain())?;
 
@@ -419,6 +445,24 @@ impl<'a> Discovery<'a> {
 		Ok(())
 	}
 
+	/// Asks the node for its record if it announced a newer one than the one we know.
+	fn request_enr(&mut self, node: &NodeEntry, seq: u64) -> Result<(), Error> {
+		if self.in_flight_enr_requests.contains_key(&node.id) || self.enr_seqs.get_mut(&node.id).map_or(false, |known| *known >= seq) {
+			return Ok(());
+		}
+		let mut rlp = RlpStream::new_list(1);
+		append_expiration(&mut rlp);
+		let hash = self.send_packet(PACKET_ENR_REQUEST, &node.endpoint.udp_address(), &rlp.drain())?;
+
+		self.in_flight_enr_requests.insert(node.id, EnrRequest {
+			sent_at: Instant::now(),
+			hash,
+		});
+
+		trace!(target: "discovery", "Sent ENRRequest to {:?}", &node.endpoint);
+		Ok(())
+	}
+
 	fn send_packet(&mut self, packet_id: u8, address: &SocketAddr, payload: &[u8]) -> Result<H256, Error> {
 		let packet = assemble_packet(packet_id, payload, &self.secret)?;
 		let hash = H256::from_slice(&packet[0..32]);
@@ -481,16 +525,18 @@ impl<'a> Discovery<'a> {
 		self.send_queue.push_back(Datagram { payload, address });
 	}
 
+	/// Whether the packet is a v4 one, i.e. starts with the hash of the rest of it.
+	pub fn is_v4_packet(packet: &[u8]) -> bool {
+		packet.len() >= 32 + 65 + 4 + 1 && keccak(&packet[32..])[..] == packet[0..32]
+	}
+
 	pub fn on_packet(&mut self, packet: &[u8], from: SocketAddr) -> Result<Option<TableUpdates>, Error> {
 		// validate packet
-		if packet.len() < 32 + 65 + 4 + 1 {
+		if !Self::is_v4_packet(packet) {
 			return Err(Error::BadProtocol);
 		}
 
 		let hash_signed = keccak(&packet[32..]);
-		if hash_signed[..] != packet[0..32] {
-			return Err(Error::BadProtocol);
-		}
 
 		let signed = &packet[(32 + 65)..];
 		let signature = H520::from_slice(&packet[32..(32 + 65)]);
@@ -502,6 +548,8 @@ impl<'a> Discovery<'a> {
 			PACKET_PONG => self.on_pong(&rlp, &node_id, &from),
 			PACKET_FIND_NODE => self.on_find_node(&rlp, &node_id, &from),
 			PACKET_NEIGHBOURS => self.on_neighbours(&rlp, &node_id, &from),
+			PACKET_ENR_REQUEST => self.on_enr_request(&rlp, &node_id, &from, hash_signed.as_bytes()),
+			PACKET_ENR_RESPONSE => self.on_enr_response(&rlp, &node_id, &from),
 			_ => {
 				debug!(target: "discovery", "Unknown UDP packet: {}", packet_id);
 				Ok(None)
@@ -540,7 +588,7 @@ impl<'a> Discovery<'a> {
 		let ping_to = NodeEndpoint::from_rlp(&rlp.at(2)?)?;
 		let timestamp: u64 = rlp.val_at(3)?;
 		self.check_timestamp(timestamp)?;
-		let mut response = RlpStream::new_list(3);
+		let mut response = RlpStream::new_list(4);
 		let pong_to = NodeEndpoint {
 			address: from.clone(),
 			udp_port: ping_from.udp_port
@@ -555,6 +603,7 @@ impl<'a> Discovery<'a> {
 
 		response.append(&echo_hash);
 		append_expiration(&mut response);
+		response.append(&self.enr.seq());
 		self.send_packet(PACKET_PONG, from, &response.drain())?;
 
 		let entry = NodeEntry { id: *node_id, endpoint: pong_to.clone() };
@@ -574,6 +623,8 @@ impl<'a> Discovery<'a> {
 		let echo_hash: H256 = rlp.val_at(1)?;
 		let timestamp: u64 = rlp.val_at(2)?;
 		self.check_timestamp(timestamp)?;
+		// The record sequence number was added by EIP-868, older nodes don't send it.
+		let enr_seq: Option<u64> = rlp.val_at(3).ok();
 
 		let expected_node = match self.in_flight_pings.entry(*node_id) {
 			Entry::Occupied(entry) => {
@@ -601,6 +652,9 @@ impl<'a> Discovery<'a> {
 		};
 
 		if let Some((node, ping_reason)) = expected_node {
+			if let Some(seq) = enr_seq {
+				self.request_enr(&node, seq)?;
+			}
 			if let PingReason::FromDiscoveryRequest(target, validity) = ping_reason {
 				self.respond_with_discovery(target, &node)?;
 				// kirushik: I would prefer to probe the network id of the remote node here, and add it to the nodes list if it's on "our" net --
@@ -652,6 +706,55 @@ impl<'a> Discovery<'a> {
 		Ok(None)
 	}
 
+	fn on_enr_request(&mut self, rlp: &Rlp, node_id: &NodeId, from: &SocketAddr, request_hash: &[u8]) -> Result<Option<TableUpdates>, Error> {
+		trace!(target: "discovery", "Got ENRRequest from {:?}", &from);
+		let timestamp: u64 = rlp.val_at(0)?;
+		self.check_timestamp(timestamp)?;
+
+		let node = NodeEntry {
+			id: *node_id,
+			endpoint: NodeEndpoint {
+				address: *from,
+				udp_port: from.port()
+			}
+		};
+
+		// Only answer nodes which proved they own their endpoint, the response is larger than the request.
+		match self.check_validity(&node) {
+			NodeValidity::ValidNode(_) => {
+				let mut response = RlpStream::new_list(2);
+				response.append(&request_hash);
+				response.append(&self.enr);
+				self.send_packet(PACKET_ENR_RESPONSE, from, &response.drain())?;
+				trace!(target: "discovery", "Sent ENRResponse to {:?}", &from);
+			},
+			_ => debug!(target: "discovery", "Ignoring ENRRequest from unknown node {:?}", &node),
+		}
+		Ok(None)
+	}
+
+	fn on_enr_response(&mut self, rlp: &Rlp, node_id: &NodeId, from: &SocketAddr) -> Result<Option<TableUpdates>, Error> {
+		trace!(target: "discovery", "Got ENRResponse from {:?}", &from);
+		let request_hash: H256 = rlp.val_at(0)?;
+
+		if self.in_flight_enr_requests.get(node_id).map_or(true, |request| request.hash != request_hash) {
+			debug!(target: "discovery", "Got unexpected ENRResponse from {:?} ; node_id={:#x}", &from, node_id);
+			return Ok(None);
+		}
+		self.in_flight_enr_requests.remove(node_id);
+
+		let enr: Enr = rlp.val_at(1)?;
+		if enr.id() != node_id {
+			debug!(target: "discovery", "Got ENRResponse from {:?} with the record of another node", &from);
+			return Err(Error::BadProtocol);
+		}
+		self.enr_seqs.insert(*node_id, enr.seq());
+
+		let mut records = HashMap::with_capacity(1);
+		records.insert(*node_id, enr);
+		Ok(Some(TableUpdates { added: HashMap::new(), removed: HashSet::new(), records }))
+	}
+
 	fn check_validity(&mut self, node: &NodeEntry) -> NodeValidity {
 		let id_hash = keccak(node.id);
 		let dist = match Discovery::distance(&self.id_hash, &id_hash) {
@@ -775,6 +878,14 @@ impl<'a> Discovery<'a> {
 				true
 			}
 		});
+		self.in_flight_enr_requests.retain(|node_id, enr_request| {
+			if time.duration_since(enr_request.sent_at) > ENR_REQUEST_TIMEOUT {
+				debug!(target: "discovery", "Removing expired ENR request for node_id={:#x}", node_id);
+				false
+			} else {
+				true
+			}
+		});
 		self.in_flight_find_nodes.retain(|node_id, find_node_request| {
 			if time.duration_since(find_node_request.sent_at) > FIND_NODE_TIMEOUT {
 				if !find_node_request.answered {
@@ -1326,4 +1437,44 @@ mod tests {
 			panic!("Expected no changes to discovery1's table for unexpected pong");
 		}
 	}
+
+	#[test]
+	fn enr_request() {
+		let key1 = Random.generate().unwrap();
+		let key2 = Random.generate().unwrap();
+		let ep1 = NodeEndpoint { address: SocketAddr::from_str("127.0.0.1:40354").unwrap(), udp_port: 40354 };
+		let ep2 = NodeEndpoint { address: SocketAddr::from_str("127.0.0.1:40355").unwrap(), udp_port: 40355 };
+		let mut discovery1 = Discovery::new(&key1, ep1.clone(), IpFilter::default());
+		let mut discovery2 = Discovery::new(&key2, ep2.clone(), IpFilter::default());
+
+		// Bond both nodes, `discovery1` asks for the record announced in the pong.
+		discovery1.ping(&NodeEntry { id: discovery2.id, endpoint: ep2.clone() }, PingReason::Default).unwrap();
+		let ping = discovery1.dequeue_send().unwrap();
+		discovery2.on_packet(&ping.payload, ep1.address.clone()).unwrap();
+		let pong = discovery2.dequeue_send().unwrap();
+		let ping_back = discovery2.dequeue_send().unwrap();
+		assert!(discovery1.on_packet(&pong.payload, ep2.address.clone()).unwrap().is_some());
+		let enr_request = discovery1.dequeue_send().unwrap();
+		assert_eq!(enr_request.payload[32 + 65], PACKET_ENR_REQUEST);
+		discovery1.on_packet(&ping_back.payload, ep2.address.clone()).unwrap();
+		let pong_back = discovery1.dequeue_send().unwrap();
+		discovery2.on_packet(&pong_back.payload, ep1.address.clone()).unwrap();
+		let enr_request_back = discovery2.dequeue_send().unwrap();
+		assert_eq!(enr_request_back.payload[32 + 65], PACKET_ENR_REQUEST);
+
+		discovery2.on_packet(&enr_request.payload, ep1.address.clone()).unwrap();
+		let enr_response = discovery2.dequeue_send().unwrap();
+		assert_eq!(enr_response.payload[32 + 65], PACKET_ENR_RESPONSE);
+
+		let updates = discovery1.on_packet(&enr_response.payload, ep2.address.clone()).unwrap().unwrap();
+		assert_eq!(updates.records.get(&discovery2.id), Some(&discovery2.enr));
+		assert!(discovery1.in_flight_enr_requests.is_empty());
+
+		// A known record is not requested again.
+		discovery1.request_enr(&NodeEntry { id: discovery2.id, endpoint: ep2.clone() }, discovery2.enr.seq()).unwrap();
+		assert!(!discovery1.any_sends_queued());
+
+		// Unexpected responses are ignored.
+		assert!(discovery1.on_packet(&enr_response.payload, ep2.address.clone()).unwrap().is_none());
+	}
 }
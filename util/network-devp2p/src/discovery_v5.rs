@@ -0,0 +1,1251 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Node Discovery Protocol v5.
+//!
+//! Messages are encrypted with session keys agreed in a WHOAREYOU handshake. The protocol shares
+//! the UDP socket of the v4 discovery, packets of the two versions are told apart by the hash
+//! prefixing v4 packets. Records learnt over v5 are handed over to the node table.
+//!
+//! Topic advertisement is simplified: registrars accept ads right away and never issue tickets
+//! with a waiting time.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use ethereum_types::H256;
+use keccak_hash::keccak;
+use log::{debug, trace};
+use lru_cache::LruCache;
+use parity_bytes::Bytes;
+use parity_crypto::{aes, digest};
+use parity_crypto::publickey::{ec_math_utils, Generator, KeyPair, Random, Secret, sign};
+use ring::{aead, hkdf};
+use rlp::{Rlp, RlpStream};
+
+use network::{Error, IpFilter};
+
+use crate::discovery::{Datagram, MAX_DATAGRAM_SIZE, NodeEntry, TableUpdates};
+use crate::enr::{compress, decompress, recover_signer, Enr};
+use crate::node_table::{NodeEndpoint, NodeId};
+
+const PROTOCOL_ID: &[u8] = b"discv5";
+const PROTOCOL_VERSION: u16 = 1;
+
+const FLAG_MESSAGE: u8 = 0;
+const FLAG_WHOAREYOU: u8 = 1;
+const FLAG_HANDSHAKE: u8 = 2;
+
+const MESSAGE_PING: u8 = 1;
+const MESSAGE_PONG: u8 = 2;
+const MESSAGE_FIND_NODE: u8 = 3;
+const MESSAGE_NODES: u8 = 4;
+const MESSAGE_TALK_REQUEST: u8 = 5;
+const MESSAGE_TALK_RESPONSE: u8 = 6;
+const MESSAGE_REG_TOPIC: u8 = 7;
+const MESSAGE_TICKET: u8 = 8;
+const MESSAGE_REG_CONFIRMATION: u8 = 9;
+const MESSAGE_TOPIC_QUERY: u8 = 10;
+
+const MASKING_IV_SIZE: usize = 16;
+const NONCE_SIZE: usize = 12;
+const STATIC_HEADER_SIZE: usize = 23;
+const ID_NONCE_SIZE: usize = 16;
+const MIN_PACKET_SIZE: usize = 63;
+/// Size of the random message of packets sent to nodes we have no session with.
+const RANDOM_MESSAGE_SIZE: usize = 20;
+const MAX_REQUEST_ID_SIZE: usize = 8;
+
+const ID_SIGNATURE_TEXT: &[u8] = b"discovery v5 identity proof";
+const KEY_AGREEMENT_INFO: &[u8] = b"discovery v5 key agreement";
+
+const BUCKET_SIZE: usize = 16;		// Max number of nodes stored at each distance.
+const ALPHA: usize = 3;				// Number of concurrent lookup requests.
+const LOOKUP_STEPS: usize = 8;		// Max iterations of a lookup.
+const MAX_NODES_RESPONSE: usize = 16;
+const RECORDS_PER_PACKET: usize = 3;
+const MAX_SESSIONS: usize = 1000;
+const MAX_SENT_MESSAGES: usize = 1000;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(1);
+
+const TOPIC_REGISTRARS: usize = 3;
+const AD_LIFETIME: Duration = Duration::from_secs(15 * 60);
+const MAX_TOPIC_ADS: usize = 100;
+const MAX_TOPICS: usize = 500;
+
+/// Handler of a protocol running over TALKREQ/TALKRESP.
+pub trait TalkHandler: Send + Sync {
+	/// Answers a request of the node.
+	fn on_request(&self, node: &H256, request: &[u8]) -> Bytes;
+	/// Handles the response to a request sent with `DiscoveryV5::send_talk_request`.
+	fn on_response(&self, node: &H256, response: &[u8]);
+}
+
+/// Node known to the v5 discovery.
+#[derive(Clone, Debug)]
+struct Node {
+	public: NodeId,
+	endpoint: NodeEndpoint,
+	enr: Option<Enr>,
+}
+
+struct Session {
+	write_key: [u8; 16],
+	read_key: [u8; 16],
+}
+
+/// Messages waiting for the WHOAREYOU challenge of the recipient.
+struct PendingHandshake {
+	node: Node,
+	/// The first message is sent with the handshake, the rest once the session is established.
+	messages: Vec<Bytes>,
+	sent_at: Instant,
+}
+
+/// WHOAREYOU challenge sent to a node.
+struct Challenge {
+	data: Bytes,
+	sent_at: Instant,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum RequestKind {
+	Ping,
+	FindNode { distances: Vec<u16>, lookup: bool },
+	Talk(Bytes),
+	RegTopic(H256),
+	TopicQuery(H256),
+}
+
+struct Request {
+	node: H256,
+	kind: RequestKind,
+	sent_at: Instant,
+	/// Number of NODES responses received.
+	responses: u64,
+}
+
+struct Lookup {
+	target: H256,
+	asked: HashSet<H256>,
+	steps: usize,
+}
+
+/// Unmasked packet header.
+struct Header {
+	masking_iv: [u8; MASKING_IV_SIZE],
+	flag: u8,
+	nonce: [u8; NONCE_SIZE],
+	authdata: Bytes,
+}
+
+impl Header {
+	fn new(flag: u8, nonce: [u8; NONCE_SIZE], authdata: Bytes) -> Self {
+		Header {
+			masking_iv: rand::random(),
+			flag,
+			nonce,
+			authdata,
+		}
+	}
+
+	/// Static header followed by the authdata.
+	fn encode(&self) -> Bytes {
+		let mut header = Vec::with_capacity(STATIC_HEADER_SIZE + self.authdata.len());
+		header.extend_from_slice(PROTOCOL_ID);
+		header.extend_from_slice(&PROTOCOL_VERSION.to_be_bytes());
+		header.push(self.flag);
+		header.extend_from_slice(&self.nonce);
+		header.extend_from_slice(&(self.authdata.len() as u16).to_be_bytes());
+		header.extend_from_slice(&self.authdata);
+		header
+	}
+
+	/// Associated data of the message encryption. For WHOAREYOU packets this is the challenge data.
+	fn message_ad(&self) -> Bytes {
+		let mut ad = self.masking_iv.to_vec();
+		ad.extend_from_slice(&self.encode());
+		ad
+	}
+
+	/// Masks the header with the node id of the recipient.
+	fn encode_masked(&self, recipient: &H256) -> Result<Bytes, Error> {
+		let header = self.encode();
+		let mut packet = vec![0u8; MASKING_IV_SIZE + header.len()];
+		packet[..MASKING_IV_SIZE].copy_from_slice(&self.masking_iv);
+		aes::encrypt_128_ctr(&recipient[..16], &self.masking_iv, &header, &mut packet[MASKING_IV_SIZE..])?;
+		Ok(packet)
+	}
+
+	/// Unmasks the header of a packet sent to `local`, returns it with the message following it.
+	fn decode<'a>(packet: &'a [u8], local: &H256) -> Result<(Header, &'a [u8]), Error> {
+		if packet.len() < MIN_PACKET_SIZE || packet.len() > MAX_DATAGRAM_SIZE {
+			return Err(Error::BadProtocol);
+		}
+		let (masking_iv, masked) = packet.split_at(MASKING_IV_SIZE);
+		let mut static_header = [0u8; STATIC_HEADER_SIZE];
+		aes::decrypt_128_ctr(&local[..16], masking_iv, &masked[..STATIC_HEADER_SIZE], &mut static_header)?;
+		if &static_header[..6] != PROTOCOL_ID || static_header[6..8] != PROTOCOL_VERSION.to_be_bytes() {
+			return Err(Error::BadProtocol);
+		}
+
+		let header_size = STATIC_HEADER_SIZE + u16::from_be_bytes([static_header[21], static_header[22]]) as usize;
+		if masked.len() < header_size {
+			return Err(Error::BadProtocol);
+		}
+		let mut header = vec![0u8; header_size];
+		aes::decrypt_128_ctr(&local[..16], masking_iv, &masked[..header_size], &mut header)?;
+
+		let mut iv = [0u8; MASKING_IV_SIZE];
+		iv.copy_from_slice(masking_iv);
+		let mut nonce = [0u8; NONCE_SIZE];
+		nonce.copy_from_slice(&header[9..21]);
+		Ok((Header {
+			masking_iv: iv,
+			flag: header[8],
+			nonce,
+			authdata: header[STATIC_HEADER_SIZE..].to_vec(),
+		}, &masked[header_size..]))
+	}
+}
+
+fn encrypt_message(key: &[u8; 16], nonce: &[u8; NONCE_SIZE], message: &[u8], ad: &[u8]) -> Result<Bytes, Error> {
+	let key = aead::UnboundKey::new(&aead::AES_128_GCM, key).map_err(|_| Error::Auth)?;
+	let mut data = message.to_vec();
+	aead::LessSafeKey::new(key)
+		.seal_in_place_append_tag(aead::Nonce::assume_unique_for_key(*nonce), aead::Aad::from(ad), &mut data)
+		.map_err(|_| Error::Auth)?;
+	Ok(data)
+}
+
+fn decrypt_message(key: &[u8; 16], nonce: &[u8; NONCE_SIZE], message: &[u8], ad: &[u8]) -> Result<Bytes, Error> {
+	let key = aead::UnboundKey::new(&aead::AES_128_GCM, key).map_err(|_| Error::Auth)?;
+	let mut data = message.to_vec();
+	let len = aead::LessSafeKey::new(key)
+		.open_in_place(aead::Nonce::assume_unique_for_key(*nonce), aead::Aad::from(ad), &mut data)
+		.map_err(|_| Error::Auth)?
+		.len();
+	data.truncate(len);
+	Ok(data)
+}
+
+/// Compressed shared point of the ECDH key agreement.
+fn ecdh(public: &NodeId, secret: &Secret) -> Result<[u8; 33], Error> {
+	let mut shared = *public;
+	ec_math_utils::public_mul_secret(&mut shared, secret)?;
+	Ok(compress(&shared))
+}
+
+/// Derives the initiator and recipient keys of a session.
+fn derive_keys(secret: &[u8], challenge_data: &[u8], initiator: &H256, recipient: &H256) -> Result<([u8; 16], [u8; 16]), Error> {
+	let mut info = KEY_AGREEMENT_INFO.to_vec();
+	info.extend_from_slice(initiator.as_bytes());
+	info.extend_from_slice(recipient.as_bytes());
+	let mut keys = [0u8; 32];
+	hkdf::Salt::new(hkdf::HKDF_SHA256, challenge_data).extract(secret)
+		.expand(&[&info[..]], hkdf::HKDF_SHA256)
+		.and_then(|okm| okm.fill(&mut keys))
+		.map_err(|_| Error::Auth)?;
+	let (mut initiator_key, mut recipient_key) = ([0u8; 16], [0u8; 16]);
+	initiator_key.copy_from_slice(&keys[..16]);
+	recipient_key.copy_from_slice(&keys[16..]);
+	Ok((initiator_key, recipient_key))
+}
+
+/// Hash signed by the initiator of a handshake to prove its identity.
+fn id_signature_hash(challenge_data: &[u8], ephemeral: &[u8], recipient: &H256) -> H256 {
+	let mut input = ID_SIGNATURE_TEXT.to_vec();
+	input.extend_from_slice(challenge_data);
+	input.extend_from_slice(ephemeral);
+	input.extend_from_slice(recipient.as_bytes());
+	H256::from_slice(&digest::sha256(&input))
+}
+
+/// Logarithmic distance between two node ids, zero if they are equal.
+fn log_distance(a: &H256, b: &H256) -> u16 {
+	for (i, (a, b)) in a.as_bytes().iter().zip(b.as_bytes()).enumerate() {
+		let xor = a ^ b;
+		if xor != 0 {
+			return ((32 - i) * 8 - xor.leading_zeros() as usize) as u16;
+		}
+	}
+	0
+}
+
+fn new_request_id() -> Bytes {
+	rand::random::<u64>().to_be_bytes().to_vec()
+}
+
+/// Returns a message consisting of the type and an RLP list of `fields` items.
+fn message<F>(message_type: u8, fields: usize, append: F) -> Bytes where F: FnOnce(&mut RlpStream) {
+	let mut rlp = RlpStream::new_list(fields);
+	append(&mut rlp);
+	let mut message = vec![message_type];
+	message.extend_from_slice(&rlp.out());
+	message
+}
+
+pub struct DiscoveryV5 {
+	id: NodeId,
+	node_id: H256,
+	secret: Secret,
+	enr: Enr,
+	/// Known nodes keyed by node id.
+	nodes: HashMap<H256, Node>,
+	sessions: LruCache<(H256, SocketAddr), Session>,
+	/// Messages waiting for a handshake, keyed by the nonce of the packet which started it.
+	handshakes: HashMap<[u8; NONCE_SIZE], PendingHandshake>,
+	challenges: HashMap<(H256, SocketAddr), Challenge>,
+	requests: HashMap<Bytes, Request>,
+	lookup: Option<Lookup>,
+	talk_handlers: HashMap<Bytes, Arc<dyn TalkHandler>>,
+	/// Ads registered with us, newest last.
+	topic_ads: HashMap<H256, VecDeque<(Enr, Instant)>>,
+	/// Topics we advertise ourselves under, with the time of the last registration.
+	advertised_topics: HashMap<H256, Option<Instant>>,
+	/// Records found by topic queries.
+	topic_results: HashMap<H256, HashMap<H256, Enr>>,
+	/// Messages sent over sessions keyed by their nonce, resent with a new handshake if the
+	/// recipient lost the session and answers with WHOAREYOU.
+	sent_messages: LruCache<[u8; NONCE_SIZE], (H256, SocketAddr, Bytes)>,
+	send_queue: VecDeque<Datagram>,
+	ip_filter: IpFilter,
+}
+
+impl DiscoveryV5 {
+	pub fn new(key: &KeyPair, public: NodeEndpoint, ip_filter: IpFilter) -> Self {
+		DiscoveryV5 {
+			id: *key.public(),
+			node_id: keccak(key.public()),
+			secret: key.secret().clone(),
+			enr: Enr::new(key, &public).expect("the key pair is valid and a record with an endpoint is small enough; qed"),
+			nodes: HashMap::new(),
+			sessions: LruCache::new(MAX_SESSIONS),
+			handshakes: HashMap::new(),
+			challenges: HashMap::new(),
+			requests: HashMap::new(),
+			lookup: None,
+			talk_handlers: HashMap::new(),
+			topic_ads: HashMap::new(),
+			advertised_topics: HashMap::new(),
+			topic_results: HashMap::new(),
+			sent_messages: LruCache::new(MAX_SENT_MESSAGES),
+			send_queue: VecDeque::new(),
+			ip_filter,
+		}
+	}
+
+	/// Replaces our node record.
+	pub fn set_enr(&mut self, enr: Enr) {
+		self.enr = enr;
+	}
+
+	/// Adds a node without a known record.
+	pub fn add_node(&mut self, e: NodeEntry) {
+		let node_id = keccak(e.id);
+		if !self.nodes.contains_key(&node_id) {
+			self.insert_node(node_id, Node { public: e.id, endpoint: e.endpoint, enr: None });
+		}
+	}
+
+	/// Adds a node record, returns whether the record is new.
+	pub fn add_enr(&mut self, enr: Enr) -> bool {
+		let node_id = keccak(enr.id());
+		if self.nodes.get(&node_id).and_then(|n| n.enr.as_ref()).map_or(false, |known| known.seq() >= enr.seq()) {
+			return false;
+		}
+		let endpoint = match enr.endpoint() {
+			Some(endpoint) => endpoint,
+			None => return false,
+		};
+		self.insert_node(node_id, Node { public: *enr.id(), endpoint, enr: Some(enr) })
+	}
+
+	fn insert_node(&mut self, node_id: H256, node: Node) -> bool {
+		if node.public == self.id || !node.endpoint.is_valid_discovery_node() || !node.endpoint.is_allowed(&self.ip_filter) {
+			return false;
+		}
+		if !self.nodes.contains_key(&node_id) {
+			let distance = log_distance(&self.node_id, &node_id);
+			if self.nodes.keys().filter(|id| log_distance(&self.node_id, id) == distance).count() >= BUCKET_SIZE {
+				return false;
+			}
+		}
+		self.nodes.insert(node_id, node);
+		true
+	}
+
+	/// Registers the handler of a TALKREQ protocol.
+	pub fn register_talk_protocol(&mut self, protocol: &[u8], handler: Arc<dyn TalkHandler>) {
+		self.talk_handlers.insert(protocol.to_vec(), handler);
+	}
+
+	/// Sends a TALKREQ, the response is passed to the handler of the protocol.
+	pub fn send_talk_request(&mut self, node: &H256, protocol: &[u8], request: &[u8]) -> Result<(), Error> {
+		let node = self.nodes.get(node).cloned().ok_or(Error::PeerNotFound)?;
+		self.send_request(&node, RequestKind::Talk(protocol.to_vec()), |rlp| {
+			rlp.append(&protocol);
+			rlp.append(&request);
+		})
+	}
+
+	/// Advertises us under the topic.
+	pub fn register_topic(&mut self, topic: H256) {
+		self.advertised_topics.entry(topic).or_insert(None);
+		self.register_topics();
+	}
+
+	/// Asks the nodes closest to the topic for its ads.
+	pub fn query_topic(&mut self, topic: H256) {
+		for node in self.nearest_nodes(&topic, TOPIC_REGISTRARS) {
+			let request = self.send_request(&node, RequestKind::TopicQuery(topic), |rlp| {
+				rlp.append(&topic);
+			});
+			if let Err(e) = request {
+				debug!(target: "discovery", "Error sending topic query: {:?}", e);
+			}
+		}
+	}
+
+	/// Records advertised under the topic, registered with us or found by topic queries.
+	pub fn topic_nodes(&self, topic: &H256) -> Vec<Enr> {
+		let mut records: HashMap<H256, Enr> = self.topic_results.get(topic).cloned().unwrap_or_default();
+		if let Some(ads) = self.topic_ads.get(topic) {
+			records.extend(ads.iter().map(|(enr, _)| (keccak(enr.id()), enr.clone())));
+		}
+		records.into_iter().map(|(_, enr)| enr).collect()
+	}
+
+	fn register_topics(&mut self) {
+		let now = Instant::now();
+		let topics: Vec<_> = self.advertised_topics.iter()
+			.filter(|(_, registered)| registered.map_or(true, |at| now >= at + AD_LIFETIME / 2))
+			.map(|(topic, _)| *topic)
+			.collect();
+		for topic in topics {
+			let registrars = self.nearest_nodes(&topic, TOPIC_REGISTRARS);
+			if registrars.is_empty() {
+				continue;
+			}
+			self.advertised_topics.insert(topic, Some(now));
+			let enr = self.enr.clone();
+			for node in registrars {
+				let request = self.send_request(&node, RequestKind::RegTopic(topic), |rlp| {
+					rlp.append(&topic);
+					rlp.append(&enr);
+					rlp.append_empty_data();
+				});
+				if let Err(e) = request {
+					debug!(target: "discovery", "Error sending topic registration: {:?}", e);
+				}
+			}
+		}
+	}
+
+	/// Returns up to `count` nodes closest to the target.
+	fn nearest_nodes(&self, target: &H256, count: usize) -> Vec<Node> {
+		let mut nodes: Vec<_> = self.nodes.iter().collect();
+		nodes.sort_by_key(|(id, _)| **id ^ *target);
+		nodes.into_iter().take(count).map(|(_, node)| node.clone()).collect()
+	}
+
+	/// Places the ad of the node under the topic, replacing its previous ad and dropping the
+	/// oldest one once the topic has `MAX_TOPIC_ADS`. Returns false if the topic table is full.
+	fn add_topic_ad(&mut self, topic: H256, enr: Enr) -> bool {
+		if !self.topic_ads.contains_key(&topic) && self.topic_ads.len() >= MAX_TOPICS {
+			return false;
+		}
+		let ads = self.topic_ads.entry(topic).or_insert_with(VecDeque::new);
+		ads.retain(|(ad, _)| ad.id() != enr.id());
+		ads.push_back((enr, Instant::now()));
+		if ads.len() > MAX_TOPIC_ADS {
+			ads.pop_front();
+		}
+		true
+	}
+
+	pub fn on_packet(&mut self, packet: &[u8], from: SocketAddr) -> Result<Option<TableUpdates>, Error> {
+		let (header, message) = Header::decode(packet, &self.node_id)?;
+		let records = match header.flag {
+			FLAG_MESSAGE => self.on_message_packet(&header, message, from)?,
+			FLAG_WHOAREYOU => self.on_whoareyou(&header, from)?,
+			FLAG_HANDSHAKE => self.on_handshake(&header, message, from)?,
+			_ => return Err(Error::BadProtocol),
+		};
+		if records.is_empty() {
+			return Ok(None);
+		}
+
+		let mut updates = TableUpdates { added: HashMap::new(), removed: HashSet::new(), records: HashMap::new() };
+		for enr in records {
+			if let Some(endpoint) = enr.endpoint() {
+				updates.added.insert(*enr.id(), NodeEntry { id: *enr.id(), endpoint });
+				updates.records.insert(*enr.id(), enr);
+			}
+		}
+		Ok(Some(updates))
+	}
+
+	fn on_message_packet(&mut self, header: &Header, message: &[u8], from: SocketAddr) -> Result<Vec<Enr>, Error> {
+		if header.authdata.len() != 32 {
+			return Err(Error::BadProtocol);
+		}
+		let src = H256::from_slice(&header.authdata);
+		let plain = self.sessions.get_mut(&(src, from))
+			.and_then(|session| decrypt_message(&session.read_key, &header.nonce, message, &header.message_ad()).ok());
+		match plain {
+			Some(plain) => self.on_message(&src, from, &plain),
+			None => {
+				self.send_whoareyou(src, from, header.nonce)?;
+				Ok(Vec::new())
+			}
+		}
+	}
+
+	fn send_whoareyou(&mut self, node_id: H256, address: SocketAddr, nonce: [u8; NONCE_SIZE]) -> Result<(), Error> {
+		if self.challenges.contains_key(&(node_id, address)) {
+			return Ok(());
+		}
+		let enr_seq = self.nodes.get(&node_id).and_then(|n| n.enr.as_ref()).map_or(0, Enr::seq);
+		let mut authdata = rand::random::<[u8; ID_NONCE_SIZE]>().to_vec();
+		authdata.extend_from_slice(&enr_seq.to_be_bytes());
+
+		trace!(target: "discovery", "Sending WHOAREYOU to {:?}", address);
+		let header = Header::new(FLAG_WHOAREYOU, nonce, authdata);
+		let packet = header.encode_masked(&node_id)?;
+		self.challenges.insert((node_id, address), Challenge { data: header.message_ad(), sent_at: Instant::now() });
+		self.send_to(packet, address);
+		Ok(())
+	}
+
+	fn on_whoareyou(&mut self, header: &Header, from: SocketAddr) -> Result<Vec<Enr>, Error> {
+		if header.authdata.len() != ID_NONCE_SIZE + 8 {
+			return Err(Error::BadProtocol);
+		}
+		let pending = match self.handshakes.remove(&header.nonce) {
+			Some(ref pending) if pending.node.endpoint.udp_address() != from => return Err(Error::BadProtocol),
+			Some(pending) => pending,
+			None => match self.restart_handshake(&header.nonce, from) {
+				Some(pending) => pending,
+				None => {
+					debug!(target: "discovery", "Unexpected WHOAREYOU from {:?}", from);
+					return Ok(Vec::new());
+				}
+			},
+		};
+		let mut enr_seq = [0u8; 8];
+		enr_seq.copy_from_slice(&header.authdata[ID_NONCE_SIZE..]);
+		let enr_seq = u64::from_be_bytes(enr_seq);
+
+		let challenge_data = header.message_ad();
+		let remote_id = keccak(pending.node.public);
+		let ephemeral = Random.generate()?;
+		let ephemeral_public = compress(ephemeral.public());
+		let (initiator_key, recipient_key) = derive_keys(&ecdh(&pending.node.public, ephemeral.secret())?, &challenge_data, &self.node_id, &remote_id)?;
+		let signature = sign(&self.secret, &id_signature_hash(&challenge_data, &ephemeral_public, &remote_id))?;
+
+		let mut authdata = self.node_id.as_bytes().to_vec();
+		authdata.push(64);
+		authdata.push(ephemeral_public.len() as u8);
+		authdata.extend_from_slice(&signature[..64]);
+		authdata.extend_from_slice(&ephemeral_public);
+		if enr_seq < self.enr.seq() {
+			authdata.extend_from_slice(&rlp::encode(&self.enr));
+		}
+
+		trace!(target: "discovery", "Sending handshake to {:?}", from);
+		let header = Header::new(FLAG_HANDSHAKE, rand::random(), authdata);
+		let mut packet = header.encode_masked(&remote_id)?;
+		packet.extend_from_slice(&encrypt_message(&initiator_key, &header.nonce, &pending.messages[0], &header.message_ad())?);
+		self.sessions.insert((remote_id, from), Session { write_key: initiator_key, read_key: recipient_key });
+		self.send_to(packet, from);
+		for message in &pending.messages[1..] {
+			let packet = self.encode_message(&remote_id, from, &initiator_key, message)?;
+			self.send_to(packet, from);
+		}
+		Ok(Vec::new())
+	}
+
+	/// Drops the session the node answered a message of with WHOAREYOU, the message is resent
+	/// with a new handshake.
+	fn restart_handshake(&mut self, nonce: &[u8; NONCE_SIZE], from: SocketAddr) -> Option<PendingHandshake> {
+		let (node_id, address, message) = self.sent_messages.remove(nonce)?;
+		if address != from {
+			return None;
+		}
+		let node = self.nodes.get(&node_id).cloned()?;
+		trace!(target: "discovery", "Node {:?} lost the session, restarting the handshake", node_id);
+		self.sessions.remove(&(node_id, from));
+		Some(PendingHandshake { node, messages: vec![message], sent_at: Instant::now() })
+	}
+
+	fn on_handshake(&mut self, header: &Header, message: &[u8], from: SocketAddr) -> Result<Vec<Enr>, Error> {
+		let authdata = &header.authdata;
+		if authdata.len() < 34 || authdata[32] != 64 || authdata[33] != 33 || authdata.len() < 34 + 64 + 33 {
+			return Err(Error::BadProtocol);
+		}
+		let src = H256::from_slice(&authdata[..32]);
+		let signature = &authdata[34..98];
+		let ephemeral_public = &authdata[98..131];
+		let record = &authdata[131..];
+
+		let challenge = match self.challenges.remove(&(src, from)) {
+			Some(challenge) => challenge,
+			None => {
+				debug!(target: "discovery", "Unexpected handshake from {:?}", from);
+				return Ok(Vec::new());
+			}
+		};
+		let enr = if record.is_empty() { None } else { Some(rlp::decode::<Enr>(record)?) };
+		let public = match enr {
+			Some(ref enr) if keccak(enr.id()) == src => *enr.id(),
+			Some(_) => return Err(Error::BadProtocol),
+			None => self.nodes.get(&src).map(|n| n.public).ok_or(Error::BadProtocol)?,
+		};
+		let signature_hash = id_signature_hash(&challenge.data, ephemeral_public, &self.node_id);
+		if recover_signer(signature, &signature_hash, &compress(&public)).is_none() {
+			return Err(Error::Auth);
+		}
+
+		let ephemeral_public = decompress(ephemeral_public).ok_or(Error::BadProtocol)?;
+		let (initiator_key, recipient_key) = derive_keys(&ecdh(&ephemeral_public, &self.secret)?, &challenge.data, &src, &self.node_id)?;
+		let plain = decrypt_message(&initiator_key, &header.nonce, message, &header.message_ad())?;
+		self.sessions.insert((src, from), Session { write_key: recipient_key, read_key: initiator_key });
+
+		let mut records = Vec::new();
+		if let Some(enr) = enr {
+			if self.add_enr(enr.clone()) {
+				records.push(enr);
+			}
+		}
+		records.extend(self.on_message(&src, from, &plain)?);
+		Ok(records)
+	}
+
+	fn on_message(&mut self, src: &H256, from: SocketAddr, message: &[u8]) -> Result<Vec<Enr>, Error> {
+		if message.is_empty() {
+			return Err(Error::BadProtocol);
+		}
+		let rlp = Rlp::new(&message[1..]);
+		let request_id: Bytes = rlp.val_at(0)?;
+		if request_id.len() > MAX_REQUEST_ID_SIZE {
+			return Err(Error::BadProtocol);
+		}
+		match message[0] {
+			MESSAGE_PING => self.on_ping(&rlp, src, from, request_id),
+			MESSAGE_PONG => self.on_pong(&rlp, src, request_id),
+			MESSAGE_FIND_NODE => self.on_find_node(&rlp, src, from, request_id),
+			MESSAGE_NODES => self.on_nodes(&rlp, src, request_id),
+			MESSAGE_TALK_REQUEST => self.on_talk_request(&rlp, src, from, request_id),
+			MESSAGE_TALK_RESPONSE => self.on_talk_response(&rlp, src, request_id),
+			MESSAGE_REG_TOPIC => self.on_reg_topic(&rlp, src, from, request_id),
+			MESSAGE_TICKET => {
+				trace!(target: "discovery", "Got topic ticket from {:?}", from);
+				Ok(Vec::new())
+			},
+			MESSAGE_REG_CONFIRMATION => self.on_reg_confirmation(&rlp, src, request_id),
+			MESSAGE_TOPIC_QUERY => self.on_topic_query(&rlp, src, from, request_id),
+			message_type => {
+				debug!(target: "discovery", "Unknown v5 message: {}", message_type);
+				Ok(Vec::new())
+			}
+		}
+	}
+
+	/// Takes the request a response belongs to.
+	fn take_request(&mut self, src: &H256, request_id: &[u8]) -> Option<Request> {
+		match self.requests.get(request_id) {
+			Some(request) if request.node == *src => self.requests.remove(request_id),
+			_ => {
+				debug!(target: "discovery", "Unexpected response from {:?}", src);
+				None
+			}
+		}
+	}
+
+	/// Asks the node for its record if it's newer than the known one.
+	fn check_enr_seq(&mut self, src: &H256, seq: u64) -> Result<(), Error> {
+		let node = match self.nodes.get(src) {
+			Some(node) if node.enr.as_ref().map_or(true, |enr| enr.seq() < seq) => node.clone(),
+			_ => return Ok(()),
+		};
+		self.send_request(&node, RequestKind::FindNode { distances: vec![0], lookup: false }, |rlp| {
+			rlp.begin_list(1).append(&0u16);
+		})
+	}
+
+	fn on_ping(&mut self, rlp: &Rlp, src: &H256, from: SocketAddr, request_id: Bytes) -> Result<Vec<Enr>, Error> {
+		trace!(target: "discovery", "Got v5 PING from {:?}", from);
+		let enr_seq: u64 = rlp.val_at(1)?;
+		let ip = match from.ip() {
+			IpAddr::V4(ip) => ip.octets().to_vec(),
+			IpAddr::V6(ip) => ip.octets().to_vec(),
+		};
+		let seq = self.enr.seq();
+		self.respond(src, from, message(MESSAGE_PONG, 4, |rlp| {
+			rlp.append(&request_id);
+			rlp.append(&seq);
+			rlp.append(&ip);
+			rlp.append(&from.port());
+		}))?;
+		self.check_enr_seq(src, enr_seq)?;
+		Ok(Vec::new())
+	}
+
+	fn on_pong(&mut self, rlp: &Rlp, src: &H256, request_id: Bytes) -> Result<Vec<Enr>, Error> {
+		trace!(target: "discovery", "Got v5 PONG from {:?}", src);
+		if self.take_request(src, &request_id).map_or(false, |request| request.kind == RequestKind::Ping) {
+			let enr_seq: u64 = rlp.val_at(1)?;
+			self.check_enr_seq(src, enr_seq)?;
+		}
+		Ok(Vec::new())
+	}
+
+	fn on_find_node(&mut self, rlp: &Rlp, src: &H256, from: SocketAddr, request_id: Bytes) -> Result<Vec<Enr>, Error> {
+		trace!(target: "discovery", "Got v5 FINDNODE from {:?}", from);
+		let distances: Vec<u16> = rlp.list_at(1)?;
+		let mut records = Vec::new();
+		for distance in distances.into_iter().collect::<HashSet<_>>() {
+			if distance == 0 {
+				records.push(self.enr.clone());
+			} else {
+				records.extend(self.nodes.iter()
+					.filter(|(id, _)| log_distance(&self.node_id, id) == distance)
+					.filter_map(|(_, node)| node.enr.clone()));
+			}
+		}
+		records.truncate(MAX_NODES_RESPONSE);
+		self.send_records(src, from, &request_id, &records)?;
+		Ok(Vec::new())
+	}
+
+	/// Sends NODES responses with the records.
+	fn send_records(&mut self, src: &H256, from: SocketAddr, request_id: &[u8], records: &[Enr]) -> Result<(), Error> {
+		let chunks: Vec<_> = if records.is_empty() {
+			vec![&records[..]]
+		} else {
+			records.chunks(RECORDS_PER_PACKET).collect()
+		};
+		let total = chunks.len() as u64;
+		for chunk in chunks {
+			self.respond(src, from, message(MESSAGE_NODES, 3, |rlp| {
+				rlp.append(&request_id);
+				rlp.append(&total);
+				rlp.append_list(chunk);
+			}))?;
+		}
+		Ok(())
+	}
+
+	fn on_nodes(&mut self, rlp: &Rlp, src: &H256, request_id: Bytes) -> Result<Vec<Enr>, Error> {
+		trace!(target: "discovery", "Got v5 NODES from {:?}", src);
+		let total: u64 = rlp.val_at(1)?;
+		let kind = match self.requests.get_mut(&request_id) {
+			Some(request) if request.node == *src => {
+				request.responses += 1;
+				request.kind.clone()
+			},
+			_ => {
+				debug!(target: "discovery", "Unexpected NODES from {:?}", src);
+				return Ok(Vec::new());
+			}
+		};
+		if self.requests.get(&request_id).map_or(false, |request| request.responses >= total) {
+			self.requests.remove(&request_id);
+		}
+
+		let mut records = Vec::new();
+		for enr in rlp.at(2)?.iter().take(MAX_NODES_RESPONSE).map(|rlp| rlp.as_val::<Enr>()) {
+			let enr = enr?;
+			let node_id = keccak(enr.id());
+			match kind {
+				RequestKind::FindNode { ref distances, .. } => {
+					let distance = log_distance(src, &node_id);
+					if !distances.contains(&distance) {
+						debug!(target: "discovery", "Node {:?} sent a record at unrequested distance {}", src, distance);
+						continue;
+					}
+				},
+				RequestKind::TopicQuery(topic) => {
+					let results = self.topic_results.entry(topic).or_insert_with(HashMap::new);
+					if results.len() < MAX_TOPIC_ADS {
+						results.insert(node_id, enr.clone());
+					}
+				},
+				_ => return Err(Error::BadProtocol),
+			}
+			if self.add_enr(enr.clone()) {
+				records.push(enr);
+			}
+		}
+		Ok(records)
+	}
+
+	fn on_talk_request(&mut self, rlp: &Rlp, src: &H256, from: SocketAddr, request_id: Bytes) -> Result<Vec<Enr>, Error> {
+		let protocol: Bytes = rlp.val_at(1)?;
+		let request: Bytes = rlp.val_at(2)?;
+		// unknown protocols are answered with an empty response
+		let response = self.talk_handlers.get(&protocol)
+			.map_or_else(Bytes::new, |handler| handler.on_request(src, &request));
+		self.respond(src, from, message(MESSAGE_TALK_RESPONSE, 2, |rlp| {
+			rlp.append(&request_id);
+			rlp.append(&response);
+		}))?;
+		Ok(Vec::new())
+	}
+
+	fn on_talk_response(&mut self, rlp: &Rlp, src: &H256, request_id: Bytes) -> Result<Vec<Enr>, Error> {
+		if let Some(RequestKind::Talk(protocol)) = self.take_request(src, &request_id).map(|request| request.kind) {
+			let response: Bytes = rlp.val_at(1)?;
+			if let Some(handler) = self.talk_handlers.get(&protocol) {
+				handler.on_response(src, &response);
+			}
+		}
+		Ok(Vec::new())
+	}
+
+	fn on_reg_topic(&mut self, rlp: &Rlp, src: &H256, from: SocketAddr, request_id: Bytes) -> Result<Vec<Enr>, Error> {
+		let topic: H256 = rlp.val_at(1)?;
+		let enr: Enr = rlp.val_at(2)?;
+		if keccak(enr.id()) != *src {
+			return Err(Error::BadProtocol);
+		}
+		if !self.add_topic_ad(topic, enr) {
+			debug!(target: "discovery", "Topic table is full, ignoring registration from {:?}", from);
+			return Ok(Vec::new());
+		}
+		trace!(target: "discovery", "Registered {:?} for topic {:?}", src, topic);
+
+		// the ad is placed right away, the ticket is empty
+		self.respond(src, from, message(MESSAGE_TICKET, 3, |rlp| {
+			rlp.append(&request_id);
+			rlp.append_empty_data();
+			rlp.append(&0u64);
+		}))?;
+		self.respond(src, from, message(MESSAGE_REG_CONFIRMATION, 2, |rlp| {
+			rlp.append(&request_id);
+			rlp.append(&topic);
+		}))?;
+		Ok(Vec::new())
+	}
+
+	fn on_reg_confirmation(&mut self, rlp: &Rlp, src: &H256, request_id: Bytes) -> Result<Vec<Enr>, Error> {
+		let topic: H256 = rlp.val_at(1)?;
+		match self.take_request(src, &request_id) {
+			Some(Request { kind: RequestKind::RegTopic(requested), .. }) if requested == topic => {
+				trace!(target: "discovery", "Registered for topic {:?} with {:?}", topic, src);
+			},
+			_ => debug!(target: "discovery", "Unexpected topic confirmation from {:?}", src),
+		}
+		Ok(Vec::new())
+	}
+
+	fn on_topic_query(&mut self, rlp: &Rlp, src: &H256, from: SocketAddr, request_id: Bytes) -> Result<Vec<Enr>, Error> {
+		let topic: H256 = rlp.val_at(1)?;
+		let records: Vec<_> = self.topic_ads.get(&topic)
+			.map(|ads| ads.iter().rev().take(MAX_NODES_RESPONSE).map(|(enr, _)| enr.clone()).collect())
+			.unwrap_or_default();
+		self.send_records(src, from, &request_id, &records)?;
+		Ok(Vec::new())
+	}
+
+	/// Sends a response over an established session.
+	fn respond(&mut self, node_id: &H256, address: SocketAddr, message: Bytes) -> Result<(), Error> {
+		let write_key = self.sessions.get_mut(&(*node_id, address)).map(|session| session.write_key).ok_or(Error::Auth)?;
+		let packet = self.encode_message(node_id, address, &write_key, &message)?;
+		self.send_to(packet, address);
+		Ok(())
+	}
+
+	fn encode_message(&mut self, node_id: &H256, address: SocketAddr, key: &[u8; 16], message: &[u8]) -> Result<Bytes, Error> {
+		let header = Header::new(FLAG_MESSAGE, rand::random(), self.node_id.as_bytes().to_vec());
+		let mut packet = header.encode_masked(node_id)?;
+		packet.extend_from_slice(&encrypt_message(key, &header.nonce, message, &header.message_ad())?);
+		self.sent_messages.insert(header.nonce, (*node_id, address, message.to_vec()));
+		Ok(packet)
+	}
+
+	/// Sends a request, starting a handshake if there is no session with the node.
+	fn send_request<F>(&mut self, node: &Node, kind: RequestKind, append: F) -> Result<(), Error> where F: FnOnce(&mut RlpStream) {
+		let (message_type, fields) = match kind {
+			RequestKind::Ping => (MESSAGE_PING, 2),
+			RequestKind::FindNode { .. } => (MESSAGE_FIND_NODE, 2),
+			RequestKind::Talk(_) => (MESSAGE_TALK_REQUEST, 3),
+			RequestKind::RegTopic(_) => (MESSAGE_REG_TOPIC, 4),
+			RequestKind::TopicQuery(_) => (MESSAGE_TOPIC_QUERY, 2),
+		};
+		let request_id = new_request_id();
+		let message = message(message_type, fields, |rlp| {
+			rlp.append(&request_id);
+			append(rlp);
+		});
+
+		let node_id = keccak(node.public);
+		let address = node.endpoint.udp_address();
+		let write_key = self.sessions.get_mut(&(node_id, address)).map(|session| session.write_key);
+		match write_key {
+			Some(key) => {
+				let packet = self.encode_message(&node_id, address, &key, &message)?;
+				self.send_to(packet, address);
+			},
+			None => match self.handshakes.values_mut().find(|pending| keccak(pending.node.public) == node_id) {
+				Some(pending) => pending.messages.push(message),
+				None => {
+					// a packet the node can't decrypt, answered with WHOAREYOU
+					let nonce = rand::random();
+					let header = Header::new(FLAG_MESSAGE, nonce, self.node_id.as_bytes().to_vec());
+					let mut packet = header.encode_masked(&node_id)?;
+					packet.extend_from_slice(&rand::random::<[u8; RANDOM_MESSAGE_SIZE]>());
+					self.handshakes.insert(nonce, PendingHandshake { node: node.clone(), messages: vec![message], sent_at: Instant::now() });
+					self.send_to(packet, address);
+				},
+			},
+		}
+		self.requests.insert(request_id, Request { node: node_id, kind, sent_at: Instant::now(), responses: 0 });
+		Ok(())
+	}
+
+	fn send_find_node(&mut self, node: &Node, distances: Vec<u16>, lookup: bool) {
+		let request = self.send_request(node, RequestKind::FindNode { distances: distances.clone(), lookup }, |rlp| {
+			rlp.append_list(&distances);
+		});
+		if let Err(e) = request {
+			debug!(target: "discovery", "Error sending FINDNODE: {:?}", e);
+		}
+	}
+
+	/// Pings the node, its record is requested if the PONG announces a newer one.
+	fn ping(&mut self, node: &Node) {
+		let seq = self.enr.seq();
+		let request = self.send_request(node, RequestKind::Ping, |rlp| {
+			rlp.append(&seq);
+		});
+		if let Err(e) = request {
+			debug!(target: "discovery", "Error sending v5 PING: {:?}", e);
+		}
+	}
+
+	fn send_to(&mut self, payload: Bytes, address: SocketAddr) {
+		self.send_queue.push_back(Datagram { payload, address });
+	}
+
+	/// Continues the current lookup.
+	fn discover(&mut self) {
+		let (target, asked) = match self.lookup {
+			Some(ref mut lookup) if lookup.steps < LOOKUP_STEPS => {
+				lookup.steps += 1;
+				(lookup.target, lookup.asked.clone())
+			},
+			_ => {
+				self.lookup = None;
+				return;
+			},
+		};
+		let mut candidates: Vec<_> = self.nodes.iter().filter(|(id, _)| !asked.contains(*id)).collect();
+		candidates.sort_by_key(|(id, _)| **id ^ target);
+		let candidates: Vec<_> = candidates.into_iter().take(ALPHA).map(|(id, node)| (*id, node.clone())).collect();
+		if candidates.is_empty() {
+			trace!(target: "discovery", "v5 lookup of {:?} completed", target);
+			self.lookup = None;
+			return;
+		}
+
+		for (node_id, node) in candidates {
+			if let Some(ref mut lookup) = self.lookup {
+				lookup.asked.insert(node_id);
+			}
+			// ask for the distance of the target and its neighbours
+			let distance = log_distance(&node_id, &target);
+			let distances = [distance, distance.saturating_add(1), distance.saturating_sub(1)].iter()
+				.cloned()
+				.filter(|d| *d > 0 && *d <= 256)
+				.collect::<HashSet<_>>()
+				.into_iter()
+				.collect();
+			self.send_find_node(&node, distances, true);
+		}
+	}
+
+	fn check_expired(&mut self, now: Instant) {
+		let mut failed = Vec::new();
+		self.requests.retain(|_, request| {
+			let expired = now >= request.sent_at + REQUEST_TIMEOUT;
+			// lookups can end with fewer NODES responses than announced
+			if expired && request.responses == 0 {
+				failed.push(request.node);
+			}
+			!expired
+		});
+		self.handshakes.retain(|_, handshake| {
+			let expired = now >= handshake.sent_at + REQUEST_TIMEOUT;
+			if expired {
+				failed.push(keccak(handshake.node.public));
+			}
+			!expired
+		});
+		self.challenges.retain(|_, challenge| now < challenge.sent_at + REQUEST_TIMEOUT);
+		for ads in self.topic_ads.values_mut() {
+			ads.retain(|(_, registered_at)| now < *registered_at + AD_LIFETIME);
+		}
+		self.topic_ads.retain(|_, ads| !ads.is_empty());
+
+		for node_id in failed {
+			debug!(target: "discovery", "Removing unresponsive v5 node {:?}", node_id);
+			// the node may have lost the session, a new one is established if it comes back
+			if let Some(node) = self.nodes.remove(&node_id) {
+				self.sessions.remove(&(node_id, node.endpoint.udp_address()));
+			}
+		}
+	}
+
+	pub fn round(&mut self) {
+		self.check_expired(Instant::now());
+		let lookup_pending = self.requests.values().any(|request| match request.kind {
+			RequestKind::FindNode { lookup, .. } => lookup,
+			_ => false,
+		});
+		if !lookup_pending {
+			self.discover();
+		}
+	}
+
+	/// Starts a lookup of a random target, fetches missing records and renews our topic ads.
+	pub fn refresh(&mut self) {
+		if self.lookup.is_none() {
+			self.lookup = Some(Lookup { target: H256::random(), asked: HashSet::new(), steps: 0 });
+		}
+		let without_record: Vec<_> = self.nodes.values().filter(|node| node.enr.is_none()).take(ALPHA).cloned().collect();
+		for node in without_record {
+			self.ping(&node);
+		}
+		self.register_topics();
+	}
+
+	pub fn any_sends_queued(&self) -> bool {
+		!self.send_queue.is_empty()
+	}
+
+	pub fn dequeue_send(&mut self) -> Option<Datagram> {
+		self.send_queue.pop_front()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+
+	use parking_lot::Mutex;
+	use rustc_hex::FromHex;
+
+	use super::*;
+
+	fn discovery(port: u16) -> (DiscoveryV5, NodeEntry) {
+		let key = Random.generate().unwrap();
+		let endpoint = NodeEndpoint { address: SocketAddr::from_str(&format!("127.0.0.1:{}", port)).unwrap(), udp_port: port };
+		let entry = NodeEntry { id: *key.public(), endpoint: endpoint.clone() };
+		(DiscoveryV5::new(&key, endpoint, IpFilter::default()), entry)
+	}
+
+	/// Delivers queued packets until both sides are idle, returns the table updates of `a`.
+	fn exchange(a: &mut DiscoveryV5, a_address: SocketAddr, b: &mut DiscoveryV5, b_address: SocketAddr) -> Vec<TableUpdates> {
+		let mut updates = Vec::new();
+		loop {
+			let mut idle = true;
+			while let Some(datagram) = a.dequeue_send() {
+				idle = false;
+				assert_eq!(datagram.address, b_address);
+				b.on_packet(&datagram.payload, a_address).unwrap();
+			}
+			while let Some(datagram) = b.dequeue_send() {
+				idle = false;
+				assert_eq!(datagram.address, a_address);
+				updates.extend(a.on_packet(&datagram.payload, b_address).unwrap());
+			}
+			if idle {
+				return updates;
+			}
+		}
+	}
+
+	#[test]
+	fn derives_session_keys() {
+		// test vector of the discv5 specification
+		let ephemeral = Secret::from_str("fb757dc581730490a1d7a00deea65e9b1936924caaea8f44d476014856b68736").unwrap();
+		let public = decompress(&"0317931e6e0840220642f230037d285d122bc59063221ef3226b1f403ddc69ca91".from_hex::<Bytes>().unwrap()).unwrap();
+		let challenge_data: Bytes = "000000000000000000000000000000006469736376350001010102030405060708090a0b0c00180102030405060708090a0b0c0d0e0f100000000000000000".from_hex().unwrap();
+		let node_a = H256::from_str("aaaa8419e9f49d0083561b48287df592939a8d19947d8c0ef88f2a4856a69fbb").unwrap();
+		let node_b = H256::from_str("bbbb9d047f0488c0b5a93c1c3f2d8bafc7c8ff337024a55434a0d0555de64db9").unwrap();
+
+		let (initiator_key, recipient_key) = derive_keys(&ecdh(&public, &ephemeral).unwrap(), &challenge_data, &node_a, &node_b).unwrap();
+		assert_eq!(initiator_key.to_vec(), "dccc82d81bd610f4f76d3ebe97a40571".from_hex::<Bytes>().unwrap());
+		assert_eq!(recipient_key.to_vec(), "ac74bb8773749920b0d3a8881c173ec5".from_hex::<Bytes>().unwrap());
+	}
+
+	#[test]
+	fn masks_packet_headers() {
+		let recipient = H256::random();
+		let header = Header::new(FLAG_WHOAREYOU, rand::random(), vec![7u8; ID_NONCE_SIZE + 8]);
+		let packet = header.encode_masked(&recipient).unwrap();
+		assert_eq!(packet.len(), MIN_PACKET_SIZE);
+
+		let (decoded, message) = Header::decode(&packet, &recipient).unwrap();
+		assert_eq!(decoded.message_ad(), header.message_ad());
+		assert!(message.is_empty());
+		assert!(Header::decode(&packet, &H256::random()).is_err());
+	}
+
+	#[test]
+	fn log_distances() {
+		let a = H256::zero();
+		assert_eq!(log_distance(&a, &a), 0);
+		assert_eq!(log_distance(&a, &H256::from_low_u64_be(1)), 1);
+		assert_eq!(log_distance(&a, &H256::from_low_u64_be(0x80)), 8);
+		let mut far = H256::zero();
+		far.as_bytes_mut()[0] = 0x80;
+		assert_eq!(log_distance(&a, &far), 256);
+	}
+
+	#[test]
+	fn handshake_and_find_node() {
+		let (mut discovery1, entry1) = discovery(40444);
+		let (mut discovery2, entry2) = discovery(40445);
+		let (address1, address2) = (entry1.endpoint.udp_address(), entry2.endpoint.udp_address());
+		let (_, entry3) = discovery(40446);
+		let enr3 = {
+			let key = Random.generate().unwrap();
+			Enr::new(&key, &entry3.endpoint).unwrap()
+		};
+		discovery2.add_enr(enr3.clone());
+
+		discovery1.add_node(entry2.clone());
+		let node2 = keccak(entry2.id);
+		let distance = log_distance(&node2, &keccak(enr3.id()));
+		let node = discovery1.nodes[&node2].clone();
+		discovery1.send_find_node(&node, vec![0, distance], false);
+
+		// random packet, WHOAREYOU, handshake and the NODES response
+		let updates = exchange(&mut discovery1, address1, &mut discovery2, address2);
+		let records: HashSet<_> = updates.into_iter().flat_map(|u| u.records.into_iter().map(|(id, _)| id)).collect();
+		assert_eq!(records, vec![entry2.id, *enr3.id()].into_iter().collect());
+		assert!(discovery1.requests.is_empty());
+		assert!(discovery1.handshakes.is_empty());
+		assert!(discovery2.challenges.is_empty());
+
+		// the session is reused
+		discovery1.ping(&node);
+		assert!(discovery1.handshakes.is_empty());
+		exchange(&mut discovery1, address1, &mut discovery2, address2);
+		assert!(discovery1.requests.is_empty());
+	}
+
+	#[test]
+	fn restarts_handshake_of_lost_session() {
+		let (mut discovery1, entry1) = discovery(40449);
+		let (mut discovery2, entry2) = discovery(40450);
+		let (address1, address2) = (entry1.endpoint.udp_address(), entry2.endpoint.udp_address());
+		discovery1.add_node(entry2.clone());
+		let node = discovery1.nodes[&keccak(entry2.id)].clone();
+		discovery1.ping(&node);
+		exchange(&mut discovery1, address1, &mut discovery2, address2);
+		assert!(discovery1.requests.is_empty());
+
+		// the node forgets the session and answers the next PING with WHOAREYOU
+		discovery2.sessions = LruCache::new(MAX_SESSIONS);
+		discovery1.ping(&node);
+		exchange(&mut discovery1, address1, &mut discovery2, address2);
+		assert!(discovery1.requests.is_empty());
+		assert!(discovery1.handshakes.is_empty());
+		assert!(discovery2.challenges.is_empty());
+		assert_eq!(discovery2.sessions.len(), 1);
+	}
+
+	#[test]
+	fn talk_requests() {
+		#[derive(Default)]
+		struct Echo(Mutex<Vec<Bytes>>);
+
+		impl TalkHandler for Echo {
+			fn on_request(&self, _node: &H256, request: &[u8]) -> Bytes {
+				request.to_vec()
+			}
+
+			fn on_response(&self, _node: &H256, response: &[u8]) {
+				self.0.lock().push(response.to_vec());
+			}
+		}
+
+		let (mut discovery1, entry1) = discovery(40447);
+		let (mut discovery2, entry2) = discovery(40448);
+		let echo = Arc::new(Echo::default());
+		discovery1.register_talk_protocol(b"echo", echo.clone());
+		discovery2.register_talk_protocol(b"echo", echo.clone());
+
+		discovery1.add_node(entry2.clone());
+		discovery1.send_talk_request(&keccak(entry2.id), b"echo", b"hello").unwrap();
+		discovery1.send_talk_request(&keccak(entry2.id), b"unknown", b"hello").unwrap();
+		exchange(&mut discovery1, entry1.endpoint.udp_address(), &mut discovery2, entry2.endpoint.udp_address());
+		assert_eq!(*echo.0.lock(), vec![b"hello".to_vec()]);
+		assert!(discovery1.requests.is_empty());
+	}
+
+	#[test]
+	fn topic_advertisement() {
+		let (mut discovery1, entry1) = discovery(40451);
+		let (mut registrar, registrar_entry) = discovery(40452);
+		let (address1, registrar_address) = (entry1.endpoint.udp_address(), registrar_entry.endpoint.udp_address());
+		let topic = keccak("eth");
+
+		discovery1.add_node(registrar_entry.clone());
+		discovery1.register_topic(topic);
+		exchange(&mut discovery1, address1, &mut registrar, registrar_address);
+		assert!(discovery1.requests.is_empty());
+		assert_eq!(registrar.topic_nodes(&topic).iter().map(|enr| *enr.id()).collect::<Vec<_>>(), vec![entry1.id]);
+
+		// a third node queries the registrar
+		let (mut discovery3, entry3) = discovery(40453);
+		discovery3.add_node(registrar_entry);
+		discovery3.query_topic(topic);
+		exchange(&mut discovery3, entry3.endpoint.udp_address(), &mut registrar, registrar_address);
+		assert_eq!(discovery3.topic_nodes(&topic).iter().map(|enr| *enr.id()).collect::<Vec<_>>(), vec![entry1.id]);
+	}
+
+	#[test]
+	fn topic_ads_are_bounded() {
+		let (mut registrar, _) = discovery(40454);
+		let topic = keccak("eth");
+		let records: Vec<_> = (0..=MAX_TOPIC_ADS).map(|i| discovery(40455 + i as u16).0.enr).collect();
+
+		for enr in &records {
+			assert!(registrar.add_topic_ad(topic, enr.clone()));
+		}
+		// the oldest ad made room for the newest
+		assert_eq!(registrar.topic_ads[&topic].len(), MAX_TOPIC_ADS);
+		assert!(registrar.topic_ads[&topic].iter().all(|(enr, _)| enr.id() != records[0].id()));
+
+		// renewing an ad doesn't duplicate it
+		assert!(registrar.add_topic_ad(topic, records[1].clone()));
+		assert_eq!(registrar.topic_ads[&topic].len(), MAX_TOPIC_ADS);
+		assert_eq!(registrar.topic_ads[&topic].back().unwrap().0.id(), records[1].id());
+
+		for i in 1..MAX_TOPICS {
+			assert!(registrar.add_topic_ad(H256::from_low_u64_be(i as u64), records[0].clone()));
+		}
+		assert!(!registrar.add_topic_ad(H256::from_low_u64_be(MAX_TOPICS as u64), records[0].clone()));
+		assert_eq!(registrar.topic_ads.len(), MAX_TOPICS);
+	}
+}
@@ -0,0 +1,666 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! DNS based node discovery as defined by EIP-1459.
+//!
+//! Node lists are published as a Merkle tree of TXT records below a domain. The root entry is
+//! signed by the list operator, every other entry is authenticated by its hash in the parent branch.
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::fs;
+use std::iter;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use keccak_hash::keccak;
+use log::{debug, trace};
+use parity_bytes::Bytes;
+use parity_crypto::publickey::{KeyPair, sign};
+
+use network::Error;
+
+use crate::enr::{compress, recover_signer, Enr, ENR_PREFIX};
+use crate::node_table::NodeId;
+
+/// Prefix of a tree location.
+pub const TREE_PREFIX: &str = "enrtree://";
+const ROOT_PREFIX: &str = "enrtree-root:v1";
+const BRANCH_PREFIX: &str = "enrtree-branch:";
+
+/// Maximal number of hashes in a branch, keeps the entries within a single DNS packet.
+const MAX_BRANCH_HASHES: usize = 13;
+/// Number of bytes of the entry hash used as its subdomain.
+const HASH_SIZE: usize = 16;
+/// How often the root of a completely synced tree is checked for updates.
+const ROOT_RECHECK_INTERVAL: Duration = Duration::from_secs(30 * 60);
+const DNS_PORT: u16 = 53;
+const DNS_TIMEOUT: Duration = Duration::from_secs(5);
+const DNS_TYPE_TXT: u16 = 16;
+const MAX_DNS_PACKET_SIZE: usize = 512;
+const RESOLV_CONF: &str = "/etc/resolv.conf";
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Source of DNS TXT records.
+pub trait DnsResolver: Send + Sync {
+	/// Returns the TXT records of the given name.
+	fn txt(&self, name: &str) -> Result<Vec<String>, Error>;
+}
+
+/// Resolver sending queries to the first name server of the system configuration.
+pub struct SystemResolver {
+	server: SocketAddr,
+}
+
+impl Default for SystemResolver {
+	fn default() -> Self {
+		let server = fs::read_to_string(RESOLV_CONF).ok()
+			.and_then(|conf| conf.lines()
+				.filter_map(|line| {
+					let mut fields = line.split_whitespace();
+					match fields.next() {
+						Some("nameserver") => fields.next().and_then(|ip| IpAddr::from_str(ip).ok()),
+						_ => None,
+					}
+				})
+				.next())
+			.unwrap_or_else(|| IpAddr::V4(Ipv4Addr::LOCALHOST));
+		SystemResolver { server: SocketAddr::new(server, DNS_PORT) }
+	}
+}
+
+impl DnsResolver for SystemResolver {
+	fn txt(&self, name: &str) -> Result<Vec<String>, Error> {
+		let local: SocketAddr = if self.server.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" }.parse()?;
+		let socket = UdpSocket::bind(local)?;
+		socket.set_read_timeout(Some(DNS_TIMEOUT))?;
+		let id = rand::random();
+		socket.send_to(&dns_query(id, name)?, self.server)?;
+
+		let mut buf = [0u8; MAX_DNS_PACKET_SIZE];
+		let (len, _) = socket.recv_from(&mut buf)?;
+		dns_txt_records(id, &buf[..len])
+	}
+}
+
+/// Encodes a recursive TXT query.
+fn dns_query(id: u16, name: &str) -> Result<Bytes, Error> {
+	let mut query = Vec::with_capacity(name.len() + 18);
+	query.extend_from_slice(&id.to_be_bytes());
+	// recursion desired, one question
+	query.extend_from_slice(&[0x01, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+	for label in name.trim_end_matches('.').split('.') {
+		if label.is_empty() || label.len() > 63 {
+			return Err(Error::DnsDiscovery("invalid domain name"));
+		}
+		query.push(label.len() as u8);
+		query.extend_from_slice(label.as_bytes());
+	}
+	query.push(0);
+	query.extend_from_slice(&DNS_TYPE_TXT.to_be_bytes());
+	// class IN
+	query.extend_from_slice(&[0x00, 0x01]);
+	Ok(query)
+}
+
+/// Returns the TXT records of a response to `dns_query`. Non-existent names have no records.
+fn dns_txt_records(id: u16, response: &[u8]) -> Result<Vec<String>, Error> {
+	let malformed = || Error::DnsDiscovery("malformed DNS response");
+	if response.len() < 12 || response[0..2] != id.to_be_bytes() {
+		return Err(Error::DnsDiscovery("unexpected DNS response"));
+	}
+	if response[2] & 0x02 != 0 {
+		return Err(Error::DnsDiscovery("truncated DNS response"));
+	}
+	match response[3] & 0x0f {
+		0 => (),
+		3 => return Ok(Vec::new()),
+		_ => return Err(Error::DnsDiscovery("DNS query failed")),
+	}
+	let questions = u16::from_be_bytes([response[4], response[5]]);
+	let answers = u16::from_be_bytes([response[6], response[7]]);
+
+	let mut pos = 12;
+	for _ in 0..questions {
+		// skip name, type and class
+		pos = skip_dns_name(response, pos).ok_or_else(malformed)? + 4;
+	}
+
+	let mut records = Vec::new();
+	for _ in 0..answers {
+		pos = skip_dns_name(response, pos).ok_or_else(malformed)?;
+		let header = response.get(pos..pos + 10).ok_or_else(malformed)?;
+		let record_type = u16::from_be_bytes([header[0], header[1]]);
+		let len = u16::from_be_bytes([header[8], header[9]]) as usize;
+		pos += 10;
+		let data = response.get(pos..pos + len).ok_or_else(malformed)?;
+		pos += len;
+		if record_type != DNS_TYPE_TXT {
+			continue;
+		}
+
+		// a record consists of length prefixed strings
+		let mut txt = Vec::with_capacity(len);
+		let mut i = 0;
+		while i < data.len() {
+			let end = i + 1 + data[i] as usize;
+			txt.extend_from_slice(data.get(i + 1..end).ok_or_else(malformed)?);
+			i = end;
+		}
+		records.push(String::from_utf8(txt).map_err(|_| malformed())?);
+	}
+	Ok(records)
+}
+
+/// Returns the position following the name starting at `pos`.
+fn skip_dns_name(packet: &[u8], mut pos: usize) -> Option<usize> {
+	loop {
+		match *packet.get(pos)? as usize {
+			0 => return Some(pos + 1),
+			// compression pointer
+			len if len & 0xc0 == 0xc0 => return Some(pos + 2),
+			len => pos += 1 + len,
+		}
+	}
+}
+
+fn base32_encode(data: &[u8]) -> String {
+	let mut encoded = String::with_capacity((data.len() * 8 + 4) / 5);
+	let (mut acc, mut bits) = (0u32, 0);
+	for byte in data {
+		acc = (acc << 8) | u32::from(*byte);
+		bits += 8;
+		while bits >= 5 {
+			bits -= 5;
+			encoded.push(BASE32_ALPHABET[((acc >> bits) & 0x1f) as usize] as char);
+		}
+		acc &= (1 << bits) - 1;
+	}
+	if bits > 0 {
+		encoded.push(BASE32_ALPHABET[((acc << (5 - bits)) & 0x1f) as usize] as char);
+	}
+	encoded
+}
+
+fn base32_decode(s: &str) -> Option<Bytes> {
+	let mut decoded = Vec::with_capacity(s.len() * 5 / 8);
+	let (mut acc, mut bits) = (0u32, 0);
+	for c in s.bytes() {
+		acc = (acc << 5) | BASE32_ALPHABET.iter().position(|a| *a == c)? as u32;
+		bits += 5;
+		if bits >= 8 {
+			bits -= 8;
+			decoded.push((acc >> bits) as u8);
+			acc &= (1 << bits) - 1;
+		}
+	}
+	Some(decoded)
+}
+
+/// Subdomain of a tree entry.
+fn entry_hash(entry: &str) -> String {
+	base32_encode(&keccak(entry)[..HASH_SIZE])
+}
+
+fn is_valid_hash(hash: &str) -> bool {
+	base32_decode(hash).map_or(false, |hash| hash.len() == HASH_SIZE)
+}
+
+/// Location of a node tree, `enrtree://<public key>@<domain>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TreeLink {
+	/// Compressed public key of the tree signer.
+	public: Bytes,
+	/// Domain of the tree root.
+	pub domain: String,
+}
+
+impl TreeLink {
+	/// Creates the location of a tree signed by `public` and published at `domain`.
+	pub fn new(public: &NodeId, domain: &str) -> Self {
+		TreeLink {
+			public: compress(public).to_vec(),
+			domain: domain.to_owned(),
+		}
+	}
+}
+
+impl fmt::Display for TreeLink {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}{}@{}", TREE_PREFIX, base32_encode(&self.public), self.domain)
+	}
+}
+
+impl FromStr for TreeLink {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if !s.starts_with(TREE_PREFIX) {
+			return Err(Error::DnsDiscovery("invalid tree link"));
+		}
+		let mut parts = s[TREE_PREFIX.len()..].splitn(2, '@');
+		match (parts.next().and_then(base32_decode), parts.next()) {
+			(Some(public), Some(domain)) if public.len() == 33 && !domain.is_empty() => Ok(TreeLink {
+				public,
+				domain: domain.to_owned(),
+			}),
+			_ => Err(Error::DnsDiscovery("invalid tree link")),
+		}
+	}
+}
+
+/// Signed root entry of a tree.
+#[derive(Debug, Clone, PartialEq)]
+struct Root {
+	/// Hash of the node records subtree.
+	enr_root: String,
+	/// Hash of the tree links subtree.
+	link_root: String,
+	seq: u64,
+	signature: Bytes,
+}
+
+impl Root {
+	fn signed_content(&self) -> String {
+		format!("{} e={} l={} seq={}", ROOT_PREFIX, self.enr_root, self.link_root, self.seq)
+	}
+
+	/// Checks that the entry is signed by the owner of the tree.
+	fn verify(&self, link: &TreeLink) -> bool {
+		self.signature.len() == 65 &&
+			recover_signer(&self.signature[..64], &keccak(self.signed_content()), &link.public).is_some()
+	}
+}
+
+impl fmt::Display for Root {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{} sig={}", self.signed_content(), base64::encode_config(&self.signature, base64::URL_SAFE_NO_PAD))
+	}
+}
+
+/// Entry of a node tree.
+#[derive(Debug)]
+enum Entry {
+	Root(Root),
+	Branch(Vec<String>),
+	Link(TreeLink),
+	Record(Enr),
+}
+
+impl FromStr for Entry {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.starts_with(ROOT_PREFIX) {
+			let (mut enr_root, mut link_root, mut seq, mut signature) = (None, None, None, None);
+			for field in s[ROOT_PREFIX.len()..].split_whitespace() {
+				let mut pair = field.splitn(2, '=');
+				match (pair.next(), pair.next()) {
+					(Some("e"), Some(hash)) if is_valid_hash(hash) => enr_root = Some(hash.to_owned()),
+					(Some("l"), Some(hash)) if is_valid_hash(hash) => link_root = Some(hash.to_owned()),
+					(Some("seq"), Some(value)) => seq = value.parse().ok(),
+					(Some("sig"), Some(value)) => signature = base64::decode_config(value, base64::URL_SAFE_NO_PAD).ok(),
+					_ => return Err(Error::DnsDiscovery("invalid root entry")),
+				}
+			}
+			match (enr_root, link_root, seq, signature) {
+				(Some(enr_root), Some(link_root), Some(seq), Some(signature)) =>
+					Ok(Entry::Root(Root { enr_root, link_root, seq, signature })),
+				_ => Err(Error::DnsDiscovery("invalid root entry")),
+			}
+		} else if s.starts_with(BRANCH_PREFIX) {
+			let hashes: Vec<_> = s[BRANCH_PREFIX.len()..].split(',')
+				.filter(|hash| !hash.is_empty())
+				.map(str::to_owned)
+				.collect();
+			if !hashes.iter().all(|hash| is_valid_hash(hash)) {
+				return Err(Error::DnsDiscovery("invalid branch entry"));
+			}
+			Ok(Entry::Branch(hashes))
+		} else if s.starts_with(TREE_PREFIX) {
+			Ok(Entry::Link(s.parse()?))
+		} else if s.starts_with(ENR_PREFIX) {
+			Ok(Entry::Record(s.parse()?))
+		} else {
+			Err(Error::DnsDiscovery("unknown tree entry"))
+		}
+	}
+}
+
+/// Subtree an entry was referenced from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Subtree {
+	Records,
+	Links,
+}
+
+/// Sync state of a single tree.
+struct TreeSync {
+	link: TreeLink,
+	root: Option<Root>,
+	last_root_check: Option<Instant>,
+	/// Entries referenced by the current root which are yet to be resolved.
+	missing: VecDeque<(String, Subtree)>,
+	/// Entries of the current root which are already resolved.
+	resolved: HashSet<String>,
+}
+
+impl TreeSync {
+	fn new(link: TreeLink) -> Self {
+		TreeSync {
+			link,
+			root: None,
+			last_root_check: None,
+			missing: VecDeque::new(),
+			resolved: HashSet::new(),
+		}
+	}
+
+	/// Resolves up to `limit` entries, collects the node records and links to other trees found.
+	fn sync(&mut self, resolver: &dyn DnsResolver, limit: usize, records: &mut Vec<Enr>, links: &mut Vec<TreeLink>) {
+		let now = Instant::now();
+		if self.missing.is_empty() && self.last_root_check.map_or(true, |checked| now >= checked + ROOT_RECHECK_INTERVAL) {
+			self.last_root_check = Some(now);
+			match self.resolve_root(resolver) {
+				Ok(ref root) if self.root.as_ref().map_or(false, |known| known.seq >= root.seq) => (),
+				Ok(root) => {
+					trace!(target: "discovery", "Syncing node tree {} at seq {}", self.link.domain, root.seq);
+					self.resolved.clear();
+					self.missing = vec![(root.enr_root.clone(), Subtree::Records), (root.link_root.clone(), Subtree::Links)].into();
+					self.root = Some(root);
+				},
+				Err(e) => {
+					debug!(target: "discovery", "Error resolving root of node tree {}: {:?}", self.link.domain, e);
+					return;
+				},
+			}
+		}
+
+		for _ in 0..limit {
+			let (hash, subtree) = match self.missing.pop_front() {
+				Some(entry) => entry,
+				None => break,
+			};
+			if !self.resolved.insert(hash.clone()) {
+				continue;
+			}
+			match self.resolve_entry(resolver, &hash) {
+				Ok(Entry::Branch(hashes)) => self.missing.extend(hashes.into_iter().map(|hash| (hash, subtree))),
+				Ok(Entry::Record(enr)) if subtree == Subtree::Records => records.push(enr),
+				Ok(Entry::Link(link)) if subtree == Subtree::Links => links.push(link),
+				Ok(_) => debug!(target: "discovery", "Unexpected entry {} in node tree {}", hash, self.link.domain),
+				Err(e) => debug!(target: "discovery", "Error resolving entry {} of node tree {}: {:?}", hash, self.link.domain, e),
+			}
+		}
+	}
+
+	fn resolve_root(&self, resolver: &dyn DnsResolver) -> Result<Root, Error> {
+		let txt = resolver.txt(&self.link.domain)?.into_iter()
+			.find(|txt| txt.starts_with(ROOT_PREFIX))
+			.ok_or(Error::DnsDiscovery("missing root entry"))?;
+		match txt.parse::<Entry>()? {
+			Entry::Root(ref root) if !root.verify(&self.link) => Err(Error::DnsDiscovery("invalid root signature")),
+			Entry::Root(root) => Ok(root),
+			_ => Err(Error::DnsDiscovery("invalid root entry")),
+		}
+	}
+
+	fn resolve_entry(&self, resolver: &dyn DnsResolver, hash: &str) -> Result<Entry, Error> {
+		resolver.txt(&format!("{}.{}", hash, self.link.domain))?.into_iter()
+			.find(|txt| entry_hash(txt) == hash)
+			.ok_or(Error::DnsDiscovery("missing tree entry"))?
+			.parse()
+	}
+}
+
+/// Collects node records from trees published in DNS.
+pub struct DnsDiscovery {
+	resolver: Arc<dyn DnsResolver>,
+	/// Trees keyed by domain.
+	trees: HashMap<String, TreeSync>,
+}
+
+impl DnsDiscovery {
+	pub fn new(resolver: Arc<dyn DnsResolver>) -> Self {
+		DnsDiscovery {
+			resolver,
+			trees: HashMap::new(),
+		}
+	}
+
+	/// Adds the tree at an `enrtree://` location.
+	pub fn add_tree(&mut self, url: &str) -> Result<(), Error> {
+		self.add_link(url.parse()?);
+		Ok(())
+	}
+
+	fn add_link(&mut self, link: TreeLink) {
+		self.trees.entry(link.domain.clone()).or_insert_with(|| TreeSync::new(link));
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.trees.is_empty()
+	}
+
+	/// Resolves up to `limit` entries of each tree and returns the node records found.
+	/// Trees linked from the resolved entries are synced by the following calls.
+	pub fn sync(&mut self, limit: usize) -> Vec<Enr> {
+		let mut records = Vec::new();
+		let mut links = Vec::new();
+		for tree in self.trees.values_mut() {
+			tree.sync(&*self.resolver, limit, &mut records, &mut links);
+		}
+		for link in links {
+			self.add_link(link);
+		}
+		records
+	}
+}
+
+/// Node tree signed for publication in DNS.
+pub struct NodeTree {
+	root: Root,
+	/// Entries keyed by their hash.
+	entries: BTreeMap<String, String>,
+}
+
+impl NodeTree {
+	/// Builds a tree of node records and links to other trees, signed with `key`.
+	pub fn new(key: &KeyPair, seq: u64, records: &[Enr], links: &[TreeLink]) -> Result<Self, Error> {
+		let mut entries = BTreeMap::new();
+		let enr_root = build_subtree(records.iter().map(ToString::to_string).collect(), &mut entries);
+		let link_root = build_subtree(links.iter().map(ToString::to_string).collect(), &mut entries);
+		let mut root = Root { enr_root, link_root, seq, signature: Bytes::new() };
+		root.signature = sign(key.secret(), &keccak(root.signed_content()))?[..].to_vec();
+		Ok(NodeTree { root, entries })
+	}
+
+	/// Returns the names and values of the TXT records publishing the tree at `domain`,
+	/// starting with the root entry.
+	pub fn txt_records(&self, domain: &str) -> Vec<(String, String)> {
+		iter::once((domain.to_owned(), self.root.to_string()))
+			.chain(self.entries.iter().map(|(hash, entry)| (format!("{}.{}", hash, domain), entry.clone())))
+			.collect()
+	}
+}
+
+/// Adds the branches referencing `leaves` to `entries` and returns the hash of the topmost one.
+fn build_subtree(leaves: Vec<String>, entries: &mut BTreeMap<String, String>) -> String {
+	let mut add_entry = |entry: String| {
+		let hash = entry_hash(&entry);
+		entries.insert(hash.clone(), entry);
+		hash
+	};
+	let mut hashes: Vec<_> = leaves.into_iter().map(&mut add_entry).collect();
+	while hashes.len() > MAX_BRANCH_HASHES {
+		hashes = hashes.chunks(MAX_BRANCH_HASHES)
+			.map(|chunk| add_entry(format!("{}{}", BRANCH_PREFIX, chunk.join(","))))
+			.collect();
+	}
+	add_entry(format!("{}{}", BRANCH_PREFIX, hashes.join(",")))
+}
+
+#[cfg(test)]
+mod tests {
+	use parity_crypto::publickey::{Generator, Random};
+
+	use crate::node_table::NodeEndpoint;
+	use super::*;
+
+	/// Example tree of EIP-1459.
+	const EXAMPLE_TREE: &[(&str, &str)] = &[
+		("nodes.example.org", "enrtree-root:v1 e=JWXYDBPXYWG6FX3GMDIBFA6CJ4 l=C7HRFPF3BLGF3YR4DY5KX3SMBE seq=1 sig=o908WmNp7LibOfPsr4btQwatZJ5URBr2ZAuxvK4UWHlsB9sUOTJQaGAlLPVAhM__XJesCHxLISo94z5Z2a463gA"),
+		("C7HRFPF3BLGF3YR4DY5KX3SMBE.nodes.example.org", "enrtree://AM5FCQLWIZX2QFPNJAP7VUERCCRNGRHWZG3YYHIUV7BVDQ5FDPRT2@morenodes.example.org"),
+		("JWXYDBPXYWG6FX3GMDIBFA6CJ4.nodes.example.org", "enrtree-branch:2XS2367YHAXJFGLZHVAWLQD4ZY,H4FHT4B454P6UXFD7JCYQ5PWDY,MHTDO6TMUBRIA2XWG5LUDACK24"),
+		("2XS2367YHAXJFGLZHVAWLQD4ZY.nodes.example.org", "enr:-HW4QOFzoVLaFJnNhbgMoDXPnOvcdVuj7pDpqRvh6BRDO68aVi5ZcjB3vzQRZH2IcLBGHzo8uUN3snqmgTiE56CH3AMBgmlkgnY0iXNlY3AyNTZrMaECC2_24YYkYHEgdzxlSNKQEnHhuNAbNlMlWJxrJxbAFvA"),
+		("H4FHT4B454P6UXFD7JCYQ5PWDY.nodes.example.org", "enr:-HW4QAggRauloj2SDLtIHN1XBkvhFZ1vtf1raYQp9TBW2RD5EEawDzbtSmlXUfnaHcvwOizhVYLtr7e6vw7NAf6mTuoCgmlkgnY0iXNlY3AyNTZrMaECjrXI8TLNXU0f8cthpAMxEshUyQlK-AM0PW2wfrnacNI"),
+		("MHTDO6TMUBRIA2XWG5LUDACK24.nodes.example.org", "enr:-HW4QLAYqmrwllBEnzWWs7I5Ev2IAs7x_dZlbYdRdMUx5EyKHDXp7AV5CkuPGUPdvbv1_Ms1CPfhcGCvSElSosZmyoqAgmlkgnY0iXNlY3AyNTZrMaECriawHKWdDRk2xeZkrOXBQ0dfMFLHY4eENZwdufn1S1o"),
+	];
+	const EXAMPLE_TREE_URL: &str = "enrtree://AKPYQIUQIL7PSIACI32J7FGZW56E5FKHEFCCOFHILBIMW3M6LWXS2@nodes.example.org";
+
+	/// In-memory DNS zone.
+	#[derive(Default)]
+	struct Zone(HashMap<String, Vec<String>>);
+
+	impl Zone {
+		fn add<S: ToString>(&mut self, name: S, txt: S) {
+			self.0.entry(name.to_string()).or_insert_with(Vec::new).push(txt.to_string());
+		}
+	}
+
+	impl DnsResolver for Zone {
+		fn txt(&self, name: &str) -> Result<Vec<String>, Error> {
+			Ok(self.0.get(name).cloned().unwrap_or_default())
+		}
+	}
+
+	fn random_records(count: u16) -> Vec<Enr> {
+		(0..count).map(|i| {
+			let endpoint = NodeEndpoint { address: SocketAddr::from(([10, 0, 0, 1], 30303 + i)), udp_port: 30303 + i };
+			Enr::new(&Random.generate().unwrap(), &endpoint).unwrap()
+		}).collect()
+	}
+
+	fn sorted(records: &[Enr]) -> Vec<String> {
+		let mut records: Vec<_> = records.iter().map(ToString::to_string).collect();
+		records.sort();
+		records
+	}
+
+	#[test]
+	fn syncs_eip_1459_example_tree() {
+		let mut zone = Zone::default();
+		for (name, txt) in EXAMPLE_TREE {
+			zone.add(*name, *txt);
+		}
+		let mut discovery = DnsDiscovery::new(Arc::new(zone));
+		discovery.add_tree(EXAMPLE_TREE_URL).unwrap();
+
+		let records = discovery.sync(10);
+		let mut expected: Vec<_> = EXAMPLE_TREE[3..].iter().map(|(_, txt)| txt.to_string()).collect();
+		expected.sort();
+		assert_eq!(sorted(&records), expected);
+		// the linked tree is synced next
+		assert!(discovery.trees.contains_key("morenodes.example.org"));
+		assert!(discovery.sync(10).is_empty());
+	}
+
+	#[test]
+	fn builds_and_syncs_trees() {
+		let key = Random.generate().unwrap();
+		let records = random_records(40);
+		let link = TreeLink::from_str(EXAMPLE_TREE_URL).unwrap();
+		let tree = NodeTree::new(&key, 3, &records, &[link.clone()]).unwrap();
+
+		let mut zone = Zone::default();
+		for (name, txt) in tree.txt_records("nodes.example.com") {
+			zone.add(name, txt);
+		}
+		let mut discovery = DnsDiscovery::new(Arc::new(zone));
+		discovery.add_tree(&TreeLink::new(key.public(), "nodes.example.com").to_string()).unwrap();
+
+		// resolves the root and the first two entries
+		assert!(discovery.sync(2).is_empty());
+		let mut synced = Vec::new();
+		for _ in 0..10 {
+			synced.extend(discovery.sync(8));
+		}
+		assert_eq!(sorted(&synced), sorted(&records));
+		assert_eq!(discovery.trees["nodes.example.com"].root.as_ref().unwrap().seq, 3);
+		assert_eq!(discovery.trees[&link.domain].link, link);
+	}
+
+	#[test]
+	fn rejects_tree_signed_by_other_key() {
+		let tree = NodeTree::new(&Random.generate().unwrap(), 1, &random_records(2), &[]).unwrap();
+		let mut zone = Zone::default();
+		for (name, txt) in tree.txt_records("nodes.example.com") {
+			zone.add(name, txt);
+		}
+		let mut discovery = DnsDiscovery::new(Arc::new(zone));
+		let other = Random.generate().unwrap();
+		discovery.add_tree(&TreeLink::new(other.public(), "nodes.example.com").to_string()).unwrap();
+
+		assert!(discovery.sync(10).is_empty());
+		assert!(discovery.trees["nodes.example.com"].root.is_none());
+	}
+
+	#[test]
+	fn parses_tree_entries() {
+		let link = TreeLink::from_str(EXAMPLE_TREE_URL).unwrap();
+		assert_eq!(link.to_string(), EXAMPLE_TREE_URL);
+		assert_eq!(entry_hash(EXAMPLE_TREE[2].1), "JWXYDBPXYWG6FX3GMDIBFA6CJ4");
+		match Entry::from_str(EXAMPLE_TREE[0].1).unwrap() {
+			Entry::Root(root) => {
+				assert!(root.verify(&link));
+				assert_eq!(root.to_string(), EXAMPLE_TREE[0].1);
+			},
+			entry => panic!("Unexpected entry {:?}", entry),
+		}
+
+		assert!(TreeLink::from_str("enrtree://nodes.example.org").is_err());
+		assert!(Entry::from_str("enrtree-branch:invalid").is_err());
+		assert!(Entry::from_str("enrtree-root:v1 e=JWXYDBPXYWG6FX3GMDIBFA6CJ4 seq=1").is_err());
+		assert!(Entry::from_str("unknown").is_err());
+	}
+
+	#[test]
+	fn parses_dns_responses() {
+		let query = dns_query(0x1234, "nodes.example.org").unwrap();
+		let mut response = query.clone();
+		// response flags and one answer
+		response[2] = 0x81;
+		response[3] = 0x80;
+		response[7] = 1;
+		// name pointing to the question, TXT, IN, TTL
+		response.extend_from_slice(&[0xc0, 0x0c, 0x00, 0x10, 0x00, 0x01, 0x00, 0x00, 0x0e, 0x10]);
+		response.extend_from_slice(&[0x00, 0x0a, 0x04]);
+		response.extend_from_slice(b"enr:");
+		response.push(0x04);
+		response.extend_from_slice(b"abcd");
+
+		assert_eq!(dns_txt_records(0x1234, &response).unwrap(), vec!["enr:abcd".to_owned()]);
+		assert!(dns_txt_records(0x4321, &response).is_err());
+		assert!(dns_txt_records(0x1234, &response[..response.len() - 1]).is_err());
+
+		// non-existent domain
+		response[3] = 0x83;
+		assert_eq!(dns_txt_records(0x1234, &response).unwrap(), Vec::<String>::new());
+	}
+}
@@ -0,0 +1,343 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Ethereum Node Records as defined by EIP-778.
+//!
+//! A record is a signed, sequence-numbered set of key/value pairs describing a node. Only the
+//! `v4` identity scheme is supported: records are signed with the node's secp256k1 key and
+//! carry its compressed public key in the `secp256k1` entry.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::str::FromStr;
+
+use ethereum_types::{H256, H520};
+use keccak_hash::keccak;
+use parity_bytes::Bytes;
+use parity_crypto::publickey::{ec_math_utils, KeyPair, recover, sign};
+use rlp::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
+
+use network::Error;
+
+use crate::node_table::{NodeEndpoint, NodeId};
+
+/// Maximal size of an encoded record.
+pub const MAX_ENR_SIZE: usize = 300;
+/// Prefix of the textual form of a record.
+pub const ENR_PREFIX: &str = "enr:";
+const ID_SCHEME: &[u8] = b"v4";
+
+/// Keys of the entries describing the node endpoint.
+const ENDPOINT_KEYS: [&str; 6] = ["ip", "tcp", "udp", "ip6", "tcp6", "udp6"];
+
+/// Predicate on the RLP encoded value of a record entry.
+pub type EnrFilter = Box<dyn Fn(&Rlp) -> bool + Send + Sync>;
+
+/// Signed node record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Enr {
+	/// Public key of the node that signed the record.
+	id: NodeId,
+	seq: u64,
+	/// Entries sorted by key, values are kept RLP encoded.
+	pairs: BTreeMap<Bytes, Bytes>,
+	signature: Bytes,
+}
+
+impl Enr {
+	/// Creates a record announcing the given endpoint, signed with `key`.
+	pub fn new(key: &KeyPair, endpoint: &NodeEndpoint) -> Result<Self, Error> {
+		Enr::with_seq(key, endpoint, 0)
+	}
+
+	/// Creates a record with a sequence number above `seq`, so that it supersedes the records
+	/// published before a restart.
+	pub fn with_seq(key: &KeyPair, endpoint: &NodeEndpoint, seq: u64) -> Result<Self, Error> {
+		let mut pairs = BTreeMap::new();
+		pairs.insert(b"id".to_vec(), rlp::encode(&ID_SCHEME));
+		pairs.insert(b"secp256k1".to_vec(), rlp::encode(&&compress(key.public())[..]));
+
+		let mut enr = Enr {
+			id: *key.public(),
+			seq,
+			pairs,
+			signature: Bytes::new(),
+		};
+		enr.set_endpoint(key, endpoint)?;
+		Ok(enr)
+	}
+
+	/// Public key of the node.
+	pub fn id(&self) -> &NodeId {
+		&self.id
+	}
+
+	/// Sequence number, increased each time the record changes.
+	pub fn seq(&self) -> u64 {
+		self.seq
+	}
+
+	/// Returns the RLP encoded value of an entry.
+	pub fn get(&self, key: &str) -> Option<Rlp> {
+		self.pairs.get(key.as_bytes()).map(|value| Rlp::new(value))
+	}
+
+	/// Returns the endpoint announced by the record, IPv4 addresses are preferred.
+	/// Missing ports are set to zero.
+	pub fn endpoint(&self) -> Option<NodeEndpoint> {
+		let port = |key: &str| self.get(key).and_then(|port| port.as_val::<u16>().ok()).unwrap_or(0);
+		let ip = |key: &str| self.get(key).and_then(|ip| ip.data().ok().map(|ip| ip.to_vec()));
+
+		if let Some(ip) = ip("ip").filter(|ip| ip.len() == 4) {
+			let ip = Ipv4Addr::new(ip[0], ip[1], ip[2], ip[3]);
+			return Some(NodeEndpoint {
+				address: SocketAddr::V4(SocketAddrV4::new(ip, port("tcp"))),
+				udp_port: port("udp"),
+			});
+		}
+		if let Some(ip) = ip("ip6").filter(|ip| ip.len() == 16) {
+			let mut octets = [0u8; 16];
+			octets.copy_from_slice(&ip);
+			return Some(NodeEndpoint {
+				address: SocketAddr::V6(SocketAddrV6::new(Ipv6Addr::from(octets), port("tcp6"), 0, 0)),
+				udp_port: port("udp6"),
+			});
+		}
+		None
+	}
+
+	/// Replaces the endpoint announced by the record.
+	pub fn set_endpoint(&mut self, key: &KeyPair, endpoint: &NodeEndpoint) -> Result<(), Error> {
+		let mut pairs = self.pairs.clone();
+		for k in &ENDPOINT_KEYS {
+			pairs.remove(k.as_bytes());
+		}
+		let (ip, tcp, udp) = match endpoint.address {
+			SocketAddr::V4(a) => (("ip", rlp::encode(&&a.ip().octets()[..])), "tcp", "udp"),
+			SocketAddr::V6(a) => (("ip6", rlp::encode(&&a.ip().octets()[..])), "tcp6", "udp6"),
+		};
+		pairs.insert(ip.0.as_bytes().to_vec(), ip.1);
+		pairs.insert(tcp.as_bytes().to_vec(), rlp::encode(&endpoint.address.port()));
+		pairs.insert(udp.as_bytes().to_vec(), rlp::encode(&endpoint.udp_port));
+		self.update(key, pairs)
+	}
+
+	/// Sets the RLP encoded value of an entry.
+	pub fn set(&mut self, key: &KeyPair, name: &str, value: Bytes) -> Result<(), Error> {
+		let mut pairs = self.pairs.clone();
+		pairs.insert(name.as_bytes().to_vec(), value);
+		self.update(key, pairs)
+	}
+
+	/// Signs the new entries with the next sequence number. Does nothing if the entries didn't change.
+	fn update(&mut self, key: &KeyPair, pairs: BTreeMap<Bytes, Bytes>) -> Result<(), Error> {
+		if pairs == self.pairs && !self.signature.is_empty() {
+			return Ok(());
+		}
+		let seq = self.seq + 1;
+		let signature = sign(key.secret(), &keccak(signed_content(seq, &pairs)))?;
+		let enr = Enr {
+			id: self.id,
+			seq,
+			pairs,
+			signature: signature[..64].to_vec(),
+		};
+		if rlp::encode(&enr).len() > MAX_ENR_SIZE {
+			return Err(Error::OversizedPacket);
+		}
+		*self = enr;
+		Ok(())
+	}
+}
+
+/// Returns the RLP encoded content covered by the signature.
+fn signed_content(seq: u64, pairs: &BTreeMap<Bytes, Bytes>) -> Bytes {
+	let mut s = RlpStream::new_list(1 + pairs.len() * 2);
+	s.append(&seq);
+	for (key, value) in pairs {
+		s.append(key);
+		s.append_raw(value, 1);
+	}
+	s.out()
+}
+
+/// Compressed form of a secp256k1 public key.
+pub(crate) fn compress(public: &NodeId) -> [u8; 33] {
+	let mut compressed = [0u8; 33];
+	compressed[0] = 2 + (public[63] & 1);
+	compressed[1..].copy_from_slice(&public[..32]);
+	compressed
+}
+
+/// Decompresses a secp256k1 public key `R`. Recovering the signer of a signature with
+/// `r = s = x(R)` over the message `x(R)` yields `R - G`.
+pub(crate) fn decompress(compressed: &[u8]) -> Option<NodeId> {
+	if compressed.len() != 33 || (compressed[0] != 2 && compressed[0] != 3) {
+		return None;
+	}
+	let mut signature = [0u8; 65];
+	signature[..32].copy_from_slice(&compressed[1..]);
+	signature[32..64].copy_from_slice(&compressed[1..]);
+	signature[64] = compressed[0] - 2;
+	let mut public = recover(&H520::from(signature).into(), &H256::from_slice(&compressed[1..])).ok()?;
+	ec_math_utils::public_add(&mut public, &ec_math_utils::generation_point()).ok()?;
+	Some(public)
+}
+
+/// Recovers the signer of a 64 byte signature without recovery id, returns it if it owns the
+/// compressed public key.
+pub(crate) fn recover_signer(signature: &[u8], message: &H256, public: &[u8]) -> Option<NodeId> {
+	if signature.len() != 64 {
+		return None;
+	}
+	(0..2).filter_map(|v| {
+		let mut signature = signature.to_vec();
+		signature.push(v);
+		recover(&H520::from_slice(&signature).into(), message).ok()
+	}).find(|id| compress(id)[..] == *public)
+}
+
+impl Encodable for Enr {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(2 + self.pairs.len() * 2);
+		s.append(&self.signature);
+		s.append(&self.seq);
+		for (key, value) in &self.pairs {
+			s.append(key);
+			s.append_raw(value, 1);
+		}
+	}
+}
+
+impl Decodable for Enr {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		if rlp.as_raw().len() > MAX_ENR_SIZE {
+			return Err(DecoderError::Custom("Node record is too large"));
+		}
+		let item_count = rlp.item_count()?;
+		if item_count < 2 || item_count % 2 != 0 {
+			return Err(DecoderError::RlpIncorrectListLen);
+		}
+
+		let signature: Bytes = rlp.val_at(0)?;
+		let seq: u64 = rlp.val_at(1)?;
+		let mut pairs = BTreeMap::new();
+		for i in (2..item_count).step_by(2) {
+			let key: Bytes = rlp.val_at(i)?;
+			if pairs.keys().next_back().map_or(false, |last| *last >= key) {
+				return Err(DecoderError::Custom("Node record keys are not sorted"));
+			}
+			pairs.insert(key, rlp.at(i + 1)?.as_raw().to_vec());
+		}
+
+		let entry = |key: &[u8]| pairs.get(key).and_then(|value| Rlp::new(value).data().ok().map(|data| data.to_vec()));
+		if entry(b"id").as_ref().map(|id| &id[..]) != Some(ID_SCHEME) {
+			return Err(DecoderError::Custom("Unsupported node record identity scheme"));
+		}
+		let public = entry(b"secp256k1").ok_or(DecoderError::Custom("Node record public key is missing"))?;
+		let id = recover_signer(&signature, &keccak(signed_content(seq, &pairs)), &public)
+			.ok_or(DecoderError::Custom("Invalid node record signature"))?;
+
+		Ok(Enr { id, seq, pairs, signature })
+	}
+}
+
+impl fmt::Display for Enr {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}{}", ENR_PREFIX, base64::encode_config(&rlp::encode(self), base64::URL_SAFE_NO_PAD))
+	}
+}
+
+impl FromStr for Enr {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if !s.starts_with(ENR_PREFIX) {
+			return Err(Error::InvalidNodeId);
+		}
+		let bytes = base64::decode_config(&s[ENR_PREFIX.len()..], base64::URL_SAFE_NO_PAD).map_err(|_| Error::InvalidNodeId)?;
+		Ok(rlp::decode(&bytes)?)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use parity_crypto::publickey::{Generator, Random, Secret};
+
+	use super::*;
+
+	const EIP_778_RECORD: &str = "enr:-IS4QHCYrYZbAKWCBRlAy5zzaDZXJBGkcnh4MHcBFZntXNFrdvJjX04jRzjzCBOonrkTfj499SZuOh8R33Ls8RRcy5wBgmlkgnY0gmlwhH8AAAGJc2VjcDI1NmsxoQPKY0yuDUmstAHYpMa2_oxVtw0RW_QAdpzBQA8yWM0xOIN1ZHCCdl8";
+
+	fn key() -> KeyPair {
+		let secret = Secret::from_str("b71c71a67e1177ad4e901695e1b4b9ee17ae16c6668d313eac2f96dbcda3f291").unwrap();
+		KeyPair::from_secret(secret).unwrap()
+	}
+
+	#[test]
+	fn decodes_eip_778_example_record() {
+		let enr = Enr::from_str(EIP_778_RECORD).unwrap();
+		assert_eq!(enr.id(), key().public());
+		assert_eq!(enr.seq(), 1);
+		assert_eq!(
+			enr.endpoint(),
+			Some(NodeEndpoint { address: SocketAddr::from_str("127.0.0.1:0").unwrap(), udp_port: 30303 })
+		);
+		assert_eq!(keccak(enr.id()), H256::from_str("a448f24c6d18e575453db13171562b71999873db5b286df957af199ec94617f7").unwrap());
+		assert_eq!(enr.to_string(), EIP_778_RECORD);
+	}
+
+	#[test]
+	fn signs_updated_records() {
+		let key = key();
+		let endpoint = NodeEndpoint { address: SocketAddr::from_str("10.0.0.1:30303").unwrap(), udp_port: 30301 };
+		let mut enr = Enr::new(&key, &endpoint).unwrap();
+		assert_eq!(enr.seq(), 1);
+		assert_eq!(enr.endpoint(), Some(endpoint.clone()));
+
+		// unchanged entries keep the sequence number
+		enr.set_endpoint(&key, &endpoint).unwrap();
+		assert_eq!(enr.seq(), 1);
+
+		enr.set(&key, "eth", rlp::encode_list::<u64, _>(&[1, 2])).unwrap();
+		assert_eq!(enr.seq(), 2);
+		assert_eq!(enr.get("eth").unwrap().as_list::<u64>().unwrap(), vec![1, 2]);
+
+		let decoded = Enr::from_str(&enr.to_string()).unwrap();
+		assert_eq!(decoded, enr);
+	}
+
+	#[test]
+	fn decompresses_public_keys() {
+		for _ in 0..8 {
+			let key = Random.generate().unwrap();
+			assert_eq!(decompress(&compress(key.public())), Some(*key.public()));
+		}
+		assert_eq!(decompress(&[4; 33]), None);
+	}
+
+	#[test]
+	fn rejects_invalid_records() {
+		let mut bytes = base64::decode_config(&EIP_778_RECORD[ENR_PREFIX.len()..], base64::URL_SAFE_NO_PAD).unwrap();
+		// change the last byte of the udp port
+		let last = bytes.len() - 1;
+		bytes[last] ^= 1;
+		assert_eq!(rlp::decode::<Enr>(&bytes), Err(DecoderError::Custom("Invalid node record signature")));
+
+		assert!(Enr::from_str("enr:invalid").is_err());
+		assert!(Enr::from_str(&EIP_778_RECORD[ENR_PREFIX.len()..]).is_err());
+	}
+}
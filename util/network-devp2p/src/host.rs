@@ -22,8 +22,9 @@ use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
 use std::ops::*;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{mpsc, Arc};
 use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::thread;
 use std::time::Duration;
 
 use ethereum_types::H256;
@@ -38,17 +39,21 @@ use parity_path::restrict_permissions_owner;
 use parking_lot::{Mutex, RwLock};
 use rlp::{Encodable, RlpStream};
 
-use ethcore_io::{IoContext, IoHandler, IoManager, StreamToken, TimerToken};
+use ethcore_io::{IoChannel, IoContext, IoHandler, IoManager, StreamToken, TimerToken};
+use parity_bytes::Bytes;
 use parity_crypto::publickey::{Generator, KeyPair, Random, Secret};
 use network::{
 	client_version::ClientVersion, ConnectionDirection, ConnectionFilter, DisconnectReason, Error,
 	NetworkConfiguration, NetworkContext as NetworkContextTrait, NetworkIoMessage, NetworkProtocolHandler,
-	NonReservedPeerMode, PacketId, PeerId, ProtocolId, SessionInfo
+	NonReservedPeerMode, PacketId, PeerId, ProtocolId, SessionInfo, protocol_name
 };
 
 use crate::{
 	connection::PAYLOAD_SOFT_LIMIT,
 	discovery::{Discovery, MAX_DATAGRAM_SIZE, NodeEntry, TableUpdates},
+	discovery_v5::{DiscoveryV5, TalkHandler},
+	dns_discovery::{DnsDiscovery, SystemResolver},
+	enr::{Enr, EnrFilter},
 	ip_utils::{map_external_address, select_public_address},
 	node_table::*,
 	PROTOCOL_VERSION,
@@ -70,6 +75,7 @@ const DISCOVERY_REFRESH: TimerToken = SYS_TIMER + 4;
 const FAST_DISCOVERY_REFRESH: TimerToken = SYS_TIMER + 5;
 const DISCOVERY_ROUND: TimerToken = SYS_TIMER + 6;
 const NODE_TABLE: TimerToken = SYS_TIMER + 7;
+const DNS_DISCOVERY: TimerToken = SYS_TIMER + 8;
 const FIRST_SESSION: StreamToken = 0;
 const LAST_SESSION: StreamToken = FIRST_SESSION + MAX_SESSIONS - 1;
 const USER_TIMER: TimerToken = LAST_SESSION + 256;
@@ -86,6 +92,11 @@ const FAST_DISCOVERY_REFRESH_TIMEOUT: Duration = Duration::from_secs(10);
 const DISCOVERY_ROUND_TIMEOUT: Duration = Duration::from_millis(300);
 // for NODE_TABLE TimerToken
 const NODE_TABLE_TIMEOUT: Duration = Duration::from_secs(300);
+// for DNS_DISCOVERY TimerToken
+const DNS_DISCOVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Maximal number of entries resolved per DNS node tree in each DNS discovery round.
+const DNS_DISCOVERY_BATCH: usize = 16;
 
 #[derive(Debug, PartialEq, Eq)]
 /// Protocol info
@@ -101,7 +112,7 @@ pub struct CapabilityInfo {
 impl Encodable for CapabilityInfo {
 	fn rlp_append(&self, s: &mut RlpStream) {
 		s.begin_list(2);
-		s.append(&&self.protocol[..]);
+		s.append(&protocol_name(&self.protocol));
 		s.append(&self.version);
 	}
 }
@@ -230,6 +241,8 @@ pub struct HostInfo {
 	pub local_endpoint: NodeEndpoint,
 	/// Public address + discovery port
 	pub public_endpoint: Option<NodeEndpoint>,
+	/// Our signed node record.
+	enr: Enr,
 }
 
 impl HostInfo {
@@ -249,6 +262,21 @@ impl HostInfo {
 	pub(crate) fn id(&self) -> &NodeId {
 		self.keys.public()
 	}
+
+	/// Announces the given endpoint in our node record.
+	fn set_enr_endpoint(&mut self, endpoint: &NodeEndpoint) {
+		if let Err(e) = self.enr.set_endpoint(&self.keys, endpoint) {
+			warn!(target: "network", "Error updating node record: {:?}", e);
+		}
+		self.save_enr_seq();
+	}
+
+	/// Stores the sequence number of our node record next to the node key.
+	fn save_enr_seq(&self) {
+		if let Some(ref path) = self.config.config_path {
+			save_enr_seq(Path::new(path), self.enr.seq());
+		}
+	}
 }
 
 type SharedSession = Arc<Mutex<Session>>;
@@ -268,7 +296,14 @@ pub struct Host {
 	tcp_listener: Mutex<TcpListener>,
 	sessions: Arc<RwLock<Slab<SharedSession>>>,
 	discovery: Mutex<Option<Discovery<'static>>>,
+	discovery_v5: Mutex<Option<DiscoveryV5>>,
+	/// DNS node trees, moved to the DNS discovery thread once the network is started.
+	dns_discovery: Mutex<Option<DnsDiscovery>>,
+	/// Wakes up the DNS discovery thread.
+	dns_discovery_tx: Mutex<Option<mpsc::SyncSender<()>>>,
 	nodes: RwLock<NodeTable>,
+	/// Filters of node record entries, nodes whose record fails one aren't dialed.
+	enr_filters: RwLock<HashMap<String, EnrFilter>>,
 	handlers: RwLock<HashMap<ProtocolId, Arc<dyn NetworkProtocolHandler + Sync>>>,
 	timers: RwLock<HashMap<TimerToken, ProtocolTimer>>,
 	timer_counter: RwLock<usize>,
@@ -305,8 +340,20 @@ impl Host {
 		debug!(target: "network", "Listening at {:?}", listen_address);
 		let udp_port = config.udp_port.unwrap_or_else(|| listen_address.port());
 		let local_endpoint = NodeEndpoint { address: listen_address, udp_port };
+		// the record changed since the last run at the very least by being signed again
+		let enr_seq = config.config_path.as_ref().and_then(|p| load_enr_seq(Path::new(p))).unwrap_or(0);
+		let enr = Enr::with_seq(&keys, &local_endpoint, enr_seq)?;
+		if let Some(ref path) = config.config_path {
+			save_enr_seq(Path::new(path), enr.seq());
+		}
 
 		let boot_nodes = config.boot_nodes.clone();
+		let mut dns_discovery = DnsDiscovery::new(Arc::new(SystemResolver::default()));
+		for url in &config.dns_discovery {
+			if let Err(e) = dns_discovery.add_tree(url) {
+				warn!(target: "network", "Invalid DNS node tree {}: {}", url, e);
+			}
+		}
 		let reserved_nodes = config.reserved_nodes.clone();
 		config.max_handshakes = min(config.max_handshakes, MAX_HANDSHAKES as u32);
 
@@ -319,12 +366,17 @@ impl Host {
 				capabilities: Vec::new(),
 				public_endpoint: None,
 				local_endpoint,
+				enr,
 			}),
 			discovery: Mutex::new(None),
+			discovery_v5: Mutex::new(None),
+			dns_discovery: Mutex::new(Some(dns_discovery)),
+			dns_discovery_tx: Mutex::new(None),
 			udp_socket: Mutex::new(None),
 			tcp_listener: Mutex::new(tcp_listener),
 			sessions: Arc::new(RwLock::new(Slab::new_starting_at(FIRST_SESSION, MAX_SESSIONS))),
 			nodes: RwLock::new(NodeTable::new(path)),
+			enr_filters: RwLock::new(HashMap::new()),
 			handlers: RwLock::new(HashMap::new()),
 			timers: RwLock::new(HashMap::new()),
 			timer_counter: RwLock::new(USER_TIMER),
@@ -352,6 +404,9 @@ impl Host {
 				let entry = NodeEntry { endpoint: n.endpoint.clone(), id: n.id };
 
 				self.nodes.write().add_node(n);
+				if let Some(ref mut discovery) = *self.discovery_v5.lock() {
+					discovery.add_node(entry.clone());
+				}
 				if let Some(ref mut discovery) = *self.discovery.lock() {
 					discovery.add_node(entry);
 				}
@@ -364,8 +419,11 @@ impl Host {
 
 		let entry = NodeEntry { endpoint: n.endpoint.clone(), id: n.id };
 		self.reserved_nodes.write().insert(n.id);
-		self.nodes.write().add_node(Node::new(entry.id, entry.endpoint.clone()));
+		self.nodes.write().add_node(n);
 
+		if let Some(ref mut discovery) = *self.discovery_v5.lock() {
+			discovery.add_node(entry.clone());
+		}
 		if let Some(ref mut discovery) = *self.discovery.lock() {
 			discovery.add_node(entry);
 		}
@@ -420,8 +478,94 @@ impl Host {
 		format!("{}", Node::new(*info.id(), info.local_endpoint.clone()))
 	}
 
+	/// Returns our node record once the public endpoint is known.
+	pub fn external_enr(&self) -> Option<String> {
+		let info = self.info.read();
+		info.public_endpoint.as_ref().map(|_| info.enr.to_string())
+	}
+
+	/// Sets an entry of our node record, `value` is RLP encoded.
+	pub fn set_enr_entry(&self, key: &str, value: Bytes) {
+		let enr = {
+			let mut info = self.info.write();
+			let info = &mut *info;
+			let seq = info.enr.seq();
+			if let Err(e) = info.enr.set(&info.keys, key, value) {
+				warn!(target: "network", "Error updating node record: {:?}", e);
+			}
+			if info.enr.seq() == seq {
+				return;
+			}
+			info.save_enr_seq();
+			info.enr.clone()
+		};
+		debug!(target: "network", "Updated node record, seq={}", enr.seq());
+		if let Some(ref mut discovery) = *self.discovery_v5.lock() {
+			discovery.set_enr(enr.clone());
+		}
+		if let Some(ref mut discovery) = *self.discovery.lock() {
+			discovery.set_enr(enr);
+		}
+	}
+
+	/// Sets the filter of the record entry `key`. Nodes whose record has the entry and fails the
+	/// filter aren't dialed unless they are reserved.
+	pub fn set_enr_filter(&self, key: &str, filter: EnrFilter) {
+		self.enr_filters.write().insert(key.to_owned(), filter);
+	}
+
+	/// Registers the handler of a protocol running over discovery v5 TALKREQ messages.
+	pub fn register_talk_protocol(&self, protocol: &[u8], handler: Arc<dyn TalkHandler>) {
+		if let Some(ref mut discovery) = *self.discovery_v5.lock() {
+			discovery.register_talk_protocol(protocol, handler);
+		}
+	}
+
+	/// Sends a TALKREQ to the node, the response is passed to the handler of the protocol.
+	pub fn send_talk_request(&self, id: &NodeId, protocol: &[u8], request: &[u8]) -> Result<(), Error> {
+		match *self.discovery_v5.lock() {
+			Some(ref mut discovery) => discovery.send_talk_request(&keccak(id), protocol, request),
+			None => Err(Error::PeerNotFound),
+		}
+	}
+
+	/// Advertises our node under the discovery v5 topic.
+	pub fn register_topic(&self, topic: H256) {
+		if let Some(ref mut discovery) = *self.discovery_v5.lock() {
+			discovery.register_topic(topic);
+		}
+	}
+
+	/// Looks up nodes advertised under the topic, found ones are returned by `topic_nodes`.
+	pub fn query_topic(&self, topic: H256) {
+		if let Some(ref mut discovery) = *self.discovery_v5.lock() {
+			discovery.query_topic(topic);
+		}
+	}
+
+	/// Records of the nodes known to be advertised under the topic.
+	pub fn topic_nodes(&self, topic: &H256) -> Vec<Enr> {
+		self.discovery_v5.lock().as_ref().map_or_else(Vec::new, |discovery| discovery.topic_nodes(topic))
+	}
+
+	/// Whether the known record of the node passes the entry filters.
+	fn enr_allowed(&self, id: &NodeId) -> bool {
+		let filters = self.enr_filters.read();
+		if filters.is_empty() {
+			return true;
+		}
+		let nodes = self.nodes.read();
+		let enr = match nodes.get(id).and_then(|n| n.enr.as_ref()) {
+			Some(enr) => enr,
+			None => return true,
+		};
+		filters.iter().all(|(key, filter)| enr.get(key).map_or(true, |entry| filter(&entry)))
+	}
+
 	pub fn stop(&self, io: &IoContext<NetworkIoMessage>) {
 		self.stopping.store(true, AtomicOrdering::Release);
+		// the DNS discovery thread exits once the channel is closed
+		self.dns_discovery_tx.lock().take();
 		let mut to_kill = Vec::new();
 		for e in self.sessions.read().iter() {
 			let mut s = e.lock();
@@ -475,7 +619,11 @@ impl Host {
 			Some(addr) => NodeEndpoint { address: addr, udp_port: local_endpoint.udp_port }
 		};
 
-		self.info.write().public_endpoint = Some(public_endpoint.clone());
+		{
+			let mut info = self.info.write();
+			info.public_endpoint = Some(public_endpoint.clone());
+			info.set_enr_endpoint(&public_endpoint);
+		}
 
 		if let Some(url) = self.external_url() {
 			io.message(NetworkIoMessage::NetworkStarted(url)).unwrap_or_else(|e| warn!("Error sending IO notification: {:?}", e));
@@ -485,28 +633,79 @@ impl Host {
 		let discovery = {
 			let info = self.info.read();
 			if info.config.discovery_enabled && info.config.non_reserved_mode == NonReservedPeerMode::Accept {
-				Some(Discovery::new(&info.keys, public_endpoint, allow_ips))
+				let mut discovery = Discovery::new(&info.keys, public_endpoint.clone(), allow_ips.clone());
+				discovery.set_enr(info.enr.clone());
+				let mut discovery_v5 = DiscoveryV5::new(&info.keys, public_endpoint, allow_ips);
+				discovery_v5.set_enr(info.enr.clone());
+				Some((discovery, discovery_v5))
 			} else { None }
 		};
 
-		if let Some(mut discovery) = discovery {
+		if let Some((mut discovery, mut discovery_v5)) = discovery {
 			let mut udp_addr = local_endpoint.address;
 			udp_addr.set_port(local_endpoint.udp_port);
 			let socket = UdpSocket::bind(&udp_addr).expect("Error binding UDP socket");
 			*self.udp_socket.lock() = Some(socket);
 
+			{
+				let nodes = self.nodes.read();
+				for entry in nodes.entries() {
+					match nodes.get(&entry.id).and_then(|n| n.enr.clone()) {
+						Some(enr) => { discovery_v5.add_enr(enr); },
+						None => discovery_v5.add_node(entry),
+					}
+				}
+			}
 			discovery.add_node_list(self.nodes.read().entries());
 			*self.discovery.lock() = Some(discovery);
+			*self.discovery_v5.lock() = Some(discovery_v5);
 			io.register_stream(DISCOVERY)?;
 			io.register_timer(FAST_DISCOVERY_REFRESH, FAST_DISCOVERY_REFRESH_TIMEOUT)?;
 			io.register_timer(DISCOVERY_REFRESH, DISCOVERY_REFRESH_TIMEOUT)?;
 			io.register_timer(DISCOVERY_ROUND, DISCOVERY_ROUND_TIMEOUT)?;
 		}
+		let dns_discovery = match self.info.read().config.non_reserved_mode {
+			NonReservedPeerMode::Accept => self.dns_discovery.lock().take().filter(|d| !d.is_empty()),
+			NonReservedPeerMode::Deny => None,
+		};
+		if let Some(dns_discovery) = dns_discovery {
+			*self.dns_discovery_tx.lock() = Some(start_dns_discovery(dns_discovery, io.channel())?);
+			io.register_timer(DNS_DISCOVERY, DNS_DISCOVERY_TIMEOUT)?;
+		}
 		io.register_timer(NODE_TABLE, NODE_TABLE_TIMEOUT)?;
 		io.register_stream(TCP_ACCEPT)?;
 		Ok(())
 	}
 
+	/// Adds the nodes found by DNS discovery to the node table and to discovery.
+	fn on_dns_nodes_found(&self, io: &IoContext<NetworkIoMessage>, records: &[Bytes]) {
+		let nodes: Vec<_> = records.iter()
+			.filter_map(|record| rlp::decode::<Enr>(record).ok())
+			.filter_map(|enr| Node::from_enr(enr).ok())
+			.filter(|node| node.endpoint.is_valid_sync_node())
+			.collect();
+		if nodes.is_empty() {
+			return;
+		}
+		trace!(target: "network", "Adding {} nodes found by DNS discovery", nodes.len());
+		let entries: Vec<_> = nodes.iter().map(|n| NodeEntry { endpoint: n.endpoint.clone(), id: n.id }).collect();
+		if let Some(ref mut discovery) = *self.discovery_v5.lock() {
+			for enr in nodes.iter().filter_map(|n| n.enr.clone()) {
+				discovery.add_enr(enr);
+			}
+		}
+		{
+			let mut table = self.nodes.write();
+			for node in nodes {
+				table.add_node(node);
+			}
+		}
+		if let Some(ref mut discovery) = *self.discovery.lock() {
+			discovery.add_node_list(entries);
+			io.update_registration(DISCOVERY).unwrap_or_else(|e| debug!("Error updating discovery registration: {:?}", e));
+		}
+	}
+
 	fn maintain_network(&self, io: &IoContext<NetworkIoMessage>) {
 		self.keep_alive(io);
 		self.connect_peers(io);
@@ -604,6 +803,7 @@ impl Host {
 				!self.have_session(id) &&
 				!self.connecting_to(id) &&
 				*id != self_id &&
+				(reserved_nodes.contains(id) || self.enr_allowed(id)) &&
 				self.filter.as_ref().map_or(true, |f| f.connection_allowed(&self_id, &id, ConnectionDirection::Outbound))
 			).take(min(max_handshakes_per_round, max_handshakes - handshake_count)) {
 			self.connect_peer(&id, io);
@@ -795,6 +995,9 @@ impl Host {
 									let mut nodes = self.nodes.write();
 									if !nodes.contains(&entry.id) {
 										nodes.add_node(Node::new(entry.id, entry.endpoint.clone()));
+										if let Some(ref mut discovery) = *self.discovery_v5.lock() {
+											discovery.add_node(entry.clone());
+										}
 										let mut discovery = self.discovery.lock();
 										if let Some(ref mut discovery) = *discovery {
 											discovery.add_node(entry);
@@ -866,22 +1069,30 @@ impl Host {
 	}
 
 	fn discovery_readable(&self, io: &IoContext<NetworkIoMessage>) {
-		let node_changes = match (self.udp_socket.lock().as_ref(), self.discovery.lock().as_mut()) {
-			(Some(udp_socket), Some(discovery)) => {
+		let node_changes = match (self.udp_socket.lock().as_ref(), self.discovery.lock().as_mut(), self.discovery_v5.lock().as_mut()) {
+			(Some(udp_socket), Some(discovery), Some(discovery_v5)) => {
 				let mut buf = [0u8; MAX_DATAGRAM_SIZE];
-				let writable = discovery.any_sends_queued();
+				let writable = discovery.any_sends_queued() || discovery_v5.any_sends_queued();
 				let res = match udp_socket.recv_from(&mut buf) {
-					Ok(Some((len, address))) => discovery.on_packet(&buf[0..len], address).unwrap_or_else(|e| {
-						debug!(target: "network", "Error processing UDP packet: {:?}", e);
-						None
-					}),
+					Ok(Some((len, address))) => {
+						let packet = &buf[0..len];
+						let res = if Discovery::is_v4_packet(packet) {
+							discovery.on_packet(packet, address)
+						} else {
+							discovery_v5.on_packet(packet, address)
+						};
+						res.unwrap_or_else(|e| {
+							debug!(target: "network", "Error processing UDP packet: {:?}", e);
+							None
+						})
+					},
 					Ok(_) => None,
 					Err(e) => {
 						debug!(target: "network", "Error reading UPD socket: {:?}", e);
 						None
 					}
 				};
-				let new_writable = discovery.any_sends_queued();
+				let new_writable = discovery.any_sends_queued() || discovery_v5.any_sends_queued();
 				if writable != new_writable {
 					io.update_registration(DISCOVERY)
 						.unwrap_or_else(|e| {
@@ -898,8 +1109,9 @@ impl Host {
 	}
 
 	fn discovery_writable(&self, io: &IoContext<NetworkIoMessage>) {
-		if let (Some(udp_socket), Some(discovery)) = (self.udp_socket.lock().as_ref(), self.discovery.lock().as_mut()) {
-			while let Some(data) = discovery.dequeue_send() {
+		if let (Some(udp_socket), Some(discovery), Some(discovery_v5)) = (self.udp_socket.lock().as_ref(), self.discovery.lock().as_mut(), self.discovery_v5.lock().as_mut()) {
+			// the v5 datagrams are requeued into the v4 queue when the socket is busy
+			while let Some(data) = discovery_v5.dequeue_send().or_else(|| discovery.dequeue_send()) {
 				match udp_socket.send_to(&data.payload, &data.address) {
 					Ok(Some(size)) if size == data.payload.len() => {
 					},
@@ -984,6 +1196,11 @@ impl Host {
 		for i in to_remove {
 			trace!(target: "network", "Removed from node table: {}", i);
 		}
+		if let Some(ref mut discovery) = *self.discovery_v5.lock() {
+			for enr in node_changes.records.values() {
+				discovery.add_enr(enr.clone());
+			}
+		}
 		let reserved_nodes = self.reserved_nodes.read();
 		self.nodes.write().update(node_changes, &*reserved_nodes);
 	}
@@ -1055,6 +1272,7 @@ impl IoHandler<NetworkIoMessage> for Host {
 					return;
 				}
 				self.discovery.lock().as_mut().map(|d| d.refresh());
+				self.discovery_v5.lock().as_mut().map(|d| d.refresh());
 				io.update_registration(DISCOVERY).unwrap_or_else(|e| debug!("Error updating discovery registration: {:?}", e));
 			},
 			FAST_DISCOVERY_REFRESH => {
@@ -1063,10 +1281,12 @@ impl IoHandler<NetworkIoMessage> for Host {
 					return;
 				}
 				self.discovery.lock().as_mut().map(|d| d.refresh());
+				self.discovery_v5.lock().as_mut().map(|d| d.refresh());
 				io.update_registration(DISCOVERY).unwrap_or_else(|e| debug!("Error updating discovery registration: {:?}", e));
 			},
 			DISCOVERY_ROUND => {
 				self.discovery.lock().as_mut().map(|d| d.round());
+				self.discovery_v5.lock().as_mut().map(|d| d.round());
 				io.update_registration(DISCOVERY).unwrap_or_else(|e| debug!("Error updating discovery registration: {:?}", e));
 			},
 			NODE_TABLE => {
@@ -1075,6 +1295,12 @@ impl IoHandler<NetworkIoMessage> for Host {
 				nodes.clear_useless();
 				nodes.save();
 			},
+			DNS_DISCOVERY => {
+				// the channel is full while a round is still in progress, skip this one then
+				if let Some(ref tx) = *self.dns_discovery_tx.lock() {
+					let _ = tx.try_send(());
+				}
+			},
 			_ => match self.timers.read().get(&token).cloned() {
 				Some(timer) => match self.handlers.read().get(&timer.protocol).cloned() {
 					None => { warn!(target: "network", "No handler found for protocol: {:?}", timer.protocol) },
@@ -1151,6 +1377,7 @@ impl IoHandler<NetworkIoMessage> for Host {
 			},
 			NetworkIoMessage::InitPublicInterface =>
 				self.init_public_interface(io).unwrap_or_else(|e| warn!("Error initializing public interface: {:?}", e)),
+			NetworkIoMessage::DnsNodesFound(ref records) => self.on_dns_nodes_found(io, records),
 			_ => {}	// ignore others.
 		}
 	}
@@ -1200,9 +1427,9 @@ impl IoHandler<NetworkIoMessage> for Host {
 					connection.lock().update_socket(reg, event_loop).expect("Error updating socket");
 				}
 			}
-			DISCOVERY => match (self.udp_socket.lock().as_ref(), self.discovery.lock().as_ref()) {
-				(Some(udp_socket), Some(discovery)) => {
-					let registration = if discovery.any_sends_queued() {
+			DISCOVERY => match (self.udp_socket.lock().as_ref(), self.discovery.lock().as_ref(), self.discovery_v5.lock().as_ref()) {
+				(Some(udp_socket), Some(discovery), Some(discovery_v5)) => {
+					let registration = if discovery.any_sends_queued() || discovery_v5.any_sends_queued() {
 						Ready::readable() | Ready::writable()
 					} else {
 						Ready::readable()
@@ -1218,6 +1445,26 @@ impl IoHandler<NetworkIoMessage> for Host {
 	}
 }
 
+/// Resolves DNS node trees on a dedicated thread since lookups block. Each message sent to the
+/// returned channel starts a round, the node records found are sent back to the IO handler.
+fn start_dns_discovery(mut dns_discovery: DnsDiscovery, channel: IoChannel<NetworkIoMessage>) -> io::Result<mpsc::SyncSender<()>> {
+	let (tx, rx) = mpsc::sync_channel(1);
+	thread::Builder::new()
+		.name("dns-discovery".into())
+		.spawn(move || {
+			for () in rx {
+				let records: Vec<_> = dns_discovery.sync(DNS_DISCOVERY_BATCH).iter().map(rlp::encode).collect();
+				if records.is_empty() {
+					continue;
+				}
+				if let Err(e) = channel.send(NetworkIoMessage::DnsNodesFound(records)) {
+					debug!(target: "network", "Error sending DNS discovery results: {:?}", e);
+				}
+			}
+		})?;
+	Ok(tx)
+}
+
 fn save_key(path: &Path, key: &Secret) {
 	let mut path_buf = PathBuf::from(path);
 	if let Err(e) = fs::create_dir_all(path_buf.as_path()) {
@@ -1268,6 +1515,37 @@ fn load_key(path: &Path) -> Option<Secret> {
 	}
 }
 
+fn save_enr_seq(path: &Path, seq: u64) {
+	let mut path_buf = PathBuf::from(path);
+	if let Err(e) = fs::create_dir_all(path_buf.as_path()) {
+		warn!("Error creating key directory: {:?}", e);
+		return;
+	};
+	path_buf.push("enr_seq");
+	if let Err(e) = fs::write(path_buf.as_path(), seq.to_string()) {
+		warn!("Error writing node record sequence number: {:?}", e);
+	}
+}
+
+fn load_enr_seq(path: &Path) -> Option<u64> {
+	let mut path_buf = PathBuf::from(path);
+	path_buf.push("enr_seq");
+	let buf = match fs::read_to_string(path_buf.as_path()) {
+		Ok(buf) => buf,
+		Err(e) => {
+			debug!("Error opening node record sequence number file: {:?}", e);
+			return None;
+		}
+	};
+	match buf.trim().parse() {
+		Ok(seq) => Some(seq),
+		Err(e) => {
+			warn!("Error parsing node record sequence number: {:?}", e);
+			None
+		}
+	}
+}
+
 #[test]
 fn key_save_load() {
 	use tempdir::TempDir;
@@ -1287,3 +1565,41 @@ fn host_client_url() {
 	let host: Host = Host::new(config, None).unwrap();
 	assert!(host.local_url().starts_with("enode://101b3ef5a4ea7a1c7928e24c4c75fd053c235d7b80c22ae5c03d145d0ac7396e2a4ffff9adee3133a7b05044a5cee08115fd65145e5165d646bde371010d803c@"));
 }
+
+#[test]
+fn host_enr() {
+	let mut config = NetworkConfiguration::new_local();
+	let key: Secret = "6f7b0d801bc7b5ce7bbd930b84fd0369b3eb25d09be58d64ba811091046f3aa2".parse().unwrap();
+	config.use_secret = Some(key);
+	let host: Host = Host::new(config, None).unwrap();
+	assert_eq!(host.external_enr(), None);
+
+	let seq = host.info.read().enr.seq();
+	host.set_enr_entry("eth", rlp::encode_list::<u64, _>(&[1, 2]));
+	host.set_enr_entry("eth", rlp::encode_list::<u64, _>(&[1, 2]));
+	let info = host.info.read();
+	assert_eq!(info.enr.seq(), seq + 1);
+	assert_eq!(info.enr.id(), info.id());
+	assert_eq!(info.enr.endpoint(), Some(info.local_endpoint.clone()));
+}
+
+#[test]
+fn host_enr_seq_survives_restart() {
+	use tempdir::TempDir;
+
+	let tempdir = TempDir::new("").unwrap();
+	let mut config = NetworkConfiguration::new_local();
+	config.config_path = Some(tempdir.path().to_str().unwrap().to_owned());
+
+	let seq = {
+		let host = Host::new(config.clone(), None).unwrap();
+		host.set_enr_entry("eth", rlp::encode_list::<u64, _>(&[1, 2]));
+		let seq = host.info.read().enr.seq();
+		assert_eq!(load_enr_seq(tempdir.path()), Some(seq));
+		seq
+	};
+
+	let host = Host::new(config, None).unwrap();
+	assert_eq!(host.info.read().enr.seq(), seq + 1);
+	assert_eq!(load_enr_seq(tempdir.path()), Some(seq + 1));
+}
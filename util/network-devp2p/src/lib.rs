@@ -49,7 +49,7 @@
 //! fn main () {
 //! 	let mut service = NetworkService::new(NetworkConfiguration::new_local(), None).expect("Error creating network service");
 //! 	service.start().expect("Error starting service");
-//! 	service.register_protocol(Arc::new(MyHandler), *b"myp", &[(1u8, 1u8)]);
+//! 	service.register_protocol(Arc::new(MyHandler), protocol_id(b"myp").unwrap(), &[(1u8, 1u8)]);
 //!
 //! 	// Wait for quit condition
 //! 	// ...
@@ -61,6 +61,9 @@
 #![allow(deprecated)]
 
 pub use ethcore_io::TimerToken;
+pub use discovery_v5::TalkHandler;
+pub use dns_discovery::{DnsResolver, NodeTree, TreeLink};
+pub use enr::{Enr, EnrFilter};
 pub use host::NetworkContext;
 pub use node_table::{MAX_NODES_IN_TABLE, NodeId, validate_node_url};
 pub use service::NetworkService;
@@ -70,6 +73,9 @@ mod connection;
 mod handshake;
 mod session;
 mod discovery;
+mod discovery_v5;
+mod dns_discovery;
+mod enr;
 mod service;
 mod node_table;
 mod ip_utils;
@@ -35,6 +35,7 @@ use network::{AllowIP, Error, IpFilter};
 
 use crate::{
 	discovery::{NodeEntry, TableUpdates},
+	enr::{Enr, ENR_PREFIX},
 	ip_utils::*,
 };
 
@@ -193,6 +194,8 @@ pub struct Node {
 	pub endpoint: NodeEndpoint,
 	pub peer_type: PeerType,
 	pub last_contact: Option<NodeContact>,
+	/// Latest known record of the node.
+	pub enr: Option<Enr>,
 }
 
 impl Node {
@@ -202,6 +205,26 @@ impl Node {
 			endpoint,
 			peer_type: PeerType::Optional,
 			last_contact: None,
+			enr: None,
+		}
+	}
+
+	/// Creates a node announced by a record.
+	pub fn from_enr(enr: Enr) -> Result<Node, Error> {
+		let endpoint = enr.endpoint().ok_or(Error::AddressParse)?;
+		Ok(Node {
+			id: *enr.id(),
+			endpoint,
+			peer_type: PeerType::Optional,
+			last_contact: None,
+			enr: Some(enr),
+		})
+	}
+
+	/// Keeps the given record if it's newer than the known one.
+	fn update_enr(&mut self, enr: Enr) {
+		if self.enr.as_ref().map_or(true, |known| known.seq() < enr.seq()) {
+			self.enr = Some(enr);
 		}
 	}
 }
@@ -220,6 +243,10 @@ impl Display for Node {
 impl FromStr for Node {
 	type Err = Error;
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.starts_with(ENR_PREFIX) {
+			return Node::from_enr(Enr::from_str(s)?);
+		}
+
 		let (id, endpoint) = if s.len() > 136 && &s[0..8] == "enode://" && &s[136..137] == "@" {
 			(s[8..136].parse().map_err(|_| Error::InvalidNodeId)?, NodeEndpoint::from_str(&s[137..])?)
 		}
@@ -232,6 +259,7 @@ impl FromStr for Node {
 			endpoint,
 			peer_type: PeerType::Optional,
 			last_contact: None,
+			enr: None,
 		})
 	}
 }
@@ -275,8 +303,11 @@ impl NodeTable {
 
 	/// Add a node to table
 	pub fn add_node(&mut self, mut node: Node) {
-		// preserve node last_contact
+		// preserve node last_contact and record
 		node.last_contact = self.nodes.get(&node.id).and_then(|n| n.last_contact);
+		if let Some(enr) = self.nodes.get(&node.id).and_then(|n| n.enr.clone()) {
+			node.update_enr(enr);
+		}
 		let id = node.id;
 		if self.ordered_ids.len() == MAX_NODES_IN_TABLE {
 			self.nodes.remove(&self.ordered_ids.pop().expect("ordered_ids is not empty; qed"));
@@ -428,6 +459,11 @@ impl NodeTable {
 				self.ordered_ids.insert(index, node.id);
 			};
 		};
+		for (id, enr) in update.records {
+			if let Some(node) = self.nodes.get_mut(&id) {
+				node.update_enr(enr);
+			}
+		}
 		for r in update.removed {
 			if !reserved.contains(&r) {
 				self.ordered_ids.iter().position(|&i| r == i).map(|p| self.ordered_ids.remove(p));
@@ -574,6 +610,8 @@ mod json {
 	pub struct Node {
 		pub url: String,
 		pub last_contact: Option<NodeContact>,
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		pub enr: Option<String>,
 	}
 
 	impl Node {
@@ -581,6 +619,9 @@ mod json {
 			match super::Node::from_str(&self.url) {
 				Ok(mut node) => {
 					node.last_contact = self.last_contact.map(|c| c.into_node_contact());
+					if let Some(enr) = self.enr.and_then(|enr| Enr::from_str(&enr).ok()).filter(|enr| *enr.id() == node.id) {
+						node.update_enr(enr);
+					}
 					Some(node)
 				},
 				_ => None,
@@ -601,7 +642,8 @@ mod json {
 
 			Node {
 				url: format!("{}", node),
-				last_contact
+				last_contact,
+				enr: node.enr.as_ref().map(|enr| enr.to_string()),
 			}
 		}
 	}
@@ -616,6 +658,7 @@ mod tests {
 
 	use ethereum_types::H512;
 	use ipnetwork::IpNetwork;
+	use parity_crypto::publickey::{Generator, Random};
 	use tempdir::TempDir;
 
 	use assert_matches::assert_matches;
@@ -683,6 +726,21 @@ mod tests {
 		assert_matches!(node.unwrap_err(), Error::AddressParse);
 	}
 
+	#[test]
+	fn node_parse_enr() {
+		let key = Random.generate().unwrap();
+		let endpoint = NodeEndpoint::from_str("22.99.55.44:7770").unwrap();
+		let enr = Enr::new(&key, &endpoint).unwrap().to_string();
+		assert!(validate_node_url(&enr).is_none());
+
+		let node = Node::from_str(&enr).unwrap();
+		assert_eq!(&node.id, key.public());
+		assert_eq!(node.endpoint, endpoint);
+		assert_eq!(node.enr.map(|enr| enr.seq()), Some(1));
+
+		assert!(Node::from_str("enr:-IS4QHCYrYZbAKWCBRlAy5zzaDZXJBGkcnh4MHcBFZntXNFrdvJjX04jRzjzCBOonrkTfj499SZuOh8R33Ls8RRcy5wBgmlkgnY0gmlwhH8AAAHJc2VjcDI1NmsxoQPKY0yuDUmstAHYpMa2_oxVtw0RW_QAdpzBQA8yWM0xOIN1ZHCCdl8").is_err());
+	}
+
 	#[test]
 	fn table_last_contact_order() {
 		let node1 = Node::from_str("enode://a979fb575495b8d6db44f750317d0f4622bf4c2aa3365d6af7c284339968eef29b69ad0dce72a4d8db5ebb4968de0e3bec910127f134779fbcb0cb6d3331163c@22.99.55.44:7770").unwrap();
@@ -809,6 +867,28 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn table_save_load_records() {
+		let tempdir = TempDir::new("").unwrap();
+		let key = Random.generate().unwrap();
+		let endpoint = NodeEndpoint::from_str("22.99.55.44:7770").unwrap();
+		let mut enr = Enr::new(&key, &endpoint).unwrap();
+
+		{
+			let mut table = NodeTable::new(Some(tempdir.path().to_str().unwrap().to_owned()));
+			table.add_node(Node::new(*key.public(), endpoint.clone()));
+			enr.set(&key, "eth", rlp::encode_list::<u64, _>(&[1, 2])).unwrap();
+			let mut records = HashMap::new();
+			records.insert(*key.public(), enr.clone());
+			table.update(TableUpdates { added: HashMap::new(), removed: HashSet::new(), records }, &HashSet::new());
+		}
+
+		{
+			let table = NodeTable::new(Some(tempdir.path().to_str().unwrap().to_owned()));
+			assert_eq!(table.get(key.public()).and_then(|node| node.enr.clone()), Some(enr));
+		}
+	}
+
 	#[test]
 	fn custom_allow() {
 		let filter = IpFilter {
@@ -23,13 +23,18 @@ use log::info;
 use parking_lot::RwLock;
 
 use ethcore_io::{IoContext, IoHandler, IoService};
+use ethereum_types::H256;
+use parity_bytes::Bytes;
 use network::{
 	ConnectionFilter, Error, NetworkConfiguration, NetworkContext,
 	NetworkIoMessage, NetworkProtocolHandler, NonReservedPeerMode, PeerId, ProtocolId,
 
 };
 
+use crate::discovery_v5::TalkHandler;
+use crate::enr::{Enr, EnrFilter};
 use crate::host::Host;
+use crate::node_table::NodeId;
 
 struct HostHandler {
 	public_url: RwLock<Option<String>>
@@ -117,6 +122,68 @@ impl NetworkService {
 		host.as_ref().map(|h| h.local_url())
 	}
 
+	/// Returns our node record if available.
+	pub fn external_enr(&self) -> Option<String> {
+		let host = self.host.read();
+		host.as_ref().and_then(|h| h.external_enr())
+	}
+
+	/// Sets an entry of our node record, `value` is RLP encoded. Does nothing if the network is not started.
+	pub fn set_enr_entry(&self, key: &str, value: Bytes) {
+		let host = self.host.read();
+		if let Some(ref host) = *host {
+			host.set_enr_entry(key, value);
+		}
+	}
+
+	/// Sets the filter of the record entry `key`, nodes whose record fails it aren't dialed.
+	/// Does nothing if the network is not started.
+	pub fn set_enr_filter(&self, key: &str, filter: EnrFilter) {
+		let host = self.host.read();
+		if let Some(ref host) = *host {
+			host.set_enr_filter(key, filter);
+		}
+	}
+
+	/// Registers the handler of a protocol running over discovery v5 TALKREQ messages.
+	/// Does nothing if the network is not started.
+	pub fn register_talk_protocol(&self, protocol: &[u8], handler: Arc<dyn TalkHandler>) {
+		let host = self.host.read();
+		if let Some(ref host) = *host {
+			host.register_talk_protocol(protocol, handler);
+		}
+	}
+
+	/// Sends a TALKREQ to the node.
+	pub fn send_talk_request(&self, id: &NodeId, protocol: &[u8], request: &[u8]) -> Result<(), Error> {
+		let host = self.host.read();
+		match *host {
+			Some(ref host) => host.send_talk_request(id, protocol, request),
+			None => Err(Error::PeerNotFound),
+		}
+	}
+
+	/// Advertises our node under the discovery v5 topic. Does nothing if the network is not started.
+	pub fn register_topic(&self, topic: H256) {
+		let host = self.host.read();
+		if let Some(ref host) = *host {
+			host.register_topic(topic);
+		}
+	}
+
+	/// Looks up nodes advertised under the topic. Does nothing if the network is not started.
+	pub fn query_topic(&self, topic: H256) {
+		let host = self.host.read();
+		if let Some(ref host) = *host {
+			host.query_topic(topic);
+		}
+	}
+
+	/// Records of the nodes known to be advertised under the topic.
+	pub fn topic_nodes(&self, topic: &H256) -> Vec<Enr> {
+		self.host.read().as_ref().map(|h| h.topic_nodes(topic)).unwrap_or_else(Vec::new)
+	}
+
 	/// Start network IO.
 	///
 	/// In case of error, also returns the listening address for better error reporting.
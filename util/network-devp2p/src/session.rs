@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::{io, str};
+use std::io;
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::time::{Duration, Instant};
@@ -28,7 +28,7 @@ use parity_snappy as snappy;
 use rlp::{EMPTY_LIST_RLP, Rlp, RlpStream};
 
 use ethcore_io::{IoContext, StreamToken};
-use network::{DisconnectReason, Error, PeerCapabilityInfo, ProtocolId, SessionInfo};
+use network::{protocol_name, DisconnectReason, Error, PeerCapabilityInfo, ProtocolId, SessionInfo};
 use network::client_version::ClientVersion;
 use network::SessionCapabilityInfo;
 
@@ -86,7 +86,7 @@ pub enum SessionData {
 		/// Packet data
 		data: Vec<u8>,
 		/// Packet protocol ID
-		protocol: [u8; 3],
+		protocol: ProtocolId,
 		/// Zero based packet ID
 		packet_id: u8,
 	},
@@ -225,12 +225,12 @@ impl Session {
 	}
 
 	/// Checks if peer supports given capability
-	pub fn have_capability(&self, protocol: [u8; 3]) -> bool {
+	pub fn have_capability(&self, protocol: ProtocolId) -> bool {
 		self.info.capabilities.iter().any(|c| c.protocol == protocol)
 	}
 
 	/// Checks if peer supports given capability
-	pub fn capability_version(&self, protocol: [u8; 3]) -> Option<u8> {
+	pub fn capability_version(&self, protocol: ProtocolId) -> Option<u8> {
 		self.info.capabilities.iter().filter_map(|c| if c.protocol == protocol { Some(c.version) } else { None }).max()
 	}
 
@@ -256,10 +256,10 @@ impl Session {
 	}
 
 	/// Send a protocol packet to peer.
-	pub fn send_packet<Message>(&mut self, io: &IoContext<Message>, protocol: Option<[u8; 3]>, packet_id: u8, data: &[u8]) -> Result<(), Error>
+	pub fn send_packet<Message>(&mut self, io: &IoContext<Message>, protocol: Option<ProtocolId>, packet_id: u8, data: &[u8]) -> Result<(), Error>
         where Message: Send + Sync + Clone {
 		if protocol.is_some() && (self.info.capabilities.is_empty() || !self.had_hello) {
-			debug!(target: "network", "Sending to unconfirmed session {}, protocol: {:?}, packet: {}", self.token(), protocol.as_ref().map(|p| str::from_utf8(&p[..]).unwrap_or("??")), packet_id);
+			debug!(target: "network", "Sending to unconfirmed session {}, protocol: {:?}, packet: {}", self.token(), protocol.as_ref().map(|p| String::from_utf8_lossy(&protocol_name(p)).into_owned()), packet_id);
 			return Err(Error::BadProtocol);
 		}
 		if self.expired() {
@@ -24,7 +24,7 @@ use std::time::Duration;
 use parity_bytes::Bytes;
 use parking_lot::Mutex;
 
-use network::{PeerId, NetworkContext, NetworkProtocolHandler, NetworkConfiguration};
+use network::{protocol_id, PeerId, NetworkContext, NetworkProtocolHandler, NetworkConfiguration};
 use ethcore_network_devp2p::NetworkService;
 use parity_crypto::publickey::{Generator, Random};
 use ethcore_io::TimerToken;
@@ -48,7 +48,7 @@ impl TestProtocol {
 	/// Creates and register protocol with the network service
 	pub fn register(service: &mut NetworkService, drop_session: bool) -> Arc<TestProtocol> {
 		let handler = Arc::new(TestProtocol::new(drop_session));
-		service.register_protocol(handler.clone(), *b"tst", &[(42u8, 1u8), (43u8, 1u8)]).expect("Error registering test protocol handler");
+		service.register_protocol(handler.clone(), protocol_id(b"tst").unwrap(), &[(42u8, 1u8), (43u8, 1u8)]).expect("Error registering test protocol handler");
 		handler
 	}
 
@@ -99,7 +99,7 @@ impl NetworkProtocolHandler for TestProtocol {
 fn net_service() {
 	let service = NetworkService::new(NetworkConfiguration::new_local(), None).expect("Error creating network service");
 	service.start().unwrap();
-	service.register_protocol(Arc::new(TestProtocol::new(false)), *b"myp", &[(1u8, 1u8)]).unwrap();
+	service.register_protocol(Arc::new(TestProtocol::new(false)), protocol_id(b"myp").unwrap(), &[(1u8, 1u8)]).unwrap();
 }
 
 #[test]
@@ -114,6 +114,9 @@ pub enum Error {
 	/// Invalid node id
 	#[display(fmt = "Invalid node id")]
 	InvalidNodeId,
+	/// DNS node discovery failure
+	#[display(fmt = "DNS discovery error: {}", _0)]
+	DnsDiscovery(&'static str),
 	/// Packet size is over the protocol limit
 	#[display(fmt = "Packet is too large")]
 	OversizedPacket,
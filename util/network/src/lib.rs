@@ -54,17 +54,36 @@ use std::sync::Arc;
 use std::time::Duration;
 use ipnetwork::{IpNetwork, IpNetworkError};
 use crypto::publickey::Secret;
-use ethereum_types::H512;
+use ethereum_types::{H512, U64};
 use rlp::{Decodable, DecoderError, Rlp};
 
 /// Protocol handler level packet id
 pub type PacketId = u8;
-/// Protocol / handler id
-pub type ProtocolId = [u8; 3];
+/// Protocol / handler id: the capability name, up to 8 bytes, packed from the most
+/// significant byte so that ids compare like the names do.
+pub type ProtocolId = U64;
 
 /// Node public key
 pub type NodeId = H512;
 
+/// Get the protocol id of a capability name, `None` if the name is longer than 8 bytes.
+pub fn protocol_id(name: &[u8]) -> Option<ProtocolId> {
+	if name.len() > 8 {
+		return None;
+	}
+	let mut id = [0u8; 8];
+	id[..name.len()].copy_from_slice(name);
+	Some(U64::from_big_endian(&id))
+}
+
+/// Get the capability name of a protocol id.
+pub fn protocol_name(id: &ProtocolId) -> Vec<u8> {
+	let mut name = [0u8; 8];
+	id.to_big_endian(&mut name);
+	let len = name.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+	name[..len].to_vec()
+}
+
 /// Local (temporary) peer session ID.
 pub type PeerId = usize;
 
@@ -97,6 +116,8 @@ pub enum NetworkIoMessage {
 	DisablePeer(PeerId),
 	/// Network has been started with the host as the given enode.
 	NetworkStarted(String),
+	/// Node records found by DNS discovery, RLP encoded.
+	DnsNodesFound(Vec<Vec<u8>>),
 }
 
 /// Shared session information
@@ -131,13 +152,10 @@ pub struct PeerCapabilityInfo {
 impl Decodable for PeerCapabilityInfo {
 	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
 		let p: Vec<u8> = rlp.val_at(0)?;
-		if p.len() != 3 {
-			return Err(DecoderError::Custom("Invalid subprotocol string length. Should be 3"));
-		}
-		let mut p2: ProtocolId = [0u8; 3];
-		p2.clone_from_slice(&p);
+		let protocol = protocol_id(&p)
+			.ok_or(DecoderError::Custom("Invalid subprotocol string length. Should be at most 8"))?;
 		Ok(PeerCapabilityInfo {
-			protocol: p2,
+			protocol,
 			version: rlp.val_at(1)?
 		})
 	}
@@ -145,13 +163,13 @@ impl Decodable for PeerCapabilityInfo {
 
 impl ToString for PeerCapabilityInfo {
 	fn to_string(&self) -> String {
-		format!("{}/{}", str::from_utf8(&self.protocol[..]).unwrap_or("???"), self.version)
+		format!("{}/{}", str::from_utf8(&protocol_name(&self.protocol)).unwrap_or("???"), self.version)
 	}
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SessionCapabilityInfo {
-	pub protocol: [u8; 3],
+	pub protocol: ProtocolId,
 	pub version: u8,
 	pub packet_count: u8,
 	pub id_offset: u8,
@@ -204,6 +222,8 @@ pub struct NetworkConfiguration {
 	pub discovery_enabled: bool,
 	/// List of initial node addresses
 	pub boot_nodes: Vec<String>,
+	/// Locations (`enrtree://`) of node lists published in DNS
+	pub dns_discovery: Vec<String>,
 	/// Use provided node key instead of default
 	pub use_secret: Option<Secret>,
 	/// Minimum number of connected peers to maintain
@@ -243,6 +263,7 @@ impl NetworkConfiguration {
 			nat_type: NatType::Any,
 			discovery_enabled: true,
 			boot_nodes: Vec::new(),
+			dns_discovery: Vec::new(),
 			use_secret: None,
 			min_peers: 25,
 			max_peers: 50,
@@ -455,3 +476,30 @@ pub enum AllowIP {
 	/// Block all addresses
 	None,
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rlp::RlpStream;
+
+	#[test]
+	fn protocol_ids_keep_names_and_their_order() {
+		let eth = protocol_id(b"eth").unwrap();
+		let snap = protocol_id(b"snap").unwrap();
+		assert_eq!(protocol_name(&eth), b"eth".to_vec());
+		assert_eq!(protocol_name(&snap), b"snap".to_vec());
+		assert!(eth < protocol_id(b"par").unwrap());
+		assert!(protocol_id(b"par").unwrap() < snap);
+		assert!(protocol_id(b"sna").unwrap() < snap);
+		assert!(protocol_id(b"too_long_").is_none());
+	}
+
+	#[test]
+	fn decodes_long_capability_names() {
+		let mut stream = RlpStream::new_list(2);
+		stream.append(&"snap").append(&1u8);
+		let info: PeerCapabilityInfo = rlp::decode(&stream.out()).unwrap();
+		assert_eq!(info.protocol, protocol_id(b"snap").unwrap());
+		assert_eq!(info.to_string(), "snap/1");
+	}
+}
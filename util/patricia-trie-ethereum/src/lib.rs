@@ -25,6 +25,7 @@ extern crate keccak_hasher;
 extern crate rlp;
 
 mod rlp_node_codec;
+pub mod range_proof;
 
 pub use rlp_node_codec::RlpNodeCodec;
 
@@ -0,0 +1,664 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Range proofs and path addressing of trie nodes, as used by the `snap` protocol.
+//!
+//! A range proof of consecutive trie entries is made of the trie nodes on the paths to the
+//! origin of the range and to its last key. Together with the entries, it is enough to rebuild
+//! the root of the trie, which shows that no entry of the range was left out.
+
+use std::collections::HashMap;
+use std::{fmt, iter, mem};
+
+use ethereum_types::H256;
+use hash_db::Hasher;
+use keccak_hasher::KeccakHasher;
+use parity_bytes::Bytes;
+use rlp::{DecoderError, Rlp, RlpStream};
+
+/// Range proof verification error.
+#[derive(Debug, PartialEq)]
+pub enum RangeProofError {
+	/// Entries are not sorted by key or start before the origin.
+	UnorderedEntries,
+	/// An entry has an empty value.
+	EmptyValue,
+	/// A node needed to rebuild the trie is missing from the proof.
+	MissingNode(H256),
+	/// A node of the proof is invalid.
+	Decoder(DecoderError),
+	/// The entries and the proof do not rebuild the expected root.
+	RootMismatch,
+	/// No entries were given but the proof shows some past the origin.
+	MissingEntries,
+}
+
+impl fmt::Display for RangeProofError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			RangeProofError::UnorderedEntries => write!(f, "Entries are not ordered"),
+			RangeProofError::EmptyValue => write!(f, "Entry with an empty value"),
+			RangeProofError::MissingNode(ref hash) => write!(f, "Proof node {:?} is missing", hash),
+			RangeProofError::Decoder(ref err) => write!(f, "Invalid proof node: {}", err),
+			RangeProofError::RootMismatch => write!(f, "Proof does not match the root"),
+			RangeProofError::MissingEntries => write!(f, "Entries are missing from the range"),
+		}
+	}
+}
+
+impl From<DecoderError> for RangeProofError {
+	fn from(err: DecoderError) -> Self {
+		RangeProofError::Decoder(err)
+	}
+}
+
+/// Reference found in a trie node.
+#[derive(Debug, PartialEq)]
+pub enum NodeReference {
+	/// Child node stored under its hash, with its path relative to the node.
+	Child(Vec<u8>, H256),
+	/// Trie entry, with the path of its key relative to the node.
+	Leaf(Vec<u8>, Bytes),
+}
+
+#[derive(Debug)]
+enum Node {
+	Empty,
+	/// Node known only by its hash.
+	Hash(H256),
+	Leaf(Vec<u8>, Bytes),
+	Extension(Vec<u8>, Box<Node>),
+	Branch(Vec<Node>, Option<Bytes>),
+}
+
+#[derive(Debug, PartialEq)]
+enum Span {
+	Inside,
+	Outside,
+	Crossing,
+}
+
+/// Splits bytes into nibbles.
+pub fn to_nibbles(bytes: &[u8]) -> Vec<u8> {
+	bytes.iter().flat_map(|b| iter::once(b >> 4).chain(iter::once(b & 0x0f))).collect()
+}
+
+/// Joins nibbles into bytes, an odd last nibble is padded with zero.
+pub fn from_nibbles(nibbles: &[u8]) -> Vec<u8> {
+	nibbles.chunks(2).map(|pair| pair[0] << 4 | pair.get(1).cloned().unwrap_or(0)).collect()
+}
+
+/// Compact (hex-prefix) encoding of a trie path given in nibbles.
+pub fn encode_path(nibbles: &[u8]) -> Bytes {
+	encode_hex_prefix(nibbles, false)
+}
+
+/// Decodes a compact (hex-prefix) encoded trie path into nibbles.
+pub fn decode_path(encoded: &[u8]) -> Result<Vec<u8>, DecoderError> {
+	decode_hex_prefix(encoded).map(|(nibbles, _)| nibbles)
+}
+
+fn encode_hex_prefix(nibbles: &[u8], is_leaf: bool) -> Bytes {
+	let flag = if is_leaf { 0x20 } else { 0 };
+	let mut encoded = Vec::with_capacity(nibbles.len() / 2 + 1);
+	let even = if nibbles.len() % 2 == 1 {
+		encoded.push(flag | 0x10 | nibbles[0]);
+		&nibbles[1..]
+	} else {
+		encoded.push(flag);
+		nibbles
+	};
+	encoded.extend(even.chunks(2).map(|pair| pair[0] << 4 | pair[1]));
+	encoded
+}
+
+fn decode_hex_prefix(encoded: &[u8]) -> Result<(Vec<u8>, bool), DecoderError> {
+	let first = *encoded.first().ok_or(DecoderError::RlpIsTooShort)?;
+	if first >> 4 > 3 {
+		return Err(DecoderError::Custom("Invalid hex prefix"));
+	}
+	let mut nibbles = Vec::with_capacity(encoded.len() * 2);
+	if first & 0x10 != 0 {
+		nibbles.push(first & 0x0f);
+	}
+	nibbles.extend(to_nibbles(&encoded[1..]));
+	Ok((nibbles, first & 0x20 != 0))
+}
+
+fn decode_node(data: &[u8]) -> Result<Node, DecoderError> {
+	let rlp = Rlp::new(data);
+	if rlp.is_data() && rlp.data()?.is_empty() {
+		return Ok(Node::Empty);
+	}
+	match rlp.item_count()? {
+		2 => {
+			let (path, is_leaf) = decode_hex_prefix(rlp.at(0)?.data()?)?;
+			if is_leaf {
+				Ok(Node::Leaf(path, rlp.at(1)?.data()?.to_vec()))
+			} else {
+				Ok(Node::Extension(path, Box::new(decode_child(&rlp.at(1)?)?)))
+			}
+		},
+		17 => {
+			let mut children = Vec::with_capacity(16);
+			for i in 0..16 {
+				children.push(decode_child(&rlp.at(i)?)?);
+			}
+			let value = rlp.at(16)?.data()?;
+			Ok(Node::Branch(children, if value.is_empty() { None } else { Some(value.to_vec()) }))
+		},
+		_ => Err(DecoderError::RlpIncorrectListLen),
+	}
+}
+
+fn decode_child(rlp: &Rlp) -> Result<Node, DecoderError> {
+	if rlp.is_list() {
+		return decode_node(rlp.as_raw());
+	}
+	let data = rlp.data()?;
+	match data.len() {
+		0 => Ok(Node::Empty),
+		32 => Ok(Node::Hash(H256::from_slice(data))),
+		_ => Err(DecoderError::RlpInvalidLength),
+	}
+}
+
+fn encode_node(node: &Node) -> Bytes {
+	let mut stream = RlpStream::new();
+	match *node {
+		Node::Empty | Node::Hash(_) => {
+			stream.append_empty_data();
+		},
+		Node::Leaf(ref path, ref value) => {
+			stream.begin_list(2);
+			stream.append(&encode_hex_prefix(path, true));
+			stream.append(&&**value);
+		},
+		Node::Extension(ref path, ref child) => {
+			stream.begin_list(2);
+			stream.append(&encode_hex_prefix(path, false));
+			append_child(&mut stream, child);
+		},
+		Node::Branch(ref children, ref value) => {
+			stream.begin_list(17);
+			for child in children {
+				append_child(&mut stream, child);
+			}
+			match *value {
+				Some(ref value) => { stream.append(&&**value); },
+				None => { stream.append_empty_data(); },
+			}
+		},
+	}
+	stream.out()
+}
+
+fn append_child(stream: &mut RlpStream, node: &Node) {
+	match *node {
+		Node::Empty => { stream.append_empty_data(); },
+		Node::Hash(ref hash) => { stream.append(hash); },
+		_ => {
+			let encoded = encode_node(node);
+			if encoded.len() < 32 {
+				stream.append_raw(&encoded, 1);
+			} else {
+				stream.append(&KeccakHasher::hash(&encoded));
+			}
+		},
+	}
+}
+
+fn node_hash(node: &Node) -> H256 {
+	match *node {
+		Node::Hash(ref hash) => *hash,
+		_ => KeccakHasher::hash(&encode_node(node)),
+	}
+}
+
+fn resolve(hash: &H256, proof: &HashMap<H256, &[u8]>) -> Result<Node, RangeProofError> {
+	let data = proof.get(hash).ok_or(RangeProofError::MissingNode(*hash))?;
+	Ok(decode_node(data)?)
+}
+
+fn with_extension(path: &[u8], node: Node) -> Node {
+	if path.is_empty() {
+		node
+	} else {
+		Node::Extension(path.to_vec(), Box::new(node))
+	}
+}
+
+fn common_prefix(a: &[u8], b: &[u8]) -> usize {
+	a.iter().zip(b).take_while(|(x, y)| x == y).count()
+}
+
+fn insert(node: Node, path: &[u8], value: Bytes) -> Result<Node, RangeProofError> {
+	Ok(match node {
+		Node::Empty => Node::Leaf(path.to_vec(), value),
+		Node::Hash(hash) => return Err(RangeProofError::MissingNode(hash)),
+		Node::Leaf(leaf_path, leaf_value) => {
+			let common = common_prefix(&leaf_path, path);
+			if common == leaf_path.len() && common == path.len() {
+				Node::Leaf(leaf_path, value)
+			} else {
+				let branch = Node::Branch((0..16).map(|_| Node::Empty).collect(), None);
+				let branch = insert(branch, &leaf_path[common..], leaf_value)?;
+				let branch = insert(branch, &path[common..], value)?;
+				with_extension(&path[..common], branch)
+			}
+		},
+		Node::Extension(extension_path, child) => {
+			let common = common_prefix(&extension_path, path);
+			if common == extension_path.len() {
+				Node::Extension(extension_path, Box::new(insert(*child, &path[common..], value)?))
+			} else {
+				let mut children: Vec<_> = (0..16).map(|_| Node::Empty).collect();
+				children[extension_path[common] as usize] = with_extension(&extension_path[common + 1..], *child);
+				let branch = insert(Node::Branch(children, None), &path[common..], value)?;
+				with_extension(&path[..common], branch)
+			}
+		},
+		Node::Branch(mut children, branch_value) => {
+			if path.is_empty() {
+				Node::Branch(children, Some(value))
+			} else {
+				let index = path[0] as usize;
+				let child = mem::replace(&mut children[index], Node::Empty);
+				children[index] = insert(child, &path[1..], value)?;
+				Node::Branch(children, branch_value)
+			}
+		},
+	})
+}
+
+/// Where the keys under `prefix` are relative to the range `lo..=hi`.
+fn span(prefix: &[u8], lo: &[u8], hi: &[u8]) -> Span {
+	let len = prefix.len().min(lo.len());
+	let prefix = &prefix[..len];
+	let (lo_head, lo_tail) = lo.split_at(len);
+	let (hi_head, hi_tail) = hi.split_at(len);
+	if prefix < lo_head || prefix > hi_head {
+		return Span::Outside;
+	}
+	let above_lo = prefix > lo_head || lo_tail.iter().all(|n| *n == 0);
+	let below_hi = prefix < hi_head || hi_tail.iter().all(|n| *n == 0x0f);
+	if above_lo && below_hi {
+		Span::Inside
+	} else {
+		Span::Crossing
+	}
+}
+
+/// Removes the entries with keys in `lo..=hi` from the subtrie at `prefix`. Nodes crossing the
+/// bounds of the range are resolved from the proof, the others are kept as they are.
+fn clear_range(
+	node: Node,
+	prefix: &mut Vec<u8>,
+	lo: &[u8],
+	hi: &[u8],
+	proof: &HashMap<H256, &[u8]>,
+) -> Result<Node, RangeProofError> {
+	let node = match node {
+		Node::Hash(hash) => match span(prefix, lo, hi) {
+			Span::Inside => return Ok(Node::Empty),
+			Span::Outside => return Ok(Node::Hash(hash)),
+			Span::Crossing => resolve(&hash, proof)?,
+		},
+		node => node,
+	};
+	let start = prefix.len();
+	let cleared = match node {
+		Node::Leaf(path, value) => {
+			prefix.extend_from_slice(&path);
+			if span(prefix, lo, hi) == Span::Inside {
+				Node::Empty
+			} else {
+				Node::Leaf(path, value)
+			}
+		},
+		Node::Extension(path, child) => {
+			prefix.extend_from_slice(&path);
+			match span(prefix, lo, hi) {
+				Span::Inside => Node::Empty,
+				Span::Outside => Node::Extension(path, child),
+				Span::Crossing => match clear_range(*child, prefix, lo, hi, proof)? {
+					Node::Empty => Node::Empty,
+					child => Node::Extension(path, Box::new(child)),
+				},
+			}
+		},
+		Node::Branch(children, value) => {
+			let mut cleared = Vec::with_capacity(children.len());
+			for (index, child) in children.into_iter().enumerate() {
+				prefix.push(index as u8);
+				cleared.push(clear_range(child, prefix, lo, hi, proof)?);
+				prefix.pop();
+			}
+			Node::Branch(cleared, value)
+		},
+		node => node,
+	};
+	prefix.truncate(start);
+	Ok(cleared)
+}
+
+/// Whether the trie has entries with keys greater than `path`, or equal to it if `inclusive`.
+/// Only the nodes on the way to `path` need to be in the proof.
+fn has_entries_after(
+	node: Node,
+	path: &[u8],
+	inclusive: bool,
+	proof: &HashMap<H256, &[u8]>,
+) -> Result<bool, RangeProofError> {
+	Ok(match node {
+		Node::Empty => false,
+		Node::Hash(hash) => has_entries_after(resolve(&hash, proof)?, path, inclusive, proof)?,
+		Node::Leaf(leaf_path, _) => leaf_path.as_slice() > path || (inclusive && leaf_path.as_slice() == path),
+		Node::Extension(extension_path, child) => {
+			if path.starts_with(&extension_path) {
+				has_entries_after(*child, &path[extension_path.len()..], inclusive, proof)?
+			} else {
+				extension_path.as_slice() > path
+			}
+		},
+		Node::Branch(mut children, value) => match path.first() {
+			None => (inclusive && value.is_some()) || children.iter().any(|c| match *c { Node::Empty => false, _ => true }),
+			Some(nibble) => {
+				let index = *nibble as usize;
+				let child = mem::replace(&mut children[index], Node::Empty);
+				children[index + 1..].iter().any(|c| match *c { Node::Empty => false, _ => true })
+					|| has_entries_after(child, &path[1..], inclusive, proof)?
+			},
+		},
+	})
+}
+
+/// Verifies that `entries` are all the entries of the trie with the given root from `origin` up
+/// to the last entry's key. Without a proof, the entries must make up the whole trie.
+/// Returns whether the trie has more entries past the range.
+pub fn verify_range_proof(
+	root: &H256,
+	origin: &H256,
+	entries: &[(H256, Bytes)],
+	proof: &[Bytes],
+) -> Result<bool, RangeProofError> {
+	let mut previous: Option<&H256> = None;
+	for (key, value) in entries {
+		if value.is_empty() {
+			return Err(RangeProofError::EmptyValue);
+		}
+		if key < origin || previous.map_or(false, |previous| key <= previous) {
+			return Err(RangeProofError::UnorderedEntries);
+		}
+		previous = Some(key);
+	}
+
+	let proof: HashMap<H256, &[u8]> = proof.iter().map(|node| (KeccakHasher::hash(node), &node[..])).collect();
+	let lo = to_nibbles(origin.as_bytes());
+	let (last, trie) = match entries.last() {
+		_ if proof.is_empty() => (None, Node::Empty),
+		None => {
+			if has_entries_after(Node::Hash(*root), &lo, true, &proof)? {
+				return Err(RangeProofError::MissingEntries);
+			}
+			return Ok(false);
+		},
+		Some((last, _)) => {
+			let hi = to_nibbles(last.as_bytes());
+			(Some(hi.clone()), clear_range(Node::Hash(*root), &mut Vec::new(), &lo, &hi, &proof)?)
+		},
+	};
+
+	let mut trie = trie;
+	for (key, value) in entries {
+		trie = insert(trie, &to_nibbles(key.as_bytes()), value.clone())?;
+	}
+	if node_hash(&trie) != *root {
+		return Err(RangeProofError::RootMismatch);
+	}
+
+	match last {
+		Some(hi) => has_entries_after(Node::Hash(*root), &hi, false, &proof),
+		None => Ok(false),
+	}
+}
+
+/// Looks up the node at `path` (in nibbles) of the trie with the given root, `lookup` gives
+/// the encoded nodes by hash. Nodes embedded in their parent have no path of their own.
+pub fn node_at_path<F>(root: &H256, path: &[u8], lookup: F) -> Result<Option<Bytes>, DecoderError>
+	where F: Fn(&H256) -> Option<Bytes>
+{
+	let mut data = match lookup(root) {
+		Some(data) => data,
+		None => return Ok(None),
+	};
+	let mut path = path;
+	while !path.is_empty() {
+		let child = match decode_node(&data)? {
+			Node::Extension(extension_path, child) => {
+				if !path.starts_with(&extension_path) {
+					return Ok(None);
+				}
+				path = &path[extension_path.len()..];
+				*child
+			},
+			Node::Branch(mut children, _) => {
+				if path[0] >= 16 {
+					return Ok(None);
+				}
+				let child = mem::replace(&mut children[path[0] as usize], Node::Empty);
+				path = &path[1..];
+				child
+			},
+			_ => return Ok(None),
+		};
+		data = match child {
+			Node::Hash(hash) => match lookup(&hash) {
+				Some(data) => data,
+				None => return Ok(None),
+			},
+			_ => return Ok(None),
+		};
+	}
+	Ok(Some(data))
+}
+
+/// Lists the children stored under their hash and the entries of an encoded trie node,
+/// looking into the children embedded in it.
+pub fn node_references(data: &[u8]) -> Result<Vec<NodeReference>, DecoderError> {
+	fn walk(node: Node, path: &mut Vec<u8>, references: &mut Vec<NodeReference>) {
+		let start = path.len();
+		match node {
+			Node::Empty => {},
+			Node::Hash(hash) => references.push(NodeReference::Child(path.clone(), hash)),
+			Node::Leaf(leaf_path, value) => {
+				path.extend_from_slice(&leaf_path);
+				references.push(NodeReference::Leaf(path.clone(), value));
+			},
+			Node::Extension(extension_path, child) => {
+				path.extend_from_slice(&extension_path);
+				walk(*child, path, references);
+			},
+			Node::Branch(children, value) => {
+				for (index, child) in children.into_iter().enumerate() {
+					path.push(index as u8);
+					walk(child, path, references);
+					path.pop();
+				}
+				if let Some(value) = value {
+					references.push(NodeReference::Leaf(path.clone(), value));
+				}
+			},
+		}
+		path.truncate(start);
+	}
+
+	let mut references = Vec::new();
+	walk(decode_node(data)?, &mut Vec::new(), &mut references);
+	Ok(references)
+}
+
+#[cfg(test)]
+mod tests {
+	use ethereum_types::H256;
+	use hash_db::Hasher;
+	use keccak_hasher::KeccakHasher;
+	use parity_bytes::Bytes;
+	use trie::{Recorder, Trie, TrieMut};
+
+	use crate::{TrieDB, TrieDBMut};
+	use super::*;
+
+	fn entries(count: u64) -> Vec<(H256, Bytes)> {
+		let mut entries: Vec<_> = (0..count)
+			.map(|i| (KeccakHasher::hash(&i.to_be_bytes()), vec![i as u8 + 1; 1 + i as usize % 40]))
+			.collect();
+		entries.sort();
+		entries
+	}
+
+	type MemoryDB = memory_db::MemoryDB<KeccakHasher, memory_db::HashKey<KeccakHasher>, trie::DBValue>;
+
+	/// Returns the database and the root of a trie of the entries.
+	fn build_trie(entries: &[(H256, Bytes)]) -> (MemoryDB, H256) {
+		let mut db = journaldb::new_memory_db();
+		let mut root = H256::zero();
+		{
+			let mut trie = TrieDBMut::new(&mut db, &mut root);
+			for (key, value) in entries {
+				trie.insert(key.as_bytes(), value).unwrap();
+			}
+		}
+		(db, root)
+	}
+
+	fn prove(trie: &TrieDB, key: &H256) -> Vec<Bytes> {
+		let mut recorder = Recorder::new();
+		trie.get_with(key.as_bytes(), (&mut recorder, |v: &[u8]| v.to_vec())).unwrap();
+		recorder.drain().into_iter().map(|r| r.data).collect()
+	}
+
+	fn next(key: &H256) -> H256 {
+		let mut next = *key;
+		for byte in next.as_bytes_mut().iter_mut().rev() {
+			if *byte == 0xff {
+				*byte = 0;
+			} else {
+				*byte += 1;
+				break;
+			}
+		}
+		next
+	}
+
+	fn range_proof(trie: &TrieDB, origin: &H256, last: Option<&H256>) -> Vec<Bytes> {
+		let mut proof = prove(trie, origin);
+		if let Some(last) = last {
+			proof.extend(prove(trie, last));
+		}
+		proof
+	}
+
+	#[test]
+	fn encodes_paths() {
+		assert_eq!(encode_path(&[1, 2, 3]), vec![0x11, 0x23]);
+		assert_eq!(encode_path(&[1, 2]), vec![0x00, 0x12]);
+		assert_eq!(decode_path(&[0x11, 0x23]).unwrap(), vec![1, 2, 3]);
+		assert_eq!(decode_path(&[0x00, 0x12]).unwrap(), vec![1, 2]);
+		assert_eq!(from_nibbles(&to_nibbles(&[0xab, 0xcd])), vec![0xab, 0xcd]);
+	}
+
+	#[test]
+	fn verifies_ranges() {
+		let entries = entries(200);
+		let (db, root) = build_trie(&entries);
+		let trie = TrieDB::new(&db, &root).unwrap();
+
+		// the whole trie without proof
+		assert_eq!(verify_range_proof(&root, &H256::zero(), &entries, &[]), Ok(false));
+
+		// a range in the middle, starting at an existing key
+		let range = &entries[10..60];
+		let proof = range_proof(&trie, &range[0].0, Some(&range[49].0));
+		assert_eq!(verify_range_proof(&root, &range[0].0, range, &proof), Ok(true));
+
+		// a range starting between keys
+		let origin = next(&entries[9].0);
+		assert!(origin > entries[9].0 && origin < entries[10].0);
+		let proof = range_proof(&trie, &origin, Some(&range[49].0));
+		assert_eq!(verify_range_proof(&root, &origin, range, &proof), Ok(true));
+
+		// the last range
+		let range = &entries[150..];
+		let proof = range_proof(&trie, &range[0].0, Some(&range[49].0));
+		assert_eq!(verify_range_proof(&root, &range[0].0, range, &proof), Ok(false));
+	}
+
+	#[test]
+	fn rejects_incomplete_ranges() {
+		let entries = entries(100);
+		let (db, root) = build_trie(&entries);
+		let trie = TrieDB::new(&db, &root).unwrap();
+
+		let mut range = entries[20..40].to_vec();
+		let proof = range_proof(&trie, &range[0].0, Some(&range[19].0));
+		range.remove(10);
+		assert_eq!(verify_range_proof(&root, &range[0].0, &range, &proof), Err(RangeProofError::RootMismatch));
+
+		let mut range = entries[20..40].to_vec();
+		range[5].1 = vec![0xff];
+		assert_eq!(verify_range_proof(&root, &range[0].0, &range, &proof), Err(RangeProofError::RootMismatch));
+
+		let mut range = entries[20..40].to_vec();
+		range.swap(3, 4);
+		assert_eq!(verify_range_proof(&root, &range[0].0, &range, &proof), Err(RangeProofError::UnorderedEntries));
+
+		assert_eq!(verify_range_proof(&root, &H256::zero(), &entries[..99], &[]), Err(RangeProofError::RootMismatch));
+	}
+
+	#[test]
+	fn verifies_empty_ranges() {
+		let entries = entries(50);
+		let (db, root) = build_trie(&entries);
+		let trie = TrieDB::new(&db, &root).unwrap();
+
+		let origin = next(&entries[49].0);
+		let proof = range_proof(&trie, &origin, None);
+		assert_eq!(verify_range_proof(&root, &origin, &[], &proof), Ok(false));
+
+		let proof = range_proof(&trie, &entries[30].0, None);
+		assert_eq!(verify_range_proof(&root, &entries[30].0, &[], &proof), Err(RangeProofError::MissingEntries));
+	}
+
+	#[test]
+	fn finds_nodes_by_path() {
+		let entries = entries(100);
+		let (db, root) = build_trie(&entries);
+		let trie = TrieDB::new(&db, &root).unwrap();
+		let proof = prove(&trie, &entries[0].0);
+		let lookup = |hash: &H256| proof.iter().find(|node| KeccakHasher::hash(node) == *hash).cloned();
+
+		assert_eq!(node_at_path(&root, &[], &lookup).unwrap(), Some(proof[0].clone()));
+
+		let references = node_references(&proof[0]).unwrap();
+		let (path, hash) = references.iter().filter_map(|r| match *r {
+			NodeReference::Child(ref path, hash) if to_nibbles(entries[0].0.as_bytes()).starts_with(path) => Some((path.clone(), hash)),
+			_ => None,
+		}).next().unwrap();
+		let node = node_at_path(&root, &path, &lookup).unwrap().unwrap();
+		assert_eq!(KeccakHasher::hash(&node), hash);
+	}
+}